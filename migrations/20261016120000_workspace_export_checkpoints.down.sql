@@ -0,0 +1,3 @@
+drop table if exists workspace_export_checkpoints;
+
+drop type if exists workspace_export_status;
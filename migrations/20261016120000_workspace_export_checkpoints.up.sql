@@ -0,0 +1,25 @@
+-- Allowed states for a volunteer within a workspace export job
+create type workspace_export_status as enum(
+  'pending',
+  'created',
+  'failed'
+);
+
+--
+-- workspace_export_checkpoints table
+-- This table records the progress of each volunteer in a workspace export job, so that an export
+-- that dies partway through can be resumed without re-creating users that already exist.
+create table if not exists workspace_export_checkpoints(
+  created_at timestamptz not null default now(),
+  updated_at timestamptz,
+  job_id uuid not null references jobs(id) on delete cascade,
+  volunteer_id uuid not null references volunteers(id) on delete cascade,
+  workspace_email text not null,
+  org_unit text not null,
+  status workspace_export_status not null default 'pending' ::workspace_export_status,
+  error text,
+  primary key (job_id, volunteer_id)
+);
+
+select
+  trigger_updated_at('workspace_export_checkpoints');
@@ -0,0 +1,3 @@
+drop table if exists job_progress;
+
+drop type if exists job_phase;
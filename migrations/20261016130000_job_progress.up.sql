@@ -0,0 +1,22 @@
+-- Phases a job moves through while it runs
+create type job_phase as enum(
+  'provisioning',
+  'persisting',
+  'emailing'
+);
+
+--
+-- job_progress table
+-- This table records how far a running job has gotten, so that callers can poll its progress
+-- instead of only seeing whether it is pending, complete, or errored.
+create table if not exists job_progress(
+  job_id uuid primary key references jobs(id) on delete cascade,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz,
+  phase job_phase not null,
+  processed integer not null default 0,
+  total integer not null default 0
+);
+
+select
+  trigger_updated_at('job_progress');
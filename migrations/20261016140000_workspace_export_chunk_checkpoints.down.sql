@@ -0,0 +1,25 @@
+-- Postgres cannot drop values from an enum, so the type is rebuilt without them
+alter table workspace_export_checkpoints
+  alter column status drop default;
+
+alter type workspace_export_status rename to workspace_export_status_old;
+
+create type workspace_export_status as enum(
+  'pending',
+  'created',
+  'failed'
+);
+
+alter table workspace_export_checkpoints
+  alter column status type workspace_export_status
+  using (
+    case when status::text in ('recorded', 'emailed') then
+      'created'
+    else
+      status::text
+    end)::workspace_export_status;
+
+alter table workspace_export_checkpoints
+  alter column status set default 'pending' ::workspace_export_status;
+
+drop type workspace_export_status_old;
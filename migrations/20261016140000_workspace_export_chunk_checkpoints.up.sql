@@ -0,0 +1,5 @@
+-- Exports are checkpointed after each chunk is recorded and emailed, not just after each user is
+-- created
+alter type workspace_export_status add value if not exists 'recorded';
+
+alter type workspace_export_status add value if not exists 'emailed';
@@ -0,0 +1,15 @@
+--
+-- email_templates table
+-- This table stores the copy of emails the app sends, so that staff can edit it without a deploy.
+-- Every edit is recorded as a new version; the newest version of a template is the one that is
+-- sent.
+create table if not exists email_templates(
+  id uuid not null default uuid_generate_v4() primary key,
+  created_at timestamptz not null default now(),
+  name text not null,
+  version integer not null,
+  subject text not null,
+  body text not null,
+  created_by text not null,
+  unique (name, version)
+);
@@ -0,0 +1,3 @@
+drop table if exists onboarding_email_deliveries;
+
+drop type if exists email_delivery_status;
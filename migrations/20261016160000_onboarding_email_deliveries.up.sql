@@ -0,0 +1,27 @@
+-- What the mail provider last reported about an onboarding email
+create type email_delivery_status as enum(
+  'delivered',
+  'bounced',
+  'dropped',
+  'opened'
+);
+
+--
+-- onboarding_email_deliveries table
+-- This table records delivery events reported by the mail provider for each onboarding email sent
+-- by a workspace export job, so that volunteers who never received their credentials can be found.
+create table if not exists onboarding_email_deliveries(
+  created_at timestamptz not null default now(),
+  updated_at timestamptz,
+  job_id uuid not null references jobs(id) on delete cascade,
+  volunteer_id uuid not null references volunteers(id) on delete cascade,
+  email text not null,
+  status email_delivery_status not null,
+  reason text,
+  message_id text,
+  event_at timestamptz not null,
+  primary key (job_id, volunteer_id)
+);
+
+select
+  trigger_updated_at('onboarding_email_deliveries');
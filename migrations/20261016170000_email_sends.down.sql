@@ -0,0 +1,3 @@
+drop table if exists email_sends;
+
+drop type if exists email_send_status;
@@ -0,0 +1,26 @@
+-- Whether a mail provider accepted an email
+create type email_send_status as enum(
+  'sent',
+  'failed'
+);
+
+--
+-- email_sends table
+-- This table is an audit log of every attempt to send an email, so that whether someone was ever
+-- emailed can be answered without searching the mail provider's logs.
+create table if not exists email_sends(
+  id uuid primary key default uuid_generate_v4(),
+  created_at timestamptz not null default now(),
+  job_id uuid references jobs(id) on delete set null,
+  volunteer_id uuid references volunteers(id) on delete set null,
+  recipient text not null,
+  template text not null,
+  provider text,
+  message_id text,
+  status email_send_status not null,
+  error text
+);
+
+create index if not exists email_sends_job_id_idx on email_sends(job_id);
+
+create index if not exists email_sends_recipient_idx on email_sends(recipient);
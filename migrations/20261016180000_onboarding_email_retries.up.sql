@@ -0,0 +1,30 @@
+--
+-- onboarding_email_retries table
+-- This table queues onboarding emails that no mail provider accepted, so they can be sent again
+-- with backoff. Rows are deleted once the email is sent, since they hold temporary passwords.
+create table if not exists onboarding_email_retries(
+  id uuid primary key default uuid_generate_v4(),
+  created_at timestamptz not null default now(),
+  updated_at timestamptz,
+  job_id uuid not null references jobs(id) on delete cascade,
+  volunteer_id uuid not null references volunteers(id) on delete cascade,
+  first_name text not null,
+  last_name text not null,
+  email text not null,
+  workspace_email text not null,
+  temporary_password text,
+  locale text not null,
+  attempts int not null default 0,
+  next_attempt_at timestamptz not null default now(),
+  exhausted boolean not null default false,
+  last_error text,
+  unique (job_id, volunteer_id)
+);
+
+create index if not exists onboarding_email_retries_next_attempt_at_idx on
+  onboarding_email_retries(next_attempt_at)
+where
+  not exhausted;
+
+select
+  trigger_updated_at('onboarding_email_retries');
@@ -0,0 +1,3 @@
+drop table if exists email_suppressions;
+
+drop type if exists email_suppression_reason;
@@ -0,0 +1,24 @@
+-- Why email is not sent to an address
+create type email_suppression_reason as enum(
+  'bounced',
+  'unsubscribed',
+  'spam_report',
+  'manual'
+);
+
+--
+-- email_suppressions table
+-- This table is the suppression list: addresses that hard bounced, unsubscribed, or reported email
+-- as spam. No email is sent to an address on the list. Addresses are stored in lowercase.
+create table if not exists email_suppressions(
+  email text primary key,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz,
+  reason email_suppression_reason not null,
+  provider text,
+  detail text,
+  suppressed_at timestamptz not null default now()
+);
+
+select
+  trigger_updated_at('email_suppressions');
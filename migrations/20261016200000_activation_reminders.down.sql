@@ -0,0 +1,5 @@
+drop table if exists activation_reminders;
+
+drop table if exists activation_reminder_settings;
+
+drop type if exists activation_reminder_status;
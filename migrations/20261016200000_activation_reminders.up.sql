@@ -0,0 +1,38 @@
+-- What happened when a volunteer was checked for an activation reminder
+create type activation_reminder_status as enum(
+  'sent',
+  'activated',
+  'skipped'
+);
+
+--
+-- activation_reminder_settings table
+-- This table configures, per project cycle, whether volunteers who have not signed in to their
+-- workspace account are sent a reminder, and how many days after their account was created.
+create table if not exists activation_reminder_settings(
+  project_cycle_id uuid primary key references project_cycles(id) on delete cascade,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz,
+  enabled boolean not null default true,
+  remind_after_days int not null check (remind_after_days > 0),
+  locale text not null default 'en',
+  principal text not null -- the staff member who configured reminders, whom workspace lookups are made as
+);
+
+select
+  trigger_updated_at('activation_reminder_settings');
+
+--
+-- activation_reminders table
+-- This table records each exported volunteer who has been checked for an activation reminder, so
+-- that nobody is reminded (or looked up in workspace) more than once.
+create table if not exists activation_reminders(
+  id uuid primary key default uuid_generate_v4(),
+  created_at timestamptz not null default now(),
+  job_id uuid not null references jobs(id) on delete cascade,
+  volunteer_id uuid not null references volunteers(id) on delete cascade,
+  workspace_email text not null,
+  status activation_reminder_status not null,
+  detail text,
+  unique (job_id, volunteer_id)
+);
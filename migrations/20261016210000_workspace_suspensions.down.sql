@@ -0,0 +1,3 @@
+drop table if exists workspace_suspensions;
+
+drop type if exists workspace_suspension_action;
@@ -0,0 +1,21 @@
+-- What an offboarding job did to a volunteer's workspace account
+create type workspace_suspension_action as enum(
+  'suspend',
+  'unsuspend'
+);
+
+--
+-- workspace_suspensions table
+-- This table records each time an offboarding job suspended (or unsuspended) an exported
+-- volunteer's workspace account, and whether it worked.
+create table if not exists workspace_suspensions(
+  id uuid primary key default uuid_generate_v4(),
+  created_at timestamptz not null default now(),
+  job_id uuid not null references jobs(id) on delete cascade,
+  volunteer_id uuid not null references volunteers(id) on delete cascade,
+  workspace_email text not null,
+  action workspace_suspension_action not null,
+  succeeded boolean not null,
+  error text,
+  principal text not null -- the staff member who started the job, whom workspace calls are made as
+);
@@ -0,0 +1,14 @@
+--
+-- workspace_aliases table
+-- This table records the extra addresses (e.g. jane@ for jane.doe@) that exports added to
+-- volunteers' workspace accounts, so that later exports do not hand them out again.
+create table if not exists workspace_aliases(
+  id uuid primary key default uuid_generate_v4(),
+  created_at timestamptz not null default now(),
+  volunteer_id uuid not null references volunteers(id) on delete cascade,
+  job_id uuid not null references jobs(id) on delete cascade,
+  workspace_email text not null,
+  alias text not null unique
+);
+
+create index if not exists workspace_aliases_volunteer_id_idx on workspace_aliases(volunteer_id);
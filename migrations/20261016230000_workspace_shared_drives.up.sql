@@ -0,0 +1,13 @@
+--
+-- workspace_shared_drives table
+-- This table records the shared drive provisioned for each cohort of a project cycle, so that
+-- later exports into the same cohort reuse the drive rather than creating another.
+create table if not exists workspace_shared_drives(
+  id uuid primary key default uuid_generate_v4(),
+  created_at timestamptz not null default now(),
+  project_cycle_id uuid not null references project_cycles(id) on delete cascade,
+  job_id uuid not null references jobs(id) on delete cascade, -- The export that created the drive
+  drive_id text not null, -- The ID Google gave the drive
+  name text not null,
+  unique(project_cycle_id, name)
+);
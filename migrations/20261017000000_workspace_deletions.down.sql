@@ -0,0 +1,3 @@
+drop table if exists workspace_deletions;
+
+drop type if exists workspace_deletion_status;
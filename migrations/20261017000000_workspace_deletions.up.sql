@@ -0,0 +1,33 @@
+-- Where a scheduled deletion of a volunteer's workspace account stands
+create type workspace_deletion_status as enum(
+  'scheduled',
+  'cancelled',
+  'deleted',
+  'failed'
+);
+
+--
+-- workspace_deletions table
+-- This table records each workspace account a deprovisioning job suspended and scheduled to be
+-- deleted once its grace period is over. Deletions can be cancelled until then.
+create table if not exists workspace_deletions(
+  id uuid primary key default uuid_generate_v4(),
+  created_at timestamptz not null default now(),
+  updated_at timestamptz,
+  job_id uuid not null references jobs(id) on delete cascade,
+  volunteer_id uuid not null references volunteers(id) on delete cascade,
+  workspace_email text not null,
+  delete_after timestamptz not null,
+  status workspace_deletion_status not null default 'scheduled',
+  error text,
+  principal text not null, -- the staff member who started the job, whom workspace calls are made as
+  cancelled_by text
+);
+
+-- an account can only be scheduled for deletion once at a time
+create unique index if not exists workspace_deletions_scheduled_idx on workspace_deletions(workspace_email)
+where
+  status = 'scheduled';
+
+select
+  trigger_updated_at('workspace_deletions');
@@ -0,0 +1 @@
+drop table if exists workspace_logins;
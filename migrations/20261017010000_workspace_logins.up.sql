@@ -0,0 +1,16 @@
+--
+-- workspace_logins table
+-- This table records when each exported volunteer last signed in to their workspace account, as
+-- of the last time it was checked. It backs activation reminders and "never activated" reports.
+create table if not exists workspace_logins(
+  id uuid primary key default uuid_generate_v4(),
+  created_at timestamptz not null default now(),
+  updated_at timestamptz,
+  volunteer_id uuid not null references volunteers(id) on delete cascade,
+  workspace_email text not null unique,
+  last_login_at timestamptz, -- null if the volunteer had never signed in when last checked
+  checked_at timestamptz not null default now()
+);
+
+select
+  trigger_updated_at('workspace_logins');
@@ -0,0 +1,3 @@
+drop table if exists workspace_drive_transfers;
+
+drop type if exists workspace_transfer_status;
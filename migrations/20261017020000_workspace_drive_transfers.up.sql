@@ -0,0 +1,31 @@
+-- Where a transfer of a departing volunteer's Drive files to an archive account stands
+create type workspace_transfer_status as enum(
+  'pending',
+  'in_progress',
+  'completed',
+  'failed'
+);
+
+--
+-- workspace_drive_transfers table
+-- This table records each transfer of a volunteer's Drive files to a program's archive account that
+-- an offboarding job started, and how far it got. Accounts are only suspended once their files have
+-- been transferred.
+create table if not exists workspace_drive_transfers(
+  id uuid primary key default uuid_generate_v4(),
+  created_at timestamptz not null default now(),
+  updated_at timestamptz,
+  job_id uuid not null references jobs(id) on delete cascade,
+  volunteer_id uuid not null references volunteers(id) on delete cascade,
+  workspace_email text not null,
+  archive_email text not null,
+  transfer_id text, -- null until the transfer has been started
+  status workspace_transfer_status not null default 'pending',
+  error text,
+  suspended boolean not null default false,
+  principal text not null, -- the staff member who started the job, whom workspace calls are made as
+  unique (job_id, volunteer_id)
+);
+
+select
+  trigger_updated_at('workspace_drive_transfers');
@@ -0,0 +1,150 @@
+-- Volunteers and their export records are soft-deleted, so a volunteer deleted by mistake can be
+-- restored within a retention window. Rows with a deleted_at are left out of every view.
+alter table volunteers
+  add column deleted_at timestamptz;
+
+alter table volunteers_exported_to_workspace
+  add column deleted_at timestamptz;
+
+-- A deleted volunteer's email can be used again, so it is only unique among volunteers that have not
+-- been deleted
+alter table volunteers
+  drop constraint if exists volunteers_email_key;
+
+create unique index if not exists volunteers_email_key on volunteers(email)
+where
+  deleted_at is null;
+
+create or replace view volunteer_details as
+select
+  v.id as volunteer_id,
+  v.created_at,
+  v.updated_at,
+  v.project_cycle_id,
+  pc.name as project_cycle_name,
+  v.first_name,
+  v.last_name,
+  v.email,
+  v.phone,
+  v.volunteer_gender,
+  v.volunteer_ethnicity,
+  v.volunteer_age_range,
+  v.university,
+  v.lgbt,
+  v.country,
+  v.us_state,
+  v.fli,
+  v.student_stage,
+  v.majors,
+  v.minors,
+  v.hear_about,
+  vew.workspace_email,
+  coalesce(json_agg(distinct jsonb_build_object('clientId', nc.id, 'orgName', nc.org_name, 'projectName', nc.project_name, 'currentlyActive', cv.currently_active)) filter (where nc.id is not null), '[]') as clients,
+  coalesce(json_agg(distinct jsonb_build_object('mentorId', vm.mentor_id, 'firstName', m.first_name, 'lastName', m.last_name, 'email', m.email, 'phone', m.phone, 'company', m.company, 'jobTitle', m.job_title)) filter (where vm.mentor_id is not null), '[]') as mentors,
+  coalesce(json_agg(distinct jsonb_build_object('roleId', vtr.role_id, 'name', tr.name, 'description', tr.description)) filter (where vtr.role_id is not null), '[]') as roles
+from
+  volunteers v
+  left join client_volunteers cv on v.id = cv.volunteer_id
+  left join nonprofit_clients nc on cv.client_id = nc.id
+  left join volunteer_mentors vm on v.id = vm.volunteer_id
+  left join mentors m on vm.mentor_id = m.id
+  left join volunteer_team_roles vtr on v.id = vtr.volunteer_id
+  left join team_roles tr on vtr.role_id = tr.id
+  left join project_cycles pc on pc.id = v.project_cycle_id
+  left join volunteers_exported_to_workspace vew on v.id = vew.volunteer_id
+    and vew.deleted_at is null
+where
+  v.deleted_at is null
+group by
+  v.id,
+  vew.workspace_email,
+  pc.name;
+
+create or replace view nonprofit_client_details as
+select
+  nc.id as client_id,
+  nc.created_at,
+  nc.updated_at,
+  nc.project_cycle_id,
+  pc.name as project_cycle_name,
+  nc.representative_first_name,
+  nc.representative_last_name,
+  nc.representative_job_title,
+  nc.email,
+  nc.email_cc,
+  nc.phone,
+  nc.org_name,
+  nc.project_name,
+  nc.impact_causes,
+  nc.org_website,
+  nc.country_hq,
+  nc.us_state_hq,
+  nc.address,
+  nc.size,
+  coalesce(json_agg(distinct jsonb_build_object('id', v.id, 'first_name', v.first_name, 'last_name', v.last_name, 'email', v.email, 'phone', v.phone, 'volunteer_gender', v.volunteer_gender, 'volunteer_ethnicity', v.volunteer_ethnicity, 'volunteer_age_range', v.volunteer_age_range)) filter (where cv.volunteer_id is not null and v.deleted_at is null), '[]') as volunteers,
+  coalesce(json_agg(distinct jsonb_build_object('id', m.id, 'first_name', m.first_name, 'last_name', m.last_name, 'email', m.email, 'phone', m.phone, 'company', m.company, 'job_title', m.job_title)) filter (where cm.mentor_id is not null), '[]') as mentors
+from
+  nonprofit_clients nc
+  left join client_volunteers cv on nc.id = cv.client_id
+  left join volunteers v on cv.volunteer_id = v.id
+  left join client_mentors cm on nc.id = cm.client_id
+  left join mentors m on cm.mentor_id = m.id
+  left join project_cycles pc on pc.id = nc.project_cycle_id
+group by
+  nc.id,
+  pc.name;
+
+create or replace view mentor_details as
+select
+  m.id as mentor_id,
+  m.created_at,
+  m.updated_at,
+  m.project_cycle_id,
+  pc.name as project_cycle_name,
+  m.first_name,
+  m.last_name,
+  m.email,
+  m.phone,
+  m.company,
+  m.job_title,
+  m.country,
+  m.us_state,
+  m.years_experience,
+  m.experience_level,
+  m.prior_mentor,
+  m.prior_mentee,
+  m.prior_student,
+  m.university,
+  m.hear_about,
+  coalesce(json_agg(distinct jsonb_build_object('volunteer_id', vm.volunteer_id, 'email', v.email, 'name', v.first_name || ' ' || v.last_name)) filter (where vm.volunteer_id is not null and v.deleted_at is null), '[]') as volunteers,
+  coalesce(json_agg(distinct jsonb_build_object('client_id', cm.client_id, 'org_name', nc.org_name, 'project_name', nc.project_name)) filter (where cm.client_id is not null), '[]') as clients
+from
+  mentors m
+  left join volunteer_mentors vm on m.id = vm.mentor_id
+  left join volunteers v on vm.volunteer_id = v.id
+  left join client_mentors cm on m.id = cm.mentor_id
+  left join nonprofit_clients nc on cm.client_id = nc.id
+  left join project_cycles pc on m.project_cycle_id = pc.id
+group by
+  m.id,
+  pc.name;
+
+create or replace view exported_volunteer_details as
+select
+  ev.id,
+  ev.created_at,
+  ev.updated_at,
+  ev.volunteer_id,
+  ev.workspace_email,
+  ev.org_unit,
+  j.id as job_id,
+  j.project_cycle_id,
+  j.status
+from
+  volunteers_exported_to_workspace ev
+  left join jobs j on ev.job_id = j.id
+where
+  ev.deleted_at is null
+group by
+  ev.id,
+  j.id;
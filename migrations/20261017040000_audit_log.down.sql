@@ -0,0 +1,55 @@
+drop trigger if exists audit_mutation on activation_reminder_settings;
+
+drop trigger if exists audit_mutation on activation_reminders;
+
+drop trigger if exists audit_mutation on client_mentors;
+
+drop trigger if exists audit_mutation on client_volunteers;
+
+drop trigger if exists audit_mutation on email_sends;
+
+drop trigger if exists audit_mutation on email_suppressions;
+
+drop trigger if exists audit_mutation on email_templates;
+
+drop trigger if exists audit_mutation on jobs;
+
+drop trigger if exists audit_mutation on mentors;
+
+drop trigger if exists audit_mutation on nonprofit_clients;
+
+drop trigger if exists audit_mutation on onboarding_email_deliveries;
+
+drop trigger if exists audit_mutation on onboarding_email_retries;
+
+drop trigger if exists audit_mutation on project_cycles;
+
+drop trigger if exists audit_mutation on team_roles;
+
+drop trigger if exists audit_mutation on volunteer_mentors;
+
+drop trigger if exists audit_mutation on volunteer_team_roles;
+
+drop trigger if exists audit_mutation on volunteers;
+
+drop trigger if exists audit_mutation on volunteers_exported_to_workspace;
+
+drop trigger if exists audit_mutation on workspace_aliases;
+
+drop trigger if exists audit_mutation on workspace_deletions;
+
+drop trigger if exists audit_mutation on workspace_drive_transfers;
+
+drop trigger if exists audit_mutation on workspace_logins;
+
+drop trigger if exists audit_mutation on workspace_shared_drives;
+
+drop trigger if exists audit_mutation on workspace_suspensions;
+
+drop function if exists trigger_audit(regclass);
+
+drop function if exists audit_mutation();
+
+drop table if exists audit_log;
+
+drop type if exists audit_operation;
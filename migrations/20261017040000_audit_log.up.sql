@@ -0,0 +1,141 @@
+-- How a row was changed
+create type audit_operation as enum(
+  'insert',
+  'update',
+  'delete'
+);
+
+--
+-- audit_log table
+-- This table records every insert, update, and delete made to the tables that hold volunteers'
+-- data, who made it, and what the row looked like before and after. Rows are written by triggers,
+-- so nothing can change these tables without being recorded. The principal is read from the
+-- `scipio.principal` setting, which the storage layer sets for the transaction when it knows who
+-- is making the change; it is null for changes made by background jobs.
+create table if not exists audit_log(
+  id uuid primary key default uuid_generate_v4(),
+  created_at timestamptz not null default now(),
+  principal text,
+  entity text not null, -- the table the row belongs to
+  entity_id text, -- the row's id, if the table has one
+  operation audit_operation not null,
+  before jsonb, -- null for inserts
+  after jsonb -- null for deletes
+);
+
+create index if not exists audit_log_created_at_idx on audit_log(created_at desc, id desc);
+
+create index if not exists audit_log_entity_idx on audit_log(entity, entity_id);
+
+-- Records a change to a row. Temporary passwords are never copied into the log, and updates that
+-- change nothing are skipped.
+create or replace function audit_mutation()
+  returns trigger
+  as $$
+declare
+  before_row jsonb;
+  after_row jsonb;
+begin
+  if tg_op in ('UPDATE', 'DELETE') then
+    before_row := to_jsonb(old) - 'temporary_password';
+  end if;
+  if tg_op in ('INSERT', 'UPDATE') then
+    after_row := to_jsonb(new) - 'temporary_password';
+  end if;
+  if tg_op = 'UPDATE' and (before_row - 'updated_at') = (after_row - 'updated_at') then
+    return null;
+  end if;
+  insert into audit_log(principal, entity, entity_id, operation, before, after)
+    values (nullif(current_setting('scipio.principal', true), ''), tg_table_name, coalesce(after_row, before_row) ->> 'id', lower(tg_op)::audit_operation, before_row, after_row);
+  return null;
+end;
+$$
+language plpgsql;
+
+-- automate audit trigger creation
+create or replace function trigger_audit(tablename regclass)
+  returns void
+  as $$
+begin
+  execute format('create trigger audit_mutation
+        after insert or update or delete
+        on %s
+        for each row
+    execute function audit_mutation();', tablename);
+end;
+$$
+language plpgsql;
+
+-- Job progress and export checkpoints are bookkeeping rewritten for every volunteer a job
+-- processes, so they are not audited.
+select
+  trigger_audit('activation_reminder_settings');
+
+select
+  trigger_audit('activation_reminders');
+
+select
+  trigger_audit('client_mentors');
+
+select
+  trigger_audit('client_volunteers');
+
+select
+  trigger_audit('email_sends');
+
+select
+  trigger_audit('email_suppressions');
+
+select
+  trigger_audit('email_templates');
+
+select
+  trigger_audit('jobs');
+
+select
+  trigger_audit('mentors');
+
+select
+  trigger_audit('nonprofit_clients');
+
+select
+  trigger_audit('onboarding_email_deliveries');
+
+select
+  trigger_audit('onboarding_email_retries');
+
+select
+  trigger_audit('project_cycles');
+
+select
+  trigger_audit('team_roles');
+
+select
+  trigger_audit('volunteer_mentors');
+
+select
+  trigger_audit('volunteer_team_roles');
+
+select
+  trigger_audit('volunteers');
+
+select
+  trigger_audit('volunteers_exported_to_workspace');
+
+select
+  trigger_audit('workspace_aliases');
+
+select
+  trigger_audit('workspace_deletions');
+
+select
+  trigger_audit('workspace_drive_transfers');
+
+select
+  trigger_audit('workspace_logins');
+
+select
+  trigger_audit('workspace_shared_drives');
+
+select
+  trigger_audit('workspace_suspensions');
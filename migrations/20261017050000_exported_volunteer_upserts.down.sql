@@ -0,0 +1 @@
+drop index if exists volunteers_exported_to_workspace_volunteer_id_workspace_email_key;
@@ -0,0 +1,11 @@
+-- A volunteer's workspace account is recorded once, however many times it is exported, so that
+-- re-running a repaired export updates the record instead of failing on a duplicate. Only the most
+-- recent record of each account is kept.
+delete from volunteers_exported_to_workspace vew
+  using volunteers_exported_to_workspace newer
+where newer.volunteer_id = vew.volunteer_id
+  and newer.workspace_email = vew.workspace_email
+  and (newer.created_at, newer.id) > (vew.created_at, vew.id);
+
+create unique index if not exists volunteers_exported_to_workspace_volunteer_id_workspace_email_key
+  on volunteers_exported_to_workspace(volunteer_id, workspace_email);
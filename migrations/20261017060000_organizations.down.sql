@@ -0,0 +1,273 @@
+alter view exported_volunteer_details reset (security_invoker);
+
+alter view mentor_details reset (security_invoker);
+
+alter view nonprofit_client_details reset (security_invoker);
+
+alter view volunteer_details reset (security_invoker);
+
+-- Only the default organization's data fits the unscoped constraints
+delete from organizations
+where id <> '00000000-0000-0000-0000-000000000000';
+
+alter table email_suppressions
+  drop constraint if exists email_suppressions_pkey,
+  add primary key (email);
+
+alter table email_templates
+  drop constraint if exists email_templates_org_id_name_version_key,
+  add constraint email_templates_name_version_key unique (name, version);
+
+alter table mentors
+  drop constraint if exists mentors_org_id_email_key,
+  add constraint mentors_email_key unique (email);
+
+drop index if exists volunteers_email_key;
+
+create unique index if not exists volunteers_email_key on volunteers(email)
+where
+  deleted_at is null;
+
+alter table project_cycles
+  drop constraint if exists project_cycles_org_id_name_key,
+  add constraint project_cycles_name_key unique (name);
+
+drop policy if exists org_isolation on activation_reminder_settings;
+
+alter table activation_reminder_settings no force row level security;
+
+alter table activation_reminder_settings disable row level security;
+
+alter table activation_reminder_settings
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on activation_reminders;
+
+alter table activation_reminders no force row level security;
+
+alter table activation_reminders disable row level security;
+
+alter table activation_reminders
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on audit_log;
+
+alter table audit_log no force row level security;
+
+alter table audit_log disable row level security;
+
+alter table audit_log
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on client_mentors;
+
+alter table client_mentors no force row level security;
+
+alter table client_mentors disable row level security;
+
+alter table client_mentors
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on client_volunteers;
+
+alter table client_volunteers no force row level security;
+
+alter table client_volunteers disable row level security;
+
+alter table client_volunteers
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on email_sends;
+
+alter table email_sends no force row level security;
+
+alter table email_sends disable row level security;
+
+alter table email_sends
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on email_suppressions;
+
+alter table email_suppressions no force row level security;
+
+alter table email_suppressions disable row level security;
+
+alter table email_suppressions
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on email_templates;
+
+alter table email_templates no force row level security;
+
+alter table email_templates disable row level security;
+
+alter table email_templates
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on job_progress;
+
+alter table job_progress no force row level security;
+
+alter table job_progress disable row level security;
+
+alter table job_progress
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on jobs;
+
+alter table jobs no force row level security;
+
+alter table jobs disable row level security;
+
+alter table jobs
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on mentors;
+
+alter table mentors no force row level security;
+
+alter table mentors disable row level security;
+
+alter table mentors
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on nonprofit_clients;
+
+alter table nonprofit_clients no force row level security;
+
+alter table nonprofit_clients disable row level security;
+
+alter table nonprofit_clients
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on onboarding_email_deliveries;
+
+alter table onboarding_email_deliveries no force row level security;
+
+alter table onboarding_email_deliveries disable row level security;
+
+alter table onboarding_email_deliveries
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on onboarding_email_retries;
+
+alter table onboarding_email_retries no force row level security;
+
+alter table onboarding_email_retries disable row level security;
+
+alter table onboarding_email_retries
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on project_cycles;
+
+alter table project_cycles no force row level security;
+
+alter table project_cycles disable row level security;
+
+alter table project_cycles
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on volunteer_mentors;
+
+alter table volunteer_mentors no force row level security;
+
+alter table volunteer_mentors disable row level security;
+
+alter table volunteer_mentors
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on volunteer_team_roles;
+
+alter table volunteer_team_roles no force row level security;
+
+alter table volunteer_team_roles disable row level security;
+
+alter table volunteer_team_roles
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on volunteers;
+
+alter table volunteers no force row level security;
+
+alter table volunteers disable row level security;
+
+alter table volunteers
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on volunteers_exported_to_workspace;
+
+alter table volunteers_exported_to_workspace no force row level security;
+
+alter table volunteers_exported_to_workspace disable row level security;
+
+alter table volunteers_exported_to_workspace
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on workspace_aliases;
+
+alter table workspace_aliases no force row level security;
+
+alter table workspace_aliases disable row level security;
+
+alter table workspace_aliases
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on workspace_deletions;
+
+alter table workspace_deletions no force row level security;
+
+alter table workspace_deletions disable row level security;
+
+alter table workspace_deletions
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on workspace_drive_transfers;
+
+alter table workspace_drive_transfers no force row level security;
+
+alter table workspace_drive_transfers disable row level security;
+
+alter table workspace_drive_transfers
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on workspace_export_checkpoints;
+
+alter table workspace_export_checkpoints no force row level security;
+
+alter table workspace_export_checkpoints disable row level security;
+
+alter table workspace_export_checkpoints
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on workspace_logins;
+
+alter table workspace_logins no force row level security;
+
+alter table workspace_logins disable row level security;
+
+alter table workspace_logins
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on workspace_shared_drives;
+
+alter table workspace_shared_drives no force row level security;
+
+alter table workspace_shared_drives disable row level security;
+
+alter table workspace_shared_drives
+  drop column if exists org_id;
+
+drop policy if exists org_isolation on workspace_suspensions;
+
+alter table workspace_suspensions no force row level security;
+
+alter table workspace_suspensions disable row level security;
+
+alter table workspace_suspensions
+  drop column if exists org_id;
+
+drop function if exists scope_to_org(regclass);
+
+drop function if exists current_org_id();
+
+drop table if exists organizations;
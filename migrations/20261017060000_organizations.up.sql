@@ -0,0 +1,164 @@
+--
+-- organizations table
+-- This table records the nonprofits that share a deployment. Every row of every other table that
+-- holds an organization's data belongs to exactly one organization, and row level security keeps
+-- each organization from seeing any other's rows. Data that existed before organizations belongs to
+-- the default organization.
+create table if not exists organizations(
+  id uuid not null default uuid_generate_v4() primary key,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz,
+  name text not null,
+  -- constraints
+  unique (name)
+);
+
+select
+  trigger_updated_at('organizations');
+
+select
+  trigger_audit('organizations');
+
+insert into organizations(id, name)
+  values ('00000000-0000-0000-0000-000000000000', 'Develop for Good');
+
+-- The organization the current transaction acts for, which the storage layer sets in the
+-- `scipio.org_id` setting. Transactions that do not set it (such as migrations) act for the
+-- default organization.
+create or replace function current_org_id()
+  returns uuid
+  as $$
+  select
+    coalesce(nullif(current_setting('scipio.org_id', true), '')::uuid, '00000000-0000-0000-0000-000000000000'::uuid);
+$$
+language sql
+stable;
+
+-- Scope a table to organizations. Rows are inserted into the current organization, and only the
+-- current organization's rows can be read or changed. Row level security is forced so that it also
+-- applies to the owner of the table, which is usually the role the app connects as. Superusers and
+-- roles with `bypassrls` still see every row, so the app should not connect as one.
+create or replace function scope_to_org(tablename regclass)
+  returns void
+  as $$
+begin
+  execute format('alter table %s add column if not exists org_id uuid not null default current_org_id() references organizations(id) on delete cascade', tablename);
+  execute format('create index if not exists %I on %s(org_id)', tablename::text || '_org_id_idx', tablename);
+  execute format('alter table %s enable row level security', tablename);
+  execute format('alter table %s force row level security', tablename);
+  execute format('create policy org_isolation on %s using (org_id = current_org_id()) with check (org_id = current_org_id())', tablename);
+end;
+$$
+language plpgsql;
+
+-- Team roles are the same for every organization, so they are not scoped.
+select
+  scope_to_org('activation_reminder_settings');
+
+select
+  scope_to_org('activation_reminders');
+
+select
+  scope_to_org('audit_log');
+
+select
+  scope_to_org('client_mentors');
+
+select
+  scope_to_org('client_volunteers');
+
+select
+  scope_to_org('email_sends');
+
+select
+  scope_to_org('email_suppressions');
+
+select
+  scope_to_org('email_templates');
+
+select
+  scope_to_org('job_progress');
+
+select
+  scope_to_org('jobs');
+
+select
+  scope_to_org('mentors');
+
+select
+  scope_to_org('nonprofit_clients');
+
+select
+  scope_to_org('onboarding_email_deliveries');
+
+select
+  scope_to_org('onboarding_email_retries');
+
+select
+  scope_to_org('project_cycles');
+
+select
+  scope_to_org('volunteer_mentors');
+
+select
+  scope_to_org('volunteer_team_roles');
+
+select
+  scope_to_org('volunteers');
+
+select
+  scope_to_org('volunteers_exported_to_workspace');
+
+select
+  scope_to_org('workspace_aliases');
+
+select
+  scope_to_org('workspace_deletions');
+
+select
+  scope_to_org('workspace_drive_transfers');
+
+select
+  scope_to_org('workspace_export_checkpoints');
+
+select
+  scope_to_org('workspace_logins');
+
+select
+  scope_to_org('workspace_shared_drives');
+
+select
+  scope_to_org('workspace_suspensions');
+
+-- Names and addresses only need to be unique within an organization
+alter table project_cycles
+  drop constraint if exists project_cycles_name_key,
+  add constraint project_cycles_org_id_name_key unique (org_id, name);
+
+drop index if exists volunteers_email_key;
+
+create unique index if not exists volunteers_email_key on volunteers(org_id, email)
+where
+  deleted_at is null;
+
+alter table mentors
+  drop constraint if exists mentors_email_key,
+  add constraint mentors_org_id_email_key unique (org_id, email);
+
+alter table email_templates
+  drop constraint if exists email_templates_name_version_key,
+  add constraint email_templates_org_id_name_version_key unique (org_id, name, version);
+
+alter table email_suppressions
+  drop constraint if exists email_suppressions_pkey,
+  add primary key (org_id, email);
+
+-- Views check row level security as the user querying them rather than as their owner, so that
+-- they only show the current organization's rows whoever owns them
+alter view volunteer_details set (security_invoker = true);
+
+alter view nonprofit_client_details set (security_invoker = true);
+
+alter view mentor_details set (security_invoker = true);
+
+alter view exported_volunteer_details set (security_invoker = true);
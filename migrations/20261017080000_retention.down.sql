@@ -0,0 +1,6 @@
+drop trigger if exists set_archived_at on project_cycles;
+
+drop function if exists set_archived_at();
+
+alter table project_cycles
+  drop column if exists archived_at;
@@ -0,0 +1,31 @@
+-- When a project cycle was archived, which is when its program ended. Retention periods for
+-- volunteers' data are counted from it.
+alter table project_cycles
+  add column archived_at timestamptz;
+
+update
+  project_cycles
+set
+  archived_at = coalesce(updated_at, created_at)
+where
+  archived;
+
+-- Keeps archived_at in step with archived, whichever query archives or unarchives the cycle
+create or replace function set_archived_at()
+  returns trigger
+  as $$
+begin
+  if new.archived and (tg_op = 'INSERT' or not old.archived) then
+    new.archived_at = coalesce(new.archived_at, now());
+  elsif not new.archived then
+    new.archived_at = null;
+  end if;
+  return new;
+end;
+$$
+language plpgsql;
+
+create trigger set_archived_at
+  before insert or update of archived on project_cycles
+  for each row
+  execute function set_archived_at();
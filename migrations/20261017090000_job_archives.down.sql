@@ -0,0 +1,24 @@
+-- Restore the details of every organization's archived jobs before the archive is dropped. Row
+-- level security is lifted while they are restored, since it would hide the other organizations'
+-- rows from the role running the migration.
+alter table jobs no force row level security;
+
+alter table job_archives no force row level security;
+
+update
+  jobs j
+set
+  details = ja.details
+from
+  job_archives ja
+where
+  ja.job_id = j.id;
+
+alter table jobs force row level security;
+
+drop table if exists job_archives;
+
+drop index if exists jobs_unarchived_idx;
+
+alter table jobs
+  drop column if exists archived_at;
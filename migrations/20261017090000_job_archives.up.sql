@@ -0,0 +1,25 @@
+-- When a finished job was archived, if it has been
+alter table jobs
+  add column archived_at timestamptz;
+
+create index if not exists jobs_unarchived_idx on jobs(created_at)
+where
+  archived_at is null;
+
+--
+-- job_archives table
+-- This table holds the full details of archived jobs, which include their inputs and results.
+-- Once a job is archived, its row in `jobs` keeps only a summary (its type and any error), so the
+-- jobs the UI lists and polls stay small. The row itself is kept, along with the records that refer
+-- to it, like the volunteers an export created. Details are compressed even when they are small,
+-- since they are rarely read again.
+create table if not exists job_archives(
+  job_id uuid primary key references jobs(id) on delete cascade,
+  archived_at timestamptz not null default now(),
+  details jsonb not null
+);
+
+alter table job_archives set (toast_tuple_target = 128);
+
+select
+  scope_to_org('job_archives');
@@ -0,0 +1,2 @@
+alter table jobs
+  drop column if exists version;
@@ -0,0 +1,5 @@
+-- How many times a job's status has changed. A job is only resumed at the version it was read at,
+-- and only finished while it is pending, so two workers can't both change its status and
+-- overwrite each other's state.
+alter table jobs
+  add column version integer not null default 0;
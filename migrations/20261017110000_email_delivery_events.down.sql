@@ -0,0 +1,3 @@
+drop table if exists email_delivery_events;
+
+drop type if exists email_event_type;
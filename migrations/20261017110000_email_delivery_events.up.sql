@@ -0,0 +1,38 @@
+-- What happened to an email: Scipio queued it, a mail provider accepted it, or the provider
+-- reported what became of it
+create type email_event_type as enum(
+  'queued',
+  'sent',
+  'delivered',
+  'bounced',
+  'dropped'
+);
+
+--
+-- email_delivery_events table
+-- This table records every event in the life of each email, so the history of a message can be
+-- followed from when it was queued to when it was delivered or bounced. Events the provider
+-- reports are matched to the job and volunteer the email was sent for by the provider's message
+-- ID, which is recorded once the provider accepts the email.
+create table if not exists email_delivery_events(
+  id uuid primary key default uuid_generate_v4(),
+  created_at timestamptz not null default now(),
+  job_id uuid references jobs(id) on delete cascade,
+  volunteer_id uuid references volunteers(id) on delete cascade,
+  provider text,
+  message_id text,
+  recipient text not null,
+  event email_event_type not null,
+  reason text,
+  event_at timestamptz not null
+);
+
+create index if not exists email_delivery_events_job_id_idx on email_delivery_events(job_id, event_at);
+
+-- Providers retry webhooks that time out, so the same event can be reported more than once
+create unique index if not exists email_delivery_events_message_id_idx on email_delivery_events(message_id, event, event_at)
+where
+  message_id is not null;
+
+select
+  scope_to_org('email_delivery_events');
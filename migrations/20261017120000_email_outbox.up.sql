@@ -0,0 +1,34 @@
+--
+-- email_outbox table
+-- This table holds the onboarding emails an export has committed to sending. They are written in
+-- the same transaction that records the exported volunteers, so an email is never lost to a crash
+-- between recording a volunteer and emailing them, and deleted once a mail provider has accepted
+-- or refused them. A row is claimed by whoever is sending it until `claimed_until`, so the relay
+-- only picks up emails whose sender stopped before it was done. Rows hold temporary passwords, so
+-- they are never kept after they are sent.
+create table if not exists email_outbox(
+  id uuid primary key,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz,
+  job_id uuid not null references jobs(id) on delete cascade,
+  volunteer_id uuid not null references volunteers(id) on delete cascade,
+  first_name text not null,
+  last_name text not null,
+  email text not null,
+  workspace_email text not null,
+  temporary_password text,
+  locale text not null,
+  onboarding_session_link text,
+  claimed_by uuid,
+  claimed_until timestamptz not null default now()
+);
+
+create index if not exists email_outbox_claimed_until_idx on email_outbox(claimed_until);
+
+create index if not exists email_outbox_job_id_idx on email_outbox(job_id);
+
+select
+  trigger_updated_at('email_outbox');
+
+select
+  scope_to_org('email_outbox');
@@ -0,0 +1,22 @@
+--
+-- settings table
+-- This table holds the settings staff can change without a deploy, such as the org unit exports
+-- create users in by default, as one JSON value per key for each organization.
+create table if not exists settings(
+  key text not null,
+  value jsonb not null,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz
+);
+
+select
+  trigger_updated_at('settings');
+
+select
+  trigger_audit('settings');
+
+select
+  scope_to_org('settings');
+
+alter table settings
+  add primary key (org_id, key);
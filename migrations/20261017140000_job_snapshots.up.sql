@@ -0,0 +1,18 @@
+--
+-- job_snapshots table
+-- This table holds point-in-time snapshots of jobs, each a single JSON document with the job's
+-- parameters (with secrets redacted), the outcome for each volunteer, and the status of each email
+-- the job sent, so support can investigate a job after its state has moved on.
+create table if not exists job_snapshots(
+  id uuid primary key default uuid_generate_v4(),
+  job_id uuid not null references jobs(id) on delete cascade,
+  created_at timestamptz not null default now(),
+  created_by text,
+  snapshot jsonb not null
+);
+
+create index if not exists job_snapshots_job_id_idx on job_snapshots(job_id);
+
+-- Snapshots are never changed once taken and record who took them, so they are not audited.
+select
+  scope_to_org('job_snapshots');
@@ -0,0 +1,28 @@
+--
+-- job_queue table
+-- This table holds the jobs waiting to be run by the queue workers, along with what each needs to
+-- run. A worker claims a job until `claimed_until` and renews its claim while the job runs, so a
+-- job whose worker stopped (for example, because the process restarted) is picked up by another
+-- worker once its claim runs out. Rows are kept once the job has finished, so it can be seen how
+-- many attempts it took and why the last one failed.
+create table if not exists job_queue(
+  job_id uuid primary key references jobs(id) on delete cascade,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz,
+  payload jsonb not null,
+  attempts integer not null default 0,
+  claimed_by uuid,
+  claimed_until timestamptz not null default now(),
+  finished_at timestamptz,
+  error text
+);
+
+create index if not exists job_queue_claimed_until_idx on job_queue(claimed_until)
+where
+  finished_at is null;
+
+select
+  trigger_updated_at('job_queue');
+
+select
+  scope_to_org('job_queue');
@@ -0,0 +1,34 @@
+--
+-- job_schedules table
+-- This table holds the named jobs that run on a cron schedule, such as the nightly reconciliation
+-- or the weekly digest, one row per job for each organization. `next_run_at` is only moved forward
+-- by the process that claims the run, so a run is not started twice when several processes are up.
+create table if not exists job_schedules(
+  name text not null,
+  cron text not null,
+  enabled boolean not null default true,
+  params jsonb not null default '{}',
+  created_at timestamptz not null default now(),
+  updated_at timestamptz,
+  updated_by text,
+  next_run_at timestamptz not null,
+  last_run_at timestamptz,
+  last_job_id uuid references jobs(id) on delete set null,
+  last_error text
+);
+
+create index if not exists job_schedules_next_run_at_idx on job_schedules(next_run_at)
+where
+  enabled;
+
+select
+  trigger_updated_at('job_schedules');
+
+select
+  trigger_audit('job_schedules');
+
+select
+  scope_to_org('job_schedules');
+
+alter table job_schedules
+  add primary key (org_id, name);
@@ -0,0 +1,30 @@
+alter table job_queue
+  drop column if exists attempt_errors,
+  drop column if exists dead_lettered_at;
+
+-- Postgres cannot drop values from an enum, so the type is rebuilt without it
+alter table jobs
+  alter column status drop default;
+
+alter type job_status rename to job_status_old;
+
+create type job_status as enum(
+  'pending',
+  'complete',
+  'cancelled',
+  'error'
+);
+
+alter table jobs
+  alter column status type job_status
+  using (
+    case when status::text = 'dead_lettered' then
+      'error'
+    else
+      status::text
+    end)::job_status;
+
+alter table jobs
+  alter column status set default 'pending' ::job_status;
+
+drop type job_status_old;
@@ -0,0 +1,9 @@
+-- Queued jobs that fail are retried with backoff, and once they have failed on every attempt they
+-- are dead-lettered until someone requeues them
+alter type job_status add value if not exists 'dead_lettered';
+
+-- The error chain of every failed attempt, oldest first, is kept so a dead-lettered job can be
+-- investigated before it is requeued. Requeueing a job does not clear it.
+alter table job_queue
+  add column if not exists attempt_errors jsonb not null default '[]',
+  add column if not exists dead_lettered_at timestamptz;
@@ -0,0 +1,18 @@
+--
+-- job_events table
+-- This table holds the progress events a job emits while it runs (the phases it moves through and
+-- the outcome for each volunteer), in the order they were emitted, so they can be streamed to
+-- whoever is watching the job. Events are kept in the database rather than in memory because the
+-- job may run in another process than the one serving the stream.
+create table if not exists job_events(
+  id bigserial primary key,
+  job_id uuid not null references jobs(id) on delete cascade,
+  created_at timestamptz not null default now(),
+  event jsonb not null
+);
+
+create index if not exists job_events_job_id_idx on job_events(job_id, id);
+
+-- Events are never changed once emitted, so they are not audited.
+select
+  scope_to_org('job_events');
@@ -0,0 +1,6 @@
+drop index if exists job_queue_priority_idx;
+
+alter table job_queue
+  drop column if exists priority;
+
+drop type if exists job_priority;
@@ -0,0 +1,15 @@
+-- How urgently a queued job should run. The values are declared from most to least urgent, so
+-- ordering by the type puts the most urgent jobs first.
+create type job_priority as enum(
+  'high',
+  'normal',
+  'low'
+);
+
+alter table job_queue
+  add column if not exists priority job_priority not null default 'normal';
+
+-- Workers claim the most urgent of the waiting jobs, and the oldest of those
+create index if not exists job_queue_priority_idx on job_queue(priority, created_at)
+where
+  finished_at is null;
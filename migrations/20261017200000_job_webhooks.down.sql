@@ -0,0 +1,8 @@
+drop trigger if exists queue_job_webhook on jobs;
+
+drop function if exists queue_job_webhook();
+
+drop table if exists job_webhooks;
+
+alter table jobs
+  drop column if exists callback_url;
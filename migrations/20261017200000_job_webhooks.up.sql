@@ -0,0 +1,46 @@
+-- The URL to call when the job finishes, in place of the one configured for every job
+alter table jobs
+  add column if not exists callback_url text;
+
+--
+-- job_webhooks table
+-- This table holds the webhook calls to make when jobs finish (complete, error, are cancelled, or
+-- are dead-lettered). A row is added by a trigger whenever a job's status changes to one of them,
+-- whichever query changed it, and a background task makes the call and retries it until it
+-- succeeds or is given up on.
+create table if not exists job_webhooks(
+  id uuid primary key default uuid_generate_v4(),
+  job_id uuid not null references jobs(id) on delete cascade,
+  status job_status not null,
+  created_at timestamptz not null default now(),
+  attempts integer not null default 0,
+  next_attempt_at timestamptz not null default now(),
+  finished_at timestamptz,
+  error text
+);
+
+create index if not exists job_webhooks_next_attempt_at_idx on job_webhooks(next_attempt_at)
+where
+  finished_at is null;
+
+select
+  scope_to_org('job_webhooks');
+
+create or replace function queue_job_webhook()
+  returns trigger
+  as $$
+begin
+  if new.status in ('complete', 'error', 'cancelled', 'dead_lettered') then
+    insert into job_webhooks(job_id, status, org_id)
+      values (new.id, new.status, new.org_id);
+  end if;
+  return new;
+end;
+$$
+language plpgsql;
+
+create trigger queue_job_webhook
+  after update of status on jobs
+  for each row
+  when (old.status is distinct from new.status)
+  execute function queue_job_webhook();
@@ -0,0 +1,4 @@
+drop index if exists jobs_depends_on_idx;
+
+alter table jobs
+  drop column if exists depends_on;
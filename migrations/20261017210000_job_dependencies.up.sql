@@ -0,0 +1,8 @@
+-- The job that has to complete before this one runs. A job whose dependency fails, is cancelled,
+-- or is dead-lettered is cancelled as well.
+alter table jobs
+  add column if not exists depends_on uuid references jobs(id) on delete set null;
+
+create index if not exists jobs_depends_on_idx on jobs(depends_on)
+where
+  depends_on is not null;
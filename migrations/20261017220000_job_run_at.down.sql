@@ -0,0 +1,2 @@
+alter table job_queue
+  drop column if exists run_at;
@@ -0,0 +1,3 @@
+-- When a queued job may run at the earliest, so that a job can be scheduled for a future time
+alter table job_queue
+  add column if not exists run_at timestamptz not null default now();
@@ -0,0 +1,7 @@
+drop table if exists job_progress;
+
+drop table if exists jobs;
+
+drop table if exists project_cycles;
+
+drop table if exists organizations;
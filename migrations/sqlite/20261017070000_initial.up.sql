@@ -0,0 +1,69 @@
+-- The schema of the SQLite backend, which is used for local development and CI.
+--
+-- It mirrors the Postgres schema for the tables the SQLite backend implements queries for. UUIDs
+-- are stored as blobs, timestamps as RFC 3339 text, enums as text, and JSON as text.
+
+create table if not exists organizations(
+  id blob not null primary key,
+  created_at text not null default (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+  updated_at text,
+  name text not null
+);
+
+-- The SQLite backend serves a single organization
+insert into organizations(id, name)
+  values (zeroblob(16), 'Develop for Good');
+
+create table if not exists project_cycles(
+  id blob not null primary key,
+  created_at text not null default (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+  updated_at text,
+  name text not null unique,
+  description text,
+  archived integer not null default 0
+);
+
+create trigger if not exists project_cycles_updated_at
+  after update on project_cycles
+  for each row
+  when new.updated_at is old.updated_at
+begin
+  update project_cycles set updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') where id = new.id;
+end;
+
+create table if not exists jobs(
+  id blob not null primary key,
+  created_at text not null default (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+  updated_at text,
+  project_cycle_id blob references project_cycles(id) on delete cascade,
+  status text not null default 'pending',
+  label text not null,
+  description text,
+  details text not null default '{}'
+);
+
+create trigger if not exists jobs_updated_at
+  after update on jobs
+  for each row
+  when new.updated_at is old.updated_at
+begin
+  update jobs set updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') where id = new.id;
+end;
+
+create table if not exists job_progress(
+  job_id blob not null primary key references jobs(id) on delete cascade,
+  created_at text not null default (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+  updated_at text,
+  phase text not null,
+  processed integer not null default 0,
+  total integer not null default 0
+);
+
+create trigger if not exists job_progress_updated_at
+  after update on job_progress
+  for each row
+  when new.updated_at is old.updated_at
+begin
+  update job_progress set updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+  where job_id = new.job_id;
+end;
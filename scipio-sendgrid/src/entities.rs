@@ -1,3 +1,5 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -72,6 +74,23 @@ pub struct Attachment {
     pub content_id: Option<String>,
 }
 
+impl Attachment {
+    /// Build an attachment from the raw contents of a file, which SendGrid expects base64 encoded.
+    ///
+    /// * `filename`: The name of the file as the recipient sees it
+    /// * `mime_type`: The MIME type of the file (e.g. `application/pdf`)
+    /// * `content`: The raw contents of the file
+    pub fn from_bytes(filename: &str, mime_type: &str, content: &[u8]) -> Self {
+        Self {
+            content: STANDARD.encode(content),
+            filename: filename.to_owned(),
+            mime_type: mime_type.to_owned(),
+            disposition: Some(AttachmentDisposition::Attachment),
+            content_id: None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Builder, Clone)]
 #[serde_with::skip_serializing_none]
 pub struct Asm {
@@ -1,6 +1,8 @@
 pub mod entities;
 mod mail_send;
 mod retry;
+pub mod suppressions;
+pub mod webhook;
 
 #[cfg(test)]
 mod tests;
@@ -4,15 +4,24 @@ use reqwest::header;
 use crate::entities::Mail;
 use crate::Sendgrid;
 
+/// The header SendGrid returns the ID of an accepted message in.
+const MESSAGE_ID_HEADER: &str = "X-Message-Id";
+
 impl Sendgrid {
-    pub async fn send_mail(&self, mail: Mail) -> Result<()> {
-        self.http
+    /// Send an email, returning SendGrid's ID for the message if it returned one.
+    pub async fn send_mail(&self, mail: Mail) -> Result<Option<String>> {
+        let res = self
+            .http
             .post("https://api.sendgrid.com/v3/mail/send")
             .header(header::CONTENT_TYPE, "application/json")
             .body(Vec::try_from(mail)?)
             .send()
-            .await?;
+            .await?
+            .error_for_status()?;
+
+        let message_id =
+            res.headers().get(MESSAGE_ID_HEADER).and_then(|v| v.to_str().ok()).map(str::to_owned);
 
-        Ok(())
+        Ok(message_id)
     }
 }
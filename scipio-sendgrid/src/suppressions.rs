@@ -0,0 +1,82 @@
+//! SendGrid's suppression lists: the addresses SendGrid refuses to send email to.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::Sendgrid;
+
+/// The most entries SendGrid returns in one page of a suppression list.
+const PAGE_SIZE: usize = 500;
+
+/// The suppression lists SendGrid keeps.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SuppressionList {
+    /// Addresses whose mail server permanently rejected email
+    Bounces,
+    /// Addresses that unsubscribed from all email
+    Unsubscribes,
+    /// Addresses that reported email as spam
+    SpamReports,
+}
+
+impl SuppressionList {
+    fn path(self) -> &'static str {
+        match self {
+            SuppressionList::Bounces => "bounces",
+            SuppressionList::Unsubscribes => "unsubscribes",
+            SuppressionList::SpamReports => "spam_reports",
+        }
+    }
+}
+
+/// An address on a suppression list.
+///
+/// * `email`: The suppressed address
+/// * `created`: When the address was suppressed, as a UNIX timestamp in seconds
+/// * `reason`: Why the address was suppressed (e.g. the bounce message), if SendGrid recorded it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Suppression {
+    pub email: String,
+    pub created: i64,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl Sendgrid {
+    /// Fetch every address on a suppression list.
+    ///
+    /// * `list`: The suppression list
+    /// * `start_time`: Only fetch addresses suppressed at or after this UNIX timestamp, if set
+    pub async fn fetch_suppressions(
+        &self,
+        list: SuppressionList,
+        start_time: Option<i64>,
+    ) -> Result<Vec<Suppression>> {
+        let url = format!("https://api.sendgrid.com/v3/suppression/{}", list.path());
+
+        let mut suppressions = Vec::new();
+        loop {
+            let mut query =
+                vec![("limit", PAGE_SIZE.to_string()), ("offset", suppressions.len().to_string())];
+            if let Some(start_time) = start_time {
+                query.push(("start_time", start_time.to_string()));
+            }
+
+            let page = self
+                .http
+                .get(&url)
+                .query(&query)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<Vec<Suppression>>()
+                .await?;
+
+            let done = page.len() < PAGE_SIZE;
+            suppressions.extend(page);
+            if done {
+                return Ok(suppressions);
+            }
+        }
+    }
+}
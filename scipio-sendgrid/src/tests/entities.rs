@@ -0,0 +1,13 @@
+use crate::entities::Attachment;
+
+#[test]
+pub fn test_attachment_from_bytes() {
+    let attachment = Attachment::from_bytes("welcome.pdf", "application/pdf", b"%PDF-1.7");
+
+    let value = serde_json::to_value(&attachment).unwrap();
+    assert_eq!(value["content"], "JVBERi0xLjc=");
+    assert_eq!(value["filename"], "welcome.pdf");
+    assert_eq!(value["type"], "application/pdf");
+    assert_eq!(value["disposition"], "attachment");
+    assert!(value["content_id"].is_null());
+}
@@ -1,2 +1,5 @@
+mod entities;
 mod fixtures;
 mod mail_send;
+mod suppressions;
+mod webhook;
@@ -0,0 +1,33 @@
+use anyhow::Result;
+use rstest::rstest;
+
+use super::fixtures::sendgrid;
+use crate::suppressions::{Suppression, SuppressionList};
+use crate::Sendgrid;
+
+#[test]
+pub fn test_parse_suppressions() -> Result<()> {
+    let bounces = r#"[{"created":1729000000,"email":"mary@example.com",
+        "reason":"550 5.1.1 The email account does not exist","status":"5.1.1"}]"#;
+    let unsubscribes = r#"[{"created":1729000001,"email":"john@example.com"}]"#;
+
+    assert_eq!(
+        serde_json::from_str::<Vec<Suppression>>(bounces)?,
+        vec![Suppression {
+            email: "mary@example.com".to_owned(),
+            created: 1_729_000_000,
+            reason: Some("550 5.1.1 The email account does not exist".to_owned()),
+        }]
+    );
+    assert_eq!(serde_json::from_str::<Vec<Suppression>>(unsubscribes)?[0].reason, None);
+
+    Ok(())
+}
+
+#[cfg(feature = "integration")]
+#[rstest]
+#[tokio::test]
+pub async fn test_fetch_suppressions(sendgrid: Sendgrid) -> Result<()> {
+    sendgrid.fetch_suppressions(SuppressionList::Bounces, None).await?;
+    Ok(())
+}
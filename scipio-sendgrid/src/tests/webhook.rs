@@ -0,0 +1,103 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{Signature, SigningKey};
+use p256::pkcs8::EncodePublicKey;
+
+use crate::webhook::{Event, EventType, EventWebhookVerifier, TIMESTAMP_TOLERANCE};
+
+const PAYLOAD: &str = r#"[{"email":"mary@example.com","timestamp":1729000000,"event":"bounce","sg_message_id":"abc.123","reason":"550 mailbox unavailable","job_id":"413eed73-3c6f-456a-b9f0-ae72d136c742"}]"#;
+
+fn sign(key: &SigningKey, timestamp: &str, payload: &str) -> String {
+    let signature: Signature = key.sign(format!("{timestamp}{payload}").as_bytes());
+    STANDARD.encode(signature.to_der().as_bytes())
+}
+
+/// When the requests in these tests were signed.
+fn signed_at() -> DateTime<Utc> {
+    DateTime::from_timestamp(1_729_000_000, 0).unwrap()
+}
+
+fn verifier(key: &SigningKey) -> EventWebhookVerifier {
+    let der = key.verifying_key().to_public_key_der().unwrap();
+    EventWebhookVerifier::new(&STANDARD.encode(der.as_bytes())).unwrap()
+}
+
+#[test]
+pub fn test_verify_signature() {
+    let key = SigningKey::from_slice(&[7; 32]).unwrap();
+    let signature = sign(&key, "1729000000", PAYLOAD);
+
+    assert!(verifier(&key)
+        .verify_at(&signature, "1729000000", PAYLOAD.as_bytes(), signed_at())
+        .is_ok());
+}
+
+#[test]
+pub fn test_reject_tampered_payload() {
+    let key = SigningKey::from_slice(&[7; 32]).unwrap();
+    let signature = sign(&key, "1729000000", PAYLOAD);
+    let tampered = PAYLOAD.replace("bounce", "delivered");
+
+    assert!(verifier(&key)
+        .verify_at(&signature, "1729000000", tampered.as_bytes(), signed_at())
+        .is_err());
+    assert!(verifier(&key)
+        .verify_at(&signature, "1729000001", PAYLOAD.as_bytes(), signed_at())
+        .is_err());
+}
+
+#[test]
+pub fn test_reject_stale_timestamp() {
+    let key = SigningKey::from_slice(&[7; 32]).unwrap();
+    let signature = sign(&key, "1729000000", PAYLOAD);
+    let verify = |now| verifier(&key).verify_at(&signature, "1729000000", PAYLOAD.as_bytes(), now);
+
+    // requests signed a little while ago, or by a clock that is a little ahead, are accepted
+    assert!(verify(signed_at() + TIMESTAMP_TOLERANCE).is_ok());
+    assert!(verify(signed_at() - TIMESTAMP_TOLERANCE).is_ok());
+
+    // a correctly signed request is rejected once it is too old to be anything but a replay
+    let err = verify(signed_at() + TIMESTAMP_TOLERANCE + Duration::seconds(1)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "timestamp 1729000000 is too far from the current time, so it may be a replay"
+    );
+    assert!(verify(signed_at() - TIMESTAMP_TOLERANCE - Duration::seconds(1)).is_err());
+    assert!(verifier(&key).verify(&signature, "1729000000", PAYLOAD.as_bytes()).is_err());
+
+    // the timestamp must be a number
+    let signature = sign(&key, "yesterday", PAYLOAD);
+    assert!(verifier(&key)
+        .verify_at(&signature, "yesterday", PAYLOAD.as_bytes(), signed_at())
+        .is_err());
+}
+
+#[test]
+pub fn test_reject_other_key() {
+    let key = SigningKey::from_slice(&[7; 32]).unwrap();
+    let other = SigningKey::from_slice(&[9; 32]).unwrap();
+    let signature = sign(&other, "1729000000", PAYLOAD);
+
+    assert!(verifier(&key)
+        .verify_at(&signature, "1729000000", PAYLOAD.as_bytes(), signed_at())
+        .is_err());
+    assert!(EventWebhookVerifier::new("not a key").is_err());
+}
+
+#[test]
+pub fn test_parse_events() {
+    let events = serde_json::from_str::<Vec<Event>>(PAYLOAD).unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event, EventType::Bounce);
+    assert_eq!(events[0].reason.as_deref(), Some("550 mailbox unavailable"));
+    assert_eq!(events[0].custom_args["job_id"], "413eed73-3c6f-456a-b9f0-ae72d136c742");
+
+    let unknown = serde_json::from_str::<Event>(
+        r#"{"email":"mary@example.com","timestamp":1,"event":"something_new"}"#,
+    )
+    .unwrap();
+    assert_eq!(unknown.event, EventType::Unknown);
+}
@@ -0,0 +1,173 @@
+//! This module encodes requests to, and decodes responses from, Google's batch endpoint, which
+//! packs several Admin SDK calls into a single HTTP request.
+//!
+//! Each call in a batch still counts against the API quota, but the batch only costs one round
+//! trip. Complete documentation of the format may be found
+//! [here](https://developers.google.com/admin-sdk/directory/v1/guides/batch)
+
+use std::fmt::{self, Write};
+
+use anyhow::{Context, Result};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// The URL of the batch endpoint for the Admin SDK Directory API.
+pub const DIRECTORY_BATCH_URL: &str = "https://admin.googleapis.com/batch/admin/directory_v1";
+
+/// The most calls Google accepts in a single batch request.
+pub const MAX_BATCH_SIZE: usize = 1000;
+
+/// A single call in a batch request.
+#[derive(Debug, Clone)]
+pub struct BatchCall {
+    /// The HTTP method of the call (e.g. `POST`).
+    pub method: &'static str,
+    /// The path of the call, relative to the API host (e.g. `/admin/directory/v1/users`).
+    pub path: String,
+    /// The JSON body of the call, if it has one.
+    pub body: Option<Value>,
+}
+
+/// The response to a single call in a batch request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchResponse {
+    /// The HTTP status code of the response.
+    pub status: u16,
+    /// The body of the response.
+    pub body: String,
+}
+
+impl BatchResponse {
+    /// Deserialize the body of a successful response.
+    ///
+    /// Fails with a `BatchEntryError` if the call was not successful.
+    pub fn json<T: DeserializeOwned>(self) -> Result<T> {
+        if !(200..300).contains(&self.status) {
+            return Err(BatchEntryError::from(self).into());
+        }
+
+        Ok(serde_json::from_str(&self.body)?)
+    }
+}
+
+/// The error returned for a single call in a batch request that Google did not accept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchEntryError {
+    /// The HTTP status code of the response.
+    pub status: u16,
+    /// Google's explanation of the error, or the body of the response if it has none.
+    pub message: String,
+}
+
+impl From<BatchResponse> for BatchEntryError {
+    fn from(value: BatchResponse) -> Self {
+        let message = serde_json::from_str::<Value>(&value.body)
+            .ok()
+            .and_then(|body| body.pointer("/error/message")?.as_str().map(str::to_owned))
+            .unwrap_or(value.body);
+
+        Self { status: value.status, message }
+    }
+}
+
+impl fmt::Display for BatchEntryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Google rejected the call with status {}: {}", self.status, self.message)
+    }
+}
+
+impl std::error::Error for BatchEntryError {}
+
+/// Generate a boundary to separate the calls in a batch request.
+pub fn boundary() -> String {
+    let suffix =
+        rand::thread_rng().sample_iter(&Alphanumeric).take(24).map(char::from).collect::<String>();
+    format!("batch_{suffix}")
+}
+
+/// Encode calls as the body of a `multipart/mixed` batch request.
+///
+/// * `boundary`: The boundary separating the calls, which must also be sent in the `Content-Type`
+///   header of the request
+/// * `calls`: The calls
+///
+/// Each call is tagged with its index, so its response can be matched up with it regardless of
+/// the order Google returns responses in.
+pub fn encode(boundary: &str, calls: &[BatchCall]) -> Result<Vec<u8>> {
+    let mut body = String::new();
+    for (i, call) in calls.iter().enumerate() {
+        write!(body, "--{boundary}\r\n")?;
+        write!(body, "Content-Type: application/http\r\nContent-ID: <item-{i}>\r\n\r\n")?;
+        write!(body, "{} {}\r\n", call.method, call.path)?;
+        match &call.body {
+            Some(json) => {
+                let json = serde_json::to_string(json)?;
+                write!(body, "Content-Type: application/json\r\n")?;
+                write!(body, "Content-Length: {}\r\n\r\n{json}\r\n", json.len())?;
+            }
+            None => body.push_str("\r\n"),
+        }
+    }
+    write!(body, "--{boundary}--\r\n")?;
+
+    Ok(body.into_bytes())
+}
+
+/// Decode the body of a batch response.
+///
+/// * `content_type`: The `Content-Type` header of the response, which holds the boundary
+/// * `body`: The body of the response
+/// * `calls`: The number of calls in the request
+///
+/// Returns the response to each call, in the order of the calls. A call is `None` if Google did
+/// not return a response for it.
+pub fn decode(content_type: &str, body: &str, calls: usize) -> Result<Vec<Option<BatchResponse>>> {
+    let boundary = content_type
+        .split(';')
+        .map(str::trim)
+        .find_map(|param| param.strip_prefix("boundary="))
+        .map(|b| b.trim_matches('"'))
+        .with_context(|| format!("Batch response has no boundary in {content_type:?}"))?;
+
+    let mut responses = (0..calls).map(|_| None).collect::<Vec<Option<BatchResponse>>>();
+    let delimiter = format!("--{boundary}");
+    for (n, part) in body.split(&delimiter).skip(1).enumerate() {
+        if part.starts_with("--") {
+            break;
+        }
+
+        let part = part.replace("\r\n", "\n");
+        let (headers, http) = part
+            .trim_start_matches('\n')
+            .split_once("\n\n")
+            .with_context(|| format!("Part {n} of the batch response is malformed"))?;
+
+        // Google echoes the Content-ID of the call back as `<response-item-{i}>`
+        let index = headers
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.trim().eq_ignore_ascii_case("content-id").then_some(value.trim())
+            })
+            .and_then(|id| id.trim_matches(['<', '>']).rsplit('-').next()?.parse::<usize>().ok())
+            .unwrap_or(n);
+
+        let (status_line, rest) = http.split_once('\n').unwrap_or((http, ""));
+        let status = status_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|s| s.parse::<u16>().ok())
+            .with_context(|| {
+                format!("Batch response has a malformed status line {status_line:?}")
+            })?;
+        let body = rest.split_once("\n\n").map(|(_, b)| b.trim()).unwrap_or_default();
+
+        if let Some(slot) = responses.get_mut(index) {
+            *slot = Some(BatchResponse { status, body: body.to_owned() });
+        }
+    }
+
+    Ok(responses)
+}
@@ -0,0 +1,66 @@
+//! This module defines the calendar event entities that ServiceAccount relies on.
+//!
+//! Complete documentation of these entities may be found
+//! [here](https://developers.google.com/calendar/api/v3/reference/events)
+
+// There's no point documenting here because everything can be found at the links in the file
+// header.
+#![allow(missing_docs)]
+use chrono::{DateTime, Utc};
+use derive_builder::Builder;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    pub id: String,
+    pub status: Option<String>,
+    pub html_link: Option<String>,
+    pub hangout_link: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub start: Option<EventDateTime>,
+    pub end: Option<EventDateTime>,
+    #[serde(default)]
+    pub attendees: Vec<EventAttendee>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventDateTime {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_time: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventAttendee {
+    pub email: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<String>,
+}
+
+#[derive(Builder, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateEvent {
+    #[builder(setter(into, strip_option), default = "None")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[builder(setter(into))]
+    pub summary: String,
+    #[builder(setter(into, strip_option), default = "None")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub start: EventDateTime,
+    pub end: EventDateTime,
+    #[builder(default = "Vec::new()")]
+    pub attendees: Vec<EventAttendee>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchEventAttendees {
+    pub attendees: Vec<EventAttendee>,
+}
@@ -0,0 +1,31 @@
+//! This module defines the domain entities that ServiceAccount relies on.
+//!
+//! Complete documentation of these entities may be found
+//! [here](https://developers.google.com/admin-sdk/directory/reference/rest/v1/domains)
+
+// There's no point documenting here because everything can be found at the link in the file
+// header.
+#![allow(missing_docs)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Domain {
+    pub domain_name: String,
+    pub etag: Option<String>,
+    pub kind: Option<String>,
+    pub creation_time: Option<String>,
+    #[serde(default)]
+    pub is_primary: bool,
+    #[serde(default)]
+    pub verified: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Domains {
+    pub etag: Option<String>,
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub domains: Vec<Domain>,
+}
@@ -0,0 +1,47 @@
+//! This module defines the shared drive entities that ServiceAccount relies on.
+//!
+//! Complete documentation of these entities may be found
+//! [here](https://developers.google.com/drive/api/reference/rest/v3/drives) and
+//! [here](https://developers.google.com/drive/api/reference/rest/v3/permissions)
+
+// There's no point documenting here because everything can be found at the links in the file
+// header.
+#![allow(missing_docs)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Drive {
+    pub id: String,
+    pub name: String,
+    pub kind: Option<String>,
+    pub created_time: Option<String>,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDrive {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Permission {
+    pub id: String,
+    pub role: String,
+    #[serde(rename = "type")]
+    pub permission_type: String,
+    pub email_address: Option<String>,
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePermission {
+    pub role: String,
+    #[serde(rename = "type")]
+    pub permission_type: String,
+    pub email_address: String,
+}
@@ -0,0 +1,54 @@
+//! This module defines the group entities that ServiceAccount relies on.
+//!
+//! Complete documentation of these entities may be found
+//! [here](https://developers.google.com/admin-sdk/directory/reference/rest/v1/groups) and
+//! [here](https://developers.google.com/admin-sdk/directory/reference/rest/v1/members)
+
+// There's no point documenting here because everything can be found at the links in the file
+// header.
+#![allow(missing_docs)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Group {
+    pub id: String,
+    pub email: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub etag: Option<String>,
+    pub kind: Option<String>,
+    pub direct_members_count: Option<String>,
+    #[serde(default)]
+    pub admin_created: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateGroup {
+    pub email: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Member {
+    pub email: String,
+    pub role: Option<String>,
+    #[serde(rename = "type")]
+    pub member_type: Option<String>,
+    pub id: Option<String>,
+    pub status: Option<String>,
+    pub etag: Option<String>,
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMember {
+    pub email: String,
+    pub role: String,
+}
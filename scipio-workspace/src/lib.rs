@@ -7,20 +7,50 @@
 #[cfg(test)]
 mod tests;
 
+pub mod batch;
+pub mod calendar;
+pub mod domain;
+pub mod drive;
+pub mod group;
+pub mod license;
+pub mod org_unit;
+pub mod policy;
+mod rate_limit;
 mod retry;
+pub mod schema;
+pub mod transfer;
 pub mod user;
 
-use anyhow::Result;
+use std::fmt;
+
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::URL_SAFE;
+use base64::Engine;
+use batch::{BatchCall, DIRECTORY_BATCH_URL, MAX_BATCH_SIZE};
+use calendar::{CreateEvent, Event, EventAttendee, PatchEventAttendees};
 use chrono::Utc;
 use derive_builder::Builder;
+use domain::{Domain, Domains};
+use drive::{CreateDrive, CreatePermission, Drive, Permission};
+use group::{CreateGroup, CreateMember, Group, Member};
 use jsonwebtoken::{Algorithm, EncodingKey, Header};
-use reqwest::Client;
+use license::{Customer, LicenseAssignment, LicenseAssignmentList};
+use org_unit::{CreateOrgUnit, OrgUnit, OrgUnits};
+use policy::{Policies, Policy};
+use rate_limit::RateLimitMiddleware;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, StatusCode};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::policies::ExponentialBackoff;
 use reqwest_retry::RetryTransientMiddleware;
 use retry::DefaultRetryStrategy;
+use schema::{CreateSchema, Schema};
 use serde::{Deserialize, Serialize};
-use user::{CreateWorkspaceUser, WorkspaceUser};
+use transfer::{Application, Applications, DataTransfer};
+use user::{CreateWorkspaceUser, UpdateWorkspaceUser, WorkspaceUser, WorkspaceUsers};
+
+/// The most requests a service account sends at once, if no budget is given.
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 10;
 
 /// [RFC 7523 Bearer Token Grant Type](https://datatracker.ietf.org/doc/html/rfc7523#section-8.1)
 const BEARER_TOKEN_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
@@ -83,11 +113,37 @@ pub struct GoogleAccessTokenResponse {
     pub access_token: String,
 }
 
+/// The error returned when Google will not issue an access token on the behalf of a principal,
+/// for example because the principal's account is suspended or may not be impersonated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessTokenError {
+    /// The email of the principal the token was requested for.
+    pub principal: String,
+    /// The HTTP status code of the response.
+    pub status: u16,
+    /// The body of the response.
+    pub body: String,
+}
+
+impl fmt::Display for AccessTokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Google refused an access token for {} with status {}: {}",
+            self.principal, self.status, self.body
+        )
+    }
+}
+
+impl std::error::Error for AccessTokenError {}
+
 /// A service account that can be used to authenticate with Google APIs.
 ///
 /// * `json`: The JSON data of the service account.
 /// * `http`: The HTTP client that will be used to make requests to the token endpoint. By default,
-///   it is configured to backoff and retry on status codes 412, 429, as well as on network errors.
+///   it is configured to backoff and retry on status code 412, as well as on network errors. When
+///   Google rate limits a request (a 429, or a 403 with a rate limit reason), every request is
+///   paused for as long as the `Retry-After` header asks before the request is retried.
 pub struct ServiceAccount {
     json: ServiceAccountJson,
     http: ClientWithMiddleware,
@@ -100,13 +156,30 @@ impl ServiceAccount {
     /// * `max_retries`: The maximum number of retries to attempt when making requests to the admin
     ///   directory API.
     pub fn new(json: ServiceAccountJson, max_retries: u32) -> Self {
+        Self::with_request_budget(json, max_retries, DEFAULT_MAX_CONCURRENT_REQUESTS)
+    }
+
+    /// Create a new service account that sends at most `max_concurrent_requests` requests at once.
+    ///
+    /// * `json`: Valid service account JSON data.
+    /// * `max_retries`: The maximum number of retries to attempt when making requests to the admin
+    ///   directory API.
+    /// * `max_concurrent_requests`: The most requests to have in flight at once. The budget is
+    ///   shared by everything using this service account, so share one service account between
+    ///   jobs to keep them within the API quota together.
+    pub fn with_request_budget(
+        json: ServiceAccountJson,
+        max_retries: u32,
+        max_concurrent_requests: usize,
+    ) -> Self {
         let retry_policy = ExponentialBackoff::builder().build_with_max_retries(max_retries);
         let retry_strategy = RetryTransientMiddleware::new_with_policy_and_strategy(
             retry_policy,
             DefaultRetryStrategy,
         );
+        let rate_limit = RateLimitMiddleware::new(max_concurrent_requests, max_retries);
 
-        let http = ClientBuilder::new(Client::new()).with(retry_strategy).build();
+        let http = ClientBuilder::new(Client::new()).with(retry_strategy).with(rate_limit).build();
 
         Self { json, http }
     }
@@ -153,6 +226,11 @@ impl ServiceAccount {
             .body(Vec::<u8>::try_from(assertion)?)
             .send()
             .await?;
+        if !res.status().is_success() {
+            let status = res.status().as_u16();
+            let body = res.text().await.unwrap_or_default();
+            return Err(AccessTokenError { principal: principal.to_owned(), status, body }.into());
+        }
         let data = res.json::<GoogleAccessTokenResponse>().await?;
 
         Ok(data.access_token)
@@ -179,12 +257,76 @@ impl ServiceAccount {
             .body(Vec::<u8>::try_from(data)?)
             .send()
             .await?
+            .error_for_status()?
             .json::<WorkspaceUser>()
             .await?;
 
         Ok(user)
     }
 
+    /// Create several users in Google Workspace with a single batch request.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `users`: The users to create. At most `MAX_BATCH_SIZE` users can be created at once.
+    ///
+    /// This function returns the newly created user or the error Google returned for each user,
+    /// in the same order as `users`. It only fails as a whole if the batch request itself fails,
+    /// in which case none of the users may have been created.
+    pub async fn batch_create_users(
+        &self,
+        principal: &str,
+        users: Vec<CreateWorkspaceUser>,
+    ) -> Result<Vec<Result<WorkspaceUser>>> {
+        if users.is_empty() {
+            return Ok(Vec::new());
+        }
+        if users.len() > MAX_BATCH_SIZE {
+            bail!("At most {MAX_BATCH_SIZE} users can be created at once, got {}", users.len());
+        }
+
+        let scope = "https://www.googleapis.com/auth/admin.directory.user";
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let calls = users
+            .into_iter()
+            .map(|user| {
+                Ok(BatchCall {
+                    method: "POST",
+                    path: "/admin/directory/v1/users".to_owned(),
+                    body: Some(serde_json::to_value(user)?),
+                })
+            })
+            .collect::<Result<Vec<BatchCall>>>()?;
+
+        let boundary = batch::boundary();
+        let res = self
+            .http
+            .post(DIRECTORY_BATCH_URL)
+            .bearer_auth(&access_token)
+            .header(CONTENT_TYPE, format!("multipart/mixed; boundary={boundary}"))
+            .body(batch::encode(&boundary, &calls)?)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let content_type = res
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_owned();
+        let body = res.text().await?;
+
+        let users = batch::decode(&content_type, &body, calls.len())?
+            .into_iter()
+            .map(|response| {
+                response.context("Google did not return a response for this user")?.json()
+            })
+            .collect();
+
+        Ok(users)
+    }
+
     /// Delete a user from Google Workspace.
     ///
     /// * `principal`: The email of the user requesting this action.
@@ -205,4 +347,956 @@ impl ServiceAccount {
 
         Ok(())
     }
+
+    /// Suspend a user in Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    ///
+    /// Suspend a user in Google Workspace given their email. A suspended user cannot sign in, but
+    /// their account and data are kept, so the suspension can be undone.
+    pub async fn suspend_user(
+        &self,
+        principal: &str,
+        email_of_user_to_suspend: &str,
+    ) -> Result<()> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.user";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        self.http
+            .patch(format!(
+                "https://admin.googleapis.com/admin/directory/v1/users/{email_of_user_to_suspend}"
+            ))
+            .bearer_auth(&access_token)
+            .body(serde_json::to_vec(&serde_json::json!({ "suspended": true }))?)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Update a user in Google Workspace.
+    ///
+    /// * `principal`: The email of the authenticated user requesting this action.
+    /// * `user_key`: The primary email of the user to update.
+    /// * `data`: The fields to change. Fields that are `None` are left as they are.
+    ///
+    /// This function returns the updated user.
+    pub async fn update_user(
+        &self,
+        principal: &str,
+        user_key: &str,
+        data: UpdateWorkspaceUser,
+    ) -> Result<WorkspaceUser> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.user";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let user = self
+            .http
+            .patch(format!("https://admin.googleapis.com/admin/directory/v1/users/{user_key}"))
+            .bearer_auth(&access_token)
+            .body(Vec::<u8>::try_from(data)?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<WorkspaceUser>()
+            .await?;
+
+        Ok(user)
+    }
+
+    /// Unsuspend a user in Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    ///
+    /// Lift the suspension of a user in Google Workspace given their email, so they can sign in
+    /// again.
+    pub async fn unsuspend_user(
+        &self,
+        principal: &str,
+        email_of_user_to_unsuspend: &str,
+    ) -> Result<()> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.user";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        self.http
+            .patch(format!(
+                "https://admin.googleapis.com/admin/directory/v1/users/{email_of_user_to_unsuspend}"
+            ))
+            .bearer_auth(&access_token)
+            .body(serde_json::to_vec(&serde_json::json!({ "suspended": false }))?)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Add an alias to a user in Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `user_key`: The primary email, alias email, or ID of the user.
+    /// * `alias`: The alias to add. Its domain must be a domain of the Google Workspace account.
+    ///
+    /// Mail sent to the alias is delivered to the user, and they can sign in with it.
+    pub async fn insert_user_alias(
+        &self,
+        principal: &str,
+        user_key: &str,
+        alias: &str,
+    ) -> Result<()> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.user.alias";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        self.http
+            .post(format!(
+                "https://admin.googleapis.com/admin/directory/v1/users/{user_key}/aliases"
+            ))
+            .bearer_auth(&access_token)
+            .body(serde_json::to_vec(&serde_json::json!({ "alias": alias }))?)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Fetch a user from Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `user_key`: The primary email, alias email, or ID of the user to fetch.
+    ///
+    /// This function returns `None` if no such user exists.
+    pub async fn get_user(&self, principal: &str, user_key: &str) -> Result<Option<WorkspaceUser>> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.user";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let response = self
+            .http
+            .get(format!("https://admin.googleapis.com/admin/directory/v1/users/{user_key}"))
+            .bearer_auth(&access_token)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let user = response.error_for_status()?.json::<WorkspaceUser>().await?;
+
+        Ok(Some(user))
+    }
+
+    /// List the users in an org unit and every org unit below it.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `org_unit_path`: The full path of the org unit (e.g. `/Programs/PantheonUsers`).
+    pub async fn list_users(
+        &self,
+        principal: &str,
+        org_unit_path: &str,
+    ) -> Result<Vec<WorkspaceUser>> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.user.readonly";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let filter = format!("orgUnitPath='{}'", org_unit_path.replace('\'', "\\'"));
+        let mut users = Vec::new();
+        let mut page_token = None::<String>;
+        loop {
+            let mut query = vec![
+                ("customer", "my_customer"),
+                ("query", filter.as_str()),
+                ("maxResults", "500"),
+            ];
+            if let Some(token) = page_token.as_deref() {
+                query.push(("pageToken", token));
+            }
+
+            let page = self
+                .http
+                .get("https://admin.googleapis.com/admin/directory/v1/users")
+                .query(&query)
+                .bearer_auth(&access_token)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<WorkspaceUsers>()
+                .await?;
+
+            users.extend(page.users);
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
+            }
+        }
+
+        Ok(users)
+    }
+
+    /// Fetch an org unit from Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `org_unit_path`: The full path of the org unit to fetch (e.g. `/Programs/PantheonUsers`).
+    ///
+    /// This function returns `None` if no such org unit exists.
+    pub async fn get_org_unit(
+        &self,
+        principal: &str,
+        org_unit_path: &str,
+    ) -> Result<Option<OrgUnit>> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.orgunit";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let response = self
+            .http
+            .get(format!(
+                "https://admin.googleapis.com/admin/directory/v1/customer/my_customer/orgunits/{}",
+                org_unit_path.trim_start_matches('/')
+            ))
+            .bearer_auth(&access_token)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let org_unit = response.error_for_status()?.json::<OrgUnit>().await?;
+
+        Ok(Some(org_unit))
+    }
+
+    /// List every org unit in Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    ///
+    /// The root org unit (`/`) is not included.
+    pub async fn list_org_units(&self, principal: &str) -> Result<Vec<OrgUnit>> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.orgunit";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let org_units = self
+            .http
+            .get("https://admin.googleapis.com/admin/directory/v1/customer/my_customer/orgunits")
+            .query(&[("type", "all")])
+            .bearer_auth(&access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<OrgUnits>()
+            .await?;
+
+        Ok(org_units.organization_units)
+    }
+
+    /// Create a new org unit in Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `data`: The org unit to create. Its parent must already exist.
+    ///
+    /// This function returns the newly created org unit.
+    pub async fn create_org_unit(&self, principal: &str, data: CreateOrgUnit) -> Result<OrgUnit> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.orgunit";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let org_unit = self
+            .http
+            .post("https://admin.googleapis.com/admin/directory/v1/customer/my_customer/orgunits")
+            .bearer_auth(&access_token)
+            .body(serde_json::to_vec(&data)?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<OrgUnit>()
+            .await?;
+
+        Ok(org_unit)
+    }
+
+    /// Fetch a group from Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `group_key`: The email, alias email, or ID of the group to fetch.
+    ///
+    /// This function returns `None` if no such group exists.
+    pub async fn get_group(&self, principal: &str, group_key: &str) -> Result<Option<Group>> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.group";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let response = self
+            .http
+            .get(format!("https://admin.googleapis.com/admin/directory/v1/groups/{group_key}"))
+            .bearer_auth(&access_token)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let group = response.error_for_status()?.json::<Group>().await?;
+
+        Ok(Some(group))
+    }
+
+    /// Create a new group in Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `data`: The group to create.
+    ///
+    /// This function returns the newly created group.
+    pub async fn create_group(&self, principal: &str, data: CreateGroup) -> Result<Group> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.group";
+        let url = "https://admin.googleapis.com/admin/directory/v1/groups";
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let group = self
+            .http
+            .post(url)
+            .bearer_auth(&access_token)
+            .body(serde_json::to_vec(&data)?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Group>()
+            .await?;
+
+        Ok(group)
+    }
+
+    /// Add a member to a group in Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `group_key`: The email, alias email, or ID of the group.
+    /// * `member_email`: The email of the user to add to the group.
+    ///
+    /// The user is added as a regular member. This function returns `None` if the user is already
+    /// a member of the group, since there is nothing to do.
+    pub async fn add_group_member(
+        &self,
+        principal: &str,
+        group_key: &str,
+        member_email: &str,
+    ) -> Result<Option<Member>> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.group.member";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let data = CreateMember { email: member_email.to_owned(), role: "MEMBER".to_owned() };
+        let response = self
+            .http
+            .post(format!(
+                "https://admin.googleapis.com/admin/directory/v1/groups/{group_key}/members"
+            ))
+            .bearer_auth(&access_token)
+            .body(serde_json::to_vec(&data)?)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::CONFLICT {
+            return Ok(None);
+        }
+
+        let member = response.error_for_status()?.json::<Member>().await?;
+
+        Ok(Some(member))
+    }
+
+    /// Create a shared drive in Google Drive.
+    ///
+    /// * `principal`: The email of the user requesting this action. They become the drive's first
+    ///   organizer.
+    /// * `request_id`: A unique ID for this request. Repeating a request with the same ID does not
+    ///   create a second drive.
+    /// * `data`: The drive to create.
+    ///
+    /// This function returns `None` if a drive was already created with `request_id`, since
+    /// Google does not say which drive that was.
+    pub async fn create_drive(
+        &self,
+        principal: &str,
+        request_id: &str,
+        data: CreateDrive,
+    ) -> Result<Option<Drive>> {
+        let scope = "https://www.googleapis.com/auth/drive";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let response = self
+            .http
+            .post("https://www.googleapis.com/drive/v3/drives")
+            .query(&[("requestId", request_id)])
+            .bearer_auth(&access_token)
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&data)?)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::CONFLICT {
+            return Ok(None);
+        }
+
+        let drive = response.error_for_status()?.json::<Drive>().await?;
+
+        Ok(Some(drive))
+    }
+
+    /// Give a user access to a shared drive.
+    ///
+    /// * `principal`: The email of the user requesting this action. They must be an organizer of
+    ///   the drive.
+    /// * `drive_id`: The ID of the shared drive.
+    /// * `email`: The email of the user to give access to.
+    /// * `role`: The role to give the user (e.g. `writer`).
+    ///
+    /// The user is not notified by email. Giving a user access they already have succeeds.
+    pub async fn create_drive_permission(
+        &self,
+        principal: &str,
+        drive_id: &str,
+        email: &str,
+        role: &str,
+    ) -> Result<Permission> {
+        let scope = "https://www.googleapis.com/auth/drive";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let data = CreatePermission {
+            role: role.to_owned(),
+            permission_type: "user".to_owned(),
+            email_address: email.to_owned(),
+        };
+        let permission = self
+            .http
+            .post(format!("https://www.googleapis.com/drive/v3/files/{drive_id}/permissions"))
+            .query(&[("supportsAllDrives", "true"), ("sendNotificationEmail", "false")])
+            .bearer_auth(&access_token)
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&data)?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Permission>()
+            .await?;
+
+        Ok(permission)
+    }
+
+    /// Create an event in Google Calendar.
+    ///
+    /// * `principal`: The email of the user requesting this action. They are the event's organizer.
+    /// * `calendar_id`: The calendar to create the event on (e.g. `primary` for the principal's own
+    ///   calendar).
+    /// * `data`: The event to create. If it has an ID, creating it again does not create a second
+    ///   event.
+    ///
+    /// Attendees are sent an invitation. This function returns `None` if an event with the same ID
+    /// already exists.
+    pub async fn create_event(
+        &self,
+        principal: &str,
+        calendar_id: &str,
+        data: CreateEvent,
+    ) -> Result<Option<Event>> {
+        let scope = "https://www.googleapis.com/auth/calendar.events";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let response = self
+            .http
+            .post(format!("https://www.googleapis.com/calendar/v3/calendars/{calendar_id}/events"))
+            .query(&[("sendUpdates", "all")])
+            .bearer_auth(&access_token)
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&data)?)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::CONFLICT {
+            return Ok(None);
+        }
+
+        let event = response.error_for_status()?.json::<Event>().await?;
+
+        Ok(Some(event))
+    }
+
+    /// Fetch an event from Google Calendar.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `calendar_id`: The calendar the event is on.
+    /// * `event_id`: The ID of the event.
+    ///
+    /// This function returns `None` if no such event exists.
+    pub async fn get_event(
+        &self,
+        principal: &str,
+        calendar_id: &str,
+        event_id: &str,
+    ) -> Result<Option<Event>> {
+        let scope = "https://www.googleapis.com/auth/calendar.events";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let response = self
+            .http
+            .get(format!(
+                "https://www.googleapis.com/calendar/v3/calendars/{calendar_id}/events/{event_id}"
+            ))
+            .bearer_auth(&access_token)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let event = response.error_for_status()?.json::<Event>().await?;
+
+        Ok(Some(event))
+    }
+
+    /// Invite users to an event in Google Calendar.
+    ///
+    /// * `principal`: The email of the user requesting this action. They must be able to edit the
+    ///   event.
+    /// * `calendar_id`: The calendar the event is on.
+    /// * `event_id`: The ID of the event.
+    /// * `emails`: The emails of the users to invite.
+    ///
+    /// Google replaces an event's attendees wholesale, so the event is fetched first and the users
+    /// are added to its current attendees. Only the users who were not already invited are sent an
+    /// invitation.
+    pub async fn add_event_attendees(
+        &self,
+        principal: &str,
+        calendar_id: &str,
+        event_id: &str,
+        emails: &[String],
+    ) -> Result<Event> {
+        let scope = "https://www.googleapis.com/auth/calendar.events";
+
+        let Some(event) = self.get_event(principal, calendar_id, event_id).await? else {
+            bail!("Event {event_id} does not exist");
+        };
+
+        let mut attendees = event.attendees;
+        for email in emails {
+            if !attendees.iter().any(|a| a.email.eq_ignore_ascii_case(email)) {
+                attendees.push(EventAttendee { email: email.clone(), response_status: None });
+            }
+        }
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let event = self
+            .http
+            .patch(format!(
+                "https://www.googleapis.com/calendar/v3/calendars/{calendar_id}/events/{event_id}"
+            ))
+            .query(&[("sendUpdates", "all")])
+            .bearer_auth(&access_token)
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&PatchEventAttendees { attendees })?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Event>()
+            .await?;
+
+        Ok(event)
+    }
+
+    /// Fetch a custom user schema from Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `schema_key`: The name or ID of the schema.
+    ///
+    /// This function returns `None` if no such schema exists.
+    pub async fn get_schema(&self, principal: &str, schema_key: &str) -> Result<Option<Schema>> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.userschema";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let response = self
+            .http
+            .get(format!(
+                "https://admin.googleapis.com/admin/directory/v1/customer/my_customer/schemas/{schema_key}"
+            ))
+            .bearer_auth(&access_token)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let schema = response.error_for_status()?.json::<Schema>().await?;
+
+        Ok(Some(schema))
+    }
+
+    /// Create a custom user schema in Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `data`: The schema to create.
+    ///
+    /// This function returns the newly created schema. Users can only be given values for a
+    /// schema's fields once the schema exists.
+    pub async fn create_schema(&self, principal: &str, data: CreateSchema) -> Result<Schema> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.userschema";
+        let url = "https://admin.googleapis.com/admin/directory/v1/customer/my_customer/schemas";
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let schema = self
+            .http
+            .post(url)
+            .bearer_auth(&access_token)
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&data)?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Schema>()
+            .await?;
+
+        Ok(schema)
+    }
+
+    /// Fetch the ID of the Google Workspace account (e.g. `C00000000`).
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    pub async fn get_customer_id(&self, principal: &str) -> Result<String> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.customer.readonly";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let customer = self
+            .http
+            .get("https://admin.googleapis.com/admin/directory/v1/customers/my_customer")
+            .bearer_auth(&access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Customer>()
+            .await?;
+
+        Ok(customer.id)
+    }
+
+    /// List the users that have been assigned a license.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `customer_id`: The ID of the Google Workspace account (see `get_customer_id`).
+    /// * `product_id`: The product the license is for (e.g. `Google-Apps`).
+    /// * `sku_id`: The edition of the product (e.g. `1010020027` for Business Starter).
+    pub async fn list_license_assignments(
+        &self,
+        principal: &str,
+        customer_id: &str,
+        product_id: &str,
+        sku_id: &str,
+    ) -> Result<Vec<LicenseAssignment>> {
+        let scope = "https://www.googleapis.com/auth/apps.licensing";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let url = format!(
+            "https://licensing.googleapis.com/apps/licensing/v1/product/{product_id}/sku/{sku_id}/users"
+        );
+        let mut assignments = Vec::new();
+        let mut page_token = None::<String>;
+        loop {
+            let mut query = vec![("customerId", customer_id), ("maxResults", "1000")];
+            if let Some(token) = page_token.as_deref() {
+                query.push(("pageToken", token));
+            }
+
+            let page = self
+                .http
+                .get(&url)
+                .query(&query)
+                .bearer_auth(&access_token)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<LicenseAssignmentList>()
+                .await?;
+
+            assignments.extend(page.items);
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
+            }
+        }
+
+        Ok(assignments)
+    }
+
+    /// List the policies that apply settings to the users of the Google Workspace account.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `filter`: Which policies to list (e.g.
+    ///   `setting.type.matches('settings/security.two_step_verification.*')`).
+    pub async fn list_policies(&self, principal: &str, filter: &str) -> Result<Vec<Policy>> {
+        let scope = "https://www.googleapis.com/auth/cloud-identity.policies.readonly";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let mut policies = Vec::new();
+        let mut page_token = None::<String>;
+        loop {
+            let mut query = vec![("filter", filter), ("pageSize", "100")];
+            if let Some(token) = page_token.as_deref() {
+                query.push(("pageToken", token));
+            }
+
+            let page = self
+                .http
+                .get("https://cloudidentity.googleapis.com/v1/policies")
+                .query(&query)
+                .bearer_auth(&access_token)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<Policies>()
+                .await?;
+
+            policies.extend(page.policies);
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
+            }
+        }
+
+        Ok(policies)
+    }
+
+    /// Assign a license to a user.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `product_id`: The product the license is for (e.g. `Google-Apps`).
+    /// * `sku_id`: The edition of the product (e.g. `1010020027` for Business Starter).
+    /// * `user_id`: The primary email or ID of the user.
+    ///
+    /// This function returns `None` if the user already has the license, since there is nothing
+    /// to do.
+    pub async fn assign_license(
+        &self,
+        principal: &str,
+        product_id: &str,
+        sku_id: &str,
+        user_id: &str,
+    ) -> Result<Option<LicenseAssignment>> {
+        let scope = "https://www.googleapis.com/auth/apps.licensing";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let response = self
+            .http
+            .post(format!(
+                "https://licensing.googleapis.com/apps/licensing/v1/product/{product_id}/sku/{sku_id}/user"
+            ))
+            .bearer_auth(&access_token)
+            .body(serde_json::to_vec(&serde_json::json!({ "userId": user_id }))?)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::CONFLICT {
+            return Ok(None);
+        }
+
+        let assignment = response.error_for_status()?.json::<LicenseAssignment>().await?;
+
+        Ok(Some(assignment))
+    }
+
+    /// List the domains of the Google Workspace account.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    pub async fn list_domains(&self, principal: &str) -> Result<Vec<Domain>> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.domain.readonly";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let domains = self
+            .http
+            .get("https://admin.googleapis.com/admin/directory/v1/customer/my_customer/domains")
+            .bearer_auth(&access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Domains>()
+            .await?;
+
+        Ok(domains.domains)
+    }
+
+    /// Set the Gmail signature of a user's primary address.
+    ///
+    /// * `user_email`: The primary email of the user. Gmail settings can only be changed by the
+    ///   user they belong to, so the service account acts as them rather than as an admin.
+    /// * `signature`: The signature, as HTML. An empty signature removes it.
+    pub async fn update_signature(&self, user_email: &str, signature: &str) -> Result<()> {
+        let scope = "https://www.googleapis.com/auth/gmail.settings.basic";
+
+        let access_token = self.get_access_token(user_email, scope).await?;
+
+        self.http
+            .patch(format!(
+                "https://gmail.googleapis.com/gmail/v1/users/me/settings/sendAs/{user_email}"
+            ))
+            .bearer_auth(&access_token)
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&serde_json::json!({ "signature": signature }))?)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// Set a user's profile photo.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `user_key`: The primary email, alias email, or ID of the user.
+    /// * `photo`: The contents of the photo file.
+    /// * `mime_type`: The format of the photo (`JPEG`, `PNG`, `GIF`, `BMP`, or `TIFF`).
+    pub async fn update_user_photo(
+        &self,
+        principal: &str,
+        user_key: &str,
+        photo: &[u8],
+        mime_type: &str,
+    ) -> Result<()> {
+        let scope = "https://www.googleapis.com/auth/admin.directory.user";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        self.http
+            .put(format!(
+                "https://admin.googleapis.com/admin/directory/v1/users/{user_key}/photos/thumbnail"
+            ))
+            .bearer_auth(&access_token)
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&serde_json::json!({
+                "photoData": URL_SAFE.encode(photo),
+                "mimeType": mime_type,
+            }))?)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+
+    /// List the applications whose data can be transferred between users.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    pub async fn list_transfer_applications(&self, principal: &str) -> Result<Vec<Application>> {
+        let scope = "https://www.googleapis.com/auth/admin.datatransfer";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let mut applications = Vec::new();
+        let mut page_token = None::<String>;
+        loop {
+            let mut query = vec![("customerId", "my_customer")];
+            if let Some(token) = page_token.as_deref() {
+                query.push(("pageToken", token));
+            }
+
+            let page = self
+                .http
+                .get("https://admin.googleapis.com/admin/datatransfer/v1/applications")
+                .query(&query)
+                .bearer_auth(&access_token)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<Applications>()
+                .await?;
+
+            applications.extend(page.applications);
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
+            }
+        }
+
+        Ok(applications)
+    }
+
+    /// Start transferring one user's data to another user.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `data`: The transfer to start.
+    ///
+    /// The transfer runs in the background, so the returned transfer is usually still in progress.
+    pub async fn create_transfer(
+        &self,
+        principal: &str,
+        data: DataTransfer,
+    ) -> Result<DataTransfer> {
+        let scope = "https://www.googleapis.com/auth/admin.datatransfer";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let transfer = self
+            .http
+            .post("https://admin.googleapis.com/admin/datatransfer/v1/transfers")
+            .bearer_auth(&access_token)
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&data)?)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<DataTransfer>()
+            .await?;
+
+        Ok(transfer)
+    }
+
+    /// Fetch a data transfer.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `transfer_id`: The ID of the transfer.
+    ///
+    /// This function returns `None` if no such transfer exists.
+    pub async fn get_transfer(
+        &self,
+        principal: &str,
+        transfer_id: &str,
+    ) -> Result<Option<DataTransfer>> {
+        let scope = "https://www.googleapis.com/auth/admin.datatransfer";
+
+        let access_token = self.get_access_token(principal, scope).await?;
+
+        let response = self
+            .http
+            .get(format!(
+                "https://admin.googleapis.com/admin/datatransfer/v1/transfers/{transfer_id}"
+            ))
+            .bearer_auth(&access_token)
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let transfer = response.error_for_status()?.json::<DataTransfer>().await?;
+
+        Ok(Some(transfer))
+    }
 }
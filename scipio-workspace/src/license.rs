@@ -0,0 +1,41 @@
+//! This module defines the license entities that ServiceAccount relies on.
+//!
+//! Complete documentation of these entities may be found
+//! [here](https://developers.google.com/admin-sdk/licensing/reference/rest/v1/licenseAssignments)
+
+// There's no point documenting here because everything can be found at the link in the file
+// header.
+#![allow(missing_docs)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseAssignment {
+    pub product_id: String,
+    pub sku_id: String,
+    pub user_id: String,
+    pub product_name: Option<String>,
+    pub sku_name: Option<String>,
+    pub self_link: Option<String>,
+    pub etag: Option<String>,
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseAssignmentList {
+    pub etag: Option<String>,
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub items: Vec<LicenseAssignment>,
+    pub next_page_token: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Customer {
+    pub id: String,
+    pub customer_domain: Option<String>,
+    pub etag: Option<String>,
+    pub kind: Option<String>,
+}
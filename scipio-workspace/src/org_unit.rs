@@ -0,0 +1,42 @@
+//! This module defines the org unit entities that ServiceAccount relies on.
+//!
+//! Complete documentation of these entities may be found
+//! [here](https://developers.google.com/admin-sdk/directory/reference/rest/v1/orgunits)
+
+// There's no point documenting here because everything can be found at the link in the file
+// header.
+#![allow(missing_docs)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrgUnit {
+    pub name: String,
+    pub description: Option<String>,
+    pub etag: Option<String>,
+    pub kind: Option<String>,
+    pub org_unit_path: String,
+    pub org_unit_id: Option<String>,
+    pub parent_org_unit_path: Option<String>,
+    pub parent_org_unit_id: Option<String>,
+    #[serde(default)]
+    pub block_inheritance: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrgUnits {
+    pub etag: Option<String>,
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub organization_units: Vec<OrgUnit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrgUnit {
+    pub name: String,
+    pub parent_org_unit_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
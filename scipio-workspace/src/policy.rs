@@ -0,0 +1,90 @@
+//! This module defines the policy entities that ServiceAccount relies on.
+//!
+//! Complete documentation of these entities may be found
+//! [here](https://cloud.google.com/identity/docs/reference/rest/v1/policies) and the settings
+//! they hold [here](https://cloud.google.com/identity/docs/concepts/supported-policy-api-settings)
+
+// There's no point documenting here because everything can be found at the links in the file
+// header.
+#![allow(missing_docs)]
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The setting that enforces 2-Step Verification.
+pub const TWO_STEP_VERIFICATION_ENFORCEMENT: &str =
+    "settings/security.two_step_verification_enforcement";
+
+/// The setting that lets users enroll in 2-Step Verification.
+pub const TWO_STEP_VERIFICATION_ENROLLMENT: &str =
+    "settings/security.two_step_verification_enrollment";
+
+/// The setting that gives new users time to enroll in 2-Step Verification before it is enforced.
+pub const TWO_STEP_VERIFICATION_GRACE_PERIOD: &str =
+    "settings/security.two_step_verification_grace_period";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Policy {
+    pub name: String,
+    pub customer: Option<String>,
+    pub policy_query: PolicyQuery,
+    pub setting: PolicySetting,
+    #[serde(rename = "type")]
+    pub policy_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyQuery {
+    pub org_unit: Option<String>,
+    pub group: Option<String>,
+    pub query: Option<String>,
+    pub sort_order: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicySetting {
+    #[serde(rename = "type")]
+    pub setting_type: String,
+    #[serde(default)]
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Policies {
+    #[serde(default)]
+    pub policies: Vec<Policy>,
+    pub next_page_token: Option<String>,
+}
+
+/// Find the policy that applies a setting to the users of an org unit.
+///
+/// * `policies`: The policies to search
+/// * `setting_type`: The setting (e.g. `TWO_STEP_VERIFICATION_ENFORCEMENT`)
+/// * `org_unit_ids`: The IDs of the org unit and its ancestors, nearest first, without the `id:`
+///   prefix the Directory API gives them
+///
+/// Org units inherit the settings of their nearest ancestor that sets them. Policies applied to
+/// groups are left out, since they do not apply to every user in the org unit. If an org unit has
+/// more than one policy for the setting, the one with the highest sort order wins.
+pub fn effective_policy<'a>(
+    policies: &'a [Policy],
+    setting_type: &str,
+    org_unit_ids: &[String],
+) -> Option<&'a Policy> {
+    org_unit_ids.iter().find_map(|id| {
+        let org_unit = format!("orgUnits/{id}");
+        policies
+            .iter()
+            .filter(|p| p.setting.setting_type == setting_type)
+            .filter(|p| p.policy_query.group.is_none())
+            .filter(|p| p.policy_query.org_unit.as_deref() == Some(org_unit.as_str()))
+            .max_by(|a, b| {
+                let a = a.policy_query.sort_order.unwrap_or_default();
+                let b = b.policy_query.sort_order.unwrap_or_default();
+                a.total_cmp(&b)
+            })
+    })
+}
@@ -0,0 +1,204 @@
+//! This module defines middleware that backs off when Google rate limits the service account.
+//!
+//! Google reports rate limiting either as a 429, or as a 403 whose reason is `rateLimitExceeded`
+//! or `userRateLimitExceeded`, and may say how long to wait in a `Retry-After` header. Every
+//! request a service account makes counts against the same quota, so being rate limited pauses
+//! every request made through the client, not just the one that was rejected. The number of
+//! requests in flight at once is also capped, so that several jobs sharing a client cannot
+//! exhaust the quota between them.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use http::Extensions;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Request, Response, ResponseBuilderExt, StatusCode};
+use reqwest_middleware::{Middleware, Next};
+use serde_json::Value;
+use tokio::sync::Semaphore;
+use tokio::time::Instant;
+
+/// The reasons Google gives in a 403 response when the request was rate limited, rather than
+/// forbidden.
+const RATE_LIMIT_REASONS: [&str; 2] = ["rateLimitExceeded", "userRateLimitExceeded"];
+
+/// How long to wait before the first retry when Google does not say how long to wait. Each
+/// subsequent retry doubles the delay.
+const BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// The longest the middleware will wait before retrying. If Google asks for a longer wait, the
+/// rate-limited response is returned to the caller instead.
+const MAX_DELAY: Duration = Duration::from_secs(120);
+
+/// Middleware that caps the number of requests in flight, and pauses and retries requests when
+/// Google rate limits them.
+///
+/// * `budget`: Permits for the requests that may be in flight at once
+/// * `paused_until`: When requests may be sent again after the last rate-limited response
+/// * `max_retries`: The most times a single request is retried after being rate limited
+pub(crate) struct RateLimitMiddleware {
+    budget: Semaphore,
+    paused_until: Mutex<Instant>,
+    max_retries: u32,
+}
+
+impl RateLimitMiddleware {
+    /// Create the middleware.
+    ///
+    /// * `max_concurrent_requests`: The most requests that may be in flight at once
+    /// * `max_retries`: The most times a single request is retried after being rate limited
+    pub(crate) fn new(max_concurrent_requests: usize, max_retries: u32) -> Self {
+        Self {
+            budget: Semaphore::new(max_concurrent_requests.max(1)),
+            paused_until: Mutex::new(Instant::now()),
+            max_retries,
+        }
+    }
+
+    /// Wait until requests are no longer paused.
+    async fn wait(&self) {
+        let until = *self.paused_until.lock().expect("rate limit lock poisoned");
+        tokio::time::sleep_until(until).await;
+    }
+
+    /// Pause every request for at least `delay`.
+    fn pause(&self, delay: Duration) {
+        let mut paused_until = self.paused_until.lock().expect("rate limit lock poisoned");
+        *paused_until = (*paused_until).max(Instant::now() + delay);
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for RateLimitMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let mut req = req;
+        let mut attempt = 0;
+        loop {
+            // Requests with a streaming body cannot be cloned, so they are only ever sent once
+            let retry = if attempt < self.max_retries { req.try_clone() } else { None };
+
+            self.wait().await;
+            let response = {
+                let _permit = self.budget.acquire().await.expect("request budget closed");
+                next.clone().run(req, extensions).await?
+            };
+
+            let Some(retry) = retry else {
+                return Ok(response);
+            };
+
+            let (response, limited) = check_rate_limit(response).await?;
+            if !limited {
+                return Ok(response);
+            }
+
+            let delay = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| parse_retry_after(value, Utc::now()))
+                .unwrap_or_else(|| backoff_delay(attempt));
+            if delay > MAX_DELAY {
+                log::warn!(
+                    "Rate limited by Google for {}s, which is longer than we are willing to wait",
+                    delay.as_secs()
+                );
+                return Ok(response);
+            }
+
+            log::warn!(
+                "Rate limited by Google with status {}, pausing requests for {}ms",
+                response.status(),
+                delay.as_millis()
+            );
+            self.pause(delay);
+            req = retry;
+            attempt += 1;
+        }
+    }
+}
+
+/// Check whether a response means the request was rate limited.
+///
+/// * `response`: The response to check
+///
+/// A 403 has to be read to tell whether it is a rate limit, so the response is handed back with
+/// its body buffered.
+async fn check_rate_limit(response: Response) -> reqwest::Result<(Response, bool)> {
+    match response.status() {
+        StatusCode::TOO_MANY_REQUESTS => Ok((response, true)),
+        StatusCode::FORBIDDEN => {
+            let (response, body) = buffer(response).await?;
+            Ok((response, is_rate_limit_error(&body)))
+        }
+        _ => Ok((response, false)),
+    }
+}
+
+/// Read the body of a response, and build an identical response around it.
+///
+/// * `response`: The response to read
+async fn buffer(response: Response) -> reqwest::Result<(Response, Vec<u8>)> {
+    let status = response.status();
+    let version = response.version();
+    let url = response.url().clone();
+    let headers = response.headers().clone();
+    let body = response.bytes().await?.to_vec();
+
+    let mut rebuilt = http::Response::builder()
+        .status(status)
+        .version(version)
+        .url(url)
+        .body(body.clone())
+        .expect("parts were taken from a valid response");
+    *rebuilt.headers_mut() = headers;
+
+    Ok((Response::from(rebuilt), body))
+}
+
+/// Whether the body of a 403 response says the request was rate limited.
+///
+/// * `body`: The body of the response
+pub(crate) fn is_rate_limit_error(body: &[u8]) -> bool {
+    let Ok(body) = serde_json::from_slice::<Value>(body) else {
+        return false;
+    };
+
+    body.pointer("/error/errors").and_then(Value::as_array).is_some_and(|errors| {
+        errors.iter().any(|error| {
+            error
+                .get("reason")
+                .and_then(Value::as_str)
+                .is_some_and(|reason| RATE_LIMIT_REASONS.contains(&reason))
+        })
+    })
+}
+
+/// Parse a `Retry-After` header, which is either a number of seconds or an HTTP date.
+///
+/// * `value`: The value of the header
+/// * `now`: The current time, which an HTTP date is measured from
+///
+/// A date in the past means the request may be retried right away.
+pub(crate) fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?.with_timezone(&Utc);
+    Some((date - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// How long to wait before retrying a rate-limited request when Google does not say.
+///
+/// * `attempt`: The number of times the request has already been retried
+pub(crate) fn backoff_delay(attempt: u32) -> Duration {
+    BASE_DELAY.saturating_mul(1u32 << attempt.min(31)).min(MAX_DELAY)
+}
@@ -7,11 +7,9 @@ pub(crate) struct DefaultRetryStrategy;
 impl RetryableStrategy for DefaultRetryStrategy {
     fn handle(&self, res: &Result<Response, reqwest_middleware::Error>) -> Option<Retryable> {
         match res {
-            // retry if 412 or 429
-            Ok(success)
-                if success.status() == StatusCode::PRECONDITION_FAILED
-                    || success.status() == StatusCode::TOO_MANY_REQUESTS =>
-            {
+            // retry if 412. Rate limiting is handled by `RateLimitMiddleware`, which honors the
+            // `Retry-After` header
+            Ok(success) if success.status() == StatusCode::PRECONDITION_FAILED => {
                 println!("Retrying request because of status code: {}", success.status());
                 dbg!(success.status());
                 log::info!("Retrying request because of status code: {}", success.status());
@@ -0,0 +1,60 @@
+//! This module defines the custom user schema entities that ServiceAccount relies on.
+//!
+//! Complete documentation of these entities may be found
+//! [here](https://developers.google.com/admin-sdk/directory/reference/rest/v1/schemas)
+
+// There's no point documenting here because everything can be found at the link in the file
+// header.
+#![allow(missing_docs)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SchemaFieldType {
+    String,
+    Int64,
+    Bool,
+    Double,
+    Email,
+    Phone,
+    Date,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReadAccessType {
+    AllDomainUsers,
+    AdminsAndSelf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde_with::skip_serializing_none]
+pub struct SchemaField {
+    pub field_name: String,
+    pub field_type: SchemaFieldType,
+    pub field_id: Option<String>,
+    pub display_name: Option<String>,
+    pub multi_valued: Option<bool>,
+    pub indexed: Option<bool>,
+    pub read_access_type: Option<ReadAccessType>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schema {
+    pub schema_id: String,
+    pub schema_name: String,
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub fields: Vec<SchemaField>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSchema {
+    pub schema_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    pub fields: Vec<SchemaField>,
+}
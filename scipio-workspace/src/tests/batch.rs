@@ -0,0 +1,78 @@
+use anyhow::Result;
+use serde_json::json;
+
+use crate::batch::{decode, encode, BatchCall, BatchEntryError, BatchResponse};
+use crate::user::WorkspaceUser;
+
+#[test]
+fn test_encode_batch_request() -> Result<()> {
+    let calls = vec![
+        BatchCall {
+            method: "POST",
+            path: "/admin/directory/v1/users".to_owned(),
+            body: Some(json!({ "primaryEmail": "anish@developforgood.org" })),
+        },
+        BatchCall { method: "GET", path: "/admin/directory/v1/users/mary".to_owned(), body: None },
+    ];
+
+    let body = String::from_utf8(encode("batch_abc", &calls)?)?;
+
+    assert!(body.starts_with("--batch_abc\r\nContent-Type: application/http\r\n"));
+    assert!(body.contains("Content-ID: <item-0>\r\n\r\nPOST /admin/directory/v1/users\r\n"));
+    assert!(body.contains("{\"primaryEmail\":\"anish@developforgood.org\"}"));
+    assert!(body.contains("Content-ID: <item-1>\r\n\r\nGET /admin/directory/v1/users/mary\r\n"));
+    assert!(body.ends_with("--batch_abc--\r\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_decode_batch_response() -> Result<()> {
+    // Google may answer calls out of order, and matches them up by Content-ID
+    let body = [
+        "--batch_xyz",
+        "Content-Type: application/http",
+        "Content-ID: <response-item-1>",
+        "",
+        "HTTP/1.1 409 Conflict",
+        "Content-Type: application/json; charset=UTF-8",
+        "",
+        r#"{"error":{"code":409,"message":"Entity already exists."}}"#,
+        "--batch_xyz",
+        "Content-Type: application/http",
+        "Content-ID: <response-item-0>",
+        "",
+        "HTTP/1.1 200 OK",
+        "Content-Type: application/json; charset=UTF-8",
+        "",
+        r#"{"primaryEmail":"anish@developforgood.org"}"#,
+        "--batch_xyz--",
+        "",
+    ]
+    .join("\r\n");
+
+    let responses = decode("multipart/mixed; boundary=batch_xyz", &body, 3)?;
+
+    assert_eq!(responses.len(), 3);
+    assert_eq!(responses[0].as_ref().map(|r| r.status), Some(200));
+    assert_eq!(
+        responses[1],
+        Some(BatchResponse {
+            status: 409,
+            body: r#"{"error":{"code":409,"message":"Entity already exists."}}"#.to_owned(),
+        })
+    );
+    assert_eq!(responses[2], None);
+
+    let err = responses[1].clone().unwrap().json::<WorkspaceUser>().unwrap_err();
+    let err = err.downcast::<BatchEntryError>()?;
+    assert_eq!(err.status, 409);
+    assert_eq!(err.message, "Entity already exists.");
+
+    Ok(())
+}
+
+#[test]
+fn test_decode_batch_response_without_boundary() {
+    assert!(decode("application/json", "", 1).is_err());
+}
@@ -1,4 +1,8 @@
+mod batch;
 mod fixtures;
+mod policy;
+mod rate_limit;
+mod transfer;
 
 use anyhow::Result;
 use rand::distributions::Alphanumeric;
@@ -0,0 +1,54 @@
+use serde_json::json;
+
+use crate::policy::{
+    effective_policy, Policy, TWO_STEP_VERIFICATION_ENFORCEMENT, TWO_STEP_VERIFICATION_GRACE_PERIOD,
+};
+
+fn policy(name: &str, setting_type: &str, org_unit: &str, sort_order: f64) -> Policy {
+    serde_json::from_value(json!({
+        "name": name,
+        "customer": "customers/C00000000",
+        "policyQuery": { "orgUnit": format!("orgUnits/{org_unit}"), "sortOrder": sort_order },
+        "setting": { "type": setting_type, "value": {} },
+        "type": "ADMIN",
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_effective_policy_is_inherited_from_nearest_ancestor() {
+    let policies = vec![
+        policy("policies/root", TWO_STEP_VERIFICATION_ENFORCEMENT, "root", 1.0),
+        policy("policies/programs", TWO_STEP_VERIFICATION_ENFORCEMENT, "programs", 1.0),
+        policy("policies/grace", TWO_STEP_VERIFICATION_GRACE_PERIOD, "cohort", 1.0),
+    ];
+    let ancestors = ["cohort", "programs", "root"].map(str::to_owned);
+
+    let enforcement =
+        effective_policy(&policies, TWO_STEP_VERIFICATION_ENFORCEMENT, &ancestors).unwrap();
+    assert_eq!(enforcement.name, "policies/programs");
+
+    let grace =
+        effective_policy(&policies, TWO_STEP_VERIFICATION_GRACE_PERIOD, &ancestors).unwrap();
+    assert_eq!(grace.name, "policies/grace");
+
+    assert!(
+        effective_policy(&policies, TWO_STEP_VERIFICATION_GRACE_PERIOD, &ancestors[1..]).is_none()
+    );
+}
+
+#[test]
+fn test_effective_policy_prefers_highest_sort_order_and_skips_groups() {
+    let mut group_policy = policy("policies/group", TWO_STEP_VERIFICATION_ENFORCEMENT, "root", 9.0);
+    group_policy.policy_query.group = Some("groups/01abcdef".to_owned());
+    let policies = vec![
+        policy("policies/low", TWO_STEP_VERIFICATION_ENFORCEMENT, "root", 1.0),
+        policy("policies/high", TWO_STEP_VERIFICATION_ENFORCEMENT, "root", 2.0),
+        group_policy,
+    ];
+
+    let enforcement =
+        effective_policy(&policies, TWO_STEP_VERIFICATION_ENFORCEMENT, &["root".to_owned()])
+            .unwrap();
+    assert_eq!(enforcement.name, "policies/high");
+}
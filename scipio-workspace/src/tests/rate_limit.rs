@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+
+use crate::rate_limit::{backoff_delay, is_rate_limit_error, parse_retry_after};
+
+#[test]
+fn test_parse_retry_after() {
+    let now = Utc.with_ymd_and_hms(2024, 10, 21, 7, 28, 0).unwrap();
+
+    assert_eq!(parse_retry_after("30", now), Some(Duration::from_secs(30)));
+    assert_eq!(parse_retry_after(" 0 ", now), Some(Duration::ZERO));
+    assert_eq!(
+        parse_retry_after("Mon, 21 Oct 2024 07:28:45 GMT", now),
+        Some(Duration::from_secs(45))
+    );
+    assert_eq!(parse_retry_after("Mon, 21 Oct 2024 07:27:00 GMT", now), Some(Duration::ZERO));
+    assert_eq!(parse_retry_after("soon", now), None);
+    assert_eq!(parse_retry_after("-5", now), None);
+}
+
+#[test]
+fn test_is_rate_limit_error() {
+    let rate_limited = json!({
+        "error": {
+            "code": 403,
+            "message": "Rate Limit Exceeded",
+            "errors": [{ "reason": "rateLimitExceeded", "domain": "usageLimits" }]
+        }
+    });
+    let user_rate_limited = json!({
+        "error": { "code": 403, "errors": [{ "reason": "userRateLimitExceeded" }] }
+    });
+    let forbidden = json!({
+        "error": { "code": 403, "errors": [{ "reason": "forbidden" }] }
+    });
+
+    assert!(is_rate_limit_error(rate_limited.to_string().as_bytes()));
+    assert!(is_rate_limit_error(user_rate_limited.to_string().as_bytes()));
+    assert!(!is_rate_limit_error(forbidden.to_string().as_bytes()));
+    assert!(!is_rate_limit_error(b"Forbidden"));
+}
+
+#[test]
+fn test_backoff_delay() {
+    assert_eq!(backoff_delay(0), Duration::from_secs(1));
+    assert_eq!(backoff_delay(3), Duration::from_secs(8));
+    assert_eq!(backoff_delay(40), Duration::from_secs(120));
+}
@@ -0,0 +1,38 @@
+use serde_json::json;
+
+use crate::transfer::{drive_application, Applications, DataTransfer, PRIVACY_LEVEL};
+
+#[test]
+fn test_drive_application_is_found_by_name() {
+    let applications: Applications = serde_json::from_value(json!({
+        "kind": "admin#datatransfer#applicationsList",
+        "applications": [
+            { "id": "435070579839", "name": "Calendar", "transferParams": [] },
+            {
+                "id": "55656082996",
+                "name": "Drive and Docs",
+                "transferParams": [{ "key": "PRIVACY_LEVEL", "value": ["PRIVATE", "SHARED"] }],
+            },
+        ],
+    }))
+    .unwrap();
+
+    let drive = drive_application(&applications.applications).unwrap();
+    assert_eq!(drive.id, "55656082996");
+
+    assert!(drive_application(&applications.applications[..1]).is_none());
+}
+
+#[test]
+fn test_drive_transfer_moves_private_and_shared_files() {
+    let transfer = DataTransfer::drive("55656082996", "111", "222");
+    let body = serde_json::to_value(&transfer).unwrap();
+
+    assert_eq!(body["oldOwnerUserId"], "111");
+    assert_eq!(body["newOwnerUserId"], "222");
+    assert_eq!(body["applicationDataTransfers"][0]["applicationId"], "55656082996");
+    assert_eq!(
+        body["applicationDataTransfers"][0]["applicationTransferParams"][0],
+        json!({ "key": PRIVACY_LEVEL, "value": ["PRIVATE", "SHARED"] })
+    );
+}
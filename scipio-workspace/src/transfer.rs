@@ -0,0 +1,103 @@
+//! This module defines the data transfer entities that ServiceAccount relies on.
+//!
+//! Complete documentation of these entities may be found
+//! [here](https://developers.google.com/admin-sdk/data-transfer/reference/rest/v1/transfers) and
+//! [here](https://developers.google.com/admin-sdk/data-transfer/reference/rest/v1/applications)
+
+// There's no point documenting here because everything can be found at the links in the file
+// header.
+#![allow(missing_docs)]
+use serde::{Deserialize, Serialize};
+
+/// The name of the application that transfers a user's Google Drive files.
+pub const DRIVE_APPLICATION_NAME: &str = "Drive and Docs";
+
+/// The transfer parameter that selects which of a user's Drive files are transferred.
+pub const PRIVACY_LEVEL: &str = "PRIVACY_LEVEL";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataTransfer {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    pub old_owner_user_id: String,
+    pub new_owner_user_id: String,
+    #[serde(default)]
+    pub application_data_transfers: Vec<ApplicationDataTransfer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overall_transfer_status_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_time: Option<String>,
+}
+
+impl DataTransfer {
+    /// A transfer of every Drive file one user owns, private or shared, to another user.
+    ///
+    /// * `application_id`: The ID of the Drive application
+    /// * `old_owner_user_id`: The ID of the user whose files are transferred
+    /// * `new_owner_user_id`: The ID of the user who receives the files
+    pub fn drive(application_id: &str, old_owner_user_id: &str, new_owner_user_id: &str) -> Self {
+        Self {
+            id: None,
+            kind: None,
+            old_owner_user_id: old_owner_user_id.to_owned(),
+            new_owner_user_id: new_owner_user_id.to_owned(),
+            application_data_transfers: vec![ApplicationDataTransfer {
+                application_id: application_id.to_owned(),
+                application_transfer_params: vec![ApplicationTransferParam {
+                    key: PRIVACY_LEVEL.to_owned(),
+                    value: vec!["PRIVATE".to_owned(), "SHARED".to_owned()],
+                }],
+                application_transfer_status: None,
+            }],
+            overall_transfer_status_code: None,
+            request_time: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplicationDataTransfer {
+    pub application_id: String,
+    #[serde(default)]
+    pub application_transfer_params: Vec<ApplicationTransferParam>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application_transfer_status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplicationTransferParam {
+    pub key: String,
+    #[serde(default)]
+    pub value: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Application {
+    pub id: String,
+    pub name: String,
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub transfer_params: Vec<ApplicationTransferParam>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Applications {
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub applications: Vec<Application>,
+    pub next_page_token: Option<String>,
+}
+
+/// Find the application that transfers Drive files.
+///
+/// * `applications`: The applications whose data can be transferred
+pub fn drive_application(applications: &[Application]) -> Option<&Application> {
+    applications.iter().find(|a| a.name == DRIVE_APPLICATION_NAME)
+}
@@ -8,7 +8,7 @@
 #![allow(missing_docs)]
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 /// The hash function used to hash the user's password.
 ///
@@ -451,7 +451,7 @@ pub struct WorkspaceUser {
     #[serde(default)]
     pub ims: Vec<Im>,
     #[serde(default)]
-    pub custom_schemas: Vec<Value>,
+    pub custom_schemas: Map<String, Value>,
     #[serde(default, rename = "isEnrolledIn2Sv")]
     pub is_enrolled_in_two_step_verification: bool,
     #[serde(default, rename = "isEnforcedIn2Sv")]
@@ -463,6 +463,14 @@ pub struct WorkspaceUser {
     pub recovery_phone: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceUsers {
+    #[serde(default)]
+    pub users: Vec<WorkspaceUser>,
+    pub next_page_token: Option<String>,
+}
+
 /// Information needed to create a user in Google Workspace.
 ///
 /// `primary_email`, `password`, and `name` are required fields.
@@ -514,7 +522,7 @@ pub struct CreateWorkspaceUser {
     #[builder(setter(into), default = "None")]
     pub ims: Option<Vec<Im>>,
     #[builder(setter(into), default = "None")]
-    pub custom_schemas: Option<Vec<Value>>,
+    pub custom_schemas: Option<Map<String, Value>>,
     #[builder(setter(into), default = "None")]
     pub archived: Option<bool>,
     #[builder(setter(into), default = "None")]
@@ -532,3 +540,23 @@ impl TryFrom<CreateWorkspaceUser> for Vec<u8> {
         Ok(serde_json::to_vec(&value)?)
     }
 }
+
+/// Information needed to update a user in Google Workspace. Fields that are `None` are left as
+/// they are.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Builder)]
+#[serde(rename_all = "camelCase")]
+#[serde_with::skip_serializing_none]
+pub struct UpdateWorkspaceUser {
+    #[builder(setter(into), default = "None")]
+    pub name: Option<UserName>,
+    #[builder(setter(into), default = "None")]
+    pub recovery_email: Option<String>,
+}
+
+impl TryFrom<UpdateWorkspaceUser> for Vec<u8> {
+    type Error = anyhow::Error;
+
+    fn try_from(value: UpdateWorkspaceUser) -> std::result::Result<Self, Self::Error> {
+        Ok(serde_json::to_vec(&value)?)
+    }
+}
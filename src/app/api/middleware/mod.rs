@@ -18,6 +18,7 @@ use crate::app::api_response;
 use crate::app::errors::AppError;
 use crate::app::state::Services;
 use crate::services::auth::AuthData;
+use crate::services::storage::organizations::with_org;
 
 /// Middleware for role-based access control (RBAC).
 ///
@@ -25,6 +26,9 @@ use crate::services::auth::AuthData;
 /// * `request`: The request object from axum
 /// * `next`: The next middleware in the chain
 /// * `permissions`: The permissions required to access the route (such as `["read:volunteers"]`)
+///
+/// The rest of the request is scoped to the user's organization, so every query it makes only
+/// sees that organization's data.
 pub async fn rbac(
     State(ctx): State<Arc<Services>>,
     header: TypedHeader<Authorization<Bearer>>,
@@ -40,8 +44,9 @@ pub async fn rbac(
     match data {
         AuthData::Auth0(ref rbac_data) => {
             if permissions.iter().all(|p| rbac_data.permissions.contains(p)) {
+                let org_id = data.org_id();
                 request.extensions_mut().insert(data);
-                Ok(next.run(request).await)
+                Ok(with_org(org_id, next.run(request)).await)
             } else {
                 Ok(api_response::error(StatusCode::FORBIDDEN, "unauthorized"))
             }
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::Response;
+
+use super::requests::AuditLogQuery;
+use super::responses::AuditLogResponse;
+use crate::app::api_response;
+use crate::app::errors::AppError;
+use crate::app::state::Services;
+use crate::services::storage::audit::{AuditLogCursor, AuditLogFilter};
+use crate::services::storage::ExecOptsBuilder;
+
+/// List the changes recorded in the audit log, newest first, one page at a time.
+///
+/// * `ctx`: The application context
+/// * `query`: Filters and pagination for the audit log
+///
+/// Pass the `nextCursor` of a page as the `cursor` of the next request to fetch the page after it.
+#[utoipa::path(
+    get,
+    path = "/",
+    operation_id = "Get audit log",
+    responses(
+        (status = 200, description = "Successfully fetched a page of the audit log"),
+        (status = 400, description = "The cursor is malformed"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:audit`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer"),
+        ("changedAfter" = Option<String>, Query, description = "Only list changes made at or after this time (RFC 3339)"),
+        ("changedBefore" = Option<String>, Query, description = "Only list changes made before this time (RFC 3339)"),
+        ("cursor" = Option<String>, Query, description = "Where the page starts, as returned by the previous page"),
+        ("entity" = Option<String>, Query, description = "Only list changes to rows of this table"),
+        ("entityId" = Option<String>, Query, description = "Only list changes to the row with this id"),
+        ("limit" = Option<i64>, Query, description = "The most changes to list (at most 500)"),
+        ("operation" = Option<String>, Query, description = "Only list inserts, updates, or deletes"),
+        ("principal" = Option<String>, Query, description = "Only list changes made by this user")
+    ),
+)]
+pub async fn fetch_audit_log(
+    State(ctx): State<Arc<Services>>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<Response, AppError> {
+    let cursor = match query.cursor.as_deref().map(AuditLogCursor::decode) {
+        None => None,
+        Some(Ok(cursor)) => Some(cursor),
+        Some(Err(e)) => return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string())),
+    };
+
+    let page_size = query.page_size();
+    let filter = AuditLogFilter {
+        entity: query.entity,
+        entity_id: query.entity_id,
+        principal: query.principal,
+        operation: query.operation,
+        changed_after: query.changed_after,
+        changed_before: query.changed_before,
+    };
+
+    // One extra change is fetched to tell whether there is another page
+    let mut entries = ctx
+        .storage_layer
+        .fetch_audit_log(filter, cursor, page_size + 1, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    let next_cursor = if entries.len() as i64 > page_size {
+        entries.truncate(page_size as usize);
+        entries.last().map(|e| AuditLogCursor::after(e).encode())
+    } else {
+        None
+    };
+
+    Ok(api_response::success(StatusCode::OK, AuditLogResponse { entries, next_cursor })?)
+}
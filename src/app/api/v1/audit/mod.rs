@@ -0,0 +1,49 @@
+//! Audit log API.
+//!
+//! Every insert, update, and delete made to the tables that hold volunteers' data is recorded in
+//! the audit log, along with who made it and what the row looked like before and after. Exports
+//! touch volunteers' personal data and account credentials, so staff can review here who changed
+//! what, and when.
+
+use std::sync::Arc;
+
+use axum::middleware::from_fn_with_state;
+use axum::{routing, Router};
+use utoipa::OpenApi;
+
+use crate::app::api::middleware::make_rbac;
+use crate::app::state::Services;
+
+mod controllers;
+mod requests;
+mod responses;
+
+/// How many changes a page of the audit log holds if the client does not say.
+pub const DEFAULT_AUDIT_LOG_PAGE_SIZE: i64 = 100;
+
+/// The most changes a page of the audit log can hold.
+pub const MAX_AUDIT_LOG_PAGE_SIZE: i64 = 500;
+
+/// Documents the API for reading the audit log
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        controllers::fetch_audit_log,
+    ),
+    security(("http" = ["JWT"]))
+)]
+pub struct AuditApi;
+
+/// Builds the audit log API.
+///
+/// * `ctx`: The application context
+pub async fn build(ctx: Arc<Services>) -> Router<()> {
+    let read_audit_guard = make_rbac(vec!["read:audit".to_owned()]).await;
+
+    let fetch_audit_log = routing::get(controllers::fetch_audit_log);
+
+    Router::new()
+        .route("/", fetch_audit_log)
+        .route_layer(from_fn_with_state(ctx.clone(), read_audit_guard))
+        .with_state(ctx.clone())
+}
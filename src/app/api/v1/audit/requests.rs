@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{DEFAULT_AUDIT_LOG_PAGE_SIZE, MAX_AUDIT_LOG_PAGE_SIZE};
+use crate::services::storage::types::AuditOperation;
+
+/// Query to list a page of the audit log, newest first. Every filter that is set must match.
+///
+/// * `changed_after`: Only list changes made at or after this time
+/// * `changed_before`: Only list changes made before this time
+/// * `cursor`: Where the page starts, as returned by the previous page. Defaults to the first page.
+/// * `entity`: Only list changes to rows of this table (e.g. `volunteers`)
+/// * `entity_id`: Only list changes to the row with this id
+/// * `limit`: The most changes to list. Defaults to 100, and can be at most 500.
+/// * `operation`: Only list inserts, updates, or deletes
+/// * `principal`: Only list changes made by this user
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogQuery {
+    #[serde(default)]
+    pub changed_after: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub changed_before: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub cursor: Option<String>,
+    #[serde(default)]
+    pub entity: Option<String>,
+    #[serde(default)]
+    pub entity_id: Option<String>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub operation: Option<AuditOperation>,
+    #[serde(default)]
+    pub principal: Option<String>,
+}
+
+impl AuditLogQuery {
+    /// How many changes to list, kept between 1 and the most allowed.
+    pub fn page_size(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_AUDIT_LOG_PAGE_SIZE).clamp(1, MAX_AUDIT_LOG_PAGE_SIZE)
+    }
+}
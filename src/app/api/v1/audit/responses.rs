@@ -0,0 +1,14 @@
+use serde::Serialize;
+
+use crate::services::storage::entities::AuditLogEntry;
+
+/// A page of the audit log.
+///
+/// * `entries`: The changes on this page, newest first
+/// * `next_cursor`: The cursor of the next page, or `None` if this is the last page
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogResponse {
+    pub entries: Vec<AuditLogEntry>,
+    pub next_cursor: Option<String>,
+}
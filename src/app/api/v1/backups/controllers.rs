@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::Response;
+use axum::Extension;
+use tokio::task;
+
+use super::responses::CreateBackupResponse;
+use super::{create_backup_job, run_backup};
+use crate::app::api_response;
+use crate::app::errors::AppError;
+use crate::app::state::Services;
+use crate::services::auth::AuthData;
+use crate::services::storage::organizations::with_org;
+
+/// Back up the caller's organization's data to object storage.
+///
+/// * `ctx`: The application context
+/// * `auth`: The caller
+///
+/// The backup runs as a job, whose result records how much was backed up.
+#[utoipa::path(
+    post,
+    path = "/",
+    operation_id = "Create backup",
+    responses(
+        (status = 200, description = "Successfully started a job to back up the organization's data"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:backups`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn create_backup(
+    State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
+) -> Result<Response, AppError> {
+    let org_id = auth.org_id();
+    let (job_id, object_key) = create_backup_job(&ctx, org_id, auth.email().ok()).await?;
+
+    log::info!("Started job {job_id} to back up organization {org_id} to {object_key}");
+
+    let key = object_key.clone();
+    task::spawn(with_org(org_id, async move {
+        let _ = run_backup(&ctx, org_id, job_id, &key).await;
+    }));
+
+    Ok(api_response::success(StatusCode::OK, CreateBackupResponse { job_id, object_key })?)
+}
@@ -0,0 +1,240 @@
+//! Backups API.
+//!
+//! A backup is a logical export of an organization's data (see `storage::backups`) written to
+//! object storage, encrypted with the object store's keys. Backups are taken on a schedule for
+//! every organization, and on demand for the caller's. Each backup runs as a job, so it can be
+//! followed like any other. A backup is restored with the `restore-backup` command.
+
+mod controllers;
+mod responses;
+#[cfg(test)]
+mod tests;
+
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use axum::middleware::from_fn_with_state;
+use axum::{routing, Router};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use crate::app::api::middleware::make_rbac;
+use crate::app::api::v1::data_exports::workspace::retention::parse_period;
+use crate::app::state::Services;
+use crate::services::storage::backups::{Backup, BACKUP_FORMAT_VERSION};
+use crate::services::storage::jobs::{CreateJobBuilder, NEW_JOB_VERSION};
+use crate::services::storage::organizations::{with_org, DEFAULT_ORG_ID};
+use crate::services::storage::types::{JobData, JobDetails, JobType};
+use crate::services::storage::ExecOptsBuilder;
+
+/// The name of the environment variable holding how many hours apart scheduled backups are taken.
+/// `off`, or leaving it unset, takes none.
+pub const BACKUP_INTERVAL_HOURS_ENV_VAR: &str = "BACKUP_INTERVAL_HOURS";
+
+/// The prefix of the keys backups are stored under.
+pub const BACKUP_KEY_PREFIX: &str = "backups";
+
+/// Documents the API for taking backups
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        controllers::create_backup,
+    ),
+    security(("http" = ["JWT"]))
+)]
+pub struct BackupsApi;
+
+/// Builds the backups API, and starts taking scheduled backups if they are configured.
+///
+/// * `ctx`: The application context
+pub async fn build(ctx: Arc<Services>) -> Router<()> {
+    let write_backups_guard = make_rbac(vec!["write:backups".to_owned()]).await;
+
+    let create_backup = routing::post(controllers::create_backup);
+
+    match backup_interval_from_env() {
+        Ok(Some(interval)) => {
+            spawn_backup_task(ctx.clone(), interval);
+        }
+        Ok(None) => {
+            log::info!("Not taking scheduled backups, {BACKUP_INTERVAL_HOURS_ENV_VAR} is off")
+        }
+        Err(e) => log::error!("Not taking scheduled backups: {e}"),
+    }
+
+    Router::new()
+        .route("/", create_backup)
+        .route_layer(from_fn_with_state(ctx.clone(), write_backups_guard))
+        .with_state(ctx.clone())
+}
+
+/// Read how often scheduled backups are taken from `BACKUP_INTERVAL_HOURS`. `None` means none are.
+pub fn backup_interval_from_env() -> Result<Option<Duration>> {
+    let Ok(raw) = env::var(BACKUP_INTERVAL_HOURS_ENV_VAR) else {
+        return Ok(None);
+    };
+    let hours =
+        parse_period(&raw).with_context(|| format!("invalid {BACKUP_INTERVAL_HOURS_ENV_VAR}"))?;
+    Ok(hours.map(|hours| Duration::from_secs(u64::from(hours) * 60 * 60)))
+}
+
+/// The key a backup of an organization taken at a time is stored under. Keys sort by the time
+/// the backup was taken.
+///
+/// * `org_id`: The organization that is backed up
+/// * `taken_at`: When the backup is taken
+pub fn backup_key(org_id: Uuid, taken_at: DateTime<Utc>) -> String {
+    format!("{BACKUP_KEY_PREFIX}/{org_id}/{}.json", taken_at.format("%Y%m%dT%H%M%SZ"))
+}
+
+/// What a backup held, recorded as the result of its job.
+///
+/// * `bytes`: The size of the backup before it was encrypted
+/// * `volunteers`: How many volunteers were backed up
+/// * `jobs`: How many jobs were backed up
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSummary {
+    pub bytes: usize,
+    pub volunteers: usize,
+    pub jobs: usize,
+}
+
+/// Create the job that backs up the current organization. Returns the ID of the job and the key
+/// the backup will be stored under.
+///
+/// * `ctx`: The application services
+/// * `org_id`: The current organization
+/// * `principal`: The user who asked for the backup, if it is not a scheduled one
+pub async fn create_backup_job(
+    ctx: &Services,
+    org_id: Uuid,
+    principal: Option<String>,
+) -> Result<(Uuid, String)> {
+    let object_key = backup_key(org_id, Utc::now());
+    let data = CreateJobBuilder::default()
+        .label("Back Up Database")
+        .description(Some(format!("Back up Pantheon's data to {object_key}")))
+        .data(JobDetails {
+            job_type: JobType::BackupDatabase,
+            error: None,
+            result: None,
+            data: JobData::BackupDatabase { object_key: object_key.clone() },
+        })
+        .build()?;
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    exec_opts.principal = principal;
+    let job_id = ctx.storage_layer.create_job(None, data, &mut exec_opts).await?;
+    Ok((job_id, object_key))
+}
+
+/// Back up the current organization's data to object storage, then mark the backup's job complete,
+/// or errored if the backup failed.
+///
+/// * `ctx`: The application services
+/// * `org_id`: The current organization
+/// * `job_id`: The ID of the backup's job
+/// * `object_key`: The key to store the backup under
+pub async fn run_backup(
+    ctx: &Services,
+    org_id: Uuid,
+    job_id: Uuid,
+    object_key: &str,
+) -> Result<()> {
+    match write_backup(ctx, org_id, object_key).await {
+        Ok(summary) => {
+            log::info!("Backed up organization {org_id} to {object_key} ({} bytes)", summary.bytes);
+            ctx.storage_layer
+                .set_job_result(
+                    job_id,
+                    serde_json::to_value(summary)?,
+                    &mut ExecOptsBuilder::default().build()?,
+                )
+                .await?;
+            ctx.storage_layer
+                .mark_job_complete(
+                    job_id,
+                    NEW_JOB_VERSION,
+                    &mut ExecOptsBuilder::default().build()?,
+                )
+                .await
+        }
+        Err(e) => {
+            log::error!("Failed to back up organization {org_id}: {e:#}");
+            ctx.storage_layer
+                .mark_job_errored(
+                    job_id,
+                    NEW_JOB_VERSION,
+                    format!("{e:#}"),
+                    &mut ExecOptsBuilder::default().build()?,
+                )
+                .await
+        }
+    }
+}
+
+/// Fetch the current organization's data and store it in object storage.
+///
+/// * `ctx`: The application services
+/// * `org_id`: The current organization
+/// * `object_key`: The key to store the backup under
+async fn write_backup(ctx: &Services, org_id: Uuid, object_key: &str) -> Result<BackupSummary> {
+    let data =
+        ctx.storage_layer.fetch_backup_data(&mut ExecOptsBuilder::default().build()?).await?;
+    let count = |table: &str| data[table].as_array().map_or(0, Vec::len);
+    let (volunteers, jobs) = (count("volunteers"), count("jobs"));
+
+    let backup =
+        Backup { format_version: BACKUP_FORMAT_VERSION, org_id, created_at: Utc::now(), data };
+    let body = backup.to_bytes()?;
+    let bytes = body.len();
+
+    ctx.objects.put_object(object_key, body).await?;
+    Ok(BackupSummary { bytes, volunteers, jobs })
+}
+
+/// Start the background task that backs up every organization's data on an interval.
+///
+/// * `ctx`: The application services
+/// * `interval`: How long to wait between backups
+pub fn spawn_backup_task(ctx: Arc<Services>, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        // The first backup is taken an interval after startup, so restarts do not each take one
+        let mut interval = tokio::time::interval_at(Instant::now() + interval, interval);
+        loop {
+            interval.tick().await;
+
+            let org_ids = match ExecOptsBuilder::default().build() {
+                Ok(mut exec_opts) => ctx.storage_layer.fetch_organizations(&mut exec_opts).await,
+                Err(e) => Err(e.into()),
+            };
+            let org_ids = match org_ids {
+                Ok(organizations) => organizations.into_iter().map(|org| org.id).collect(),
+                Err(e) => {
+                    log::error!(
+                        "Failed to list organizations, only backing up the default one: {e}"
+                    );
+                    vec![DEFAULT_ORG_ID]
+                }
+            };
+
+            for org_id in org_ids {
+                let res = with_org(org_id, async {
+                    let (job_id, object_key) = create_backup_job(&ctx, org_id, None).await?;
+                    run_backup(&ctx, org_id, job_id, &object_key).await
+                })
+                .await;
+                if let Err(e) = res {
+                    log::error!("Failed to record the backup of organization {org_id}: {e}");
+                }
+            }
+        }
+    })
+}
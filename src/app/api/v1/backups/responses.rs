@@ -0,0 +1,13 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+/// The backup that was started.
+///
+/// * `job_id`: The ID of the job taking the backup
+/// * `object_key`: The key the backup will be stored under, which restoring it takes
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBackupResponse {
+    pub job_id: Uuid,
+    pub object_key: String,
+}
@@ -0,0 +1,19 @@
+use chrono::{TimeZone, Utc};
+use uuid::uuid;
+
+use crate::app::api::v1::backups::backup_key;
+
+#[test]
+fn test_backup_key() {
+    let org_id = uuid!("6f1c2a4e-3b5d-4e7f-8a9b-0c1d2e3f4a5b");
+    let earlier = Utc.with_ymd_and_hms(2026, 10, 16, 9, 5, 0).unwrap();
+    let later = Utc.with_ymd_and_hms(2026, 10, 16, 21, 0, 0).unwrap();
+
+    assert_eq!(
+        backup_key(org_id, earlier),
+        "backups/6f1c2a4e-3b5d-4e7f-8a9b-0c1d2e3f4a5b/20261016T090500Z.json"
+    );
+
+    // the backups of an organization sort by when they were taken
+    assert!(backup_key(org_id, earlier) < backup_key(org_id, later));
+}
@@ -6,12 +6,14 @@ use anyhow::Result;
 use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::response::Response;
+use axum::Extension;
 use uuid::Uuid;
 
 use crate::app::api::v1::cycles::responses::CyclesResponse;
 use crate::app::api_response;
 use crate::app::errors::AppError;
 use crate::app::state::Services;
+use crate::services::auth::AuthData;
 use crate::services::storage::ExecOptsBuilder;
 
 /// Fetch cycles
@@ -41,6 +43,7 @@ pub async fn fetch_cycles(State(ctx): State<Arc<Services>>) -> Result<Response,
 /// Delete a cycle
 ///
 /// * `ctx`: The application context extracted as Axum state
+/// * `auth`: Auth data about the user
 /// * `id`: The ID of the cycle to delete
 #[utoipa::path(
     delete,
@@ -57,9 +60,11 @@ pub async fn fetch_cycles(State(ctx): State<Arc<Services>>) -> Result<Response,
 )]
 pub async fn delete_cycle(
     State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
     Path(id): Path<Uuid>,
 ) -> Result<Response, AppError> {
     let storage_layer = &ctx.storage_layer;
-    storage_layer.delete_cycle(id, &mut ExecOptsBuilder::default().build()?).await?;
+    let mut exec_opts = ExecOptsBuilder::default().principal(auth.email()?).build()?;
+    storage_layer.delete_cycle(id, &mut exec_opts).await?;
     Ok(api_response::no_content())
 }
@@ -1,6 +1,6 @@
 //! Controllers for the data exports API.
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use axum::response::Response;
 use axum::{Extension, Json};
@@ -8,17 +8,61 @@ use chrono::Utc;
 use tokio::task;
 use uuid::Uuid;
 
-use super::workspace::policies::{EmailPolicy, PasswordPolicy};
-use super::workspace::{export_task, ExportParams};
+use super::workspace::calendar::OnboardingSessionSettings;
+use super::workspace::deprovisioning::{
+    deprovision_task, validate_grace_period, DeprovisionParams, DEFAULT_DELETION_GRACE_PERIOD_DAYS,
+};
+use super::workspace::drives::SharedDriveSettings;
+use super::workspace::email_retries::retry_due_emails;
+use super::workspace::groups::validate_groups;
+use super::workspace::logins::{login_sync_task, LoginSyncParams};
+use super::workspace::org_units::ensure_org_unit;
+use super::workspace::outcome::ExportOutcome;
+use super::workspace::personalization::PersonalizationSettings;
+use super::workspace::policies::WorkspaceLicense;
+use super::workspace::profiles::{profile_sync_task, ProfileSyncParams};
+use super::workspace::programs::ProgramSettings;
+use super::workspace::queue::{QueuedExport, QueuedTask};
+use super::workspace::reconciliation::{create_reconcile_job, reconcile_task};
+use super::workspace::reminders;
+use super::workspace::retention::{purge_expired_data, RetentionConfig};
+use super::workspace::slack::{invite_to_slack_task, InviteToSlackParams, SlackInviteSettings};
+use super::workspace::suspensions::{suspension_task, SuspensionParams};
+use super::workspace::transfers::{
+    drive_transfer_task, DriveTransferParams, DRIVE_TRANSFER_POLL_INTERVAL, DRIVE_TRANSFER_TIMEOUT,
+};
+use super::workspace::{
+    default_org_unit, export_task, validate_org_unit_path, ExportParams, DEFAULT_EXPORT_CONCURRENCY,
+};
 use super::ExportServices;
-use crate::app::api::v1::data_exports::requests::ExportUsersToWorkspaceRequest;
-use crate::app::api::v1::data_exports::responses::ExportUsersToWorkspaceResponse;
+use crate::app::api::v1::data_exports::requests::{
+    ActivationReminderSettingsRequest, CancelWorkspaceDeletionRequest,
+    DeprovisionWorkspaceUsersRequest, EmailHistoryQuery, ExportUsersToWorkspaceRequest,
+    ExportedVolunteersQuery, InviteToSlackRequest, ReconcileWorkspaceUsersRequest,
+    ResumeWorkspaceExportQuery, SuspendWorkspaceUsersRequest, SyncWorkspaceLoginsRequest,
+    SyncWorkspaceProfilesRequest, TransferDriveFilesRequest, WorkspaceLoginsQuery,
+};
+use crate::app::api::v1::data_exports::responses::{
+    ActivationRemindersResponse, DriveTransfersResponse, EmailHistoryResponse, EmailRetryResponse,
+    ExportPreviewResponse, ExportUsersToWorkspaceResponse, ExportedVolunteersResponse,
+    OnboardingEmailDeliveriesResponse, SendActivationRemindersResponse, WorkspaceDeletionsResponse,
+    WorkspaceLoginsResponse, WorkspaceOrgUnitsResponse, WorkspaceSuspensionsResponse,
+};
+use crate::app::api::v1::jobs::webhooks::validate_callback_url;
 use crate::app::api_response;
 use crate::app::errors::AppError;
 use crate::services::auth::AuthData;
-use crate::services::storage::jobs::CreateJobBuilder;
-use crate::services::storage::types::{ExportDesination, JobData, JobDetails, JobType};
+use crate::services::mail::DEFAULT_LOCALE;
+use crate::services::storage::jobs::{CreateJobBuilder, NEW_JOB_VERSION};
+use crate::services::storage::organizations::with_org;
+use crate::services::storage::reminders::UpsertActivationReminderSettingsBuilder;
+use crate::services::storage::suspensions::RecordWorkspaceSuspension;
+use crate::services::storage::types::{
+    ExportDesination, JobData, JobDetails, JobStatus, JobType, WorkspaceSuspensionAction,
+};
+use crate::services::storage::volunteers::{ExportedVolunteerCursor, ExportedVolunteerFilter};
 use crate::services::storage::ExecOptsBuilder;
+use crate::services::workspace::retry::RetryPolicy;
 
 /// Start a job to export users to Google Workspace.
 ///
@@ -27,13 +71,18 @@ use crate::services::storage::ExecOptsBuilder;
 /// * `auth`: Auth data about the user
 /// * `request`: The request data
 ///
-/// This endpoint starts a job, records it in the database, and returns immediately. The task it
-/// spawns does not block.
+/// This endpoint records a job in the database, queues it to be run by a worker, and returns
+/// immediately. A job left running by a process that stopped is picked up by another worker.
+///
+/// If `dryRun` is set in the request, no job is started. Instead, the endpoint generates the
+/// workspace emails and org units that the export would use and returns them without calling the
+/// Workspace API, recording anything in the database, or sending any emails.
 #[utoipa::path(
     post,
     path = "/{project_cycle_id}/workspace",
     responses(
-        (status = 200, description = "Successfully started job to export users to Google Workspace"),
+        (status = 200, description = "Successfully started job to export users to Google Workspace (or previewed the export if `dryRun` is set)"),
+        (status = 400, description = "One or more users have already been exported, volunteers were listed for a cohort export, the export policies, groups, license, shared drive, onboarding session, program, personalization, or callback URL are invalid, the org unit does not exist (or could not be created), the domain does not exist, or the job the export depends on does not exist or will never complete"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden")
     ),
@@ -47,6 +96,164 @@ pub async fn export_users_to_workspace(
     Extension(auth): Extension<AuthData>,
     Json(request): Json<ExportUsersToWorkspaceRequest>,
 ) -> Result<Response, AppError> {
+    let org_unit = match &request.org_unit {
+        Some(org_unit) => org_unit.clone(),
+        None => default_org_unit(&services).await?,
+    };
+    let principal = auth.email()?;
+
+    if let Err(e) = validate_org_unit_path(&org_unit) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string()));
+    }
+
+    if let Err(e) = validate_groups(&request.groups) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string()));
+    }
+
+    if let Some(Err(e)) = request.license.as_ref().map(WorkspaceLicense::validate) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string()));
+    }
+
+    if let Some(Err(e)) = request.shared_drive.as_ref().map(SharedDriveSettings::validate) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string()));
+    }
+
+    if let Some(Err(e)) =
+        request.onboarding_session.as_ref().map(OnboardingSessionSettings::validate)
+    {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string()));
+    }
+
+    if let Some(Err(e)) = request.program.as_ref().map(ProgramSettings::validate) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string()));
+    }
+
+    if let Some(Err(e)) = request.slack.as_ref().map(SlackInviteSettings::validate) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string()));
+    }
+
+    if let Some(Err(e)) = request.personalization.as_ref().map(PersonalizationSettings::validate) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &format!("{e:#}")));
+    }
+
+    if let Some(url) = &request.callback_url {
+        if let Err(e) = validate_callback_url(url).await {
+            return Ok(api_response::error(StatusCode::BAD_REQUEST, &format!("{e:#}")));
+        }
+    }
+
+    // The parameters are built here to check them, and the profile photo is read, so a missing
+    // file is caught before the job is queued. The worker that runs the job builds them again. A
+    // dry run has no job, so the nil UUID stands in for the job ID in the generated plan.
+    let params = match ExportParams::from_request(
+        Uuid::nil(),
+        NEW_JOB_VERSION,
+        project_cycle_id,
+        principal.clone(),
+        org_unit.clone(),
+        &request,
+    ) {
+        Ok(params) => params,
+        Err(e) => {
+            log::error!("{e:#}");
+            return Ok(api_response::error(StatusCode::BAD_REQUEST, &format!("{e:#}")));
+        }
+    };
+
+    if let Err(e) = params.email_policy.validate() {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string()));
+    }
+
+    if request.export_cohort && !request.volunteers.is_empty() {
+        return Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            "Volunteers cannot be listed when exporting the whole cohort",
+        ));
+    }
+
+    if request.skip_users_on_conflict || request.export_cohort {
+        log::info!("Skipping users that have already been exported");
+    } else {
+        let already_exported = services
+            .storage_layer
+            .fetch_exported_volunteer_details_by_ids(
+                request.volunteers.iter().map(|v| v.volunteer_id).collect(),
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?;
+
+        if !already_exported.is_empty() {
+            log::error!("One or more users have already been exported");
+            return Ok(api_response::error(
+                StatusCode::BAD_REQUEST,
+                "One or more users have already been exported",
+            ));
+        }
+    }
+
+    if params.dry_run {
+        // A dry run never touches Workspace, the database, or the mail service, so it is not
+        // queued
+        return match export_task(&services, params).await {
+            Ok(plan) => Ok(api_response::success(
+                StatusCode::OK,
+                ExportPreviewResponse {
+                    volunteers: plan.volunteers,
+                    skipped: plan.skipped,
+                    blocked: plan.blocked,
+                    needs_attention: plan.needs_attention,
+                },
+            )?),
+            Err(e) => Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string())),
+        };
+    }
+
+    if !services.workspace.org_unit_exists(&principal, &org_unit).await? {
+        if !request.create_org_unit {
+            log::error!("Org unit {org_unit} does not exist in workspace");
+            return Ok(api_response::error(
+                StatusCode::BAD_REQUEST,
+                &format!("Org unit {org_unit} does not exist in workspace"),
+            ));
+        }
+
+        if let Err(e) = ensure_org_unit(&services, &principal, &org_unit).await {
+            log::error!("{e:#}");
+            return Ok(api_response::error(StatusCode::BAD_REQUEST, &format!("{e:#}")));
+        }
+    }
+
+    let domain = &params.email_policy.domain;
+    if !services.workspace.domain_exists(&principal, domain).await? {
+        log::error!("Domain {domain} is not a verified domain in workspace");
+        return Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            &format!("Domain {domain} is not a verified domain in workspace"),
+        ));
+    }
+
+    if let Some(depends_on) = request.depends_on {
+        let chain = services
+            .storage_layer
+            .fetch_job_chain(depends_on, &mut ExecOptsBuilder::default().build()?)
+            .await?;
+        match chain.iter().find(|job| job.id == depends_on).map(|job| job.status) {
+            None => {
+                return Ok(api_response::error(
+                    StatusCode::BAD_REQUEST,
+                    &format!("Job {depends_on} does not exist"),
+                ))
+            }
+            Some(JobStatus::Cancelled | JobStatus::DeadLettered) => {
+                return Ok(api_response::error(
+                    StatusCode::BAD_REQUEST,
+                    &format!("Job {depends_on} will never complete"),
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+
     let current_time = Utc::now();
     let time_only = current_time.format("%H:%M:%S").to_string();
 
@@ -56,64 +263,1355 @@ pub async fn export_users_to_workspace(
         .data(JobDetails {
             job_type: JobType::AirtableExportUsers,
             error: None,
+            result: None,
             data: JobData::AirtableExportUsers {
                 export_destination: ExportDesination::GoogleWorkspace,
             },
         })
         .build()?;
 
-    let job_id = services
+    // The job is queued in the same transaction that records it, so a job is never recorded
+    // without a worker to run it
+    let mut tx = services.storage_layer.acquire().await?;
+    let mut exec_opts =
+        ExecOptsBuilder::default().tx(&mut tx).principal(principal.clone()).build()?;
+    let job_id =
+        services.storage_layer.create_job(Some(project_cycle_id), data, &mut exec_opts).await?;
+    if request.callback_url.is_some() {
+        services
+            .storage_layer
+            .set_job_callback_url(job_id, request.callback_url.clone(), &mut exec_opts)
+            .await?;
+    }
+    if let Some(depends_on) = request.depends_on {
+        services.storage_layer.set_job_dependency(job_id, depends_on, &mut exec_opts).await?;
+    }
+    let priority = request.priority;
+    let run_at = request.run_at;
+    let task = QueuedTask::WorkspaceExport(QueuedExport {
+        project_cycle_id,
+        principal,
+        org_unit,
+        request,
+    });
+    task.enqueue(&services, job_id, priority, run_at, &mut exec_opts).await?;
+    tx.commit().await?;
+
+    match run_at {
+        Some(run_at) => log::info!("Queued export job {job_id} @ {time_only} to run at {run_at}"),
+        None => log::info!("Queued export job {job_id} @ {time_only}"),
+    }
+
+    Ok(api_response::success(StatusCode::OK, ExportUsersToWorkspaceResponse { job_id })?)
+}
+
+/// Resume a job exporting users to Google Workspace that stopped before it finished.
+///
+/// * `services`: The application services
+/// * `job_id`: The ID of the export job to resume
+/// * `query`: How urgently the resumed job should run
+/// * `auth`: Auth data about the user
+///
+/// Like `export_users_to_workspace`, this endpoint queues the job and returns immediately. Users
+/// that the job already created are skipped.
+#[utoipa::path(
+    post,
+    path = "/workspace/jobs/{job_id}/resume",
+    responses(
+        (status = 200, description = "Successfully queued job to export users to Google Workspace to be resumed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "The job is already queued or running")
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer"),
+        ("priority" = Option<String>, Query, description = "How urgently to run the job: high, normal (the default), or low")
+    ),
+)]
+pub async fn resume_workspace_export(
+    State(services): State<ExportServices>,
+    Path(job_id): Path<Uuid>,
+    Query(query): Query<ResumeWorkspaceExportQuery>,
+    Extension(auth): Extension<AuthData>,
+) -> Result<Response, AppError> {
+    let task = QueuedTask::ResumeWorkspaceExport { principal: auth.email()? };
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    if !task.enqueue(&services, job_id, query.priority, None, &mut exec_opts).await? {
+        return Ok(api_response::error(StatusCode::CONFLICT, "Job is already queued or running"));
+    }
+
+    log::info!("Queued export job {job_id} to be resumed");
+
+    Ok(api_response::success(StatusCode::OK, ExportUsersToWorkspaceResponse { job_id })?)
+}
+
+/// List the org units in Google Workspace that users can be exported to.
+///
+/// * `services`: The application services
+/// * `auth`: Auth data about the user
+#[utoipa::path(
+    get,
+    path = "/workspace/org-units",
+    responses(
+        (status = 200, description = "Successfully listed the org units in Google Workspace"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden")
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn list_workspace_org_units(
+    State(services): State<ExportServices>,
+    Extension(auth): Extension<AuthData>,
+) -> Result<Response, AppError> {
+    let mut org_units = services.workspace.list_org_units(&auth.email()?).await?;
+    org_units.sort_by(|a, b| a.org_unit_path.cmp(&b.org_unit_path));
+
+    Ok(api_response::success(StatusCode::OK, WorkspaceOrgUnitsResponse { org_units })?)
+}
+
+/// Fetch the outcome of a job exporting users to Google Workspace.
+///
+/// * `services`: The application services
+/// * `job_id`: The ID of the export job
+///
+/// The outcome lists, for every user in the job, whether they were exported, skipped, or failed
+/// (and why). Users that the job has not gotten to yet are not listed.
+#[utoipa::path(
+    get,
+    path = "/workspace/jobs/{job_id}/outcome",
+    responses(
+        (status = 200, description = "Successfully fetched the outcome of the export job"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "The job has not recorded an outcome")
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_workspace_export_outcome(
+    State(services): State<ExportServices>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let job =
+        services.storage_layer.fetch_job(job_id, &mut ExecOptsBuilder::default().build()?).await?;
+
+    match ExportOutcome::from_job_details(&job.details) {
+        Some(outcome) => Ok(api_response::success(StatusCode::OK, outcome)?),
+        None => Ok(api_response::error(
+            StatusCode::NOT_FOUND,
+            "The job has not recorded an export outcome",
+        )),
+    }
+}
+
+/// Fetch the delivery status of the onboarding emails sent by a job exporting users to Google
+/// Workspace.
+///
+/// * `services`: The application services
+/// * `job_id`: The ID of the export job
+///
+/// Statuses come from the mail provider's event webhook, so volunteers whose onboarding email
+/// bounced or was dropped (and who never got their credentials) can be followed up with.
+#[utoipa::path(
+    get,
+    path = "/workspace/jobs/{job_id}/deliveries",
+    responses(
+        (status = 200, description = "Successfully fetched the delivery status of the onboarding emails"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_onboarding_email_deliveries(
+    State(services): State<ExportServices>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let deliveries = services
+        .storage_layer
+        .fetch_onboarding_email_deliveries(job_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    Ok(api_response::success(StatusCode::OK, OnboardingEmailDeliveriesResponse { deliveries })?)
+}
+
+/// Fetch every attempt a job exporting users to Google Workspace made to send an email.
+///
+/// * `services`: The application services
+/// * `job_id`: The ID of the export job
+/// * `query`: Filters for the emails
+///
+/// Unlike the delivery status, which depends on the mail provider reporting back, every attempt is
+/// recorded as it is made, so this answers whether someone was ever sent an email at all.
+#[utoipa::path(
+    get,
+    path = "/workspace/jobs/{job_id}/emails",
+    responses(
+        (status = 200, description = "Successfully fetched the emails sent by the export job"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer"),
+        ("recipient" = Option<String>, Query, description = "Only list emails sent to this address")
+    ),
+)]
+pub async fn fetch_email_history(
+    State(services): State<ExportServices>,
+    Path(job_id): Path<Uuid>,
+    Query(query): Query<EmailHistoryQuery>,
+) -> Result<Response, AppError> {
+    let emails = services
+        .storage_layer
+        .fetch_email_sends(job_id, query.recipient, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    Ok(api_response::success(StatusCode::OK, EmailHistoryResponse { emails })?)
+}
+
+/// Send the onboarding emails a job exporting users to Google Workspace failed to send again.
+///
+/// * `services`: The application services
+/// * `job_id`: The ID of the export job
+///
+/// Failed emails are retried in the background with backoff, and given up on after several
+/// attempts. This sends every queued email for the job right away, including ones that were given
+/// up on, and starts their attempts over.
+#[utoipa::path(
+    post,
+    path = "/workspace/jobs/{job_id}/emails/retry",
+    responses(
+        (status = 200, description = "Successfully retried the queued emails of the export job"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn retry_failed_emails(
+    State(services): State<ExportServices>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    services
+        .storage_layer
+        .reset_email_retries(job_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    let summary = retry_due_emails(&services, Some(job_id)).await?;
+    let queued = services
+        .storage_layer
+        .fetch_email_retries(job_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    Ok(api_response::success(StatusCode::OK, EmailRetryResponse { summary, queued })?)
+}
+
+/// Fetch how activation reminders are configured for a project cycle, and which of its exported
+/// volunteers have been checked for a reminder.
+///
+/// * `services`: The application services
+/// * `project_cycle_id`: The ID of the project cycle
+#[utoipa::path(
+    get,
+    path = "/{project_cycle_id}/workspace/reminders",
+    responses(
+        (status = 200, description = "Successfully fetched the activation reminders of the project cycle"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_activation_reminders(
+    State(services): State<ExportServices>,
+    Path(project_cycle_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let settings = services
+        .storage_layer
+        .fetch_activation_reminder_settings(
+            project_cycle_id,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+    let reminders = services
         .storage_layer
-        .create_job(Some(project_cycle_id), data, &mut ExecOptsBuilder::default().build()?)
+        .fetch_activation_reminders(project_cycle_id, &mut ExecOptsBuilder::default().build()?)
         .await?;
 
-    log::info!("Started import job {job_id} @ {time_only}");
+    Ok(api_response::success(StatusCode::OK, ActivationRemindersResponse { settings, reminders })?)
+}
 
-    let email_policy = EmailPolicy::from(&request);
-    let password_policy = PasswordPolicy::from(&request);
+/// Configure activation reminders for a project cycle.
+///
+/// * `services`: The application services
+/// * `project_cycle_id`: The ID of the project cycle
+/// * `auth`: Auth data about the user
+/// * `request`: The request data
+///
+/// Exported volunteers in the cycle who have not signed in to their workspace account
+/// `remindAfterDays` days after their export are sent a reminder. Reminders are sent in the
+/// background, and the workspace accounts are looked up on behalf of the user who configured them.
+#[utoipa::path(
+    put,
+    path = "/{project_cycle_id}/workspace/reminders",
+    responses(
+        (status = 200, description = "Successfully configured activation reminders for the project cycle"),
+        (status = 400, description = "The number of days is not positive"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn configure_activation_reminders(
+    State(services): State<ExportServices>,
+    Path(project_cycle_id): Path<Uuid>,
+    Extension(auth): Extension<AuthData>,
+    Json(request): Json<ActivationReminderSettingsRequest>,
+) -> Result<Response, AppError> {
+    if request.remind_after_days <= 0 {
+        return Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            "The number of days before a reminder must be positive",
+        ));
+    }
 
-    let already_exported = services
+    let data = UpsertActivationReminderSettingsBuilder::default()
+        .enabled(request.enabled)
+        .remind_after_days(request.remind_after_days)
+        .locale(request.locale.unwrap_or_else(|| DEFAULT_LOCALE.to_owned()))
+        .principal(auth.email()?)
+        .build()?;
+    let settings = services
         .storage_layer
-        .fetch_exported_volunteer_details_by_project_cycle(
+        .upsert_activation_reminder_settings(
             project_cycle_id,
+            data,
             &mut ExecOptsBuilder::default().build()?,
         )
+        .await?;
+
+    Ok(api_response::success(StatusCode::OK, settings)?)
+}
+
+/// Check the exported volunteers of a project cycle who are due for an activation reminder right
+/// away, instead of waiting for the background task.
+///
+/// * `services`: The application services
+/// * `project_cycle_id`: The ID of the project cycle
+#[utoipa::path(
+    post,
+    path = "/{project_cycle_id}/workspace/reminders/send",
+    responses(
+        (status = 200, description = "Successfully checked the project cycle's volunteers for activation reminders"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn send_activation_reminders(
+    State(services): State<ExportServices>,
+    Path(project_cycle_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let summary = reminders::send_activation_reminders(&services, Some(project_cycle_id)).await?;
+    let reminders = services
+        .storage_layer
+        .fetch_activation_reminders(project_cycle_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    Ok(api_response::success(
+        StatusCode::OK,
+        SendActivationRemindersResponse { summary, reminders },
+    )?)
+}
+
+/// Suspend (or unsuspend) the workspace accounts of a project cycle's exported volunteers.
+///
+/// * `services`: The application services
+/// * `project_cycle_id`: The ID of the project cycle
+/// * `auth`: Auth data about the user
+///
+/// Accounts can only be suspended once the project cycle has been archived, but suspensions can
+/// be lifted at any time. Like `export_users_to_workspace`, this endpoint returns immediately and
+/// the task it spawns does not block.
+#[utoipa::path(
+    post,
+    path = "/{project_cycle_id}/workspace/suspend",
+    responses(
+        (status = 200, description = "Successfully started job to suspend (or unsuspend) the project cycle's workspace accounts"),
+        (status = 400, description = "The project cycle has not been archived, or none of its volunteers have been exported"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "The project cycle does not exist"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn suspend_workspace_users(
+    State(services): State<ExportServices>,
+    Path(project_cycle_id): Path<Uuid>,
+    Extension(auth): Extension<AuthData>,
+    Json(request): Json<SuspendWorkspaceUsersRequest>,
+) -> Result<Response, AppError> {
+    let principal = auth.email()?;
+    let action = request.action;
+    let concurrency = request.concurrency.unwrap_or(DEFAULT_EXPORT_CONCURRENCY);
+
+    let Some(cycle) = services
+        .storage_layer
+        .fetch_cycle_by_id(project_cycle_id, &mut ExecOptsBuilder::default().build()?)
         .await?
-        .iter()
-        .map(|v| v.volunteer_id)
-        .collect::<Vec<Uuid>>();
+    else {
+        return Ok(api_response::error(
+            StatusCode::NOT_FOUND,
+            &format!("Project cycle {project_cycle_id} does not exist"),
+        ));
+    };
 
-    let volunteers = if request.skip_users_on_conflict {
-        log::info!("Skipping users that have already been exported");
-        request
-            .volunteers
-            .into_iter()
-            .filter(|v| !already_exported.contains(&v.volunteer_id))
-            .collect()
-    } else {
-        for v in &request.volunteers {
-            if already_exported.contains(&v.volunteer_id) {
-                log::error!("One or more users have already been exported");
-                return Ok(api_response::error(
-                    StatusCode::BAD_REQUEST,
-                    "One or more users have already been exported",
-                ));
-            }
+    if action == WorkspaceSuspensionAction::Suspend && !cycle.archived {
+        log::error!("Project cycle {project_cycle_id} has not been archived");
+        return Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            "Workspace accounts can only be suspended once the project cycle has been archived",
+        ));
+    }
+
+    let volunteers = services
+        .storage_layer
+        .fetch_suspension_candidates(
+            project_cycle_id,
+            request.volunteer_ids,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    if volunteers.is_empty() {
+        return Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            "None of the volunteers have been exported to workspace",
+        ));
+    }
+
+    let (label, description) = match action {
+        WorkspaceSuspensionAction::Suspend => {
+            ("Suspend Users", "Suspend users in Google Workspace")
+        }
+        WorkspaceSuspensionAction::Unsuspend => {
+            ("Unsuspend Users", "Unsuspend users in Google Workspace")
         }
-        request.volunteers
     };
 
-    let params = ExportParams {
-        job_id,
-        email_policy,
-        password_policy,
-        principal: auth.email()?,
-        volunteers,
-    };
+    let data = CreateJobBuilder::default()
+        .label(label)
+        .description(Some(description.to_owned()))
+        .data(JobDetails {
+            job_type: JobType::SuspendWorkspaceUsers,
+            error: None,
+            result: None,
+            data: JobData::SuspendWorkspaceUsers { action },
+        })
+        .build()?;
 
-    task::spawn(async move {
-        let _ = export_task(&services, params).await;
-    });
+    let job_id = services
+        .storage_layer
+        .create_job(
+            Some(project_cycle_id),
+            data,
+            &mut ExecOptsBuilder::default().principal(principal.clone()).build()?,
+        )
+        .await?;
+
+    log::info!("Started job {job_id} to {action} {} users", volunteers.len());
+
+    let params = SuspensionParams { job_id, principal, action, concurrency, volunteers };
+
+    task::spawn(with_org(services.org_id, async move {
+        let _ = suspension_task(&services, params).await;
+    }));
+
+    Ok(api_response::success(StatusCode::OK, ExportUsersToWorkspaceResponse { job_id })?)
+}
+
+/// Push the names and personal emails of a project cycle's exported volunteers from Pantheon to
+/// their workspace accounts.
+///
+/// * `services`: The application services
+/// * `project_cycle_id`: The ID of the project cycle
+/// * `auth`: Auth data about the user
+///
+/// Accounts are found by the workspace email each volunteer was exported with, so their addresses
+/// do not change. Like `export_users_to_workspace`, this endpoint returns immediately and the task
+/// it spawns does not block.
+#[utoipa::path(
+    post,
+    path = "/{project_cycle_id}/workspace/sync",
+    responses(
+        (status = 200, description = "Successfully started job to sync the project cycle's workspace accounts"),
+        (status = 400, description = "None of the volunteers have been exported"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "The project cycle does not exist"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn sync_workspace_profiles(
+    State(services): State<ExportServices>,
+    Path(project_cycle_id): Path<Uuid>,
+    Extension(auth): Extension<AuthData>,
+    Json(request): Json<SyncWorkspaceProfilesRequest>,
+) -> Result<Response, AppError> {
+    let principal = auth.email()?;
+    let concurrency = request.concurrency.unwrap_or(DEFAULT_EXPORT_CONCURRENCY);
+
+    if services
+        .storage_layer
+        .fetch_cycle_by_id(project_cycle_id, &mut ExecOptsBuilder::default().build()?)
+        .await?
+        .is_none()
+    {
+        return Ok(api_response::error(
+            StatusCode::NOT_FOUND,
+            &format!("Project cycle {project_cycle_id} does not exist"),
+        ));
+    }
+
+    let volunteers = services
+        .storage_layer
+        .fetch_profile_sync_candidates(
+            project_cycle_id,
+            request.volunteer_ids,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    if volunteers.is_empty() {
+        return Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            "None of the volunteers have been exported to workspace",
+        ));
+    }
+
+    let data = CreateJobBuilder::default()
+        .label("Sync Profiles")
+        .description(Some("Sync user profiles to Google Workspace".to_owned()))
+        .data(JobDetails {
+            job_type: JobType::SyncWorkspaceProfiles,
+            error: None,
+            result: None,
+            data: JobData::SyncWorkspaceProfiles { volunteer_count: volunteers.len() },
+        })
+        .build()?;
+
+    let job_id = services
+        .storage_layer
+        .create_job(
+            Some(project_cycle_id),
+            data,
+            &mut ExecOptsBuilder::default().principal(principal.clone()).build()?,
+        )
+        .await?;
+
+    log::info!("Started job {job_id} to sync the profiles of {} users", volunteers.len());
+
+    let params = ProfileSyncParams { job_id, principal, concurrency, volunteers };
+
+    task::spawn(with_org(services.org_id, async move {
+        let _ = profile_sync_task(&services, params).await;
+    }));
 
     Ok(api_response::success(StatusCode::OK, ExportUsersToWorkspaceResponse { job_id })?)
 }
+
+/// Fetch every suspension (and unsuspension) of a project cycle's workspace accounts.
+///
+/// * `services`: The application services
+/// * `project_cycle_id`: The ID of the project cycle
+#[utoipa::path(
+    get,
+    path = "/{project_cycle_id}/workspace/suspensions",
+    responses(
+        (status = 200, description = "Successfully fetched the project cycle's workspace account suspensions"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_workspace_suspensions(
+    State(services): State<ExportServices>,
+    Path(project_cycle_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let suspensions = services
+        .storage_layer
+        .fetch_workspace_suspensions(project_cycle_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    Ok(api_response::success(StatusCode::OK, WorkspaceSuspensionsResponse { suspensions })?)
+}
+
+/// Deprovision the workspace accounts of a project cycle's exported volunteers.
+///
+/// * `services`: The application services
+/// * `project_cycle_id`: The ID of the project cycle
+/// * `auth`: Auth data about the user
+///
+/// Every account is suspended right away and scheduled to be deleted once the grace period is
+/// over. Until then, the deletion can be cancelled with `cancel_workspace_deletion`. Like
+/// `suspend_workspace_users`, accounts can only be deprovisioned once the project cycle has been
+/// archived, and this endpoint returns immediately without blocking on the task it spawns.
+#[utoipa::path(
+    post,
+    path = "/{project_cycle_id}/workspace/deprovision",
+    responses(
+        (status = 200, description = "Successfully started job to deprovision the project cycle's workspace accounts"),
+        (status = 400, description = "The grace period is invalid, the project cycle has not been archived, or none of its volunteers have been exported"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "The project cycle does not exist"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn deprovision_workspace_users(
+    State(services): State<ExportServices>,
+    Path(project_cycle_id): Path<Uuid>,
+    Extension(auth): Extension<AuthData>,
+    Json(request): Json<DeprovisionWorkspaceUsersRequest>,
+) -> Result<Response, AppError> {
+    let principal = auth.email()?;
+    let concurrency = request.concurrency.unwrap_or(DEFAULT_EXPORT_CONCURRENCY);
+    let grace_period_days = request.grace_period_days.unwrap_or(DEFAULT_DELETION_GRACE_PERIOD_DAYS);
+
+    if let Err(e) = validate_grace_period(grace_period_days) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string()));
+    }
+
+    let Some(cycle) = services
+        .storage_layer
+        .fetch_cycle_by_id(project_cycle_id, &mut ExecOptsBuilder::default().build()?)
+        .await?
+    else {
+        return Ok(api_response::error(
+            StatusCode::NOT_FOUND,
+            &format!("Project cycle {project_cycle_id} does not exist"),
+        ));
+    };
+
+    if !cycle.archived {
+        log::error!("Project cycle {project_cycle_id} has not been archived");
+        return Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            "Workspace accounts can only be deprovisioned once the project cycle has been archived",
+        ));
+    }
+
+    let volunteers = services
+        .storage_layer
+        .fetch_suspension_candidates(
+            project_cycle_id,
+            request.volunteer_ids,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    if volunteers.is_empty() {
+        return Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            "None of the volunteers have been exported to workspace",
+        ));
+    }
+
+    let data = CreateJobBuilder::default()
+        .label("Deprovision Users")
+        .description(Some(
+            "Suspend users in Google Workspace and schedule their deletion".to_owned(),
+        ))
+        .data(JobDetails {
+            job_type: JobType::DeprovisionWorkspaceUsers,
+            error: None,
+            result: None,
+            data: JobData::DeprovisionWorkspaceUsers { grace_period_days },
+        })
+        .build()?;
+
+    let job_id = services
+        .storage_layer
+        .create_job(
+            Some(project_cycle_id),
+            data,
+            &mut ExecOptsBuilder::default().principal(principal.clone()).build()?,
+        )
+        .await?;
+
+    log::info!(
+        "Started job {job_id} to deprovision {} users after {grace_period_days} days",
+        volunteers.len()
+    );
+
+    let params =
+        DeprovisionParams { job_id, principal, grace_period_days, concurrency, volunteers };
+
+    task::spawn(with_org(services.org_id, async move {
+        let _ = deprovision_task(&services, params).await;
+    }));
+
+    Ok(api_response::success(StatusCode::OK, ExportUsersToWorkspaceResponse { job_id })?)
+}
+
+/// Fetch every deletion scheduled for a project cycle's workspace accounts.
+///
+/// * `services`: The application services
+/// * `project_cycle_id`: The ID of the project cycle
+#[utoipa::path(
+    get,
+    path = "/{project_cycle_id}/workspace/deletions",
+    responses(
+        (status = 200, description = "Successfully fetched the project cycle's scheduled workspace account deletions"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_workspace_deletions(
+    State(services): State<ExportServices>,
+    Path(project_cycle_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let deletions = services
+        .storage_layer
+        .fetch_workspace_deletions(project_cycle_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    Ok(api_response::success(StatusCode::OK, WorkspaceDeletionsResponse { deletions })?)
+}
+
+/// Cancel the scheduled deletion of a volunteer's workspace account, so the account is kept.
+///
+/// * `services`: The application services
+/// * `deletion_id`: The ID of the scheduled deletion
+/// * `auth`: Auth data about the user
+///
+/// The account stays suspended unless `unsuspend` is set in the request. If lifting the suspension
+/// fails, the deletion is still cancelled and the suspension can be lifted with
+/// `suspend_workspace_users`.
+#[utoipa::path(
+    post,
+    path = "/workspace/deletions/{deletion_id}/cancel",
+    responses(
+        (status = 200, description = "Successfully cancelled the deletion"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "The deletion does not exist, or is no longer scheduled"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn cancel_workspace_deletion(
+    State(services): State<ExportServices>,
+    Path(deletion_id): Path<Uuid>,
+    Extension(auth): Extension<AuthData>,
+    Json(request): Json<CancelWorkspaceDeletionRequest>,
+) -> Result<Response, AppError> {
+    let principal = auth.email()?;
+
+    let Some(deletion) = services
+        .storage_layer
+        .cancel_workspace_deletion(
+            deletion_id,
+            principal.clone(),
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?
+    else {
+        return Ok(api_response::error(
+            StatusCode::NOT_FOUND,
+            &format!("Deletion {deletion_id} does not exist or is no longer scheduled"),
+        ));
+    };
+
+    log::info!("{principal} cancelled the deletion of {}", deletion.workspace_email);
+
+    if request.unsuspend {
+        let result = RetryPolicy::default()
+            .run(&format!("unsuspend {}", deletion.workspace_email), || {
+                services.workspace.unsuspend_volunteer(&principal, &deletion.workspace_email)
+            })
+            .await;
+
+        services
+            .storage_layer
+            .record_workspace_suspensions(
+                vec![RecordWorkspaceSuspension {
+                    job_id: deletion.job_id,
+                    volunteer_id: deletion.volunteer_id,
+                    workspace_email: deletion.workspace_email.clone(),
+                    action: WorkspaceSuspensionAction::Unsuspend,
+                    succeeded: result.is_ok(),
+                    error: result.as_ref().err().map(|e| e.to_string()),
+                    principal: principal.clone(),
+                }],
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?;
+
+        result?;
+    }
+
+    Ok(api_response::success(StatusCode::OK, deletion)?)
+}
+
+/// Reconcile the accounts in a workspace org unit with the volunteers Pantheon has exported to it.
+///
+/// * `services`: The application services
+/// * `auth`: Auth data about the user
+///
+/// The job reports the volunteers recorded as exported whose account is not in the org unit, and
+/// the accounts in the org unit that Pantheon has no record of. Its report is saved as the job's
+/// result. This endpoint returns immediately without blocking on the task it spawns.
+#[utoipa::path(
+    post,
+    path = "/workspace/reconcile",
+    responses(
+        (status = 200, description = "Successfully started job to reconcile the org unit"),
+        (status = 400, description = "The org unit is not a valid path"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn reconcile_workspace_users(
+    State(services): State<ExportServices>,
+    Extension(auth): Extension<AuthData>,
+    Json(request): Json<ReconcileWorkspaceUsersRequest>,
+) -> Result<Response, AppError> {
+    let principal = auth.email()?;
+    let org_unit = match request.org_unit {
+        Some(org_unit) => org_unit,
+        None => default_org_unit(&services).await?,
+    };
+    let fix = request.fix;
+
+    if let Err(e) = validate_org_unit_path(&org_unit) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string()));
+    }
+
+    let params = create_reconcile_job(&services, principal, org_unit, fix).await?;
+    let job_id = params.job_id;
+    log::info!("Started job {job_id} to reconcile {}", params.org_unit);
+
+    task::spawn(with_org(services.org_id, async move {
+        let _ = reconcile_task(&services, params).await;
+    }));
+
+    Ok(api_response::success(StatusCode::OK, ExportUsersToWorkspaceResponse { job_id })?)
+}
+
+/// Invite the exported volunteers of a project cycle to Slack, and add them to the cohort's
+/// channels.
+///
+/// * `services`: The application services
+/// * `project_cycle_id`: The ID of the project cycle
+/// * `auth`: Auth data about the user
+/// * `request`: The request data
+///
+/// This is for volunteers who were exported without Slack settings. Volunteers are invited with
+/// their workspace email, and those who are already members of the Slack workspace are only added
+/// to the channels. Like `export_users_to_workspace`, this endpoint returns immediately and the
+/// task it spawns does not block.
+#[utoipa::path(
+    post,
+    path = "/{project_cycle_id}/workspace/slack",
+    responses(
+        (status = 200, description = "Successfully started job to invite the project cycle's volunteers to Slack"),
+        (status = 400, description = "The channels are invalid, or none of the volunteers have been exported"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "The project cycle does not exist"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn invite_to_slack(
+    State(services): State<ExportServices>,
+    Path(project_cycle_id): Path<Uuid>,
+    Extension(auth): Extension<AuthData>,
+    Json(request): Json<InviteToSlackRequest>,
+) -> Result<Response, AppError> {
+    let principal = auth.email()?;
+    let concurrency = request.concurrency.unwrap_or(DEFAULT_EXPORT_CONCURRENCY);
+    let slack = SlackInviteSettings { channel_ids: request.channel_ids };
+
+    if let Err(e) = slack.validate() {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string()));
+    }
+
+    if services
+        .storage_layer
+        .fetch_cycle_by_id(project_cycle_id, &mut ExecOptsBuilder::default().build()?)
+        .await?
+        .is_none()
+    {
+        return Ok(api_response::error(
+            StatusCode::NOT_FOUND,
+            &format!("Project cycle {project_cycle_id} does not exist"),
+        ));
+    }
+
+    let volunteers = services
+        .storage_layer
+        .fetch_suspension_candidates(
+            project_cycle_id,
+            request.volunteer_ids,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    if volunteers.is_empty() {
+        return Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            "None of the volunteers have been exported to workspace",
+        ));
+    }
+
+    let data = CreateJobBuilder::default()
+        .label("Invite to Slack")
+        .description(Some("Invite users to Slack".to_owned()))
+        .data(JobDetails {
+            job_type: JobType::InviteToSlack,
+            error: None,
+            result: None,
+            data: JobData::InviteToSlack { channel_ids: slack.channel_ids.clone() },
+        })
+        .build()?;
+
+    let job_id = services
+        .storage_layer
+        .create_job(
+            Some(project_cycle_id),
+            data,
+            &mut ExecOptsBuilder::default().principal(principal).build()?,
+        )
+        .await?;
+
+    log::info!("Started job {job_id} to invite {} users to Slack", volunteers.len());
+
+    let params = InviteToSlackParams { job_id, slack, concurrency, volunteers };
+
+    task::spawn(with_org(services.org_id, async move {
+        let _ = invite_to_slack_task(&services, params).await;
+    }));
+
+    Ok(api_response::success(StatusCode::OK, ExportUsersToWorkspaceResponse { job_id })?)
+}
+
+/// Look up when a project cycle's exported volunteers last signed in to workspace, and record it.
+///
+/// * `services`: The application services
+/// * `project_cycle_id`: The ID of the project cycle
+/// * `auth`: Auth data about the user
+///
+/// The recorded sign ins can be fetched with `fetch_workspace_logins`. Like
+/// `export_users_to_workspace`, this endpoint returns immediately and the task it spawns does not
+/// block.
+#[utoipa::path(
+    post,
+    path = "/{project_cycle_id}/workspace/logins/sync",
+    responses(
+        (status = 200, description = "Successfully started job to look up the project cycle's workspace sign ins"),
+        (status = 400, description = "None of the project cycle's volunteers have been exported"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "The project cycle does not exist"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn sync_workspace_logins(
+    State(services): State<ExportServices>,
+    Path(project_cycle_id): Path<Uuid>,
+    Extension(auth): Extension<AuthData>,
+    Json(request): Json<SyncWorkspaceLoginsRequest>,
+) -> Result<Response, AppError> {
+    let principal = auth.email()?;
+    let concurrency = request.concurrency.unwrap_or(DEFAULT_EXPORT_CONCURRENCY);
+
+    if services
+        .storage_layer
+        .fetch_cycle_by_id(project_cycle_id, &mut ExecOptsBuilder::default().build()?)
+        .await?
+        .is_none()
+    {
+        return Ok(api_response::error(
+            StatusCode::NOT_FOUND,
+            &format!("Project cycle {project_cycle_id} does not exist"),
+        ));
+    }
+
+    let volunteers = services
+        .storage_layer
+        .fetch_suspension_candidates(
+            project_cycle_id,
+            request.volunteer_ids,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    if volunteers.is_empty() {
+        return Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            "None of the volunteers have been exported to workspace",
+        ));
+    }
+
+    let data = CreateJobBuilder::default()
+        .label("Sync Sign Ins")
+        .description(Some("Look up when users last signed in to Google Workspace".to_owned()))
+        .data(JobDetails {
+            job_type: JobType::SyncWorkspaceLogins,
+            error: None,
+            result: None,
+            data: JobData::SyncWorkspaceLogins { account_count: volunteers.len() },
+        })
+        .build()?;
+
+    let job_id = services
+        .storage_layer
+        .create_job(
+            Some(project_cycle_id),
+            data,
+            &mut ExecOptsBuilder::default().principal(principal.clone()).build()?,
+        )
+        .await?;
+
+    log::info!("Started job {job_id} to look up the sign ins of {} users", volunteers.len());
+
+    let params = LoginSyncParams { job_id, principal, concurrency, volunteers };
+
+    task::spawn(with_org(services.org_id, async move {
+        let _ = login_sync_task(&services, params).await;
+    }));
+
+    Ok(api_response::success(StatusCode::OK, ExportUsersToWorkspaceResponse { job_id })?)
+}
+
+/// Fetch when a project cycle's exported volunteers last signed in to workspace, as of the last
+/// time each account was checked.
+///
+/// * `services`: The application services
+/// * `project_cycle_id`: The ID of the project cycle
+/// * `query`: Filters for the sign ins
+///
+/// With `neverActivated`, this is the list of volunteers who had never signed in when checked.
+#[utoipa::path(
+    get,
+    path = "/{project_cycle_id}/workspace/logins",
+    responses(
+        (status = 200, description = "Successfully fetched the project cycle's workspace sign ins"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer"),
+        ("neverActivated" = Option<bool>, Query, description = "Only list volunteers who had never signed in when checked")
+    ),
+)]
+pub async fn fetch_workspace_logins(
+    State(services): State<ExportServices>,
+    Path(project_cycle_id): Path<Uuid>,
+    Query(query): Query<WorkspaceLoginsQuery>,
+) -> Result<Response, AppError> {
+    let logins = services
+        .storage_layer
+        .fetch_workspace_logins(
+            project_cycle_id,
+            query.never_activated,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    Ok(api_response::success(StatusCode::OK, WorkspaceLoginsResponse { logins })?)
+}
+
+/// Transfer the Drive files of a project cycle's departing volunteers to an archive account,
+/// then suspend their accounts.
+///
+/// * `services`: The application services
+/// * `project_cycle_id`: The ID of the project cycle
+/// * `auth`: Auth data about the user
+///
+/// Only accounts whose files were all transferred are suspended. Like `suspend_workspace_users`,
+/// accounts can only be suspended once the project cycle has been archived, so unless `suspend`
+/// is turned off in the request, the cycle must have been archived. This endpoint returns
+/// immediately without blocking on the task it spawns, and how far every transfer got can be
+/// fetched with `fetch_drive_transfers`.
+#[utoipa::path(
+    post,
+    path = "/{project_cycle_id}/workspace/transfers",
+    responses(
+        (status = 200, description = "Successfully started job to transfer the project cycle's drive files"),
+        (status = 400, description = "The archive account does not exist or is being transferred, the project cycle has not been archived, or none of its volunteers have been exported"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "The project cycle does not exist"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn transfer_drive_files(
+    State(services): State<ExportServices>,
+    Path(project_cycle_id): Path<Uuid>,
+    Extension(auth): Extension<AuthData>,
+    Json(request): Json<TransferDriveFilesRequest>,
+) -> Result<Response, AppError> {
+    let principal = auth.email()?;
+    let concurrency = request.concurrency.unwrap_or(DEFAULT_EXPORT_CONCURRENCY);
+    let archive_email = request.archive_email.trim().to_owned();
+
+    let Some(cycle) = services
+        .storage_layer
+        .fetch_cycle_by_id(project_cycle_id, &mut ExecOptsBuilder::default().build()?)
+        .await?
+    else {
+        return Ok(api_response::error(
+            StatusCode::NOT_FOUND,
+            &format!("Project cycle {project_cycle_id} does not exist"),
+        ));
+    };
+
+    if request.suspend && !cycle.archived {
+        log::error!("Project cycle {project_cycle_id} has not been archived");
+        return Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            "Workspace accounts can only be suspended once the project cycle has been archived",
+        ));
+    }
+
+    let archive = RetryPolicy::default()
+        .run(&format!("looking up {archive_email}"), || {
+            services.workspace.find_user(&principal, &archive_email)
+        })
+        .await?;
+
+    if archive.is_none() {
+        return Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            &format!("The archive account {archive_email} does not exist"),
+        ));
+    }
+
+    let volunteers = services
+        .storage_layer
+        .fetch_suspension_candidates(
+            project_cycle_id,
+            request.volunteer_ids,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    if volunteers.is_empty() {
+        return Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            "None of the volunteers have been exported to workspace",
+        ));
+    }
+
+    if volunteers.iter().any(|v| v.workspace_email.eq_ignore_ascii_case(&archive_email)) {
+        return Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            "The archive account cannot be one of the accounts whose files are transferred",
+        ));
+    }
+
+    let data = CreateJobBuilder::default()
+        .label("Transfer Drive Files")
+        .description(Some(format!("Transfer users' Google Drive files to {archive_email}")))
+        .data(JobDetails {
+            job_type: JobType::TransferDriveFiles,
+            error: None,
+            result: None,
+            data: JobData::TransferDriveFiles {
+                archive_email: archive_email.clone(),
+                suspend_after_transfer: request.suspend,
+            },
+        })
+        .build()?;
+
+    let job_id = services
+        .storage_layer
+        .create_job(
+            Some(project_cycle_id),
+            data,
+            &mut ExecOptsBuilder::default().principal(principal.clone()).build()?,
+        )
+        .await?;
+
+    log::info!(
+        "Started job {job_id} to transfer the drive files of {} users to {archive_email}",
+        volunteers.len()
+    );
+
+    let params = DriveTransferParams {
+        job_id,
+        principal,
+        archive_email,
+        suspend_after_transfer: request.suspend,
+        concurrency,
+        poll_interval: DRIVE_TRANSFER_POLL_INTERVAL,
+        timeout: DRIVE_TRANSFER_TIMEOUT,
+        volunteers,
+    };
+
+    task::spawn(with_org(services.org_id, async move {
+        let _ = drive_transfer_task(&services, params).await;
+    }));
+
+    Ok(api_response::success(StatusCode::OK, ExportUsersToWorkspaceResponse { job_id })?)
+}
+
+/// Fetch every transfer of a project cycle's volunteers' Drive files to an archive account.
+///
+/// * `services`: The application services
+/// * `project_cycle_id`: The ID of the project cycle
+#[utoipa::path(
+    get,
+    path = "/{project_cycle_id}/workspace/transfers",
+    responses(
+        (status = 200, description = "Successfully fetched the project cycle's drive transfers"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_drive_transfers(
+    State(services): State<ExportServices>,
+    Path(project_cycle_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let transfers = services
+        .storage_layer
+        .fetch_drive_transfers(project_cycle_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    Ok(api_response::success(StatusCode::OK, DriveTransfersResponse { transfers })?)
+}
+
+/// List the volunteers exported to workspace, newest first, one page at a time.
+///
+/// * `services`: The application services
+/// * `query`: Filters and pagination for the exported volunteers
+///
+/// Pages are keyed on when each volunteer was exported rather than numbered, so volunteers
+/// exported while a client pages through the list do not shift later pages. Pass the
+/// `nextCursor` of a page as the `cursor` of the next request to fetch the page after it.
+#[utoipa::path(
+    get,
+    path = "/workspace/exports",
+    responses(
+        (status = 200, description = "Successfully fetched a page of exported volunteers"),
+        (status = 400, description = "The cursor is malformed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer"),
+        ("cursor" = Option<String>, Query, description = "Where the page starts, as returned by the previous page"),
+        ("emailDomain" = Option<String>, Query, description = "Only list volunteers whose workspace email is in this domain"),
+        ("exportedAfter" = Option<String>, Query, description = "Only list volunteers exported at or after this time (RFC 3339)"),
+        ("exportedBefore" = Option<String>, Query, description = "Only list volunteers exported before this time (RFC 3339)"),
+        ("jobId" = Option<Uuid>, Query, description = "Only list volunteers exported by this job"),
+        ("limit" = Option<i64>, Query, description = "The most volunteers to list (at most 500)"),
+        ("orgUnit" = Option<String>, Query, description = "Only list volunteers exported to this org unit or any org unit below it"),
+        ("projectCycleId" = Option<Uuid>, Query, description = "Only list volunteers exported by a job of this project cycle")
+    ),
+)]
+pub async fn list_exported_volunteers(
+    State(services): State<ExportServices>,
+    Query(query): Query<ExportedVolunteersQuery>,
+) -> Result<Response, AppError> {
+    let cursor = match query.cursor.as_deref().map(ExportedVolunteerCursor::decode) {
+        None => None,
+        Some(Ok(cursor)) => Some(cursor),
+        Some(Err(e)) => return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string())),
+    };
+
+    let page_size = query.page_size();
+    let filter = ExportedVolunteerFilter {
+        job_id: query.job_id,
+        project_cycle_id: query.project_cycle_id,
+        exported_after: query.exported_after,
+        exported_before: query.exported_before,
+        org_unit: query.org_unit,
+        email_domain: query.email_domain.map(|d| d.trim_start_matches('@').to_owned()),
+    };
+
+    // One extra volunteer is fetched to tell whether there is another page
+    let mut volunteers = services
+        .storage_layer
+        .fetch_exported_volunteer_details_page(
+            filter,
+            cursor,
+            page_size + 1,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    let next_cursor = if volunteers.len() as i64 > page_size {
+        volunteers.truncate(page_size as usize);
+        volunteers.last().map(|v| ExportedVolunteerCursor::after(v).encode())
+    } else {
+        None
+    };
+
+    Ok(api_response::success(
+        StatusCode::OK,
+        ExportedVolunteersResponse { volunteers, next_cursor },
+    )?)
+}
+
+/// Report how much of the volunteers' data is past its retention period, and would be purged
+/// the next time the purge runs. Nothing is purged.
+///
+/// * `services`: The application services
+#[utoipa::path(
+    get,
+    path = "/workspace/retention",
+    responses(
+        (status = 200, description = "Successfully reported the data that would be purged"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "The retention policies are misconfigured"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_retention_report(
+    State(services): State<ExportServices>,
+) -> Result<Response, AppError> {
+    let config = RetentionConfig::from_env()?;
+    let report = purge_expired_data(&services, &config, true).await?;
+
+    Ok(api_response::success(StatusCode::OK, report)?)
+}
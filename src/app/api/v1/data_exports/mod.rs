@@ -3,7 +3,7 @@
 mod controllers;
 mod requests;
 mod responses;
-mod workspace;
+pub(super) mod workspace;
 
 use std::sync::Arc;
 
@@ -11,22 +11,69 @@ use axum::extract::FromRef;
 use axum::middleware::from_fn_with_state;
 use axum::{routing, Router};
 use utoipa::OpenApi;
+use uuid::Uuid;
 
 use crate::app::api::middleware::make_rbac;
 use crate::app::state::Services;
+use crate::services::storage::organizations::{current_org, DEFAULT_ORG_ID};
+use crate::services::storage::ExecOptsBuilder;
 
+/// The services export jobs need.
+///
+/// * `org_id`: The organization the request that built the services acts for. Jobs spawned for the
+///   request are scoped to it, since spawned tasks do not inherit the request's scope.
 struct ExportServices {
+    pub org_id: Uuid,
     pub storage_layer: Arc<dyn crate::services::storage::StorageService>,
     pub workspace: Arc<dyn crate::services::workspace::WorkspaceService>,
     pub mail: Arc<dyn crate::services::mail::MailService>,
+    pub sms: Arc<dyn crate::services::sms::SmsService>,
+    pub slack: Arc<dyn crate::services::slack::SlackService>,
 }
 
 impl FromRef<Arc<Services>> for ExportServices {
     fn from_ref(ctx: &Arc<Services>) -> Self {
         Self {
+            org_id: current_org().unwrap_or(DEFAULT_ORG_ID),
             storage_layer: ctx.storage_layer.clone(),
             workspace: ctx.workspace.clone(),
             mail: ctx.mail.clone(),
+            sms: ctx.sms.clone(),
+            slack: ctx.slack.clone(),
+        }
+    }
+}
+
+impl ExportServices {
+    /// The same services, acting for another organization. Background tasks that run for every
+    /// organization use this so the jobs they run spawn their own tasks for the right one.
+    ///
+    /// * `org_id`: The ID of the organization
+    fn for_org(&self, org_id: Uuid) -> Self {
+        Self {
+            org_id,
+            storage_layer: self.storage_layer.clone(),
+            workspace: self.workspace.clone(),
+            mail: self.mail.clone(),
+            sms: self.sms.clone(),
+            slack: self.slack.clone(),
+        }
+    }
+
+    /// The IDs of every organization, which background tasks run for in turn. If they cannot be
+    /// listed, the tasks only run for the default organization.
+    async fn org_ids(&self) -> Vec<Uuid> {
+        let organizations = match ExecOptsBuilder::default().build() {
+            Ok(mut exec_opts) => self.storage_layer.fetch_organizations(&mut exec_opts).await,
+            Err(e) => Err(e.into()),
+        };
+
+        match organizations {
+            Ok(organizations) => organizations.into_iter().map(|org| org.id).collect(),
+            Err(e) => {
+                log::error!("Failed to list organizations, only running for the default one: {e}");
+                vec![DEFAULT_ORG_ID]
+            }
         }
     }
 }
@@ -36,6 +83,29 @@ impl FromRef<Arc<Services>> for ExportServices {
 #[openapi(
     paths(
         controllers::export_users_to_workspace,
+        controllers::resume_workspace_export,
+        controllers::list_workspace_org_units,
+        controllers::fetch_workspace_export_outcome,
+        controllers::fetch_onboarding_email_deliveries,
+        controllers::fetch_email_history,
+        controllers::retry_failed_emails,
+        controllers::fetch_activation_reminders,
+        controllers::configure_activation_reminders,
+        controllers::send_activation_reminders,
+        controllers::suspend_workspace_users,
+        controllers::fetch_workspace_suspensions,
+        controllers::sync_workspace_profiles,
+        controllers::deprovision_workspace_users,
+        controllers::fetch_workspace_deletions,
+        controllers::cancel_workspace_deletion,
+        controllers::reconcile_workspace_users,
+        controllers::invite_to_slack,
+        controllers::sync_workspace_logins,
+        controllers::fetch_workspace_logins,
+        controllers::transfer_drive_files,
+        controllers::fetch_drive_transfers,
+        controllers::list_exported_volunteers,
+        controllers::fetch_retention_report,
     ),
     security(("http" = ["JWT"]))
 )]
@@ -48,9 +118,113 @@ pub async fn build(ctx: Arc<Services>) -> Router<()> {
     let export_workspace_guard = make_rbac(vec!["export:volunteers-workspace".to_owned()]).await;
 
     let export_users_to_workspace = routing::post(controllers::export_users_to_workspace);
+    let resume_workspace_export = routing::post(controllers::resume_workspace_export);
+    let list_workspace_org_units = routing::get(controllers::list_workspace_org_units);
+    let fetch_workspace_export_outcome = routing::get(controllers::fetch_workspace_export_outcome);
+    let fetch_onboarding_email_deliveries =
+        routing::get(controllers::fetch_onboarding_email_deliveries);
+    let fetch_email_history = routing::get(controllers::fetch_email_history);
+    let retry_failed_emails = routing::post(controllers::retry_failed_emails);
+    let activation_reminders = routing::get(controllers::fetch_activation_reminders)
+        .put(controllers::configure_activation_reminders);
+    let send_activation_reminders = routing::post(controllers::send_activation_reminders);
+    let suspend_workspace_users = routing::post(controllers::suspend_workspace_users);
+    let fetch_workspace_suspensions = routing::get(controllers::fetch_workspace_suspensions);
+    let sync_workspace_profiles = routing::post(controllers::sync_workspace_profiles);
+    let deprovision_workspace_users = routing::post(controllers::deprovision_workspace_users);
+    let fetch_workspace_deletions = routing::get(controllers::fetch_workspace_deletions);
+    let cancel_workspace_deletion = routing::post(controllers::cancel_workspace_deletion);
+    let reconcile_workspace_users = routing::post(controllers::reconcile_workspace_users);
+    let invite_to_slack = routing::post(controllers::invite_to_slack);
+    let sync_workspace_logins = routing::post(controllers::sync_workspace_logins);
+    let fetch_workspace_logins = routing::get(controllers::fetch_workspace_logins);
+    let drive_transfers =
+        routing::get(controllers::fetch_drive_transfers).post(controllers::transfer_drive_files);
+    let fetch_retention_report = routing::get(controllers::fetch_retention_report);
+
+    // Exports are queued and run by a pool of workers, which pick up the jobs left running by a
+    // process that stopped
+    match workspace::queue::queue_workers_from_env() {
+        Ok(0) => log::info!(
+            "Not running queued jobs, {} is 0",
+            workspace::queue::JOB_QUEUE_WORKERS_ENV_VAR
+        ),
+        Ok(workers) => {
+            let config = workspace::queue::JobRetryPolicy::from_env()
+                .and_then(|policy| Ok((policy, workspace::queue::JobTimeouts::from_env()?)));
+            match config {
+                Ok((policy, timeouts)) => {
+                    workspace::queue::spawn_queue_workers(
+                        ExportServices::from_ref(&ctx),
+                        workers,
+                        policy,
+                        timeouts,
+                    );
+                }
+                Err(e) => log::error!("Not running queued jobs: {e}"),
+            }
+        }
+        Err(e) => log::error!("Not running queued jobs: {e}"),
+    }
+
+    // Onboarding emails that fail to send during an export are queued and sent again in the
+    // background for as long as the app runs
+    workspace::email_retries::spawn_email_retry_task(ExportServices::from_ref(&ctx));
+
+    // Onboarding emails left in the outbox by exports that stopped before sending them are sent by
+    // a background task
+    workspace::outbox::spawn_outbox_relay_task(ExportServices::from_ref(&ctx));
+
+    // Exported volunteers who have not signed in are reminded by a background task, for the
+    // project cycles that have activation reminders configured
+    workspace::reminders::spawn_activation_reminder_task(ExportServices::from_ref(&ctx));
+
+    // Deprovisioned accounts are deleted by a background task once their grace period is over
+    workspace::deprovisioning::spawn_workspace_deletion_task(ExportServices::from_ref(&ctx));
+
+    // Admins are emailed a digest of the exports, if any are configured to receive one
+    match workspace::digest::DigestConfig::from_env() {
+        Ok(Some(config)) => {
+            workspace::digest::spawn_admin_digest_task(ExportServices::from_ref(&ctx), config);
+        }
+        Ok(None) => {}
+        Err(e) => log::error!("Not sending admin digests: {e}"),
+    }
+
+    // Volunteers' data is purged by a background task once it is past its retention period
+    match workspace::retention::RetentionConfig::from_env() {
+        Ok(config) => {
+            workspace::retention::spawn_purge_task(ExportServices::from_ref(&ctx), config);
+        }
+        Err(e) => log::error!("Not purging expired data: {e}"),
+    }
+
+    // Jobs scheduled through the schedules API are run by a background task when they are due
+    workspace::scheduler::spawn_scheduler_task(ExportServices::from_ref(&ctx));
 
     Router::new()
         .route("/:project_cycle_id/workspace", export_users_to_workspace)
+        .route("/workspace/jobs/:job_id/resume", resume_workspace_export)
+        .route("/workspace/org-units", list_workspace_org_units)
+        .route("/workspace/jobs/:job_id/outcome", fetch_workspace_export_outcome)
+        .route("/workspace/jobs/:job_id/deliveries", fetch_onboarding_email_deliveries)
+        .route("/workspace/jobs/:job_id/emails", fetch_email_history)
+        .route("/workspace/jobs/:job_id/emails/retry", retry_failed_emails)
+        .route("/:project_cycle_id/workspace/reminders", activation_reminders)
+        .route("/:project_cycle_id/workspace/reminders/send", send_activation_reminders)
+        .route("/:project_cycle_id/workspace/suspend", suspend_workspace_users)
+        .route("/:project_cycle_id/workspace/suspensions", fetch_workspace_suspensions)
+        .route("/:project_cycle_id/workspace/sync", sync_workspace_profiles)
+        .route("/:project_cycle_id/workspace/deprovision", deprovision_workspace_users)
+        .route("/:project_cycle_id/workspace/deletions", fetch_workspace_deletions)
+        .route("/workspace/deletions/:deletion_id/cancel", cancel_workspace_deletion)
+        .route("/workspace/reconcile", reconcile_workspace_users)
+        .route("/:project_cycle_id/workspace/slack", invite_to_slack)
+        .route("/:project_cycle_id/workspace/logins/sync", sync_workspace_logins)
+        .route("/:project_cycle_id/workspace/logins", fetch_workspace_logins)
+        .route("/:project_cycle_id/workspace/transfers", drive_transfers)
+        .route("/workspace/exports", list_exported_volunteers)
+        .route("/workspace/retention", fetch_retention_report)
         .route_layer(from_fn_with_state(ctx.clone(), export_workspace_guard))
         .with_state(ctx.clone())
 }
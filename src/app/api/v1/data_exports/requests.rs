@@ -1,28 +1,407 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
+use super::workspace::calendar::OnboardingSessionSettings;
+use super::workspace::drives::SharedDriveSettings;
+use super::workspace::personalization::PersonalizationSettings;
+use super::workspace::policies::{
+    CollisionStrategy, EmailFormat, PasswordDelivery, PasswordStyle, RollbackPolicy,
+    TwoStepVerificationPolicy, WorkspaceLicense,
+};
+use super::workspace::programs::ProgramSettings;
+use super::workspace::slack::SlackInviteSettings;
 use crate::services::storage::entities::VolunteerDetails;
+use crate::services::storage::types::{JobPriority, WorkspaceSuspensionAction};
 
 /// Request to export users to a workspace.
 ///
 /// * `add_unique_numeric_suffix`: Whether to add a unique 2-digit numeric suffix to the email
 ///   handle.
-/// * `change_password_at_next_login`: Whether to force users to change their password at their
-///   next login.
-/// * `generated_password_length`: The length of the generated password.
+/// * `alias_formats`: The formats of extra addresses to add to every user's account (e.g.
+///   `jane@` alongside `jane.doe@`), in the same layouts as `email_format`. Aliases never get a
+///   numeric suffix, and an alias that is already taken is left out rather than changed. At most
+///   5. Defaults to none.
+/// * `callback_url`: An https URL to call with a signed summary of the job once it finishes, in
+///   place of the one configured for every job. Its host must resolve to public addresses. Defaults
+///   to that one.
+/// * `change_password_at_next_login`: Whether Google Workspace should force users to change their
+///   temporary password the first time they sign in.
+/// * `create_org_unit`: Whether to create `org_unit` (and any of its missing parents) if it does
+///   not exist in Google Workspace, instead of rejecting the request. Defaults to `false`.
+/// * `depends_on`: The ID of a job that has to complete before the export runs (e.g. a
+///   reconciliation). If that job fails, is cancelled, or is dead-lettered, the export is
+///   cancelled. Defaults to running the export as soon as a worker is free.
+/// * `domain`: The domain to issue workspace emails on (e.g. `developforgood.org`). It must be a
+///   verified domain of the Google Workspace account. Defaults to `developforgood.org`.
+/// * `dry_run`: Whether to only preview the export. A dry run generates workspace emails and org
+///   units without creating any users, recording anything, or sending any emails.
+/// * `email_collision_strategy`: How to pick a different workspace email when the one generated
+///   for a user is already taken, either by another user in the export, by a previous export, or
+///   by an existing Google Workspace user. Defaults to appending a numeric suffix.
+/// * `email_format`: The layout of the workspace email handle (e.g. `first.last`, `f.last`,
+///   `firstl`, or a custom template). The handle must fit in 64 characters. Defaults to the first
+///   name, the separator, and the last name.
+/// * `export_chunk_size`: The number of users to create, record, and email before moving on to
+///   the next batch. Progress is checkpointed after each batch. Defaults to 100.
+/// * `export_cohort`: Whether to export every volunteer in the project cycle instead of
+///   `volunteers`, which must then be empty. The volunteers are read from the database and exported
+///   a page at a time, so a cohort of any size is never held in memory at once. Volunteers that
+///   were already exported are skipped regardless of `skip_users_on_conflict`, and license seats
+///   are checked before each page rather than once for the whole cohort. Defaults to `false`.
+/// * `export_concurrency`: The maximum number of users to create in Google Workspace at once.
+///   Defaults to 8.
+/// * `generated_password_length`: The length of the generated password. It must be at least the
+///   minimum length configured for the deployment and at most 64.
+/// * `groups`: The email addresses of the Google Groups (e.g. a cohort mailing list) to add every
+///   exported user to. Groups that do not exist are created. Defaults to none.
+/// * `license`: The Google Workspace edition to license every exported user for. The export fails
+///   before creating anyone if there are not enough seats left. The number of seats purchased is
+///   looked up with the provider unless the license gives it. Defaults to leaving licensing to
+///   Google Workspace.
+/// * `locale`: The locale to send onboarding emails in (e.g. `es`). Emails fall back to English
+///   when there is no translation for the locale. Defaults to English.
+/// * `max_workspace_attempts`: The maximum number of times to attempt creating each user in
+///   Google Workspace when the failure is transient. Defaults to 5.
+/// * `onboarding_session`: An onboarding session to schedule on the requester's Google Calendar.
+///   Every exported user is invited to it, and its link is included in their onboarding email.
+///   Defaults to none.
+/// * `org_unit`: The full path of the org unit to create users in (e.g. `/Programs/2025-Spring`).
+///   It must already exist in Google Workspace unless `create_org_unit` is set. Defaults to
+///   `/Programs/PantheonUsers`.
+/// * `password_delivery`: How temporary passwords reach users. With `sms`, the password is texted
+///   to the user's phone and left out of the onboarding email. Defaults to `email`.
+/// * `password_style`: Whether to generate random passwords or passphrases (e.g.
+///   `maple-otter-canyon-piano-42`) as temporary passwords. Defaults to random passwords.
+/// * `personalization`: Whether to give every exported user the standard Gmail signature (or one
+///   rendered from a given Tera template) and the default profile photo from
+///   `DEFAULT_PROFILE_PHOTO_PATH`. Defaults to leaving accounts as Google Workspace creates them.
+/// * `priority`: How urgently the export should run. Workers run waiting `high` exports first and
+///   `low` exports last, so a small urgent re-export is not stuck behind a large cohort export.
+///   Defaults to `normal`.
+/// * `program`: The program to tag every exported user's Workspace account with. Each account is
+///   given the program name, the user's cohort (their project cycle), and their volunteer ID in a
+///   custom schema, so Workspace admins can filter users by them. Defaults to none.
+/// * `rollback_policy`: What to do with Workspace accounts that were created if recording them or
+///   sending their onboarding emails fails. Defaults to leaving them as they are.
+/// * `run_at`: When to run the export at the earliest (e.g. the morning a cohort starts). It can be
+///   changed until a worker starts the export. Defaults to running it as soon as a worker is free.
 /// * `separator`: The separator to use for the email handle (between the first and last names).
-/// * `skip_users_on_conflict`: Whether to skip users on conflict. THIS IS CURRENTLY IGNORED.
+/// * `shared_drive`: The Shared Drive to give every exported user access to (e.g. a cohort's
+///   drive). The drive is created the first time its name is used in the project cycle, and reused
+///   by later exports. Defaults to none.
+/// * `skip_users_on_conflict`: Whether to skip users that already have a workspace account. If
+///   this is `false`, the request is rejected when any user has already been exported by a
+///   previous job. Either way, users who already have a Google Workspace account with their
+///   generated email (and their email as its recovery email) are skipped, and every skipped user
+///   is reported in the job result.
+/// * `slack`: The Slack channels to add every exported user to. Users are invited to the
+///   program's Slack workspace with their workspace email before their onboarding email is sent.
+///   Defaults to not inviting anyone.
+/// * `two_step_verification`: Require the org unit users are created in to enforce 2-Step
+///   Verification, with a grace period for new users to enroll (set up in the Google Admin
+///   console). The export fails before creating anyone if it does not. Defaults to not checking.
 /// * `use_first_and_last_name`: Whether to use the first and last names for the email handle.
-/// * `volunteers`: The volunteers to export.
-// TODO: Either remove `skip_users_on_conflict` or implement it. If it is implemented, its
-// semantics need to be crystal clear.
+/// * `verify_recovery_email_domains`: Whether to look up the MX records of users' recovery email
+///   domains, and set aside users whose recovery email cannot receive mail. Recovery emails are
+///   always checked for syntactic validity. Defaults to `false`.
+/// * `volunteers`: The volunteers to export, unless `export_cohort` is set.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportUsersToWorkspaceRequest {
     pub add_unique_numeric_suffix: bool,
+    #[serde(default)]
+    pub alias_formats: Vec<EmailFormat>,
+    #[serde(default)]
+    pub callback_url: Option<String>,
     pub change_password_at_next_login: bool,
+    #[serde(default)]
+    pub create_org_unit: bool,
+    #[serde(default)]
+    pub depends_on: Option<Uuid>,
+    #[serde(default)]
+    pub domain: Option<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub email_collision_strategy: CollisionStrategy,
+    #[serde(default)]
+    pub email_format: EmailFormat,
+    #[serde(default)]
+    pub export_chunk_size: Option<usize>,
+    #[serde(default)]
+    pub export_cohort: bool,
+    #[serde(default)]
+    pub export_concurrency: Option<usize>,
     pub generated_password_length: u8,
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub license: Option<WorkspaceLicense>,
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub max_workspace_attempts: Option<u32>,
+    #[serde(default)]
+    pub onboarding_session: Option<OnboardingSessionSettings>,
+    #[serde(default)]
+    pub org_unit: Option<String>,
+    #[serde(default)]
+    pub password_delivery: PasswordDelivery,
+    #[serde(default)]
+    pub password_style: PasswordStyle,
+    #[serde(default)]
+    pub personalization: Option<PersonalizationSettings>,
+    #[serde(default)]
+    pub priority: JobPriority,
+    #[serde(default)]
+    pub program: Option<ProgramSettings>,
+    #[serde(default)]
+    pub rollback_policy: RollbackPolicy,
+    #[serde(default)]
+    pub run_at: Option<DateTime<Utc>>,
     pub separator: Option<String>,
+    #[serde(default)]
+    pub shared_drive: Option<SharedDriveSettings>,
     pub skip_users_on_conflict: bool,
+    #[serde(default)]
+    pub slack: Option<SlackInviteSettings>,
+    #[serde(default)]
+    pub two_step_verification: Option<TwoStepVerificationPolicy>,
     pub use_first_and_last_name: bool,
+    #[serde(default)]
+    pub verify_recovery_email_domains: bool,
+    #[serde(default)]
     pub volunteers: Vec<VolunteerDetails>,
 }
+
+/// Options for resuming an export to Google Workspace.
+///
+/// * `priority`: How urgently the resumed export should run. Defaults to `normal`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumeWorkspaceExportQuery {
+    #[serde(default)]
+    pub priority: JobPriority,
+}
+
+/// Filters for the emails sent by an export job.
+///
+/// * `recipient`: Only list emails sent to this address
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailHistoryQuery {
+    pub recipient: Option<String>,
+}
+
+/// How many exported volunteers are listed per page, if a request does not say.
+pub const DEFAULT_EXPORTED_VOLUNTEERS_PAGE_SIZE: i64 = 100;
+
+/// The most exported volunteers that can be listed per page.
+pub const MAX_EXPORTED_VOLUNTEERS_PAGE_SIZE: i64 = 500;
+
+/// Filters and pagination for the volunteers exported to workspace. Every filter that is set must
+/// match.
+///
+/// * `cursor`: Where the page starts, as returned by the previous page. Defaults to the first page.
+/// * `email_domain`: Only list volunteers whose workspace email is in this domain
+/// * `exported_after`: Only list volunteers exported at or after this time
+/// * `exported_before`: Only list volunteers exported before this time
+/// * `job_id`: Only list volunteers exported by this job
+/// * `limit`: The most volunteers to list. Defaults to 100, and can be at most 500.
+/// * `org_unit`: Only list volunteers exported to this org unit or any org unit below it
+/// * `project_cycle_id`: Only list volunteers exported by a job of this project cycle
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedVolunteersQuery {
+    #[serde(default)]
+    pub cursor: Option<String>,
+    #[serde(default)]
+    pub email_domain: Option<String>,
+    #[serde(default)]
+    pub exported_after: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub exported_before: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub job_id: Option<Uuid>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub org_unit: Option<String>,
+    #[serde(default)]
+    pub project_cycle_id: Option<Uuid>,
+}
+
+impl ExportedVolunteersQuery {
+    /// How many volunteers to list, kept between 1 and the most allowed.
+    pub fn page_size(&self) -> i64 {
+        self.limit
+            .unwrap_or(DEFAULT_EXPORTED_VOLUNTEERS_PAGE_SIZE)
+            .clamp(1, MAX_EXPORTED_VOLUNTEERS_PAGE_SIZE)
+    }
+}
+
+/// Request to configure activation reminders for a project cycle.
+///
+/// * `enabled`: Whether reminders are sent. Defaults to `true`.
+/// * `remind_after_days`: How many days after a volunteer is exported to remind them, if they have
+///   not signed in to their workspace account
+/// * `locale`: The locale to send reminders in (e.g. `es`). Defaults to English.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivationReminderSettingsRequest {
+    #[serde(default = "default_reminders_enabled")]
+    pub enabled: bool,
+    pub remind_after_days: i32,
+    #[serde(default)]
+    pub locale: Option<String>,
+}
+
+fn default_reminders_enabled() -> bool {
+    true
+}
+
+/// Request to suspend (or unsuspend) the workspace accounts of a project cycle's volunteers.
+///
+/// * `action`: Whether to suspend or unsuspend the accounts. Defaults to suspending them.
+/// * `volunteer_ids`: Only act on these volunteers. Defaults to every exported volunteer in the
+///   cycle.
+/// * `concurrency`: The maximum number of accounts to act on at once
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuspendWorkspaceUsersRequest {
+    #[serde(default)]
+    pub action: WorkspaceSuspensionAction,
+    #[serde(default)]
+    pub volunteer_ids: Option<Vec<Uuid>>,
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+}
+
+/// Request to deprovision the workspace accounts of a project cycle's volunteers.
+///
+/// * `concurrency`: The maximum number of accounts to suspend at once
+/// * `grace_period_days`: How many days to keep the accounts suspended before deleting them. Their
+///   deletion can be cancelled until then. Defaults to 30, and can be at most 365.
+/// * `volunteer_ids`: Only deprovision these volunteers. Defaults to every exported volunteer in
+///   the cycle.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeprovisionWorkspaceUsersRequest {
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    #[serde(default)]
+    pub grace_period_days: Option<u32>,
+    #[serde(default)]
+    pub volunteer_ids: Option<Vec<Uuid>>,
+}
+
+/// Request to cancel the scheduled deletion of a volunteer's workspace account.
+///
+/// * `unsuspend`: Whether to also lift the suspension of the account, so the volunteer can sign in
+///   again. Defaults to keeping the account suspended.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelWorkspaceDeletionRequest {
+    #[serde(default)]
+    pub unsuspend: bool,
+}
+
+/// Request to reconcile the accounts in a workspace org unit with the volunteers exported to it.
+///
+/// * `fix`: Whether to fix the orphans that can be fixed, instead of only reporting them. Records
+///   of accounts that no longer exist are removed, and accounts whose recovery email belongs to a
+///   volunteer with no workspace email are recorded as that volunteer's export. Defaults to
+///   `false`.
+/// * `org_unit`: The full path of the org unit to reconcile, including its child org units.
+///   Defaults to `/Programs/PantheonUsers`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileWorkspaceUsersRequest {
+    #[serde(default)]
+    pub fix: bool,
+    #[serde(default)]
+    pub org_unit: Option<String>,
+}
+
+/// Request to push the Pantheon profiles of a project cycle's volunteers to their workspace
+/// accounts.
+///
+/// * `concurrency`: The maximum number of accounts to update at once
+/// * `volunteer_ids`: Only update these volunteers. Defaults to every exported volunteer in the
+///   cycle.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncWorkspaceProfilesRequest {
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    #[serde(default)]
+    pub volunteer_ids: Option<Vec<Uuid>>,
+}
+
+/// Request to invite the exported volunteers of a project cycle to Slack.
+///
+/// * `channel_ids`: The IDs of the Slack channels to add the volunteers to (e.g. `C0123456789`)
+/// * `concurrency`: The maximum number of invitations to send at once
+/// * `volunteer_ids`: Only invite these volunteers. Defaults to every exported volunteer in the
+///   cycle.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteToSlackRequest {
+    pub channel_ids: Vec<String>,
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    #[serde(default)]
+    pub volunteer_ids: Option<Vec<Uuid>>,
+}
+
+/// Request to look up when a project cycle's exported volunteers last signed in to workspace.
+///
+/// * `concurrency`: The maximum number of accounts to look up at once
+/// * `volunteer_ids`: Only look up these volunteers. Defaults to every exported volunteer in the
+///   cycle.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncWorkspaceLoginsRequest {
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    #[serde(default)]
+    pub volunteer_ids: Option<Vec<Uuid>>,
+}
+
+/// Request to transfer the Drive files of a project cycle's departing volunteers to an archive
+/// account.
+///
+/// * `archive_email`: The email of the workspace account that receives the files (e.g. a
+///   program's archive account)
+/// * `concurrency`: The maximum number of transfers to run at once
+/// * `suspend`: Whether to suspend each volunteer's account once all of their files have been
+///   transferred. Defaults to `true`, which needs the project cycle to have been archived.
+/// * `volunteer_ids`: Only transfer the files of these volunteers. Defaults to every exported
+///   volunteer in the cycle.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferDriveFilesRequest {
+    pub archive_email: String,
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    #[serde(default = "default_suspend_after_transfer")]
+    pub suspend: bool,
+    #[serde(default)]
+    pub volunteer_ids: Option<Vec<Uuid>>,
+}
+
+fn default_suspend_after_transfer() -> bool {
+    true
+}
+
+/// Filters for the recorded sign ins of a project cycle's volunteers.
+///
+/// * `never_activated`: Only list volunteers who had never signed in when their account was last
+///   checked. Defaults to `false`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceLoginsQuery {
+    #[serde(default)]
+    pub never_activated: bool,
+}
@@ -1,8 +1,151 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::workspace::email_retries::EmailRetrySummary;
+use super::workspace::recovery::NeedsAttention;
+use super::workspace::reminders::ActivationReminderSummary;
+use super::workspace::{BlockedEmail, PlannedExport, SkippedVolunteer};
+use crate::services::storage::entities::{
+    ActivationReminder, ActivationReminderSettings, EmailRetry, EmailSend,
+    ExportedVolunteerDetails, OnboardingEmailDelivery, WorkspaceDeletion, WorkspaceDriveTransfer,
+    WorkspaceLogin, WorkspaceSuspension,
+};
+use crate::services::workspace::entities::WorkspaceOrgUnit;
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportUsersToWorkspaceResponse {
     pub job_id: Uuid,
 }
+
+/// The response to a dry run export.
+///
+/// * `volunteers`: The workspace accounts that the export would create
+/// * `skipped`: The volunteers that the export would skip because they were already exported
+/// * `blocked`: The generated emails that the export would pass over because they are reserved or
+///   blocklisted
+/// * `needs_attention`: The volunteers that the export would leave out because their recovery
+///   emails are invalid or cannot receive mail
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPreviewResponse {
+    pub volunteers: Vec<PlannedExport>,
+    pub skipped: Vec<SkippedVolunteer>,
+    pub blocked: Vec<BlockedEmail>,
+    pub needs_attention: Vec<NeedsAttention>,
+}
+
+/// What the mail provider has reported about the onboarding emails sent by an export job.
+///
+/// * `deliveries`: The latest delivery event for each onboarding email the provider has reported
+///   on. Emails the provider has not reported on yet are not listed.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingEmailDeliveriesResponse {
+    pub deliveries: Vec<OnboardingEmailDelivery>,
+}
+
+/// Every attempt an export job made to send an email.
+///
+/// * `emails`: The attempts, oldest first, including the ones no mail provider accepted
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailHistoryResponse {
+    pub emails: Vec<EmailSend>,
+}
+
+/// The outcome of retrying the onboarding emails an export job failed to send.
+///
+/// * `summary`: How many of the emails were sent, and how many failed again
+/// * `queued`: The emails that are still queued, because they failed again
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailRetryResponse {
+    pub summary: EmailRetrySummary,
+    pub queued: Vec<EmailRetry>,
+}
+
+/// How activation reminders are configured for a project cycle.
+///
+/// * `settings`: The settings, or `None` if reminders were never configured for the cycle
+/// * `reminders`: Every exported volunteer in the cycle who was checked for a reminder, newest
+///   first
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivationRemindersResponse {
+    pub settings: Option<ActivationReminderSettings>,
+    pub reminders: Vec<ActivationReminder>,
+}
+
+/// The outcome of checking a project cycle's exported volunteers for activation reminders.
+///
+/// * `summary`: How many volunteers were reminded, had signed in, were skipped, or failed
+/// * `reminders`: Every exported volunteer in the cycle who was checked for a reminder, newest
+///   first
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendActivationRemindersResponse {
+    pub summary: ActivationReminderSummary,
+    pub reminders: Vec<ActivationReminder>,
+}
+
+/// The workspace accounts of a project cycle's volunteers that offboarding jobs have suspended or
+/// unsuspended.
+///
+/// * `suspensions`: Every action taken on an account in the cycle, newest first, including the
+///   ones that failed
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceSuspensionsResponse {
+    pub suspensions: Vec<WorkspaceSuspension>,
+}
+
+/// The scheduled deletions of a project cycle's workspace accounts.
+///
+/// * `deletions`: Every deletion scheduled for an account in the cycle, newest first, including the
+///   ones that were cancelled, carried out, or failed
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceDeletionsResponse {
+    pub deletions: Vec<WorkspaceDeletion>,
+}
+
+/// The transfers of a project cycle's volunteers' Drive files to archive accounts.
+///
+/// * `transfers`: Every transfer of a volunteer's files in the cycle, newest first, including the
+///   ones that failed
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveTransfersResponse {
+    pub transfers: Vec<WorkspaceDriveTransfer>,
+}
+
+/// When a project cycle's exported volunteers last signed in to workspace.
+///
+/// * `logins`: The last sign in of every volunteer in the cycle, as of when their account was last
+///   checked
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceLoginsResponse {
+    pub logins: Vec<WorkspaceLogin>,
+}
+
+/// A page of the volunteers exported to workspace.
+///
+/// * `volunteers`: The exported volunteers on this page, newest first
+/// * `next_cursor`: The cursor of the next page, or `None` if this is the last page
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedVolunteersResponse {
+    pub volunteers: Vec<ExportedVolunteerDetails>,
+    pub next_cursor: Option<String>,
+}
+
+/// The org units in Google Workspace that users can be exported to.
+///
+/// * `org_units`: Every org unit except the root, sorted by path
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceOrgUnitsResponse {
+    pub org_units: Vec<WorkspaceOrgUnit>,
+}
@@ -0,0 +1,192 @@
+//! This module gives exported volunteers extra addresses on their workspace accounts (e.g.
+//! `jane@developforgood.org` alongside `jane.doe@developforgood.org`).
+//!
+//! Aliases are a convenience, so an alias that is blocked or already taken (by an earlier export,
+//! another volunteer in this export, or any Google Workspace user) is left out rather than
+//! changed, and failing to add one does not fail the volunteer's export. Every alias that is added
+//! is recorded, so later exports do not hand it out again.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use futures::{stream, StreamExt};
+use uuid::Uuid;
+
+use super::{AssignedEmail, ExportParams, ExportSettings};
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::storage::entities::VolunteerDetails;
+use crate::services::storage::volunteers::InsertWorkspaceAlias;
+use crate::services::storage::ExecOptsBuilder;
+
+/// Pick the aliases each volunteer's workspace account will have.
+///
+/// * `services`: The services needed to run the export
+/// * `params`: The export parameters
+/// * `volunteers`: The volunteers to export
+/// * `emails`: The workspace email assigned to each volunteer by `assign_workspace_emails`
+///
+/// Returns the aliases for each volunteer, in the same order as `volunteers`. Volunteers who
+/// already have a workspace account get none. Unless this is a dry run, every alias is looked up
+/// in Google Workspace, and aliases that belong to an existing user are left out.
+pub(super) async fn assign_aliases(
+    services: &ExportServices,
+    params: &ExportParams,
+    volunteers: &[VolunteerDetails],
+    emails: &[AssignedEmail],
+) -> Result<Vec<Vec<String>>> {
+    let mut assigned = vec![Vec::<String>::new(); volunteers.len()];
+    if params.email_policy.alias_formats.is_empty() {
+        return Ok(assigned);
+    }
+
+    let candidates = volunteers
+        .iter()
+        .zip(emails)
+        .map(|(v, email)| match email {
+            AssignedEmail::Available(primary_email) => {
+                params.email_policy.alias_emails(&v.first_name, &v.last_name, primary_email)
+            }
+            AssignedEmail::Existing(_) => Vec::new(),
+        })
+        .collect::<Vec<Vec<String>>>();
+
+    let mut taken = services
+        .storage_layer
+        .fetch_taken_workspace_emails(
+            candidates.iter().flatten().cloned().collect(),
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?
+        .into_iter()
+        .collect::<HashSet<String>>();
+    taken.extend(emails.iter().map(|email| match email {
+        AssignedEmail::Available(email) | AssignedEmail::Existing(email) => email.clone(),
+    }));
+
+    let mut picked = Vec::<(usize, String)>::new();
+    for (i, aliases) in candidates.into_iter().enumerate() {
+        for alias in aliases {
+            if let Some(reason) = params.email_policy.blocklist.check(&alias) {
+                log::info!(
+                    "Leaving out alias {alias} for {}: {reason}",
+                    volunteers[i].volunteer_id
+                );
+            } else if !taken.insert(alias.clone()) {
+                log::info!("Leaving out alias {alias} for {}: taken", volunteers[i].volunteer_id);
+            } else {
+                picked.push((i, alias));
+            }
+        }
+    }
+
+    if !params.dry_run {
+        let found = stream::iter(picked.iter())
+            .map(|(_, alias)| async move {
+                let label = format!("Looking up {alias} in workspace");
+                params
+                    .retry_policy
+                    .run(&label, || services.workspace.find_user(&params.principal, alias))
+                    .await
+                    .unwrap_or_else(|e| {
+                        log::warn!("Could not look up {alias} in workspace: {e}");
+                        None
+                    })
+            })
+            .buffered(params.concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await;
+
+        picked = picked
+            .into_iter()
+            .zip(found)
+            .filter_map(|(picked, account)| match account {
+                None => Some(picked),
+                Some(_) => {
+                    log::info!("Leaving out alias {}: taken in workspace", picked.1);
+                    None
+                }
+            })
+            .collect();
+    }
+
+    for (i, alias) in picked {
+        assigned[i].push(alias);
+    }
+
+    Ok(assigned)
+}
+
+/// Add aliases to volunteers' newly created workspace accounts, and record the ones that were
+/// added.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+/// * `settings`: Settings for the export
+/// * `accounts`: The accounts, as volunteer IDs, workspace emails, and the aliases to add
+///
+/// Aliases are added up to `settings.concurrency` at once, and transient failures are retried.
+/// Returns the aliases each volunteer could not be given, by workspace email. Volunteers that were
+/// given every alias are left out.
+pub(super) async fn add_aliases(
+    services: &ExportServices,
+    job_id: Uuid,
+    settings: &ExportSettings<'_>,
+    accounts: &[(Uuid, String, Vec<String>)],
+) -> HashMap<String, Vec<String>> {
+    let aliases = accounts
+        .iter()
+        .flat_map(|(volunteer_id, email, aliases)| {
+            aliases.iter().map(move |alias| (*volunteer_id, email, alias))
+        })
+        .collect::<Vec<(Uuid, &String, &String)>>();
+
+    if aliases.is_empty() {
+        return HashMap::new();
+    }
+
+    let results = stream::iter(aliases)
+        .map(|(volunteer_id, email, alias)| async move {
+            let result = settings
+                .retry_policy
+                .run(&format!("Adding alias {alias} to {email}"), || {
+                    services.workspace.create_alias(settings.principal, email, alias)
+                })
+                .await;
+
+            if let Err(e) = &result {
+                log::error!("Failed to add alias {alias} to {email}: {e}");
+            }
+
+            (volunteer_id, email, alias, result.is_ok())
+        })
+        .buffered(settings.concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut added = Vec::<InsertWorkspaceAlias>::with_capacity(results.len());
+    let mut failed = HashMap::<String, Vec<String>>::new();
+    for (volunteer_id, email, alias, ok) in results {
+        if ok {
+            added.push(InsertWorkspaceAlias {
+                volunteer_id,
+                job_id,
+                workspace_email: email.clone(),
+                alias: alias.clone(),
+            });
+        } else {
+            failed.entry(email.clone()).or_default().push(alias.clone());
+        }
+    }
+
+    let recorded = match ExecOptsBuilder::default().build() {
+        Ok(mut exec_opts) => {
+            services.storage_layer.batch_insert_workspace_aliases(added, &mut exec_opts).await
+        }
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = recorded {
+        log::error!("Failed to record the aliases added by job {job_id}: {e}");
+    }
+
+    failed
+}
@@ -0,0 +1,129 @@
+//! This module invites exported volunteers to an onboarding session in Google Calendar.
+//!
+//! The session is scheduled on the calendar of the staff member who started the export before
+//! anyone is exported, so that its link can go in every onboarding email. Volunteers are invited
+//! once their accounts exist. Like groups, failing to invite a volunteer does not roll back their
+//! account; it is reported in the export outcome instead.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::ExportSettings;
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::workspace::calendar::{CalendarEvent, CreateCalendarEvent};
+
+/// The onboarding session to invite every volunteer in an export to.
+///
+/// * `summary`: The title of the session (e.g. `Spring 2025 Orientation`)
+/// * `description`: A description of the session, if any
+/// * `start`: When the session starts
+/// * `end`: When the session ends
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingSessionSettings {
+    pub summary: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl OnboardingSessionSettings {
+    /// Check that the session settings are well formed.
+    pub fn validate(&self) -> Result<()> {
+        if self.summary.trim().is_empty() {
+            bail!("The onboarding session summary must not be empty");
+        }
+
+        if self.end <= self.start {
+            bail!("The onboarding session must end after it starts");
+        }
+
+        Ok(())
+    }
+}
+
+/// Schedule the onboarding session of an export.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+/// * `principal`: The email of the user requesting the export, who organizes the session
+/// * `session`: The session settings of the export
+///
+/// The event is given an ID derived from the job ID, so scheduling it again returns the same event
+/// rather than creating another.
+pub async fn schedule_onboarding_session(
+    services: &ExportServices,
+    job_id: Uuid,
+    principal: &str,
+    session: &OnboardingSessionSettings,
+) -> Result<CalendarEvent> {
+    // Calendar event IDs may only contain the digits and the letters a-v, which a UUID's hex
+    // digits without their hyphens satisfy
+    let event = CreateCalendarEvent {
+        id: job_id.simple().to_string(),
+        summary: session.summary.clone(),
+        description: session.description.clone(),
+        start: session.start,
+        end: session.end,
+    };
+
+    let event =
+        services.workspace.create_calendar_event(principal, event).await.with_context(|| {
+            format!("Failed to schedule onboarding session {}", session.summary)
+        })?;
+
+    log::info!("Scheduled onboarding session {} ({})", session.summary, event.id);
+
+    Ok(event)
+}
+
+/// Invite exported volunteers to the event in `settings.onboarding_session`, if the export has one.
+///
+/// * `services`: The services needed to run the export
+/// * `settings`: Settings for the export
+/// * `workspace_emails`: The workspace emails of the volunteers
+///
+/// The volunteers are invited with one request, and transient failures are retried. Returns the
+/// workspace emails of the volunteers that could not be invited, which is either none of them or
+/// all of them.
+pub(super) async fn invite_to_onboarding_session<'a>(
+    services: &ExportServices,
+    settings: &ExportSettings<'_>,
+    workspace_emails: impl IntoIterator<Item = &'a String>,
+) -> HashSet<String> {
+    let Some(event_id) = settings.onboarding_session else {
+        return HashSet::new();
+    };
+
+    let workspace_emails = workspace_emails.into_iter().cloned().collect::<Vec<String>>();
+    if workspace_emails.is_empty() {
+        return HashSet::new();
+    }
+
+    let result = settings
+        .retry_policy
+        .run(&format!("Inviting users to onboarding session {event_id}"), || {
+            services.workspace.invite_to_calendar_event(
+                settings.principal,
+                event_id,
+                &workspace_emails,
+            )
+        })
+        .await;
+
+    match result {
+        Ok(_) => HashSet::new(),
+        Err(e) => {
+            log::error!(
+                "Failed to invite {} users to onboarding session {event_id}: {e}",
+                workspace_emails.len()
+            );
+            workspace_emails.into_iter().collect()
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! This module lets a running export find out that its job was cancelled.
+//!
+//! Cancelling a job only changes its status, so an export checks the status as it goes and stops
+//! cleanly between volunteers once it sees the job was cancelled. Checking costs a query, so the
+//! status is read again at most every `CANCELLATION_CHECK_INTERVAL`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::storage::types::JobStatus;
+use crate::services::storage::ExecOptsBuilder;
+
+/// How long an export goes without reading its job's status again.
+pub const CANCELLATION_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Why volunteers an export did not get to before it was cancelled were not exported.
+pub const CANCELLED_REASON: &str = "Export was cancelled";
+
+/// Tracks whether the job an export runs has been cancelled.
+///
+/// * `job_id`: The ID of the export job
+/// * `cancelled`: Whether the job was seen to be cancelled, which it stays once it is
+/// * `checked_at`: When the job's status was last read
+#[derive(Debug)]
+pub struct JobCancellation {
+    job_id: Uuid,
+    cancelled: AtomicBool,
+    checked_at: Mutex<Option<Instant>>,
+}
+
+impl JobCancellation {
+    pub fn new(job_id: Uuid) -> Self {
+        Self { job_id, cancelled: AtomicBool::new(false), checked_at: Mutex::new(None) }
+    }
+
+    /// Whether the job has been cancelled, reading its status again if it has not been read in the
+    /// last `CANCELLATION_CHECK_INTERVAL`.
+    ///
+    /// * `services`: The services needed to run the export
+    pub async fn is_cancelled(&self, services: &ExportServices) -> bool {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        {
+            let mut checked_at = self.checked_at.lock().expect("cancellation lock poisoned");
+            if checked_at.is_some_and(|at| at.elapsed() < CANCELLATION_CHECK_INTERVAL) {
+                return false;
+            }
+            *checked_at = Some(Instant::now());
+        }
+
+        self.check(services).await
+    }
+
+    /// Read the job's status to find out whether it has been cancelled, however recently it was
+    /// last read.
+    ///
+    /// * `services`: The services needed to run the export
+    ///
+    /// An export that cannot read its job's status carries on, so failing to read it is logged
+    /// rather than taken as a cancellation.
+    pub async fn check(&self, services: &ExportServices) -> bool {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        let status = match ExecOptsBuilder::default().build() {
+            Ok(mut exec_opts) => {
+                services.storage_layer.fetch_job_status(self.job_id, &mut exec_opts).await
+            }
+            Err(e) => Err(e.into()),
+        };
+
+        match status {
+            Ok(JobStatus::Cancelled) => {
+                log::info!("Job {} was cancelled, stopping the export", self.job_id);
+                self.cancelled.store(true, Ordering::Relaxed);
+                true
+            }
+            Ok(_) => false,
+            Err(e) => {
+                log::warn!("Failed to check whether job {} was cancelled: {e}", self.job_id);
+                false
+            }
+        }
+    }
+}
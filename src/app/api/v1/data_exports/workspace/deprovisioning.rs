@@ -0,0 +1,337 @@
+//! This module deprovisions the workspace accounts of a project cycle's exported volunteers.
+//!
+//! Deprovisioning suspends each account right away and schedules it to be deleted once a grace
+//! period is over. Until then, the deletion can be cancelled to rescue the account. Deleting an
+//! account also deletes its mail and files, so a background task only deletes accounts that are
+//! still suspended when their grace period ends.
+
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::suspensions::{suspend_volunteers, SuspensionParams};
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::storage::deletions::ScheduleWorkspaceDeletion;
+use crate::services::storage::entities::{SuspensionCandidate, WorkspaceDeletion};
+use crate::services::storage::jobs::NEW_JOB_VERSION;
+use crate::services::storage::organizations::with_org;
+use crate::services::storage::types::WorkspaceSuspensionAction;
+use crate::services::storage::ExecOptsBuilder;
+use crate::services::workspace::retry::RetryPolicy;
+
+/// How many days accounts stay suspended before they are deleted, if a deprovisioning job does not
+/// say.
+pub const DEFAULT_DELETION_GRACE_PERIOD_DAYS: u32 = 30;
+
+/// The longest grace period a deprovisioning job can give accounts.
+pub const MAX_DELETION_GRACE_PERIOD_DAYS: u32 = 365;
+
+/// How often the background task looks for accounts that are due to be deleted.
+pub const WORKSPACE_DELETION_POLL_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// The most accounts to delete at once.
+const WORKSPACE_DELETION_BATCH_SIZE: i64 = 100;
+
+/// Held while due accounts are being deleted, so that two runs never delete the same account.
+static DELETION_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Parameters for a job that deprovisions workspace accounts.
+///
+/// * `job_id`: The ID of the job
+/// * `principal`: The email of the user who started the job
+/// * `grace_period_days`: How many days to keep the accounts suspended before deleting them
+/// * `concurrency`: The maximum number of accounts to suspend at once
+/// * `volunteers`: The volunteers whose accounts are deprovisioned
+pub struct DeprovisionParams {
+    pub job_id: Uuid,
+    pub principal: String,
+    pub grace_period_days: u32,
+    pub concurrency: usize,
+    pub volunteers: Vec<SuspensionCandidate>,
+}
+
+/// What a job that deprovisions workspace accounts did.
+///
+/// * `suspended`: The number of accounts that were suspended and scheduled to be deleted
+/// * `failed`: The number of accounts that could not be suspended. They are not deleted.
+/// * `delete_after`: When the suspended accounts will be deleted, unless their deletion is
+///   cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeprovisionSummary {
+    pub suspended: usize,
+    pub failed: usize,
+    pub delete_after: DateTime<Utc>,
+}
+
+/// What deleting due workspace accounts did.
+///
+/// * `deleted`: The number of accounts that were deleted
+/// * `failed`: The number of accounts that could not be deleted, or were kept because they are no
+///   longer suspended
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceDeletionSummary {
+    pub deleted: usize,
+    pub failed: usize,
+}
+
+/// Check that the grace period of a deprovisioning job is allowed.
+///
+/// * `grace_period_days`: How many days to keep the accounts suspended before deleting them
+pub fn validate_grace_period(grace_period_days: u32) -> Result<()> {
+    if grace_period_days == 0 {
+        bail!("Accounts must be kept for at least one day before they are deleted");
+    }
+
+    if grace_period_days > MAX_DELETION_GRACE_PERIOD_DAYS {
+        bail!("Accounts can be kept for at most {MAX_DELETION_GRACE_PERIOD_DAYS} days");
+    }
+
+    Ok(())
+}
+
+/// When accounts suspended now will be deleted.
+///
+/// * `grace_period_days`: How many days to keep the accounts suspended before deleting them
+/// * `now`: The current time
+pub fn delete_after(grace_period_days: u32, now: DateTime<Utc>) -> DateTime<Utc> {
+    now + chrono::Duration::days(i64::from(grace_period_days))
+}
+
+/// Suspend volunteers' workspace accounts and schedule them to be deleted.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+///
+/// Every suspension is recorded like those of an offboarding job, and only the accounts that were
+/// suspended are scheduled to be deleted. The summary is saved as the job's result, and the job is
+/// marked complete, or errored if any account could not be suspended.
+pub async fn deprovision_task(
+    services: &ExportServices,
+    params: DeprovisionParams,
+) -> Result<DeprovisionSummary> {
+    let job_id = params.job_id;
+    let result = deprovision_volunteers(services, params).await;
+
+    let finished = match &result {
+        Ok(summary) => finish_deprovisioning(services, job_id, summary).await,
+        Err(e) => {
+            log::error!("Job {job_id} failed to deprovision users: {e}");
+            services
+                .storage_layer
+                .mark_job_errored(
+                    job_id,
+                    NEW_JOB_VERSION,
+                    e.to_string(),
+                    &mut ExecOptsBuilder::default().build()?,
+                )
+                .await
+        }
+    };
+
+    if let Err(e) = finished {
+        log::error!("Failed to record the outcome of job {job_id}: {e}");
+    }
+
+    result
+}
+
+/// Suspend every volunteer's account and schedule the suspended ones to be deleted.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+async fn deprovision_volunteers(
+    services: &ExportServices,
+    params: DeprovisionParams,
+) -> Result<DeprovisionSummary> {
+    let suspension = SuspensionParams {
+        job_id: params.job_id,
+        principal: params.principal,
+        action: WorkspaceSuspensionAction::Suspend,
+        concurrency: params.concurrency,
+        volunteers: params.volunteers,
+    };
+
+    let (summary, suspended) = suspend_volunteers(services, &suspension).await?;
+    let delete_after = delete_after(params.grace_period_days, Utc::now());
+
+    let deletions = suspended
+        .into_iter()
+        .map(|v| ScheduleWorkspaceDeletion {
+            job_id: suspension.job_id,
+            volunteer_id: v.volunteer_id,
+            workspace_email: v.workspace_email.clone(),
+            delete_after,
+            principal: suspension.principal.clone(),
+        })
+        .collect::<Vec<ScheduleWorkspaceDeletion>>();
+
+    services
+        .storage_layer
+        .schedule_workspace_deletions(deletions, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    log::info!(
+        "Job {} suspended {} users, who will be deleted after {delete_after}",
+        suspension.job_id,
+        summary.succeeded
+    );
+
+    Ok(DeprovisionSummary { suspended: summary.succeeded, failed: summary.failed, delete_after })
+}
+
+/// Save the summary of a deprovisioning job and mark the job complete, or errored if any account
+/// could not be suspended.
+///
+/// * `services`: The services needed to run the job
+/// * `job_id`: The ID of the job
+/// * `summary`: What the job did
+async fn finish_deprovisioning(
+    services: &ExportServices,
+    job_id: Uuid,
+    summary: &DeprovisionSummary,
+) -> Result<()> {
+    services
+        .storage_layer
+        .set_job_result(
+            job_id,
+            serde_json::to_value(summary)?,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    if summary.failed > 0 {
+        services
+            .storage_layer
+            .mark_job_errored(
+                job_id,
+                NEW_JOB_VERSION,
+                format!("Failed to suspend {} users", summary.failed),
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await
+    } else {
+        services
+            .storage_layer
+            .mark_job_complete(job_id, NEW_JOB_VERSION, &mut ExecOptsBuilder::default().build()?)
+            .await
+    }
+}
+
+/// Delete the workspace accounts whose grace period is over.
+///
+/// * `services`: The services needed to run the job
+///
+/// An account that was unsuspended since it was scheduled (e.g. by an admin in the Google Admin
+/// console) is kept, and its deletion is recorded as failed. An account that no longer exists is
+/// recorded as deleted.
+pub async fn delete_due_accounts(services: &ExportServices) -> Result<WorkspaceDeletionSummary> {
+    let _guard = DELETION_LOCK.lock().await;
+    let mut summary = WorkspaceDeletionSummary::default();
+
+    loop {
+        let due = services
+            .storage_layer
+            .fetch_due_workspace_deletions(
+                WORKSPACE_DELETION_BATCH_SIZE,
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?;
+
+        // Deleted and failed accounts are no longer scheduled, so every batch is new
+        if due.is_empty() {
+            return Ok(summary);
+        }
+
+        for deletion in due {
+            match delete_account(services, &deletion).await {
+                Ok(()) => {
+                    log::info!("Deleted {} after its grace period", deletion.workspace_email);
+                    services
+                        .storage_layer
+                        .complete_workspace_deletion(
+                            deletion.id,
+                            &mut ExecOptsBuilder::default().build()?,
+                        )
+                        .await?;
+                    summary.deleted += 1;
+                }
+                Err(e) => {
+                    log::error!("Failed to delete {}: {e}", deletion.workspace_email);
+                    services
+                        .storage_layer
+                        .fail_workspace_deletion(
+                            deletion.id,
+                            e.to_string(),
+                            &mut ExecOptsBuilder::default().build()?,
+                        )
+                        .await?;
+                    summary.failed += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Delete a workspace account if it is still suspended.
+///
+/// * `services`: The services needed to run the job
+/// * `deletion`: The scheduled deletion
+async fn delete_account(services: &ExportServices, deletion: &WorkspaceDeletion) -> Result<()> {
+    let retry_policy = RetryPolicy::default();
+
+    let account = retry_policy
+        .run(&format!("looking up {}", deletion.workspace_email), || {
+            services.workspace.find_user(&deletion.principal, &deletion.workspace_email)
+        })
+        .await?;
+
+    match account {
+        None => {
+            log::warn!("{} was already deleted", deletion.workspace_email);
+            Ok(())
+        }
+        Some(account) if !account.suspended => {
+            bail!("The account is no longer suspended, so it was kept")
+        }
+        Some(_) => {
+            retry_policy
+                .run(&format!("deleting {}", deletion.workspace_email), || {
+                    services.workspace.delete_user(&deletion.principal, &deletion.workspace_email)
+                })
+                .await
+        }
+    }
+}
+
+/// Start the background task that deletes workspace accounts once their grace period is over.
+///
+/// * `services`: The services needed to run the job
+pub fn spawn_workspace_deletion_task(services: ExportServices) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(WORKSPACE_DELETION_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            for org_id in services.org_ids().await {
+                match with_org(org_id, delete_due_accounts(&services)).await {
+                    Ok(summary) if summary != WorkspaceDeletionSummary::default() => log::info!(
+                        "Deleted {} workspace accounts for organization {org_id} after their \
+                         grace period, {} failed",
+                        summary.deleted,
+                        summary.failed
+                    ),
+                    Ok(_) => {}
+                    Err(e) => log::error!(
+                        "Failed to delete workspace accounts for organization {org_id}: {e}"
+                    ),
+                }
+            }
+        }
+    })
+}
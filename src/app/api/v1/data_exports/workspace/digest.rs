@@ -0,0 +1,234 @@
+//! This module emails admins a digest of the exports to Google Workspace.
+//!
+//! The digest covers the period since the last one was sent, which is looked up in the email
+//! audit log, so restarting the app neither skips a digest nor sends one twice.
+
+use std::env;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::mail::{
+    localized_template, DigestEmailParams, SentEmail, DEFAULT_LOCALE, DIGEST_EMAIL_TEMPLATE,
+};
+use crate::services::storage::emails::RecordEmailSend;
+use crate::services::storage::organizations::with_org;
+use crate::services::storage::types::EmailSendStatus;
+use crate::services::storage::ExecOptsBuilder;
+
+/// The name of the environment variable holding the comma separated addresses digests are sent
+/// to. Digests are not sent if it is not set.
+pub const ADMIN_DIGEST_RECIPIENTS_ENV_VAR: &str = "ADMIN_DIGEST_RECIPIENTS";
+
+/// The name of the environment variable holding how often digests are sent (`daily` or `weekly`).
+pub const ADMIN_DIGEST_FREQUENCY_ENV_VAR: &str = "ADMIN_DIGEST_FREQUENCY";
+
+/// How often the background task checks whether a digest is due.
+pub const ADMIN_DIGEST_POLL_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// How often digests are sent.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DigestFrequency {
+    #[default]
+    Daily,
+    Weekly,
+}
+
+impl DigestFrequency {
+    /// Parse a frequency.
+    ///
+    /// * `raw`: The frequency (`daily` or `weekly`, in any case)
+    pub fn parse(raw: &str) -> Result<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "daily" => Ok(Self::Daily),
+            "weekly" => Ok(Self::Weekly),
+            _ => bail!("{raw} is not a digest frequency, expected daily or weekly"),
+        }
+    }
+
+    /// How much time a digest covers.
+    pub fn period(self) -> TimeDelta {
+        match self {
+            Self::Daily => TimeDelta::days(1),
+            Self::Weekly => TimeDelta::weeks(1),
+        }
+    }
+}
+
+/// Who digests are sent to, and how often.
+///
+/// * `recipients`: The admins' email addresses
+/// * `frequency`: How often digests are sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestConfig {
+    pub recipients: Vec<String>,
+    pub frequency: DigestFrequency,
+}
+
+impl DigestConfig {
+    /// Read the configuration from `ADMIN_DIGEST_RECIPIENTS` and `ADMIN_DIGEST_FREQUENCY`.
+    ///
+    /// Returns `None` if there are no recipients, in which case digests are not sent. The
+    /// frequency defaults to daily.
+    pub fn from_env() -> Result<Option<Self>> {
+        let recipients = env::var(ADMIN_DIGEST_RECIPIENTS_ENV_VAR)
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(str::to_owned)
+            .collect::<Vec<String>>();
+        if recipients.is_empty() {
+            return Ok(None);
+        }
+
+        let frequency = match env::var(ADMIN_DIGEST_FREQUENCY_ENV_VAR) {
+            Ok(raw) => DigestFrequency::parse(&raw)?,
+            Err(_) => DigestFrequency::default(),
+        };
+
+        Ok(Some(Self { recipients, frequency }))
+    }
+}
+
+/// The period the next digest covers, or `None` if it is not due yet.
+///
+/// * `last_sent_at`: When the last digest was sent, if one ever was
+/// * `frequency`: How often digests are sent
+/// * `now`: The current time
+///
+/// A digest picks up where the last one left off, so a digest that was due while the app was down
+/// covers the whole time since the last one.
+pub fn due_period(
+    last_sent_at: Option<DateTime<Utc>>,
+    frequency: DigestFrequency,
+    now: DateTime<Utc>,
+) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    match last_sent_at {
+        None => Some((now - frequency.period(), now)),
+        Some(last) if now - last >= frequency.period() => Some((last, now)),
+        Some(_) => None,
+    }
+}
+
+/// Send a digest to every admin, if one is due.
+///
+/// * `services`: The services needed to run the export
+/// * `config`: Who digests are sent to, and how often
+///
+/// Returns the number of admins the digest was sent to. Every attempt is recorded in the email
+/// audit log. If the digest fails to send to everyone, it is tried again on the next check.
+pub async fn send_admin_digest(services: &ExportServices, config: &DigestConfig) -> Result<usize> {
+    let template = localized_template(DIGEST_EMAIL_TEMPLATE, DEFAULT_LOCALE, "html");
+    let last_sent_at = services
+        .storage_layer
+        .fetch_last_email_sent_at(
+            DIGEST_EMAIL_TEMPLATE.to_owned(),
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    let Some((since, until)) = due_period(last_sent_at, config.frequency, Utc::now()) else {
+        return Ok(0);
+    };
+
+    let digest = services
+        .storage_layer
+        .fetch_export_digest(since, until, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    let failed_jobs = services
+        .storage_layer
+        .fetch_failed_export_jobs(since, until, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    let mut sent = 0;
+    for recipient in &config.recipients {
+        let params = DigestEmailParams {
+            email: recipient.clone(),
+            period_start: since,
+            period_end: until,
+            digest: digest.clone(),
+            failed_jobs: failed_jobs.clone(),
+        };
+
+        let result = services.mail.send_digest_email(params).await;
+        match &result {
+            Ok(_) => sent += 1,
+            Err(e) => log::error!("Failed to send the admin digest to {recipient}: {e}"),
+        }
+        record_digest_send(services, recipient, &template, &result).await;
+    }
+
+    Ok(sent)
+}
+
+/// Record an attempt to send a digest in the email audit log.
+///
+/// * `services`: The services needed to run the export
+/// * `recipient`: The admin the digest was sent to
+/// * `template`: The template the digest was rendered from
+/// * `result`: The result of sending the digest
+async fn record_digest_send(
+    services: &ExportServices,
+    recipient: &str,
+    template: &str,
+    result: &Result<SentEmail>,
+) {
+    let (status, provider, message_id, error) = match result {
+        Ok(sent) => {
+            (EmailSendStatus::Sent, Some(sent.provider.clone()), sent.message_id.clone(), None)
+        }
+        Err(e) => (EmailSendStatus::Failed, None, None, Some(e.to_string())),
+    };
+    let data = RecordEmailSend {
+        job_id: None,
+        volunteer_id: None,
+        recipient: recipient.to_owned(),
+        template: template.to_owned(),
+        provider,
+        message_id,
+        status,
+        error,
+    };
+
+    let recorded = match ExecOptsBuilder::default().build() {
+        Ok(mut exec_opts) => {
+            services.storage_layer.record_email_send(data, &mut exec_opts).await.map(|_| ())
+        }
+        Err(e) => Err(e.into()),
+    };
+
+    // The next digest starts where the last recorded one left off, so this one may be sent again
+    if let Err(e) = recorded {
+        log::error!("Failed to record the admin digest to {recipient} in the audit log: {e}");
+    }
+}
+
+/// Start the background task that sends digests when they are due.
+///
+/// * `services`: The services needed to run the export
+/// * `config`: Who digests are sent to, and how often
+pub fn spawn_admin_digest_task(services: ExportServices, config: DigestConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ADMIN_DIGEST_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            for org_id in services.org_ids().await {
+                match with_org(org_id, send_admin_digest(&services, &config)).await {
+                    Ok(0) => {}
+                    Ok(sent) => log::info!(
+                        "Sent the admin digest for organization {org_id} to {sent} admins"
+                    ),
+                    Err(e) => log::error!(
+                        "Failed to send the admin digest for organization {org_id}: {e}"
+                    ),
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,146 @@
+//! This module gives exported volunteers access to their cohort's Shared Drive.
+//!
+//! Each cohort's drive is created the first time an export into it runs, and recorded against the
+//! project cycle so that later exports into the same cohort reuse it. Like groups, failing to add
+//! a volunteer to the drive does not roll back their account; it is reported in the export
+//! outcome instead.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Context, Result};
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::ExportSettings;
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::storage::drives::RecordSharedDriveBuilder;
+use crate::services::storage::ExecOptsBuilder;
+use crate::services::workspace::drive::DriveRole;
+
+/// The Shared Drive to give every volunteer in an export access to.
+///
+/// * `name`: The name of the drive (e.g. `Spring 2025 Cohort`). A drive is created for the project
+///   cycle the first time the name is used.
+/// * `role`: The role volunteers are given on the drive. Defaults to `writer`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedDriveSettings {
+    pub name: String,
+    #[serde(default)]
+    pub role: DriveRole,
+}
+
+impl SharedDriveSettings {
+    /// Check that the drive settings are well formed.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            bail!("The shared drive name must not be empty");
+        }
+
+        if self.role == DriveRole::Organizer {
+            bail!("Volunteers cannot be made organizers of a shared drive");
+        }
+
+        Ok(())
+    }
+}
+
+/// Find the Shared Drive for an export's cohort, creating it if this is the first export into the
+/// cohort.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+/// * `project_cycle_id`: The ID of the project cycle the cohort belongs to
+/// * `principal`: The email of the user requesting the export
+/// * `drive`: The drive settings of the export
+///
+/// The job ID is used as the request ID for creating the drive, so a retried request does not
+/// create a second drive. Returns the ID of the drive.
+pub async fn ensure_shared_drive(
+    services: &ExportServices,
+    job_id: Uuid,
+    project_cycle_id: Uuid,
+    principal: &str,
+    drive: &SharedDriveSettings,
+) -> Result<String> {
+    let existing = services
+        .storage_layer
+        .fetch_shared_drive(project_cycle_id, &drive.name, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    if let Some(existing) = existing {
+        log::info!("Using shared drive {} ({})", existing.name, existing.drive_id);
+        return Ok(existing.drive_id);
+    }
+
+    let Some(created) = services
+        .workspace
+        .create_shared_drive(principal, &job_id.to_string(), &drive.name)
+        .await
+        .with_context(|| format!("Failed to create shared drive {}", drive.name))?
+    else {
+        bail!("Shared drive {} was already created by this job but never recorded", drive.name);
+    };
+
+    log::info!("Created shared drive {} ({})", created.name, created.id);
+
+    services
+        .storage_layer
+        .record_shared_drive(
+            RecordSharedDriveBuilder::default()
+                .project_cycle_id(project_cycle_id)
+                .job_id(job_id)
+                .drive_id(created.id.clone())
+                .name(drive.name.clone())
+                .build()?,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    Ok(created.id)
+}
+
+/// Give exported volunteers access to the drive in `settings.shared_drive`, if the export has one.
+///
+/// * `services`: The services needed to run the export
+/// * `settings`: Settings for the export
+/// * `workspace_emails`: The workspace emails of the volunteers
+///
+/// Members are added up to `settings.concurrency` at once, and transient failures are retried.
+/// Returns the workspace emails of the volunteers that could not be added.
+pub(super) async fn add_to_shared_drive<'a>(
+    services: &ExportServices,
+    settings: &ExportSettings<'_>,
+    workspace_emails: impl IntoIterator<Item = &'a String>,
+) -> HashSet<String> {
+    let Some((drive_id, role)) = settings.shared_drive else {
+        return HashSet::new();
+    };
+
+    stream::iter(workspace_emails)
+        .map(|email| async move {
+            let result = settings
+                .retry_policy
+                .run(&format!("Adding {email} to shared drive {drive_id}"), || {
+                    services.workspace.add_shared_drive_member(
+                        settings.principal,
+                        drive_id,
+                        email,
+                        role,
+                    )
+                })
+                .await;
+
+            match result {
+                Ok(_) => None,
+                Err(e) => {
+                    log::error!("Failed to add {email} to shared drive {drive_id}: {e}");
+                    Some(email.clone())
+                }
+            }
+        })
+        .buffered(settings.concurrency.max(1))
+        .filter_map(|failed| async move { failed })
+        .collect::<HashSet<String>>()
+        .await
+}
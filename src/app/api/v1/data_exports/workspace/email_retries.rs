@@ -0,0 +1,226 @@
+//! This module retries onboarding emails that no mail provider accepted.
+//!
+//! An onboarding email is the only way a volunteer learns their workspace credentials, so an email
+//! that fails to send is queued and sent again with exponential backoff. The queue holds temporary
+//! passwords, so emails are removed from it as soon as they are sent. Emails that keep failing are
+//! given up on until staff retry them manually.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::{checkpoint_volunteers, record_email_send, OnboardingTemplates};
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::mail::suppression::suppression_reason;
+use crate::services::mail::{OnboardingEmailKind, OnboardingEmailParams};
+use crate::services::storage::emails::CreateEmailRetry;
+use crate::services::storage::entities::EmailRetry;
+use crate::services::storage::organizations::with_org;
+use crate::services::storage::types::WorkspaceExportStatus;
+use crate::services::storage::ExecOptsBuilder;
+
+/// The number of times a queued email is sent again before it is given up on.
+pub const MAX_EMAIL_RETRY_ATTEMPTS: i32 = 5;
+
+/// How long to wait before sending a queued email again after its first retry fails. The wait
+/// doubles after every failed retry.
+pub const EMAIL_RETRY_BASE_DELAY: Duration = Duration::from_secs(60);
+
+/// How often the background task looks for queued emails that are due.
+pub const EMAIL_RETRY_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The most queued emails to send at once.
+const EMAIL_RETRY_BATCH_SIZE: i64 = 100;
+
+/// Held while queued emails are being sent, so the background task and a manual retry never send
+/// the same email twice.
+static RETRY_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// The outcome of sending queued emails again.
+///
+/// * `sent`: The number of emails that were sent
+/// * `failed`: The number of emails that failed again
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailRetrySummary {
+    pub sent: usize,
+    pub failed: usize,
+}
+
+/// When to next send a queued email, or `None` to give up on it.
+///
+/// * `attempts`: The number of times the email has been sent again and failed, including the
+///   attempt that just failed
+/// * `now`: The current time
+pub fn next_attempt_at(attempts: i32, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if attempts >= MAX_EMAIL_RETRY_ATTEMPTS {
+        return None;
+    }
+
+    let delay = EMAIL_RETRY_BASE_DELAY * 2u32.pow(attempts.max(1) as u32 - 1);
+    Some(now + delay)
+}
+
+/// The data needed to queue an onboarding email that no mail provider accepted to be sent again,
+/// or `None` if the email was not sent by an export.
+///
+/// * `email`: The email to queue
+pub fn email_retry(email: &OnboardingEmailParams) -> Option<CreateEmailRetry> {
+    Some(CreateEmailRetry {
+        job_id: email.job_id?,
+        volunteer_id: email.volunteer_id?,
+        first_name: email.first_name.clone(),
+        last_name: email.last_name.clone(),
+        email: email.email.clone(),
+        workspace_email: email.workspace_email.clone(),
+        temporary_password: email.temporary_password.clone(),
+        locale: email.locale.clone(),
+    })
+}
+
+/// Send queued emails that are due.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: Only send emails queued by this job, if set
+///
+/// Sent emails are removed from the queue, and their volunteers' checkpoints are marked as
+/// emailed. Emails that fail again are rescheduled, or given up on after
+/// `MAX_EMAIL_RETRY_ATTEMPTS` retries. Emails to suppressed addresses are given up on at once.
+pub async fn retry_due_emails(
+    services: &ExportServices,
+    job_id: Option<Uuid>,
+) -> Result<EmailRetrySummary> {
+    let _guard = RETRY_LOCK.lock().await;
+    let mut summary = EmailRetrySummary::default();
+    let mut templates = OnboardingTemplates::new();
+
+    loop {
+        let due = services
+            .storage_layer
+            .fetch_due_email_retries(
+                job_id,
+                EMAIL_RETRY_BATCH_SIZE,
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?;
+
+        // Sent emails are deleted and failed ones are rescheduled, so every batch is new
+        if due.is_empty() {
+            return Ok(summary);
+        }
+
+        let mut emails = Vec::<(EmailRetry, OnboardingEmailParams, String)>::new();
+        for retry in due {
+            let mut email = OnboardingEmailParams::from(&retry);
+            let template = templates.apply(services, &mut email).await;
+            emails.push((retry, email, template));
+        }
+
+        let results = services
+            .mail
+            .send_onboarding_emails(emails.iter().map(|(_, email, _)| email.clone()).collect())
+            .await;
+
+        for ((retry, email, template), result) in emails.into_iter().zip(results) {
+            record_email_send(services, &email, &template, &result).await;
+            match result {
+                Ok(_) => {
+                    log::info!("Sent queued onboarding email to {}", retry.email);
+                    services
+                        .storage_layer
+                        .delete_email_retry(retry.id, &mut ExecOptsBuilder::default().build()?)
+                        .await?;
+                    checkpoint_volunteers(
+                        services,
+                        retry.job_id,
+                        vec![retry.volunteer_id],
+                        WorkspaceExportStatus::Emailed,
+                    )
+                    .await;
+                    summary.sent += 1;
+                }
+                Err(e) => {
+                    // A suppressed address would be refused on every attempt
+                    let next = match suppression_reason(&e) {
+                        Some(_) => None,
+                        None => next_attempt_at(retry.attempts + 1, Utc::now()),
+                    };
+                    match next {
+                        Some(at) => log::warn!(
+                            "Failed to send queued onboarding email to {}, trying again at {at}: \
+                             {e}",
+                            retry.email
+                        ),
+                        None => log::error!(
+                            "Failed to send queued onboarding email to {} after {} attempts, \
+                             giving up: {e}",
+                            retry.email,
+                            retry.attempts + 1
+                        ),
+                    }
+                    services
+                        .storage_layer
+                        .record_email_retry_failure(
+                            retry.id,
+                            e.to_string(),
+                            next,
+                            &mut ExecOptsBuilder::default().build()?,
+                        )
+                        .await?;
+                    summary.failed += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Start the background task that sends queued emails when they are due.
+///
+/// * `services`: The services needed to run the export
+pub fn spawn_email_retry_task(services: ExportServices) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(EMAIL_RETRY_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            for org_id in services.org_ids().await {
+                match with_org(org_id, retry_due_emails(&services, None)).await {
+                    Ok(summary) if summary.sent + summary.failed > 0 => log::info!(
+                        "Sent {} queued onboarding emails for organization {org_id}, {} failed \
+                         again",
+                        summary.sent,
+                        summary.failed
+                    ),
+                    Ok(_) => {}
+                    Err(e) => log::error!(
+                        "Failed to send queued onboarding emails for organization {org_id}: {e}"
+                    ),
+                }
+            }
+        }
+    })
+}
+
+impl From<&EmailRetry> for OnboardingEmailParams {
+    fn from(value: &EmailRetry) -> Self {
+        OnboardingEmailParams {
+            first_name: value.first_name.clone(),
+            last_name: value.last_name.clone(),
+            email: value.email.clone(),
+            workspace_email: value.workspace_email.clone(),
+            temporary_password: value.temporary_password.clone(),
+            send_at: None,
+            template: None,
+            locale: value.locale.clone(),
+            attachments: Vec::new(),
+            job_id: Some(value.job_id),
+            volunteer_id: Some(value.volunteer_id),
+            kind: OnboardingEmailKind::Welcome,
+            onboarding_session_link: None,
+        }
+    }
+}
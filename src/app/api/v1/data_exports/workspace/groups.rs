@@ -0,0 +1,112 @@
+//! This module adds exported volunteers to Google Groups (e.g. a cohort mailing list or an
+//! announcements list).
+//!
+//! Groups that do not exist yet are created before any volunteer is exported. Adding a volunteer
+//! to a group only fails that group for that volunteer, so their account is still kept and the
+//! groups they missed are reported in the export outcome.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Context, Result};
+use futures::{stream, StreamExt};
+
+use super::recovery::validate_recovery_email;
+use super::ExportSettings;
+use crate::app::api::v1::data_exports::ExportServices;
+
+/// The most groups an export can add volunteers to.
+pub const MAX_EXPORT_GROUPS: usize = 20;
+
+/// Check that the groups an export adds volunteers to are well formed.
+///
+/// * `groups`: The email addresses of the groups
+pub fn validate_groups(groups: &[String]) -> Result<()> {
+    if groups.len() > MAX_EXPORT_GROUPS {
+        bail!("An export can add users to at most {MAX_EXPORT_GROUPS} groups");
+    }
+
+    let mut seen = HashSet::<String>::with_capacity(groups.len());
+    for group in groups {
+        validate_recovery_email(group).with_context(|| format!("Group {group} is not valid"))?;
+        if !seen.insert(group.to_ascii_lowercase()) {
+            bail!("Group {group} is listed more than once");
+        }
+    }
+
+    Ok(())
+}
+
+/// Make sure every group an export adds volunteers to exists, creating the ones that do not.
+///
+/// * `services`: The services needed to run the export
+/// * `principal`: The email of the user requesting the export
+/// * `groups`: The email addresses of the groups
+pub async fn ensure_groups(
+    services: &ExportServices,
+    principal: &str,
+    groups: &[String],
+) -> Result<()> {
+    for group in groups {
+        let created = services
+            .workspace
+            .ensure_group(principal, group)
+            .await
+            .with_context(|| format!("Failed to set up group {group}"))?;
+
+        if created {
+            log::info!("Created group {group} in workspace");
+        }
+    }
+
+    Ok(())
+}
+
+/// Add exported volunteers to every group in `settings.groups`.
+///
+/// * `services`: The services needed to run the export
+/// * `settings`: Settings for the export
+/// * `workspace_emails`: The workspace emails of the volunteers
+///
+/// Memberships are added up to `settings.concurrency` at once, and transient failures are retried
+/// (Google briefly returns 412 for accounts that were only just created). Returns the groups each
+/// volunteer could not be added to, by workspace email. Volunteers that were added to every group
+/// are left out.
+pub(super) async fn add_to_groups<'a>(
+    services: &ExportServices,
+    settings: &ExportSettings<'_>,
+    workspace_emails: impl IntoIterator<Item = &'a String>,
+) -> HashMap<String, Vec<String>> {
+    let memberships = workspace_emails
+        .into_iter()
+        .flat_map(|email| settings.groups.iter().map(move |group| (email, group)))
+        .collect::<Vec<(&String, &String)>>();
+
+    let failed = stream::iter(memberships)
+        .map(|(email, group)| async move {
+            let result = settings
+                .retry_policy
+                .run(&format!("Adding {email} to {group}"), || {
+                    services.workspace.add_group_member(settings.principal, group, email)
+                })
+                .await;
+
+            match result {
+                Ok(_) => None,
+                Err(e) => {
+                    log::error!("Failed to add {email} to group {group}: {e}");
+                    Some((email.clone(), group.clone()))
+                }
+            }
+        })
+        .buffered(settings.concurrency.max(1))
+        .filter_map(|failed| async move { failed })
+        .collect::<Vec<(String, String)>>()
+        .await;
+
+    let mut by_volunteer = HashMap::<String, Vec<String>>::new();
+    for (email, group) in failed {
+        by_volunteer.entry(email).or_default().push(group);
+    }
+
+    by_volunteer
+}
@@ -0,0 +1,128 @@
+//! This module licenses exported volunteers for a Google Workspace edition.
+//!
+//! An export that licenses its volunteers first checks that enough seats are left for all of them,
+//! so it fails before creating anyone instead of leaving part of a cohort without a license.
+//! Licenses are assigned once accounts are created, and failing to license a volunteer does not
+//! fail their export, since the license can be assigned by hand.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Context, Result};
+use futures::{stream, StreamExt};
+
+use super::policies::WorkspaceLicense;
+use super::queue::PermanentJobError;
+use super::ExportSettings;
+use crate::app::api::v1::data_exports::ExportServices;
+
+/// Check that enough seats are left to license every volunteer in an export.
+///
+/// * `services`: The services needed to run the export
+/// * `principal`: The email of the user requesting the export
+/// * `license`: The edition to license volunteers for
+/// * `needed`: The number of volunteers the export will create
+///
+/// If the license does not say how many seats were purchased, the provider is asked, and the check
+/// fails if it cannot say either.
+pub async fn check_license_seats(
+    services: &ExportServices,
+    principal: &str,
+    license: &WorkspaceLicense,
+    needed: usize,
+) -> Result<()> {
+    if needed == 0 {
+        return Ok(());
+    }
+
+    let seats = match license.seats {
+        Some(seats) => seats,
+        None => services
+            .workspace
+            .license_seats(principal, &license.product_id, &license.sku_id)
+            .await?
+            .with_context(|| {
+                format!(
+                    "The number of {} seats purchased is not reported, so it must be given with \
+                     the license",
+                    license.sku_id
+                )
+            })
+            .map_err(PermanentJobError)?,
+    };
+
+    let assigned = services
+        .workspace
+        .count_license_assignments(principal, &license.product_id, &license.sku_id)
+        .await?;
+    // Trying again would not free up any seats
+    let available =
+        check_seats(&license.sku_id, needed, seats, assigned).map_err(PermanentJobError)?;
+
+    log::info!("{available} {} seats are free for {needed} users", license.sku_id);
+
+    Ok(())
+}
+
+/// Check that enough seats of an edition are free, returning how many are.
+///
+/// * `sku_id`: The edition, for errors
+/// * `needed`: The number of seats needed
+/// * `seats`: The number of seats purchased
+/// * `assigned`: The number of seats already assigned
+pub fn check_seats(sku_id: &str, needed: usize, seats: usize, assigned: usize) -> Result<usize> {
+    let available = seats.saturating_sub(assigned);
+
+    if needed > available {
+        bail!(
+            "Not enough {sku_id} licenses: the export needs {needed} seats, but only {available} \
+             of {seats} are available"
+        );
+    }
+
+    Ok(available)
+}
+
+/// Assign every newly created volunteer a license, if the export licenses volunteers.
+///
+/// * `services`: The services needed to run the export
+/// * `settings`: Settings for the export
+/// * `workspace_emails`: The workspace emails of the volunteers
+///
+/// Licenses are assigned up to `settings.concurrency` at once, and transient failures are retried.
+/// Returns the workspace emails of the volunteers that could not be licensed.
+pub(super) async fn assign_licenses<'a>(
+    services: &ExportServices,
+    settings: &ExportSettings<'_>,
+    workspace_emails: impl IntoIterator<Item = &'a String>,
+) -> HashSet<String> {
+    let Some(license) = settings.license else {
+        return HashSet::new();
+    };
+
+    stream::iter(workspace_emails)
+        .map(|email| async move {
+            let result = settings
+                .retry_policy
+                .run(&format!("Licensing {email}"), || {
+                    services.workspace.assign_license(
+                        settings.principal,
+                        &license.product_id,
+                        &license.sku_id,
+                        email,
+                    )
+                })
+                .await;
+
+            match result {
+                Ok(_) => None,
+                Err(e) => {
+                    log::error!("Failed to license {email} for {}: {e}", license.sku_id);
+                    Some(email.clone())
+                }
+            }
+        })
+        .buffered(settings.concurrency.max(1))
+        .filter_map(|failed| async move { failed })
+        .collect::<HashSet<String>>()
+        .await
+}
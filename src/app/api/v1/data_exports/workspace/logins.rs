@@ -0,0 +1,202 @@
+//! This module looks up when exported volunteers last signed in to their workspace accounts and
+//! stores it.
+//!
+//! Google Workspace reports each user's last sign in through the Directory API, so the stored
+//! times are only as fresh as the last check. They back the "never activated" report, and
+//! activation reminders record what they see here too. An account that cannot be looked up keeps
+//! whatever was recorded for it before.
+
+use anyhow::Result;
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::report_progress;
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::storage::entities::SuspensionCandidate;
+use crate::services::storage::jobs::NEW_JOB_VERSION;
+use crate::services::storage::logins::RecordWorkspaceLogin;
+use crate::services::storage::types::JobPhase;
+use crate::services::storage::ExecOptsBuilder;
+use crate::services::workspace::entities::WorkspaceAccount;
+use crate::services::workspace::retry::RetryPolicy;
+
+/// Parameters for a job that checks when volunteers last signed in.
+///
+/// * `job_id`: The ID of the job
+/// * `principal`: The email of the user who started the job
+/// * `concurrency`: The maximum number of accounts to look up at once
+/// * `volunteers`: The volunteers whose accounts are checked
+pub struct LoginSyncParams {
+    pub job_id: Uuid,
+    pub principal: String,
+    pub concurrency: usize,
+    pub volunteers: Vec<SuspensionCandidate>,
+}
+
+/// What a job that checks when volunteers last signed in found.
+///
+/// * `activated`: The number of volunteers who have signed in
+/// * `never_activated`: The number of volunteers who have never signed in
+/// * `missing`: The number of volunteers whose account no longer exists
+/// * `failed`: The number of volunteers whose account could not be looked up
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginSyncSummary {
+    pub activated: usize,
+    pub never_activated: usize,
+    pub missing: usize,
+    pub failed: usize,
+}
+
+impl LoginSyncSummary {
+    /// Count what a lookup found for a volunteer, returning the sign in to record, if any.
+    ///
+    /// * `volunteer`: The volunteer
+    /// * `account`: The volunteer's workspace account, or `None` if it does not exist
+    pub fn record(
+        &mut self,
+        volunteer: &SuspensionCandidate,
+        account: Option<&WorkspaceAccount>,
+    ) -> Option<RecordWorkspaceLogin> {
+        let Some(account) = account else {
+            self.missing += 1;
+            return None;
+        };
+
+        match account.last_login_at {
+            Some(_) => self.activated += 1,
+            None => self.never_activated += 1,
+        }
+
+        Some(RecordWorkspaceLogin {
+            volunteer_id: volunteer.volunteer_id,
+            workspace_email: volunteer.workspace_email.clone(),
+            last_login_at: account.last_login_at,
+        })
+    }
+}
+
+/// Look up when volunteers last signed in to their workspace accounts and record it.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+///
+/// The summary is saved as the job's result, and the job is marked complete, or errored if any
+/// account could not be looked up.
+pub async fn login_sync_task(
+    services: &ExportServices,
+    params: LoginSyncParams,
+) -> Result<LoginSyncSummary> {
+    let result = sync_logins(services, &params).await;
+
+    let finished = match &result {
+        Ok(summary) => finish_login_sync(services, params.job_id, summary).await,
+        Err(e) => {
+            log::error!("Job {} failed to check sign ins: {e}", params.job_id);
+            services
+                .storage_layer
+                .mark_job_errored(
+                    params.job_id,
+                    NEW_JOB_VERSION,
+                    e.to_string(),
+                    &mut ExecOptsBuilder::default().build()?,
+                )
+                .await
+        }
+    };
+
+    if let Err(e) = finished {
+        log::error!("Failed to record the outcome of job {}: {e}", params.job_id);
+    }
+
+    result
+}
+
+/// Look up every volunteer's account and record their last sign in.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+async fn sync_logins(
+    services: &ExportServices,
+    params: &LoginSyncParams,
+) -> Result<LoginSyncSummary> {
+    let total = params.volunteers.len();
+    let retry_policy = RetryPolicy::default();
+    let retry_policy = &retry_policy;
+
+    report_progress(services, params.job_id, JobPhase::Provisioning, 0, total).await;
+
+    let mut stream = stream::iter(params.volunteers.iter())
+        .map(|volunteer| async move {
+            let result = retry_policy
+                .run(&format!("Looking up {}", volunteer.workspace_email), || {
+                    services.workspace.find_user(&params.principal, &volunteer.workspace_email)
+                })
+                .await;
+
+            (volunteer, result)
+        })
+        .buffer_unordered(params.concurrency.max(1));
+
+    let mut summary = LoginSyncSummary::default();
+    let mut records = Vec::with_capacity(total);
+    let mut done = 0;
+    while let Some((volunteer, result)) = stream.next().await {
+        match result {
+            Ok(account) => records.extend(summary.record(volunteer, account.as_ref())),
+            Err(e) => {
+                log::error!("Failed to look up {}: {e}", volunteer.workspace_email);
+                summary.failed += 1;
+            }
+        }
+
+        done += 1;
+        report_progress(services, params.job_id, JobPhase::Provisioning, done, total).await;
+    }
+
+    services
+        .storage_layer
+        .record_workspace_logins(records, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    Ok(summary)
+}
+
+/// Save the summary of a job that checks sign ins and mark the job complete, or errored if any
+/// account could not be looked up.
+///
+/// * `services`: The services needed to run the job
+/// * `job_id`: The ID of the job
+/// * `summary`: What the job found
+async fn finish_login_sync(
+    services: &ExportServices,
+    job_id: Uuid,
+    summary: &LoginSyncSummary,
+) -> Result<()> {
+    services
+        .storage_layer
+        .set_job_result(
+            job_id,
+            serde_json::to_value(summary)?,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    if summary.failed > 0 {
+        services
+            .storage_layer
+            .mark_job_errored(
+                job_id,
+                NEW_JOB_VERSION,
+                format!("Failed to look up {} users", summary.failed),
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await
+    } else {
+        services
+            .storage_layer
+            .mark_job_complete(job_id, NEW_JOB_VERSION, &mut ExecOptsBuilder::default().build()?)
+            .await
+    }
+}
@@ -1,144 +1,1584 @@
+pub mod aliases;
+pub mod calendar;
+pub mod cancellation;
+pub mod deprovisioning;
+pub mod digest;
+pub mod drives;
+pub mod email_retries;
+pub mod groups;
+pub mod licenses;
+pub mod logins;
+pub mod org_units;
+pub mod outbox;
+pub mod outcome;
+pub mod personalization;
 pub mod policies;
+pub mod profiles;
+pub mod programs;
+pub mod queue;
+pub mod reconciliation;
+pub mod recovery;
+pub mod reminders;
+pub mod retention;
+pub mod scheduler;
+pub mod slack;
+pub mod suspensions;
+#[cfg(test)]
+mod tests;
+pub mod transfers;
 
+use std::collections::{HashMap, HashSet};
 use std::env;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use anyhow::Result;
-use policies::{EmailPolicy, PasswordPolicy};
+use aliases::{add_aliases, assign_aliases};
+use anyhow::{anyhow, bail, Result};
+use calendar::{
+    invite_to_onboarding_session, schedule_onboarding_session, OnboardingSessionSettings,
+};
+use cancellation::{JobCancellation, CANCELLED_REASON};
+use drives::{add_to_shared_drive, ensure_shared_drive, SharedDriveSettings};
+use futures::{stream, StreamExt};
+use groups::{add_to_groups, ensure_groups};
+use licenses::{assign_licenses, check_license_seats};
+use outbox::{claimed_until, complete_outbox_email, OutboxClaim};
+use outcome::{ExportOutcome, ExportStepFailures, VolunteerExportStatus};
+use personalization::{
+    personalize_accounts, Personalization, PersonalizationSettings, SignatureDetails,
+};
+use policies::{
+    EmailBlocklist, EmailPolicy, PasswordConfig, PasswordDelivery, PasswordPolicy, RollbackPolicy,
+    TwoStepVerificationPolicy, WorkspaceLicense,
+};
+use programs::{ensure_program_schema, ProgramSettings};
+use queue::PermanentJobError;
+use recovery::{check_recovery_email, find_undeliverable_domains, NeedsAttention};
+use serde::Serialize;
+use slack::{invite_to_slack, SlackInviteSettings};
 use uuid::Uuid;
 
 use super::ExportServices;
-use crate::services::mail::{OnboardingEmailParams, OnboardingEmailParamsBuilder};
-use crate::services::storage::entities::VolunteerDetails;
-use crate::services::storage::volunteers::InsertVolunteerExportedToWorkspace;
+use crate::app::api::v1::data_exports::requests::ExportUsersToWorkspaceRequest;
+use crate::app::api::v1::jobs::events::{record_job_event, ProgressEvent};
+use crate::services::mail::suppression::suppression_reason;
+use crate::services::mail::{
+    localized_template, localized_template_name, CustomTemplate, EmailAttachment,
+    OnboardingEmailParams, OnboardingEmailParamsBuilder, SentEmail, DEFAULT_LOCALE,
+    ONBOARDING_TEMPLATE,
+};
+use crate::services::settings::{
+    DefaultOrgUnit, MailRecipientOverride, SettingsService, SmsRecipientOverride,
+};
+use crate::services::sms::{TemporaryPasswordSmsParams, TemporaryPasswordSmsParamsBuilder};
+use crate::services::storage::emails::RecordEmailSend;
+use crate::services::storage::entities::{EmailTemplate, Job, VolunteerDetails};
+use crate::services::storage::exports::CreateExportCheckpoint;
+use crate::services::storage::jobs::UpdateJobProgress;
+use crate::services::storage::outbox::CreateOutboxEmail;
+use crate::services::storage::types::{
+    EmailSendStatus, EmailSuppressionReason, JobPhase, JobStatus, WorkspaceExportStatus,
+};
+use crate::services::storage::volunteers::{InsertVolunteerExportedToWorkspace, VolunteerCursor};
 use crate::services::storage::ExecOptsBuilder;
-use crate::services::workspace::entities::CreateWorkspaceVolunteer;
+use crate::services::workspace::calendar::CalendarEvent;
+use crate::services::workspace::drive::DriveRole;
+use crate::services::workspace::entities::{
+    recovery_phone, CreateVolunteerOutcome, CreateWorkspaceVolunteer, ProgramMetadata,
+};
+use crate::services::workspace::retry::{is_conflict, is_retryable, RetryPolicy};
+
+/// The default number of users to create in Google Workspace at once.
+pub const DEFAULT_EXPORT_CONCURRENCY: usize = 8;
+
+/// The default number of users to export in each chunk.
+pub const DEFAULT_EXPORT_CHUNK_SIZE: usize = 100;
+
+/// The number of users to create in Google Workspace with each batch request.
+pub const WORKSPACE_BATCH_SIZE: usize = 50;
+
+/// The number of volunteers read from the database at a time when exporting a whole cohort.
+pub const EXPORT_PAGE_SIZE: usize = 1_000;
+
+/// The org unit users are created in if an export does not specify one and no default has been
+/// saved in the settings.
+pub const DEFAULT_ORG_UNIT: &str = "/Programs/PantheonUsers";
+
+/// The environment variable holding the path of the welcome packet attached to onboarding emails.
+pub const WELCOME_PACKET_PATH_ENV_VAR: &str = "WELCOME_PACKET_PATH";
 
 pub struct ExportParams {
     pub job_id: Uuid,
+    pub job_version: i32,
+    pub project_cycle_id: Uuid,
     pub principal: String,
+    pub org_unit: String,
     pub email_policy: EmailPolicy,
     pub password_policy: PasswordPolicy,
-    pub volunteers: Vec<VolunteerDetails>,
+    pub retry_policy: RetryPolicy,
+    pub rollback_policy: RollbackPolicy,
+    pub concurrency: usize,
+    pub chunk_size: usize,
+    pub dry_run: bool,
+    pub verify_recovery_email_domains: bool,
+    pub locale: String,
+    pub groups: Vec<String>,
+    pub license: Option<WorkspaceLicense>,
+    pub onboarding_session: Option<OnboardingSessionSettings>,
+    pub personalization: Option<Personalization>,
+    pub program: Option<ProgramSettings>,
+    pub shared_drive: Option<SharedDriveSettings>,
+    pub slack: Option<SlackInviteSettings>,
+    pub two_step_verification: Option<TwoStepVerificationPolicy>,
+    pub volunteers: ExportVolunteers,
+}
+
+impl ExportParams {
+    /// Build the parameters of an export from the request that started it.
+    ///
+    /// * `job_id`: The ID of the export job
+    /// * `job_version`: The version of the export job when it was claimed, which it is finished at
+    /// * `project_cycle_id`: The ID of the project cycle the volunteers are exported from
+    /// * `principal`: The email of the user requesting the export
+    /// * `org_unit`: The org unit to create users in, which the request may leave to the default
+    /// * `request`: The request
+    ///
+    /// Fails if the email blocklist or password config in the environment are invalid, or if the
+    /// requested personalization cannot be loaded.
+    pub fn from_request(
+        job_id: Uuid,
+        job_version: i32,
+        project_cycle_id: Uuid,
+        principal: String,
+        org_unit: String,
+        request: &ExportUsersToWorkspaceRequest,
+    ) -> Result<Self> {
+        let volunteers = if request.export_cohort {
+            ExportVolunteers::Cohort(VolunteerCursor::new(project_cycle_id, EXPORT_PAGE_SIZE))
+        } else {
+            ExportVolunteers::Listed(request.volunteers.clone())
+        };
+
+        Ok(Self {
+            job_id,
+            job_version,
+            project_cycle_id,
+            principal,
+            org_unit,
+            email_policy: EmailPolicy {
+                blocklist: EmailBlocklist::from_env()?,
+                ..EmailPolicy::from(request)
+            },
+            password_policy: PasswordPolicy {
+                config: PasswordConfig::from_env()?,
+                ..PasswordPolicy::from(request)
+            },
+            retry_policy: RetryPolicy::from(request),
+            rollback_policy: request.rollback_policy,
+            concurrency: request.export_concurrency.unwrap_or(DEFAULT_EXPORT_CONCURRENCY),
+            chunk_size: request.export_chunk_size.unwrap_or(DEFAULT_EXPORT_CHUNK_SIZE),
+            dry_run: request.dry_run,
+            verify_recovery_email_domains: request.verify_recovery_email_domains,
+            locale: request.locale.clone().unwrap_or_else(|| DEFAULT_LOCALE.to_owned()),
+            groups: request.groups.clone(),
+            license: request.license.clone(),
+            onboarding_session: request.onboarding_session.clone(),
+            personalization: request
+                .personalization
+                .as_ref()
+                .map(PersonalizationSettings::load)
+                .transpose()?,
+            program: request.program.clone(),
+            shared_drive: request.shared_drive.clone(),
+            slack: request.slack.clone(),
+            two_step_verification: request.two_step_verification.clone(),
+            volunteers,
+        })
+    }
+}
+
+/// The volunteers an export creates accounts for.
+pub enum ExportVolunteers {
+    /// Volunteers listed in the request, which are exported as a single batch
+    Listed(Vec<VolunteerDetails>),
+    /// Every volunteer in a project cycle, which are read from the database and exported a page at
+    /// a time
+    Cohort(VolunteerCursor),
+}
+
+impl Default for ExportVolunteers {
+    fn default() -> Self {
+        Self::Listed(Vec::new())
+    }
+}
+
+impl ExportVolunteers {
+    /// The next batch of volunteers to export, or `None` once every volunteer has been returned.
+    ///
+    /// * `services`: The services needed to run the export
+    async fn next_batch(
+        &mut self,
+        services: &ExportServices,
+    ) -> Result<Option<Vec<VolunteerDetails>>> {
+        match self {
+            Self::Listed(volunteers) if volunteers.is_empty() => Ok(None),
+            Self::Listed(volunteers) => Ok(Some(std::mem::take(volunteers))),
+            Self::Cohort(cursor) => cursor.next_page(services.storage_layer.as_ref()).await,
+        }
+    }
+}
+
+/// The workspace account a volunteer will be (or was) issued by an export.
+///
+/// * `volunteer_id`: The ID of the volunteer
+/// * `first_name`: The volunteer's first name
+/// * `last_name`: The volunteer's last name
+/// * `workspace_email`: The generated workspace email
+/// * `org_unit`: The org unit the account is placed in
+/// * `aliases`: The extra addresses the account will be given
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedExport {
+    pub volunteer_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub workspace_email: String,
+    pub org_unit: String,
+    pub aliases: Vec<String>,
+}
+
+/// A volunteer that an export left out because they already have a workspace account.
+///
+/// * `volunteer_id`: The ID of the volunteer
+/// * `workspace_email`: The workspace email the volunteer already has (or would have been issued)
+/// * `reason`: Why the volunteer was skipped
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedVolunteer {
+    pub volunteer_id: Uuid,
+    pub workspace_email: String,
+    pub reason: String,
+}
+
+/// A workspace email that was passed over for a volunteer because it is on the blocklist.
+///
+/// * `volunteer_id`: The ID of the volunteer
+/// * `workspace_email`: The email that was passed over
+/// * `reason`: Why the email is blocked
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedEmail {
+    pub volunteer_id: Uuid,
+    pub workspace_email: String,
+    pub reason: String,
+}
+
+/// What an export will do (or did).
+///
+/// * `volunteers`: The volunteers to export
+/// * `skipped`: The volunteers left out because they were already exported
+/// * `blocked`: The emails passed over because they are on the blocklist
+/// * `needs_attention`: The volunteers left out because their recovery emails need to be fixed
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPlan {
+    pub volunteers: Vec<PlannedExport>,
+    pub skipped: Vec<SkippedVolunteer>,
+    pub blocked: Vec<BlockedEmail>,
+    pub needs_attention: Vec<NeedsAttention>,
+}
+
+impl ExportPlan {
+    /// Add the plan for a batch of volunteers.
+    ///
+    /// * `volunteers`: The volunteers in the batch to export
+    /// * `skipped`: The volunteers in the batch left out because they were already exported
+    /// * `blocked`: The emails passed over for volunteers in the batch
+    /// * `needs_attention`: The volunteers in the batch left out because their recovery emails need
+    ///   to be fixed
+    fn extend(
+        &mut self,
+        volunteers: Vec<PlannedExport>,
+        skipped: Vec<SkippedVolunteer>,
+        blocked: Vec<BlockedEmail>,
+        needs_attention: Vec<NeedsAttention>,
+    ) {
+        self.volunteers.extend(volunteers);
+        self.skipped.extend(skipped);
+        self.blocked.extend(blocked);
+        self.needs_attention.extend(needs_attention);
+    }
+}
+
+/// Settings that control how volunteers are created in Google Workspace.
+///
+/// * `principal`: The email of the user requesting the export
+/// * `retry_policy`: How to retry transient failures for each user
+/// * `rollback_policy`: What to do with created accounts if a later step fails
+/// * `concurrency`: The maximum number of users to create at once
+/// * `chunk_size`: The number of users to export before checkpointing
+/// * `groups`: The email addresses of the Google Groups to add every exported user to
+/// * `license`: The Google Workspace edition to license every exported user for, if any
+/// * `shared_drive`: The ID of the Shared Drive to add every exported user to, and the role they
+///   are given, if any
+/// * `onboarding_session`: The ID of the calendar event to invite every exported user to, if any
+/// * `slack`: The Slack channels to invite every exported user to, if any
+/// * `personalization`: The signature and profile photo to give every exported user, if any
+/// * `cancellation`: Tracks whether the job was cancelled, if the export can be cancelled
+struct ExportSettings<'a> {
+    pub principal: &'a str,
+    pub retry_policy: &'a RetryPolicy,
+    pub rollback_policy: RollbackPolicy,
+    pub concurrency: usize,
+    pub chunk_size: usize,
+    pub groups: &'a [String],
+    pub license: Option<&'a WorkspaceLicense>,
+    pub shared_drive: Option<(&'a str, DriveRole)>,
+    pub onboarding_session: Option<&'a str>,
+    pub slack: Option<&'a SlackInviteSettings>,
+    pub personalization: Option<&'a Personalization>,
+    pub cancellation: Option<&'a JobCancellation>,
+}
+
+impl ExportSettings<'_> {
+    /// Whether the job running the export has been cancelled.
+    ///
+    /// * `services`: The services needed to run the export
+    async fn is_cancelled(&self, services: &ExportServices) -> bool {
+        match self.cancellation {
+            Some(cancellation) => cancellation.is_cancelled(services).await,
+            None => false,
+        }
+    }
+}
+
+impl<'a> From<&'a ExportParams> for ExportSettings<'a> {
+    fn from(params: &'a ExportParams) -> Self {
+        Self {
+            principal: &params.principal,
+            retry_policy: &params.retry_policy,
+            rollback_policy: params.rollback_policy,
+            concurrency: params.concurrency,
+            chunk_size: params.chunk_size,
+            groups: &params.groups,
+            license: params.license.as_ref(),
+            shared_drive: None,
+            onboarding_session: None,
+            slack: params.slack.as_ref(),
+            personalization: params.personalization.as_ref(),
+            cancellation: None,
+        }
+    }
+}
+
+/// The recipients every onboarding email and temporary password text are sent to instead of the
+/// volunteers', for testing exports without contacting volunteers.
+///
+/// * `email`: The address onboarding emails are sent to, if they are redirected
+/// * `phone`: The phone number temporary passwords are texted to, if they are redirected
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecipientOverrides {
+    pub email: Option<String>,
+    pub phone: Option<String>,
+}
+
+impl RecipientOverrides {
+    /// Read the overriding recipients from the organization's settings.
+    ///
+    /// * `services`: The services needed to run the export
+    pub async fn fetch(services: &ExportServices) -> Result<Self> {
+        let settings = SettingsService::new(services.storage_layer.clone());
+        let overrides = Self {
+            email: settings.get::<MailRecipientOverride>().await?,
+            phone: settings.get::<SmsRecipientOverride>().await?,
+        };
+
+        if let Some(email) = overrides.email.as_ref() {
+            log::info!("Sending every onboarding email to {email} instead of the volunteers");
+        }
+        if let Some(phone) = overrides.phone.as_ref() {
+            log::info!("Texting every temporary password to {phone} instead of the volunteers");
+        }
+        Ok(overrides)
+    }
 }
 
 struct ProcessedVolunteers {
     pub export_data: Vec<CreateWorkspaceVolunteer>,
     pub pantheon_data: Vec<InsertVolunteerExportedToWorkspace>,
     pub onboarding_email_data: Vec<OnboardingEmailParams>,
+    pub password_sms_data: Vec<Option<TemporaryPasswordSmsParams>>,
 }
 
-fn process_volunteers(params: &ExportParams) -> Result<ProcessedVolunteers> {
-    let mut pantheon_data =
-        Vec::<InsertVolunteerExportedToWorkspace>::with_capacity(params.volunteers.len());
-
-    let mut export_data = Vec::<CreateWorkspaceVolunteer>::with_capacity(params.volunteers.len());
-
-    let mut onboarding_email_data =
-        Vec::<OnboardingEmailParams>::with_capacity(params.volunteers.len());
-
-    for v in &params.volunteers {
-        let primary_email = params.email_policy.build_volunteer_email(&v.first_name, &v.last_name);
-        let temporary_password = params.password_policy.generate_password();
+impl ProcessedVolunteers {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            export_data: Vec::with_capacity(capacity),
+            pantheon_data: Vec::with_capacity(capacity),
+            onboarding_email_data: Vec::with_capacity(capacity),
+            password_sms_data: Vec::with_capacity(capacity),
+        }
+    }
 
+    /// Add a volunteer to be exported.
+    ///
+    /// * `job_id`: The ID of the export job
+    /// * `v`: The volunteer
+    /// * `primary_email`: The workspace email the volunteer will be issued
+    /// * `aliases`: The extra addresses the volunteer's account will be given
+    /// * `temporary_password`: The volunteer's temporary password
+    /// * `org_unit`: The org unit the volunteer will be exported to
+    /// * `password_policy`: The policy the temporary password was generated under
+    /// * `locale`: The locale to send the onboarding email in
+    /// * `program`: The program metadata to store on the volunteer's account, if any
+    fn push(
+        &mut self,
+        job_id: Uuid,
+        v: &VolunteerDetails,
+        primary_email: String,
+        aliases: Vec<String>,
+        temporary_password: String,
+        org_unit: String,
+        password_policy: &PasswordPolicy,
+        locale: &str,
+        program: Option<ProgramMetadata>,
+    ) -> Result<()> {
         let workspace_user = CreateWorkspaceVolunteer {
             primary_email: primary_email.clone(),
             first_name: v.first_name.clone(),
             last_name: v.last_name.clone(),
-            password: temporary_password.clone(),
+            password: temporary_password,
             recovery_email: v.email.clone(),
+            recovery_phone: v.phone.as_deref().and_then(|phone| recovery_phone(phone, &v.country)),
+            org_unit: org_unit.clone(),
+            change_password_at_next_login: password_policy.change_password_at_next_login,
+            aliases,
+            program,
         };
 
-        // if let Some(override) = env::var("MAIL_RECIPIENT_OVERRIDE")
+        let phone = match (password_policy.delivery, v.phone.as_deref()) {
+            (PasswordDelivery::Sms, Some(phone)) if !phone.trim().is_empty() => Some(phone),
+            (PasswordDelivery::Sms, _) => {
+                log::warn!(
+                    "Volunteer {} has no phone number, emailing their temporary password instead",
+                    v.volunteer_id
+                );
+                None
+            }
+            (PasswordDelivery::Email, _) => None,
+        };
 
-        onboarding_email_data.push(
-            OnboardingEmailParamsBuilder::default()
-                .first_name(workspace_user.first_name.clone())
-                .last_name(workspace_user.last_name.clone())
-                .email(
-                    env::var("MAIL_RECIPIENT_OVERRIDE")
-                        .unwrap_or_else(|_| workspace_user.recovery_email.clone()),
-                )
-                // .email(workspace_user.recovery_email.clone())
-                // .email("anish@developforgood.org")
-                .workspace_email(workspace_user.primary_email.clone())
-                .temporary_password(workspace_user.password.clone())
-                .build()?,
-        );
+        let password_sms = match phone {
+            Some(phone) => Some(
+                TemporaryPasswordSmsParamsBuilder::default()
+                    .first_name(workspace_user.first_name.clone())
+                    .phone(phone)
+                    .workspace_email(workspace_user.primary_email.clone())
+                    .temporary_password(workspace_user.password.clone())
+                    .build()?,
+            ),
+            None => None,
+        };
+
+        let mut onboarding_email = OnboardingEmailParamsBuilder::default();
+        onboarding_email
+            .first_name(workspace_user.first_name.clone())
+            .last_name(workspace_user.last_name.clone())
+            .email(workspace_user.recovery_email.clone())
+            .workspace_email(workspace_user.primary_email.clone())
+            .locale(locale)
+            .job_id(job_id)
+            .volunteer_id(v.volunteer_id);
+        if password_sms.is_none() {
+            onboarding_email.temporary_password(workspace_user.password.clone());
+        }
+
+        self.onboarding_email_data.push(onboarding_email.build()?);
+        self.password_sms_data.push(password_sms);
 
-        export_data.push(workspace_user);
+        self.export_data.push(workspace_user);
 
-        pantheon_data.push(InsertVolunteerExportedToWorkspace {
+        self.pantheon_data.push(InsertVolunteerExportedToWorkspace {
             volunteer_id: v.volunteer_id,
-            job_id: params.job_id,
+            job_id,
             workspace_email: primary_email,
-            org_unit: "/Programs/PantheonUsers".to_owned(),
+            org_unit,
         });
+
+        Ok(())
+    }
+
+    /// Redirect every onboarding email and temporary password text to the overriding recipients,
+    /// if any are set.
+    ///
+    /// * `overrides`: The recipients to send to instead of the volunteers
+    fn override_recipients(&mut self, overrides: &RecipientOverrides) {
+        if let Some(email) = overrides.email.as_ref() {
+            for onboarding_email in self.onboarding_email_data.iter_mut() {
+                onboarding_email.email = email.clone();
+            }
+        }
+        if let Some(phone) = overrides.phone.as_ref() {
+            for password_sms in self.password_sms_data.iter_mut().flatten() {
+                password_sms.phone = phone.clone();
+            }
+        }
+    }
+
+    /// Split the volunteers into chunks of at most `chunk_size` volunteers, preserving their order.
+    fn into_chunks(mut self, chunk_size: usize) -> Vec<ProcessedVolunteers> {
+        let chunk_size = chunk_size.max(1);
+        let mut chunks = Vec::with_capacity(self.export_data.len().div_ceil(chunk_size));
+        while !self.export_data.is_empty() {
+            let n = chunk_size.min(self.export_data.len());
+            chunks.push(ProcessedVolunteers {
+                export_data: self.export_data.drain(..n).collect(),
+                pantheon_data: self.pantheon_data.drain(..n).collect(),
+                onboarding_email_data: self.onboarding_email_data.drain(..n).collect(),
+                password_sms_data: self.password_sms_data.drain(..n).collect(),
+            });
+        }
+        chunks
+    }
+}
+
+/// Process volunteers for export.
+///
+/// * `params`: The export parameters
+/// * `volunteers`: The volunteers to process
+/// * `emails`: The workspace email assigned to each volunteer by `assign_workspace_emails`
+/// * `aliases`: The aliases assigned to each volunteer by `assign_aliases`
+/// * `undeliverable_domains`: The recovery email domains that cannot receive mail, if they were
+///   looked up
+///
+/// Returns the processed volunteers, along with the volunteers that were skipped because they
+/// already have a workspace account and the volunteers that need attention because their recovery
+/// email is invalid or cannot receive mail. Volunteers that need attention are not exported, since
+/// their onboarding emails would never arrive.
+fn process_volunteers(
+    params: &ExportParams,
+    volunteers: &[VolunteerDetails],
+    emails: Vec<AssignedEmail>,
+    aliases: Vec<Vec<String>>,
+    undeliverable_domains: &HashSet<String>,
+) -> Result<(ProcessedVolunteers, Vec<SkippedVolunteer>, Vec<NeedsAttention>)> {
+    let mut processed = ProcessedVolunteers::with_capacity(volunteers.len());
+    let mut skipped = Vec::<SkippedVolunteer>::new();
+    let mut needs_attention = Vec::<NeedsAttention>::new();
+
+    for ((v, email), aliases) in volunteers.iter().zip(emails).zip(aliases) {
+        match email {
+            AssignedEmail::Available(primary_email) => {
+                if let Some(reason) = check_recovery_email(&v.email, undeliverable_domains) {
+                    log::warn!("Volunteer {} needs attention: {reason}", v.volunteer_id);
+                    needs_attention.push(NeedsAttention {
+                        volunteer_id: v.volunteer_id,
+                        workspace_email: primary_email,
+                        recovery_email: v.email.clone(),
+                        reason,
+                    });
+                    continue;
+                }
+
+                let temporary_password = params.password_policy.generate_password();
+
+                processed.push(
+                    params.job_id,
+                    v,
+                    primary_email,
+                    aliases,
+                    temporary_password,
+                    params.org_unit.clone(),
+                    &params.password_policy,
+                    &params.locale,
+                    params.program.as_ref().map(|p| p.metadata(v)),
+                )?;
+            }
+            AssignedEmail::Existing(workspace_email) => {
+                log::info!(
+                    "Skipping volunteer {}: {workspace_email} already exists",
+                    v.volunteer_id
+                );
+                skipped.push(SkippedVolunteer {
+                    volunteer_id: v.volunteer_id,
+                    workspace_email,
+                    reason: "A workspace user with this email already exists".to_owned(),
+                });
+            }
+        }
     }
 
-    Ok(ProcessedVolunteers { export_data, pantheon_data, onboarding_email_data })
+    Ok((processed, skipped, needs_attention))
+}
+
+/// Build the export plan from processed volunteers.
+///
+/// * `processed`: The processed volunteers
+fn plan_export(processed: &ProcessedVolunteers) -> Vec<PlannedExport> {
+    processed
+        .export_data
+        .iter()
+        .zip(processed.pantheon_data.iter())
+        .map(|(v, p)| PlannedExport {
+            volunteer_id: p.volunteer_id,
+            first_name: v.first_name.clone(),
+            last_name: v.last_name.clone(),
+            workspace_email: p.workspace_email.clone(),
+            org_unit: p.org_unit.clone(),
+            aliases: v.aliases.clone(),
+        })
+        .collect()
 }
 
+/// Filter out volunteers that Pantheon has recorded as exported by any job.
+///
+/// * `services`: The services needed to run the export
+/// * `volunteers`: The volunteers to export
+///
+/// Returns the volunteers that have not been exported, along with the ones that were skipped.
+async fn find_already_exported(
+    services: &ExportServices,
+    volunteers: Vec<VolunteerDetails>,
+) -> Result<(Vec<VolunteerDetails>, Vec<SkippedVolunteer>)> {
+    let recorded = services
+        .storage_layer
+        .fetch_exported_volunteer_details_by_ids(
+            volunteers.iter().map(|v| v.volunteer_id).collect(),
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?
+        .into_iter()
+        .map(|v| (v.volunteer_id, v.workspace_email))
+        .collect::<HashMap<Uuid, String>>();
+
+    let mut skipped = Vec::<SkippedVolunteer>::new();
+    let mut remaining = Vec::<VolunteerDetails>::with_capacity(volunteers.len());
+
+    for v in volunteers {
+        match recorded.get(&v.volunteer_id) {
+            Some(workspace_email) => {
+                log::info!("Skipping volunteer {}: already exported", v.volunteer_id);
+                skipped.push(SkippedVolunteer {
+                    volunteer_id: v.volunteer_id,
+                    workspace_email: workspace_email.clone(),
+                    reason: "Already exported to workspace".to_owned(),
+                });
+            }
+            None => remaining.push(v),
+        }
+    }
+
+    Ok((remaining, skipped))
+}
+
+/// The workspace email assigned to a volunteer.
+enum AssignedEmail {
+    /// The email is free and will be issued to the volunteer
+    Available(String),
+    /// The email already belongs to the volunteer in Google Workspace
+    Existing(String),
+}
+
+/// Assign every volunteer a workspace email that is not already taken.
+///
+/// * `services`: The services needed to run the export
+/// * `params`: The export parameters
+/// * `volunteers`: The volunteers to assign emails to
+/// * `planned_emails`: The emails planned for earlier batches of the export that have not been
+///   issued yet (e.g. in a dry run)
+///
+/// Each volunteer is assigned the first of their candidate emails (see
+/// `EmailPolicy::candidate_emails`) that is not on the blocklist and has not been assigned to an
+/// earlier volunteer in the export or issued by a previous export. Blocked candidates that are
+/// passed over are returned alongside the assigned emails. Unless this is a dry run, the assigned
+/// emails are then looked up in Google Workspace. An email that belongs to a Workspace user whose
+/// recovery email is the volunteer's email is the volunteer's own account, so it is kept as
+/// `Existing`. Any other Workspace user is a collision, and the volunteer moves on to their next
+/// candidate.
+async fn assign_workspace_emails(
+    services: &ExportServices,
+    params: &ExportParams,
+    volunteers: &[VolunteerDetails],
+    planned_emails: &HashSet<String>,
+) -> Result<(Vec<AssignedEmail>, Vec<BlockedEmail>)> {
+    let candidates = volunteers
+        .iter()
+        .map(|v| params.email_policy.candidate_emails(&v.first_name, &v.last_name))
+        .collect::<Result<Vec<Vec<String>>>>()?;
+
+    let mut taken = services
+        .storage_layer
+        .fetch_taken_workspace_emails(
+            candidates.iter().flatten().cloned().collect(),
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?
+        .into_iter()
+        .collect::<HashSet<String>>();
+    taken.extend(
+        candidates.iter().flatten().filter(|email| planned_emails.contains(*email)).cloned(),
+    );
+
+    let mut next = vec![0; volunteers.len()];
+    let mut assigned = volunteers.iter().map(|_| None).collect::<Vec<Option<AssignedEmail>>>();
+    let mut unresolved = (0..volunteers.len()).collect::<Vec<usize>>();
+    let mut blocked = Vec::<BlockedEmail>::new();
+
+    while !unresolved.is_empty() {
+        let mut picked = Vec::<(usize, String)>::with_capacity(unresolved.len());
+        for i in unresolved.drain(..) {
+            while let Some(candidate) = candidates[i].get(next[i]) {
+                if let Some(reason) = params.email_policy.blocklist.check(candidate) {
+                    log::info!(
+                        "Passing over {candidate} for {}: {reason}",
+                        volunteers[i].volunteer_id
+                    );
+                    blocked.push(BlockedEmail {
+                        volunteer_id: volunteers[i].volunteer_id,
+                        workspace_email: candidate.clone(),
+                        reason,
+                    });
+                } else if !taken.contains(candidate) {
+                    break;
+                }
+                next[i] += 1;
+            }
+            let Some(email) = candidates[i].get(next[i]) else {
+                let v = &volunteers[i];
+                bail!(
+                    "Could not find an available workspace email for {} {}",
+                    v.first_name,
+                    v.last_name
+                );
+            };
+            taken.insert(email.clone());
+            picked.push((i, email.clone()));
+        }
+
+        if params.dry_run {
+            for (i, email) in picked {
+                assigned[i] = Some(AssignedEmail::Available(email));
+            }
+            break;
+        }
+
+        let found = stream::iter(picked.iter())
+            .map(|(_, email)| async move {
+                let label = format!("Looking up {email} in workspace");
+                params
+                    .retry_policy
+                    .run(&label, || services.workspace.find_user(&params.principal, email))
+                    .await
+                    .unwrap_or_else(|e| {
+                        log::warn!("Could not look up {email} in workspace: {e}");
+                        None
+                    })
+            })
+            .buffered(params.concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await;
+
+        for ((i, email), account) in picked.into_iter().zip(found) {
+            match account {
+                None => assigned[i] = Some(AssignedEmail::Available(email)),
+                Some(account)
+                    if account
+                        .recovery_email
+                        .as_deref()
+                        .is_some_and(|r| r.eq_ignore_ascii_case(&volunteers[i].email)) =>
+                {
+                    assigned[i] = Some(AssignedEmail::Existing(email));
+                }
+                Some(_) => {
+                    log::info!("{email} is taken in workspace, trying another email");
+                    unresolved.push(i);
+                }
+            }
+        }
+    }
+
+    Ok((assigned.into_iter().flatten().collect(), blocked))
+}
+
+/// Check that an export plan is sound before anything is created.
+///
+/// * `params`: The export parameters
+/// * `plan`: The export plan
+fn validate_plan(params: &ExportParams, plan: &[PlannedExport]) -> Result<()> {
+    params.password_policy.validate()?;
+    groups::validate_groups(&params.groups)?;
+    if let Some(license) = &params.license {
+        license.validate()?;
+    }
+
+    let mut seen = HashSet::<&str>::with_capacity(plan.len());
+    for p in plan {
+        if p.workspace_email.starts_with('@') {
+            bail!("Could not generate a workspace email for {} {}", p.first_name, p.last_name);
+        }
+        if !seen.insert(p.workspace_email.as_str()) {
+            bail!("Generated workspace email {} more than once", p.workspace_email);
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that an org unit path is well formed.
+///
+/// * `org_unit`: The full path of the org unit (e.g. `/Programs/PantheonUsers`)
+pub fn validate_org_unit_path(org_unit: &str) -> Result<()> {
+    if !org_unit.starts_with('/') {
+        bail!("Org unit {org_unit} must be a full path starting with '/'");
+    }
+    if org_unit.len() > 1 && (org_unit.ends_with('/') || org_unit.contains("//")) {
+        bail!("Org unit {org_unit} is not a valid path");
+    }
+    Ok(())
+}
+
+/// The org unit users are created in when a request does not name one: the one saved in the
+/// organization's settings, or `DEFAULT_ORG_UNIT`.
+///
+/// * `services`: The services needed to run the export
+pub async fn default_org_unit(services: &ExportServices) -> Result<String> {
+    let settings = SettingsService::new(services.storage_layer.clone());
+    let org_unit = settings.get::<DefaultOrgUnit>().await?;
+    Ok(org_unit.unwrap_or_else(|| DEFAULT_ORG_UNIT.to_owned()))
+}
+
+/// Create users in Google Workspace in batches.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+/// * `settings`: Settings for the export
+/// * `position`: Where these users sit within the export
+/// * `export_data`: The users to create, keyed by volunteer ID
+///
+/// Users are sent to Google `WORKSPACE_BATCH_SIZE` at a time. Every user in a batch counts against
+/// the API quota, so batches are sent one after another. Users whose creation failed transiently,
+/// or whose batch could not be sent at all, are then retried one at a time, up to `concurrency`
+/// at once.
+///
+/// Users whose primary email was already taken are also retried one at a time, so that an account
+/// left behind by an earlier attempt counts as exported rather than failed.
+///
+/// Returns whether each user was created (or already existed), or why they were not, in the same
+/// order as `export_data`. The outcome for each user is checkpointed as soon as it is known, and
+/// the job's progress is updated as each user finishes. A failure for one user does not stop the
+/// others from being created. Once the job is cancelled, users that are not created yet are left
+/// alone and fail with `CANCELLED_REASON`, without being checkpointed.
 async fn export_volunteers_to_workspace(
     services: &ExportServices,
-    principal: &str,
-    export_data: Vec<CreateWorkspaceVolunteer>,
-) -> usize {
-    let mut successfully_exported = 0usize;
-    for user in export_data {
-        let name = format!("{} {}", &user.first_name, &user.last_name);
-        match services.workspace.create_volunteer(principal, user).await {
+    job_id: Uuid,
+    settings: &ExportSettings<'_>,
+    position: ChunkPosition,
+    export_data: Vec<(Uuid, CreateWorkspaceVolunteer)>,
+) -> Vec<Result<CreateVolunteerOutcome, String>> {
+    let processed = AtomicUsize::new(0);
+    let processed = &processed;
+
+    position.report(services, job_id, JobPhase::Provisioning, 0).await;
+
+    let mut results = Vec::with_capacity(export_data.len());
+    for batch in export_data.chunks(WORKSPACE_BATCH_SIZE) {
+        if settings.is_cancelled(services).await {
+            results.extend(batch.iter().map(|_| Err(CANCELLED_REASON.to_owned())));
+            continue;
+        }
+
+        let users = batch.iter().map(|(_, user)| user.clone()).collect::<Vec<_>>();
+
+        // `None` means the user still has to be created on their own
+        let created = match services
+            .workspace
+            .batch_create_volunteers(settings.principal, users)
+            .await
+        {
+            Ok(created) if created.len() == batch.len() => created
+                .into_iter()
+                .map(|result| match result {
+                    Err(e) if is_retryable(&e) || is_conflict(&e) => None,
+                    result => Some(result),
+                })
+                .collect::<Vec<_>>(),
             Ok(_) => {
-                log::info!("Successfully exported user {} to workspace", name);
-                successfully_exported += 1;
+                log::warn!("Workspace returned the wrong number of results for a batch of users");
+                batch.iter().map(|_| None).collect()
             }
             Err(e) => {
-                log::error!("Failed to export user to workspace: {}", e);
-                break;
+                log::warn!("Failed to create a batch of {} users in workspace: {e}", batch.len());
+                batch.iter().map(|_| None).collect()
             }
+        };
+
+        let exported = stream::iter(batch.iter().zip(created))
+            .map(|((volunteer_id, user), created)| async move {
+                let name = format!("{} {}", &user.first_name, &user.last_name);
+                let result = match created {
+                    Some(result) => result,
+                    None => {
+                        if settings.is_cancelled(services).await {
+                            return Err(CANCELLED_REASON.to_owned());
+                        }
+                        settings
+                            .retry_policy
+                            .run(&format!("Exporting {name} to workspace"), || {
+                                services
+                                    .workspace
+                                    .create_volunteer(settings.principal, user.clone())
+                            })
+                            .await
+                    }
+                };
+
+                let (exported, status, error) = match result {
+                    Ok(CreateVolunteerOutcome::Created) => {
+                        log::info!("Successfully exported user {} to workspace", name);
+                        (Ok(CreateVolunteerOutcome::Created), WorkspaceExportStatus::Created, None)
+                    }
+                    Ok(CreateVolunteerOutcome::AlreadyExists) => {
+                        log::info!(
+                            "{} already exists in workspace as {}, treating it as exported",
+                            name,
+                            &user.primary_email
+                        );
+                        let outcome = CreateVolunteerOutcome::AlreadyExists;
+                        (Ok(outcome), WorkspaceExportStatus::Created, None)
+                    }
+                    Err(e) => {
+                        log::error!("Failed to export user {} to workspace: {}", name, e);
+                        (Err(e.to_string()), WorkspaceExportStatus::Failed, Some(e.to_string()))
+                    }
+                };
+
+                let event = ProgressEvent::VolunteerExported {
+                    volunteer_id: *volunteer_id,
+                    status,
+                    error: error.clone(),
+                };
+                if let Err(e) =
+                    checkpoint_volunteer(services, job_id, *volunteer_id, status, error).await
+                {
+                    log::error!("Failed to checkpoint export of user {}: {}", name, e);
+                }
+                record_job_event(services.storage_layer.as_ref(), job_id, event).await;
+
+                let done = processed.fetch_add(1, Ordering::Relaxed) + 1;
+                position.report(services, job_id, JobPhase::Provisioning, done).await;
+
+                exported
+            })
+            .buffered(settings.concurrency.max(1))
+            .collect::<Vec<Result<CreateVolunteerOutcome, String>>>()
+            .await;
+
+        results.extend(exported);
+    }
+
+    results
+}
+
+/// Where a chunk of volunteers sits within an export, so that progress can be reported for the
+/// whole export rather than for each chunk.
+///
+/// * `offset`: The number of volunteers in earlier chunks
+/// * `total`: The number of volunteers in the whole export
+#[derive(Debug, Clone, Copy)]
+struct ChunkPosition {
+    offset: usize,
+    total: usize,
+}
+
+impl ChunkPosition {
+    /// Report that `done` volunteers in this chunk have been processed in the given phase. The
+    /// start of each phase of each chunk is also emitted as an event.
+    async fn report(&self, services: &ExportServices, job_id: Uuid, phase: JobPhase, done: usize) {
+        if done == 0 {
+            let event =
+                ProgressEvent::PhaseStarted { phase, processed: self.offset, total: self.total };
+            record_job_event(services.storage_layer.as_ref(), job_id, event).await;
         }
+        report_progress(services, job_id, phase, self.offset + done, self.total).await;
     }
+}
 
-    successfully_exported
+/// Report the progress of an export job.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+/// * `phase`: The phase the export is in
+/// * `processed`: How many users have been processed in this phase
+/// * `total`: How many users there are to process in this phase
+///
+/// Progress is informational, so failing to report it is logged rather than failing the export.
+async fn report_progress(
+    services: &ExportServices,
+    job_id: Uuid,
+    phase: JobPhase,
+    processed: usize,
+    total: usize,
+) {
+    let data = UpdateJobProgress {
+        phase,
+        processed: processed.try_into().unwrap_or(i32::MAX),
+        total: total.try_into().unwrap_or(i32::MAX),
+    };
+
+    let result = match ExecOptsBuilder::default().build() {
+        Ok(mut exec_opts) => {
+            services.storage_layer.update_job_progress(job_id, data, &mut exec_opts).await
+        }
+        Err(e) => Err(e.into()),
+    };
+
+    if let Err(e) = result {
+        log::warn!("Failed to report progress of job {job_id}: {e}");
+    }
 }
 
+/// Move several volunteers to the same checkpoint status. Failing to do so is logged rather than
+/// returned, since the work the checkpoint records has already been done.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+/// * `volunteer_ids`: The IDs of the volunteers
+/// * `status`: The new status
+async fn checkpoint_volunteers(
+    services: &ExportServices,
+    job_id: Uuid,
+    volunteer_ids: Vec<Uuid>,
+    status: WorkspaceExportStatus,
+) {
+    let result = match ExecOptsBuilder::default().build() {
+        Ok(mut exec_opts) => {
+            services
+                .storage_layer
+                .update_export_checkpoints(job_id, volunteer_ids, status, &mut exec_opts)
+                .await
+        }
+        Err(e) => Err(e.into()),
+    };
+
+    if let Err(e) = result {
+        log::error!("Failed to checkpoint job {job_id} as {status:?}: {e}");
+    }
+}
+
+async fn checkpoint_volunteer(
+    services: &ExportServices,
+    job_id: Uuid,
+    volunteer_id: Uuid,
+    status: WorkspaceExportStatus,
+    error: Option<String>,
+) -> Result<()> {
+    services
+        .storage_layer
+        .update_export_checkpoint(
+            job_id,
+            volunteer_id,
+            status,
+            error,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await
+}
+
+/// Keep only the entries of `data` whose corresponding entry in `exported` is `true`.
+fn retain_exported<T>(data: Vec<T>, exported: &[bool]) -> Vec<T> {
+    data.into_iter().zip(exported).filter_map(|(d, e)| e.then_some(d)).collect()
+}
+
+/// Record exported volunteers in Pantheon, checkpoint them as recorded, and write their onboarding
+/// emails to the outbox.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+/// * `position`: Where these volunteers sit within the export
+/// * `save_data`: The volunteers to record
+/// * `outbox`: The ID of the claim to hold on the volunteers' onboarding emails, and the emails to
+///   write to the outbox, if they are to be emailed
+///
+/// The records, the checkpoints, and the emails are written in one transaction, so a resumed job
+/// never records a volunteer twice or forgets to record one, and every recorded volunteer's email
+/// is sent even if the export stops before it sends it.
 async fn save_exported_volunteers<'a>(
     services: &ExportServices,
+    job_id: Uuid,
+    position: ChunkPosition,
     save_data: Vec<InsertVolunteerExportedToWorkspace>,
+    outbox: Option<(Uuid, Vec<CreateOutboxEmail>)>,
 ) -> Result<()> {
+    if save_data.is_empty() {
+        return Ok(());
+    }
+
+    let count = save_data.len();
+    let volunteer_ids = save_data.iter().map(|v| v.volunteer_id).collect::<Vec<Uuid>>();
+    position.report(services, job_id, JobPhase::Persisting, 0).await;
+
+    let mut tx = services.storage_layer.acquire().await?;
+    let mut exec_opts = ExecOptsBuilder::default().tx(&mut tx).build()?;
+
     services
         .storage_layer
-        .batch_insert_volunteers_exported_to_workspace(
-            save_data,
-            &mut ExecOptsBuilder::default().build()?,
+        .batch_insert_volunteers_exported_to_workspace(save_data, &mut exec_opts)
+        .await?;
+    services
+        .storage_layer
+        .update_export_checkpoints(
+            job_id,
+            volunteer_ids,
+            WorkspaceExportStatus::Recorded,
+            &mut exec_opts,
         )
         .await?;
+    if let Some((claimed_by, emails)) = outbox {
+        services
+            .storage_layer
+            .enqueue_outbox_emails(emails, claimed_by, claimed_until(), &mut exec_opts)
+            .await?;
+    }
+
+    tx.commit().await?;
+    position.report(services, job_id, JobPhase::Persisting, count).await;
 
     Ok(())
 }
 
+/// The outcome of sending onboarding emails to exported volunteers.
+///
+/// * `failed`: The workspace emails of the volunteers whose onboarding emails could not be sent
+/// * `suppressed`: Why each volunteer whose address is on the suppression list is suppressed, by
+///   workspace email. These volunteers are in `failed`, but their emails are never queued to be
+///   sent again, since they would be refused again.
+#[derive(Debug, Default)]
+struct SentOnboardingEmails {
+    failed: Vec<String>,
+    suppressed: HashMap<String, EmailSuppressionReason>,
+}
+
+/// The templates and attachments onboarding emails are sent with, fetched once per batch of
+/// emails.
+///
+/// * `templates`: The templates staff have saved, by name. `None` if there is no saved template
+///   for the name, and the compiled-in template is used.
+/// * `attachments`: The files to attach to every onboarding email
+struct OnboardingTemplates {
+    templates: HashMap<String, Option<EmailTemplate>>,
+    attachments: Vec<EmailAttachment>,
+}
+
+impl OnboardingTemplates {
+    fn new() -> Self {
+        Self { templates: HashMap::new(), attachments: load_onboarding_attachments() }
+    }
+
+    /// Set the template and attachments of an onboarding email.
+    ///
+    /// * `services`: The services needed to run the export
+    /// * `email`: The onboarding email
+    ///
+    /// Returns the name of the template for the email audit log.
+    async fn apply(
+        &mut self,
+        services: &ExportServices,
+        email: &mut OnboardingEmailParams,
+    ) -> String {
+        let name = localized_template_name(ONBOARDING_TEMPLATE, &email.locale);
+        if !self.templates.contains_key(&name) {
+            let template = fetch_onboarding_template(services, &name).await;
+            self.templates.insert(name.clone(), template);
+        }
+
+        email.template = self.templates[&name].clone().map(CustomTemplate::from);
+        email.attachments = self.attachments.clone();
+
+        match &self.templates[&name] {
+            Some(t) => format!("{} v{}", t.name, t.version),
+            None => localized_template("email/onboard", &email.locale, "html"),
+        }
+    }
+}
+
+/// Send onboarding emails to exported volunteers from the outbox.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+/// * `position`: Where these volunteers sit within the export
+/// * `claim`: The claim the export holds on the emails in the outbox
+/// * `requeue`: Whether to queue emails no mail provider accepted to be sent again
+/// * `onboarding_data`: The onboarding emails to send
+/// * `password_sms_data`: The temporary passwords to text, for volunteers whose passwords are
+///   delivered by SMS
+///
+/// A volunteer whose password is delivered by SMS is texted before their onboarding email is sent.
+/// If the text cannot be sent, neither is the email, since the volunteer could not sign in anyway.
+/// Every email is removed from the outbox once it is sent or given up on. Emails the relay took
+/// over after the claim ran out are left to it, and are not counted as failed.
 async fn send_onboarding_emails(
     services: &ExportServices,
+    job_id: Uuid,
+    position: ChunkPosition,
+    claim: &mut OutboxClaim,
+    requeue: bool,
     onboarding_data: Vec<OnboardingEmailParams>,
-) -> Result<()> {
-    for email in onboarding_data {
-        match services.mail.send_onboarding_email(email.clone()).await {
+    password_sms_data: Vec<Option<TemporaryPasswordSmsParams>>,
+) -> SentOnboardingEmails {
+    let total = onboarding_data.len();
+    let mut sent = SentOnboardingEmails::default();
+    let mut templates = OnboardingTemplates::new();
+    let mut emails = Vec::<(Uuid, OnboardingEmailParams, String)>::with_capacity(total);
+
+    // The claim may have run out while the rest of the chunk was set up
+    claim.renew(services).await;
+
+    for (i, (mut email, sms)) in onboarding_data.into_iter().zip(password_sms_data).enumerate() {
+        position.report(services, job_id, JobPhase::Emailing, i).await;
+
+        let Some(outbox_id) = claim.email_id(&email) else {
+            log::warn!("Leaving onboarding email to {} to the outbox relay", email.workspace_email);
+            continue;
+        };
+
+        let template = templates.apply(services, &mut email).await;
+
+        if let Some(sms) = sms {
+            if let Err(e) = services.sms.send_temporary_password(sms).await {
+                log::error!(
+                    "Failed to text temporary password for {}: {}",
+                    email.workspace_email,
+                    e
+                );
+                complete_outbox_email(services, claim.id, outbox_id, None).await;
+                let event = ProgressEvent::OnboardingEmailSent {
+                    workspace_email: email.workspace_email.clone(),
+                    error: Some(format!("failed to text temporary password: {e}")),
+                };
+                record_job_event(services.storage_layer.as_ref(), job_id, event).await;
+                sent.failed.push(email.workspace_email);
+                continue;
+            }
+            log::info!("Texted temporary password for {}", email.workspace_email);
+        }
+
+        emails.push((outbox_id, email, template));
+    }
+
+    // The emails are sent together so providers that support it can send them in batches
+    let results = services
+        .mail
+        .send_onboarding_emails(emails.iter().map(|(_, email, _)| email.clone()).collect())
+        .await;
+    for ((outbox_id, email, template), result) in emails.into_iter().zip(results) {
+        // A suppressed address would be refused again
+        let retry = match &result {
+            Err(e) if requeue && suppression_reason(e).is_none() => Some(&email),
+            _ => None,
+        };
+        complete_outbox_email(services, claim.id, outbox_id, retry).await;
+        record_email_send(services, &email, &template, &result).await;
+        let event = ProgressEvent::OnboardingEmailSent {
+            workspace_email: email.workspace_email.clone(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        };
+        record_job_event(services.storage_layer.as_ref(), job_id, event).await;
+        match result {
             Ok(_) => {
                 log::info!("Sent onboarding email to {}", email.email);
             }
             Err(e) => {
                 log::error!("Failed to send onboarding email to {}: {}", email.email, e);
+                sent.failed.push(email.workspace_email.clone());
+                if let Some(reason) = suppression_reason(&e) {
+                    sent.suppressed.insert(email.workspace_email, reason);
+                }
             }
         }
     }
-    Ok(())
+    position.report(services, job_id, JobPhase::Emailing, total).await;
+    sent
+}
+
+/// Record an attempt to send an onboarding email in the email audit log.
+///
+/// * `services`: The services needed to run the export
+/// * `email`: The onboarding email
+/// * `template`: The template the email was rendered from
+/// * `result`: The result of sending the email
+///
+/// Failing to record the attempt is logged rather than failing the export, since the email has
+/// already been sent (or not) by this point.
+async fn record_email_send(
+    services: &ExportServices,
+    email: &OnboardingEmailParams,
+    template: &str,
+    result: &Result<SentEmail>,
+) {
+    let (status, provider, message_id, error) = match result {
+        Ok(sent) => {
+            (EmailSendStatus::Sent, Some(sent.provider.clone()), sent.message_id.clone(), None)
+        }
+        Err(e) => (EmailSendStatus::Failed, None, None, Some(e.to_string())),
+    };
+    let data = RecordEmailSend {
+        job_id: email.job_id,
+        volunteer_id: email.volunteer_id,
+        recipient: email.email.clone(),
+        template: template.to_owned(),
+        provider,
+        message_id,
+        status,
+        error,
+    };
+
+    let recorded = match ExecOptsBuilder::default().build() {
+        Ok(mut exec_opts) => {
+            services.storage_layer.record_email_send(data, &mut exec_opts).await.map(|_| ())
+        }
+        Err(e) => Err(e.into()),
+    };
+
+    if let Err(e) = recorded {
+        log::error!("Failed to record onboarding email to {} in the audit log: {e}", email.email);
+    }
 }
 
-pub async fn export_task(services: &ExportServices, params: ExportParams) -> Result<()> {
-    let mut processed = process_volunteers(&params)?;
+/// Load the files to attach to onboarding emails.
+///
+/// The welcome packet is read from the path in the `WELCOME_PACKET_PATH` environment variable, if
+/// it is set. If it cannot be read, emails are sent without it rather than failing the export.
+fn load_onboarding_attachments() -> Vec<EmailAttachment> {
+    let Ok(path) = env::var(WELCOME_PACKET_PATH_ENV_VAR) else {
+        return Vec::new();
+    };
+
+    match EmailAttachment::from_path(Path::new(&path)) {
+        Ok(attachment) => vec![attachment],
+        Err(e) => {
+            log::error!("Failed to load welcome packet, sending emails without it: {e:#}");
+            Vec::new()
+        }
+    }
+}
 
+/// Fetch the onboarding template staff have saved, if any.
+///
+/// * `services`: The services needed to run the export
+/// * `name`: The name of the template, including its locale if it is a translation
+///
+/// If there is no saved template, or it cannot be fetched, emails fall back to the compiled-in
+/// template for their locale rather than failing the export.
+async fn fetch_onboarding_template(services: &ExportServices, name: &str) -> Option<EmailTemplate> {
+    let template = match ExecOptsBuilder::default().build() {
+        Ok(mut exec_opts) => {
+            services.storage_layer.fetch_email_template(name, &mut exec_opts).await
+        }
+        Err(e) => Err(e.into()),
+    };
+
+    match template {
+        Ok(template) => template,
+        Err(e) => {
+            log::error!("Failed to fetch onboarding template {name}, using the default: {e}");
+            None
+        }
+    }
+}
+
+/// Undo Workspace accounts created by an export according to the rollback policy.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+/// * `settings`: Settings for the export
+/// * `reason`: Why the accounts are being rolled back
+/// * `created`: The accounts to roll back, as volunteer IDs and workspace emails
+///
+/// Each rolled back volunteer's checkpoint is marked as failed, so resuming the job creates their
+/// account again.
+async fn roll_back_volunteers(
+    services: &ExportServices,
+    job_id: Uuid,
+    settings: &ExportSettings<'_>,
+    reason: &str,
+    created: &[(Uuid, String)],
+) {
+    for (volunteer_id, workspace_email) in created {
+        let result = match settings.rollback_policy {
+            RollbackPolicy::Disabled => return,
+            RollbackPolicy::Suspend => {
+                services.workspace.suspend_volunteer(settings.principal, workspace_email).await
+            }
+            RollbackPolicy::Delete => {
+                services.workspace.delete_user(settings.principal, workspace_email).await
+            }
+        };
+
+        match result {
+            Ok(_) => {
+                log::info!("Rolled back workspace account {workspace_email}");
+                let error = Some(format!("Rolled back: {reason}"));
+                if let Err(e) = checkpoint_volunteer(
+                    services,
+                    job_id,
+                    *volunteer_id,
+                    WorkspaceExportStatus::Failed,
+                    error,
+                )
+                .await
+                {
+                    log::error!("Failed to checkpoint rollback of {workspace_email}: {e}");
+                }
+            }
+            Err(e) => {
+                log::error!("Failed to roll back workspace account {workspace_email}: {e}");
+            }
+        }
+    }
+}
+
+/// Export the processed volunteers in chunks of `settings.chunk_size`. The outcome is saved with
+/// the job after every chunk, and the job is marked complete or errored once every chunk is done.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+/// * `job_version`: The version of the export job when it was claimed, which it is finished at
+/// * `settings`: Settings for the export
+/// * `processed`: The volunteers to export
+/// * `outcome`: The outcome of the export, which is updated for every processed volunteer
+///
+/// Every chunk is fully created, recorded, and emailed (and checkpointed at each of those steps)
+/// before the next one starts, so a crash loses at most the chunk in flight. If a chunk cannot be
+/// recorded, or the job is cancelled, the remaining chunks are not started. A cancelled job keeps
+/// the outcome saved after its last chunk rather than being marked complete or errored.
+async fn run_export(
+    services: &ExportServices,
+    job_id: Uuid,
+    job_version: i32,
+    settings: &ExportSettings<'_>,
+    processed: ProcessedVolunteers,
+    outcome: &mut ExportOutcome,
+) -> Result<()> {
+    run_export_batch(services, job_id, settings, 0, processed, outcome).await?;
+
+    if let Some(cancellation) = settings.cancellation {
+        if cancellation.check(services).await {
+            log::info!("Export job {job_id} was cancelled, keeping its partial outcome");
+            return Ok(());
+        }
+    }
+
+    finish_export(services, job_id, job_version, outcome).await
+}
+
+/// Export a batch of processed volunteers in chunks of `settings.chunk_size`, like `run_export`,
+/// without marking the job complete or errored once the batch is done.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+/// * `settings`: Settings for the export
+/// * `offset`: The number of volunteers in earlier batches of the export
+/// * `processed`: The volunteers in the batch
+/// * `outcome`: The outcome of the export, which is updated for every processed volunteer
+///
+/// Returns whether every chunk could be recorded. If one cannot, or the job is cancelled, the
+/// remaining chunks are not started, and no later batch should be either.
+async fn run_export_batch(
+    services: &ExportServices,
+    job_id: Uuid,
+    settings: &ExportSettings<'_>,
+    mut offset: usize,
+    processed: ProcessedVolunteers,
+    outcome: &mut ExportOutcome,
+) -> Result<bool> {
+    // Later batches are not counted until they are read, so the total grows with every batch
+    let total = offset + processed.export_data.len();
+    let chunks = processed.into_chunks(settings.chunk_size);
+    let number_of_chunks = chunks.len();
+
+    for (i, chunk) in chunks.into_iter().enumerate() {
+        if settings.is_cancelled(services).await {
+            log::info!("Stopping job {job_id} before chunk {}/{number_of_chunks}", i + 1);
+            return Ok(false);
+        }
+
+        log::info!("Exporting chunk {}/{number_of_chunks} of job {job_id}", i + 1);
+
+        let size = chunk.export_data.len();
+        let position = ChunkPosition { offset, total };
+        let recorded =
+            run_export_chunk(services, job_id, settings, position, chunk, outcome).await?;
+        outcome.save(services, job_id).await?;
+
+        if !recorded {
+            log::error!("Stopping job {job_id} after chunk {}/{number_of_chunks}", i + 1);
+            return Ok(false);
+        }
+
+        offset += size;
+    }
+
+    Ok(true)
+}
+
+/// Create a chunk of processed volunteers in Google Workspace, record them in Pantheon, and send
+/// their onboarding emails.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+/// * `settings`: Settings for the export
+/// * `position`: Where the chunk sits within the export
+/// * `processed`: The volunteers in the chunk
+/// * `outcome`: The outcome of the export, which is updated for every volunteer in the chunk
+///
+/// If recording the created users fails, or some of their onboarding emails cannot be sent, the
+/// affected accounts are rolled back according to `settings.rollback_policy`. Returns whether the
+/// chunk could be recorded.
+async fn run_export_chunk(
+    services: &ExportServices,
+    job_id: Uuid,
+    settings: &ExportSettings<'_>,
+    position: ChunkPosition,
+    mut processed: ProcessedVolunteers,
+    outcome: &mut ExportOutcome,
+) -> Result<bool> {
     let number_of_users_to_export = processed.export_data.len();
-    let exported_count =
-        export_volunteers_to_workspace(services, &params.principal, processed.export_data).await;
+    let mut aliases = processed
+        .pantheon_data
+        .iter()
+        .zip(processed.export_data.iter())
+        .map(|(p, v)| (p.volunteer_id, v.aliases.clone()))
+        .collect::<HashMap<Uuid, Vec<String>>>();
+    // The export consumes the volunteers, so the ones whose accounts are personalized are kept
+    let personalized = match settings.personalization {
+        Some(_) => processed.export_data.clone(),
+        None => Vec::new(),
+    };
+    let export_data = processed
+        .pantheon_data
+        .iter()
+        .map(|p| p.volunteer_id)
+        .zip(processed.export_data)
+        .collect::<Vec<(Uuid, CreateWorkspaceVolunteer)>>();
+
+    let results =
+        export_volunteers_to_workspace(services, job_id, settings, position, export_data).await;
+    let exported = results.iter().map(Result::is_ok).collect::<Vec<bool>>();
+    let exported_count = exported.iter().filter(|e| **e).count();
+
+    let mut already_existed = HashSet::<Uuid>::new();
+    for (p, result) in processed.pantheon_data.iter().zip(results) {
+        match result {
+            Ok(CreateVolunteerOutcome::AlreadyExists) => {
+                already_existed.insert(p.volunteer_id);
+            }
+            Ok(CreateVolunteerOutcome::Created) => {}
+            Err(reason) => {
+                let status = VolunteerExportStatus::Failed { reason };
+                outcome.record(p.volunteer_id, p.workspace_email.clone(), status);
+            }
+        }
+    }
 
     if exported_count != number_of_users_to_export {
         log::error!(
@@ -146,40 +1586,612 @@ pub async fn export_task(services: &ExportServices, params: ExportParams) -> Res
             exported_count,
             number_of_users_to_export
         );
-        processed.pantheon_data.truncate(exported_count);
-        processed.onboarding_email_data.truncate(exported_count);
+        processed.pantheon_data = retain_exported(processed.pantheon_data, &exported);
+        processed.onboarding_email_data =
+            retain_exported(processed.onboarding_email_data, &exported);
+        processed.password_sms_data = retain_exported(processed.password_sms_data, &exported);
     }
 
-    match save_exported_volunteers(services, processed.pantheon_data).await {
-        Ok(_) => match send_onboarding_emails(services, processed.onboarding_email_data).await {
-            Ok(_) => {
-                services
-                    .storage_layer
-                    .mark_job_complete(params.job_id, &mut ExecOptsBuilder::default().build()?)
-                    .await?
+    let created = processed
+        .pantheon_data
+        .iter()
+        .map(|p| (p.volunteer_id, p.workspace_email.clone()))
+        .collect::<Vec<(Uuid, String)>>();
+
+    let (mut claim, outbox) = OutboxClaim::new(&processed.onboarding_email_data);
+    if let Err(e) = save_exported_volunteers(
+        services,
+        job_id,
+        position,
+        processed.pantheon_data,
+        Some((claim.id, outbox)),
+    )
+    .await
+    {
+        log::error!("Failed to record exported users: {e}");
+        roll_back_volunteers(services, job_id, settings, &e.to_string(), &created).await;
+        for (volunteer_id, workspace_email) in created {
+            let status =
+                VolunteerExportStatus::Failed { reason: format!("Failed to record export: {e}") };
+            outcome.record(volunteer_id, workspace_email, status);
+        }
+        return Ok(false);
+    }
+
+    let accounts = created
+        .iter()
+        .map(|(volunteer_id, email)| {
+            (*volunteer_id, email.clone(), aliases.remove(volunteer_id).unwrap_or_default())
+        })
+        .collect::<Vec<(Uuid, String, Vec<String>)>>();
+    let mut aliases_failed = add_aliases(services, job_id, settings, &accounts).await;
+
+    // Volunteers are added to their groups before they are emailed, so the groups are already
+    // there the first time they sign in
+    let mut groups_failed = add_to_groups(services, settings, created.iter().map(|(_, e)| e)).await;
+    let license_failed = assign_licenses(services, settings, created.iter().map(|(_, e)| e)).await;
+    let shared_drive_failed =
+        add_to_shared_drive(services, settings, created.iter().map(|(_, e)| e)).await;
+    let invite_failed =
+        invite_to_onboarding_session(services, settings, created.iter().map(|(_, e)| e)).await;
+    let slack_failed = invite_to_slack(services, settings, created.iter().map(|(_, e)| e)).await;
+    let created_emails = created.iter().map(|(_, e)| e.as_str()).collect::<HashSet<&str>>();
+    let personalization_failed = personalize_accounts(
+        services,
+        settings,
+        personalized
+            .iter()
+            .filter(|v| created_emails.contains(v.primary_email.as_str()))
+            .map(SignatureDetails::from),
+    )
+    .await;
+
+    // Accounts that are rolled back no longer exist, so there is no point emailing them again
+    let requeue = settings.rollback_policy == RollbackPolicy::Disabled;
+    let sent = send_onboarding_emails(
+        services,
+        job_id,
+        position,
+        &mut claim,
+        requeue,
+        processed.onboarding_email_data,
+        processed.password_sms_data,
+    )
+    .await;
+    let failed_emails = sent.failed;
+    let roll_back = !failed_emails.is_empty() && !requeue;
+
+    if roll_back {
+        let reason = "Failed to send onboarding email";
+        let failed = created
+            .iter()
+            .filter(|(_, workspace_email)| failed_emails.contains(workspace_email))
+            .cloned()
+            .collect::<Vec<(Uuid, String)>>();
+
+        roll_back_volunteers(services, job_id, settings, reason, &failed).await;
+        services
+            .storage_layer
+            .batch_remove_volunteers_exported_to_workspace(
+                failed.into_iter().map(|(volunteer_id, _)| volunteer_id).collect(),
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?;
+    }
+
+    let mut emailed = Vec::<Uuid>::with_capacity(created.len());
+    for (volunteer_id, workspace_email) in created {
+        let onboarding_email_sent = !failed_emails.contains(&workspace_email);
+        let onboarding_email_suppressed = sent.suppressed.get(&workspace_email).copied();
+        let status = if !onboarding_email_sent && roll_back {
+            let reason = match onboarding_email_suppressed {
+                Some(reason) => {
+                    format!("Rolled back: onboarding email address is suppressed ({reason})")
+                }
+                None => "Rolled back: failed to send onboarding email".to_owned(),
+            };
+            VolunteerExportStatus::Failed { reason }
+        } else {
+            VolunteerExportStatus::Exported {
+                onboarding_email_sent,
+                onboarding_email_suppressed,
+                failures: ExportStepFailures {
+                    groups_failed: groups_failed.remove(&workspace_email).unwrap_or_default(),
+                    aliases_failed: aliases_failed.remove(&workspace_email).unwrap_or_default(),
+                    license_failed: license_failed.contains(&workspace_email),
+                    shared_drive_failed: shared_drive_failed.contains(&workspace_email),
+                    onboarding_invite_failed: invite_failed.contains(&workspace_email),
+                    slack_invite_failed: slack_failed.contains(&workspace_email),
+                    personalization_failed: personalization_failed.contains(&workspace_email),
+                },
+                already_existed: already_existed.contains(&volunteer_id),
             }
-            Err(e) => {
+        };
+        if onboarding_email_sent {
+            emailed.push(volunteer_id);
+        }
+        outcome.record(volunteer_id, workspace_email, status);
+    }
+
+    checkpoint_volunteers(services, job_id, emailed, WorkspaceExportStatus::Emailed).await;
+
+    Ok(true)
+}
+
+/// Save the outcome of an export and mark the job complete, or errored if any volunteer failed.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+/// * `job_version`: The version of the export job when it was claimed
+/// * `outcome`: The outcome of the export
+///
+/// The outcome and the job's status are written in one transaction, so a job is never marked
+/// complete without the outcome that lists who was exported, or the other way around. If another
+/// worker resumed the job since it was claimed, neither is written.
+async fn finish_export(
+    services: &ExportServices,
+    job_id: Uuid,
+    job_version: i32,
+    outcome: &ExportOutcome,
+) -> Result<()> {
+    let mut tx = services.storage_layer.acquire().await?;
+    let mut exec_opts = ExecOptsBuilder::default().tx(&mut tx).build()?;
+
+    services
+        .storage_layer
+        .set_job_result(job_id, serde_json::to_value(outcome)?, &mut exec_opts)
+        .await?;
+
+    let failed = outcome.failed();
+    if failed > 0 {
+        services
+            .storage_layer
+            .mark_job_errored(
+                job_id,
+                job_version,
+                format!("Failed to export {failed} users"),
+                &mut exec_opts,
+            )
+            .await?;
+    } else {
+        services.storage_layer.mark_job_complete(job_id, job_version, &mut exec_opts).await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Export volunteers to Google Workspace.
+///
+/// * `services`: The services needed to run the export
+/// * `params`: The export parameters
+///
+/// Returns the export plan. Every volunteer is assigned a workspace email that is not already
+/// taken. Volunteers that already have a workspace account are skipped rather than created again,
+/// and are listed in the plan. What happened to every volunteer is saved as an `ExportOutcome` in
+/// the job result.
+///
+/// Volunteers are exported in batches: the listed volunteers make up a single batch, while a whole
+/// cohort is read from the database a page at a time, and each page is planned and exported before
+/// the next one is read. The job is marked complete or errored once the last batch is done.
+///
+/// If `params.dry_run` is set, the plan is validated and returned without creating any users,
+/// recording anything in the database, or sending any emails. Otherwise, every volunteer in a batch
+/// is checkpointed before any of its users are created so that the job can be resumed with
+/// `resume_export_job` if it stops partway through. Any of `params.groups` that do not exist are
+/// created first, and every exported volunteer is added to all of them. If `params.license` is set,
+/// the job fails before anyone in a batch is created unless there are enough seats left to license
+/// every volunteer in it. Likewise, if `params.two_step_verification` is set, the job fails before
+/// anyone is created unless the org unit enforces 2-Step Verification as the policy requires. If
+/// `params.shared_drive` is set, the cohort's drive is created (or found, if an earlier export
+/// created it) and every exported volunteer is added to it. If `params.onboarding_session` is set,
+/// the session is scheduled on the principal's calendar, every exported volunteer is invited to it,
+/// and its link is included in their onboarding emails. If `params.program` is set, the program
+/// schema is created if it does not exist and every exported volunteer's account is tagged with the
+/// program, their cohort, and their volunteer ID. If `params.slack` is set, every exported
+/// volunteer is invited to Slack and its channels before they are emailed.
+///
+/// The export checks whether its job was cancelled between volunteers, and stops once it was.
+/// Users that were already created are still recorded and emailed, volunteers it had not created
+/// yet are listed as failed, and the outcome so far is kept in the job result.
+pub async fn export_task(
+    services: &ExportServices,
+    mut params: ExportParams,
+) -> Result<ExportPlan> {
+    let mut volunteers = std::mem::take(&mut params.volunteers);
+    let mut plan = ExportPlan::default();
+    let mut outcome = ExportOutcome::default();
+    let mut prepared = None;
+    // Emails planned for earlier batches of a dry run, which are not recorded anywhere else
+    let mut planned_emails = HashSet::<String>::new();
+    let mut offset = 0;
+    let overrides = RecipientOverrides::fetch(services).await?;
+    let cancellation = JobCancellation::new(params.job_id);
+
+    // A job that was cancelled while it was queued never starts
+    if !params.dry_run && cancellation.check(services).await {
+        return Ok(plan);
+    }
+
+    while let Some(batch) = volunteers.next_batch(services).await? {
+        let (batch, mut skipped) = find_already_exported(services, batch).await?;
+
+        let undeliverable_domains = if params.verify_recovery_email_domains {
+            find_undeliverable_domains(batch.iter().map(|v| v.email.as_str())).await?
+        } else {
+            HashSet::new()
+        };
+
+        let (emails, blocked) =
+            assign_workspace_emails(services, &params, &batch, &planned_emails).await?;
+        let aliases = assign_aliases(services, &params, &batch, &emails).await?;
+        let (mut processed, existing, needs_attention) =
+            process_volunteers(&params, &batch, emails, aliases, &undeliverable_domains)?;
+        processed.override_recipients(&overrides);
+        skipped.extend(existing);
+        let planned = plan_export(&processed);
+
+        if params.dry_run {
+            validate_plan(&params, &planned)?;
+            planned_emails.extend(planned.iter().map(|p| p.workspace_email.clone()));
+            plan.extend(planned, skipped, blocked, needs_attention);
+            continue;
+        }
+
+        for skipped in skipped.iter().cloned() {
+            outcome.volunteers.push(skipped.into());
+        }
+        for needs_attention in needs_attention.iter().cloned() {
+            outcome.volunteers.push(needs_attention.into());
+        }
+        outcome.save(services, params.job_id).await?;
+
+        if let Some(license) = &params.license {
+            let needed = processed.export_data.len();
+            if let Err(e) = check_license_seats(services, &params.principal, license, needed).await
+            {
+                log::error!("Job {} cannot license its users: {e}", params.job_id);
                 services
                     .storage_layer
                     .mark_job_errored(
                         params.job_id,
+                        params.job_version,
                         e.to_string(),
                         &mut ExecOptsBuilder::default().build()?,
                     )
-                    .await?
+                    .await?;
+                return Err(e);
             }
-        },
-        Err(e) => {
+        }
+
+        if prepared.is_none() {
+            prepared = Some(prepare_export(services, &params).await?);
+        }
+        let prepared = prepared.as_ref().expect("the export was prepared before this batch");
+
+        if let Some(event) = &prepared.onboarding_session {
+            for email in processed.onboarding_email_data.iter_mut() {
+                email.onboarding_session_link = Some(event.link.clone());
+            }
+        }
+
+        let checkpoints = processed
+            .pantheon_data
+            .iter()
+            .map(|p| CreateExportCheckpoint {
+                volunteer_id: p.volunteer_id,
+                workspace_email: p.workspace_email.clone(),
+                org_unit: p.org_unit.clone(),
+            })
+            .collect::<Vec<CreateExportCheckpoint>>();
+
+        services
+            .storage_layer
+            .create_export_checkpoints(
+                params.job_id,
+                checkpoints,
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?;
+
+        let mut settings = ExportSettings::from(&params);
+        settings.shared_drive =
+            prepared.shared_drive.as_ref().map(|(drive_id, role)| (drive_id.as_str(), *role));
+        settings.onboarding_session =
+            prepared.onboarding_session.as_ref().map(|event| event.id.as_str());
+        settings.cancellation = Some(&cancellation);
+
+        let size = processed.export_data.len();
+        let recorded =
+            run_export_batch(services, params.job_id, &settings, offset, processed, &mut outcome)
+                .await?;
+        offset += size;
+        plan.extend(planned, skipped, blocked, needs_attention);
+
+        if !recorded || cancellation.is_cancelled(services).await {
+            break;
+        }
+    }
+
+    if params.dry_run {
+        log::info!("Dry run: planned export of {} users to workspace", plan.volunteers.len());
+        return Ok(plan);
+    }
+
+    // The outcome was saved after every chunk, and a cancelled job is not finished
+    if cancellation.check(services).await {
+        log::info!("Export job {} was cancelled, keeping its partial outcome", params.job_id);
+        return Ok(plan);
+    }
+
+    finish_export(services, params.job_id, params.job_version, &outcome).await?;
+
+    Ok(plan)
+}
+
+/// What an export sets up once, before it creates its first users, for every batch to share.
+///
+/// * `shared_drive`: The ID of the Shared Drive every exported user is added to, and the role they
+///   are given, if any
+/// * `onboarding_session`: The onboarding session every exported user is invited to, if any
+struct PreparedExport {
+    shared_drive: Option<(String, DriveRole)>,
+    onboarding_session: Option<CalendarEvent>,
+}
+
+/// Set up what every batch of an export shares.
+///
+/// * `services`: The services needed to run the export
+/// * `params`: The export parameters
+///
+/// If `params.two_step_verification` is set, the job fails unless the org unit enforces 2-Step
+/// Verification as the policy requires. Otherwise, the groups, program schema, shared drive, and
+/// onboarding session the export needs are created (or found, if they already exist).
+async fn prepare_export(
+    services: &ExportServices,
+    params: &ExportParams,
+) -> Result<PreparedExport> {
+    if let Some(policy) = &params.two_step_verification {
+        if let Err(e) = check_two_step_verification(services, params, policy).await {
+            log::error!(
+                "Job {} cannot protect its users with 2-Step Verification: {e}",
+                params.job_id
+            );
             services
                 .storage_layer
                 .mark_job_errored(
                     params.job_id,
+                    params.job_version,
                     e.to_string(),
                     &mut ExecOptsBuilder::default().build()?,
                 )
-                .await?
+                .await?;
+            return Err(e);
         }
     }
 
+    ensure_groups(services, &params.principal, &params.groups).await?;
+
+    if params.program.is_some() {
+        ensure_program_schema(services, &params.principal).await?;
+    }
+
+    let shared_drive = match &params.shared_drive {
+        Some(drive) => {
+            let drive_id = ensure_shared_drive(
+                services,
+                params.job_id,
+                params.project_cycle_id,
+                &params.principal,
+                drive,
+            )
+            .await?;
+            Some((drive_id, drive.role))
+        }
+        None => None,
+    };
+
+    let onboarding_session = match &params.onboarding_session {
+        Some(session) => Some(
+            schedule_onboarding_session(services, params.job_id, &params.principal, session)
+                .await?,
+        ),
+        None => None,
+    };
+
+    Ok(PreparedExport { shared_drive, onboarding_session })
+}
+
+/// Check that the org unit an export creates users in enforces 2-Step Verification as required.
+///
+/// * `services`: The services needed to run the export
+/// * `params`: The export parameters
+/// * `policy`: How exported users must be protected by 2-Step Verification
+async fn check_two_step_verification(
+    services: &ExportServices,
+    params: &ExportParams,
+    policy: &TwoStepVerificationPolicy,
+) -> Result<()> {
+    let settings = services
+        .workspace
+        .two_step_verification_settings(&params.principal, &params.org_unit)
+        .await?;
+    policy.check(&params.org_unit, &settings).map_err(PermanentJobError)?;
+
+    log::info!("Org unit {} enforces 2-Step Verification", params.org_unit);
+
     Ok(())
 }
+
+/// Fetch an export job from the database rather than the cache, so that its version is the one the
+/// job is at now.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+async fn fetch_current_job(services: &ExportServices, job_id: Uuid) -> Result<Job> {
+    // Reads made in a transaction skip the cache
+    let mut tx = services.storage_layer.acquire().await?;
+    let mut exec_opts = ExecOptsBuilder::default().tx(&mut tx).build()?;
+    services.storage_layer.fetch_job(job_id, &mut exec_opts).await
+}
+
+/// Resume a workspace export job that stopped before it finished.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job to resume
+/// * `principal`: The email of the user requesting the export
+///
+/// Volunteers that were already created in Google Workspace are not created again. Those that were
+/// created but never recorded in Pantheon are recorded. Their temporary passwords were never
+/// persisted, so they will not receive an onboarding email. Volunteers that were recorded but never
+/// emailed had their emails written to the outbox when they were recorded, so the outbox relay
+/// sends them. Every other volunteer is exported with the workspace email that was originally
+/// generated for them and a new temporary password. The groups, aliases, shared drive, onboarding
+/// session, and program of the original export are not persisted, so resumed volunteers are not
+/// given them. Only the batches an export had started are checkpointed, so volunteers of a cohort
+/// export that were never read are not resumed. Exporting the cohort again picks them up, since
+/// volunteers that were already exported are skipped.
+pub async fn resume_export_job(
+    services: &ExportServices,
+    job_id: Uuid,
+    principal: &str,
+) -> Result<()> {
+    let checkpoints = services
+        .storage_layer
+        .fetch_export_checkpoints(job_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    if checkpoints.is_empty() {
+        let e = anyhow!("No export progress was recorded for job {job_id}");
+        return Err(PermanentJobError(e).into());
+    }
+
+    let job = fetch_current_job(services, job_id).await?;
+
+    if matches!(job.status, JobStatus::Complete | JobStatus::Cancelled) {
+        bail!("Job {job_id} has already finished");
+    }
+
+    // Claim the job at the version it was read at, so that if it is resumed twice only one of the
+    // workers carries on.
+    services
+        .storage_layer
+        .resume_job(job_id, job.version, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    let already_saved = match job.project_cycle_id {
+        Some(project_cycle_id) => services
+            .storage_layer
+            .fetch_exported_volunteer_details_by_project_cycle(
+                project_cycle_id,
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?
+            .into_iter()
+            .filter(|v| v.job_id == job_id)
+            .map(|v| v.volunteer_id)
+            .collect::<HashSet<Uuid>>(),
+        None => HashSet::new(),
+    };
+
+    let mut outcome = ExportOutcome::from_job_details(&job.details).unwrap_or_default();
+    let password_policy =
+        PasswordPolicy { config: PasswordConfig::from_env()?, ..PasswordPolicy::default() };
+    let mut processed = ProcessedVolunteers::with_capacity(checkpoints.len());
+    let mut created_but_unsaved = Vec::<InsertVolunteerExportedToWorkspace>::new();
+
+    for c in checkpoints {
+        match c.status {
+            WorkspaceExportStatus::Created => {
+                if !already_saved.contains(&c.volunteer_id) {
+                    log::warn!(
+                        "{} was created before job {job_id} stopped, but no onboarding email can \
+                         be sent because their temporary password is unknown",
+                        c.workspace_email
+                    );
+                    let status = VolunteerExportStatus::resumed_without_email();
+                    outcome.record(c.volunteer_id, c.workspace_email.clone(), status);
+                    created_but_unsaved.push(InsertVolunteerExportedToWorkspace {
+                        volunteer_id: c.volunteer_id,
+                        job_id,
+                        workspace_email: c.workspace_email,
+                        org_unit: c.org_unit,
+                    });
+                }
+            }
+            WorkspaceExportStatus::Recorded => {
+                log::warn!(
+                    "{} was recorded before job {job_id} stopped, leaving their onboarding email \
+                     to the outbox relay",
+                    c.workspace_email
+                );
+                let status = VolunteerExportStatus::resumed_without_email();
+                outcome.record(c.volunteer_id, c.workspace_email, status);
+            }
+            WorkspaceExportStatus::Emailed => {}
+            WorkspaceExportStatus::Pending | WorkspaceExportStatus::Failed => {
+                let volunteer = services
+                    .storage_layer
+                    .fetch_volunteer_by_id(c.volunteer_id, &mut ExecOptsBuilder::default().build()?)
+                    .await?;
+
+                let Some(v) = volunteer else {
+                    log::warn!("Volunteer {} no longer exists, skipping", c.volunteer_id);
+                    let status = VolunteerExportStatus::Failed {
+                        reason: "Volunteer no longer exists".to_owned(),
+                    };
+                    outcome.record(c.volunteer_id, c.workspace_email, status);
+                    continue;
+                };
+
+                processed.push(
+                    job_id,
+                    &v,
+                    c.workspace_email,
+                    Vec::new(),
+                    password_policy.generate_password(),
+                    c.org_unit,
+                    &password_policy,
+                    DEFAULT_LOCALE,
+                    None,
+                )?;
+            }
+        }
+    }
+
+    processed.override_recipients(&RecipientOverrides::fetch(services).await?);
+
+    log::info!(
+        "Resuming export job {job_id}: {} users left to create, {} users left to record",
+        processed.export_data.len(),
+        created_but_unsaved.len()
+    );
+
+    save_exported_volunteers(
+        services,
+        job_id,
+        ChunkPosition { offset: 0, total: created_but_unsaved.len() },
+        created_but_unsaved,
+        None,
+    )
+    .await?;
+
+    let cancellation = JobCancellation::new(job_id);
+    let settings = ExportSettings {
+        principal,
+        retry_policy: &RetryPolicy::default(),
+        rollback_policy: RollbackPolicy::Disabled,
+        concurrency: DEFAULT_EXPORT_CONCURRENCY,
+        chunk_size: DEFAULT_EXPORT_CHUNK_SIZE,
+        groups: &[],
+        license: None,
+        shared_drive: None,
+        onboarding_session: None,
+        slack: None,
+        personalization: None,
+        cancellation: Some(&cancellation),
+    };
+
+    // Resuming the job moved it on to the next version, which is the one this worker finishes
+    run_export(services, job_id, job.version + 1, &settings, processed, &mut outcome).await
+}
@@ -0,0 +1,64 @@
+//! This module creates the org units that exports place volunteers in, so that each cohort can be
+//! given its own org unit (e.g. `/Programs/2025-Spring`) without someone setting it up in Google
+//! Workspace first.
+
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+
+use super::validate_org_unit_path;
+use crate::app::api::v1::data_exports::ExportServices;
+
+/// The org units that have to exist for an org unit to be created, ending with the org unit itself
+/// (e.g. `/Programs` and `/Programs/2025-Spring` for `/Programs/2025-Spring`). The root org unit
+/// always exists, so it is left out.
+///
+/// * `org_unit`: The full path of the org unit
+pub fn org_unit_ancestors(org_unit: &str) -> Vec<&str> {
+    org_unit
+        .match_indices('/')
+        .skip(1)
+        .map(|(i, _)| &org_unit[..i])
+        .chain((org_unit.len() > 1).then_some(org_unit))
+        .collect()
+}
+
+/// Make sure an org unit exists in Google Workspace, creating it and any of its missing parents.
+///
+/// * `services`: The services needed to run the export
+/// * `principal`: The email of the user requesting the export
+/// * `org_unit`: The full path of the org unit
+///
+/// Returns the paths of the org units that were created, parents first.
+pub async fn ensure_org_unit(
+    services: &ExportServices,
+    principal: &str,
+    org_unit: &str,
+) -> Result<Vec<String>> {
+    validate_org_unit_path(org_unit)?;
+
+    let existing = services
+        .workspace
+        .list_org_units(principal)
+        .await?
+        .into_iter()
+        .map(|o| o.org_unit_path.to_ascii_lowercase())
+        .collect::<HashSet<String>>();
+
+    let mut created = Vec::new();
+    for path in org_unit_ancestors(org_unit) {
+        if existing.contains(&path.to_ascii_lowercase()) {
+            continue;
+        }
+
+        services
+            .workspace
+            .create_org_unit(principal, path)
+            .await
+            .with_context(|| format!("Failed to create org unit {path}"))?;
+        log::info!("Created org unit {path} in workspace");
+        created.push(path.to_owned());
+    }
+
+    Ok(created)
+}
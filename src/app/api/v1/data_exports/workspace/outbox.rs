@@ -0,0 +1,314 @@
+//! This module sends onboarding emails through the outbox.
+//!
+//! An export writes the onboarding emails of a chunk to the outbox in the same transaction that
+//! records the chunk's volunteers, so a volunteer is never recorded without an email to send them,
+//! or the other way around. The export holds a claim on its emails while it sends them, and each
+//! email is removed from the outbox (and queued to be retried, if it failed) in one transaction as
+//! soon as a mail provider accepts or refuses it. If the export stops before it has sent them, its
+//! claim runs out and the relay sends them instead. Since only one claim can hold an email at a
+//! time, an export and the relay (or two relays) never both send it.
+//!
+//! An email is only sent twice if the process stops between a provider accepting it and it being
+//! removed from the outbox. Temporary passwords delivered by SMS are texted by the export, not
+//! the relay, so an email the relay sends for a volunteer who was never texted does not include
+//! their password.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::email_retries::email_retry;
+use super::{checkpoint_volunteers, record_email_send, OnboardingTemplates};
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::mail::suppression::suppression_reason;
+use crate::services::mail::{OnboardingEmailKind, OnboardingEmailParams};
+use crate::services::storage::entities::OutboxEmail;
+use crate::services::storage::organizations::with_org;
+use crate::services::storage::outbox::CreateOutboxEmail;
+use crate::services::storage::types::WorkspaceExportStatus;
+use crate::services::storage::ExecOptsBuilder;
+
+/// How long a claim on emails in the outbox lasts. An export renews its claim right before it
+/// sends its emails, so this only needs to cover the rest of a chunk (groups, licenses, invites,
+/// and the like) before then.
+pub const OUTBOX_CLAIM_DURATION: Duration = Duration::from_secs(30 * 60);
+
+/// How often the background task looks for emails left in the outbox.
+pub const OUTBOX_RELAY_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The most emails the relay claims at once.
+const OUTBOX_RELAY_BATCH_SIZE: i64 = 100;
+
+/// The outcome of relaying emails left in the outbox.
+///
+/// * `sent`: The number of emails that were sent
+/// * `failed`: The number of emails that could not be sent
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxRelaySummary {
+    pub sent: usize,
+    pub failed: usize,
+}
+
+/// When a claim on emails in the outbox taken now runs out.
+pub(super) fn claimed_until() -> DateTime<Utc> {
+    Utc::now() + OUTBOX_CLAIM_DURATION
+}
+
+/// The onboarding emails of a chunk an export wrote to the outbox, and the claim it holds on
+/// them.
+///
+/// * `id`: The ID of the claim
+/// * `emails`: The ID of each email in the outbox, by the ID of the volunteer it is sent to
+pub(super) struct OutboxClaim {
+    pub id: Uuid,
+    emails: HashMap<Uuid, Uuid>,
+}
+
+impl OutboxClaim {
+    /// Prepare the onboarding emails of a chunk to be written to the outbox under a new claim.
+    ///
+    /// * `emails`: The onboarding emails
+    ///
+    /// Returns the claim, along with the emails to write with `enqueue_outbox_emails`.
+    pub fn new(emails: &[OnboardingEmailParams]) -> (Self, Vec<CreateOutboxEmail>) {
+        let data = emails
+            .iter()
+            .filter_map(|e| {
+                Some(CreateOutboxEmail {
+                    id: Uuid::new_v4(),
+                    job_id: e.job_id?,
+                    volunteer_id: e.volunteer_id?,
+                    first_name: e.first_name.clone(),
+                    last_name: e.last_name.clone(),
+                    email: e.email.clone(),
+                    workspace_email: e.workspace_email.clone(),
+                    temporary_password: e.temporary_password.clone(),
+                    locale: e.locale.clone(),
+                    onboarding_session_link: e.onboarding_session_link.clone(),
+                })
+            })
+            .collect::<Vec<CreateOutboxEmail>>();
+        let emails = data.iter().map(|e| (e.volunteer_id, e.id)).collect();
+
+        (Self { id: Uuid::new_v4(), emails }, data)
+    }
+
+    /// The ID of the email sent to a volunteer in the outbox, if the claim still holds it.
+    ///
+    /// * `email`: The onboarding email
+    pub fn email_id(&self, email: &OnboardingEmailParams) -> Option<Uuid> {
+        email.volunteer_id.and_then(|volunteer_id| self.emails.get(&volunteer_id).copied())
+    }
+
+    /// Extend the claim so the emails can be sent, and let go of any the relay has taken over.
+    ///
+    /// * `services`: The services needed to run the export
+    ///
+    /// If the claim cannot be extended, every email is left to the relay rather than risk
+    /// sending it twice.
+    pub async fn renew(&mut self, services: &ExportServices) {
+        let ids = self.emails.values().copied().collect::<Vec<Uuid>>();
+        if ids.is_empty() {
+            return;
+        }
+
+        let held = match ExecOptsBuilder::default().build() {
+            Ok(mut exec_opts) => {
+                services
+                    .storage_layer
+                    .renew_outbox_claims(self.id, ids, claimed_until(), &mut exec_opts)
+                    .await
+            }
+            Err(e) => Err(e.into()),
+        };
+
+        match held {
+            Ok(held) => {
+                if held.len() < self.emails.len() {
+                    log::warn!(
+                        "{} onboarding emails were taken over by the outbox relay",
+                        self.emails.len() - held.len()
+                    );
+                }
+                self.emails.retain(|_, id| held.contains(id));
+            }
+            Err(e) => {
+                log::error!(
+                    "Failed to renew claim on onboarding emails, leaving them to the relay: {e}"
+                );
+                self.emails.clear();
+            }
+        }
+    }
+}
+
+/// Remove an email from the outbox once a mail provider has accepted or refused it, and queue it
+/// to be sent again in the same transaction if it should be retried.
+///
+/// * `services`: The services needed to run the export
+/// * `claimed_by`: The ID of the claim the email was sent under
+/// * `id`: The ID of the email in the outbox
+/// * `retry`: The email to queue to be sent again, if it should be retried
+///
+/// Failing to remove the email is logged rather than failing the export, since the email has
+/// already been sent (or not) by this point.
+pub(super) async fn complete_outbox_email(
+    services: &ExportServices,
+    claimed_by: Uuid,
+    id: Uuid,
+    retry: Option<&OnboardingEmailParams>,
+) {
+    if let Err(e) = remove_outbox_email(services, claimed_by, id, retry).await {
+        log::error!("Failed to remove onboarding email {id} from the outbox: {e}");
+    }
+}
+
+async fn remove_outbox_email(
+    services: &ExportServices,
+    claimed_by: Uuid,
+    id: Uuid,
+    retry: Option<&OnboardingEmailParams>,
+) -> Result<()> {
+    let mut tx = services.storage_layer.acquire().await?;
+    let mut exec_opts = ExecOptsBuilder::default().tx(&mut tx).build()?;
+
+    // An email the claim no longer holds is the relay's to send, and to retry
+    let held = services.storage_layer.delete_outbox_email(id, claimed_by, &mut exec_opts).await?;
+    if let Some(retry) = retry.and_then(email_retry).filter(|_| held) {
+        services.storage_layer.enqueue_email_retries(vec![retry], &mut exec_opts).await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Send the emails left in the outbox by exports that stopped before sending them.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: Only send emails written by this job, if set
+///
+/// Sent emails have their volunteers' checkpoints marked as emailed. Emails that fail are queued
+/// to be sent again, unless their address is suppressed. Accounts are never rolled back for emails
+/// the relay fails to send, since the export that created them has stopped.
+pub async fn relay_outbox_emails(
+    services: &ExportServices,
+    job_id: Option<Uuid>,
+) -> Result<OutboxRelaySummary> {
+    let mut summary = OutboxRelaySummary::default();
+    let mut templates = OnboardingTemplates::new();
+
+    loop {
+        // Every batch is claimed under a claim of its own, so a batch that stops partway through
+        // is only picked up again once its claim runs out
+        let claimed_by = Uuid::new_v4();
+        let claimed = services
+            .storage_layer
+            .claim_outbox_emails(
+                claimed_by,
+                job_id,
+                OUTBOX_RELAY_BATCH_SIZE,
+                claimed_until(),
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?;
+
+        if claimed.is_empty() {
+            return Ok(summary);
+        }
+
+        let mut emails = Vec::<(OutboxEmail, OnboardingEmailParams, String)>::new();
+        for outbox in claimed {
+            let mut email = OnboardingEmailParams::from(&outbox);
+            let template = templates.apply(services, &mut email).await;
+            emails.push((outbox, email, template));
+        }
+
+        let results = services
+            .mail
+            .send_onboarding_emails(emails.iter().map(|(_, email, _)| email.clone()).collect())
+            .await;
+
+        for ((outbox, email, template), result) in emails.into_iter().zip(results) {
+            let retry = match &result {
+                Ok(_) => None,
+                Err(e) if suppression_reason(e).is_some() => None,
+                Err(_) => Some(&email),
+            };
+            complete_outbox_email(services, claimed_by, outbox.id, retry).await;
+            record_email_send(services, &email, &template, &result).await;
+
+            match result {
+                Ok(_) => {
+                    log::info!("Sent onboarding email to {} from the outbox", outbox.email);
+                    checkpoint_volunteers(
+                        services,
+                        outbox.job_id,
+                        vec![outbox.volunteer_id],
+                        WorkspaceExportStatus::Emailed,
+                    )
+                    .await;
+                    summary.sent += 1;
+                }
+                Err(e) => {
+                    log::error!(
+                        "Failed to send onboarding email to {} from the outbox: {e}",
+                        outbox.email
+                    );
+                    summary.failed += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Start the background task that sends the emails left in the outbox.
+///
+/// * `services`: The services needed to run the export
+pub fn spawn_outbox_relay_task(services: ExportServices) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(OUTBOX_RELAY_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            for org_id in services.org_ids().await {
+                match with_org(org_id, relay_outbox_emails(&services, None)).await {
+                    Ok(summary) if summary.sent + summary.failed > 0 => log::info!(
+                        "Sent {} onboarding emails left in the outbox for organization {org_id}, \
+                         {} failed",
+                        summary.sent,
+                        summary.failed
+                    ),
+                    Ok(_) => {}
+                    Err(e) => log::error!(
+                        "Failed to relay onboarding emails for organization {org_id}: {e}"
+                    ),
+                }
+            }
+        }
+    })
+}
+
+impl From<&OutboxEmail> for OnboardingEmailParams {
+    fn from(value: &OutboxEmail) -> Self {
+        OnboardingEmailParams {
+            first_name: value.first_name.clone(),
+            last_name: value.last_name.clone(),
+            email: value.email.clone(),
+            workspace_email: value.workspace_email.clone(),
+            temporary_password: value.temporary_password.clone(),
+            send_at: None,
+            template: None,
+            locale: value.locale.clone(),
+            attachments: Vec::new(),
+            job_id: Some(value.job_id),
+            volunteer_id: Some(value.volunteer_id),
+            kind: OnboardingEmailKind::Welcome,
+            onboarding_session_link: value.onboarding_session_link.clone(),
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! This module defines the per-volunteer outcome of a workspace export.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::recovery::NeedsAttention;
+use super::SkippedVolunteer;
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::storage::types::EmailSuppressionReason;
+use crate::services::storage::ExecOptsBuilder;
+
+/// The steps of an export that failed for a volunteer whose account was created. None of them
+/// fail the export of the volunteer, so they are reported alongside it instead.
+///
+/// * `groups_failed`: The groups the volunteer could not be added to
+/// * `aliases_failed`: The aliases the volunteer could not be given
+/// * `license_failed`: Whether the volunteer could not be licensed
+/// * `shared_drive_failed`: Whether the volunteer could not be added to the cohort's shared drive
+/// * `onboarding_invite_failed`: Whether the volunteer could not be invited to the onboarding
+///   session
+/// * `slack_invite_failed`: Whether the volunteer could not be invited to Slack
+/// * `personalization_failed`: Whether the volunteer could not be given their signature and
+///   profile photo
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportStepFailures {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups_failed: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases_failed: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub license_failed: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub shared_drive_failed: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub onboarding_invite_failed: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub slack_invite_failed: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub personalization_failed: bool,
+}
+
+/// What happened to a single volunteer during an export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum VolunteerExportStatus {
+    /// The volunteer's workspace account was created and recorded. If their onboarding email was
+    /// not sent because their address is on the suppression list, the reason it is suppressed is
+    /// included, as are the steps of the export that failed for them. `already_existed` notes that
+    /// the account was found in Workspace rather than created, usually because an earlier attempt
+    /// succeeded.
+    #[serde(rename_all = "camelCase")]
+    Exported {
+        onboarding_email_sent: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        onboarding_email_suppressed: Option<EmailSuppressionReason>,
+        #[serde(flatten)]
+        failures: ExportStepFailures,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        already_existed: bool,
+    },
+    /// The volunteer could not be exported.
+    Failed { reason: String },
+    /// The volunteer was left out because they were already exported.
+    Skipped { reason: String },
+    /// The volunteer was left out because their recovery email needs to be fixed first.
+    NeedsAttention { reason: String },
+}
+
+impl VolunteerExportStatus {
+    /// The status of a volunteer whose account was created before an export stopped, and who is
+    /// not emailed when the export is resumed.
+    pub fn resumed_without_email() -> Self {
+        Self::Exported {
+            onboarding_email_sent: false,
+            onboarding_email_suppressed: None,
+            failures: ExportStepFailures::default(),
+            already_existed: false,
+        }
+    }
+}
+
+/// The outcome of exporting a single volunteer.
+///
+/// * `volunteer_id`: The ID of the volunteer
+/// * `workspace_email`: The workspace email the volunteer was (or would have been) issued
+/// * `status`: What happened to the volunteer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VolunteerOutcome {
+    pub volunteer_id: Uuid,
+    pub workspace_email: String,
+    #[serde(flatten)]
+    pub status: VolunteerExportStatus,
+}
+
+impl From<NeedsAttention> for VolunteerOutcome {
+    fn from(value: NeedsAttention) -> Self {
+        Self {
+            volunteer_id: value.volunteer_id,
+            workspace_email: value.workspace_email,
+            status: VolunteerExportStatus::NeedsAttention { reason: value.reason },
+        }
+    }
+}
+
+impl From<SkippedVolunteer> for VolunteerOutcome {
+    fn from(value: SkippedVolunteer) -> Self {
+        Self {
+            volunteer_id: value.volunteer_id,
+            workspace_email: value.workspace_email,
+            status: VolunteerExportStatus::Skipped { reason: value.reason },
+        }
+    }
+}
+
+/// The outcome of a workspace export job. This is persisted as the result of the job.
+///
+/// * `volunteers`: The outcome for each volunteer in the job
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOutcome {
+    #[serde(default)]
+    pub volunteers: Vec<VolunteerOutcome>,
+}
+
+impl ExportOutcome {
+    /// Record what happened to a volunteer, replacing anything previously recorded for them.
+    ///
+    /// * `volunteer_id`: The ID of the volunteer
+    /// * `workspace_email`: The workspace email of the volunteer
+    /// * `status`: What happened to the volunteer
+    pub fn record(
+        &mut self,
+        volunteer_id: Uuid,
+        workspace_email: String,
+        status: VolunteerExportStatus,
+    ) {
+        let outcome = VolunteerOutcome { volunteer_id, workspace_email, status };
+        match self.volunteers.iter_mut().find(|v| v.volunteer_id == volunteer_id) {
+            Some(existing) => *existing = outcome,
+            None => self.volunteers.push(outcome),
+        }
+    }
+
+    /// The number of volunteers that could not be exported.
+    pub fn failed(&self) -> usize {
+        self.volunteers
+            .iter()
+            .filter(|v| matches!(v.status, VolunteerExportStatus::Failed { .. }))
+            .count()
+    }
+
+    /// Read the outcome recorded in a job's details, if there is one.
+    ///
+    /// * `details`: The details of the job
+    pub fn from_job_details(details: &serde_json::Value) -> Option<Self> {
+        details.get("result").and_then(|r| serde_json::from_value(r.clone()).ok())
+    }
+
+    /// Persist the outcome as the result of the job.
+    ///
+    /// * `services`: The services needed to run the export
+    /// * `job_id`: The ID of the export job
+    pub async fn save(&self, services: &ExportServices, job_id: Uuid) -> Result<()> {
+        services
+            .storage_layer
+            .set_job_result(
+                job_id,
+                serde_json::to_value(self)?,
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await
+    }
+}
@@ -0,0 +1,223 @@
+//! This module sets up exported volunteers' accounts so they look the same across a program: a
+//! standard Gmail signature and a default profile photo.
+//!
+//! Signatures are rendered with Tera from the compiled-in template (or one given with the export),
+//! using the volunteer's name, workspace email, and program. The profile photo is read once per
+//! export from the path in the `DEFAULT_PROFILE_PHOTO_PATH` environment variable. Like groups,
+//! failing to set up a volunteer's account does not roll it back; it is reported in the export
+//! outcome instead.
+
+use std::collections::HashSet;
+use std::env;
+use std::path::Path;
+
+use anyhow::{bail, Context as _, Result};
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tera::{Context, Tera};
+
+use super::ExportSettings;
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::workspace::entities::{CreateWorkspaceVolunteer, ProfilePhoto};
+
+/// The environment variable holding the path of the default profile photo.
+pub const DEFAULT_PROFILE_PHOTO_PATH_ENV_VAR: &str = "DEFAULT_PROFILE_PHOTO_PATH";
+
+/// The signature volunteers are given if an export does not provide its own template.
+pub const DEFAULT_SIGNATURE_TEMPLATE: &str =
+    include_str!("../../../../../../templates/signature/gmail.html");
+
+/// The most characters Gmail accepts in a signature.
+pub const MAX_SIGNATURE_LENGTH: usize = 10_000;
+
+/// How to set up exported volunteers' accounts.
+///
+/// * `signature`: Whether to give every volunteer the standard Gmail signature
+/// * `signature_template`: A Tera template to render signatures from instead of the standard one.
+///   It can use `first_name`, `last_name`, `workspace_email`, `program_name`, and `cohort` (the
+///   last two are empty unless the export tags volunteers with a program).
+/// * `profile_photo`: Whether to give every volunteer the default profile photo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonalizationSettings {
+    #[serde(default)]
+    pub signature: bool,
+    #[serde(default)]
+    pub signature_template: Option<String>,
+    #[serde(default)]
+    pub profile_photo: bool,
+}
+
+impl PersonalizationSettings {
+    /// Check that the settings are well formed and that the signature template renders.
+    pub fn validate(&self) -> Result<()> {
+        if !self.signature && !self.profile_photo {
+            bail!("At least one of signature or profile photo must be set up");
+        }
+
+        if self.signature_template.is_some() && !self.signature {
+            bail!("A signature template was given, but signatures are not being set up");
+        }
+
+        if self.signature {
+            let sample = SignatureDetails {
+                first_name: "Jane",
+                last_name: "Doe",
+                workspace_email: "jane.doe@developforgood.org",
+                program_name: Some("Summer Fellowship"),
+                cohort: Some("Spring 2025"),
+            };
+            render_signature(self.template(), &sample)?;
+        }
+
+        Ok(())
+    }
+
+    /// The template signatures are rendered from.
+    fn template(&self) -> &str {
+        self.signature_template.as_deref().unwrap_or(DEFAULT_SIGNATURE_TEMPLATE)
+    }
+
+    /// Load what is needed to set up accounts, failing if the default profile photo is wanted but
+    /// cannot be read.
+    pub fn load(&self) -> Result<Personalization> {
+        let profile_photo = if self.profile_photo {
+            let path = env::var(DEFAULT_PROFILE_PHOTO_PATH_ENV_VAR).with_context(|| {
+                format!("{DEFAULT_PROFILE_PHOTO_PATH_ENV_VAR} must be set to set up profile photos")
+            })?;
+            Some(ProfilePhoto::from_path(Path::new(&path))?)
+        } else {
+            None
+        };
+
+        Ok(Personalization {
+            signature_template: self.signature.then(|| self.template().to_owned()),
+            profile_photo,
+        })
+    }
+}
+
+/// What exported volunteers' accounts are set up with.
+///
+/// * `signature_template`: The template to render each volunteer's signature from, if they are
+///   given one
+/// * `profile_photo`: The photo to give each volunteer, if any
+#[derive(Debug, Clone)]
+pub struct Personalization {
+    pub signature_template: Option<String>,
+    pub profile_photo: Option<ProfilePhoto>,
+}
+
+/// What a volunteer's signature is rendered with.
+///
+/// * `first_name`: The volunteer's first name
+/// * `last_name`: The volunteer's last name
+/// * `workspace_email`: The volunteer's workspace email
+/// * `program_name`: The program the volunteer is part of, if the export tags them with one
+/// * `cohort`: The volunteer's cohort, if the export tags them with a program
+#[derive(Debug, Clone, Serialize)]
+pub struct SignatureDetails<'a> {
+    pub first_name: &'a str,
+    pub last_name: &'a str,
+    pub workspace_email: &'a str,
+    pub program_name: Option<&'a str>,
+    pub cohort: Option<&'a str>,
+}
+
+impl<'a> From<&'a CreateWorkspaceVolunteer> for SignatureDetails<'a> {
+    fn from(value: &'a CreateWorkspaceVolunteer) -> Self {
+        Self {
+            first_name: &value.first_name,
+            last_name: &value.last_name,
+            workspace_email: &value.primary_email,
+            program_name: value.program.as_ref().map(|p| p.program_name.as_str()),
+            cohort: value.program.as_ref().map(|p| p.cohort.as_str()),
+        }
+    }
+}
+
+/// Render a volunteer's signature.
+///
+/// * `template`: The Tera template
+/// * `details`: What the signature is rendered with
+///
+/// Values are HTML escaped, since Gmail signatures are HTML.
+pub fn render_signature(template: &str, details: &SignatureDetails) -> Result<String> {
+    let signature = Tera::one_off(template, &Context::from_serialize(details)?, true)
+        .context("Could not render signature template")?;
+
+    if signature.chars().count() > MAX_SIGNATURE_LENGTH {
+        bail!("Signatures must be at most {MAX_SIGNATURE_LENGTH} characters");
+    }
+
+    Ok(signature)
+}
+
+/// Set up a volunteer's account, retrying transient failures.
+///
+/// * `services`: The services needed to set up the account
+/// * `settings`: Settings for the export
+/// * `personalization`: What to set up the account with
+/// * `details`: The volunteer
+async fn personalize(
+    services: &ExportServices,
+    settings: &ExportSettings<'_>,
+    personalization: &Personalization,
+    details: &SignatureDetails<'_>,
+) -> Result<()> {
+    let email = details.workspace_email;
+
+    if let Some(template) = &personalization.signature_template {
+        let signature = render_signature(template, details)?;
+        settings
+            .retry_policy
+            .run(&format!("Setting the signature of {email}"), || {
+                services.workspace.set_signature(settings.principal, email, &signature)
+            })
+            .await?;
+    }
+
+    if let Some(photo) = &personalization.profile_photo {
+        settings
+            .retry_policy
+            .run(&format!("Setting the profile photo of {email}"), || {
+                services.workspace.set_profile_photo(settings.principal, email, photo)
+            })
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Set up exported volunteers' accounts, if the export personalizes them.
+///
+/// * `services`: The services needed to run the export
+/// * `settings`: Settings for the export
+/// * `volunteers`: The volunteers whose accounts were created
+///
+/// Accounts are set up `settings.concurrency` at a time. Returns the workspace emails of the
+/// volunteers whose accounts could not be fully set up.
+pub(super) async fn personalize_accounts<'a>(
+    services: &ExportServices,
+    settings: &ExportSettings<'_>,
+    volunteers: impl IntoIterator<Item = SignatureDetails<'a>>,
+) -> HashSet<String> {
+    let Some(personalization) = settings.personalization else {
+        return HashSet::new();
+    };
+
+    stream::iter(volunteers)
+        .map(|details| async move {
+            match personalize(services, settings, personalization, &details).await {
+                Ok(()) => None,
+                Err(e) => {
+                    log::error!("Failed to set up the account of {}: {e}", details.workspace_email);
+                    Some(details.workspace_email.to_owned())
+                }
+            }
+        })
+        .buffered(settings.concurrency.max(1))
+        .filter_map(|failed| async move { failed })
+        .collect::<HashSet<String>>()
+        .await
+}
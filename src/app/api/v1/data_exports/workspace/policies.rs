@@ -1,29 +1,348 @@
-use rand::distributions::Alphanumeric;
+use std::env;
+
+use anyhow::{bail, Context, Result};
+use deunicode::deunicode;
+use rand::seq::SliceRandom;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use tera::{Context as TeraContext, Tera};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
 
 use crate::app::api::v1::data_exports::requests::ExportUsersToWorkspaceRequest;
+use crate::services::workspace::entities::TwoStepVerificationSettings;
+use crate::services::workspace::retry::RetryPolicy;
+
+/// The maximum number of workspace emails to try for a single volunteer before giving up.
+pub const MAX_EMAIL_CANDIDATES: usize = 20;
+
+/// The longest local part (the part before the `@`) Google Workspace allows in an email.
+pub const MAX_LOCAL_PART_LENGTH: usize = 64;
+
+/// The domain workspace emails are issued on if an export does not specify one.
+pub const DEFAULT_EMAIL_DOMAIN: &str = "developforgood.org";
+
+/// The most aliases an export can add to each workspace account.
+pub const MAX_EMAIL_ALIASES: usize = 5;
+
+/// The number of seconds in a day.
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// The environment variable operators can set to extend the email blocklist for a deployment.
+///
+/// It holds a JSON-encoded `EmailBlocklist`. Any field that is left out is empty.
+pub const EMAIL_BLOCKLIST_ENV_VAR: &str = "EMAIL_BLOCKLIST";
+
+/// Local parts that belong to role accounts and are never issued to volunteers.
+const RESERVED_LOCAL_PARTS: &[&str] = &[
+    "abuse",
+    "admin",
+    "administrator",
+    "billing",
+    "help",
+    "hostmaster",
+    "info",
+    "mailerdaemon",
+    "noreply",
+    "onboarding",
+    "postmaster",
+    "root",
+    "security",
+    "support",
+    "webmaster",
+];
+
+/// The formats tried, in order, when every email in a volunteer's usual format is blocked.
+const FALLBACK_EMAIL_FORMATS: &[EmailFormat] =
+    &[EmailFormat::InitialDotLast, EmailFormat::FirstLastInitial];
 
 pub struct EmailPolicy {
     pub add_unique_numeric_suffix: bool,
+    pub collision_strategy: CollisionStrategy,
+    pub format: EmailFormat,
+    pub alias_formats: Vec<EmailFormat>,
     pub separator: Option<String>,
     pub use_first_and_last_name: bool,
+    pub domain: String,
+    pub blocklist: EmailBlocklist,
 }
 
-impl EmailPolicy {
-    pub fn build_volunteer_email(&self, first_name: &str, last_name: &str) -> String {
-        let mut base = if self.use_first_and_last_name {
-            format!(
-                "{}{}{}",
-                first_name.to_lowercase(),
-                self.separator.as_ref().unwrap_or(&"".to_string()),
-                last_name.to_lowercase()
-            )
-        } else {
-            first_name.to_lowercase()
+/// Local parts that must never be issued to volunteers, on top of the built-in reserved role
+/// addresses (e.g. `admin@`, `abuse@`, and `postmaster@`).
+///
+/// * `reserved`: Local parts that are rejected when a generated local part is exactly one of them
+/// * `blocked_substrings`: Strings that are rejected anywhere in a generated local part (e.g.
+///   offensive words)
+///
+/// Matching ignores case and the punctuation allowed in local parts, so `ad.min@` is reserved just
+/// like `admin@`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EmailBlocklist {
+    pub reserved: Vec<String>,
+    pub blocked_substrings: Vec<String>,
+}
+
+impl EmailBlocklist {
+    /// Read the blocklist from `EMAIL_BLOCKLIST`, falling back to an empty one if it is not set.
+    pub fn from_env() -> Result<Self> {
+        match env::var(EMAIL_BLOCKLIST_ENV_VAR) {
+            Ok(raw) => serde_json::from_str(&raw).with_context(|| {
+                format!("{EMAIL_BLOCKLIST_ENV_VAR} is not a valid email blocklist")
+            }),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Check an email against the blocklist.
+    ///
+    /// * `email`: The email to check
+    ///
+    /// Returns why the email is blocked, or `None` if it may be issued.
+    pub fn check(&self, email: &str) -> Option<String> {
+        let fold = |s: &str| {
+            s.chars()
+                .filter(|c| !is_local_part_punctuation(*c))
+                .map(|c| c.to_ascii_lowercase())
+                .collect::<String>()
+        };
+        let local_part = fold(email.split('@').next().unwrap_or(email));
+
+        let reserved = RESERVED_LOCAL_PARTS
+            .iter()
+            .map(|r| r.to_string())
+            .chain(self.reserved.iter().map(|r| fold(r)))
+            .any(|r| r == local_part);
+        if reserved {
+            return Some(format!("{email} is a reserved address"));
+        }
+
+        self.blocked_substrings
+            .iter()
+            .map(|s| fold(s))
+            .find(|s| !s.is_empty() && local_part.contains(s.as_str()))
+            .map(|s| format!("{email} contains the blocked string \"{s}\""))
+    }
+}
+
+/// How to pick a different workspace email when the one generated for a volunteer is taken.
+///
+/// Both strategies are deterministic: the same volunteers and the same existing emails always
+/// produce the same workspace emails.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CollisionStrategy {
+    /// Append 2, 3, 4, ... to the local part (e.g. `janedoe2@...`)
+    #[default]
+    NumericSuffix,
+    /// Add the initials of any additional given names (e.g. `maryjdoe@...` for "Mary Jane Doe"),
+    /// then fall back to a numeric suffix
+    MiddleInitial,
+}
+
+/// Fold a name into characters that are allowed in the local part of a workspace email.
+///
+/// * `name`: The name to fold
+///
+/// The name is decomposed (NFKD) so that diacritics become separate combining marks, which are
+/// dropped. Anything that is still not ASCII (letters like `ł` that do not decompose, or names in
+/// non-Latin scripts) is transliterated. Finally, everything other than ASCII letters and digits
+/// is dropped and the result is lowercased.
+pub fn normalize_name(name: &str) -> String {
+    let folded = name.nfkd().filter(|c| !is_combining_mark(*c)).collect::<String>();
+
+    deunicode(&folded)
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Characters other than ASCII letters and digits that may appear in a generated local part.
+fn is_local_part_punctuation(c: char) -> bool {
+    matches!(c, '.' | '-' | '_')
+}
+
+/// The layout of the local part (the part before the `@`) of generated workspace emails.
+///
+/// Every format is built from the normalized names (see `normalize_name`), so `José Müller` is
+/// `jose` and `muller`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EmailFormat {
+    /// The first name, the separator, and the last name (e.g. `jane.doe@...` with a `.`
+    /// separator), or just the first name if `use_first_and_last_name` is not set
+    #[default]
+    FirstSeparatorLast,
+    /// The first and last names joined by a dot (e.g. `jane.doe@...`)
+    FirstDotLast,
+    /// The first initial and the last name joined by a dot (e.g. `j.doe@...`)
+    InitialDotLast,
+    /// The first name followed by the last initial (e.g. `janed@...`)
+    FirstLastInitial,
+    /// A Tera template. It can use `first`, `last`, `first_initial`, `last_initial`, and
+    /// `separator` (e.g. `{{ last }}_{{ first_initial }}` for `doe_j@...`). Anything it renders
+    /// other than letters, digits, `.`, `-`, and `_` is dropped.
+    Custom(String),
+}
+
+impl EmailFormat {
+    /// Build the local part of an email in this format.
+    ///
+    /// * `first`: The normalized first name
+    /// * `last`: The normalized last name
+    /// * `separator`: The separator between the names, for formats that use one
+    /// * `use_first_and_last_name`: Whether the default format should include the last name
+    fn local_part(
+        &self,
+        first: &str,
+        last: &str,
+        separator: &str,
+        use_first_and_last_name: bool,
+    ) -> Result<String> {
+        let first_initial = first.chars().take(1).collect::<String>();
+        let last_initial = last.chars().take(1).collect::<String>();
+
+        let local_part = match self {
+            EmailFormat::FirstSeparatorLast if use_first_and_last_name => {
+                format!("{first}{separator}{last}")
+            }
+            EmailFormat::FirstSeparatorLast => first.to_owned(),
+            EmailFormat::FirstDotLast => format!("{first}.{last}"),
+            EmailFormat::InitialDotLast => format!("{first_initial}.{last}"),
+            EmailFormat::FirstLastInitial => format!("{first}{last_initial}"),
+            EmailFormat::Custom(template) => {
+                let mut context = TeraContext::new();
+                context.insert("first", first);
+                context.insert("last", last);
+                context.insert("first_initial", &first_initial);
+                context.insert("last_initial", &last_initial);
+                context.insert("separator", separator);
+
+                Tera::one_off(template, &context, false)
+                    .with_context(|| format!("Could not render email template {template}"))?
+                    .chars()
+                    .filter(|c| c.is_ascii_alphanumeric() || is_local_part_punctuation(*c))
+                    .map(|c| c.to_ascii_lowercase())
+                    .collect()
+            }
         };
 
-        if self.add_unique_numeric_suffix {
+        Ok(local_part)
+    }
+}
+
+/// Check that a domain is well formed.
+///
+/// * `domain`: The domain (e.g. `developforgood.org`)
+///
+/// This only checks the shape of the domain. Whether it belongs to the Workspace account is
+/// checked with `WorkspaceClient::domain_exists`.
+pub fn validate_domain(domain: &str) -> Result<()> {
+    let valid = domain.len() <= 253
+        && domain.contains('.')
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if !valid {
+        bail!("{domain} is not a valid email domain");
+    }
+    Ok(())
+}
+
+impl EmailPolicy {
+    /// Check that emails can be generated with this policy.
+    ///
+    /// This catches custom templates that do not render, or that render nothing usable, before
+    /// an export starts.
+    pub fn validate(&self) -> Result<()> {
+        validate_domain(&self.domain)?;
+        if let EmailFormat::Custom(_) = self.format {
+            self.build_volunteer_email("Jane", "Doe")?;
+        }
+        if self.alias_formats.len() > MAX_EMAIL_ALIASES {
+            bail!("An export can add at most {MAX_EMAIL_ALIASES} aliases to each user");
+        }
+        for format in &self.alias_formats {
+            if let EmailFormat::Custom(_) = format {
+                self.build_email(format, "Jane", "Doe", false)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Generate the workspace email for a volunteer.
+    ///
+    /// * `first_name`: The volunteer's first name
+    /// * `last_name`: The volunteer's last name
+    ///
+    /// Fails if the email cannot be rendered, or if its local part is empty or longer than the
+    /// `MAX_LOCAL_PART_LENGTH` characters Google Workspace allows.
+    pub fn build_volunteer_email(&self, first_name: &str, last_name: &str) -> Result<String> {
+        self.build_email(&self.format, first_name, last_name, self.add_unique_numeric_suffix)
+    }
+
+    /// Generate the aliases a volunteer's workspace account should have, one for each of
+    /// `alias_formats`.
+    ///
+    /// * `first_name`: The volunteer's first name
+    /// * `last_name`: The volunteer's last name
+    /// * `primary_email`: The workspace email the volunteer is issued, which is never its own
+    ///   alias
+    ///
+    /// Aliases never get a numeric suffix, since an alias is only useful if it is easy to
+    /// remember. Formats that do not produce a usable email for the volunteer are left out, as
+    /// are duplicates.
+    pub fn alias_emails(
+        &self,
+        first_name: &str,
+        last_name: &str,
+        primary_email: &str,
+    ) -> Vec<String> {
+        let mut aliases = Vec::<String>::with_capacity(self.alias_formats.len());
+        for format in &self.alias_formats {
+            if let Ok(alias) = self.build_email(format, first_name, last_name, false) {
+                if alias != primary_email && !aliases.contains(&alias) {
+                    aliases.push(alias);
+                }
+            }
+        }
+        aliases
+    }
+
+    /// Generate the workspace email for a volunteer in a given format.
+    ///
+    /// * `format`: The format of the local part
+    /// * `first_name`: The volunteer's first name
+    /// * `last_name`: The volunteer's last name
+    /// * `numeric_suffix`: Whether to add a random 2-digit suffix to the local part
+    fn build_email(
+        &self,
+        format: &EmailFormat,
+        first_name: &str,
+        last_name: &str,
+        numeric_suffix: bool,
+    ) -> Result<String> {
+        let separator = self
+            .separator
+            .as_deref()
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || is_local_part_punctuation(*c))
+            .collect::<String>();
+
+        let mut base = format.local_part(
+            &normalize_name(first_name),
+            &normalize_name(last_name),
+            &separator,
+            self.use_first_and_last_name,
+        )?;
+
+        if numeric_suffix {
             let mut rng = rand::thread_rng();
             let mut suffix = rng.gen_range(10..100);
             if suffix == 69 {
@@ -33,39 +352,425 @@ impl EmailPolicy {
             base.push_str(&suffix.to_string());
         }
 
-        let mut cleaned = base.chars().filter(|c| c.is_alphanumeric()).collect::<String>();
+        let local_part = base.trim_matches(is_local_part_punctuation);
+        if local_part.is_empty() {
+            bail!("Could not generate a workspace email for {first_name} {last_name}");
+        }
+        if local_part.len() > MAX_LOCAL_PART_LENGTH {
+            bail!(
+                "The workspace email generated for {first_name} {last_name} is longer than \
+                 {MAX_LOCAL_PART_LENGTH} characters before the @"
+            );
+        }
 
-        cleaned.push_str("@developforgood.org");
-        cleaned
+        Ok(format!("{local_part}@{}", self.domain))
     }
+
+    /// Generate the workspace emails a volunteer may be issued, in order of preference.
+    ///
+    /// * `first_name`: The volunteer's first name
+    /// * `last_name`: The volunteer's last name
+    ///
+    /// The first candidate is always the email returned by `build_volunteer_email`. The rest are
+    /// generated according to `collision_strategy`, up to `MAX_EMAIL_CANDIDATES` in total.
+    /// Candidates whose local part would be too long are left out.
+    ///
+    /// Blocked candidates are not removed, since callers report them (see `EmailBlocklist`). If
+    /// the first candidate is blocked, the volunteer's email in each of `FALLBACK_EMAIL_FORMATS`
+    /// is tried right after it.
+    pub fn candidate_emails(&self, first_name: &str, last_name: &str) -> Result<Vec<String>> {
+        let base = self.build_volunteer_email(first_name, last_name)?;
+        let mut candidates = vec![base.clone()];
+
+        if self.blocklist.check(&base).is_some() {
+            for format in FALLBACK_EMAIL_FORMATS.iter().filter(|f| **f != self.format) {
+                if let Ok(candidate) =
+                    self.build_email(format, first_name, last_name, self.add_unique_numeric_suffix)
+                {
+                    if !candidates.contains(&candidate) {
+                        candidates.push(candidate);
+                    }
+                }
+            }
+        }
+
+        if self.collision_strategy == CollisionStrategy::MiddleInitial {
+            let mut given_names = first_name.split_whitespace();
+            if let Some(first) = given_names.next() {
+                let initials = given_names.filter_map(|n| n.chars().next()).collect::<String>();
+                if !initials.is_empty() {
+                    if let Ok(candidate) =
+                        self.build_volunteer_email(&format!("{first}{initials}"), last_name)
+                    {
+                        if !candidates.contains(&candidate) {
+                            candidates.push(candidate);
+                        }
+                    }
+                }
+            }
+        }
+
+        let (local_part, domain) = base.split_at(base.rfind('@').unwrap_or(base.len()));
+        let mut suffix = 2;
+        while candidates.len() < MAX_EMAIL_CANDIDATES {
+            let candidate = format!("{local_part}{suffix}");
+            if candidate.len() > MAX_LOCAL_PART_LENGTH {
+                break;
+            }
+            candidates.push(format!("{candidate}{domain}"));
+            suffix += 1;
+        }
+
+        Ok(candidates)
+    }
+}
+
+/// The environment variable operators can set to tune generated passwords for a deployment.
+///
+/// It holds a JSON-encoded `PasswordConfig`. Any field that is left out keeps its default.
+pub const PASSWORD_CONFIG_ENV_VAR: &str = "PASSWORD_POLICY";
+
+/// The longest password length an export can request.
+pub const MAX_PASSWORD_LENGTH: u8 = 64;
+
+/// The fewest words a generated passphrase contains.
+pub const MIN_PASSPHRASE_WORDS: usize = 4;
+
+/// The words passphrases are drawn from. They are short, common, and easy to type.
+const PASSPHRASE_WORDS: &str = include_str!("passphrase_words.txt");
+
+/// Characters that are easily confused with one another when read off an email.
+const AMBIGUOUS_CHARACTERS: &str = "0O1lI|";
+
+/// A class of characters a generated password can draw from.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CharacterClass {
+    Lowercase,
+    Uppercase,
+    Digit,
+    Symbol,
 }
 
+impl CharacterClass {
+    /// The characters in the class, leaving out ambiguous ones if requested.
+    ///
+    /// * `exclude_ambiguous`: Whether to leave out characters that are easily confused
+    pub fn characters(&self, exclude_ambiguous: bool) -> Vec<char> {
+        let characters = match self {
+            CharacterClass::Lowercase => "abcdefghijklmnopqrstuvwxyz",
+            CharacterClass::Uppercase => "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            CharacterClass::Digit => "0123456789",
+            CharacterClass::Symbol => "!#$%&*+-=?@^_",
+        };
+
+        characters
+            .chars()
+            .filter(|c| !exclude_ambiguous || !AMBIGUOUS_CHARACTERS.contains(*c))
+            .collect()
+    }
+}
+
+/// Deployment-wide settings for generated passwords.
+///
+/// * `min_length`: The shortest password an export may request
+/// * `required_classes`: The character classes passwords are drawn from. Every generated password
+///   contains at least one character from each of them.
+/// * `exclude_ambiguous`: Whether to leave out characters that are easily confused (such as `O`
+///   and `0`, or `l` and `1`)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PasswordConfig {
+    pub min_length: u8,
+    pub required_classes: Vec<CharacterClass>,
+    pub exclude_ambiguous: bool,
+}
+
+impl Default for PasswordConfig {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            required_classes: vec![
+                CharacterClass::Lowercase,
+                CharacterClass::Uppercase,
+                CharacterClass::Digit,
+            ],
+            exclude_ambiguous: false,
+        }
+    }
+}
+
+impl PasswordConfig {
+    /// Read the config from `PASSWORD_POLICY`, falling back to the default if it is not set.
+    pub fn from_env() -> Result<Self> {
+        match env::var(PASSWORD_CONFIG_ENV_VAR) {
+            Ok(raw) => serde_json::from_str(&raw).with_context(|| {
+                format!("{PASSWORD_CONFIG_ENV_VAR} is not a valid password config")
+            }),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Check that passwords can be generated with this config.
+    pub fn validate(&self) -> Result<()> {
+        if self.required_classes.is_empty() {
+            bail!("At least one character class is required for generated passwords");
+        }
+        let min_length = self.effective_min_length();
+        if min_length > MAX_PASSWORD_LENGTH {
+            bail!(
+                "Minimum password length cannot exceed {MAX_PASSWORD_LENGTH} characters, got \
+                 {min_length}"
+            );
+        }
+        Ok(())
+    }
+
+    /// The minimum length, raised if needed so that every required class fits.
+    fn effective_min_length(&self) -> u8 {
+        self.min_length.max(self.required_classes.len() as u8)
+    }
+}
+
+/// The kind of temporary password to generate.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PasswordStyle {
+    /// A random string drawn from the configured character classes (e.g. `q7RtX2mP`)
+    #[default]
+    Random,
+    /// Words joined by dashes and followed by a two-digit number (e.g.
+    /// `maple-otter-canyon-piano-42`), which is easier to type from an onboarding email
+    Passphrase,
+}
+
+/// How temporary passwords reach exported users.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PasswordDelivery {
+    /// Include the password in the onboarding email
+    #[default]
+    Email,
+    /// Text the password to the user's phone, and leave it out of the onboarding email. Users
+    /// without a phone number get it in their onboarding email instead.
+    Sms,
+}
+
+/// How to generate temporary passwords for exported users.
+///
+/// * `change_password_at_next_login`: Whether to force users to change their password at their
+///   next login
+/// * `generated_password_length`: The length of the generated password. Passphrases are at least
+///   this long.
+/// * `style`: The kind of password to generate
+/// * `delivery`: How the password reaches the user
+/// * `config`: The deployment-wide settings for generated passwords
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PasswordPolicy {
     pub change_password_at_next_login: bool,
     pub generated_password_length: u8,
+    pub style: PasswordStyle,
+    pub delivery: PasswordDelivery,
+    pub config: PasswordConfig,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            change_password_at_next_login: true,
+            generated_password_length: 8,
+            style: PasswordStyle::default(),
+            delivery: PasswordDelivery::default(),
+            config: PasswordConfig::default(),
+        }
+    }
 }
 
 impl PasswordPolicy {
+    /// Check that the policy can be honored as requested.
+    ///
+    /// `generate_password` silently falls back to the minimum length when the requested length is
+    /// out of range. This surfaces that case as an error instead.
+    pub fn validate(&self) -> Result<()> {
+        self.config.validate()?;
+        let min_length = self.config.effective_min_length();
+        if !(min_length..=MAX_PASSWORD_LENGTH).contains(&self.generated_password_length) {
+            bail!(
+                "Password length must be between {min_length} and {MAX_PASSWORD_LENGTH} \
+                 characters, got {}",
+                self.generated_password_length
+            );
+        }
+        Ok(())
+    }
+
     pub fn generate_password(&self) -> String {
-        if !(8..=64).contains(&self.generated_password_length) {
+        let min_length = self.config.effective_min_length().min(MAX_PASSWORD_LENGTH);
+        let length = if (min_length..=MAX_PASSWORD_LENGTH).contains(&self.generated_password_length)
+        {
+            self.generated_password_length
+        } else {
             log::warn!(
-                "Password length must be between 8 and 64 characters. Defaulting to 8 characters."
+                "Password length must be between {min_length} and {MAX_PASSWORD_LENGTH} \
+                 characters. Defaulting to {min_length} characters."
             );
+            min_length
+        };
+
+        match self.style {
+            PasswordStyle::Random => self.generate_random_password(length as usize),
+            PasswordStyle::Passphrase => self.generate_passphrase(length as usize),
         }
-        match self.generated_password_length {
-            // minimum, and default, is 8. max is 64
-            0..=7 | 65.. => rand::thread_rng()
-                .sample_iter(&Alphanumeric)
-                .take(8)
-                .map(char::from)
-                .collect::<String>(),
-            8..=64 => rand::thread_rng()
-                .sample_iter(&Alphanumeric)
-                .take(self.generated_password_length as usize)
-                .map(char::from)
-                .collect::<String>(),
+    }
+
+    /// Generate a random string with at least one character from each required class.
+    ///
+    /// * `length`: The length of the password
+    fn generate_random_password(&self, length: usize) -> String {
+        let classes = if self.config.required_classes.is_empty() {
+            PasswordConfig::default().required_classes
+        } else {
+            self.config.required_classes.clone()
+        };
+        let classes =
+            classes.iter().map(|c| c.characters(self.config.exclude_ambiguous)).collect::<Vec<_>>();
+        let alphabet = classes.concat();
+
+        let mut rng = rand::thread_rng();
+        // one character from each required class, then fill the rest from all of them
+        let mut password = classes
+            .iter()
+            .filter_map(|class| class.choose(&mut rng).copied())
+            .collect::<Vec<char>>();
+        while password.len() < length {
+            password.extend(alphabet.choose(&mut rng));
+        }
+        password.shuffle(&mut rng);
+
+        password.into_iter().collect()
+    }
+
+    /// Generate a passphrase of at least `MIN_PASSPHRASE_WORDS` words, adding words until it is
+    /// at least `length` characters long.
+    ///
+    /// * `length`: The minimum length of the passphrase
+    ///
+    /// The character classes in the config do not apply to passphrases, but ambiguous digits are
+    /// still left out of the number if `exclude_ambiguous` is set.
+    fn generate_passphrase(&self, length: usize) -> String {
+        let words = PASSPHRASE_WORDS.lines().collect::<Vec<&str>>();
+        let digits = CharacterClass::Digit.characters(self.config.exclude_ambiguous);
+
+        let mut rng = rand::thread_rng();
+        let number = (0..2).filter_map(|_| digits.choose(&mut rng)).collect::<String>();
+
+        let mut passphrase = Vec::<&str>::with_capacity(MIN_PASSPHRASE_WORDS + 1);
+        // each word adds itself and a dash, and the number adds itself
+        let mut passphrase_len = number.len();
+        while passphrase.len() < MIN_PASSPHRASE_WORDS || passphrase_len < length {
+            let Some(&word) = words.choose(&mut rng) else {
+                break;
+            };
+            passphrase.push(word);
+            passphrase_len += word.len() + 1;
         }
+        passphrase.push(&number);
+
+        passphrase.join("-")
+    }
+}
+
+/// What to do with Workspace accounts that were created by an export that later failed.
+///
+/// If Workspace accounts are created but recording them in Pantheon fails, or their onboarding
+/// emails cannot be sent, the accounts are stranded: nobody knows their temporary passwords and
+/// Pantheon has no record of them. A rollback policy other than `Disabled` undoes those accounts
+/// so the export can be retried cleanly.
+#[derive(Debug, Serialize, Deserialize, Copy, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RollbackPolicy {
+    /// Leave the accounts as they are
+    #[default]
+    Disabled,
+    /// Suspend the accounts
+    Suspend,
+    /// Delete the accounts
+    Delete,
+}
+
+/// The Google Workspace edition to license exported users for.
+///
+/// * `product_id`: The product the license is for (e.g. `Google-Apps`)
+/// * `sku_id`: The edition of the product (e.g. `1010020027` for Business Starter)
+/// * `seats`: The number of licenses purchased for the edition. An export fails before creating
+///   anyone if it would need more licenses than are left. Defaults to the number the provider
+///   reports, which Microsoft 365 does but Google Workspace does not.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceLicense {
+    pub product_id: String,
+    pub sku_id: String,
+    #[serde(default)]
+    pub seats: Option<usize>,
+}
+
+impl WorkspaceLicense {
+    /// Check that the license is well formed.
+    pub fn validate(&self) -> Result<()> {
+        if self.product_id.trim().is_empty() || self.sku_id.trim().is_empty() {
+            bail!("A license needs both a product ID and a SKU ID");
+        }
+        if self.seats == Some(0) {
+            bail!("A license needs at least one seat");
+        }
+        Ok(())
+    }
+}
+
+/// How exported users must be protected by 2-Step Verification. Google only enforces 2-Step
+/// Verification for an org unit as a whole, so this is checked against the settings of the org
+/// unit users are exported to.
+///
+/// * `max_grace_period_days`: The longest new users may go without enrolling before 2-Step
+///   Verification is enforced for them. Defaults to any grace period.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoStepVerificationPolicy {
+    #[serde(default)]
+    pub max_grace_period_days: Option<u32>,
+}
+
+impl TwoStepVerificationPolicy {
+    /// Check that the 2-Step Verification settings of an org unit satisfy the policy.
+    ///
+    /// * `org_unit`: The full path of the org unit, for errors
+    /// * `settings`: The 2-Step Verification settings that apply to the org unit
+    ///
+    /// New users need a grace period to enroll, since they are handed a password and nothing else.
+    /// Without one, they would be locked out of their account the first time they sign in.
+    pub fn check(&self, org_unit: &str, settings: &TwoStepVerificationSettings) -> Result<()> {
+        if !settings.allow_enrollment || settings.enforced_from.is_none() {
+            bail!("Org unit {org_unit} does not enforce 2-Step Verification");
+        }
+
+        let Some(grace_period) = settings.enrollment_grace_period else {
+            bail!(
+                "Org unit {org_unit} enforces 2-Step Verification without giving new users time to \
+                 enroll, so they could not sign in"
+            );
+        };
+
+        let days = grace_period.as_secs().div_ceil(SECONDS_PER_DAY);
+        if let Some(max) = self.max_grace_period_days {
+            if days > u64::from(max) {
+                bail!(
+                    "Org unit {org_unit} gives new users {days} days to enroll in 2-Step \
+                     Verification, which is longer than {max} days"
+                );
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -73,6 +778,17 @@ impl From<&ExportUsersToWorkspaceRequest> for EmailPolicy {
     fn from(request: &ExportUsersToWorkspaceRequest) -> Self {
         Self {
             add_unique_numeric_suffix: request.add_unique_numeric_suffix,
+            collision_strategy: request.email_collision_strategy,
+            format: request.email_format.clone(),
+            alias_formats: request.alias_formats.clone(),
+            domain: request
+                .domain
+                .as_deref()
+                .unwrap_or(DEFAULT_EMAIL_DOMAIN)
+                .trim()
+                .trim_start_matches('@')
+                .to_ascii_lowercase(),
+            blocklist: EmailBlocklist::default(),
             separator: request.separator.clone(),
             use_first_and_last_name: request.use_first_and_last_name,
         }
@@ -84,6 +800,18 @@ impl From<&ExportUsersToWorkspaceRequest> for PasswordPolicy {
         Self {
             change_password_at_next_login: request.change_password_at_next_login,
             generated_password_length: request.generated_password_length,
+            style: request.password_style,
+            delivery: request.password_delivery,
+            config: PasswordConfig::default(),
+        }
+    }
+}
+
+impl From<&ExportUsersToWorkspaceRequest> for RetryPolicy {
+    fn from(request: &ExportUsersToWorkspaceRequest) -> Self {
+        match request.max_workspace_attempts {
+            Some(max_attempts) => Self { max_attempts: max_attempts.max(1), ..Self::default() },
+            None => Self::default(),
         }
     }
 }
@@ -0,0 +1,175 @@
+//! This module pushes changes to exported volunteers' Pantheon profiles (their names and personal
+//! emails) to their workspace accounts.
+//!
+//! Accounts are found by the workspace email recorded when the volunteer was exported, so a
+//! volunteer whose name changes keeps their address. Each account is updated independently, so a
+//! failure for one volunteer does not stop the others.
+
+use anyhow::Result;
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::report_progress;
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::storage::entities::ProfileSyncCandidate;
+use crate::services::storage::jobs::NEW_JOB_VERSION;
+use crate::services::storage::types::JobPhase;
+use crate::services::storage::ExecOptsBuilder;
+use crate::services::workspace::entities::UpdateWorkspaceVolunteer;
+use crate::services::workspace::retry::RetryPolicy;
+
+/// Parameters for a job that syncs volunteers' profiles to their workspace accounts.
+///
+/// * `job_id`: The ID of the job
+/// * `principal`: The email of the user who started the job
+/// * `concurrency`: The maximum number of accounts to update at once
+/// * `volunteers`: The volunteers whose accounts are updated
+pub struct ProfileSyncParams {
+    pub job_id: Uuid,
+    pub principal: String,
+    pub concurrency: usize,
+    pub volunteers: Vec<ProfileSyncCandidate>,
+}
+
+/// A volunteer whose workspace account could not be updated.
+///
+/// * `volunteer_id`: The ID of the volunteer
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `error`: Why the account could not be updated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSyncFailure {
+    pub volunteer_id: Uuid,
+    pub workspace_email: String,
+    pub error: String,
+}
+
+/// What a job that syncs volunteers' profiles did.
+///
+/// * `updated`: The number of accounts that were updated
+/// * `failures`: The accounts that could not be updated
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSyncSummary {
+    pub updated: usize,
+    pub failures: Vec<ProfileSyncFailure>,
+}
+
+/// Push volunteers' names and personal emails to their workspace accounts.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+///
+/// The summary is saved as the job's result, and the job is marked complete, or errored if any
+/// account could not be updated.
+pub async fn profile_sync_task(
+    services: &ExportServices,
+    params: ProfileSyncParams,
+) -> Result<ProfileSyncSummary> {
+    let summary = sync_profiles(services, &params).await;
+
+    if let Err(e) = finish_profile_sync(services, params.job_id, &summary).await {
+        log::error!("Failed to record the outcome of job {}: {e}", params.job_id);
+    }
+
+    Ok(summary)
+}
+
+/// Update every volunteer's account.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+async fn sync_profiles(
+    services: &ExportServices,
+    params: &ProfileSyncParams,
+) -> ProfileSyncSummary {
+    let total = params.volunteers.len();
+    let retry_policy = RetryPolicy::default();
+    let retry_policy = &retry_policy;
+
+    report_progress(services, params.job_id, JobPhase::Provisioning, 0, total).await;
+
+    let mut stream = stream::iter(params.volunteers.iter())
+        .map(|volunteer| async move {
+            let label = format!("Syncing the profile of {}", volunteer.workspace_email);
+            let data = UpdateWorkspaceVolunteer {
+                first_name: volunteer.first_name.clone(),
+                last_name: volunteer.last_name.clone(),
+                recovery_email: volunteer.email.clone(),
+            };
+            let result = retry_policy
+                .run(&label, || {
+                    services.workspace.update_volunteer(
+                        &params.principal,
+                        &volunteer.workspace_email,
+                        data.clone(),
+                    )
+                })
+                .await;
+
+            (volunteer, result)
+        })
+        .buffer_unordered(params.concurrency.max(1));
+
+    let mut summary = ProfileSyncSummary::default();
+    while let Some((volunteer, result)) = stream.next().await {
+        match result {
+            Ok(_) => {
+                log::info!("Synced the profile of {}", volunteer.workspace_email);
+                summary.updated += 1;
+            }
+            Err(e) => {
+                log::error!("Failed to sync the profile of {}: {e}", volunteer.workspace_email);
+                summary.failures.push(ProfileSyncFailure {
+                    volunteer_id: volunteer.volunteer_id,
+                    workspace_email: volunteer.workspace_email.clone(),
+                    error: e.to_string(),
+                });
+            }
+        }
+
+        let done = summary.updated + summary.failures.len();
+        report_progress(services, params.job_id, JobPhase::Provisioning, done, total).await;
+    }
+
+    summary
+}
+
+/// Save the summary of a profile sync job and mark the job complete, or errored if any account
+/// could not be updated.
+///
+/// * `services`: The services needed to run the job
+/// * `job_id`: The ID of the job
+/// * `summary`: What the job did
+async fn finish_profile_sync(
+    services: &ExportServices,
+    job_id: Uuid,
+    summary: &ProfileSyncSummary,
+) -> Result<()> {
+    services
+        .storage_layer
+        .set_job_result(
+            job_id,
+            serde_json::to_value(summary)?,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    if summary.failures.is_empty() {
+        services
+            .storage_layer
+            .mark_job_complete(job_id, NEW_JOB_VERSION, &mut ExecOptsBuilder::default().build()?)
+            .await
+    } else {
+        services
+            .storage_layer
+            .mark_job_errored(
+                job_id,
+                NEW_JOB_VERSION,
+                format!("Failed to sync the profiles of {} users", summary.failures.len()),
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await
+    }
+}
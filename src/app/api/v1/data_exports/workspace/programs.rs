@@ -0,0 +1,64 @@
+//! This module tags exported volunteers' Google Workspace accounts with program metadata.
+//!
+//! The metadata is stored in a custom user schema, which is created the first time an export needs
+//! it. Unlike groups or drives, the metadata is set when each account is created, so there is no
+//! separate step that can fail for individual volunteers.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::storage::entities::VolunteerDetails;
+use crate::services::workspace::entities::ProgramMetadata;
+
+/// The program every volunteer in an export is tagged with.
+///
+/// * `name`: The name of the program (e.g. `Summer Fellowship`). Each volunteer's cohort is the
+///   name of their project cycle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgramSettings {
+    pub name: String,
+}
+
+impl ProgramSettings {
+    /// Check that the program settings are well formed.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            bail!("The program name must not be empty");
+        }
+
+        Ok(())
+    }
+
+    /// The metadata to store on a volunteer's account.
+    ///
+    /// * `volunteer`: The volunteer being exported
+    pub fn metadata(&self, volunteer: &VolunteerDetails) -> ProgramMetadata {
+        ProgramMetadata {
+            program_name: self.name.trim().to_owned(),
+            cohort: volunteer.project_cycle_name.clone(),
+            volunteer_id: volunteer.volunteer_id,
+        }
+    }
+}
+
+/// Make sure the custom user schema that holds program metadata exists, creating it if it does
+/// not. Google rejects accounts with values for a schema that does not exist, so this must run
+/// before any volunteer is exported.
+///
+/// * `services`: The services needed to run the export
+/// * `principal`: The email of the user requesting the export
+pub async fn ensure_program_schema(services: &ExportServices, principal: &str) -> Result<()> {
+    let created = services
+        .workspace
+        .ensure_program_schema(principal)
+        .await
+        .context("Failed to set up the program schema")?;
+
+    if created {
+        log::info!("Created the program schema in workspace");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,720 @@
+//! This module runs export jobs on a pool of workers, through the job queue.
+//!
+//! Starting (or resuming) an export queues it, along with everything it needs to run, in the same
+//! transaction that records the job, and returns right away. Workers claim the queued jobs of each
+//! organization one at a time and renew their claim while a job runs. If the process stops partway
+//! through a job, its claim runs out and a worker (of this process once it restarts, or of another
+//! one) claims it again. An export that is claimed again after it started is resumed from its
+//! checkpoints rather than started over, so users it already created are not created twice. A
+//! worker that cannot renew its claim in time (say it lost its connection to the database) stops
+//! running the job, so a job is never run by two workers at once.
+//!
+//! A job that fails is run again, after a backoff that doubles with every attempt, until it has
+//! been tried `JOB_MAX_ATTEMPTS` times. A job that fails on every attempt is dead-lettered, with
+//! why each attempt failed kept in the queue, and is not run again until staff requeue it. A job
+//! that fails in a way that trying it again would not fix (say its org unit does not exist, or too
+//! few license seats are free) fails with a `PermanentJobError` and is dead-lettered right away.
+//!
+//! Each attempt may only run for as long as the timeout for its kind of job. An attempt that runs
+//! over, say because an external API hangs, is aborted and counts as a failed attempt, so the
+//! worker moves on to other jobs.
+
+use std::env;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::{export_task, fetch_current_job, resume_export_job, ExportParams};
+use crate::app::api::v1::data_exports::requests::ExportUsersToWorkspaceRequest;
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::storage::jobs::is_job_conflict;
+use crate::services::storage::organizations::with_org;
+use crate::services::storage::queue::{is_claim_lost, AttemptError, ClaimLostError};
+use crate::services::storage::types::{JobPriority, JobStatus};
+use crate::services::storage::{Acquire, ExecOpts, ExecOptsBuilder};
+
+/// The name of the environment variable holding how many workers run queued jobs. `0` runs none,
+/// leaving the queue to other processes.
+pub const JOB_QUEUE_WORKERS_ENV_VAR: &str = "JOB_QUEUE_WORKERS";
+
+/// How many workers run queued jobs, unless configured otherwise.
+pub const DEFAULT_JOB_QUEUE_WORKERS: usize = 4;
+
+/// How long a worker's claim on a job lasts. Workers renew their claims well before they run out,
+/// so this only bounds how long a job whose worker stopped waits to be claimed again.
+pub const QUEUE_CLAIM_DURATION: Duration = Duration::from_secs(5 * 60);
+
+/// How often a worker renews its claim on the job it is running.
+pub const QUEUE_CLAIM_RENEW_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long a worker waits before looking for queued jobs again once it has run them all.
+pub const QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Read how many workers run queued jobs from `JOB_QUEUE_WORKERS`.
+pub fn queue_workers_from_env() -> Result<usize> {
+    match env::var(JOB_QUEUE_WORKERS_ENV_VAR) {
+        Ok(raw) => raw
+            .trim()
+            .parse()
+            .with_context(|| format!("{JOB_QUEUE_WORKERS_ENV_VAR} must be a number of workers")),
+        Err(_) => Ok(DEFAULT_JOB_QUEUE_WORKERS),
+    }
+}
+
+/// The name of the environment variable holding how many times a queued job is tried before it is
+/// dead-lettered. `1` never runs a job again once it fails.
+pub const JOB_MAX_ATTEMPTS_ENV_VAR: &str = "JOB_MAX_ATTEMPTS";
+
+/// The name of the environment variable holding how many seconds a job that failed waits before it
+/// is tried again. The wait doubles after every attempt that fails.
+pub const JOB_RETRY_BACKOFF_ENV_VAR: &str = "JOB_RETRY_BACKOFF_SECS";
+
+/// How many times a queued job is tried, unless configured otherwise.
+pub const DEFAULT_JOB_MAX_ATTEMPTS: i32 = 3;
+
+/// How long a job that failed waits before it is tried again, unless configured otherwise.
+pub const DEFAULT_JOB_RETRY_BACKOFF: Duration = Duration::from_secs(60);
+
+/// The longest a job that failed waits before it is tried again, however many attempts it failed.
+pub const MAX_JOB_RETRY_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+/// How the jobs that fail are run again.
+///
+/// * `max_attempts`: How many times a job is tried before it is dead-lettered
+/// * `backoff`: How long a job waits before it is tried again after its first attempt fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobRetryPolicy {
+    pub max_attempts: i32,
+    pub backoff: Duration,
+}
+
+impl Default for JobRetryPolicy {
+    fn default() -> Self {
+        Self { max_attempts: DEFAULT_JOB_MAX_ATTEMPTS, backoff: DEFAULT_JOB_RETRY_BACKOFF }
+    }
+}
+
+impl JobRetryPolicy {
+    /// Read the policy from `JOB_MAX_ATTEMPTS` and `JOB_RETRY_BACKOFF_SECS`.
+    pub fn from_env() -> Result<Self> {
+        let mut policy = Self::default();
+        if let Ok(raw) = env::var(JOB_MAX_ATTEMPTS_ENV_VAR) {
+            policy.max_attempts = match raw.trim().parse() {
+                Ok(attempts) if attempts > 0 => attempts,
+                _ => bail!("{JOB_MAX_ATTEMPTS_ENV_VAR} must be a positive number of attempts"),
+            };
+        }
+        if let Ok(raw) = env::var(JOB_RETRY_BACKOFF_ENV_VAR) {
+            let secs = raw.trim().parse().with_context(|| {
+                format!("{JOB_RETRY_BACKOFF_ENV_VAR} must be a number of seconds")
+            })?;
+            policy.backoff = Duration::from_secs(secs);
+        }
+        Ok(policy)
+    }
+
+    /// When to next try a job that failed, or `None` to dead-letter it.
+    ///
+    /// * `attempts`: How many times the job has been tried, including the attempt that just failed
+    /// * `now`: The current time
+    pub fn next_attempt_at(&self, attempts: i32, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if attempts >= self.max_attempts {
+            return None;
+        }
+
+        let doublings = attempts.max(1) as u32 - 1;
+        let backoff = self
+            .backoff
+            .checked_mul(2u32.saturating_pow(doublings))
+            .map_or(MAX_JOB_RETRY_BACKOFF, |backoff| backoff.min(MAX_JOB_RETRY_BACKOFF));
+        Some(now + backoff)
+    }
+}
+
+/// The name of the environment variable holding how many seconds a queued job may run before it is
+/// aborted, for the kinds of jobs with no timeout of their own. `off` lets them run for as long as
+/// they take.
+pub const JOB_TIMEOUT_ENV_VAR: &str = "JOB_TIMEOUT_SECS";
+
+/// The name of the environment variable holding how many seconds an export to Google Workspace may
+/// run before it is aborted.
+pub const WORKSPACE_EXPORT_TIMEOUT_ENV_VAR: &str = "JOB_TIMEOUT_SECS_WORKSPACE_EXPORT";
+
+/// The name of the environment variable holding how many seconds resuming an export to Google
+/// Workspace may run before it is aborted.
+pub const RESUME_WORKSPACE_EXPORT_TIMEOUT_ENV_VAR: &str =
+    "JOB_TIMEOUT_SECS_RESUME_WORKSPACE_EXPORT";
+
+/// How long a queued job may run before it is aborted, unless configured otherwise. Long enough
+/// for an export of a large cohort, whose onboarding emails are sent under the mail rate limit.
+pub const DEFAULT_JOB_TIMEOUT: Duration = Duration::from_secs(4 * 60 * 60);
+
+/// Why a queued job was aborted before it finished.
+///
+/// * `timeout`: How long the job ran for
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("job timed out after {} seconds", timeout.as_secs())]
+pub struct JobTimeoutError {
+    pub timeout: Duration,
+}
+
+/// Why a queued job failed in a way that trying it again would not fix, such as a request that is
+/// not valid or too few free license seats. It displays as the error it wraps.
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct PermanentJobError(pub anyhow::Error);
+
+/// Whether a queued job failed in a way that trying it again would not fix.
+///
+/// * `e`: The error
+pub fn is_permanent_job_error(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| cause.downcast_ref::<PermanentJobError>().is_some())
+}
+
+/// Read a timeout from an environment variable.
+///
+/// * `var`: The name of the environment variable, holding a number of seconds or `off`
+/// * `default`: The timeout if the variable is not set
+fn timeout_from_env(var: &str, default: Option<Duration>) -> Result<Option<Duration>> {
+    let Ok(raw) = env::var(var) else {
+        return Ok(default);
+    };
+
+    match raw.trim() {
+        "off" => Ok(None),
+        raw => match raw.parse() {
+            Ok(secs) if secs > 0 => Ok(Some(Duration::from_secs(secs))),
+            _ => bail!("{var} must be a positive number of seconds, or off"),
+        },
+    }
+}
+
+/// How long each kind of queued job may run before it is aborted. `None` lets a kind of job run
+/// for as long as it takes.
+///
+/// * `workspace_export`: How long an export to Google Workspace may run
+/// * `resume_workspace_export`: How long resuming an export to Google Workspace may run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobTimeouts {
+    pub workspace_export: Option<Duration>,
+    pub resume_workspace_export: Option<Duration>,
+}
+
+impl Default for JobTimeouts {
+    fn default() -> Self {
+        Self {
+            workspace_export: Some(DEFAULT_JOB_TIMEOUT),
+            resume_workspace_export: Some(DEFAULT_JOB_TIMEOUT),
+        }
+    }
+}
+
+impl JobTimeouts {
+    /// Read the timeouts from `JOB_TIMEOUT_SECS`, overridden for each kind of job by
+    /// `JOB_TIMEOUT_SECS_<KIND>`.
+    pub fn from_env() -> Result<Self> {
+        let default = timeout_from_env(JOB_TIMEOUT_ENV_VAR, Some(DEFAULT_JOB_TIMEOUT))?;
+        Ok(Self {
+            workspace_export: timeout_from_env(WORKSPACE_EXPORT_TIMEOUT_ENV_VAR, default)?,
+            resume_workspace_export: timeout_from_env(
+                RESUME_WORKSPACE_EXPORT_TIMEOUT_ENV_VAR,
+                default,
+            )?,
+        })
+    }
+
+    /// How long a task may run before it is aborted, if it may not run for as long as it takes.
+    ///
+    /// * `task`: The task
+    pub fn for_task(&self, task: &QueuedTask) -> Option<Duration> {
+        match task {
+            QueuedTask::WorkspaceExport(_) => self.workspace_export,
+            QueuedTask::ResumeWorkspaceExport { .. } => self.resume_workspace_export,
+        }
+    }
+}
+
+/// An export to Google Workspace waiting in the queue.
+///
+/// * `project_cycle_id`: The ID of the project cycle the volunteers are exported from
+/// * `principal`: The email of the user who requested the export
+/// * `org_unit`: The org unit to create users in, with the default already filled in
+/// * `request`: The request that started the export
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedExport {
+    pub project_cycle_id: Uuid,
+    pub principal: String,
+    pub org_unit: String,
+    pub request: ExportUsersToWorkspaceRequest,
+}
+
+/// What a job in the queue runs.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum QueuedTask {
+    /// Export volunteers to Google Workspace
+    WorkspaceExport(QueuedExport),
+    /// Resume an export to Google Workspace that stopped before it finished
+    ResumeWorkspaceExport { principal: String },
+}
+
+impl QueuedTask {
+    /// Queue the task to be run by a worker.
+    ///
+    /// * `services`: The services needed to run the export
+    /// * `job_id`: The ID of the job the task runs
+    /// * `priority`: How urgently the task should run
+    /// * `run_at`: When the task may run at the earliest, or `None` to run it as soon as a worker
+    ///   is free
+    /// * `exec_opts`: Execution options for the query, so the task can be queued in the same
+    ///   transaction that records its job
+    ///
+    /// Returns whether the task was queued, which it is not if the job is already waiting or
+    /// running.
+    pub async fn enqueue(
+        &self,
+        services: &ExportServices,
+        job_id: Uuid,
+        priority: JobPriority,
+        run_at: Option<DateTime<Utc>>,
+        exec_opts: &mut ExecOpts<'_>,
+    ) -> Result<bool> {
+        let payload = serde_json::to_value(self)?;
+        services.storage_layer.enqueue_job(job_id, payload, priority, run_at, exec_opts).await
+    }
+}
+
+/// When a claim on a job taken now runs out.
+fn claimed_until() -> DateTime<Utc> {
+    Utc::now() + QUEUE_CLAIM_DURATION
+}
+
+/// Run a task claimed from the queue.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the job the task runs
+/// * `task`: The task
+/// * `attempts`: How many times the job has been claimed, including this time
+async fn run_task(
+    services: &ExportServices,
+    job_id: Uuid,
+    task: QueuedTask,
+    attempts: i32,
+) -> Result<()> {
+    match task {
+        QueuedTask::WorkspaceExport(export) => {
+            // An earlier attempt may have created some of the users before it stopped, even one
+            // made before a dead-lettered job was requeued and its attempts started over. Only the
+            // volunteers it checkpointed can have been created, so an attempt that checkpointed
+            // none can safely start over.
+            let checkpoints = services
+                .storage_layer
+                .fetch_export_checkpoints(job_id, &mut ExecOptsBuilder::default().build()?)
+                .await?;
+            if !checkpoints.is_empty() {
+                log::info!(
+                    "Export job {job_id} stopped partway through (attempt {attempts}), resuming it"
+                );
+                return resume_export_job(services, job_id, &export.principal).await;
+            }
+
+            // The export is finished at the version the job was at when this attempt started, so
+            // it is left as it is if another worker resumed it in the meantime
+            let job = fetch_current_job(services, job_id).await?;
+            let params = match export_params(services, job_id, job.version, export).await {
+                Ok(params) => params,
+                Err(e) => {
+                    // The export never started, so nothing else will mark the job as failed
+                    services
+                        .storage_layer
+                        .mark_job_errored(
+                            job_id,
+                            job.version,
+                            format!("{e:#}"),
+                            &mut ExecOptsBuilder::default().build()?,
+                        )
+                        .await?;
+                    return Err(e);
+                }
+            };
+            export_task(services, params).await.map(|_| ())
+        }
+        QueuedTask::ResumeWorkspaceExport { principal } => {
+            resume_export_job(services, job_id, &principal).await
+        }
+    }
+}
+
+/// Build the parameters of a queued export, checking that what it exports to still exists. The
+/// request was checked when the export was queued, so an export whose cycle or org unit is gone
+/// fails with a `PermanentJobError`, as does one whose request is not valid.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the export job
+/// * `job_version`: The version the job is at
+/// * `export`: The queued export
+async fn export_params(
+    services: &ExportServices,
+    job_id: Uuid,
+    job_version: i32,
+    export: QueuedExport,
+) -> Result<ExportParams> {
+    let cycle = services
+        .storage_layer
+        .fetch_cycle_by_id(export.project_cycle_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    if cycle.is_none() {
+        let e = anyhow!("Project cycle {} does not exist", export.project_cycle_id);
+        return Err(PermanentJobError(e).into());
+    }
+    if !services.workspace.org_unit_exists(&export.principal, &export.org_unit).await? {
+        let e = anyhow!("Org unit {} does not exist in the workspace", export.org_unit);
+        return Err(PermanentJobError(e).into());
+    }
+
+    ExportParams::from_request(
+        job_id,
+        job_version,
+        export.project_cycle_id,
+        export.principal,
+        export.org_unit,
+        &export.request,
+    )
+    .map_err(|e| PermanentJobError(e).into())
+}
+
+/// Renew a worker's claim on the job it is running.
+///
+/// * `services`: The services needed to run the export
+/// * `worker_id`: The ID of the worker
+/// * `job_id`: The ID of the job
+///
+/// Returns when the claim now runs out, or a `ClaimLostError` if the worker no longer holds it.
+async fn renew_claim(
+    services: &ExportServices,
+    worker_id: Uuid,
+    job_id: Uuid,
+) -> Result<DateTime<Utc>> {
+    let until = claimed_until();
+    let renewed = services
+        .storage_layer
+        .renew_queued_job_claim(job_id, worker_id, until, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    if !renewed {
+        return Err(ClaimLostError { job_id }.into());
+    }
+    Ok(until)
+}
+
+/// Run a future while renewing a worker's claim on the job it runs, so that no other worker claims
+/// the job while it is still running. The future is dropped, stopping the job, as soon as the
+/// worker can no longer be sure it holds the claim: when another worker took it over, or when the
+/// claim ran out before it could be renewed. Any onboarding emails the job held in the outbox are
+/// sent by the outbox relay once its claim on them runs out.
+///
+/// * `services`: The services needed to run the export
+/// * `worker_id`: The ID of the worker
+/// * `job_id`: The ID of the job
+/// * `claimed_until`: When the claim the worker took on the job runs out
+/// * `f`: The future running the job
+async fn with_renewed_claim<F: Future<Output = Result<()>>>(
+    services: &ExportServices,
+    worker_id: Uuid,
+    job_id: Uuid,
+    claimed_until: DateTime<Utc>,
+    f: F,
+) -> Result<()> {
+    tokio::pin!(f);
+    let mut held_until = claimed_until;
+    let mut renew = tokio::time::interval(QUEUE_CLAIM_RENEW_INTERVAL);
+    // The first tick completes right away, and the claim was only just taken
+    renew.tick().await;
+
+    loop {
+        tokio::select! {
+            result = &mut f => return result,
+            _ = renew.tick() => match renew_claim(services, worker_id, job_id).await {
+                Ok(until) => held_until = until,
+                Err(e) if is_claim_lost(&e) => {
+                    log::error!("Worker {worker_id} lost its claim on job {job_id}, stopping it");
+                    return Err(e);
+                }
+                // The claim runs out before the next renewal, and another worker may then claim it
+                Err(e) if Utc::now() + QUEUE_CLAIM_RENEW_INTERVAL >= held_until => {
+                    log::error!(
+                        "Worker {worker_id} could not renew its claim on job {job_id} before it \
+                         runs out, stopping it: {e}"
+                    );
+                    return Err(ClaimLostError { job_id }.into());
+                }
+                Err(e) => log::error!("Failed to renew claim on job {job_id}: {e}"),
+            },
+        }
+    }
+}
+
+/// Mark a job that ran over its timeout as errored. The attempt that ran over has already been
+/// dropped, which stops the requests it was waiting on and the renewal of its claim. Any onboarding
+/// emails it held in the outbox are sent by the outbox relay once its claim on them runs out.
+///
+/// * `services`: The services needed to run the export
+/// * `job_id`: The ID of the job
+/// * `timeout`: How long the job ran for
+///
+/// Returns the error the attempt failed with, for the retry policy to handle like any other.
+async fn abort_timed_out_job(
+    services: &ExportServices,
+    job_id: Uuid,
+    timeout: Duration,
+) -> anyhow::Error {
+    let error = JobTimeoutError { timeout };
+    log::error!("Job {job_id} ran for longer than {}s, aborting it", timeout.as_secs());
+
+    let marked = async {
+        // The worker still holds its claim on the job, so the version the job is at now is the one
+        // its attempt left it at
+        let job = fetch_current_job(services, job_id).await?;
+        services
+            .storage_layer
+            .mark_job_errored(
+                job_id,
+                job.version,
+                error.to_string(),
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await
+    }
+    .await;
+    match marked {
+        // The job was finished or cancelled just as it timed out
+        Err(e) if is_job_conflict(&e) => {}
+        Err(e) => log::error!("Failed to mark timed out job {job_id} as errored: {e}"),
+        Ok(()) => {}
+    }
+
+    error.into()
+}
+
+/// Decide what happens to a queued job that failed: release it to be tried again after its backoff,
+/// or dead-letter it once it has been tried as many times as the policy allows. A job that was
+/// finished or cancelled while it ran is only marked as finished.
+///
+/// * `services`: The services needed to run the export
+/// * `worker_id`: The ID of the worker that ran the job
+/// * `job_id`: The ID of the job
+/// * `attempts`: How many times the job has been tried, including the attempt that just failed
+/// * `policy`: How the jobs that fail are run again
+/// * `error`: Why the attempt failed
+///
+/// Returns whether the worker still held the claim on the job. Nothing is changed if it did not.
+async fn fail_queued_job(
+    services: &ExportServices,
+    worker_id: Uuid,
+    job_id: Uuid,
+    attempts: i32,
+    policy: &JobRetryPolicy,
+    error: &anyhow::Error,
+) -> Result<bool> {
+    let attempt_error = AttemptError::new(attempts, error);
+    let mut tx = services.storage_layer.acquire().await?;
+    let mut exec_opts = ExecOptsBuilder::default().tx(&mut tx).build()?;
+    let job = services.storage_layer.fetch_job(job_id, &mut exec_opts).await?;
+
+    let held = match job.status {
+        JobStatus::Complete | JobStatus::Cancelled | JobStatus::DeadLettered => {
+            let error = Some(format!("{error:#}"));
+            services
+                .storage_layer
+                .finish_queued_job(job_id, worker_id, error, &mut exec_opts)
+                .await?
+        }
+        JobStatus::Pending | JobStatus::Error => {
+            let permanent = is_permanent_job_error(error);
+            let retry_at =
+                if permanent { None } else { policy.next_attempt_at(attempts, Utc::now()) };
+            match retry_at {
+                Some(retry_at) => {
+                    log::info!("Job {job_id} will be tried again at {retry_at}");
+                    // The job is pending again while it waits, so the next attempt can finish it
+                    if job.status == JobStatus::Error {
+                        services
+                            .storage_layer
+                            .resume_job(job_id, job.version, &mut exec_opts)
+                            .await?;
+                    }
+                    services
+                        .storage_layer
+                        .retry_queued_job(
+                            job_id,
+                            worker_id,
+                            retry_at,
+                            attempt_error,
+                            &mut exec_opts,
+                        )
+                        .await?
+                }
+                None => {
+                    if permanent {
+                        log::error!("Job {job_id} would fail again if tried, dead-lettering it");
+                    } else {
+                        log::error!(
+                            "Job {job_id} failed on all {attempts} attempts, dead-lettering it"
+                        );
+                    }
+                    services
+                        .storage_layer
+                        .mark_job_dead_lettered(job_id, format!("{error:#}"), &mut exec_opts)
+                        .await?;
+                    services
+                        .storage_layer
+                        .dead_letter_queued_job(job_id, worker_id, attempt_error, &mut exec_opts)
+                        .await?
+                }
+            }
+        }
+    };
+
+    if held {
+        tx.commit().await?;
+    }
+    Ok(held)
+}
+
+/// Claim the oldest job waiting in the current organization's queue and run it. A job is not
+/// claimed until the job it depends on has completed, and is cancelled if that job fails for good.
+///
+/// * `services`: The services needed to run the export, scoped to the current organization
+/// * `worker_id`: The ID of the worker
+/// * `policy`: How the jobs that fail are run again
+/// * `timeouts`: How long each kind of job may run before it is aborted
+///
+/// Returns whether there was a job to run. A job that fails, or runs over its timeout, is run
+/// again as the policy allows, and why every attempt failed is recorded in the queue.
+pub async fn run_next_queued_job(
+    services: &ExportServices,
+    worker_id: Uuid,
+    policy: &JobRetryPolicy,
+    timeouts: &JobTimeouts,
+) -> Result<bool> {
+    // Jobs whose dependency failed would wait forever, so they are cancelled before claiming
+    let cancelled = services
+        .storage_layer
+        .cancel_jobs_with_failed_dependencies(&mut ExecOptsBuilder::default().build()?)
+        .await?;
+    for job_id in cancelled {
+        log::info!("Job {job_id} was cancelled, since a job it depends on did not complete");
+    }
+
+    let queued = services
+        .storage_layer
+        .claim_queued_job(worker_id, claimed_until(), &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    let Some(queued) = queued else {
+        return Ok(false);
+    };
+
+    let job_id = queued.job_id;
+    log::info!("Worker {worker_id} is running job {job_id} (attempt {})", queued.attempts);
+
+    let result = match serde_json::from_value::<QueuedTask>(queued.payload) {
+        Ok(task) => {
+            let timeout = timeouts.for_task(&task);
+            let run = run_task(services, job_id, task, queued.attempts);
+            let run = with_renewed_claim(services, worker_id, job_id, queued.claimed_until, run);
+            match timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, run).await {
+                    Ok(result) => result,
+                    Err(_) => Err(abort_timed_out_job(services, job_id, timeout).await),
+                },
+                None => run.await,
+            }
+        }
+        Err(e) => Err(anyhow::Error::new(e).context("invalid queued task")),
+    };
+
+    let finished = match result {
+        Ok(()) => {
+            services
+                .storage_layer
+                .finish_queued_job(
+                    job_id,
+                    worker_id,
+                    None,
+                    &mut ExecOptsBuilder::default().build()?,
+                )
+                .await?
+        }
+        // The worker that holds the claim now runs the job, and records how it went
+        Err(e) if is_claim_lost(&e) => return Ok(true),
+        Err(e) if is_job_conflict(&e) => {
+            log::info!("Job {job_id} was already finished by another worker");
+            services
+                .storage_layer
+                .finish_queued_job(
+                    job_id,
+                    worker_id,
+                    None,
+                    &mut ExecOptsBuilder::default().build()?,
+                )
+                .await?
+        }
+        Err(e) => {
+            log::error!("Queued job {job_id} failed: {e:#}");
+            fail_queued_job(services, worker_id, job_id, queued.attempts, policy, &e).await?
+        }
+    };
+    if !finished {
+        log::warn!("Worker {worker_id} lost its claim on job {job_id} before it finished");
+    }
+    Ok(true)
+}
+
+/// Start the workers that run queued jobs, for every organization.
+///
+/// * `services`: The services needed to run the export
+/// * `workers`: How many workers to start
+/// * `policy`: How the jobs that fail are run again
+/// * `timeouts`: How long each kind of job may run before it is aborted
+pub fn spawn_queue_workers(
+    services: ExportServices,
+    workers: usize,
+    policy: JobRetryPolicy,
+    timeouts: JobTimeouts,
+) -> Vec<JoinHandle<()>> {
+    let services = Arc::new(services);
+    (0..workers)
+        .map(|_| {
+            let services = services.clone();
+            tokio::spawn(async move {
+                let worker_id = Uuid::new_v4();
+                loop {
+                    for org_id in services.org_ids().await {
+                        let services = services.for_org(org_id);
+                        loop {
+                            let run = run_next_queued_job(&services, worker_id, &policy, &timeouts);
+                            match with_org(org_id, run).await {
+                                Ok(true) => {}
+                                Ok(false) => break,
+                                Err(e) => {
+                                    log::error!(
+                                        "Failed to run queued jobs for organization {org_id}: {e}"
+                                    );
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                    tokio::time::sleep(QUEUE_POLL_INTERVAL).await;
+                }
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,348 @@
+//! This module reconciles the volunteers Pantheon has exported to an org unit with the accounts
+//! that are actually in it.
+//!
+//! The two drift apart when accounts are created, deleted or moved in the Google Admin console.
+//! A reconciliation job lists every account in the org unit, compares them with Pantheon's export
+//! records and reports the orphans on either side. It can also fix the orphans it knows how to:
+//! records of accounts that no longer exist are removed, and accounts whose recovery email belongs
+//! to a volunteer with no workspace email are recorded as that volunteer's export. Everything else
+//! is left for an admin to look at.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::storage::entities::ExportedVolunteerDetails;
+use crate::services::storage::jobs::{CreateJobBuilder, NEW_JOB_VERSION};
+use crate::services::storage::types::{JobData, JobDetails, JobType};
+use crate::services::storage::volunteers::InsertVolunteerExportedToWorkspace;
+use crate::services::storage::ExecOptsBuilder;
+use crate::services::workspace::entities::WorkspaceAccount;
+
+/// Parameters for a job that reconciles workspace accounts with Pantheon.
+///
+/// * `job_id`: The ID of the job
+/// * `principal`: The email of the user who started the job
+/// * `org_unit`: The full path of the org unit to reconcile. Accounts in its child org units are
+///   included.
+/// * `fix`: Whether to fix the orphans that can be fixed, instead of only reporting them
+pub struct ReconcileParams {
+    pub job_id: Uuid,
+    pub principal: String,
+    pub org_unit: String,
+    pub fix: bool,
+}
+
+/// A volunteer Pantheon has recorded as exported, whose account is not in the org unit.
+///
+/// * `volunteer_id`: The ID of the volunteer
+/// * `workspace_email`: The workspace email Pantheon has recorded for the volunteer
+/// * `org_unit`: The org unit Pantheon has recorded the account in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingFromWorkspace {
+    pub volunteer_id: Uuid,
+    pub workspace_email: String,
+    pub org_unit: String,
+}
+
+/// An account in the org unit that Pantheon has no export record of.
+///
+/// * `workspace_email`: The primary email of the account
+/// * `recovery_email`: The recovery email of the account, if it has one
+/// * `suspended`: Whether the account is suspended
+/// * `org_unit_path`: The org unit the account is in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingFromPantheon {
+    pub workspace_email: String,
+    pub recovery_email: Option<String>,
+    pub suspended: bool,
+    pub org_unit_path: String,
+}
+
+/// What a job that reconciles workspace accounts with Pantheon found, and fixed.
+///
+/// * `org_unit`: The org unit that was reconciled
+/// * `matched`: The number of accounts Pantheon has a matching export record of
+/// * `missing_from_workspace`: The export records whose account is not in the org unit
+/// * `missing_from_pantheon`: The accounts in the org unit with no export record
+/// * `removed`: The number of export records that were removed because their account no longer
+///   exists
+/// * `recorded`: The number of accounts that were recorded as the export of the volunteer their
+///   recovery email belongs to
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationReport {
+    pub org_unit: String,
+    pub matched: usize,
+    pub missing_from_workspace: Vec<MissingFromWorkspace>,
+    pub missing_from_pantheon: Vec<MissingFromPantheon>,
+    pub removed: usize,
+    pub recorded: usize,
+}
+
+/// Compare the accounts in an org unit with Pantheon's export records of it. Emails are compared
+/// case-insensitively, like Google does.
+///
+/// * `org_unit`: The org unit being reconciled
+/// * `exported`: Pantheon's export records of the org unit
+/// * `accounts`: The accounts in the org unit
+pub fn diff(
+    org_unit: &str,
+    exported: &[ExportedVolunteerDetails],
+    accounts: &[WorkspaceAccount],
+) -> ReconciliationReport {
+    let mut accounts_by_email = accounts
+        .iter()
+        .map(|a| (a.primary_email.to_lowercase(), a))
+        .collect::<HashMap<String, &WorkspaceAccount>>();
+
+    let mut report = ReconciliationReport { org_unit: org_unit.to_owned(), ..Default::default() };
+
+    for record in exported {
+        match accounts_by_email.remove(&record.workspace_email.to_lowercase()) {
+            Some(_) => report.matched += 1,
+            None => report.missing_from_workspace.push(MissingFromWorkspace {
+                volunteer_id: record.volunteer_id,
+                workspace_email: record.workspace_email.clone(),
+                org_unit: record.org_unit.clone(),
+            }),
+        }
+    }
+
+    report.missing_from_pantheon = accounts_by_email
+        .into_values()
+        .map(|a| MissingFromPantheon {
+            workspace_email: a.primary_email.clone(),
+            recovery_email: a.recovery_email.clone(),
+            suspended: a.suspended,
+            org_unit_path: a.org_unit_path.clone(),
+        })
+        .collect();
+    report.missing_from_pantheon.sort_by(|a, b| a.workspace_email.cmp(&b.workspace_email));
+
+    report
+}
+
+/// Record a job that reconciles the accounts in an org unit with Pantheon.
+///
+/// * `services`: The services needed to run the job
+/// * `principal`: The email of the user starting the job
+/// * `org_unit`: The full path of the org unit to reconcile
+/// * `fix`: Whether to fix the orphans that can be fixed, instead of only reporting them
+///
+/// Returns the parameters to run the job with, with `reconcile_task`.
+pub async fn create_reconcile_job(
+    services: &ExportServices,
+    principal: String,
+    org_unit: String,
+    fix: bool,
+) -> Result<ReconcileParams> {
+    let data = CreateJobBuilder::default()
+        .label("Reconcile Users")
+        .description(Some(format!("Compare the users in {org_unit} with Pantheon")))
+        .data(JobDetails {
+            job_type: JobType::ReconcileWorkspaceUsers,
+            error: None,
+            result: None,
+            data: JobData::ReconcileWorkspaceUsers { org_unit: org_unit.clone(), fix },
+        })
+        .build()?;
+
+    let job_id = services
+        .storage_layer
+        .create_job(
+            None,
+            data,
+            &mut ExecOptsBuilder::default().principal(principal.clone()).build()?,
+        )
+        .await?;
+
+    Ok(ReconcileParams { job_id, principal, org_unit, fix })
+}
+
+/// Reconcile the accounts in an org unit with Pantheon's export records of it.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+///
+/// The report is saved as the job's result, and the job is marked complete, or errored if the
+/// accounts could not be listed or an orphan could not be fixed.
+pub async fn reconcile_task(
+    services: &ExportServices,
+    params: ReconcileParams,
+) -> Result<ReconciliationReport> {
+    let job_id = params.job_id;
+    let result = reconcile(services, &params).await;
+
+    let finished = match &result {
+        Ok(report) => finish_reconciliation(services, job_id, report).await,
+        Err(e) => {
+            log::error!("Job {job_id} failed to reconcile {}: {e}", params.org_unit);
+            services
+                .storage_layer
+                .mark_job_errored(
+                    job_id,
+                    NEW_JOB_VERSION,
+                    e.to_string(),
+                    &mut ExecOptsBuilder::default().build()?,
+                )
+                .await
+        }
+    };
+
+    if let Err(e) = finished {
+        log::error!("Failed to record the outcome of job {job_id}: {e}");
+    }
+
+    result
+}
+
+/// Diff the org unit against Pantheon, and fix the orphans if the job asks to.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+async fn reconcile(
+    services: &ExportServices,
+    params: &ReconcileParams,
+) -> Result<ReconciliationReport> {
+    let accounts = services
+        .workspace
+        .list_org_unit_users(&params.principal, &params.org_unit)
+        .await
+        .with_context(|| format!("Failed to list the users in {}", params.org_unit))?;
+
+    let exported = services
+        .storage_layer
+        .fetch_exported_volunteer_details_by_org_unit(
+            &params.org_unit,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    let mut report = diff(&params.org_unit, &exported, &accounts);
+
+    log::info!(
+        "Job {} found {} matched users in {}, {} missing from workspace and {} missing from \
+         Pantheon",
+        params.job_id,
+        report.matched,
+        params.org_unit,
+        report.missing_from_workspace.len(),
+        report.missing_from_pantheon.len()
+    );
+
+    if params.fix {
+        fix_orphans(services, params, &mut report).await?;
+    }
+
+    Ok(report)
+}
+
+/// Fix the orphans that can be fixed, and count them in the report.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+/// * `report`: What the job found
+async fn fix_orphans(
+    services: &ExportServices,
+    params: &ReconcileParams,
+    report: &mut ReconciliationReport,
+) -> Result<()> {
+    // An account missing from the org unit may have been moved rather than deleted, so its record
+    // is only removed if the account no longer exists at all
+    let mut removed = vec![];
+    for orphan in &report.missing_from_workspace {
+        let account =
+            services.workspace.find_user(&params.principal, &orphan.workspace_email).await?;
+        if account.is_none() {
+            removed.push(orphan.volunteer_id);
+        }
+    }
+
+    let mut recorded: Vec<InsertVolunteerExportedToWorkspace> = vec![];
+    for orphan in &report.missing_from_pantheon {
+        let Some(recovery_email) = &orphan.recovery_email else {
+            continue;
+        };
+
+        let volunteer = services
+            .storage_layer
+            .fetch_volunteer_by_email(recovery_email, &mut ExecOptsBuilder::default().build()?)
+            .await?;
+
+        let Some(volunteer) = volunteer.filter(|v| v.workspace_email.is_none()) else {
+            continue;
+        };
+
+        // A volunteer with two unrecorded accounts is only recorded with the first of them
+        if recorded.iter().all(|r| r.volunteer_id != volunteer.volunteer_id) {
+            recorded.push(InsertVolunteerExportedToWorkspace {
+                volunteer_id: volunteer.volunteer_id,
+                job_id: params.job_id,
+                workspace_email: orphan.workspace_email.clone(),
+                org_unit: orphan.org_unit_path.clone(),
+            });
+        }
+    }
+
+    report.removed = removed.len();
+    report.recorded = recorded.len();
+
+    let mut tx = services.storage_layer.acquire().await?;
+    let mut exec_opts = ExecOptsBuilder::default().tx(&mut tx).build()?;
+
+    if !removed.is_empty() {
+        services
+            .storage_layer
+            .batch_remove_volunteers_exported_to_workspace(removed, &mut exec_opts)
+            .await?;
+    }
+
+    if !recorded.is_empty() {
+        services
+            .storage_layer
+            .batch_insert_volunteers_exported_to_workspace(recorded, &mut exec_opts)
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    log::info!(
+        "Job {} removed {} export records and recorded {} accounts",
+        params.job_id,
+        report.removed,
+        report.recorded
+    );
+
+    Ok(())
+}
+
+/// Save the report of a reconciliation job and mark the job complete.
+///
+/// * `services`: The services needed to run the job
+/// * `job_id`: The ID of the job
+/// * `report`: What the job found
+async fn finish_reconciliation(
+    services: &ExportServices,
+    job_id: Uuid,
+    report: &ReconciliationReport,
+) -> Result<()> {
+    services
+        .storage_layer
+        .set_job_result(
+            job_id,
+            serde_json::to_value(report)?,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    services
+        .storage_layer
+        .mark_job_complete(job_id, NEW_JOB_VERSION, &mut ExecOptsBuilder::default().build()?)
+        .await
+}
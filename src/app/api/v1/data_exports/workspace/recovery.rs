@@ -0,0 +1,130 @@
+//! This module checks volunteers' recovery emails before they are exported.
+//!
+//! A volunteer's recovery email is where their onboarding email is sent. If it is malformed, or
+//! its domain cannot receive mail, the onboarding email goes nowhere and the volunteer never
+//! learns their workspace credentials. Such volunteers are set aside for someone to look at
+//! instead of being exported.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Result};
+use futures::{stream, StreamExt};
+use hickory_resolver::error::ResolveErrorKind;
+use hickory_resolver::TokioAsyncResolver;
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::policies::validate_domain;
+
+/// The maximum number of domains to look up at once.
+const DNS_LOOKUP_CONCURRENCY: usize = 8;
+
+/// A volunteer that was not exported because their recovery email needs to be fixed first.
+///
+/// * `volunteer_id`: The ID of the volunteer
+/// * `workspace_email`: The workspace email the volunteer would have been issued
+/// * `recovery_email`: The volunteer's recovery email
+/// * `reason`: What is wrong with the recovery email
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NeedsAttention {
+    pub volunteer_id: Uuid,
+    pub workspace_email: String,
+    pub recovery_email: String,
+    pub reason: String,
+}
+
+/// Check that a recovery email is syntactically valid.
+///
+/// * `email`: The recovery email
+///
+/// This is deliberately stricter than RFC 5322: quoted local parts and IP address domains are
+/// rejected, since no volunteer signs up with one and they are far more likely to be typos.
+pub fn validate_recovery_email(email: &str) -> Result<()> {
+    let Some((local_part, domain)) = email.rsplit_once('@') else {
+        bail!("{email} is missing an @");
+    };
+
+    if local_part.is_empty() || local_part.len() > 64 {
+        bail!("{email} must have between 1 and 64 characters before the @");
+    }
+    if local_part.starts_with('.') || local_part.ends_with('.') || local_part.contains("..") {
+        bail!("{email} has a misplaced dot before the @");
+    }
+    let allowed = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~.".contains(c);
+    if !local_part.chars().all(allowed) {
+        bail!("{email} has characters that are not allowed before the @");
+    }
+
+    validate_domain(domain)
+}
+
+/// Find the domains, out of the given recovery emails, that cannot receive mail.
+///
+/// * `emails`: The recovery emails
+///
+/// A domain cannot receive mail if it has neither MX records nor an address to fall back to (see
+/// RFC 5321, section 5.1). Domains that cannot be looked up for any other reason (for example, a
+/// DNS timeout) are assumed to be able to receive mail, so a flaky resolver does not hold up an
+/// export.
+pub async fn find_undeliverable_domains<'a>(
+    emails: impl Iterator<Item = &'a str>,
+) -> Result<HashSet<String>> {
+    let resolver = TokioAsyncResolver::tokio_from_system_conf()?;
+    let resolver = &resolver;
+
+    let domains = emails
+        .filter_map(|e| e.rsplit_once('@').map(|(_, domain)| domain.to_ascii_lowercase()))
+        .collect::<HashSet<String>>();
+
+    let undeliverable = stream::iter(domains)
+        .map(|domain| async move {
+            let deliverable = match resolver.mx_lookup(domain.as_str()).await {
+                Ok(mx) => mx.iter().next().is_some(),
+                Err(e) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => {
+                    match resolver.lookup_ip(domain.as_str()).await {
+                        Ok(ips) => ips.iter().next().is_some(),
+                        Err(e) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => {
+                            false
+                        }
+                        Err(e) => {
+                            log::warn!("Could not look up {domain}: {e}");
+                            true
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Could not look up MX records for {domain}: {e}");
+                    true
+                }
+            };
+            (!deliverable).then_some(domain)
+        })
+        .buffer_unordered(DNS_LOOKUP_CONCURRENCY)
+        .filter_map(|domain| async move { domain })
+        .collect::<HashSet<String>>()
+        .await;
+
+    Ok(undeliverable)
+}
+
+/// Check a volunteer's recovery email.
+///
+/// * `email`: The recovery email
+/// * `undeliverable_domains`: The domains found by `find_undeliverable_domains`, if they were
+///   looked up
+///
+/// Returns what is wrong with the recovery email, if anything.
+pub fn check_recovery_email(
+    email: &str,
+    undeliverable_domains: &HashSet<String>,
+) -> Option<String> {
+    if let Err(e) = validate_recovery_email(email) {
+        return Some(e.to_string());
+    }
+
+    let (_, domain) = email.rsplit_once('@')?;
+    undeliverable_domains
+        .contains(&domain.to_ascii_lowercase())
+        .then(|| format!("{domain} cannot receive email"))
+}
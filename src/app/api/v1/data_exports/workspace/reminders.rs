@@ -0,0 +1,241 @@
+//! This module reminds exported volunteers who have not signed in to their workspace account.
+//!
+//! A volunteer who never activates their account misses everything sent to it, so once a project
+//! cycle's configured number of days have passed since an export, each exported volunteer's last
+//! sign in is looked up in Workspace. Volunteers who have never signed in are sent a reminder.
+//! Every volunteer is checked once, and the outcome of the check is recorded, along with the last
+//! sign in it found.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::Serialize;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::record_email_send;
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::mail::suppression::suppression_reason;
+use crate::services::mail::{localized_template, OnboardingEmailKind, OnboardingEmailParams};
+use crate::services::storage::entities::DueActivationReminder;
+use crate::services::storage::logins::RecordWorkspaceLogin;
+use crate::services::storage::organizations::with_org;
+use crate::services::storage::reminders::RecordActivationReminder;
+use crate::services::storage::types::ActivationReminderStatus;
+use crate::services::storage::ExecOptsBuilder;
+use crate::services::workspace::entities::WorkspaceAccount;
+
+/// How often the background task looks for volunteers who are due to be checked.
+pub const ACTIVATION_REMINDER_POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Held while volunteers are being checked, so the background task and a manual run never remind
+/// the same volunteer twice.
+static REMINDER_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// The outcome of checking exported volunteers for activation reminders.
+///
+/// * `sent`: The number of volunteers who were sent a reminder
+/// * `activated`: The number of volunteers who had already signed in
+/// * `skipped`: The number of volunteers who were not reminded because their account is missing
+///   or suspended, or their address is suppressed
+/// * `failed`: The number of volunteers who could not be checked or reminded, and will be tried
+///   again on the next run
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivationReminderSummary {
+    pub sent: usize,
+    pub activated: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Decide what to do about a volunteer given their workspace account.
+///
+/// * `account`: The volunteer's workspace account, or `None` if it does not exist
+///
+/// Returns `ActivationReminderStatus::Sent` if the volunteer should be sent a reminder, along with
+/// more about the decision, if anything.
+pub fn reminder_status(
+    account: Option<&WorkspaceAccount>,
+) -> (ActivationReminderStatus, Option<String>) {
+    match account {
+        None => (ActivationReminderStatus::Skipped, Some("The account does not exist".to_owned())),
+        Some(account) if account.suspended => {
+            (ActivationReminderStatus::Skipped, Some("The account is suspended".to_owned()))
+        }
+        Some(WorkspaceAccount { last_login_at: Some(at), .. }) => {
+            (ActivationReminderStatus::Activated, Some(format!("Last signed in at {at}")))
+        }
+        Some(_) => (ActivationReminderStatus::Sent, None),
+    }
+}
+
+/// Check the exported volunteers who are due and remind the ones who have not signed in.
+///
+/// * `services`: The services needed to run the export
+/// * `project_cycle_id`: Only check volunteers in this project cycle, if set
+///
+/// Volunteers whose account cannot be looked up, or whose reminder fails to send, are not
+/// recorded, so they are tried again on the next run.
+pub async fn send_activation_reminders(
+    services: &ExportServices,
+    project_cycle_id: Option<Uuid>,
+) -> Result<ActivationReminderSummary> {
+    let _guard = REMINDER_LOCK.lock().await;
+    let mut summary = ActivationReminderSummary::default();
+
+    let due = services
+        .storage_layer
+        .fetch_due_activation_reminders(project_cycle_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    let mut records = Vec::<RecordActivationReminder>::new();
+    let mut logins = Vec::<RecordWorkspaceLogin>::new();
+    let mut reminders = Vec::<(DueActivationReminder, OnboardingEmailParams)>::new();
+    for volunteer in due {
+        let account = match services
+            .workspace
+            .find_user(&volunteer.principal, &volunteer.workspace_email)
+            .await
+        {
+            Ok(account) => account,
+            Err(e) => {
+                log::warn!(
+                    "Failed to look up {} to check for an activation reminder: {e}",
+                    volunteer.workspace_email
+                );
+                summary.failed += 1;
+                continue;
+            }
+        };
+
+        if let Some(account) = &account {
+            logins.push(RecordWorkspaceLogin {
+                volunteer_id: volunteer.volunteer_id,
+                workspace_email: volunteer.workspace_email.clone(),
+                last_login_at: account.last_login_at,
+            });
+        }
+
+        match reminder_status(account.as_ref()) {
+            (ActivationReminderStatus::Sent, _) => {
+                let email = OnboardingEmailParams::from(&volunteer);
+                reminders.push((volunteer, email));
+            }
+            (status, detail) => {
+                match status {
+                    ActivationReminderStatus::Activated => summary.activated += 1,
+                    _ => summary.skipped += 1,
+                }
+                records.push(RecordActivationReminder {
+                    job_id: volunteer.job_id,
+                    volunteer_id: volunteer.volunteer_id,
+                    workspace_email: volunteer.workspace_email,
+                    status,
+                    detail,
+                });
+            }
+        }
+    }
+
+    let results = services
+        .mail
+        .send_onboarding_emails(reminders.iter().map(|(_, email)| email.clone()).collect())
+        .await;
+
+    for ((volunteer, email), result) in reminders.into_iter().zip(results) {
+        let template = localized_template(email.kind.template(), &email.locale, "html");
+        record_email_send(services, &email, &template, &result).await;
+
+        let (status, detail) = match result {
+            Ok(_) => {
+                log::info!("Sent activation reminder to {}", volunteer.email);
+                summary.sent += 1;
+                (ActivationReminderStatus::Sent, None)
+            }
+            // A suppressed address would be refused on every run
+            Err(e) if suppression_reason(&e).is_some() => {
+                summary.skipped += 1;
+                (ActivationReminderStatus::Skipped, Some(e.to_string()))
+            }
+            Err(e) => {
+                log::warn!("Failed to send activation reminder to {}: {e}", volunteer.email);
+                summary.failed += 1;
+                continue;
+            }
+        };
+
+        records.push(RecordActivationReminder {
+            job_id: volunteer.job_id,
+            volunteer_id: volunteer.volunteer_id,
+            workspace_email: volunteer.workspace_email,
+            status,
+            detail,
+        });
+    }
+
+    services
+        .storage_layer
+        .record_activation_reminders(records, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    // Sign ins are informational, so failing to record them does not fail the run
+    if let Err(e) = services
+        .storage_layer
+        .record_workspace_logins(logins, &mut ExecOptsBuilder::default().build()?)
+        .await
+    {
+        log::warn!("Failed to record the sign ins found by activation reminders: {e}");
+    }
+
+    Ok(summary)
+}
+
+/// Start the background task that checks exported volunteers for activation reminders.
+///
+/// * `services`: The services needed to run the export
+pub fn spawn_activation_reminder_task(services: ExportServices) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ACTIVATION_REMINDER_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            for org_id in services.org_ids().await {
+                match with_org(org_id, send_activation_reminders(&services, None)).await {
+                    Ok(summary) if summary != ActivationReminderSummary::default() => log::info!(
+                        "Sent {} activation reminders for organization {org_id}, {} volunteers \
+                         had signed in, {} were skipped and {} failed",
+                        summary.sent,
+                        summary.activated,
+                        summary.skipped,
+                        summary.failed
+                    ),
+                    Ok(_) => {}
+                    Err(e) => log::error!(
+                        "Failed to send activation reminders for organization {org_id}: {e}"
+                    ),
+                }
+            }
+        }
+    })
+}
+
+impl From<&DueActivationReminder> for OnboardingEmailParams {
+    fn from(value: &DueActivationReminder) -> Self {
+        OnboardingEmailParams {
+            first_name: value.first_name.clone(),
+            last_name: value.last_name.clone(),
+            email: value.email.clone(),
+            workspace_email: value.workspace_email.clone(),
+            temporary_password: None,
+            send_at: None,
+            template: None,
+            locale: value.locale.clone(),
+            attachments: Vec::new(),
+            job_id: Some(value.job_id),
+            volunteer_id: Some(value.volunteer_id),
+            kind: OnboardingEmailKind::ActivationReminder,
+            onboarding_session_link: None,
+        }
+    }
+}
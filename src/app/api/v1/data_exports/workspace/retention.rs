@@ -0,0 +1,189 @@
+//! This module purges volunteers' data once it is past its retention period.
+//!
+//! Each kind of data has its own retention policy:
+//! - Onboarding emails queued to be sent again hold temporary passwords, so they are purged a
+//!   number of days after they were queued, whether or not they were sent.
+//! - Exported volunteers are purged a number of months after their project cycle was archived,
+//!   which is when the program ended. This policy is off unless it is configured.
+//!
+//! A dry run reports how much data each policy would purge, without purging it.
+
+use std::env;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Months, TimeDelta, Utc};
+use serde::Serialize;
+use tokio::task::JoinHandle;
+
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::storage::organizations::with_org;
+use crate::services::storage::ExecOptsBuilder;
+
+/// The name of the environment variable holding how many days queued onboarding emails, and the
+/// temporary passwords in them, are kept for. `off` keeps them until they are sent.
+pub const TEMPORARY_PASSWORD_RETENTION_DAYS_ENV_VAR: &str = "TEMPORARY_PASSWORD_RETENTION_DAYS";
+
+/// The name of the environment variable holding how many months exported volunteers are kept for
+/// after their project cycle is archived. They are kept forever if it is not set.
+pub const EXPORTED_VOLUNTEER_RETENTION_MONTHS_ENV_VAR: &str = "EXPORTED_VOLUNTEER_RETENTION_MONTHS";
+
+/// The name of the environment variable that, when `true`, makes the background task report what
+/// it would purge instead of purging it.
+pub const RETENTION_DRY_RUN_ENV_VAR: &str = "RETENTION_DRY_RUN";
+
+/// How many days queued onboarding emails are kept for, unless configured otherwise.
+pub const DEFAULT_TEMPORARY_PASSWORD_RETENTION_DAYS: u32 = 30;
+
+/// How often the background task purges expired data.
+pub const PURGE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long each kind of data is kept for. A policy that is `None` keeps its data forever.
+///
+/// * `temporary_password_days`: How many days queued onboarding emails are kept for
+/// * `exported_volunteer_months`: How many months exported volunteers are kept for after their
+///   project cycle is archived
+/// * `dry_run`: Whether the background task only reports what it would purge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionConfig {
+    pub temporary_password_days: Option<u32>,
+    pub exported_volunteer_months: Option<u32>,
+    pub dry_run: bool,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            temporary_password_days: Some(DEFAULT_TEMPORARY_PASSWORD_RETENTION_DAYS),
+            exported_volunteer_months: None,
+            dry_run: false,
+        }
+    }
+}
+
+impl RetentionConfig {
+    /// Read the configuration from `TEMPORARY_PASSWORD_RETENTION_DAYS`,
+    /// `EXPORTED_VOLUNTEER_RETENTION_MONTHS`, and `RETENTION_DRY_RUN`.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        if let Ok(raw) = env::var(TEMPORARY_PASSWORD_RETENTION_DAYS_ENV_VAR) {
+            config.temporary_password_days = parse_period(&raw)
+                .with_context(|| format!("invalid {TEMPORARY_PASSWORD_RETENTION_DAYS_ENV_VAR}"))?;
+        }
+        if let Ok(raw) = env::var(EXPORTED_VOLUNTEER_RETENTION_MONTHS_ENV_VAR) {
+            config.exported_volunteer_months = parse_period(&raw).with_context(|| {
+                format!("invalid {EXPORTED_VOLUNTEER_RETENTION_MONTHS_ENV_VAR}")
+            })?;
+        }
+        if let Ok(raw) = env::var(RETENTION_DRY_RUN_ENV_VAR) {
+            config.dry_run = raw.trim().eq_ignore_ascii_case("true");
+        }
+        Ok(config)
+    }
+
+    /// The times before which data is past its retention period, as (queued onboarding emails,
+    /// archived project cycles). A policy that is off has no cutoff.
+    ///
+    /// * `now`: The current time
+    pub fn cutoffs(&self, now: DateTime<Utc>) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+        let queued_before =
+            self.temporary_password_days.map(|days| now - TimeDelta::days(days.into()));
+        let archived_before = self
+            .exported_volunteer_months
+            .and_then(|months| now.checked_sub_months(Months::new(months)));
+        (queued_before, archived_before)
+    }
+}
+
+/// Parse a retention period, a positive number of days or months, or `off` to keep data forever.
+///
+/// * `raw`: The retention period
+pub fn parse_period(raw: &str) -> Result<Option<u32>> {
+    let raw = raw.trim();
+    if raw.eq_ignore_ascii_case("off") {
+        return Ok(None);
+    }
+    match raw.parse::<u32>() {
+        Ok(period) if period > 0 => Ok(Some(period)),
+        _ => bail!("{raw} is not a retention period, expected a positive number or off"),
+    }
+}
+
+/// How much data a purge removed, or would remove in a dry run. A policy that is off has no
+/// count.
+///
+/// * `dry_run`: Whether nothing was actually purged
+/// * `email_retries`: How many queued onboarding emails were purged
+/// * `exported_volunteers`: How many exported volunteers were purged
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeReport {
+    pub dry_run: bool,
+    pub email_retries: Option<u64>,
+    pub exported_volunteers: Option<u64>,
+}
+
+/// Purge the data that is past its retention period.
+///
+/// * `services`: The services needed to run the export
+/// * `config`: How long each kind of data is kept for
+/// * `dry_run`: Only count the data that would be purged
+///
+/// Each policy is purged in its own transaction, so a policy that fails does not undo the others.
+pub async fn purge_expired_data(
+    services: &ExportServices,
+    config: &RetentionConfig,
+    dry_run: bool,
+) -> Result<PurgeReport> {
+    let (queued_before, archived_before) = config.cutoffs(Utc::now());
+    let mut report = PurgeReport { dry_run, ..Default::default() };
+
+    if let Some(queued_before) = queued_before {
+        let purged = services
+            .storage_layer
+            .purge_email_retries(queued_before, dry_run, &mut ExecOptsBuilder::default().build()?)
+            .await?;
+        report.email_retries = Some(purged);
+    }
+
+    if let Some(archived_before) = archived_before {
+        let purged = services
+            .storage_layer
+            .purge_exported_volunteers(
+                archived_before,
+                dry_run,
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?;
+        report.exported_volunteers = Some(purged);
+    }
+
+    Ok(report)
+}
+
+/// Start the background task that purges expired data every day.
+///
+/// * `services`: The services needed to run the export
+/// * `config`: How long each kind of data is kept for
+pub fn spawn_purge_task(services: ExportServices, config: RetentionConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            for org_id in services.org_ids().await {
+                match with_org(org_id, purge_expired_data(&services, &config, config.dry_run)).await
+                {
+                    Ok(report) if report.dry_run => log::info!(
+                        "Expired data that would be purged for organization {org_id}: {report:?}"
+                    ),
+                    Ok(report) => {
+                        log::info!("Purged expired data for organization {org_id}: {report:?}")
+                    }
+                    Err(e) => {
+                        log::error!("Failed to purge expired data for organization {org_id}: {e}")
+                    }
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,269 @@
+//! This module runs named jobs on cron schedules.
+//!
+//! Staff schedule the jobs that should run regularly, such as a nightly reconciliation of the
+//! default org unit, a weekly admin digest, or a purge of expired data, through the schedules API.
+//! A background task checks every minute which schedules are due and runs them. Each run is claimed
+//! by moving the schedule's next run forward first, so a run is started once however many
+//! processes are up. Runs that were missed while no process was up are not made up for; the job
+//! runs once when it is next checked, then carries on with its schedule.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::digest::{send_admin_digest, DigestConfig, ADMIN_DIGEST_RECIPIENTS_ENV_VAR};
+use super::reconciliation::{create_reconcile_job, reconcile_task};
+use super::retention::{purge_expired_data, RetentionConfig};
+use super::{default_org_unit, validate_org_unit_path};
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::storage::entities::JobSchedule;
+use crate::services::storage::organizations::with_org;
+use crate::services::storage::ExecOptsBuilder;
+
+/// How often the background task checks whether any scheduled job is due.
+pub const SCHEDULER_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The jobs that can be run on a schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScheduledJob {
+    /// Reconcile an org unit with Pantheon's export records of it
+    Reconciliation,
+    /// Email admins a digest of the exports, if one is due
+    AdminDigest,
+    /// Purge the data that is past its retention period
+    RetentionPurge,
+}
+
+impl ScheduledJob {
+    /// Every job that can be scheduled.
+    pub const ALL: [Self; 3] = [Self::Reconciliation, Self::AdminDigest, Self::RetentionPurge];
+
+    /// The name the job is scheduled under.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Reconciliation => "reconciliation",
+            Self::AdminDigest => "admin-digest",
+            Self::RetentionPurge => "retention-purge",
+        }
+    }
+
+    /// Parse the name of a job.
+    ///
+    /// * `name`: The name the job is scheduled under (e.g. `admin-digest`)
+    pub fn parse(name: &str) -> Result<Self> {
+        match Self::ALL.into_iter().find(|job| job.name() == name) {
+            Some(job) => Ok(job),
+            None => {
+                let names = Self::ALL.map(Self::name).join(", ");
+                bail!("{name} is not a job that can be scheduled, expected one of {names}")
+            }
+        }
+    }
+}
+
+/// What a scheduled reconciliation runs with. It runs as the user who last saved its schedule.
+///
+/// * `org_unit`: The full path of the org unit to reconcile, or the default org unit if not set
+/// * `fix`: Whether to fix the orphans that can be fixed, instead of only reporting them
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ReconciliationSchedule {
+    pub org_unit: Option<String>,
+    #[serde(default)]
+    pub fix: bool,
+}
+
+/// Parse a cron expression, in UTC.
+///
+/// * `cron`: The cron expression. Standard five field expressions (minute, hour, day of month,
+///   month and day of week) are accepted, as are expressions with a leading seconds field and a
+///   trailing year field. Days of the week are best given by name (e.g. `Mon`), since they are
+///   numbered from 1 for Sunday rather than from 0.
+pub fn parse_cron(cron: &str) -> Result<Schedule> {
+    let cron = cron.trim();
+    let expression = match cron.split_whitespace().count() {
+        5 => format!("0 {cron}"),
+        _ => cron.to_owned(),
+    };
+    Schedule::from_str(&expression).with_context(|| format!("{cron:?} is not a cron expression"))
+}
+
+/// When a cron expression next fires after a time.
+///
+/// * `cron`: The cron expression
+/// * `after`: The time
+pub fn next_run_after(cron: &str, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    match parse_cron(cron)?.after(&after).next() {
+        Some(next) => Ok(next),
+        None => bail!("{cron:?} never fires again"),
+    }
+}
+
+/// Check that what a scheduled job runs with is usable before it is scheduled.
+///
+/// * `job`: The scheduled job
+/// * `params`: What the job runs with
+pub fn validate_params(job: ScheduledJob, params: &Value) -> Result<()> {
+    match job {
+        ScheduledJob::Reconciliation => {
+            let schedule = serde_json::from_value::<ReconciliationSchedule>(params.clone())
+                .context("Invalid reconciliation parameters")?;
+            if let Some(org_unit) = schedule.org_unit.as_deref() {
+                validate_org_unit_path(org_unit)?;
+            }
+        }
+        ScheduledJob::AdminDigest | ScheduledJob::RetentionPurge => {
+            let empty = match params {
+                Value::Null => true,
+                Value::Object(map) => map.is_empty(),
+                _ => false,
+            };
+            if !empty {
+                bail!("{} does not take any parameters", job.name());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Run a scheduled job.
+///
+/// * `services`: The services needed to run the job
+/// * `job`: The scheduled job
+/// * `schedule`: The job's schedule, with what it runs with
+///
+/// Returns the ID of the job the run started, if it started one.
+async fn run_scheduled_job(
+    services: &ExportServices,
+    job: ScheduledJob,
+    schedule: &JobSchedule,
+) -> Result<Option<Uuid>> {
+    match job {
+        ScheduledJob::Reconciliation => {
+            let params = serde_json::from_value::<ReconciliationSchedule>(schedule.params.clone())?;
+            let Some(principal) = schedule.updated_by.clone() else {
+                bail!("The reconciliation has no user to run as, save its schedule again");
+            };
+            let org_unit = match params.org_unit {
+                Some(org_unit) => org_unit,
+                None => default_org_unit(services).await?,
+            };
+
+            let params = create_reconcile_job(services, principal, org_unit, params.fix).await?;
+            let job_id = params.job_id;
+            reconcile_task(services, params)
+                .await
+                .with_context(|| format!("Job {job_id} failed"))?;
+            Ok(Some(job_id))
+        }
+        ScheduledJob::AdminDigest => {
+            let config = DigestConfig::from_env()?
+                .with_context(|| format!("{ADMIN_DIGEST_RECIPIENTS_ENV_VAR} is not set"))?;
+            let sent = send_admin_digest(services, &config).await?;
+            log::info!("The scheduled admin digest was sent to {sent} admins");
+            Ok(None)
+        }
+        ScheduledJob::RetentionPurge => {
+            let config = RetentionConfig::from_env()?;
+            let report = purge_expired_data(services, &config, config.dry_run).await?;
+            log::info!("The scheduled purge of expired data finished: {report:?}");
+            Ok(None)
+        }
+    }
+}
+
+/// Run the current organization's scheduled jobs that are due.
+///
+/// * `services`: The services needed to run the jobs, scoped to the current organization
+///
+/// Returns the number of jobs that were run. How each run went is recorded on its schedule, so a
+/// job that fails does not stop the others.
+pub async fn run_due_schedules(services: &ExportServices) -> Result<usize> {
+    let now = Utc::now();
+    let due = services
+        .storage_layer
+        .fetch_due_job_schedules(now, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    let mut ran = 0;
+    for schedule in due {
+        let next_run_at = match next_run_after(&schedule.cron, now) {
+            Ok(next_run_at) => next_run_at,
+            Err(e) => {
+                log::error!("Not running {}, its schedule is invalid: {e:#}", schedule.name);
+                continue;
+            }
+        };
+
+        let claimed = services
+            .storage_layer
+            .claim_job_schedule_run(
+                &schedule.name,
+                schedule.next_run_at,
+                next_run_at,
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?;
+        // Another process is running it, or the schedule changed since it was read
+        if !claimed {
+            continue;
+        }
+
+        log::info!("Running scheduled job {}, next run at {next_run_at}", schedule.name);
+        let result = match ScheduledJob::parse(&schedule.name) {
+            Ok(job) => run_scheduled_job(services, job, &schedule).await,
+            Err(e) => Err(e),
+        };
+
+        let (job_id, error) = match result {
+            Ok(job_id) => (job_id, None),
+            Err(e) => {
+                log::error!("Scheduled job {} failed: {e:#}", schedule.name);
+                (None, Some(format!("{e:#}")))
+            }
+        };
+        services
+            .storage_layer
+            .record_job_schedule_run(
+                &schedule.name,
+                job_id,
+                error,
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?;
+        ran += 1;
+    }
+
+    Ok(ran)
+}
+
+/// Start the background task that runs scheduled jobs when they are due, for every organization.
+///
+/// * `services`: The services needed to run the jobs
+pub fn spawn_scheduler_task(services: ExportServices) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULER_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            for org_id in services.org_ids().await {
+                let services = services.for_org(org_id);
+                match with_org(org_id, run_due_schedules(&services)).await {
+                    Ok(0) => {}
+                    Ok(ran) => log::info!("Ran {ran} scheduled jobs for organization {org_id}"),
+                    Err(e) => {
+                        log::error!("Failed to run scheduled jobs for organization {org_id}: {e}")
+                    }
+                }
+            }
+        }
+    })
+}
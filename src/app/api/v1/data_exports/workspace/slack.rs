@@ -0,0 +1,268 @@
+//! This module invites exported volunteers to the program's Slack workspace and adds them to their
+//! cohort's channels.
+//!
+//! Volunteers are invited with their workspace email, so the invitation lands in the inbox they
+//! are onboarded into. Invitations are sent either as part of an export, once volunteers' accounts
+//! exist, or by a follow-up job for a project cycle's volunteers who were already exported. Like
+//! groups, failing to invite a volunteer does not roll back their account; it is reported in the
+//! export outcome (or the follow-up job's result) instead.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Result};
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{report_progress, ExportSettings};
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::slack::{SlackInviteOutcome, SlackInviteParamsBuilder};
+use crate::services::storage::entities::SuspensionCandidate;
+use crate::services::storage::jobs::NEW_JOB_VERSION;
+use crate::services::storage::types::JobPhase;
+use crate::services::storage::ExecOptsBuilder;
+use crate::services::workspace::retry::RetryPolicy;
+
+/// The most Slack channels volunteers can be added to at once.
+pub const MAX_SLACK_CHANNELS: usize = 20;
+
+/// The Slack channels to add invited volunteers to.
+///
+/// * `channel_ids`: The IDs of the cohort's channels (e.g. `C0123456789`). Slack requires at
+///   least one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlackInviteSettings {
+    pub channel_ids: Vec<String>,
+}
+
+impl SlackInviteSettings {
+    /// Check that the Slack settings are well formed.
+    pub fn validate(&self) -> Result<()> {
+        if self.channel_ids.is_empty() {
+            bail!("At least one Slack channel must be given");
+        }
+
+        if self.channel_ids.len() > MAX_SLACK_CHANNELS {
+            bail!("Volunteers can be added to at most {MAX_SLACK_CHANNELS} Slack channels");
+        }
+
+        let mut seen = HashSet::<&str>::with_capacity(self.channel_ids.len());
+        for channel_id in &self.channel_ids {
+            if !is_channel_id(channel_id) {
+                bail!("{channel_id} is not a Slack channel ID");
+            }
+            if !seen.insert(channel_id.as_str()) {
+                bail!("Slack channel {channel_id} is listed more than once");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether a string looks like the ID of a public (`C...`) or private (`G...`) Slack channel.
+///
+/// * `id`: The string to check
+fn is_channel_id(id: &str) -> bool {
+    id.len() > 1
+        && (id.starts_with('C') || id.starts_with('G'))
+        && id.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+/// Invite a volunteer to Slack, retrying transient failures.
+///
+/// * `services`: The services needed to send the invitation
+/// * `retry_policy`: How to retry transient failures
+/// * `slack`: The channels to add the volunteer to
+/// * `workspace_email`: The volunteer's workspace email
+async fn invite(
+    services: &ExportServices,
+    retry_policy: &RetryPolicy,
+    slack: &SlackInviteSettings,
+    workspace_email: &str,
+) -> Result<SlackInviteOutcome> {
+    let params = SlackInviteParamsBuilder::default()
+        .email(workspace_email)
+        .channel_ids(slack.channel_ids.clone())
+        .build()?;
+
+    retry_policy
+        .run(&format!("Inviting {workspace_email} to Slack"), || {
+            services.slack.invite_user(params.clone())
+        })
+        .await
+}
+
+/// Invite exported volunteers to Slack, if the export has Slack settings.
+///
+/// * `services`: The services needed to run the export
+/// * `settings`: Settings for the export
+/// * `workspace_emails`: The workspace emails of the volunteers
+///
+/// Invitations are sent up to `settings.concurrency` at once. Returns the workspace emails of the
+/// volunteers that could not be invited.
+pub(super) async fn invite_to_slack<'a>(
+    services: &ExportServices,
+    settings: &ExportSettings<'_>,
+    workspace_emails: impl IntoIterator<Item = &'a String>,
+) -> HashSet<String> {
+    let Some(slack) = settings.slack else {
+        return HashSet::new();
+    };
+
+    stream::iter(workspace_emails)
+        .map(|email| async move {
+            match invite(services, settings.retry_policy, slack, email).await {
+                Ok(_) => None,
+                Err(e) => {
+                    log::error!("Failed to invite {email} to Slack: {e}");
+                    Some(email.clone())
+                }
+            }
+        })
+        .buffered(settings.concurrency.max(1))
+        .filter_map(|failed| async move { failed })
+        .collect::<HashSet<String>>()
+        .await
+}
+
+/// Parameters for a job that invites a project cycle's exported volunteers to Slack.
+///
+/// * `job_id`: The ID of the job
+/// * `slack`: The channels to add the volunteers to
+/// * `concurrency`: The maximum number of invitations to send at once
+/// * `volunteers`: The volunteers to invite
+pub struct InviteToSlackParams {
+    pub job_id: Uuid,
+    pub slack: SlackInviteSettings,
+    pub concurrency: usize,
+    pub volunteers: Vec<SuspensionCandidate>,
+}
+
+/// A volunteer a Slack invitation job could not invite.
+///
+/// * `volunteer_id`: The ID of the volunteer
+/// * `workspace_email`: The volunteer's workspace email
+/// * `reason`: Why the volunteer could not be invited
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedSlackInvite {
+    pub volunteer_id: Uuid,
+    pub workspace_email: String,
+    pub reason: String,
+}
+
+/// What a Slack invitation job did.
+///
+/// * `invited`: The number of volunteers invited to the Slack workspace
+/// * `already_members`: The number of volunteers who were already members, and were only added to
+///   the channels
+/// * `failed`: The volunteers that could not be invited
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlackInviteSummary {
+    pub invited: usize,
+    pub already_members: usize,
+    pub failed: Vec<FailedSlackInvite>,
+}
+
+impl SlackInviteSummary {
+    /// Count what happened to a volunteer.
+    ///
+    /// * `volunteer`: The volunteer
+    /// * `result`: What inviting the volunteer did
+    pub fn record(&mut self, volunteer: &SuspensionCandidate, result: Result<SlackInviteOutcome>) {
+        match result {
+            Ok(SlackInviteOutcome::Invited) => self.invited += 1,
+            Ok(SlackInviteOutcome::AlreadyMember) => self.already_members += 1,
+            Err(e) => self.failed.push(FailedSlackInvite {
+                volunteer_id: volunteer.volunteer_id,
+                workspace_email: volunteer.workspace_email.clone(),
+                reason: e.to_string(),
+            }),
+        }
+    }
+}
+
+/// Invite a project cycle's exported volunteers to Slack.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+///
+/// The summary is saved as the job's result, and the job is marked complete, or errored if any
+/// volunteer could not be invited.
+pub async fn invite_to_slack_task(
+    services: &ExportServices,
+    params: InviteToSlackParams,
+) -> Result<SlackInviteSummary> {
+    let total = params.volunteers.len();
+    let retry_policy = RetryPolicy::default();
+    let retry_policy = &retry_policy;
+    let slack = &params.slack;
+
+    report_progress(services, params.job_id, JobPhase::Provisioning, 0, total).await;
+
+    let mut stream = stream::iter(params.volunteers.iter())
+        .map(|volunteer| async move {
+            let result = invite(services, retry_policy, slack, &volunteer.workspace_email).await;
+            (volunteer, result)
+        })
+        .buffer_unordered(params.concurrency.max(1));
+
+    let mut summary = SlackInviteSummary::default();
+    let mut done = 0;
+    while let Some((volunteer, result)) = stream.next().await {
+        if let Err(e) = &result {
+            log::error!("Failed to invite {} to Slack: {e}", volunteer.workspace_email);
+        }
+        summary.record(volunteer, result);
+
+        done += 1;
+        report_progress(services, params.job_id, JobPhase::Provisioning, done, total).await;
+    }
+
+    if let Err(e) = finish_slack_invites(services, params.job_id, &summary).await {
+        log::error!("Failed to record the outcome of job {}: {e}", params.job_id);
+    }
+
+    Ok(summary)
+}
+
+/// Save the summary of a Slack invitation job and mark the job complete, or errored if any
+/// volunteer could not be invited.
+///
+/// * `services`: The services needed to run the job
+/// * `job_id`: The ID of the job
+/// * `summary`: What the job did
+async fn finish_slack_invites(
+    services: &ExportServices,
+    job_id: Uuid,
+    summary: &SlackInviteSummary,
+) -> Result<()> {
+    services
+        .storage_layer
+        .set_job_result(
+            job_id,
+            serde_json::to_value(summary)?,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    if summary.failed.is_empty() {
+        services
+            .storage_layer
+            .mark_job_complete(job_id, NEW_JOB_VERSION, &mut ExecOptsBuilder::default().build()?)
+            .await
+    } else {
+        services
+            .storage_layer
+            .mark_job_errored(
+                job_id,
+                NEW_JOB_VERSION,
+                format!("Failed to invite {} users to Slack", summary.failed.len()),
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await
+    }
+}
@@ -0,0 +1,204 @@
+//! This module suspends the workspace accounts of a project cycle's exported volunteers once their
+//! program is over (or lifts the suspensions).
+//!
+//! Suspending keeps an account and its data, so volunteers can be brought back if needed. Each
+//! account is acted on independently, so a failure for one volunteer does not stop the others, and
+//! what happened to every account is recorded.
+
+use anyhow::Result;
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::report_progress;
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::storage::entities::SuspensionCandidate;
+use crate::services::storage::jobs::NEW_JOB_VERSION;
+use crate::services::storage::suspensions::RecordWorkspaceSuspension;
+use crate::services::storage::types::{JobPhase, WorkspaceSuspensionAction};
+use crate::services::storage::ExecOptsBuilder;
+use crate::services::workspace::retry::RetryPolicy;
+
+/// Parameters for a job that suspends (or unsuspends) workspace accounts.
+///
+/// * `job_id`: The ID of the job
+/// * `principal`: The email of the user who started the job
+/// * `action`: Whether to suspend or unsuspend the accounts
+/// * `concurrency`: The maximum number of accounts to act on at once
+/// * `volunteers`: The volunteers whose accounts are acted on
+pub struct SuspensionParams {
+    pub job_id: Uuid,
+    pub principal: String,
+    pub action: WorkspaceSuspensionAction,
+    pub concurrency: usize,
+    pub volunteers: Vec<SuspensionCandidate>,
+}
+
+/// What a job that suspends (or unsuspends) workspace accounts did.
+///
+/// * `action`: Whether the accounts were suspended or unsuspended
+/// * `succeeded`: The number of accounts that were acted on
+/// * `failed`: The number of accounts that could not be acted on
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuspensionSummary {
+    pub action: WorkspaceSuspensionAction,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+/// Suspend (or unsuspend) the workspace accounts of volunteers.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+///
+/// The outcome for every account is recorded, the summary is saved as the job's result, and the
+/// job is marked complete, or errored if any account failed.
+pub async fn suspension_task(
+    services: &ExportServices,
+    params: SuspensionParams,
+) -> Result<SuspensionSummary> {
+    let result = suspend_volunteers(services, &params).await.map(|(summary, _)| summary);
+
+    let finished = match &result {
+        Ok(summary) => finish_suspension(services, params.job_id, summary).await,
+        Err(e) => {
+            log::error!("Job {} failed to {} users: {e}", params.job_id, params.action);
+            services
+                .storage_layer
+                .mark_job_errored(
+                    params.job_id,
+                    NEW_JOB_VERSION,
+                    e.to_string(),
+                    &mut ExecOptsBuilder::default().build()?,
+                )
+                .await
+        }
+    };
+
+    if let Err(e) = finished {
+        log::error!("Failed to record the outcome of job {}: {e}", params.job_id);
+    }
+
+    result
+}
+
+/// Act on every volunteer's account and record what happened.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+///
+/// Returns the summary, along with the volunteers whose accounts were acted on.
+pub(super) async fn suspend_volunteers<'a>(
+    services: &ExportServices,
+    params: &'a SuspensionParams,
+) -> Result<(SuspensionSummary, Vec<&'a SuspensionCandidate>)> {
+    let total = params.volunteers.len();
+    let retry_policy = RetryPolicy::default();
+    let retry_policy = &retry_policy;
+
+    report_progress(services, params.job_id, JobPhase::Provisioning, 0, total).await;
+
+    let mut stream = stream::iter(params.volunteers.iter())
+        .map(|volunteer| async move {
+            let label = format!("{} {}", params.action, volunteer.workspace_email);
+            let result = retry_policy
+                .run(&label, || async {
+                    match params.action {
+                        WorkspaceSuspensionAction::Suspend => {
+                            services
+                                .workspace
+                                .suspend_volunteer(&params.principal, &volunteer.workspace_email)
+                                .await
+                        }
+                        WorkspaceSuspensionAction::Unsuspend => {
+                            services
+                                .workspace
+                                .unsuspend_volunteer(&params.principal, &volunteer.workspace_email)
+                                .await
+                        }
+                    }
+                })
+                .await;
+
+            (volunteer, result)
+        })
+        .buffer_unordered(params.concurrency.max(1));
+
+    let mut summary = SuspensionSummary { action: params.action, ..Default::default() };
+    let mut records = Vec::with_capacity(total);
+    let mut succeeded = Vec::with_capacity(total);
+    while let Some((volunteer, result)) = stream.next().await {
+        let error = match result {
+            Ok(_) => {
+                log::info!("{}ed {}", params.action, volunteer.workspace_email);
+                summary.succeeded += 1;
+                succeeded.push(volunteer);
+                None
+            }
+            Err(e) => {
+                log::error!("Failed to {} {}: {e}", params.action, volunteer.workspace_email);
+                summary.failed += 1;
+                Some(e.to_string())
+            }
+        };
+
+        records.push(RecordWorkspaceSuspension {
+            job_id: params.job_id,
+            volunteer_id: volunteer.volunteer_id,
+            workspace_email: volunteer.workspace_email.clone(),
+            action: params.action,
+            succeeded: error.is_none(),
+            error,
+            principal: params.principal.clone(),
+        });
+
+        let done = summary.succeeded + summary.failed;
+        report_progress(services, params.job_id, JobPhase::Provisioning, done, total).await;
+    }
+
+    services
+        .storage_layer
+        .record_workspace_suspensions(records, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    Ok((summary, succeeded))
+}
+
+/// Save the summary of a suspension job and mark the job complete, or errored if any account
+/// failed.
+///
+/// * `services`: The services needed to run the job
+/// * `job_id`: The ID of the job
+/// * `summary`: What the job did
+async fn finish_suspension(
+    services: &ExportServices,
+    job_id: Uuid,
+    summary: &SuspensionSummary,
+) -> Result<()> {
+    services
+        .storage_layer
+        .set_job_result(
+            job_id,
+            serde_json::to_value(summary)?,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    if summary.failed > 0 {
+        services
+            .storage_layer
+            .mark_job_errored(
+                job_id,
+                NEW_JOB_VERSION,
+                format!("Failed to {} {} users", summary.action, summary.failed),
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await
+    } else {
+        services
+            .storage_layer
+            .mark_job_complete(job_id, NEW_JOB_VERSION, &mut ExecOptsBuilder::default().build()?)
+            .await
+    }
+}
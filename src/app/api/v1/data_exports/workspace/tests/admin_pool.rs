@@ -0,0 +1,209 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use rstest::rstest;
+use scipio_workspace::AccessTokenError;
+
+use crate::services::workspace::admin_pool::{AdminPoolWorkspaceClient, DelegatedAdmin};
+use crate::services::workspace::emulator::{EmulatorConfig, EmulatorWorkspaceClient};
+
+const PRINCIPAL: &str = "requester@developforgood.org";
+
+fn admin(email: &str, org_unit: Option<&str>) -> DelegatedAdmin {
+    DelegatedAdmin { email: email.to_owned(), org_unit: org_unit.map(str::to_owned) }
+}
+
+fn admin_pool(admins: Vec<DelegatedAdmin>) -> Result<AdminPoolWorkspaceClient> {
+    let inner = Arc::new(EmulatorWorkspaceClient::new(EmulatorConfig::default()));
+    AdminPoolWorkspaceClient::new(inner, admins)
+}
+
+fn refused(principal: &str) -> anyhow::Error {
+    anyhow!(AccessTokenError {
+        principal: principal.to_owned(),
+        status: 401,
+        body: "unauthorized_client".to_owned(),
+    })
+}
+
+/// Make a call through the pool that Google refuses for every principal in `refuse`, returning
+/// the principals it was made on the behalf of, in order, and whether it succeeded.
+async fn call(
+    pool: &AdminPoolWorkspaceClient,
+    org_unit: Option<&str>,
+    refuse: &[&str],
+) -> (Vec<String>, bool) {
+    let tried = Mutex::new(Vec::<String>::new());
+    let tried_ref = &tried;
+    let result = pool
+        .with_admin("testing", PRINCIPAL, org_unit, |principal| async move {
+            tried_ref.lock().unwrap().push(principal.clone());
+            if refuse.contains(&principal.as_str()) {
+                Err(refused(&principal))
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+    (tried.into_inner().unwrap(), result.is_ok())
+}
+
+#[rstest]
+#[case("admin@developforgood.org", admin("admin@developforgood.org", None))]
+#[case(" admin@developforgood.org ", admin("admin@developforgood.org", None))]
+#[case(
+    "admin@developforgood.org=/Programs/2025-Spring",
+    admin("admin@developforgood.org", Some("/Programs/2025-Spring"))
+)]
+#[case(
+    "admin@developforgood.org = /Programs/2025-Spring/",
+    admin("admin@developforgood.org", Some("/Programs/2025-Spring"))
+)]
+pub fn test_parse_delegated_admin(#[case] raw: &str, #[case] expected: DelegatedAdmin) {
+    assert_eq!(raw.parse::<DelegatedAdmin>().unwrap(), expected);
+}
+
+#[rstest]
+#[case("")]
+#[case("admin")]
+#[case("=/Programs")]
+#[case("admin@developforgood.org=Programs/2025-Spring")]
+#[case("admin@developforgood.org=")]
+pub fn test_parse_delegated_admin_error(#[case] raw: &str) {
+    assert!(raw.parse::<DelegatedAdmin>().is_err(), "{raw} should not parse");
+}
+
+#[rstest]
+#[case(None, None, true)]
+#[case(None, Some("/Programs/2025-Spring"), true)]
+#[case(Some("/"), Some("/Programs/2025-Spring"), true)]
+#[case(Some("/Programs"), Some("/Programs"), true)]
+#[case(Some("/Programs"), Some("/Programs/2025-Spring"), true)]
+#[case(Some("/Programs"), Some("/Programs/2025-Spring/Team-1"), true)]
+#[case(Some("/Programs/2025-Spring"), Some("/Programs/2025-Fall"), false)]
+#[case(Some("/Programs/2025-Spring"), Some("/Programs/2025-Springfield"), false)]
+#[case(Some("/Programs/2025-Spring"), Some("/Programs"), false)]
+#[case(Some("/Programs"), None, false)]
+pub fn test_delegated_admin_covers(
+    #[case] scope: Option<&str>,
+    #[case] org_unit: Option<&str>,
+    #[case] covers: bool,
+) {
+    assert_eq!(admin("admin@developforgood.org", scope).covers(org_unit), covers);
+}
+
+#[tokio::test]
+pub async fn test_admin_pool_scopes() -> Result<()> {
+    let pool = admin_pool(vec![
+        admin("a@developforgood.org", None),
+        admin("b@developforgood.org", Some("/Programs")),
+        admin("c@developforgood.org", Some("/Programs/2025-Spring")),
+    ])?;
+    let everyone = ["a@developforgood.org", "b@developforgood.org", "c@developforgood.org"];
+
+    // the most narrowly scoped admins are tried first, and the requester once they all failed
+    let (tried, ok) = call(&pool, Some("/Programs/2025-Spring/Team-1"), &everyone).await;
+    assert!(ok);
+    assert_eq!(tried, [everyone[2], everyone[1], everyone[0], PRINCIPAL]);
+
+    // admins scoped to a sibling org unit are left out
+    let (tried, ok) = call(&pool, Some("/Programs/2025-Fall"), &[]).await;
+    assert!(ok);
+    assert_eq!(tried, [everyone[1]]);
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_admin_pool_rotation() -> Result<()> {
+    let pool = admin_pool(vec![
+        admin("a@developforgood.org", None),
+        admin("b@developforgood.org", None),
+        admin("c@developforgood.org", None),
+    ])?;
+
+    for expected in ["a", "b", "c", "a"] {
+        let (tried, _) = call(&pool, None, &[]).await;
+        assert_eq!(tried, [format!("{expected}@developforgood.org")]);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_admin_pool_cooldown() -> Result<()> {
+    let cooldown = Duration::from_millis(200);
+    let pool =
+        admin_pool(vec![admin("a@developforgood.org", None), admin("b@developforgood.org", None)])?
+            .with_cooldown(cooldown);
+
+    // a is refused and benched, so the call is made as b
+    let (tried, ok) = call(&pool, None, &["a@developforgood.org"]).await;
+    assert!(ok);
+    assert_eq!(tried, ["a@developforgood.org", "b@developforgood.org"]);
+
+    // a is tried after b, even when it is a's turn
+    let (tried, _) = call(&pool, None, &[]).await;
+    assert_eq!(tried, ["b@developforgood.org"]);
+    let (tried, _) = call(&pool, None, &[]).await;
+    assert_eq!(tried, ["b@developforgood.org"]);
+
+    // once its cooldown is over, a takes its turns again
+    tokio::time::sleep(cooldown * 2).await;
+    let (tried, _) = call(&pool, None, &[]).await;
+    assert_eq!(tried, ["b@developforgood.org"]);
+    let (tried, _) = call(&pool, None, &[]).await;
+    assert_eq!(tried, ["a@developforgood.org"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_admin_pool_falls_back_to_principal() -> Result<()> {
+    let pool = admin_pool(vec![
+        admin("a@developforgood.org", None),
+        admin("b@developforgood.org", Some("/Programs/2025-Spring")),
+    ])?;
+
+    // the only admin in scope is refused, so the call is made as the requester
+    let (tried, ok) = call(&pool, None, &["a@developforgood.org"]).await;
+    assert!(ok);
+    assert_eq!(tried, ["a@developforgood.org", PRINCIPAL]);
+
+    // a benched admin is still tried before the requester
+    let (tried, ok) = call(&pool, Some("/Programs/2025-Fall"), &["a@developforgood.org"]).await;
+    assert!(ok);
+    assert_eq!(tried, ["a@developforgood.org", PRINCIPAL]);
+
+    // with no admin in scope, the call is made as the requester right away
+    let pool = admin_pool(vec![admin("b@developforgood.org", Some("/Programs/2025-Spring"))])?;
+    let (tried, ok) = call(&pool, Some("/Programs/2025-Fall"), &[]).await;
+    assert!(ok);
+    assert_eq!(tried, [PRINCIPAL]);
+
+    // the error names everyone who was refused
+    let err = pool
+        .with_admin("testing", PRINCIPAL, Some("/Programs/2025-Spring"), |principal| async move {
+            Err::<(), _>(refused(&principal))
+        })
+        .await
+        .expect_err("everyone was refused");
+    let message = err.to_string();
+    assert!(message.starts_with("Every admin failed while testing: "), "{message}");
+    assert!(message.contains("b@developforgood.org") && message.contains(PRINCIPAL), "{message}");
+
+    // errors that are not about the admin are returned without trying anyone else
+    let calls = &Mutex::new(0);
+    let err = pool
+        .with_admin("testing", PRINCIPAL, Some("/Programs/2025-Spring"), |_| async move {
+            *calls.lock().unwrap() += 1;
+            Err::<(), _>(anyhow!("Invalid Input: primary_user_email"))
+        })
+        .await
+        .expect_err("the call is invalid");
+    assert_eq!(err.to_string(), "Invalid Input: primary_user_email");
+    assert_eq!(*calls.lock().unwrap(), 1);
+
+    Ok(())
+}
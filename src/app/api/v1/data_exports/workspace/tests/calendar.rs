@@ -0,0 +1,25 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use rstest::rstest;
+
+use crate::app::api::v1::data_exports::workspace::calendar::OnboardingSessionSettings;
+
+#[rstest]
+#[case("Spring 2025 Orientation", 60, true)]
+#[case("Spring 2025 Orientation", 0, false)]
+#[case("Spring 2025 Orientation", -60, false)]
+#[case("", 60, false)]
+#[case("   ", 60, false)]
+pub fn test_validate_onboarding_session(
+    #[case] summary: &str,
+    #[case] minutes: i64,
+    #[case] valid: bool,
+) {
+    let start = DateTime::<Utc>::from_timestamp(1_735_603_200, 0).unwrap();
+    let session = OnboardingSessionSettings {
+        summary: summary.to_owned(),
+        description: None,
+        start,
+        end: start + TimeDelta::minutes(minutes),
+    };
+    assert_eq!(session.validate().is_ok(), valid, "{session:?}");
+}
@@ -0,0 +1,23 @@
+use chrono::{TimeZone, Utc};
+use rstest::rstest;
+
+use crate::app::api::v1::data_exports::workspace::deprovisioning::{
+    delete_after, validate_grace_period, DEFAULT_DELETION_GRACE_PERIOD_DAYS,
+};
+
+#[rstest]
+#[case(0, false)]
+#[case(1, true)]
+#[case(DEFAULT_DELETION_GRACE_PERIOD_DAYS, true)]
+#[case(365, true)]
+#[case(366, false)]
+pub fn test_validate_grace_period(#[case] grace_period_days: u32, #[case] valid: bool) {
+    assert_eq!(validate_grace_period(grace_period_days).is_ok(), valid, "{grace_period_days}");
+}
+
+#[test]
+pub fn test_delete_after() {
+    let now = Utc.with_ymd_and_hms(2025, 5, 20, 12, 0, 0).unwrap();
+    assert_eq!(delete_after(30, now), Utc.with_ymd_and_hms(2025, 6, 19, 12, 0, 0).unwrap());
+    assert_eq!(delete_after(1, now), Utc.with_ymd_and_hms(2025, 5, 21, 12, 0, 0).unwrap());
+}
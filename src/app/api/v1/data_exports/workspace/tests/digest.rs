@@ -0,0 +1,37 @@
+use chrono::{DateTime, TimeDelta};
+use rstest::rstest;
+
+use crate::app::api::v1::data_exports::workspace::digest::{due_period, DigestFrequency};
+
+#[rstest]
+#[case("daily", DigestFrequency::Daily)]
+#[case(" Weekly ", DigestFrequency::Weekly)]
+fn test_parse_digest_frequency(#[case] raw: &str, #[case] expected: DigestFrequency) {
+    assert_eq!(DigestFrequency::parse(raw).unwrap(), expected);
+}
+
+#[test]
+fn test_parse_invalid_digest_frequency() {
+    assert!(DigestFrequency::parse("hourly").is_err());
+}
+
+#[rstest]
+#[case(None, DigestFrequency::Daily, Some(24))]
+#[case(None, DigestFrequency::Weekly, Some(24 * 7))]
+#[case(Some(23), DigestFrequency::Daily, None)]
+#[case(Some(24), DigestFrequency::Daily, Some(24))]
+#[case(Some(72), DigestFrequency::Daily, Some(72))]
+#[case(Some(72), DigestFrequency::Weekly, None)]
+fn test_due_period(
+    #[case] hours_since_last: Option<i64>,
+    #[case] frequency: DigestFrequency,
+    #[case] hours_covered: Option<i64>,
+) {
+    let now = DateTime::from_timestamp(1_729_000_000, 0).unwrap();
+    let last_sent_at = hours_since_last.map(|h| now - TimeDelta::hours(h));
+
+    assert_eq!(
+        due_period(last_sent_at, frequency, now),
+        hours_covered.map(|h| (now - TimeDelta::hours(h), now))
+    );
+}
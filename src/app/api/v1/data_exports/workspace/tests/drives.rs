@@ -0,0 +1,32 @@
+use rstest::rstest;
+
+use crate::app::api::v1::data_exports::workspace::drives::SharedDriveSettings;
+use crate::services::workspace::drive::DriveRole;
+
+#[rstest]
+#[case("Spring 2025 Cohort", DriveRole::Writer, true)]
+#[case("Spring 2025 Cohort", DriveRole::Reader, true)]
+#[case("Spring 2025 Cohort", DriveRole::Organizer, false)]
+#[case("", DriveRole::Writer, false)]
+#[case("   ", DriveRole::Writer, false)]
+pub fn test_validate_shared_drive(
+    #[case] name: &str,
+    #[case] role: DriveRole,
+    #[case] valid: bool,
+) {
+    let settings = SharedDriveSettings { name: name.to_owned(), role };
+    assert_eq!(settings.validate().is_ok(), valid, "{settings:?}");
+}
+
+#[test]
+pub fn test_shared_drive_role_defaults_to_writer() {
+    let settings =
+        serde_json::from_str::<SharedDriveSettings>(r#"{"name": "Spring 2025 Cohort"}"#).unwrap();
+    assert_eq!(settings.role, DriveRole::Writer);
+
+    let settings = serde_json::from_str::<SharedDriveSettings>(
+        r#"{"name": "Spring 2025 Cohort", "role": "fileOrganizer"}"#,
+    )
+    .unwrap();
+    assert_eq!(settings.role, DriveRole::FileOrganizer);
+}
@@ -0,0 +1,18 @@
+use chrono::{DateTime, TimeDelta};
+use rstest::rstest;
+
+use crate::app::api::v1::data_exports::workspace::email_retries::{
+    next_attempt_at, MAX_EMAIL_RETRY_ATTEMPTS,
+};
+
+#[rstest]
+#[case(1, Some(1))]
+#[case(2, Some(2))]
+#[case(3, Some(4))]
+#[case(4, Some(8))]
+#[case(MAX_EMAIL_RETRY_ATTEMPTS, None)]
+fn test_next_attempt_at_backs_off(#[case] attempts: i32, #[case] minutes: Option<i64>) {
+    let now = DateTime::from_timestamp(1_729_000_000, 0).unwrap();
+
+    assert_eq!(next_attempt_at(attempts, now), minutes.map(|m| now + TimeDelta::minutes(m)));
+}
@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::services::workspace::emulator::{EmulatorConfigBuilder, EmulatorWorkspaceClient};
+use crate::services::workspace::entities::{
+    CreateVolunteerOutcome, CreateWorkspaceVolunteer, CreateWorkspaceVolunteerBuilder,
+};
+use crate::services::workspace::retry::{is_conflict, is_retryable, RetryPolicyBuilder};
+use crate::services::workspace::WorkspaceClient;
+
+const PRINCIPAL: &str = "admin@developforgood.org";
+
+fn volunteer(primary_email: &str, recovery_email: &str) -> CreateWorkspaceVolunteer {
+    CreateWorkspaceVolunteerBuilder::default()
+        .primary_email(primary_email)
+        .first_name("Roger")
+        .last_name("Federer")
+        .password("password")
+        .recovery_email(recovery_email)
+        .org_unit("/Programs/PantheonUsers")
+        .build()
+        .expect("error building volunteer")
+}
+
+#[tokio::test]
+pub async fn test_emulator_duplicates() -> Result<()> {
+    let config = EmulatorConfigBuilder::default()
+        .existing_users(vec!["rafanadal@developforgood.org".to_owned()])
+        .build()?;
+    let workspace = EmulatorWorkspaceClient::new(config);
+
+    let federer = volunteer("rogerfederer@developforgood.org", "roger.federer@gmail.com");
+    let outcome = workspace.create_volunteer(PRINCIPAL, federer.clone()).await?;
+    assert_eq!(outcome, CreateVolunteerOutcome::Created);
+
+    // Creating the same volunteer again is recognised as an earlier attempt that succeeded
+    let outcome = workspace.create_volunteer(PRINCIPAL, federer.clone()).await?;
+    assert_eq!(outcome, CreateVolunteerOutcome::AlreadyExists);
+
+    // An address that belongs to someone else is not
+    let nadal = volunteer("rafanadal@developforgood.org", "rafa.nadal@gmail.com");
+    assert!(workspace.create_volunteer(PRINCIPAL, nadal.clone()).await.is_err());
+
+    // Batches report existing users as conflicts, like Google does
+    let results = workspace.batch_create_volunteers(PRINCIPAL, vec![federer, nadal]).await?;
+    assert!(results.iter().all(|r| r.as_ref().is_err_and(is_conflict)));
+
+    assert_eq!(workspace.users().len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_emulator_rate_limits() -> Result<()> {
+    let config = EmulatorConfigBuilder::default().rate_limit_every(2).build()?;
+    let workspace = EmulatorWorkspaceClient::new(config);
+
+    let volunteers = (0..4)
+        .map(|i| volunteer(&format!("user{i}@developforgood.org"), &format!("user{i}@gmail.com")))
+        .collect::<Vec<CreateWorkspaceVolunteer>>();
+
+    // Every other user in the batch is rate limited
+    let results = workspace.batch_create_volunteers(PRINCIPAL, volunteers.clone()).await?;
+    let limited = results.iter().filter(|r| r.as_ref().is_err_and(is_retryable)).count();
+    assert_eq!(limited, 2);
+
+    // Retrying the rate limited users one at a time gets them all created
+    let retry_policy = RetryPolicyBuilder::default().base_delay(Duration::ZERO).build()?;
+    for (volunteer, result) in volunteers.into_iter().zip(results) {
+        if result.is_err() {
+            retry_policy
+                .run("create user", || workspace.create_volunteer(PRINCIPAL, volunteer.clone()))
+                .await?;
+        }
+    }
+
+    assert_eq!(workspace.users().len(), 4);
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_emulator_rejects_unknown_org_units_and_groups() -> Result<()> {
+    let workspace = EmulatorWorkspaceClient::new(EmulatorConfigBuilder::default().build()?);
+
+    let mut federer = volunteer("rogerfederer@developforgood.org", "roger.federer@gmail.com");
+    federer.org_unit = "/Programs/2025-Spring".to_owned();
+    assert!(workspace.create_volunteer(PRINCIPAL, federer.clone()).await.is_err());
+
+    workspace.create_org_unit(PRINCIPAL, "/Programs/2025-Spring").await?;
+    workspace.create_volunteer(PRINCIPAL, federer).await?;
+
+    let group = "cohort@developforgood.org";
+    let member = "rogerfederer@developforgood.org";
+    assert!(workspace.add_group_member(PRINCIPAL, group, member).await.is_err());
+
+    assert!(workspace.ensure_group(PRINCIPAL, group).await?);
+    assert!(!workspace.ensure_group(PRINCIPAL, group).await?);
+    workspace.add_group_member(PRINCIPAL, group, member).await?;
+    assert_eq!(workspace.group_members(group), Some(vec![member.to_owned()]));
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_emulator_latency() -> Result<()> {
+    let config = EmulatorConfigBuilder::default().latency(Duration::from_millis(50)).build()?;
+    let workspace = EmulatorWorkspaceClient::new(config);
+
+    let start = std::time::Instant::now();
+    workspace.org_unit_exists(PRINCIPAL, "/Programs/PantheonUsers").await?;
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    Ok(())
+}
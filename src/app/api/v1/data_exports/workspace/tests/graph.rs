@@ -0,0 +1,95 @@
+use anyhow::{anyhow, Result};
+use serde_json::json;
+
+use crate::services::workspace::entities::{
+    CreateWorkspaceVolunteer, CreateWorkspaceVolunteerBuilder, ProgramMetadata,
+};
+use crate::services::workspace::graph::{mail_nickname, CreateGraphUser, GraphError};
+use crate::services::workspace::retry::{is_conflict, is_retryable};
+
+fn volunteer(org_unit: &str) -> CreateWorkspaceVolunteer {
+    CreateWorkspaceVolunteerBuilder::default()
+        .primary_email("rogerfederer@developforgood.org")
+        .first_name("Roger")
+        .last_name("Federer")
+        .password("password")
+        .recovery_email("roger.federer@gmail.com")
+        .org_unit(org_unit)
+        .build()
+        .expect("error building volunteer")
+}
+
+fn graph_error(status: u16, message: &str) -> anyhow::Error {
+    anyhow!(GraphError {
+        status,
+        code: "Request_BadRequest".to_owned(),
+        message: message.to_owned()
+    })
+}
+
+#[test]
+pub fn test_create_graph_user() -> Result<()> {
+    let user = CreateGraphUser::new(volunteer("/"), "US")?;
+
+    assert_eq!(
+        serde_json::to_value(user)?,
+        json!({
+            "accountEnabled": true,
+            "displayName": "Roger Federer",
+            "givenName": "Roger",
+            "surname": "Federer",
+            "mailNickname": "rogerfederer",
+            "userPrincipalName": "rogerfederer@developforgood.org",
+            "otherMails": ["roger.federer@gmail.com"],
+            "usageLocation": "US",
+            "passwordProfile": {
+                "password": "password",
+                "forceChangePasswordNextSignIn": true,
+            },
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+pub fn test_create_graph_user_unsupported() {
+    // Entra ID has no org units
+    assert!(CreateGraphUser::new(volunteer("/Programs/PantheonUsers"), "US").is_err());
+
+    let mut with_aliases = volunteer("/");
+    with_aliases.aliases = vec!["roger@developforgood.org".to_owned()];
+    assert!(CreateGraphUser::new(with_aliases, "US").is_err());
+
+    let mut with_program = volunteer("/");
+    with_program.program = Some(ProgramMetadata {
+        program_name: "Summer Fellowship".to_owned(),
+        cohort: "2025-Spring".to_owned(),
+        volunteer_id: uuid::Uuid::new_v4(),
+    });
+    assert!(CreateGraphUser::new(with_program, "US").is_err());
+}
+
+#[test]
+pub fn test_mail_nickname() {
+    assert_eq!(mail_nickname("rogerfederer@developforgood.org"), "rogerfederer");
+    assert_eq!(mail_nickname("cohort"), "cohort");
+}
+
+#[test]
+pub fn test_graph_error_classification() {
+    let taken = graph_error(
+        400,
+        "Another object with the same value for property userPrincipalName already exists.",
+    );
+    assert!(is_conflict(&taken));
+    assert!(!is_retryable(&taken));
+
+    let throttled = graph_error(429, "Too many requests");
+    assert!(is_retryable(&throttled));
+    assert!(!is_conflict(&throttled));
+
+    let invalid = graph_error(400, "Invalid value specified for property 'mailNickname'.");
+    assert!(!is_conflict(&invalid));
+    assert!(!is_retryable(&invalid));
+}
@@ -0,0 +1,24 @@
+use rstest::rstest;
+
+use crate::app::api::v1::data_exports::workspace::groups::{validate_groups, MAX_EXPORT_GROUPS};
+
+#[rstest]
+#[case(&[], true)]
+#[case(&["cohort-fall@developforgood.org"], true)]
+#[case(&["cohort-fall@developforgood.org", "announcements@developforgood.org"], true)]
+#[case(&["cohort-fall"], false)]
+#[case(&["cohort fall@developforgood.org"], false)]
+#[case(&["cohort@developforgood.org", "Cohort@developforgood.org"], false)]
+pub fn test_validate_groups(#[case] groups: &[&str], #[case] valid: bool) {
+    let groups = groups.iter().map(|g| g.to_string()).collect::<Vec<String>>();
+    assert_eq!(validate_groups(&groups).is_ok(), valid, "{groups:?}");
+}
+
+#[test]
+pub fn test_validate_too_many_groups() {
+    let groups = (0..=MAX_EXPORT_GROUPS)
+        .map(|i| format!("cohort-{i}@developforgood.org"))
+        .collect::<Vec<String>>();
+    assert!(validate_groups(&groups).is_err());
+    assert!(validate_groups(&groups[1..]).is_ok());
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use rstest::rstest;
+
+use crate::app::api::v1::data_exports::workspace::licenses::check_seats;
+use crate::services::workspace::emulator::{EmulatorConfigBuilder, EmulatorWorkspaceClient};
+use crate::services::workspace::WorkspaceClient;
+
+const PRINCIPAL: &str = "admin@developforgood.org";
+
+#[rstest]
+#[case(80, 100, 20, Some(80))]
+#[case(1, 100, 99, Some(1))]
+#[case(0, 100, 100, Some(0))]
+#[case(120, 100, 20, None)]
+#[case(1, 100, 100, None)]
+// More seats can be assigned than purchased while a subscription is being changed
+#[case(1, 100, 120, None)]
+pub fn test_check_seats(
+    #[case] needed: usize,
+    #[case] seats: usize,
+    #[case] assigned: usize,
+    #[case] available: Option<usize>,
+) {
+    let result = check_seats("1010020027", needed, seats, assigned);
+    assert_eq!(result.as_ref().ok().copied(), available, "{result:?}");
+}
+
+#[test]
+pub fn test_check_seats_error() {
+    let err = check_seats("1010020027", 120, 100, 20).expect_err("there are not enough seats");
+    assert_eq!(
+        err.to_string(),
+        "Not enough 1010020027 licenses: the export needs 120 seats, but only 80 of 100 are \
+         available"
+    );
+}
+
+#[tokio::test]
+pub async fn test_emulator_license_seats() -> Result<()> {
+    let config = EmulatorConfigBuilder::default()
+        .license_seats(HashMap::from([("1010020027".to_owned(), 100)]))
+        .build()?;
+    let workspace = EmulatorWorkspaceClient::new(config);
+
+    assert_eq!(workspace.license_seats(PRINCIPAL, "Google-Apps", "1010020027").await?, Some(100));
+    assert_eq!(workspace.license_seats(PRINCIPAL, "Google-Apps", "1010020028").await?, None);
+
+    Ok(())
+}
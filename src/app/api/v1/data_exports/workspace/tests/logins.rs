@@ -0,0 +1,47 @@
+use uuid::uuid;
+
+use crate::app::api::v1::data_exports::workspace::logins::LoginSyncSummary;
+use crate::services::storage::entities::SuspensionCandidate;
+use crate::services::workspace::entities::{parse_last_login_time, WorkspaceAccount};
+
+fn volunteer() -> SuspensionCandidate {
+    SuspensionCandidate {
+        volunteer_id: uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90"),
+        first_name: "Roger".to_owned(),
+        last_name: "Federer".to_owned(),
+        workspace_email: "rogerfederer@developforgood.org".to_owned(),
+    }
+}
+
+fn account(last_login_time: Option<&str>) -> WorkspaceAccount {
+    WorkspaceAccount {
+        primary_email: "rogerfederer@developforgood.org".to_owned(),
+        recovery_email: None,
+        suspended: false,
+        last_login_at: parse_last_login_time(last_login_time),
+        org_unit_path: "/Programs/PantheonUsers".to_owned(),
+    }
+}
+
+#[test]
+pub fn test_login_sync_summary() {
+    let mut summary = LoginSyncSummary::default();
+
+    let activated = summary
+        .record(&volunteer(), Some(&account(Some("2026-10-15T18:30:00.000Z"))))
+        .expect("an existing account is recorded");
+    assert!(activated.last_login_at.is_some());
+
+    // Google reports accounts that have never been signed in to as signing in at the epoch
+    let never_activated = summary
+        .record(&volunteer(), Some(&account(Some("1970-01-01T00:00:00.000Z"))))
+        .expect("an existing account is recorded");
+    assert_eq!(never_activated.last_login_at, None);
+
+    assert!(summary.record(&volunteer(), None).is_none());
+
+    assert_eq!(
+        summary,
+        LoginSyncSummary { activated: 1, never_activated: 1, missing: 1, failed: 0 }
+    );
+}
@@ -0,0 +1,27 @@
+mod admin_pool;
+mod calendar;
+mod deprovisioning;
+mod digest;
+mod drives;
+mod email_retries;
+mod emulator;
+mod graph;
+mod groups;
+mod licenses;
+mod logins;
+mod org_units;
+mod outbox;
+mod personalization;
+mod phones;
+mod policies;
+mod programs;
+mod queue;
+mod reconciliation;
+mod recovery;
+mod reminders;
+mod retention;
+mod retry;
+mod scheduler;
+mod scim;
+mod slack;
+mod transfers;
@@ -0,0 +1,12 @@
+use rstest::rstest;
+
+use crate::app::api::v1::data_exports::workspace::org_units::org_unit_ancestors;
+
+#[rstest]
+#[case("/", &[])]
+#[case("/Programs", &["/Programs"])]
+#[case("/Programs/2025-Spring", &["/Programs", "/Programs/2025-Spring"])]
+#[case("/Programs/2025/Spring", &["/Programs", "/Programs/2025", "/Programs/2025/Spring"])]
+pub fn test_org_unit_ancestors(#[case] org_unit: &str, #[case] expected: &[&str]) {
+    assert_eq!(org_unit_ancestors(org_unit), expected);
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use uuid::uuid;
+
+use crate::app::api::v1::data_exports::workspace::outbox::OutboxClaim;
+use crate::services::mail::OnboardingEmailParamsBuilder;
+
+#[test]
+fn test_outbox_claim() -> Result<()> {
+    let job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let volunteer_id = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+    let exported = OnboardingEmailParamsBuilder::default()
+        .first_name("Rafael")
+        .last_name("Nadal")
+        .email("rafaelnadal@gmail.com")
+        .workspace_email("rafaelnadal@developforgood.org")
+        .temporary_password("password123")
+        .onboarding_session_link("https://meet.google.com/abc-defg-hij")
+        .job_id(job_id)
+        .volunteer_id(volunteer_id)
+        .build()?;
+    // not sent by an export, so it has nowhere to be recorded against
+    let other = OnboardingEmailParamsBuilder::default()
+        .first_name("Roger")
+        .last_name("Federer")
+        .email("rogerfederer@gmail.com")
+        .workspace_email("rogerfederer@developforgood.org")
+        .build()?;
+
+    let (claim, outbox) = OutboxClaim::new(&[exported.clone(), other.clone()]);
+
+    assert_eq!(outbox.len(), 1);
+    assert_eq!(outbox[0].job_id, job_id);
+    assert_eq!(outbox[0].volunteer_id, volunteer_id);
+    assert_eq!(outbox[0].temporary_password.as_deref(), Some("password123"));
+    assert_eq!(
+        outbox[0].onboarding_session_link.as_deref(),
+        Some("https://meet.google.com/abc-defg-hij")
+    );
+    assert_eq!(claim.email_id(&exported), Some(outbox[0].id));
+    assert_eq!(claim.email_id(&other), None);
+
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use std::fs;
+
+use anyhow::Result;
+use rstest::rstest;
+use uuid::Uuid;
+
+use crate::app::api::v1::data_exports::workspace::personalization::{
+    render_signature, PersonalizationSettings, SignatureDetails, DEFAULT_SIGNATURE_TEMPLATE,
+};
+use crate::services::workspace::emulator::{EmulatorConfig, EmulatorWorkspaceClient};
+use crate::services::workspace::entities::{
+    CreateWorkspaceVolunteerBuilder, ProfilePhoto, ProgramMetadata,
+};
+use crate::services::workspace::WorkspaceClient;
+
+const PRINCIPAL: &str = "admin@developforgood.org";
+
+fn details<'a>(first_name: &'a str, program_name: Option<&'a str>) -> SignatureDetails<'a> {
+    SignatureDetails {
+        first_name,
+        last_name: "Federer",
+        workspace_email: "rogerfederer@developforgood.org",
+        program_name,
+        cohort: program_name.map(|_| "Spring 2025"),
+    }
+}
+
+#[rstest]
+#[case(true, None, false, true)]
+#[case(false, None, true, true)]
+#[case(true, Some("{{ first_name }} {{ last_name }}"), true, true)]
+#[case(false, None, false, false)]
+#[case(false, Some("{{ first_name }}"), true, false)]
+#[case(true, Some("{{ first_name"), false, false)]
+#[case(true, Some("{{ nickname }}"), false, false)]
+pub fn test_validate_personalization(
+    #[case] signature: bool,
+    #[case] signature_template: Option<&str>,
+    #[case] profile_photo: bool,
+    #[case] valid: bool,
+) {
+    let settings = PersonalizationSettings {
+        signature,
+        signature_template: signature_template.map(str::to_owned),
+        profile_photo,
+    };
+    assert_eq!(settings.validate().is_ok(), valid, "{settings:?}");
+}
+
+#[test]
+pub fn test_render_default_signature() -> Result<()> {
+    let signature =
+        render_signature(DEFAULT_SIGNATURE_TEMPLATE, &details("Roger", Some("Summer Fellowship")))?;
+    assert!(signature.contains("<strong>Roger Federer</strong>"));
+    assert!(signature.contains("Summer Fellowship &middot; Spring 2025"));
+    assert!(signature.contains("mailto:rogerfederer@developforgood.org"));
+
+    // Volunteers who are not tagged with a program get a signature without one
+    let signature = render_signature(DEFAULT_SIGNATURE_TEMPLATE, &details("Roger", None))?;
+    assert!(signature.contains("<strong>Roger Federer</strong>"));
+    assert!(!signature.contains("&middot;"));
+
+    Ok(())
+}
+
+#[test]
+pub fn test_render_signature_escapes_names() -> Result<()> {
+    let signature = render_signature("{{ first_name }}", &details("<b>Roger</b>", None))?;
+    assert_eq!(signature, "&lt;b&gt;Roger&lt;&#x2F;b&gt;");
+
+    Ok(())
+}
+
+#[test]
+pub fn test_signature_details_from_volunteer() -> Result<()> {
+    let volunteer = CreateWorkspaceVolunteerBuilder::default()
+        .primary_email("rogerfederer@developforgood.org")
+        .first_name("Roger")
+        .last_name("Federer")
+        .password("password")
+        .recovery_email("roger.federer@gmail.com")
+        .org_unit("/Programs/PantheonUsers")
+        .program(Some(ProgramMetadata {
+            program_name: "Summer Fellowship".to_owned(),
+            cohort: "Spring 2025".to_owned(),
+            volunteer_id: Uuid::nil(),
+        }))
+        .build()?;
+
+    let details = SignatureDetails::from(&volunteer);
+    assert_eq!(details.workspace_email, "rogerfederer@developforgood.org");
+    assert_eq!(details.program_name, Some("Summer Fellowship"));
+    assert_eq!(details.cohort, Some("Spring 2025"));
+
+    Ok(())
+}
+
+#[test]
+pub fn test_profile_photo_from_path() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("scipio-photo-{}", Uuid::new_v4()));
+    fs::create_dir_all(&dir)?;
+
+    let png = dir.join("photo.PNG");
+    fs::write(&png, [0x89, b'P', b'N', b'G'])?;
+    let photo = ProfilePhoto::from_path(&png)?;
+    assert_eq!(photo.content_type, "image/png");
+    assert_eq!(photo.data.len(), 4);
+
+    // Formats that Microsoft 365 does not accept are rejected up front
+    let webp = dir.join("photo.webp");
+    fs::write(&webp, [0x52, 0x49, 0x46, 0x46])?;
+    assert!(ProfilePhoto::from_path(&webp).is_err());
+
+    let empty = dir.join("empty.jpg");
+    fs::write(&empty, [])?;
+    assert!(ProfilePhoto::from_path(&empty).is_err());
+
+    assert!(ProfilePhoto::from_path(&dir.join("missing.jpg")).is_err());
+
+    fs::remove_dir_all(&dir)?;
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_emulator_personalization() -> Result<()> {
+    let workspace = EmulatorWorkspaceClient::new(EmulatorConfig::default());
+    let volunteer = CreateWorkspaceVolunteerBuilder::default()
+        .primary_email("rogerfederer@developforgood.org")
+        .first_name("Roger")
+        .last_name("Federer")
+        .password("password")
+        .recovery_email("roger.federer@gmail.com")
+        .org_unit("/Programs/PantheonUsers")
+        .build()?;
+    workspace.create_volunteer(PRINCIPAL, volunteer).await?;
+
+    let photo = ProfilePhoto { data: vec![0xff, 0xd8], content_type: "image/jpeg" };
+    workspace.set_signature(PRINCIPAL, "RogerFederer@developforgood.org", "<b>Roger</b>").await?;
+    workspace.set_profile_photo(PRINCIPAL, "rogerfederer@developforgood.org", &photo).await?;
+
+    assert_eq!(
+        workspace.signature("rogerfederer@developforgood.org").as_deref(),
+        Some("<b>Roger</b>")
+    );
+    assert!(workspace.has_profile_photo("rogerfederer@developforgood.org"));
+
+    // Accounts that do not exist cannot be set up
+    assert!(workspace.set_signature(PRINCIPAL, "rafanadal@developforgood.org", "").await.is_err());
+    assert!(workspace
+        .set_profile_photo(PRINCIPAL, "rafanadal@developforgood.org", &photo)
+        .await
+        .is_err());
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use rstest::rstest;
+use scipio_workspace::user::CreateWorkspaceUser;
+use serde_json::json;
+
+use crate::services::workspace::entities::{
+    recovery_phone, CreateWorkspaceVolunteer, CreateWorkspaceVolunteerBuilder,
+};
+use crate::services::workspace::graph::CreateGraphUser;
+use crate::services::workspace::scim::create_user_body;
+
+fn volunteer(recovery_phone: Option<&str>) -> CreateWorkspaceVolunteer {
+    CreateWorkspaceVolunteerBuilder::default()
+        .primary_email("rogerfederer@developforgood.org")
+        .first_name("Roger")
+        .last_name("Federer")
+        .password("password")
+        .recovery_email("roger.federer@gmail.com")
+        .recovery_phone(recovery_phone.map(str::to_owned))
+        .org_unit("/")
+        .build()
+        .expect("error building volunteer")
+}
+
+#[rstest]
+#[case("202-555-0123", "United States", Some("+12025550123"))]
+#[case("(202) 555.0123", "United States", Some("+12025550123"))]
+#[case("1 202 555 0123", "United States", Some("+12025550123"))]
+#[case(" +1 202-555-0123 ", "United States", Some("+12025550123"))]
+#[case("+44 20 7946 0958", "United Kingdom", Some("+442079460958"))]
+#[case("020 7946 0958", "United Kingdom", None)]
+#[case("555-0123", "United States", None)]
+#[case("202-555-0123 ext. 4", "United States", None)]
+#[case("+0 202 555 0123", "United States", None)]
+#[case("+1234567890123456", "United States", None)]
+#[case("", "United States", None)]
+pub fn test_recovery_phone(
+    #[case] phone: &str,
+    #[case] country: &str,
+    #[case] expected: Option<&str>,
+) {
+    assert_eq!(recovery_phone(phone, country).as_deref(), expected, "{phone} in {country}");
+}
+
+#[test]
+pub fn test_workspace_user_recovery_phone() -> Result<()> {
+    let user =
+        serde_json::to_value(CreateWorkspaceUser::try_from(volunteer(Some("+12025550123")))?)?;
+    assert_eq!(user["recoveryPhone"], json!("+12025550123"));
+
+    // Google rejects an empty recovery phone, so none is sent at all
+    let user = serde_json::to_value(CreateWorkspaceUser::try_from(volunteer(None))?)?;
+    assert!(user.get("recoveryPhone").is_none());
+
+    Ok(())
+}
+
+#[test]
+pub fn test_graph_user_recovery_phone() -> Result<()> {
+    let user = serde_json::to_value(CreateGraphUser::new(volunteer(Some("+12025550123")), "US")?)?;
+    assert_eq!(user["mobilePhone"], json!("+12025550123"));
+
+    let user = serde_json::to_value(CreateGraphUser::new(volunteer(None), "US")?)?;
+    assert!(user.get("mobilePhone").is_none());
+
+    Ok(())
+}
+
+#[test]
+pub fn test_scim_user_recovery_phone() -> Result<()> {
+    let body = create_user_body(volunteer(Some("+12025550123")))?;
+    assert_eq!(body["phoneNumbers"], json!([{ "value": "+12025550123", "type": "mobile" }]));
+
+    let body = create_user_body(volunteer(None))?;
+    assert!(body.get("phoneNumbers").is_none());
+
+    Ok(())
+}
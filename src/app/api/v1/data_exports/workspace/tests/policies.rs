@@ -0,0 +1,429 @@
+use std::time::Duration;
+
+use chrono::DateTime;
+use rstest::rstest;
+use scipio_workspace::policy::Policy;
+use serde_json::json;
+
+use crate::app::api::v1::data_exports::workspace::policies::{
+    normalize_name, validate_domain, CharacterClass, CollisionStrategy, EmailBlocklist,
+    EmailFormat, EmailPolicy, PasswordConfig, PasswordDelivery, PasswordPolicy, PasswordStyle,
+    TwoStepVerificationPolicy, WorkspaceLicense, DEFAULT_EMAIL_DOMAIN, MAX_EMAIL_ALIASES,
+    MAX_LOCAL_PART_LENGTH, MIN_PASSPHRASE_WORDS,
+};
+use crate::services::workspace::entities::{parse_policy_duration, TwoStepVerificationSettings};
+
+fn email_policy(separator: Option<&str>) -> EmailPolicy {
+    EmailPolicy {
+        add_unique_numeric_suffix: false,
+        collision_strategy: CollisionStrategy::NumericSuffix,
+        format: EmailFormat::default(),
+        alias_formats: Vec::new(),
+        separator: separator.map(str::to_owned),
+        use_first_and_last_name: true,
+        domain: DEFAULT_EMAIL_DOMAIN.to_owned(),
+        blocklist: EmailBlocklist::default(),
+    }
+}
+
+#[rstest]
+#[case("José", "jose")]
+#[case("Müller-Łukasz", "mullerlukasz")]
+#[case("Ｊｕｌｉｅ", "julie")]
+#[case("O'Brien", "obrien")]
+#[case("Zoë  Saldaña", "zoesaldana")]
+#[case("Александр", "aleksandr")]
+#[case("Σωκράτης", "sokrates")]
+#[case("北京", "beijing")]
+pub fn test_normalize_name(#[case] name: &str, #[case] expected: &str) {
+    assert_eq!(normalize_name(name), expected);
+}
+
+#[rstest]
+#[case("José", "Müller-Łukasz", None, "josemullerlukasz@developforgood.org")]
+#[case("José", "Müller-Łukasz", Some("."), "jose.mullerlukasz@developforgood.org")]
+#[case("Anna", "Иванова", Some("_"), "anna_ivanova@developforgood.org")]
+#[case("", "Doe", Some("."), "doe@developforgood.org")]
+#[case("Jane", "Doe", Some(" + "), "janedoe@developforgood.org")]
+pub fn test_build_volunteer_email(
+    #[case] first_name: &str,
+    #[case] last_name: &str,
+    #[case] separator: Option<&str>,
+    #[case] expected: &str,
+) {
+    let policy = email_policy(separator);
+    assert_eq!(policy.build_volunteer_email(first_name, last_name).unwrap(), expected);
+}
+
+#[test]
+pub fn test_build_volunteer_email_is_always_ascii() {
+    let policy = email_policy(Some("."));
+    for (first_name, last_name) in
+        [("Þórr", "Ægisson"), ("Ngọc", "Nguyễn"), ("Дмитрий", "Шостакович"), ("さくら", "山田")]
+    {
+        let email = policy.build_volunteer_email(first_name, last_name).unwrap();
+        let (local_part, _) = email.split_once('@').unwrap();
+        assert!(!local_part.is_empty(), "empty local part for {first_name} {last_name}");
+        assert!(
+            local_part.chars().all(|c| c.is_ascii_alphanumeric() || c == '.'),
+            "invalid local part {local_part} for {first_name} {last_name}"
+        );
+    }
+}
+
+#[test]
+pub fn test_candidate_emails() {
+    let policy = email_policy(None);
+    let candidates = policy.candidate_emails("Jane", "Doe").unwrap();
+    assert_eq!(candidates[0], "janedoe@developforgood.org");
+    assert_eq!(candidates[1], "janedoe2@developforgood.org");
+    assert_eq!(candidates[2], "janedoe3@developforgood.org");
+
+    let policy = EmailPolicy { collision_strategy: CollisionStrategy::MiddleInitial, ..policy };
+    let candidates = policy.candidate_emails("Mary Jane", "Doe").unwrap();
+    assert_eq!(candidates[0], "maryjanedoe@developforgood.org");
+    assert_eq!(candidates[1], "maryjdoe@developforgood.org");
+    assert_eq!(candidates[2], "maryjanedoe2@developforgood.org");
+}
+
+#[rstest]
+#[case(EmailFormat::FirstSeparatorLast, "jose_muller@developforgood.org")]
+#[case(EmailFormat::FirstDotLast, "jose.muller@developforgood.org")]
+#[case(EmailFormat::InitialDotLast, "j.muller@developforgood.org")]
+#[case(EmailFormat::FirstLastInitial, "josem@developforgood.org")]
+#[case(
+    EmailFormat::Custom("{{ last }}{{ separator }}{{ first_initial }}".to_owned()),
+    "muller_j@developforgood.org"
+)]
+#[case(EmailFormat::Custom("{{ first }} + {{ last }}!".to_owned()), "josemuller@developforgood.org")]
+pub fn test_email_formats(#[case] format: EmailFormat, #[case] expected: &str) {
+    let policy = EmailPolicy { format, ..email_policy(Some("_")) };
+    assert!(policy.validate().is_ok());
+    assert_eq!(policy.build_volunteer_email("José", "Müller").unwrap(), expected);
+}
+
+#[rstest]
+#[case(EmailFormat::Custom("{{ first".to_owned()))]
+#[case(EmailFormat::Custom("{{ middle }}".to_owned()))]
+#[case(EmailFormat::Custom("+++".to_owned()))]
+pub fn test_invalid_email_formats(#[case] format: EmailFormat) {
+    let policy = EmailPolicy { format, ..email_policy(None) };
+    assert!(policy.validate().is_err());
+}
+
+#[test]
+pub fn test_email_local_part_length() {
+    let policy = email_policy(None);
+    let first_name = "a".repeat(30);
+
+    let fits = "b".repeat(MAX_LOCAL_PART_LENGTH - 30);
+    let candidates = policy.candidate_emails(&first_name, &fits).unwrap();
+    assert_eq!(candidates.len(), 1);
+    assert!(candidates.iter().all(|c| c.split_once('@').unwrap().0.len() <= MAX_LOCAL_PART_LENGTH));
+
+    let too_long = "b".repeat(MAX_LOCAL_PART_LENGTH - 29);
+    assert!(policy.build_volunteer_email(&first_name, &too_long).is_err());
+    assert!(policy.candidate_emails(&first_name, &too_long).is_err());
+}
+
+#[test]
+pub fn test_deserialize_email_format() {
+    let format = serde_json::from_str::<EmailFormat>(r#""initialDotLast""#).unwrap();
+    assert_eq!(format, EmailFormat::InitialDotLast);
+
+    let format = serde_json::from_str::<EmailFormat>(r#"{"custom": "{{ first }}"}"#).unwrap();
+    assert_eq!(format, EmailFormat::Custom("{{ first }}".to_owned()));
+}
+
+#[rstest]
+#[case("admin@developforgood.org", true)]
+#[case("Ad.Min@developforgood.org", true)]
+#[case("post_master@developforgood.org", true)]
+#[case("admin2@developforgood.org", false)]
+#[case("president@developforgood.org", true)]
+#[case("jane.badword.doe@developforgood.org", true)]
+#[case("janedoe@developforgood.org", false)]
+pub fn test_email_blocklist(#[case] email: &str, #[case] blocked: bool) {
+    let blocklist = EmailBlocklist {
+        reserved: vec!["President".to_owned()],
+        blocked_substrings: vec!["badword".to_owned()],
+    };
+    assert_eq!(blocklist.check(email).is_some(), blocked);
+}
+
+#[test]
+pub fn test_candidate_emails_fall_back_when_blocked() {
+    let policy = EmailPolicy { use_first_and_last_name: false, ..email_policy(None) };
+    let candidates = policy.candidate_emails("Admin", "Smith").unwrap();
+    assert_eq!(candidates[0], "admin@developforgood.org");
+    assert_eq!(candidates[1], "a.smith@developforgood.org");
+    assert_eq!(candidates[2], "admins@developforgood.org");
+    assert_eq!(candidates[3], "admin2@developforgood.org");
+
+    let policy = EmailPolicy {
+        blocklist: EmailBlocklist {
+            blocked_substrings: vec!["ass".to_owned()],
+            ..Default::default()
+        },
+        ..email_policy(None)
+    };
+    let candidates = policy.candidate_emails("Cassandra", "Lee").unwrap();
+    assert_eq!(candidates[0], "cassandralee@developforgood.org");
+    assert_eq!(candidates[1], "c.lee@developforgood.org");
+    assert!(policy.blocklist.check(&candidates[1]).is_none());
+}
+
+#[test]
+pub fn test_email_domain() {
+    let policy = EmailPolicy { domain: "program-a.org".to_owned(), ..email_policy(Some(".")) };
+    assert!(policy.validate().is_ok());
+    assert_eq!(policy.build_volunteer_email("Jane", "Doe").unwrap(), "jane.doe@program-a.org");
+
+    let candidates = policy.candidate_emails("Jane", "Doe").unwrap();
+    assert!(candidates.iter().all(|c| c.ends_with("@program-a.org")));
+}
+
+#[rstest]
+#[case("developforgood.org", true)]
+#[case("mail.program-b.org", true)]
+#[case("localhost", false)]
+#[case("program-.org", false)]
+#[case("program..org", false)]
+#[case("@program-a.org", false)]
+pub fn test_validate_domain(#[case] domain: &str, #[case] valid: bool) {
+    assert_eq!(validate_domain(domain).is_ok(), valid);
+}
+
+fn password_policy(length: u8, config: PasswordConfig) -> PasswordPolicy {
+    PasswordPolicy {
+        change_password_at_next_login: true,
+        generated_password_length: length,
+        style: PasswordStyle::Random,
+        delivery: PasswordDelivery::Email,
+        config,
+    }
+}
+
+#[test]
+pub fn test_generate_password_contains_required_classes() {
+    let config = PasswordConfig {
+        min_length: 4,
+        required_classes: vec![
+            CharacterClass::Lowercase,
+            CharacterClass::Uppercase,
+            CharacterClass::Digit,
+            CharacterClass::Symbol,
+        ],
+        exclude_ambiguous: false,
+    };
+    let policy = password_policy(4, config.clone());
+    assert!(policy.validate().is_ok());
+
+    for _ in 0..100 {
+        let password = policy.generate_password();
+        assert_eq!(password.chars().count(), 4);
+        for class in &config.required_classes {
+            let characters = class.characters(false);
+            assert!(
+                password.chars().any(|c| characters.contains(&c)),
+                "{password} has no {class:?}"
+            );
+        }
+    }
+}
+
+#[test]
+pub fn test_generate_password_excludes_ambiguous_characters() {
+    let config = PasswordConfig { exclude_ambiguous: true, ..PasswordConfig::default() };
+    let policy = password_policy(64, config);
+
+    for _ in 0..100 {
+        let password = policy.generate_password();
+        assert_eq!(password.len(), 64);
+        assert!(!password.contains(['0', 'O', '1', 'l', 'I']), "{password} is ambiguous");
+    }
+}
+
+#[test]
+pub fn test_generate_password_falls_back_to_min_length() {
+    let config = PasswordConfig { min_length: 12, ..PasswordConfig::default() };
+    let policy = password_policy(8, config);
+
+    assert!(policy.validate().is_err());
+    assert_eq!(policy.generate_password().len(), 12);
+}
+
+#[rstest]
+#[case(8)]
+#[case(40)]
+pub fn test_generate_passphrase(#[case] length: u8) {
+    let config = PasswordConfig { exclude_ambiguous: true, ..PasswordConfig::default() };
+    let policy =
+        PasswordPolicy { style: PasswordStyle::Passphrase, ..password_policy(length, config) };
+
+    for _ in 0..100 {
+        let passphrase = policy.generate_password();
+        let parts = passphrase.split('-').collect::<Vec<&str>>();
+        let (number, words) = parts.split_last().unwrap();
+
+        assert!(passphrase.len() >= length as usize, "{passphrase} is too short");
+        assert!(words.len() >= MIN_PASSPHRASE_WORDS, "{passphrase} has too few words");
+        assert!(words.iter().all(|w| !w.is_empty() && w.chars().all(|c| c.is_ascii_lowercase())));
+        assert_eq!(number.len(), 2);
+        assert!(number.chars().all(|c| c.is_ascii_digit() && c != '0' && c != '1'));
+    }
+}
+
+#[rstest]
+#[case(r#"{}"#, PasswordConfig::default())]
+#[case(
+    r#"{"minLength": 16, "requiredClasses": ["lowercase", "digit"], "excludeAmbiguous": true}"#,
+    PasswordConfig {
+        min_length: 16,
+        required_classes: vec![CharacterClass::Lowercase, CharacterClass::Digit],
+        exclude_ambiguous: true,
+    }
+)]
+pub fn test_deserialize_password_config(#[case] raw: &str, #[case] expected: PasswordConfig) {
+    let config = serde_json::from_str::<PasswordConfig>(raw).unwrap();
+    assert_eq!(config, expected);
+}
+
+#[rstest]
+#[case(PasswordConfig { required_classes: vec![], ..PasswordConfig::default() })]
+#[case(PasswordConfig { min_length: 65, ..PasswordConfig::default() })]
+pub fn test_invalid_password_config(#[case] config: PasswordConfig) {
+    assert!(config.validate().is_err());
+}
+
+#[test]
+pub fn test_alias_emails() {
+    let policy = EmailPolicy {
+        add_unique_numeric_suffix: true,
+        alias_formats: vec![
+            EmailFormat::Custom("{{ first }}".to_owned()),
+            EmailFormat::FirstLastInitial,
+            EmailFormat::Custom("{{ first }}".to_owned()),
+        ],
+        ..email_policy(Some("."))
+    };
+
+    // aliases never get a numeric suffix, and duplicates are dropped
+    let aliases = policy.alias_emails("Jane", "Doe", "jane.doe42@developforgood.org");
+    assert_eq!(aliases, vec!["jane@developforgood.org", "janed@developforgood.org"]);
+
+    // an alias is never the primary email
+    let aliases = policy.alias_emails("Jane", "Doe", "jane@developforgood.org");
+    assert_eq!(aliases, vec!["janed@developforgood.org"]);
+}
+
+#[test]
+pub fn test_validate_alias_formats() {
+    let policy = EmailPolicy {
+        alias_formats: vec![EmailFormat::Custom("{{ first".to_owned())],
+        ..email_policy(None)
+    };
+    assert!(policy.validate().is_err());
+
+    let policy = EmailPolicy {
+        alias_formats: vec![EmailFormat::FirstLastInitial; MAX_EMAIL_ALIASES + 1],
+        ..email_policy(None)
+    };
+    assert!(policy.validate().is_err());
+
+    let policy = EmailPolicy {
+        alias_formats: vec![EmailFormat::FirstLastInitial; MAX_EMAIL_ALIASES],
+        ..email_policy(None)
+    };
+    assert!(policy.validate().is_ok());
+}
+
+#[rstest]
+#[case("Google-Apps", "1010020027", Some(10), true)]
+#[case("Google-Apps", "1010020027", None, true)]
+#[case("Google-Apps", "1010020027", Some(0), false)]
+#[case("", "1010020027", Some(10), false)]
+#[case("Google-Apps", " ", Some(10), false)]
+pub fn test_validate_workspace_license(
+    #[case] product_id: &str,
+    #[case] sku_id: &str,
+    #[case] seats: Option<usize>,
+    #[case] valid: bool,
+) {
+    let license =
+        WorkspaceLicense { product_id: product_id.to_owned(), sku_id: sku_id.to_owned(), seats };
+    assert_eq!(license.validate().is_ok(), valid);
+}
+
+#[rstest]
+#[case(true, true, Some(7), None, true)]
+#[case(true, true, Some(7), Some(14), true)]
+#[case(true, true, Some(7), Some(7), true)]
+#[case(true, true, Some(14), Some(7), false)]
+#[case(true, true, None, None, false)]
+#[case(true, false, Some(7), None, false)]
+#[case(false, true, Some(7), None, false)]
+pub fn test_two_step_verification_policy(
+    #[case] allow_enrollment: bool,
+    #[case] enforced: bool,
+    #[case] grace_period_days: Option<u64>,
+    #[case] max_grace_period_days: Option<u32>,
+    #[case] satisfied: bool,
+) {
+    let settings = TwoStepVerificationSettings {
+        allow_enrollment,
+        enforced_from: enforced.then_some(DateTime::UNIX_EPOCH),
+        enrollment_grace_period: grace_period_days.map(|d| Duration::from_secs(d * 24 * 60 * 60)),
+    };
+    let policy = TwoStepVerificationPolicy { max_grace_period_days };
+    assert_eq!(policy.check("/Programs/2025-Spring", &settings).is_ok(), satisfied, "{settings:?}");
+}
+
+#[test]
+pub fn test_two_step_verification_settings_from_policies() {
+    let policy = |org_unit: &str, setting_type: &str, value: serde_json::Value| {
+        serde_json::from_value::<Policy>(json!({
+            "name": format!("policies/{org_unit}-{setting_type}"),
+            "policyQuery": { "orgUnit": format!("orgUnits/{org_unit}") },
+            "setting": { "type": format!("settings/security.{setting_type}"), "value": value },
+        }))
+        .unwrap()
+    };
+    let policies = vec![
+        policy("root", "two_step_verification_enforcement", json!({})),
+        policy(
+            "programs",
+            "two_step_verification_enforcement",
+            json!({ "enforcedFrom": "2024-10-15T00:00:00Z" }),
+        ),
+        policy(
+            "programs",
+            "two_step_verification_grace_period",
+            json!({ "enrollmentGracePeriod": "604800s" }),
+        ),
+    ];
+
+    let ancestors = ["cohort", "programs", "root"].map(str::to_owned);
+    let settings = TwoStepVerificationSettings::from_policies(&policies, &ancestors);
+    assert!(settings.allow_enrollment);
+    assert_eq!(
+        settings.enforced_from,
+        Some(DateTime::parse_from_rfc3339("2024-10-15T00:00:00Z").unwrap().into())
+    );
+    assert_eq!(settings.enrollment_grace_period, Some(Duration::from_secs(604_800)));
+
+    // the root org unit does not enforce 2-Step Verification
+    let settings = TwoStepVerificationSettings::from_policies(&policies, &ancestors[2..]);
+    assert_eq!(settings.enforced_from, None);
+    assert_eq!(settings.enrollment_grace_period, None);
+}
+
+#[rstest]
+#[case("604800s", Some(Duration::from_secs(604_800)))]
+#[case("0.5s", Some(Duration::from_millis(500)))]
+#[case("0s", Some(Duration::ZERO))]
+#[case("604800", None)]
+#[case("-1s", None)]
+pub fn test_parse_policy_duration(#[case] duration: &str, #[case] parsed: Option<Duration>) {
+    assert_eq!(parse_policy_duration(duration), parsed);
+}
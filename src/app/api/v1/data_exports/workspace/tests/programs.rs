@@ -0,0 +1,53 @@
+use anyhow::Result;
+use rstest::rstest;
+use scipio_workspace::user::CreateWorkspaceUser;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::app::api::v1::data_exports::workspace::programs::ProgramSettings;
+use crate::services::workspace::entities::{CreateWorkspaceVolunteerBuilder, ProgramMetadata};
+
+#[rstest]
+#[case("Summer Fellowship", true)]
+#[case("", false)]
+#[case("   ", false)]
+pub fn test_validate_program(#[case] name: &str, #[case] valid: bool) {
+    let settings = ProgramSettings { name: name.to_owned() };
+    assert_eq!(settings.validate().is_ok(), valid, "{settings:?}");
+}
+
+#[rstest]
+#[case(None, json!(null))]
+#[case(
+    Some(ProgramMetadata {
+        program_name: "Summer Fellowship".to_owned(),
+        cohort: "Spring 2025".to_owned(),
+        volunteer_id: Uuid::nil(),
+    }),
+    json!({
+        "Program": {
+            "programName": "Summer Fellowship",
+            "cohort": "Spring 2025",
+            "volunteerId": "00000000-0000-0000-0000-000000000000",
+        },
+    })
+)]
+pub fn test_program_custom_schemas(
+    #[case] program: Option<ProgramMetadata>,
+    #[case] expected: serde_json::Value,
+) -> Result<()> {
+    let volunteer = CreateWorkspaceVolunteerBuilder::default()
+        .primary_email("jane.doe@developforgood.org")
+        .first_name("Jane")
+        .last_name("Doe")
+        .password("password")
+        .recovery_email("jane@example.com")
+        .org_unit("/Programs/PantheonUsers")
+        .program(program)
+        .build()?;
+
+    let user = serde_json::to_value(CreateWorkspaceUser::try_from(volunteer)?)?;
+    assert_eq!(user["customSchemas"], expected);
+
+    Ok(())
+}
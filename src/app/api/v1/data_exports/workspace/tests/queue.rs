@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde_json::json;
+use uuid::uuid;
+
+use crate::app::api::v1::data_exports::workspace::queue::{
+    is_permanent_job_error, JobRetryPolicy, JobTimeoutError, JobTimeouts, PermanentJobError,
+    QueuedExport, QueuedTask, DEFAULT_JOB_TIMEOUT, MAX_JOB_RETRY_BACKOFF,
+};
+use crate::services::storage::queue::{is_claim_lost, ClaimLostError};
+
+#[test]
+fn test_queued_task() -> Result<()> {
+    let project_cycle_id = uuid!("5b1a1b5e-16d0-4d4a-b18f-dc80df15b18b");
+    let request = serde_json::from_value(json!({
+        "addUniqueNumericSuffix": true,
+        "changePasswordAtNextLogin": true,
+        "exportCohort": true,
+        "generatedPasswordLength": 16,
+        "separator": ".",
+        "skipUsersOnConflict": true,
+        "useFirstAndLastName": true,
+    }))?;
+    let task = QueuedTask::WorkspaceExport(QueuedExport {
+        project_cycle_id,
+        principal: "admin@developforgood.org".to_owned(),
+        org_unit: "/Programs/PantheonUsers".to_owned(),
+        request,
+    });
+
+    let payload = serde_json::to_value(&task)?;
+    assert_eq!(payload["kind"], "workspaceExport");
+    assert_eq!(payload["orgUnit"], "/Programs/PantheonUsers");
+
+    // a worker reads back what was queued
+    let QueuedTask::WorkspaceExport(export) = serde_json::from_value(payload)? else {
+        panic!("expected an export");
+    };
+    assert_eq!(export.project_cycle_id, project_cycle_id);
+    assert!(export.request.export_cohort);
+    assert_eq!(export.request.generated_password_length, 16);
+
+    let resume = serde_json::to_value(QueuedTask::ResumeWorkspaceExport {
+        principal: "admin@developforgood.org".to_owned(),
+    })?;
+    assert_eq!(
+        resume,
+        json!({ "kind": "resumeWorkspaceExport", "principal": "admin@developforgood.org" })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_job_retry_policy() {
+    let policy = JobRetryPolicy { max_attempts: 4, backoff: Duration::from_secs(30) };
+    let now = Utc::now();
+
+    // the backoff doubles with every attempt that fails
+    assert_eq!(policy.next_attempt_at(1, now), Some(now + Duration::from_secs(30)));
+    assert_eq!(policy.next_attempt_at(2, now), Some(now + Duration::from_secs(60)));
+    assert_eq!(policy.next_attempt_at(3, now), Some(now + Duration::from_secs(120)));
+
+    // a job that failed on its last attempt is dead-lettered
+    assert_eq!(policy.next_attempt_at(4, now), None);
+
+    // the backoff never grows past the cap
+    let policy = JobRetryPolicy { max_attempts: 100, backoff: Duration::from_secs(30) };
+    assert_eq!(policy.next_attempt_at(40, now), Some(now + MAX_JOB_RETRY_BACKOFF));
+}
+
+#[test]
+fn test_job_timeouts() {
+    let resume = QueuedTask::ResumeWorkspaceExport { principal: "admin@developforgood.org".into() };
+    assert_eq!(JobTimeouts::default().for_task(&resume), Some(DEFAULT_JOB_TIMEOUT));
+
+    // each kind of job has its own timeout, and a kind without one runs for as long as it takes
+    let timeouts = JobTimeouts {
+        workspace_export: Some(Duration::from_secs(60)),
+        resume_workspace_export: None,
+    };
+    assert_eq!(timeouts.for_task(&resume), None);
+
+    let error = JobTimeoutError { timeout: Duration::from_secs(60) };
+    assert_eq!(error.to_string(), "job timed out after 60 seconds");
+}
+
+#[test]
+fn test_claim_lost() {
+    let job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let error = anyhow::Error::new(ClaimLostError { job_id }).context("error running job");
+    assert!(is_claim_lost(&error));
+    assert_eq!(
+        ClaimLostError { job_id }.to_string(),
+        "the claim on job bc080e0d-8b14-46e0-9268-4bbb370035ec was lost to another worker"
+    );
+
+    // failing to reach the database does not mean another worker holds the claim
+    assert!(!is_claim_lost(&anyhow!("connection reset")));
+}
+
+#[test]
+fn test_permanent_job_error() {
+    let error =
+        anyhow::Error::new(PermanentJobError(anyhow!("Org unit /Volunteers does not exist")))
+            .context("error running job");
+    assert!(is_permanent_job_error(&error));
+    assert_eq!(format!("{error:#}"), "error running job: Org unit /Volunteers does not exist");
+
+    // an API that is down may well be back up by the next attempt
+    assert!(!is_permanent_job_error(&anyhow!("connection reset")));
+}
@@ -0,0 +1,98 @@
+use chrono::Utc;
+use uuid::{uuid, Uuid};
+
+use crate::app::api::v1::data_exports::workspace::reconciliation::{
+    diff, MissingFromPantheon, MissingFromWorkspace,
+};
+use crate::services::storage::entities::ExportedVolunteerDetails;
+use crate::services::storage::types::JobStatus;
+use crate::services::workspace::entities::WorkspaceAccount;
+
+const ORG_UNIT: &str = "/Programs/PantheonUsers";
+
+fn exported(volunteer_id: Uuid, workspace_email: &str) -> ExportedVolunteerDetails {
+    ExportedVolunteerDetails {
+        id: Uuid::new_v4(),
+        created_at: Utc::now(),
+        updated_at: None,
+        volunteer_id,
+        workspace_email: workspace_email.to_owned(),
+        org_unit: ORG_UNIT.to_owned(),
+        job_id: uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742"),
+        project_cycle_id: uuid!("0c9d6d1c-1b1e-4f4b-9d5a-6f0b7d6f2a10"),
+        status: JobStatus::Complete,
+    }
+}
+
+fn account(primary_email: &str, recovery_email: Option<&str>) -> WorkspaceAccount {
+    WorkspaceAccount {
+        primary_email: primary_email.to_owned(),
+        recovery_email: recovery_email.map(str::to_owned),
+        suspended: false,
+        last_login_at: None,
+        org_unit_path: ORG_UNIT.to_owned(),
+    }
+}
+
+#[test]
+pub fn test_diff() {
+    let federer = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+    let nadal = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+
+    let records = vec![
+        exported(federer, "rogerfederer@developforgood.org"),
+        exported(nadal, "rafanadal@developforgood.org"),
+    ];
+    let accounts = vec![
+        // Google may return a differently cased address than the one Pantheon recorded
+        account("RogerFederer@developforgood.org", Some("roger.federer@gmail.com")),
+        account("novakdjokovic@developforgood.org", Some("novak.djokovic@gmail.com")),
+        account("andymurray@developforgood.org", None),
+    ];
+
+    let report = diff(ORG_UNIT, &records, &accounts);
+
+    assert_eq!(report.org_unit, ORG_UNIT);
+    assert_eq!(report.matched, 1);
+    assert_eq!(
+        report.missing_from_workspace,
+        vec![MissingFromWorkspace {
+            volunteer_id: nadal,
+            workspace_email: "rafanadal@developforgood.org".to_owned(),
+            org_unit: ORG_UNIT.to_owned(),
+        }]
+    );
+    assert_eq!(
+        report.missing_from_pantheon,
+        vec![
+            MissingFromPantheon {
+                workspace_email: "andymurray@developforgood.org".to_owned(),
+                recovery_email: None,
+                suspended: false,
+                org_unit_path: ORG_UNIT.to_owned(),
+            },
+            MissingFromPantheon {
+                workspace_email: "novakdjokovic@developforgood.org".to_owned(),
+                recovery_email: Some("novak.djokovic@gmail.com".to_owned()),
+                suspended: false,
+                org_unit_path: ORG_UNIT.to_owned(),
+            },
+        ]
+    );
+    assert_eq!((report.removed, report.recorded), (0, 0));
+}
+
+#[test]
+pub fn test_diff_in_sync() {
+    let federer = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+
+    let report = diff(
+        ORG_UNIT,
+        &[exported(federer, "rogerfederer@developforgood.org")],
+        &[account("rogerfederer@developforgood.org", None)],
+    );
+
+    assert_eq!(report.matched, 1);
+    assert!(report.missing_from_workspace.is_empty());
+    assert!(report.missing_from_pantheon.is_empty());
+}
@@ -0,0 +1,34 @@
+use std::collections::HashSet;
+
+use rstest::rstest;
+
+use crate::app::api::v1::data_exports::workspace::recovery::{
+    check_recovery_email, validate_recovery_email,
+};
+
+#[rstest]
+#[case("jane.doe@gmail.com", true)]
+#[case("jane+pantheon@school.edu", true)]
+#[case("o'brien@mail.example.co.uk", true)]
+#[case("jane.doe", false)]
+#[case("@gmail.com", false)]
+#[case("jane..doe@gmail.com", false)]
+#[case(".jane@gmail.com", false)]
+#[case("jane doe@gmail.com", false)]
+#[case("jane@gmail", false)]
+#[case("jane@gmail..com", false)]
+#[case("jane@-gmail.com", false)]
+pub fn test_validate_recovery_email(#[case] email: &str, #[case] valid: bool) {
+    assert_eq!(validate_recovery_email(email).is_ok(), valid, "{email}");
+}
+
+#[test]
+pub fn test_check_recovery_email() {
+    let undeliverable = HashSet::from(["gmial.com".to_owned()]);
+
+    assert!(check_recovery_email("jane@gmail.com", &undeliverable).is_none());
+    assert!(check_recovery_email("jane@GMIAL.com", &undeliverable)
+        .is_some_and(|reason| reason.contains("cannot receive email")));
+    assert!(check_recovery_email("jane", &undeliverable).is_some());
+    assert!(check_recovery_email("jane@gmial.com", &HashSet::new()).is_none());
+}
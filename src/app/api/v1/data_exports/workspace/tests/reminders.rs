@@ -0,0 +1,40 @@
+use chrono::DateTime;
+use rstest::rstest;
+
+use crate::app::api::v1::data_exports::workspace::reminders::reminder_status;
+use crate::services::storage::types::ActivationReminderStatus;
+use crate::services::workspace::entities::{parse_last_login_time, WorkspaceAccount};
+
+fn account(suspended: bool, last_login_time: Option<&str>) -> WorkspaceAccount {
+    WorkspaceAccount {
+        primary_email: "rafaelnadal@developforgood.org".to_owned(),
+        recovery_email: Some("rafael.nadal@gmail.com".to_owned()),
+        suspended,
+        last_login_at: parse_last_login_time(last_login_time),
+        org_unit_path: "/Programs/PantheonUsers".to_owned(),
+    }
+}
+
+#[rstest]
+#[case(None, ActivationReminderStatus::Skipped)]
+#[case(Some(account(true, None)), ActivationReminderStatus::Skipped)]
+#[case(Some(account(false, Some("2024-10-15T18:30:00.000Z"))), ActivationReminderStatus::Activated)]
+#[case(Some(account(false, Some("1970-01-01T00:00:00.000Z"))), ActivationReminderStatus::Sent)]
+#[case(Some(account(false, None)), ActivationReminderStatus::Sent)]
+fn test_reminder_status(
+    #[case] account: Option<WorkspaceAccount>,
+    #[case] expected: ActivationReminderStatus,
+) {
+    assert_eq!(reminder_status(account.as_ref()).0, expected);
+}
+
+#[test]
+fn test_parse_last_login_time() {
+    assert_eq!(
+        parse_last_login_time(Some("2024-10-15T18:30:00.000Z")),
+        DateTime::from_timestamp(1_729_017_000, 0)
+    );
+    assert_eq!(parse_last_login_time(Some("1970-01-01T00:00:00.000Z")), None);
+    assert_eq!(parse_last_login_time(Some("not a time")), None);
+    assert_eq!(parse_last_login_time(None), None);
+}
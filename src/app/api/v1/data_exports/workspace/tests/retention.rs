@@ -0,0 +1,44 @@
+use chrono::{DateTime, TimeDelta};
+use rstest::rstest;
+
+use crate::app::api::v1::data_exports::workspace::retention::{parse_period, RetentionConfig};
+
+#[rstest]
+#[case("30", Some(30))]
+#[case(" 6 ", Some(6))]
+#[case("off", None)]
+#[case("OFF", None)]
+fn test_parse_period(#[case] raw: &str, #[case] expected: Option<u32>) {
+    assert_eq!(parse_period(raw).unwrap(), expected);
+}
+
+#[rstest]
+#[case("0")]
+#[case("-1")]
+#[case("a month")]
+#[case("")]
+fn test_parse_period_invalid(#[case] raw: &str) {
+    assert!(parse_period(raw).is_err());
+}
+
+#[test]
+fn test_retention_cutoffs() {
+    let now = DateTime::parse_from_rfc3339("2026-03-31T12:00:00Z").unwrap().to_utc();
+
+    let (queued_before, archived_before) = RetentionConfig::default().cutoffs(now);
+    assert_eq!(queued_before, Some(now - TimeDelta::days(30)));
+    assert_eq!(archived_before, None);
+
+    let config = RetentionConfig {
+        temporary_password_days: None,
+        exported_volunteer_months: Some(1),
+        dry_run: false,
+    };
+    let (queued_before, archived_before) = config.cutoffs(now);
+    assert_eq!(queued_before, None);
+    // months are clamped to the end of shorter months
+    assert_eq!(
+        archived_before,
+        Some(DateTime::parse_from_rfc3339("2026-02-28T12:00:00Z").unwrap().to_utc())
+    );
+}
@@ -0,0 +1,141 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use rstest::rstest;
+use scipio_workspace::batch::BatchEntryError;
+
+use crate::services::workspace::graph::GraphError;
+use crate::services::workspace::retry::{
+    is_conflict, is_retryable, RetryPolicy, RetryPolicyBuilder,
+};
+use crate::services::workspace::scim::ScimError;
+
+fn graph_error(status: u16, message: &str) -> anyhow::Error {
+    anyhow!(GraphError { status, code: "Request_Failed".to_owned(), message: message.to_owned() })
+}
+
+fn batch_error(status: u16, message: &str) -> anyhow::Error {
+    anyhow!(BatchEntryError { status, message: message.to_owned() })
+}
+
+#[rstest]
+#[case(1, Duration::from_millis(500))]
+#[case(2, Duration::from_secs(1))]
+#[case(3, Duration::from_secs(2))]
+#[case(7, Duration::from_secs(30))]
+#[case(32, Duration::from_secs(30))]
+#[case(33, Duration::from_secs(30))]
+#[case(u32::MAX, Duration::from_secs(30))]
+pub fn test_retry_delay(#[case] attempt: u32, #[case] bound: Duration) {
+    let policy = RetryPolicy { jitter: false, ..RetryPolicy::default() };
+    assert_eq!(policy.delay_for(attempt), bound);
+
+    // jitter only ever shortens the wait
+    let policy = RetryPolicy::default();
+    for _ in 0..100 {
+        assert!(policy.delay_for(attempt) <= bound);
+    }
+}
+
+#[test]
+pub fn test_retry_delay_does_not_overflow() {
+    let policy = RetryPolicy {
+        base_delay: Duration::from_secs(u64::MAX / 2),
+        max_delay: Duration::MAX,
+        jitter: false,
+        ..RetryPolicy::default()
+    };
+    assert_eq!(policy.delay_for(u32::MAX), Duration::MAX);
+
+    let policy = RetryPolicy { base_delay: Duration::ZERO, ..RetryPolicy::default() };
+    assert_eq!(policy.delay_for(u32::MAX), Duration::ZERO);
+}
+
+#[rstest]
+#[case(429, true)]
+#[case(412, true)]
+#[case(500, true)]
+#[case(502, true)]
+#[case(503, true)]
+#[case(400, false)]
+#[case(401, false)]
+#[case(403, false)]
+#[case(404, false)]
+#[case(409, false)]
+pub fn test_is_retryable(#[case] status: u16, #[case] retryable: bool) {
+    assert_eq!(is_retryable(&graph_error(status, "Request failed")), retryable);
+    assert_eq!(is_retryable(&batch_error(status, "Request failed")), retryable);
+    assert_eq!(
+        is_retryable(&graph_error(status, "Request failed").context("error creating user")),
+        retryable
+    );
+}
+
+#[test]
+pub fn test_is_retryable_rate_limited_batch_entry() {
+    assert!(is_retryable(&batch_error(403, "Rate Limit Exceeded")));
+    assert!(!is_retryable(&batch_error(403, "Not Authorized to access this resource")));
+    assert!(!is_retryable(&anyhow!("connection reset")));
+}
+
+#[test]
+pub fn test_is_conflict() {
+    assert!(is_conflict(&batch_error(409, "Entity already exists.")));
+    assert!(is_conflict(&graph_error(409, "Conflict")));
+    assert!(is_conflict(&graph_error(400, "Another object with the same value already exists.")));
+    assert!(is_conflict(&anyhow!(ScimError {
+        status: 400,
+        scim_type: Some("uniqueness".to_owned()),
+        detail: "User already exists".to_owned(),
+    })));
+    assert!(is_conflict(
+        &batch_error(409, "Entity already exists.").context("error creating user")
+    ));
+
+    assert!(!is_conflict(&batch_error(400, "Invalid Input")));
+    assert!(!is_conflict(&graph_error(429, "Too many requests")));
+    assert!(!is_conflict(&anyhow!("Entity already exists.")));
+}
+
+#[tokio::test]
+pub async fn test_retry_run_gives_up_after_max_attempts() -> Result<()> {
+    let policy =
+        RetryPolicyBuilder::default().max_attempts(3).base_delay(Duration::ZERO).build()?;
+
+    let calls = &AtomicU32::new(0);
+    let result = policy
+        .run("create user", || async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(graph_error(503, "Service unavailable"))
+        })
+        .await;
+    assert!(result.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+    // errors that are not transient are returned right away
+    let calls = &AtomicU32::new(0);
+    let result = policy
+        .run("create user", || async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(graph_error(400, "Invalid value"))
+        })
+        .await;
+    assert!(result.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // an operation that recovers before running out of attempts succeeds
+    let calls = &AtomicU32::new(0);
+    let value = policy
+        .run("create user", || async move {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(graph_error(429, "Too many requests")),
+                _ => Ok("created"),
+            }
+        })
+        .await?;
+    assert_eq!(value, "created");
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+    Ok(())
+}
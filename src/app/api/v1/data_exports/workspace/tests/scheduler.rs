@@ -0,0 +1,53 @@
+use chrono::DateTime;
+use rstest::rstest;
+use serde_json::json;
+
+use crate::app::api::v1::data_exports::workspace::scheduler::{
+    next_run_after, parse_cron, validate_params, ScheduledJob,
+};
+
+#[rstest]
+#[case("0 3 * * *", "2026-04-01T03:00:00Z")]
+#[case(" */15 * * * * ", "2026-03-31T12:15:00Z")]
+#[case("0 9 * * Mon", "2026-04-06T09:00:00Z")]
+#[case("30 0 3 * * * *", "2026-04-01T03:00:30Z")]
+fn test_next_run_after(#[case] cron: &str, #[case] expected: &str) {
+    // a Tuesday
+    let now = DateTime::parse_from_rfc3339("2026-03-31T12:00:00Z").unwrap().to_utc();
+    let expected = DateTime::parse_from_rfc3339(expected).unwrap().to_utc();
+    assert_eq!(next_run_after(cron, now).unwrap(), expected);
+}
+
+#[rstest]
+#[case("")]
+#[case("every day")]
+#[case("61 * * * *")]
+#[case("* * *")]
+fn test_parse_cron_invalid(#[case] cron: &str) {
+    assert!(parse_cron(cron).is_err());
+}
+
+#[test]
+fn test_scheduled_job_names() {
+    for job in ScheduledJob::ALL {
+        assert_eq!(ScheduledJob::parse(job.name()).unwrap(), job);
+        assert_eq!(serde_json::to_value(job).unwrap(), json!(job.name()));
+    }
+    assert!(ScheduledJob::parse("Reconciliation").is_err());
+    assert!(ScheduledJob::parse("backup").is_err());
+}
+
+#[test]
+fn test_validate_params() {
+    let reconciliation = ScheduledJob::Reconciliation;
+    assert!(validate_params(reconciliation, &json!({})).is_ok());
+    assert!(
+        validate_params(reconciliation, &json!({ "orgUnit": "/Programs", "fix": true })).is_ok()
+    );
+    assert!(validate_params(reconciliation, &json!({ "orgUnit": "Programs" })).is_err());
+    assert!(validate_params(reconciliation, &json!({ "orgUnits": ["/Programs"] })).is_err());
+
+    assert!(validate_params(ScheduledJob::AdminDigest, &json!(null)).is_ok());
+    assert!(validate_params(ScheduledJob::RetentionPurge, &json!({})).is_ok());
+    assert!(validate_params(ScheduledJob::RetentionPurge, &json!({ "dryRun": true })).is_err());
+}
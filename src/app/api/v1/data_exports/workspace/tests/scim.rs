@@ -0,0 +1,98 @@
+use anyhow::{anyhow, Result};
+use serde_json::json;
+
+use crate::services::workspace::entities::{
+    CreateWorkspaceVolunteer, CreateWorkspaceVolunteerBuilder,
+};
+use crate::services::workspace::retry::{is_conflict, is_retryable};
+use crate::services::workspace::scim::{
+    create_user_body, eq_filter, recovery_email, ScimEmail, ScimError,
+};
+
+fn volunteer(org_unit: &str) -> CreateWorkspaceVolunteer {
+    CreateWorkspaceVolunteerBuilder::default()
+        .primary_email("rogerfederer@developforgood.org")
+        .first_name("Roger")
+        .last_name("Federer")
+        .password("password")
+        .recovery_email("roger.federer@gmail.com")
+        .org_unit(org_unit)
+        .build()
+        .expect("error building volunteer")
+}
+
+fn scim_error(status: u16, scim_type: Option<&str>) -> anyhow::Error {
+    anyhow!(ScimError {
+        status,
+        scim_type: scim_type.map(str::to_owned),
+        detail: "Request failed".to_owned(),
+    })
+}
+
+#[test]
+pub fn test_create_user_body() -> Result<()> {
+    assert_eq!(
+        create_user_body(volunteer("/"))?,
+        json!({
+            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
+            "userName": "rogerfederer@developforgood.org",
+            "name": { "givenName": "Roger", "familyName": "Federer" },
+            "displayName": "Roger Federer",
+            "emails": [
+                { "value": "rogerfederer@developforgood.org", "type": "work", "primary": true },
+                { "value": "roger.federer@gmail.com", "type": "other", "primary": false },
+            ],
+            "password": "password",
+            "active": true,
+        })
+    );
+
+    // SCIM has no org units
+    assert!(create_user_body(volunteer("/Programs/PantheonUsers")).is_err());
+
+    Ok(())
+}
+
+#[test]
+pub fn test_recovery_email() {
+    let emails = vec![
+        ScimEmail {
+            value: "rogerfederer@developforgood.org".to_owned(),
+            email_type: Some("work".to_owned()),
+            primary: true,
+        },
+        ScimEmail { value: "roger.federer@gmail.com".to_owned(), email_type: None, primary: false },
+    ];
+    assert_eq!(recovery_email(&emails), Some("roger.federer@gmail.com"));
+    assert_eq!(recovery_email(&emails[..1]), None);
+}
+
+#[test]
+pub fn test_eq_filter() {
+    assert_eq!(
+        eq_filter("userName", "rogerfederer@developforgood.org"),
+        r#"userName eq "rogerfederer@developforgood.org""#
+    );
+    assert_eq!(
+        eq_filter("displayName", r#"The "Big" \ Four"#),
+        r#"displayName eq "The \"Big\" \\ Four""#
+    );
+}
+
+#[test]
+pub fn test_scim_error_classification() {
+    let taken = scim_error(409, Some("uniqueness"));
+    assert!(is_conflict(&taken));
+    assert!(!is_retryable(&taken));
+
+    // Some services report uniqueness violations as bad requests
+    assert!(is_conflict(&scim_error(400, Some("uniqueness"))));
+
+    let throttled = scim_error(429, None);
+    assert!(is_retryable(&throttled));
+    assert!(!is_conflict(&throttled));
+
+    let invalid = scim_error(400, Some("invalidValue"));
+    assert!(!is_conflict(&invalid));
+    assert!(!is_retryable(&invalid));
+}
@@ -0,0 +1,60 @@
+use anyhow::anyhow;
+use uuid::uuid;
+
+use crate::app::api::v1::data_exports::workspace::slack::{
+    FailedSlackInvite, SlackInviteSettings, SlackInviteSummary, MAX_SLACK_CHANNELS,
+};
+use crate::services::slack::SlackInviteOutcome;
+use crate::services::storage::entities::SuspensionCandidate;
+
+fn settings(channel_ids: &[&str]) -> SlackInviteSettings {
+    SlackInviteSettings { channel_ids: channel_ids.iter().map(|id| (*id).to_owned()).collect() }
+}
+
+#[test]
+pub fn test_validate_slack_settings() {
+    assert!(settings(&["C0123456789", "G0123456789"]).validate().is_ok());
+
+    // Slack requires at least one channel
+    assert!(settings(&[]).validate().is_err());
+
+    // Channel names are not IDs
+    assert!(settings(&["#spring-2025"]).validate().is_err());
+    assert!(settings(&["C"]).validate().is_err());
+    assert!(settings(&["c0123456789"]).validate().is_err());
+
+    assert!(settings(&["C0123456789", "C0123456789"]).validate().is_err());
+
+    let too_many = (0..=MAX_SLACK_CHANNELS).map(|i| format!("C{i:010}")).collect::<Vec<String>>();
+    assert!(SlackInviteSettings { channel_ids: too_many }.validate().is_err());
+}
+
+#[test]
+pub fn test_slack_invite_summary() {
+    let volunteer = |workspace_email: &str| SuspensionCandidate {
+        volunteer_id: uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90"),
+        first_name: "Roger".to_owned(),
+        last_name: "Federer".to_owned(),
+        workspace_email: workspace_email.to_owned(),
+    };
+
+    let mut summary = SlackInviteSummary::default();
+    summary.record(&volunteer("rogerfederer@developforgood.org"), Ok(SlackInviteOutcome::Invited));
+    summary.record(
+        &volunteer("rogerfederer@developforgood.org"),
+        Ok(SlackInviteOutcome::AlreadyMember),
+    );
+    summary
+        .record(&volunteer("rogerfederer@developforgood.org"), Err(anyhow!("channel_not_found")));
+
+    assert_eq!(summary.invited, 1);
+    assert_eq!(summary.already_members, 1);
+    assert_eq!(
+        summary.failed,
+        vec![FailedSlackInvite {
+            volunteer_id: uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90"),
+            workspace_email: "rogerfederer@developforgood.org".to_owned(),
+            reason: "channel_not_found".to_owned(),
+        }]
+    );
+}
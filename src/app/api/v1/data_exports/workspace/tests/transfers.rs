@@ -0,0 +1,73 @@
+use anyhow::Result;
+
+use crate::services::workspace::drive::{DriveClient, DriveTransferStatus};
+use crate::services::workspace::emulator::{EmulatorConfigBuilder, EmulatorWorkspaceClient};
+
+const PRINCIPAL: &str = "admin@developforgood.org";
+
+#[test]
+pub fn test_drive_transfer_status_from_status_code() {
+    assert_eq!(
+        DriveTransferStatus::from_status_code(Some("completed")),
+        DriveTransferStatus::Completed
+    );
+    assert_eq!(DriveTransferStatus::from_status_code(Some("failed")), DriveTransferStatus::Failed);
+    assert_eq!(
+        DriveTransferStatus::from_status_code(Some("inProgress")),
+        DriveTransferStatus::InProgress
+    );
+    assert_eq!(DriveTransferStatus::from_status_code(Some("new")), DriveTransferStatus::InProgress);
+
+    // transfers that have not reported a status yet are still in progress
+    assert_eq!(DriveTransferStatus::from_status_code(None), DriveTransferStatus::InProgress);
+}
+
+#[tokio::test]
+pub async fn test_emulator_drive_transfers() -> Result<()> {
+    let config = EmulatorConfigBuilder::default()
+        .existing_users(vec![
+            "rogerfederer@developforgood.org".to_owned(),
+            "archive@developforgood.org".to_owned(),
+        ])
+        .build()?;
+    let workspace = EmulatorWorkspaceClient::new(config);
+
+    let transfer_id = workspace
+        .start_drive_transfer(
+            PRINCIPAL,
+            "RogerFederer@developforgood.org",
+            "archive@developforgood.org",
+        )
+        .await?;
+    assert_eq!(
+        workspace.drive_transfer_status(PRINCIPAL, &transfer_id).await?,
+        DriveTransferStatus::Completed
+    );
+    assert_eq!(
+        workspace.drive_transferred_to("rogerfederer@developforgood.org").as_deref(),
+        Some("archive@developforgood.org")
+    );
+
+    // files cannot be transferred to or from an account that does not exist
+    assert!(workspace
+        .start_drive_transfer(
+            PRINCIPAL,
+            "rogerfederer@developforgood.org",
+            "missing@developforgood.org"
+        )
+        .await
+        .is_err());
+    assert!(workspace
+        .start_drive_transfer(
+            PRINCIPAL,
+            "rafanadal@developforgood.org",
+            "archive@developforgood.org"
+        )
+        .await
+        .is_err());
+    assert!(workspace.drive_transferred_to("rafanadal@developforgood.org").is_none());
+
+    assert!(workspace.drive_transfer_status(PRINCIPAL, "transfer-9").await.is_err());
+
+    Ok(())
+}
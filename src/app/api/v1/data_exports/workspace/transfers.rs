@@ -0,0 +1,342 @@
+//! This module transfers the Drive files of a project cycle's departing volunteers to a program's
+//! archive account, so their work is kept once their accounts are offboarded.
+//!
+//! Google runs each transfer in the background, so every transfer is started and then checked until
+//! it finishes. Each volunteer's files are transferred independently, so a failure for one
+//! volunteer does not stop the others, and how far every transfer got is recorded. Accounts are
+//! only suspended once all of their files have been transferred.
+
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use uuid::Uuid;
+
+use super::report_progress;
+use super::suspensions::{suspend_volunteers, SuspensionParams};
+use crate::app::api::v1::data_exports::ExportServices;
+use crate::services::storage::entities::{SuspensionCandidate, WorkspaceDriveTransfer};
+use crate::services::storage::jobs::NEW_JOB_VERSION;
+use crate::services::storage::transfers::CreateDriveTransfer;
+use crate::services::storage::types::{JobPhase, WorkspaceSuspensionAction};
+use crate::services::storage::ExecOptsBuilder;
+use crate::services::workspace::drive::DriveTransferStatus;
+use crate::services::workspace::retry::RetryPolicy;
+
+/// How often a transfer that is still in progress is checked.
+pub const DRIVE_TRANSFER_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long to wait for a transfer to finish before recording it as failed. Large drives can take
+/// hours to transfer.
+pub const DRIVE_TRANSFER_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Parameters for a job that transfers volunteers' Drive files to an archive account.
+///
+/// * `job_id`: The ID of the job
+/// * `principal`: The email of the user who started the job
+/// * `archive_email`: The email of the account that receives the files
+/// * `suspend_after_transfer`: Whether to suspend each account once its files are transferred
+/// * `concurrency`: The maximum number of transfers to run at once
+/// * `poll_interval`: How often a transfer that is still in progress is checked
+/// * `timeout`: How long to wait for a transfer to finish
+/// * `volunteers`: The volunteers whose files are transferred
+pub struct DriveTransferParams {
+    pub job_id: Uuid,
+    pub principal: String,
+    pub archive_email: String,
+    pub suspend_after_transfer: bool,
+    pub concurrency: usize,
+    pub poll_interval: Duration,
+    pub timeout: Duration,
+    pub volunteers: Vec<SuspensionCandidate>,
+}
+
+/// What a job that transfers volunteers' Drive files did.
+///
+/// * `transferred`: The number of volunteers whose files were all transferred
+/// * `failed`: The number of volunteers whose files could not all be transferred. Their accounts
+///   are not suspended.
+/// * `suspended`: The number of accounts that were suspended once their files were transferred
+/// * `failed_suspensions`: The number of accounts whose files were transferred, but that could not
+///   be suspended
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveTransferSummary {
+    pub transferred: usize,
+    pub failed: usize,
+    pub suspended: usize,
+    pub failed_suspensions: usize,
+}
+
+/// Transfer volunteers' Drive files to an archive account, then suspend their accounts if asked
+/// to.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+///
+/// How far every transfer got is recorded, the summary is saved as the job's result, and the job is
+/// marked complete, or errored if any transfer or suspension failed.
+pub async fn drive_transfer_task(
+    services: &ExportServices,
+    params: DriveTransferParams,
+) -> Result<DriveTransferSummary> {
+    let job_id = params.job_id;
+    let result = transfer_drive_files(services, &params).await;
+
+    let finished = match &result {
+        Ok(summary) => finish_transfers(services, job_id, summary).await,
+        Err(e) => {
+            log::error!("Job {job_id} failed to transfer drive files: {e}");
+            services
+                .storage_layer
+                .mark_job_errored(
+                    job_id,
+                    NEW_JOB_VERSION,
+                    e.to_string(),
+                    &mut ExecOptsBuilder::default().build()?,
+                )
+                .await
+        }
+    };
+
+    if let Err(e) = finished {
+        log::error!("Failed to record the outcome of job {job_id}: {e}");
+    }
+
+    result
+}
+
+/// Transfer every volunteer's files, then suspend the accounts whose files were transferred.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+async fn transfer_drive_files(
+    services: &ExportServices,
+    params: &DriveTransferParams,
+) -> Result<DriveTransferSummary> {
+    let records = params
+        .volunteers
+        .iter()
+        .map(|v| CreateDriveTransfer {
+            job_id: params.job_id,
+            volunteer_id: v.volunteer_id,
+            workspace_email: v.workspace_email.clone(),
+            archive_email: params.archive_email.clone(),
+            principal: params.principal.clone(),
+        })
+        .collect::<Vec<CreateDriveTransfer>>();
+
+    let transfers = services
+        .storage_layer
+        .create_drive_transfers(records, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    let total = transfers.len();
+    let retry_policy = RetryPolicy::default();
+    let retry_policy = &retry_policy;
+
+    report_progress(services, params.job_id, JobPhase::Provisioning, 0, total).await;
+
+    let mut stream = stream::iter(transfers.iter())
+        .map(|transfer| async move {
+            (transfer, transfer_files(services, params, retry_policy, transfer).await)
+        })
+        .buffer_unordered(params.concurrency.max(1));
+
+    let mut summary = DriveTransferSummary::default();
+    let mut transferred = Vec::with_capacity(total);
+    while let Some((transfer, result)) = stream.next().await {
+        match result {
+            Ok(()) => {
+                log::info!(
+                    "Transferred the files of {} to {}",
+                    transfer.workspace_email,
+                    params.archive_email
+                );
+                services
+                    .storage_layer
+                    .complete_drive_transfer(transfer.id, &mut ExecOptsBuilder::default().build()?)
+                    .await?;
+                summary.transferred += 1;
+                transferred.push(transfer);
+            }
+            Err(e) => {
+                log::error!("Failed to transfer the files of {}: {e}", transfer.workspace_email);
+                services
+                    .storage_layer
+                    .fail_drive_transfer(
+                        transfer.id,
+                        e.to_string(),
+                        &mut ExecOptsBuilder::default().build()?,
+                    )
+                    .await?;
+                summary.failed += 1;
+            }
+        }
+
+        let done = summary.transferred + summary.failed;
+        report_progress(services, params.job_id, JobPhase::Provisioning, done, total).await;
+    }
+
+    if params.suspend_after_transfer && !transferred.is_empty() {
+        suspend_transferred(services, params, &transferred, &mut summary).await?;
+    }
+
+    Ok(summary)
+}
+
+/// Start transferring a volunteer's files and wait for the transfer to finish.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+/// * `retry_policy`: How calls to the workspace provider are retried
+/// * `transfer`: The record of the transfer
+async fn transfer_files(
+    services: &ExportServices,
+    params: &DriveTransferParams,
+    retry_policy: &RetryPolicy,
+    transfer: &WorkspaceDriveTransfer,
+) -> Result<()> {
+    let transfer_id = retry_policy
+        .run(&format!("transferring the files of {}", transfer.workspace_email), || {
+            services.workspace.start_drive_transfer(
+                &params.principal,
+                &transfer.workspace_email,
+                &params.archive_email,
+            )
+        })
+        .await?;
+
+    services
+        .storage_layer
+        .start_drive_transfer(
+            transfer.id,
+            transfer_id.clone(),
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    let started = Instant::now();
+    loop {
+        let status = retry_policy
+            .run(&format!("checking transfer {transfer_id}"), || {
+                services.workspace.drive_transfer_status(&params.principal, &transfer_id)
+            })
+            .await?;
+
+        match status {
+            DriveTransferStatus::Completed => return Ok(()),
+            DriveTransferStatus::Failed => bail!("Not every file could be transferred"),
+            DriveTransferStatus::InProgress if started.elapsed() >= params.timeout => {
+                bail!(
+                    "The transfer was still in progress after {} minutes",
+                    params.timeout.as_secs() / 60
+                )
+            }
+            DriveTransferStatus::InProgress => tokio::time::sleep(params.poll_interval).await,
+        }
+    }
+}
+
+/// Suspend the accounts whose files were transferred, recording the suspensions like those of an
+/// offboarding job.
+///
+/// * `services`: The services needed to run the job
+/// * `params`: The job parameters
+/// * `transferred`: The transfers that completed
+/// * `summary`: The summary to count the suspensions in
+async fn suspend_transferred(
+    services: &ExportServices,
+    params: &DriveTransferParams,
+    transferred: &[&WorkspaceDriveTransfer],
+    summary: &mut DriveTransferSummary,
+) -> Result<()> {
+    let volunteers = params
+        .volunteers
+        .iter()
+        .filter(|v| transferred.iter().any(|t| t.volunteer_id == v.volunteer_id))
+        .cloned()
+        .collect::<Vec<SuspensionCandidate>>();
+
+    let suspension = SuspensionParams {
+        job_id: params.job_id,
+        principal: params.principal.clone(),
+        action: WorkspaceSuspensionAction::Suspend,
+        concurrency: params.concurrency,
+        volunteers,
+    };
+
+    let (suspension_summary, suspended) = suspend_volunteers(services, &suspension).await?;
+
+    let ids = transferred
+        .iter()
+        .filter(|t| suspended.iter().any(|v| v.volunteer_id == t.volunteer_id))
+        .map(|t| t.id)
+        .collect::<Vec<Uuid>>();
+
+    services
+        .storage_layer
+        .mark_drive_transfers_suspended(ids, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    summary.suspended = suspension_summary.succeeded;
+    summary.failed_suspensions = suspension_summary.failed;
+    Ok(())
+}
+
+/// Save the summary of a transfer job and mark the job complete, or errored if any transfer or
+/// suspension failed.
+///
+/// * `services`: The services needed to run the job
+/// * `job_id`: The ID of the job
+/// * `summary`: What the job did
+async fn finish_transfers(
+    services: &ExportServices,
+    job_id: Uuid,
+    summary: &DriveTransferSummary,
+) -> Result<()> {
+    services
+        .storage_layer
+        .set_job_result(
+            job_id,
+            serde_json::to_value(summary)?,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    let error = match (summary.failed, summary.failed_suspensions) {
+        (0, 0) => None,
+        (failed, 0) => Some(format!("Failed to transfer the files of {failed} users")),
+        (0, failed) => Some(format!("Failed to suspend {failed} users")),
+        (failed, failed_suspensions) => Some(format!(
+            "Failed to transfer the files of {failed} users and to suspend {failed_suspensions} \
+             users"
+        )),
+    };
+
+    match error {
+        Some(error) => {
+            services
+                .storage_layer
+                .mark_job_errored(
+                    job_id,
+                    NEW_JOB_VERSION,
+                    error,
+                    &mut ExecOptsBuilder::default().build()?,
+                )
+                .await
+        }
+        None => {
+            services
+                .storage_layer
+                .mark_job_complete(
+                    job_id,
+                    NEW_JOB_VERSION,
+                    &mut ExecOptsBuilder::default().build()?,
+                )
+                .await
+        }
+    }
+}
@@ -5,6 +5,7 @@ use uuid::Uuid;
 
 use super::ImportServices;
 use crate::services::storage::cycles::CreateCycleBuilder;
+use crate::services::storage::jobs::NEW_JOB_VERSION;
 use crate::services::storage::mentors::CreateMentor;
 use crate::services::storage::nonprofits::CreateNonprofit;
 use crate::services::storage::volunteers::CreateVolunteer;
@@ -203,7 +204,11 @@ pub async fn import_task(services: &ImportServices, params: &ImportParams) -> Re
         Ok(_) => {
             services
                 .storage_layer
-                .mark_job_complete(params.job_id, &mut ExecOptsBuilder::default().build()?)
+                .mark_job_complete(
+                    params.job_id,
+                    NEW_JOB_VERSION,
+                    &mut ExecOptsBuilder::default().build()?,
+                )
                 .await?
         }
         Err(e) => {
@@ -211,6 +216,7 @@ pub async fn import_task(services: &ImportServices, params: &ImportParams) -> Re
                 .storage_layer
                 .mark_job_errored(
                     params.job_id,
+                    NEW_JOB_VERSION,
                     e.to_string(),
                     &mut ExecOptsBuilder::default().build()?,
                 )
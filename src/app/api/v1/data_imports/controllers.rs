@@ -16,6 +16,7 @@ use crate::app::api_response;
 use crate::app::errors::AppError;
 use crate::app::state::Services;
 use crate::services::storage::jobs::CreateJobBuilder;
+use crate::services::storage::organizations::with_org;
 use crate::services::storage::types::{JobData, JobDetails, JobType};
 use crate::services::storage::ExecOptsBuilder;
 
@@ -73,6 +74,7 @@ pub async fn import_airtable_base(
         .data(JobDetails {
             job_type: JobType::AirtableImportBase,
             error: None,
+            result: None,
             data: JobData::AirtableImportBase { base_id: base_id.clone() },
         })
         .build()?;
@@ -89,9 +91,9 @@ pub async fn import_airtable_base(
         base_id: base_id.clone(),
     };
 
-    task::spawn(async move {
+    task::spawn(with_org(services.org_id, async move {
         let _ = import_task(&services, &params).await;
-    });
+    }));
 
     Ok(api_response::success(
         StatusCode::OK,
@@ -10,9 +10,11 @@ use axum::middleware::from_fn_with_state;
 use axum::{routing, Router};
 use requests::ImportAirtableBase;
 use utoipa::OpenApi;
+use uuid::Uuid;
 
 use crate::app::api::middleware::make_rbac;
 use crate::app::state::Services;
+use crate::services::storage::organizations::{current_org, DEFAULT_ORG_ID};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -21,14 +23,23 @@ use crate::app::state::Services;
 )]
 pub struct DataImportsApi;
 
+/// The services import jobs need.
+///
+/// * `org_id`: The organization the request that built the services acts for, which the import
+///   job is scoped to
 pub struct ImportServices {
+    pub org_id: Uuid,
     pub storage_layer: Arc<dyn crate::services::storage::StorageService>,
     pub airtable: Arc<dyn crate::services::airtable::AirtableService>,
 }
 
 impl FromRef<Arc<Services>> for ImportServices {
     fn from_ref(ctx: &Arc<Services>) -> Self {
-        Self { storage_layer: ctx.storage_layer.clone(), airtable: ctx.airtable.clone() }
+        Self {
+            org_id: current_org().unwrap_or(DEFAULT_ORG_ID),
+            storage_layer: ctx.storage_layer.clone(),
+            airtable: ctx.airtable.clone(),
+        }
     }
 }
 
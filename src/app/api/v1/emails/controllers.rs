@@ -0,0 +1,202 @@
+//! Controllers for the emails API.
+
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::Response;
+use axum::{Extension, Json};
+
+use super::requests::{PreviewEmailRequest, SuppressEmailRequest};
+use super::responses::{
+    EmailSuppressionsResponse, PreviewEmailResponse, SyncEmailSuppressionsResponse,
+};
+use crate::app::api_response;
+use crate::app::errors::AppError;
+use crate::app::state::Services;
+use crate::services::auth::AuthData;
+use crate::services::mail::{find_template, render_template, DEFAULT_LOCALE};
+use crate::services::storage::emails::CreateEmailSuppressionBuilder;
+use crate::services::storage::types::EmailSuppressionReason;
+use crate::services::storage::ExecOptsBuilder;
+
+/// Render a compiled-in email template with the given variables without sending it.
+///
+/// * `request`: The template to render and the variables to render it with
+#[utoipa::path(
+    post,
+    path = "/preview",
+    operation_id = "Preview email",
+    responses(
+        (status = 200, description = "Successfully rendered the email"),
+        (status = 400, description = "The template does not render with the given variables"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:templates`)"),
+        (status = 404, description = "There is no template with the name"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn preview_email(Json(request): Json<PreviewEmailRequest>) -> Result<Response, AppError> {
+    let locale = request.locale.as_deref().unwrap_or(DEFAULT_LOCALE);
+
+    let Some(template) = find_template(&request.template, locale) else {
+        return Ok(api_response::error(StatusCode::NOT_FOUND, "Template not found"));
+    };
+
+    match render_template(&template, locale, &request.context) {
+        Ok(email) => Ok(api_response::success(StatusCode::OK, PreviewEmailResponse::from(email))?),
+        Err(e) => Ok(api_response::error(
+            StatusCode::BAD_REQUEST,
+            &format!("Template does not render: {e:#}"),
+        )),
+    }
+}
+
+/// Fetch every address on the suppression list.
+///
+/// * `ctx`: The application context
+#[utoipa::path(
+    get,
+    path = "/suppressions",
+    operation_id = "Get email suppressions",
+    responses(
+        (status = 200, description = "Successfully fetched the suppression list"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:emails`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_suppressions(State(ctx): State<Arc<Services>>) -> Result<Response, AppError> {
+    let suppressions = ctx
+        .storage_layer
+        .fetch_email_suppressions(&mut ExecOptsBuilder::default().build()?)
+        .await?;
+    Ok(api_response::success(StatusCode::OK, EmailSuppressionsResponse { suppressions })?)
+}
+
+/// Add an address to the suppression list, so no email is sent to it.
+///
+/// * `ctx`: The application context
+/// * `auth`: Auth data about the user
+/// * `request`: The address to suppress
+#[utoipa::path(
+    post,
+    path = "/suppressions",
+    operation_id = "Suppress email",
+    responses(
+        (status = 204, description = "Successfully suppressed the address"),
+        (status = 400, description = "The address is not an email address"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:emails`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn suppress_email(
+    State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
+    Json(request): Json<SuppressEmailRequest>,
+) -> Result<Response, AppError> {
+    let email = request.email.trim();
+    if !email.contains('@') {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, "Invalid email address"));
+    }
+
+    let suppression = CreateEmailSuppressionBuilder::default()
+        .email(email)
+        .reason(EmailSuppressionReason::Manual)
+        .detail(request.detail)
+        .build()?;
+    ctx.storage_layer
+        .suppress_emails(
+            vec![suppression],
+            &mut ExecOptsBuilder::default().principal(auth.email()?).build()?,
+        )
+        .await?;
+
+    Ok(api_response::no_content())
+}
+
+/// Remove an address from the suppression list, so email is sent to it again.
+///
+/// * `ctx`: The application context
+/// * `auth`: Auth data about the user
+/// * `email`: The address to remove
+///
+/// This does not remove the address from the mail providers' own suppression lists, so a provider
+/// may still drop email to it.
+#[utoipa::path(
+    delete,
+    path = "/suppressions/{email}",
+    operation_id = "Unsuppress email",
+    responses(
+        (status = 204, description = "Successfully removed the address from the suppression list"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:emails`)"),
+        (status = 404, description = "The address is not on the suppression list"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn unsuppress_email(
+    State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
+    Path(email): Path<String>,
+) -> Result<Response, AppError> {
+    let deleted = ctx
+        .storage_layer
+        .delete_email_suppression(
+            email,
+            &mut ExecOptsBuilder::default().principal(auth.email()?).build()?,
+        )
+        .await?;
+
+    if !deleted {
+        return Ok(api_response::error(StatusCode::NOT_FOUND, "Address is not suppressed"));
+    }
+    Ok(api_response::no_content())
+}
+
+/// Pull the suppression lists of the mail providers (hard bounces, unsubscribes, and spam reports)
+/// into the suppression list.
+///
+/// * `ctx`: The application context
+/// * `auth`: Auth data about the user
+///
+/// Addresses the event webhook already reported are updated in place.
+#[utoipa::path(
+    post,
+    path = "/suppressions/sync",
+    operation_id = "Sync email suppressions",
+    responses(
+        (status = 200, description = "Successfully pulled the suppression lists"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:emails`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn sync_suppressions(
+    State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
+) -> Result<Response, AppError> {
+    let suppressions = ctx.mail.fetch_suppressions().await?;
+    let synced = suppressions.len();
+    log::info!("Pulled {synced} suppressed addresses from the mail providers");
+
+    ctx.storage_layer
+        .suppress_emails(
+            suppressions,
+            &mut ExecOptsBuilder::default().principal(auth.email()?).build()?,
+        )
+        .await?;
+
+    Ok(api_response::success(StatusCode::OK, SyncEmailSuppressionsResponse { synced })?)
+}
@@ -0,0 +1,62 @@
+//! Emails API.
+//!
+//! Staff can render the compiled-in email templates with their own variables through this API, to
+//! check the copy and variables of an email before running an export that sends it. They can also
+//! manage the suppression list: the addresses no email is sent to because they hard bounced,
+//! unsubscribed, or reported email as spam.
+
+use std::sync::Arc;
+
+use axum::middleware::from_fn_with_state;
+use axum::{routing, Router};
+use utoipa::OpenApi;
+
+use crate::app::api::middleware::make_rbac;
+use crate::app::state::Services;
+
+mod controllers;
+mod requests;
+mod responses;
+
+/// Documents the API for emails
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        controllers::preview_email,
+        controllers::fetch_suppressions,
+        controllers::suppress_email,
+        controllers::unsuppress_email,
+        controllers::sync_suppressions,
+    ),
+    security(("http" = ["JWT"]))
+)]
+pub struct EmailsApi;
+
+/// Builds the emails API.
+///
+/// * `ctx`: The application context
+pub async fn build(ctx: Arc<Services>) -> Router<()> {
+    let read_templates_guard = make_rbac(vec!["read:templates".to_owned()]).await;
+    let read_emails_guard = make_rbac(vec!["read:emails".to_owned()]).await;
+    let write_emails_guard = make_rbac(vec!["write:emails".to_owned()]).await;
+
+    let read = from_fn_with_state(ctx.clone(), read_emails_guard);
+    let write = from_fn_with_state(ctx.clone(), write_emails_guard);
+
+    // Routes need different permissions, so each one is guarded on its own rather than with a
+    // router-wide layer
+    let preview_email = routing::post(controllers::preview_email)
+        .route_layer(from_fn_with_state(ctx.clone(), read_templates_guard));
+    let suppressions = routing::get(controllers::fetch_suppressions)
+        .route_layer(read)
+        .merge(routing::post(controllers::suppress_email).route_layer(write.clone()));
+    let suppression = routing::delete(controllers::unsuppress_email).route_layer(write.clone());
+    let sync_suppressions = routing::post(controllers::sync_suppressions).route_layer(write);
+
+    Router::new()
+        .route("/preview", preview_email)
+        .route("/suppressions", suppressions)
+        .route("/suppressions/sync", sync_suppressions)
+        .route("/suppressions/:email", suppression)
+        .with_state(ctx.clone())
+}
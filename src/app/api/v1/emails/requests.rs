@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Request to render an email template without sending it.
+///
+/// * `template`: The name of the compiled-in template, either in full (e.g.
+///   `email/onboard.es.html`) or without its locale and extension (e.g. `email/onboard`) to render
+///   the translation for `locale`
+/// * `locale`: The locale to render the email in. Defaults to `en`.
+/// * `context`: The variables to render the template with (e.g. `name`, `email`, and
+///   `temporaryPassword` for the onboarding email)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewEmailRequest {
+    pub template: String,
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub context: Map<String, Value>,
+}
+
+/// Request to add an address to the suppression list by hand.
+///
+/// * `email`: The address to stop sending email to
+/// * `detail`: Why the address is being suppressed, if given
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SuppressEmailRequest {
+    pub email: String,
+    pub detail: Option<String>,
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+use crate::services::mail::RenderedEmail;
+use crate::services::storage::entities::EmailSuppression;
+
+/// An email template rendered without being sent.
+///
+/// * `template`: The full name of the template that was rendered, which shows the translation that
+///   was picked
+/// * `subject`: The subject line of the email, if the template is sent as an email on its own
+/// * `html`: The rendered HTML body of the email
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewEmailResponse {
+    pub template: String,
+    pub subject: Option<String>,
+    pub html: String,
+}
+
+impl From<RenderedEmail> for PreviewEmailResponse {
+    fn from(value: RenderedEmail) -> Self {
+        Self { template: value.template, subject: value.subject, html: value.html }
+    }
+}
+
+/// Every address on the suppression list, most recently suppressed first.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EmailSuppressionsResponse {
+    pub suppressions: Vec<EmailSuppression>,
+}
+
+/// The outcome of pulling the suppression lists of the mail providers.
+///
+/// * `synced`: The number of addresses the providers reported, which are now on the suppression
+///   list
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncEmailSuppressionsResponse {
+    pub synced: usize,
+}
@@ -0,0 +1,103 @@
+//! This module archives jobs once they have been finished for a while.
+//!
+//! Archiving a job moves its full details, which hold its inputs and results, out of the `jobs`
+//! table and leaves a summary in their place. Archived jobs are no longer listed with the others,
+//! but their summaries and details can still be fetched.
+
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{TimeDelta, Utc};
+use tokio::task::JoinHandle;
+
+use crate::app::api::v1::data_exports::workspace::retention::parse_period;
+use crate::app::state::Services;
+use crate::services::storage::organizations::{with_org, DEFAULT_ORG_ID};
+use crate::services::storage::ExecOptsBuilder;
+
+/// The name of the environment variable holding how many days after they finish jobs are
+/// archived. `off` never archives them.
+pub const JOB_ARCHIVE_AFTER_DAYS_ENV_VAR: &str = "JOB_ARCHIVE_AFTER_DAYS";
+
+/// How many days after they finish jobs are archived, unless configured otherwise.
+pub const DEFAULT_JOB_ARCHIVE_AFTER_DAYS: u32 = 90;
+
+/// The most jobs archived in one transaction.
+pub const ARCHIVE_BATCH_SIZE: i64 = 500;
+
+/// How often the background task archives jobs.
+pub const ARCHIVE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Read how many days after they finish jobs are archived from `JOB_ARCHIVE_AFTER_DAYS`. `None`
+/// means jobs are never archived.
+pub fn archive_after_days_from_env() -> Result<Option<u32>> {
+    match env::var(JOB_ARCHIVE_AFTER_DAYS_ENV_VAR) {
+        Ok(raw) => {
+            parse_period(&raw).with_context(|| format!("invalid {JOB_ARCHIVE_AFTER_DAYS_ENV_VAR}"))
+        }
+        Err(_) => Ok(Some(DEFAULT_JOB_ARCHIVE_AFTER_DAYS)),
+    }
+}
+
+/// Archive the jobs of the current organization that finished more than a number of days ago, a
+/// batch at a time. Returns how many were archived.
+///
+/// * `ctx`: The application services
+/// * `after_days`: How many days after they finish jobs are archived
+pub async fn archive_finished_jobs(ctx: &Services, after_days: u32) -> Result<u64> {
+    let finished_before = Utc::now() - TimeDelta::days(after_days.into());
+    let mut archived = 0;
+    loop {
+        let batch = ctx
+            .storage_layer
+            .archive_jobs(
+                finished_before,
+                ARCHIVE_BATCH_SIZE,
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?;
+        archived += batch;
+        if batch < ARCHIVE_BATCH_SIZE as u64 {
+            return Ok(archived);
+        }
+    }
+}
+
+/// Start the background task that archives finished jobs every day, for every organization.
+///
+/// * `ctx`: The application services
+/// * `after_days`: How many days after they finish jobs are archived
+pub fn spawn_archive_task(ctx: Arc<Services>, after_days: u32) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ARCHIVE_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let org_ids = match ExecOptsBuilder::default().build() {
+                Ok(mut exec_opts) => ctx.storage_layer.fetch_organizations(&mut exec_opts).await,
+                Err(e) => Err(e.into()),
+            };
+            let org_ids = match org_ids {
+                Ok(organizations) => organizations.into_iter().map(|org| org.id).collect(),
+                Err(e) => {
+                    log::error!(
+                        "Failed to list organizations, only archiving the default one: {e}"
+                    );
+                    vec![DEFAULT_ORG_ID]
+                }
+            };
+
+            for org_id in org_ids {
+                match with_org(org_id, archive_finished_jobs(&ctx, after_days)).await {
+                    Ok(0) => {}
+                    Ok(archived) => {
+                        log::info!("Archived {archived} jobs for organization {org_id}")
+                    }
+                    Err(e) => log::error!("Failed to archive jobs for organization {org_id}: {e}"),
+                }
+            }
+        }
+    })
+}
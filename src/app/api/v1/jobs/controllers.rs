@@ -1,13 +1,29 @@
 use std::sync::Arc;
 
-use axum::extract::State;
-use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::sse::{KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use uuid::Uuid;
 
-use crate::app::api::v1::jobs::responses::{Job, JobsResponse};
+use crate::app::api::v1::jobs::events::job_event_stream;
+use crate::app::api::v1::jobs::requests::{
+    ArchivedJobsQuery, RescheduleJobRequest, SetJobCallbackRequest,
+};
+use crate::app::api::v1::jobs::responses::{
+    ArchivedJobsResponse, Job, JobArchiveResponse, JobChainResponse, JobProgressResponse,
+    JobSnapshotsResponse, JobsResponse,
+};
+use crate::app::api::v1::jobs::snapshots::take_job_snapshot;
+use crate::app::api::v1::jobs::webhooks::validate_callback_url;
+use crate::app::api_response;
 use crate::app::errors::AppError;
 use crate::app::state::Services;
+use crate::services::auth::AuthData;
+use crate::services::storage::organizations::{current_org, DEFAULT_ORG_ID};
 use crate::services::storage::types::JobDetails;
-use crate::services::storage::ExecOptsBuilder;
+use crate::services::storage::{Acquire, ExecOptsBuilder};
 
 #[utoipa::path(
     get,
@@ -45,3 +61,441 @@ pub async fn fetch_jobs(State(ctx): State<Arc<Services>>) -> Result<Json<JobsRes
     let res = JobsResponse { jobs };
     Ok(Json(res))
 }
+
+/// Fetch the progress of a job.
+///
+/// * `ctx`: The application context
+/// * `job_id`: The ID of the job
+///
+/// `progress` is `null` if the job has not reported any progress, either because it has not
+/// started yet or because it is a kind of job that does not report progress.
+#[utoipa::path(
+    get,
+    path = "/{job_id}/progress",
+    operation_id = "Get job progress",
+    responses(
+        (status = 200, description = "Successfully fetched job progress"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:jobs`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_job_progress(
+    State(ctx): State<Arc<Services>>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<JobProgressResponse>, AppError> {
+    let job = ctx.storage_layer.fetch_job(job_id, &mut ExecOptsBuilder::default().build()?).await?;
+    let progress = ctx
+        .storage_layer
+        .fetch_job_progress(job_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    Ok(Json(JobProgressResponse { job_id, status: job.status, progress }))
+}
+
+/// Fetch the chain of jobs a job belongs to: the jobs it waits for, and the jobs that wait for it,
+/// oldest first. Each job lists the job it depends on, so the chain can be drawn as a graph.
+///
+/// * `ctx`: The application context
+/// * `job_id`: The ID of the job
+///
+/// A job that depends on a job that fails, is cancelled, or is dead-lettered is cancelled as well.
+#[utoipa::path(
+    get,
+    path = "/{job_id}/chain",
+    operation_id = "Get job chain",
+    responses(
+        (status = 200, description = "Successfully fetched the job's chain"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:jobs`)"),
+        (status = 404, description = "The job does not exist"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_job_chain(
+    State(ctx): State<Arc<Services>>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let jobs =
+        ctx.storage_layer.fetch_job_chain(job_id, &mut ExecOptsBuilder::default().build()?).await?;
+
+    if jobs.is_empty() {
+        return Ok(api_response::error(StatusCode::NOT_FOUND, "Job not found"));
+    }
+    Ok(api_response::success(StatusCode::OK, JobChainResponse { job_id, jobs })?)
+}
+
+/// Stream the progress of a job as Server-Sent Events: the phases it moves through and the
+/// outcome for each volunteer, as they happen. Every event the job has emitted so far is sent
+/// first. A `status` event with the job's final status is sent once it has finished, and the stream
+/// ends.
+///
+/// * `ctx`: The application context
+/// * `job_id`: The ID of the job
+/// * `headers`: The request headers. A client that reconnects with `Last-Event-ID` is only sent
+///   the events after that one
+#[utoipa::path(
+    get,
+    path = "/{job_id}/events",
+    operation_id = "Stream job events",
+    responses(
+        (status = 200, description = "Streaming the job's events as `text/event-stream`"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:jobs`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer"),
+        ("Last-Event-ID" = Option<i64>, Header, description = "The ID of the last event received")
+    ),
+)]
+pub async fn stream_job_events(
+    State(ctx): State<Arc<Services>>,
+    Path(job_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    // Fails if the job does not exist, rather than streaming nothing
+    ctx.storage_layer.fetch_job(job_id, &mut ExecOptsBuilder::default().build()?).await?;
+
+    let after = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(0);
+    let org_id = current_org().unwrap_or(DEFAULT_ORG_ID);
+    let events = job_event_stream(ctx.storage_layer.clone(), org_id, job_id, after);
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()).into_response())
+}
+
+/// List the summaries of archived jobs, newest first. Jobs are archived a while after they finish,
+/// and are no longer listed with the others.
+///
+/// * `ctx`: The application context
+/// * `query`: Filters for the archived jobs
+#[utoipa::path(
+    get,
+    path = "/archive",
+    operation_id = "Get archived jobs",
+    responses(
+        (status = 200, description = "Successfully fetched archived jobs"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:jobs`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer"),
+        ("projectCycleId" = Option<Uuid>, Query, description = "Only list the jobs of this project cycle")
+    ),
+)]
+pub async fn fetch_archived_jobs(
+    State(ctx): State<Arc<Services>>,
+    Query(query): Query<ArchivedJobsQuery>,
+) -> Result<Response, AppError> {
+    let jobs = ctx
+        .storage_layer
+        .fetch_archived_jobs(query.project_cycle_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    Ok(api_response::success(StatusCode::OK, ArchivedJobsResponse { jobs })?)
+}
+
+/// Fetch the full details of an archived job, including its inputs and results.
+///
+/// * `ctx`: The application context
+/// * `job_id`: The ID of the job
+#[utoipa::path(
+    get,
+    path = "/{job_id}/archive",
+    operation_id = "Get job archive",
+    responses(
+        (status = 200, description = "Successfully fetched the archived job's details"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:jobs`)"),
+        (status = 404, description = "The job has not been archived"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_job_archive(
+    State(ctx): State<Arc<Services>>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let details = ctx
+        .storage_layer
+        .fetch_job_archive(job_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    match details {
+        Some(details) => {
+            Ok(api_response::success(StatusCode::OK, JobArchiveResponse { job_id, details })?)
+        }
+        None => Ok(api_response::error(StatusCode::NOT_FOUND, "Job has not been archived")),
+    }
+}
+
+/// Cancel a pending job. A running export notices that it was cancelled between volunteers and
+/// stops, keeping the outcome for the volunteers it already exported in the job result. A queued
+/// job that no worker has started yet stops as soon as one picks it up.
+///
+/// * `ctx`: The application context
+/// * `job_id`: The ID of the job
+#[utoipa::path(
+    post,
+    path = "/{job_id}/cancel",
+    operation_id = "Cancel job",
+    responses(
+        (status = 204, description = "Successfully cancelled the job"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:jobs`)"),
+        (status = 409, description = "The job does not exist or is no longer pending"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn cancel_job(
+    State(ctx): State<Arc<Services>>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let cancelled =
+        ctx.storage_layer.cancel_job(job_id, &mut ExecOptsBuilder::default().build()?).await?;
+
+    if !cancelled {
+        return Ok(api_response::error(StatusCode::CONFLICT, "Job is not pending"));
+    }
+
+    log::info!("Job {job_id} was cancelled");
+    Ok(api_response::no_content())
+}
+
+/// Requeue a dead-lettered job, so that workers try it again with a fresh set of attempts. Why its
+/// earlier attempts failed is kept in the queue. An export that already created some users is
+/// resumed rather than started over.
+///
+/// * `ctx`: The application context
+/// * `job_id`: The ID of the job
+#[utoipa::path(
+    post,
+    path = "/{job_id}/requeue",
+    operation_id = "Requeue job",
+    responses(
+        (status = 204, description = "Successfully requeued the job"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:jobs`)"),
+        (status = 409, description = "The job does not exist or is not dead-lettered"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn requeue_job(
+    State(ctx): State<Arc<Services>>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    // The job and its place in the queue are requeued together, so a worker never claims a job
+    // that is still dead-lettered
+    let mut tx = ctx.storage_layer.acquire().await?;
+    let mut exec_opts = ExecOptsBuilder::default().tx(&mut tx).build()?;
+    let requeued = ctx.storage_layer.requeue_job(job_id, &mut exec_opts).await?
+        && ctx.storage_layer.requeue_dead_lettered_job(job_id, &mut exec_opts).await?;
+
+    if !requeued {
+        return Ok(api_response::error(StatusCode::CONFLICT, "Job is not dead-lettered"));
+    }
+    tx.commit().await?;
+
+    log::info!("Job {job_id} was requeued");
+    Ok(api_response::no_content())
+}
+
+/// Change when a queued job runs, as long as no worker has started it. A time in the past runs the
+/// job as soon as a worker is free.
+///
+/// * `ctx`: The application context
+/// * `job_id`: The ID of the job
+/// * `request`: When to run the job
+#[utoipa::path(
+    post,
+    path = "/{job_id}/reschedule",
+    operation_id = "Reschedule job",
+    responses(
+        (status = 204, description = "Successfully rescheduled the job"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:jobs`)"),
+        (status = 409, description = "The job is not queued, or a worker has already started it"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn reschedule_job(
+    State(ctx): State<Arc<Services>>,
+    Path(job_id): Path<Uuid>,
+    Json(request): Json<RescheduleJobRequest>,
+) -> Result<Response, AppError> {
+    let rescheduled = ctx
+        .storage_layer
+        .reschedule_queued_job(job_id, request.run_at, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    if !rescheduled {
+        return Ok(api_response::error(
+            StatusCode::CONFLICT,
+            "Job is not queued or has already started",
+        ));
+    }
+
+    log::info!("Job {job_id} was rescheduled to run at {}", request.run_at);
+    Ok(api_response::no_content())
+}
+
+/// Register the URL called when a job finishes, in place of the one configured for every job, or
+/// clear it to call that one again. Calls are signed with the configured webhook secret.
+///
+/// * `ctx`: The application context
+/// * `job_id`: The ID of the job
+/// * `request`: The URL to call
+#[utoipa::path(
+    put,
+    path = "/{job_id}/callback",
+    operation_id = "Set job callback",
+    responses(
+        (status = 204, description = "Successfully set the job's callback URL"),
+        (status = 400, description = "The URL is not an http or https URL"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:jobs`)"),
+        (status = 404, description = "The job does not exist"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn set_job_callback(
+    State(ctx): State<Arc<Services>>,
+    Path(job_id): Path<Uuid>,
+    Json(request): Json<SetJobCallbackRequest>,
+) -> Result<Response, AppError> {
+    if let Some(url) = &request.url {
+        if let Err(e) = validate_callback_url(url).await {
+            return Ok(api_response::error(StatusCode::BAD_REQUEST, &format!("{e:#}")));
+        }
+    }
+
+    let updated = ctx
+        .storage_layer
+        .set_job_callback_url(job_id, request.url, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    if !updated {
+        return Ok(api_response::error(StatusCode::NOT_FOUND, "Job not found"));
+    }
+
+    Ok(api_response::no_content())
+}
+
+/// Take a snapshot of the current state of a job for a support investigation. The snapshot holds
+/// the job's parameters with secrets redacted, the outcome for each volunteer, and the status of
+/// each email the job sent.
+///
+/// * `ctx`: The application context
+/// * `auth`: Auth data about the user
+/// * `job_id`: The ID of the job
+#[utoipa::path(
+    post,
+    path = "/{job_id}/snapshots",
+    operation_id = "Take job snapshot",
+    responses(
+        (status = 201, description = "Successfully took a snapshot of the job"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:jobs`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn take_snapshot(
+    State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let snapshot = take_job_snapshot(ctx.storage_layer.as_ref(), job_id).await?;
+    let snapshot = ctx
+        .storage_layer
+        .save_job_snapshot(
+            job_id,
+            Some(auth.email()?),
+            snapshot,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    Ok(api_response::success(StatusCode::CREATED, snapshot)?)
+}
+
+/// List the snapshots taken of a job, newest first, without their contents.
+///
+/// * `ctx`: The application context
+/// * `job_id`: The ID of the job
+#[utoipa::path(
+    get,
+    path = "/{job_id}/snapshots",
+    operation_id = "Get job snapshots",
+    responses(
+        (status = 200, description = "Successfully fetched the job's snapshots"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:jobs`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_snapshots(
+    State(ctx): State<Arc<Services>>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let snapshots = ctx
+        .storage_layer
+        .fetch_job_snapshots(job_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    Ok(api_response::success(StatusCode::OK, JobSnapshotsResponse { snapshots })?)
+}
+
+/// Fetch a snapshot of a job with its contents.
+///
+/// * `ctx`: The application context
+/// * `job_id`: The ID of the job
+/// * `snapshot_id`: The ID of the snapshot
+#[utoipa::path(
+    get,
+    path = "/{job_id}/snapshots/{snapshot_id}",
+    operation_id = "Get job snapshot",
+    responses(
+        (status = 200, description = "Successfully fetched the snapshot"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:jobs`)"),
+        (status = 404, description = "The job has no such snapshot"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_snapshot(
+    State(ctx): State<Arc<Services>>,
+    Path((job_id, snapshot_id)): Path<(Uuid, Uuid)>,
+) -> Result<Response, AppError> {
+    let snapshot = ctx
+        .storage_layer
+        .fetch_job_snapshot(snapshot_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    match snapshot {
+        Some(snapshot) if snapshot.job_id == job_id => {
+            Ok(api_response::success(StatusCode::OK, snapshot)?)
+        }
+        _ => Ok(api_response::error(StatusCode::NOT_FOUND, "Snapshot not found")),
+    }
+}
@@ -0,0 +1,173 @@
+//! This module records the progress events jobs emit while they run, and streams them to clients
+//! as Server-Sent Events.
+//!
+//! Events are recorded in the database rather than passed around in memory, since the worker
+//! running a job may live in another process than the one serving the stream. A stream polls for
+//! the events recorded since the last one it sent, and ends once the job has finished and every
+//! event it emitted has been sent. Each event is sent with its ID, so a client that reconnects with
+//! `Last-Event-ID` picks up where it left off.
+
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use axum::response::sse::Event;
+use futures::{stream, Stream};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::services::storage::entities::JobEvent;
+use crate::services::storage::organizations::with_org;
+use crate::services::storage::types::{JobPhase, JobStatus, WorkspaceExportStatus};
+use crate::services::storage::{ExecOptsBuilder, StorageService};
+
+/// How often a stream checks for new events.
+pub const JOB_EVENTS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Something that happened while a job ran.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum ProgressEvent {
+    /// The job started (or went back to) a phase
+    ///
+    /// * `phase`: The phase the job is in
+    /// * `processed`: How many volunteers had been processed in this phase when it started
+    /// * `total`: How many volunteers there are to process in this phase
+    PhaseStarted { phase: JobPhase, processed: usize, total: usize },
+    /// A volunteer was created in Google Workspace, or failed to be
+    ///
+    /// * `volunteer_id`: The ID of the volunteer
+    /// * `status`: Whether the volunteer was created
+    /// * `error`: Why the volunteer was not created, if they were not
+    VolunteerExported { volunteer_id: Uuid, status: WorkspaceExportStatus, error: Option<String> },
+    /// An onboarding email was sent to a volunteer, or failed to be
+    ///
+    /// * `workspace_email`: The volunteer's Google Workspace email
+    /// * `error`: Why the email was not sent, if it was not
+    OnboardingEmailSent { workspace_email: String, error: Option<String> },
+}
+
+/// Record an event emitted by a job.
+///
+/// * `storage`: The storage layer
+/// * `job_id`: The ID of the job
+/// * `event`: What happened
+///
+/// Events are informational, so failing to record one is logged rather than failing the job.
+pub async fn record_job_event(storage: &dyn StorageService, job_id: Uuid, event: ProgressEvent) {
+    let result = async {
+        let event = serde_json::to_value(event)?;
+        storage.record_job_event(job_id, event, &mut ExecOptsBuilder::default().build()?).await
+    };
+
+    if let Err(e) = result.await {
+        log::warn!("Failed to record an event of job {job_id}: {e}");
+    }
+}
+
+/// Whether a job has stopped running, so it emits no more events.
+///
+/// * `status`: The status of the job
+pub fn is_finished(status: JobStatus) -> bool {
+    !matches!(status, JobStatus::Pending)
+}
+
+/// Where a stream of a job's events is up to.
+///
+/// * `storage`: The storage layer
+/// * `org_id`: The ID of the organization the job belongs to
+/// * `job_id`: The ID of the job
+/// * `after`: The ID of the last event sent
+/// * `pending`: Events fetched but not sent yet
+/// * `status`: The status to send before ending the stream, once the job has finished
+/// * `done`: Whether the stream has ended
+struct JobEventStream {
+    storage: Arc<dyn StorageService>,
+    org_id: Uuid,
+    job_id: Uuid,
+    after: i64,
+    pending: VecDeque<JobEvent>,
+    status: Option<JobStatus>,
+    done: bool,
+}
+
+impl JobEventStream {
+    /// Fetch the job's status and the events it emitted since the last one fetched. The status is
+    /// read first, so that no event a job emitted before it finished is missed.
+    async fn poll(&mut self) -> Result<JobStatus> {
+        let mut exec_opts = ExecOptsBuilder::default().build()?;
+        let status = self.storage.fetch_job_status(self.job_id, &mut exec_opts).await?;
+        let events = self.storage.fetch_job_events(self.job_id, self.after, &mut exec_opts).await?;
+        if let Some(last) = events.last() {
+            self.after = last.id;
+        }
+        self.pending.extend(events);
+        Ok(status)
+    }
+
+    /// The next message to send, or `None` once the stream has ended.
+    async fn next(&mut self) -> Option<Event> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(
+                    Event::default()
+                        .id(event.id.to_string())
+                        .event("progress")
+                        .data(event.event.to_string()),
+                );
+            }
+            if let Some(status) = self.status.take() {
+                self.done = true;
+                let status = serde_json::to_string(&status).unwrap_or_default();
+                return Some(Event::default().event("status").data(status));
+            }
+            if self.done {
+                return None;
+            }
+
+            match with_org(self.org_id, self.poll()).await {
+                Ok(status) if is_finished(status) => self.status = Some(status),
+                Ok(_) if self.pending.is_empty() => {
+                    tokio::time::sleep(JOB_EVENTS_POLL_INTERVAL).await
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    // The client reconnects with the last event it saw
+                    log::warn!("Failed to fetch events of job {}: {e}", self.job_id);
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// Stream the events a job emits, ending with its status once it has finished.
+///
+/// * `storage`: The storage layer
+/// * `org_id`: The ID of the organization the job belongs to. The stream is polled after the
+///   request that opened it has been handled, so its queries are scoped to the organization here
+/// * `job_id`: The ID of the job
+/// * `after`: The ID of the last event the client saw, or `0` to stream every event
+pub fn job_event_stream(
+    storage: Arc<dyn StorageService>,
+    org_id: Uuid,
+    job_id: Uuid,
+    after: i64,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    let state = JobEventStream {
+        storage,
+        org_id,
+        job_id,
+        after,
+        pending: VecDeque::new(),
+        status: None,
+        done: false,
+    };
+
+    stream::unfold(
+        state,
+        |mut state| async move { state.next().await.map(|event| (Ok(event), state)) },
+    )
+}
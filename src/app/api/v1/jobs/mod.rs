@@ -1,5 +1,12 @@
+pub mod archival;
 mod controllers;
+pub mod events;
+mod requests;
 mod responses;
+pub mod snapshots;
+#[cfg(test)]
+mod tests;
+pub mod webhooks;
 
 use std::sync::Arc;
 
@@ -14,6 +21,18 @@ use crate::app::state::Services;
 #[openapi(
     paths(
         controllers::fetch_jobs,
+        controllers::fetch_job_progress,
+        controllers::fetch_job_chain,
+        controllers::stream_job_events,
+        controllers::fetch_archived_jobs,
+        controllers::fetch_job_archive,
+        controllers::cancel_job,
+        controllers::requeue_job,
+        controllers::reschedule_job,
+        controllers::set_job_callback,
+        controllers::take_snapshot,
+        controllers::fetch_snapshots,
+        controllers::fetch_snapshot,
     ),
     security(("http" = ["JWT"]))
 )]
@@ -21,11 +40,68 @@ pub struct JobsApi;
 
 pub async fn build(ctx: Arc<Services>) -> Router<()> {
     let guard1 = make_rbac(vec!["read:jobs".to_owned()]).await;
+    let write_guard = make_rbac(vec!["write:jobs".to_owned()]).await;
 
     let fetch_jobs = routing::get(controllers::fetch_jobs);
+    let fetch_job_progress = routing::get(controllers::fetch_job_progress);
+    let fetch_job_chain = routing::get(controllers::fetch_job_chain);
+    let stream_job_events = routing::get(controllers::stream_job_events);
+    let fetch_archived_jobs = routing::get(controllers::fetch_archived_jobs);
+    let fetch_job_archive = routing::get(controllers::fetch_job_archive);
+    // Cancelling, requeueing, or rescheduling a job, setting its callback, and taking a snapshot
+    // change state, so they need `write:jobs` on top of the router-wide `read:jobs`
+    let cancel_job = routing::post(controllers::cancel_job)
+        .route_layer(from_fn_with_state(ctx.clone(), write_guard.clone()));
+    let requeue_job = routing::post(controllers::requeue_job)
+        .route_layer(from_fn_with_state(ctx.clone(), write_guard.clone()));
+    let reschedule_job = routing::post(controllers::reschedule_job)
+        .route_layer(from_fn_with_state(ctx.clone(), write_guard.clone()));
+    let set_job_callback = routing::put(controllers::set_job_callback)
+        .route_layer(from_fn_with_state(ctx.clone(), write_guard.clone()));
+    let snapshots = routing::get(controllers::fetch_snapshots).merge(
+        routing::post(controllers::take_snapshot)
+            .route_layer(from_fn_with_state(ctx.clone(), write_guard)),
+    );
+    let fetch_snapshot = routing::get(controllers::fetch_snapshot);
+
+    // Finished jobs are archived by a background task once they are old enough
+    match archival::archive_after_days_from_env() {
+        Ok(Some(after_days)) => {
+            archival::spawn_archive_task(ctx.clone(), after_days);
+        }
+        Ok(None) => {
+            log::info!("Not archiving jobs, {} is off", archival::JOB_ARCHIVE_AFTER_DAYS_ENV_VAR)
+        }
+        Err(e) => log::error!("Not archiving jobs: {e}"),
+    }
+
+    // Webhooks are called by a background task once the jobs they are about finish
+    match webhooks::WebhookConfig::from_env() {
+        Ok(Some(config)) => {
+            webhooks::spawn_webhook_task(ctx.clone(), config);
+        }
+        Ok(None) => {
+            log::info!(
+                "Not calling job webhooks, {} is not set",
+                webhooks::JOB_WEBHOOK_SECRET_ENV_VAR
+            )
+        }
+        Err(e) => log::error!("Not calling job webhooks: {e}"),
+    }
 
     Router::new()
         .route("/", fetch_jobs)
+        .route("/archive", fetch_archived_jobs)
+        .route("/:job_id/progress", fetch_job_progress)
+        .route("/:job_id/chain", fetch_job_chain)
+        .route("/:job_id/events", stream_job_events)
+        .route("/:job_id/archive", fetch_job_archive)
+        .route("/:job_id/cancel", cancel_job)
+        .route("/:job_id/requeue", requeue_job)
+        .route("/:job_id/reschedule", reschedule_job)
+        .route("/:job_id/callback", set_job_callback)
+        .route("/:job_id/snapshots", snapshots)
+        .route("/:job_id/snapshots/:snapshot_id", fetch_snapshot)
         .route_layer(from_fn_with_state(ctx.clone(), guard1))
         .with_state(ctx.clone())
 }
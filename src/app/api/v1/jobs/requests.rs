@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Query to list archived jobs.
+///
+/// * `project_cycle_id`: Only list the jobs of this project cycle
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedJobsQuery {
+    #[serde(default)]
+    pub project_cycle_id: Option<Uuid>,
+}
+
+/// Request to change when a queued job runs.
+///
+/// * `run_at`: When to run the job at the earliest
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RescheduleJobRequest {
+    pub run_at: DateTime<Utc>,
+}
+
+/// Request to set the URL called when a job finishes.
+///
+/// * `url`: The https URL to call, whose host must resolve to public addresses, or `None` to call
+///   the one configured for every job
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetJobCallbackRequest {
+    #[serde(default)]
+    pub url: Option<String>,
+}
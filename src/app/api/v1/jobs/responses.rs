@@ -2,6 +2,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::services::storage::entities::{
+    ArchivedJob, ChainedJob, JobProgress, JobSnapshotSummary,
+};
 use crate::services::storage::types::{JobDetails, JobStatus};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -38,3 +41,35 @@ pub struct Job {
 pub struct JobsResponse {
     pub jobs: Vec<Job>,
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobProgressResponse {
+    pub job_id: Uuid,
+    pub status: JobStatus,
+    pub progress: Option<JobProgress>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobChainResponse {
+    pub job_id: Uuid,
+    pub jobs: Vec<ChainedJob>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchivedJobsResponse {
+    pub jobs: Vec<ArchivedJob>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobArchiveResponse {
+    pub job_id: Uuid,
+    pub details: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobSnapshotsResponse {
+    pub snapshots: Vec<JobSnapshotSummary>,
+}
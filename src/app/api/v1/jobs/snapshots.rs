@@ -0,0 +1,102 @@
+//! This module takes point-in-time snapshots of jobs for support investigations.
+//!
+//! A snapshot gathers everything known about a job into one JSON document: its parameters and
+//! results, its progress, the outcome of the export for each volunteer, and the status of each
+//! email it sent. Secrets are redacted before the snapshot is saved, so snapshots can be shared
+//! with whoever is investigating without leaking credentials.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::services::storage::entities::{
+    EmailSend, ExportCheckpoint, Job, JobProgress, OnboardingEmailDelivery,
+};
+use crate::services::storage::{ExecOptsBuilder, StorageService};
+
+/// What secrets are replaced with in snapshots.
+pub const REDACTED: &str = "[redacted]";
+
+/// Object keys containing any of these (ignoring case) are taken to hold secrets.
+const SECRET_KEY_FRAGMENTS: &[&str] = &[
+    "password",
+    "secret",
+    "token",
+    "credential",
+    "apikey",
+    "api_key",
+    "privatekey",
+    "private_key",
+];
+
+/// The state of a job at the moment a snapshot was taken.
+///
+/// * `taken_at`: When the snapshot was taken
+/// * `job`: The job, with its parameters and results
+/// * `archived_details`: The full details of the job, if it has been archived
+/// * `progress`: The last progress the job reported, if any
+/// * `volunteers`: The outcome of the export for each volunteer, if the job is an export
+/// * `email_deliveries`: The delivery status of each onboarding email the job sent
+/// * `email_sends`: Each attempt the job made to send an email
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct JobSnapshotContents {
+    pub taken_at: DateTime<Utc>,
+    pub job: Job,
+    pub archived_details: Option<Value>,
+    pub progress: Option<JobProgress>,
+    pub volunteers: Vec<ExportCheckpoint>,
+    pub email_deliveries: Vec<OnboardingEmailDelivery>,
+    pub email_sends: Vec<EmailSend>,
+}
+
+/// Whether the value of an object key should be redacted.
+///
+/// * `key`: The object key
+pub fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    SECRET_KEY_FRAGMENTS.iter().any(|fragment| key.contains(fragment))
+}
+
+/// Replace the values of keys that hold secrets, at any depth, with `REDACTED`.
+///
+/// * `value`: The JSON value to redact
+pub fn redact_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_secret_key(key) && !value.is_null() {
+                    *value = Value::String(REDACTED.to_owned());
+                } else {
+                    redact_secrets(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact_secrets),
+        _ => {}
+    }
+}
+
+/// Gather the current state of a job into a snapshot, with its secrets redacted.
+///
+/// * `storage`: The storage layer
+/// * `job_id`: The ID of the job
+pub async fn take_job_snapshot(storage: &dyn StorageService, job_id: Uuid) -> Result<Value> {
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    let contents = JobSnapshotContents {
+        taken_at: Utc::now(),
+        job: storage.fetch_job(job_id, &mut exec_opts).await?,
+        archived_details: storage.fetch_job_archive(job_id, &mut exec_opts).await?,
+        progress: storage.fetch_job_progress(job_id, &mut exec_opts).await?,
+        volunteers: storage.fetch_export_checkpoints(job_id, &mut exec_opts).await?,
+        email_deliveries: storage.fetch_onboarding_email_deliveries(job_id, &mut exec_opts).await?,
+        email_sends: storage.fetch_email_sends(job_id, None, &mut exec_opts).await?,
+    };
+
+    let mut snapshot = serde_json::to_value(contents)?;
+    redact_secrets(&mut snapshot);
+    Ok(snapshot)
+}
@@ -0,0 +1,167 @@
+use chrono::{TimeZone, Utc};
+use rstest::rstest;
+use serde_json::json;
+use uuid::uuid;
+
+use crate::app::api::v1::jobs::events::{is_finished, ProgressEvent};
+use crate::app::api::v1::jobs::snapshots::{is_secret_key, redact_secrets, REDACTED};
+use crate::app::api::v1::jobs::webhooks::{
+    is_public_address, next_webhook_attempt_at, sign_payload, validate_callback_url,
+    JobWebhookPayload, WEBHOOK_RETRY_BACKOFF,
+};
+use crate::services::storage::types::{JobPhase, JobStatus, WorkspaceExportStatus};
+
+#[test]
+fn test_redact_secrets() {
+    assert!(is_secret_key("temporaryPassword"));
+    assert!(is_secret_key("SLACK_BOT_TOKEN"));
+    assert!(is_secret_key("apiKey"));
+    assert!(!is_secret_key("objectKey"));
+    assert!(!is_secret_key("workspaceEmail"));
+
+    let mut snapshot = json!({
+        "job": {
+            "details": {
+                "baseId": "appS5z0uqz4l0IJvP",
+                "apiKey": "pat123",
+                "result": { "accounts": [{ "email": "a@example.org", "password": "hunter2" }] },
+            },
+        },
+        "emailSends": [{ "recipient": "a@example.org", "providerToken": null }],
+    });
+    redact_secrets(&mut snapshot);
+
+    assert_eq!(
+        snapshot,
+        json!({
+            "job": {
+                "details": {
+                    "baseId": "appS5z0uqz4l0IJvP",
+                    "apiKey": REDACTED,
+                    "result": { "accounts": [{ "email": "a@example.org", "password": REDACTED }] },
+                },
+            },
+            // there is nothing to hide in a missing secret
+            "emailSends": [{ "recipient": "a@example.org", "providerToken": null }],
+        })
+    );
+}
+
+#[test]
+fn test_progress_events() {
+    let event =
+        ProgressEvent::PhaseStarted { phase: JobPhase::Provisioning, processed: 0, total: 2 };
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        json!({ "type": "phaseStarted", "phase": "provisioning", "processed": 0, "total": 2 })
+    );
+
+    let event = ProgressEvent::VolunteerExported {
+        volunteer_id: uuid!("5b1a1b5e-16d0-4d4a-b18f-dc80df15b18b"),
+        status: WorkspaceExportStatus::Failed,
+        error: Some("quota exceeded".to_owned()),
+    };
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        json!({
+            "type": "volunteerExported",
+            "volunteerId": "5b1a1b5e-16d0-4d4a-b18f-dc80df15b18b",
+            "status": "failed",
+            "error": "quota exceeded",
+        })
+    );
+
+    // a stream only ends once the job has stopped running
+    assert!(!is_finished(JobStatus::Pending));
+    assert!(is_finished(JobStatus::Complete));
+    assert!(is_finished(JobStatus::DeadLettered));
+}
+
+#[test]
+fn test_job_webhooks() {
+    // receivers check the signature with the same secret, so it must match a standard HMAC-SHA256
+    let body = br#"{"jobId":"bc080e0d-8b14-46e0-9268-4bbb370035ec"}"#;
+    assert_eq!(
+        sign_payload("whsec_test", 1700000000, body),
+        "sha256=99c1ef8096261dc721edb48b1904aa61c017279d35eddb6912ae2b5b1f734f0d"
+    );
+    assert_ne!(
+        sign_payload("whsec_test", 1700000001, body),
+        sign_payload("whsec_test", 1700000000, body)
+    );
+
+    let finished_at = Utc.with_ymd_and_hms(2026, 10, 17, 12, 0, 0).unwrap();
+    let payload = JobWebhookPayload {
+        job_id: uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742"),
+        label: "Export Users".to_owned(),
+        description: None,
+        project_cycle_id: None,
+        status: JobStatus::Error,
+        created_at: finished_at,
+        finished_at,
+        error: Some("quota exceeded".to_owned()),
+    };
+    assert_eq!(
+        serde_json::to_value(&payload).unwrap(),
+        json!({
+            "jobId": "413eed73-3c6f-456a-b9f0-ae72d136c742",
+            "label": "Export Users",
+            "description": null,
+            "projectCycleId": null,
+            "status": "error",
+            "createdAt": "2026-10-17T12:00:00Z",
+            "finishedAt": "2026-10-17T12:00:00Z",
+            "error": "quota exceeded",
+        })
+    );
+
+    // calls that fail are retried with a doubling backoff, then given up on
+    assert_eq!(next_webhook_attempt_at(1, finished_at), Some(finished_at + WEBHOOK_RETRY_BACKOFF));
+    assert_eq!(
+        next_webhook_attempt_at(3, finished_at),
+        Some(finished_at + WEBHOOK_RETRY_BACKOFF * 4)
+    );
+    assert_eq!(next_webhook_attempt_at(6, finished_at), None);
+}
+
+#[rstest]
+#[case("https://93.184.215.14/scipio")]
+#[case("https://[2606:2800:21f:cb07:6820:80da:af6b:8b2c]/scipio")]
+#[case("https://93.184.215.14:8443/scipio")]
+#[tokio::test]
+async fn test_callback_url_allowed(#[case] url: &str) {
+    assert!(validate_callback_url(url).await.is_ok(), "{url} should be allowed");
+}
+
+#[rstest]
+#[case("not a url")]
+#[case("ftp://hooks.example.org/scipio")]
+#[case("http://hooks.example.org/scipio")]
+#[case("http://169.254.169.254/latest/meta-data/")]
+#[case("https://169.254.169.254/latest/meta-data/")]
+#[case("https://127.0.0.1:8080/admin")]
+#[case("https://localhost/scipio")]
+#[case("https://0.0.0.0/scipio")]
+#[case("https://10.0.0.12/scipio")]
+#[case("https://172.16.4.2/scipio")]
+#[case("https://192.168.1.1/scipio")]
+#[case("https://100.64.0.1/scipio")]
+#[case("https://[::1]/scipio")]
+#[case("https://[::]/scipio")]
+#[case("https://[fe80::1]/scipio")]
+#[case("https://[fd00::1]/scipio")]
+#[case("https://[::ffff:127.0.0.1]/scipio")]
+#[tokio::test]
+async fn test_callback_url_rejected(#[case] url: &str) {
+    assert!(validate_callback_url(url).await.is_err(), "{url} should be rejected");
+}
+
+#[test]
+fn test_is_public_address() {
+    assert!(is_public_address("93.184.215.14".parse().unwrap()));
+    assert!(is_public_address("100.128.0.1".parse().unwrap()));
+    assert!(is_public_address("2606:2800:21f:cb07:6820:80da:af6b:8b2c".parse().unwrap()));
+    assert!(!is_public_address("169.254.169.254".parse().unwrap()));
+    assert!(!is_public_address("255.255.255.255".parse().unwrap()));
+    assert!(!is_public_address("::ffff:10.0.0.1".parse().unwrap()));
+}
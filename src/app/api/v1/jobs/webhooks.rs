@@ -0,0 +1,387 @@
+//! This module calls webhooks when jobs finish, so that external systems (Airtable automations,
+//! Slack bots) can react to a job completing, erroring, or being cancelled without polling for it.
+//!
+//! A call is queued in the database whenever a job finishes, and a background task makes the calls
+//! that are due. Each call posts a summary of the job to the URL registered for the job, or to the
+//! one configured for every job if none was. The body is signed with HMAC-SHA256 so the receiver
+//! can check that it came from Scipio: `X-Scipio-Signature` holds `sha256=` followed by the hex
+//! signature of the `X-Scipio-Timestamp` header, a `.`, and the body. A call that fails is made
+//! again with a backoff that doubles every time, until it has been tried `MAX_WEBHOOK_ATTEMPTS`
+//! times.
+//!
+//! Any user who can start a job can choose where its webhook is called, so calls are only made to
+//! https URLs whose host resolves to public addresses. Otherwise a user could have Scipio post to
+//! its own network, such as a cloud metadata service or an admin port on localhost. The host is
+//! checked when the URL is registered, and again before every call in case its DNS changed.
+
+use std::env;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::redirect::Policy;
+use reqwest::Url;
+use ring::hmac;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::net::lookup_host;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::app::state::Services;
+use crate::services::storage::entities::JobWebhook;
+use crate::services::storage::organizations::{with_org, DEFAULT_ORG_ID};
+use crate::services::storage::types::JobStatus;
+use crate::services::storage::ExecOptsBuilder;
+
+/// The name of the environment variable holding the URL to call when any job finishes. Jobs that
+/// registered a URL of their own call theirs instead.
+pub const JOB_WEBHOOK_URL_ENV_VAR: &str = "JOB_WEBHOOK_URL";
+
+/// The name of the environment variable holding the secret webhook calls are signed with. No calls
+/// are made without one.
+pub const JOB_WEBHOOK_SECRET_ENV_VAR: &str = "JOB_WEBHOOK_SECRET";
+
+/// The header holding the signature of a webhook call.
+pub const SIGNATURE_HEADER: &str = "X-Scipio-Signature";
+
+/// The header holding when a webhook call was signed, as a Unix timestamp.
+pub const TIMESTAMP_HEADER: &str = "X-Scipio-Timestamp";
+
+/// How often the background task makes the webhook calls that are due.
+pub const WEBHOOK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// The most webhook calls made at a time.
+pub const WEBHOOK_BATCH_SIZE: i64 = 50;
+
+/// How long a receiver has to answer a webhook call.
+pub const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How many times a webhook call is made before it is given up on.
+pub const MAX_WEBHOOK_ATTEMPTS: i32 = 6;
+
+/// How long a webhook call that failed waits before it is made again. The wait doubles after
+/// every attempt that fails.
+pub const WEBHOOK_RETRY_BACKOFF: Duration = Duration::from_secs(60);
+
+/// How long the background task holds its claim on the calls it is making.
+pub const WEBHOOK_CLAIM_DURATION: Duration = Duration::from_secs(5 * 60);
+
+/// Where and how webhook calls are made.
+///
+/// * `url`: The URL to call when any job finishes, if there is one
+/// * `secret`: The secret calls are signed with
+/// * `client`: The HTTP client calls are made with
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub url: Option<String>,
+    secret: String,
+    client: reqwest::Client,
+}
+
+impl WebhookConfig {
+    /// Read the configuration from `JOB_WEBHOOK_URL` and `JOB_WEBHOOK_SECRET`. Returns `None` if no
+    /// secret is configured, in which case no calls are made. The URL's host is resolved before
+    /// every call rather than here.
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(secret) = env::var(JOB_WEBHOOK_SECRET_ENV_VAR) else {
+            return Ok(None);
+        };
+
+        let url = match env::var(JOB_WEBHOOK_URL_ENV_VAR) {
+            Ok(url) => {
+                parse_callback_url(&url)
+                    .with_context(|| format!("invalid {JOB_WEBHOOK_URL_ENV_VAR}"))?;
+                Some(url)
+            }
+            Err(_) => None,
+        };
+        Ok(Some(Self::new(url, secret)?))
+    }
+
+    /// Create the configuration.
+    ///
+    /// * `url`: The URL to call when any job finishes, if there is one
+    /// * `secret`: The secret calls are signed with
+    pub fn new(url: Option<String>, secret: String) -> Result<Self> {
+        // A redirect could send the call to an address the URL was checked not to resolve to
+        let client =
+            reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT).redirect(Policy::none()).build()?;
+        Ok(Self { url, secret, client })
+    }
+}
+
+/// Whether an address is reachable from the internet at large, rather than only from the
+/// machine or network Scipio runs on.
+///
+/// * `ip`: The address
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    // 100.64.0.0/10 is shared by the customers of a carrier, like a private network
+    let shared = first == 100 && (64..128).contains(&second);
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || shared)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    // fc00::/7 holds unique local addresses, and fe80::/10 link-local ones
+    let unique_local = first & 0xfe00 == 0xfc00;
+    let link_local = first & 0xffc0 == 0xfe80;
+    !(ip.is_loopback() || ip.is_unspecified() || unique_local || link_local)
+}
+
+/// Check that a callback URL is an https URL, and that its host is not a private address. A host
+/// that is a domain is not resolved.
+///
+/// * `url`: The URL
+fn parse_callback_url(url: &str) -> Result<Url> {
+    let parsed = Url::parse(url).with_context(|| format!("{url} is not a valid URL"))?;
+    if parsed.scheme() != "https" {
+        bail!("{url} must be an https URL");
+    }
+
+    if let Some(ip) = host_address(&parsed).filter(|ip| !is_public_address(*ip)) {
+        bail!("{url} must not call {ip}, which is not a public address");
+    }
+    Ok(parsed)
+}
+
+/// The address a URL's host is, if it is one rather than a domain.
+///
+/// * `url`: The URL
+fn host_address(url: &Url) -> Option<IpAddr> {
+    // IPv6 hosts are written between brackets
+    let host = url.host_str()?;
+    host.trim_start_matches('[').trim_end_matches(']').parse().ok()
+}
+
+/// Check that a callback URL can be called: it must be an https URL, and its host must only
+/// resolve to public addresses.
+///
+/// * `url`: The URL
+pub async fn validate_callback_url(url: &str) -> Result<()> {
+    let parsed = parse_callback_url(url)?;
+    if host_address(&parsed).is_some() {
+        return Ok(());
+    }
+    let Some(domain) = parsed.host_str() else {
+        bail!("{url} has no host");
+    };
+
+    let port = parsed.port_or_known_default().unwrap_or(443);
+    let addrs = lookup_host((domain, port))
+        .await
+        .with_context(|| format!("could not resolve the host of {url}"))?
+        .map(|addr| addr.ip())
+        .collect::<Vec<IpAddr>>();
+    if addrs.is_empty() {
+        bail!("the host of {url} does not resolve to any address");
+    }
+    if let Some(ip) = addrs.into_iter().find(|ip| !is_public_address(*ip)) {
+        bail!("{url} must not call {ip}, which is not a public address");
+    }
+    Ok(())
+}
+
+/// Sign the body of a webhook call.
+///
+/// * `secret`: The secret calls are signed with
+/// * `timestamp`: When the call was signed, as a Unix timestamp
+/// * `body`: The body of the call
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let mut context = hmac::Context::with_key(&key);
+    context.update(timestamp.to_string().as_bytes());
+    context.update(b".");
+    context.update(body);
+    let signature = context.sign();
+    let hex = signature.as_ref().iter().map(|byte| format!("{byte:02x}")).collect::<String>();
+    format!("sha256={hex}")
+}
+
+/// What a webhook call sends about the job that finished. The job's inputs and results are left
+/// out, since they may hold secrets; they can be fetched from the jobs API.
+///
+/// * `job_id`: The ID of the job
+/// * `label`: A friendly label for the job
+/// * `description`: A friendly description of the job, if it has one
+/// * `project_cycle_id`: The ID of the project cycle the job is associated with, if it is
+/// * `status`: The status the job finished with
+/// * `created_at`: When the job was created
+/// * `finished_at`: When the job finished
+/// * `error`: Why the job failed, if it did
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JobWebhookPayload {
+    pub job_id: Uuid,
+    pub label: String,
+    pub description: Option<String>,
+    pub project_cycle_id: Option<Uuid>,
+    pub status: JobStatus,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub error: Option<String>,
+}
+
+/// When to next make a webhook call that failed, or `None` to give up on it.
+///
+/// * `attempts`: How many times the call has been made, including the attempt that just failed
+/// * `now`: The current time
+pub fn next_webhook_attempt_at(attempts: i32, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if attempts >= MAX_WEBHOOK_ATTEMPTS {
+        return None;
+    }
+    let doublings = attempts.max(1) as u32 - 1;
+    Some(now + WEBHOOK_RETRY_BACKOFF * 2u32.pow(doublings))
+}
+
+/// Make a webhook call about a job that finished.
+///
+/// * `ctx`: The application services
+/// * `config`: Where and how webhook calls are made
+/// * `url`: The URL to call
+/// * `webhook`: The call
+async fn call_webhook(
+    ctx: &Services,
+    config: &WebhookConfig,
+    url: &str,
+    webhook: &JobWebhook,
+) -> Result<()> {
+    let job = ctx
+        .storage_layer
+        .fetch_job(webhook.job_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    let payload = JobWebhookPayload {
+        job_id: job.id,
+        label: job.label,
+        description: job.description,
+        project_cycle_id: job.project_cycle_id,
+        status: webhook.status,
+        created_at: job.created_at,
+        finished_at: webhook.created_at,
+        error: job.details.get("error").and_then(Value::as_str).map(str::to_owned),
+    };
+
+    // The host may resolve to somewhere else than when the URL was registered
+    validate_callback_url(url).await?;
+
+    let body = serde_json::to_vec(&payload)?;
+    let timestamp = Utc::now().timestamp();
+    let response = config
+        .client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, sign_payload(&config.secret, timestamp, &body))
+        .body(body)
+        .send()
+        .await?
+        .error_for_status()?;
+    if response.status().is_redirection() {
+        bail!("{url} redirected the call to another URL");
+    }
+    Ok(())
+}
+
+/// Make the webhook calls of the current organization that are due. Returns how many were made.
+///
+/// * `ctx`: The application services
+/// * `config`: Where and how webhook calls are made
+pub async fn call_due_webhooks(ctx: &Services, config: &WebhookConfig) -> Result<usize> {
+    let claimed_until = Utc::now() + WEBHOOK_CLAIM_DURATION;
+    let webhooks = ctx
+        .storage_layer
+        .claim_due_job_webhooks(
+            WEBHOOK_BATCH_SIZE,
+            claimed_until,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    let count = webhooks.len();
+    for webhook in webhooks {
+        let mut exec_opts = ExecOptsBuilder::default().build()?;
+        let Some(url) = webhook.callback_url.as_ref().or(config.url.as_ref()) else {
+            // Nowhere to call for this job
+            ctx.storage_layer.finish_job_webhook(webhook.id, None, &mut exec_opts).await?;
+            continue;
+        };
+
+        match call_webhook(ctx, config, url, &webhook).await {
+            Ok(()) => {
+                log::info!("Called webhook for job {} at {url}", webhook.job_id);
+                ctx.storage_layer.finish_job_webhook(webhook.id, None, &mut exec_opts).await?;
+            }
+            Err(e) => match next_webhook_attempt_at(webhook.attempts, Utc::now()) {
+                Some(retry_at) => {
+                    log::warn!("Failed to call webhook for job {}, retrying: {e}", webhook.job_id);
+                    ctx.storage_layer
+                        .retry_job_webhook(webhook.id, retry_at, e.to_string(), &mut exec_opts)
+                        .await?;
+                }
+                None => {
+                    log::error!(
+                        "Failed to call webhook for job {} {} times, giving up: {e}",
+                        webhook.job_id,
+                        webhook.attempts
+                    );
+                    ctx.storage_layer
+                        .finish_job_webhook(webhook.id, Some(e.to_string()), &mut exec_opts)
+                        .await?;
+                }
+            },
+        }
+    }
+    Ok(count)
+}
+
+/// Start the background task that makes the webhook calls that are due, for every organization.
+///
+/// * `ctx`: The application services
+/// * `config`: Where and how webhook calls are made
+pub fn spawn_webhook_task(ctx: Arc<Services>, config: WebhookConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(WEBHOOK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let org_ids = match ExecOptsBuilder::default().build() {
+                Ok(mut exec_opts) => ctx.storage_layer.fetch_organizations(&mut exec_opts).await,
+                Err(e) => Err(e.into()),
+            };
+            let org_ids = match org_ids {
+                Ok(organizations) => organizations.into_iter().map(|org| org.id).collect(),
+                Err(e) => {
+                    log::error!(
+                        "Failed to list organizations, only calling webhooks for the default one: \
+                         {e}"
+                    );
+                    vec![DEFAULT_ORG_ID]
+                }
+            };
+
+            for org_id in org_ids {
+                if let Err(e) = with_org(org_id, call_due_webhooks(&ctx, &config)).await {
+                    log::error!("Failed to call webhooks for organization {org_id}: {e}");
+                }
+            }
+        }
+    })
+}
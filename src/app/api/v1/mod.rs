@@ -1,24 +1,38 @@
 //! Defines and builds the API for version 1 of the Pantheon API.
 
+mod audit;
 mod authz;
+mod backups;
 mod cycles;
 mod data_exports;
 mod data_imports;
+mod emails;
 mod jobs;
+mod schedules;
+mod settings;
 mod stats;
+mod templates;
 mod volunteers;
+mod webhooks;
 
 use std::sync::Arc;
 
+use audit::AuditApi;
 use authz::AuthzApi;
 use axum::Router;
+use backups::BackupsApi;
 use cycles::CyclesApi;
 use data_exports::DataExportsApi;
 use data_imports::DataImportsApi;
+use emails::EmailsApi;
 use jobs::JobsApi;
+use schedules::SchedulesApi;
+use settings::SettingsApi;
 use stats::StatsApi;
+use templates::TemplatesApi;
 use utoipa::OpenApi;
 use volunteers::VolunteersApi;
+use webhooks::WebhooksApi;
 
 use crate::app::state::Services;
 
@@ -33,6 +47,13 @@ use crate::app::state::Services;
         (path = "/jobs", api = JobsApi),
         (path = "/volunteers", api = VolunteersApi),
         (path = "/stats", api = StatsApi),
+        (path = "/templates", api = TemplatesApi),
+        (path = "/emails", api = EmailsApi),
+        (path = "/webhooks", api = WebhooksApi),
+        (path = "/audit", api = AuditApi),
+        (path = "/backups", api = BackupsApi),
+        (path = "/settings", api = SettingsApi),
+        (path = "/schedules", api = SchedulesApi),
     ),
 )]
 pub struct V1Api;
@@ -48,6 +69,13 @@ pub async fn build(services: Arc<Services>) -> Router<()> {
     let jobs_routes = jobs::build(services.clone()).await;
     let volunteers_routes = volunteers::build(services.clone()).await;
     let stats_routes = stats::build(services.clone()).await;
+    let templates_routes = templates::build(services.clone()).await;
+    let emails_routes = emails::build(services.clone()).await;
+    let webhooks_routes = webhooks::build(services.clone()).await;
+    let audit_routes = audit::build(services.clone()).await;
+    let backups_routes = backups::build(services.clone()).await;
+    let settings_routes = settings::build(services.clone()).await;
+    let schedules_routes = schedules::build(services.clone()).await;
 
     Router::new()
         .nest("/data-imports", data_import_routes)
@@ -57,4 +85,11 @@ pub async fn build(services: Arc<Services>) -> Router<()> {
         .nest("/jobs", jobs_routes)
         .nest("/volunteers", volunteers_routes)
         .nest("/stats", stats_routes)
+        .nest("/templates", templates_routes)
+        .nest("/emails", emails_routes)
+        .nest("/webhooks", webhooks_routes)
+        .nest("/audit", audit_routes)
+        .nest("/backups", backups_routes)
+        .nest("/settings", settings_routes)
+        .nest("/schedules", schedules_routes)
 }
@@ -0,0 +1,175 @@
+//! Controllers for the schedules API.
+
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::Response;
+use axum::{Extension, Json};
+use chrono::Utc;
+
+use super::requests::SaveScheduleRequest;
+use super::responses::SchedulesResponse;
+use crate::app::api::v1::data_exports::workspace::scheduler::{
+    next_run_after, validate_params, ScheduledJob,
+};
+use crate::app::api_response;
+use crate::app::errors::AppError;
+use crate::app::state::Services;
+use crate::services::auth::AuthData;
+use crate::services::storage::schedules::SaveJobSchedule;
+use crate::services::storage::ExecOptsBuilder;
+
+/// Fetch the jobs that are scheduled, with when each last ran and next runs.
+///
+/// * `ctx`: The application context
+#[utoipa::path(
+    get,
+    path = "",
+    operation_id = "Get schedules",
+    responses(
+        (status = 200, description = "Successfully fetched schedules"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:schedules`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_schedules(State(ctx): State<Arc<Services>>) -> Result<Response, AppError> {
+    let schedules =
+        ctx.storage_layer.fetch_job_schedules(&mut ExecOptsBuilder::default().build()?).await?;
+    let jobs = ScheduledJob::ALL.iter().map(|job| job.name().to_owned()).collect();
+
+    Ok(api_response::success(StatusCode::OK, SchedulesResponse { schedules, jobs })?)
+}
+
+/// Fetch the schedule of a job, with when it last ran and next runs.
+///
+/// * `ctx`: The application context
+/// * `name`: The name of the job (e.g. `reconciliation`)
+#[utoipa::path(
+    get,
+    path = "/{name}",
+    operation_id = "Get schedule",
+    responses(
+        (status = 200, description = "Successfully fetched the schedule"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:schedules`)"),
+        (status = 404, description = "The job is not scheduled"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_schedule(
+    State(ctx): State<Arc<Services>>,
+    Path(name): Path<String>,
+) -> Result<Response, AppError> {
+    let schedule = ctx
+        .storage_layer
+        .fetch_job_schedule(&name, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    match schedule {
+        Some(schedule) => Ok(api_response::success(StatusCode::OK, schedule)?),
+        None => Ok(api_response::error(StatusCode::NOT_FOUND, "Job is not scheduled")),
+    }
+}
+
+/// Schedule a job, or change its schedule. The job next runs when the cron expression next fires.
+///
+/// * `ctx`: The application context
+/// * `auth`: Auth data about the user
+/// * `name`: The name of the job (e.g. `reconciliation`)
+/// * `request`: The schedule
+///
+/// Jobs that act on Google Workspace run as the user who last saved their schedule.
+#[utoipa::path(
+    put,
+    path = "/{name}",
+    operation_id = "Save schedule",
+    responses(
+        (status = 200, description = "Successfully saved the schedule"),
+        (status = 400, description = "The cron expression or the job's parameters are invalid"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:schedules`)"),
+        (status = 404, description = "There is no such job"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn save_schedule(
+    State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
+    Path(name): Path<String>,
+    Json(request): Json<SaveScheduleRequest>,
+) -> Result<Response, AppError> {
+    let job = match ScheduledJob::parse(&name) {
+        Ok(job) => job,
+        Err(e) => return Ok(api_response::error(StatusCode::NOT_FOUND, &e.to_string())),
+    };
+
+    let next_run_at = match next_run_after(&request.cron, Utc::now()) {
+        Ok(next_run_at) => next_run_at,
+        Err(e) => return Ok(api_response::error(StatusCode::BAD_REQUEST, &format!("{e:#}"))),
+    };
+    if let Err(e) = validate_params(job, &request.params) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &format!("{e:#}")));
+    }
+
+    let principal = auth.email()?;
+    let data = SaveJobSchedule {
+        name,
+        cron: request.cron.trim().to_owned(),
+        enabled: request.enabled,
+        params: request.params,
+        updated_by: Some(principal.clone()),
+        next_run_at,
+    };
+    let schedule = ctx
+        .storage_layer
+        .save_job_schedule(data, &mut ExecOptsBuilder::default().principal(principal).build()?)
+        .await?;
+
+    Ok(api_response::success(StatusCode::OK, schedule)?)
+}
+
+/// Delete the schedule of a job, so it no longer runs.
+///
+/// * `ctx`: The application context
+/// * `auth`: Auth data about the user
+/// * `name`: The name of the job (e.g. `reconciliation`)
+#[utoipa::path(
+    delete,
+    path = "/{name}",
+    operation_id = "Delete schedule",
+    responses(
+        (status = 204, description = "Successfully deleted the schedule"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:schedules`)"),
+        (status = 404, description = "The job is not scheduled"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn delete_schedule(
+    State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
+    Path(name): Path<String>,
+) -> Result<Response, AppError> {
+    let deleted = ctx
+        .storage_layer
+        .delete_job_schedule(
+            &name,
+            &mut ExecOptsBuilder::default().principal(auth.email()?).build()?,
+        )
+        .await?;
+
+    if !deleted {
+        return Ok(api_response::error(StatusCode::NOT_FOUND, "Job is not scheduled"));
+    }
+    Ok(api_response::no_content())
+}
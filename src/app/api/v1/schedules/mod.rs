@@ -0,0 +1,54 @@
+//! Schedules API.
+//!
+//! Staff schedule the jobs that should run regularly through this API, such as a nightly
+//! reconciliation, a weekly admin digest, or a purge of expired data, each on a cron expression.
+//! The jobs are run by the scheduler in `data_exports::workspace::scheduler`, which records when
+//! each last ran, how it went, and when it next runs.
+
+use std::sync::Arc;
+
+use axum::middleware::from_fn_with_state;
+use axum::{routing, Router};
+use utoipa::OpenApi;
+
+use crate::app::api::middleware::make_rbac;
+use crate::app::state::Services;
+
+mod controllers;
+mod requests;
+mod responses;
+
+/// Documents the API for scheduling jobs
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        controllers::fetch_schedules,
+        controllers::fetch_schedule,
+        controllers::save_schedule,
+        controllers::delete_schedule,
+    ),
+    security(("http" = ["JWT"]))
+)]
+pub struct SchedulesApi;
+
+/// Builds the schedules API.
+///
+/// * `ctx`: The application context
+pub async fn build(ctx: Arc<Services>) -> Router<()> {
+    let read_schedules_guard = make_rbac(vec!["read:schedules".to_owned()]).await;
+    let write_schedules_guard = make_rbac(vec!["write:schedules".to_owned()]).await;
+
+    let read = from_fn_with_state(ctx.clone(), read_schedules_guard);
+    let write = from_fn_with_state(ctx.clone(), write_schedules_guard);
+
+    // Reads and writes share paths, so each method is guarded on its own rather than with a
+    // router-wide layer
+    let schedules = routing::get(controllers::fetch_schedules).route_layer(read.clone());
+    let schedule = routing::get(controllers::fetch_schedule).route_layer(read).merge(
+        routing::put(controllers::save_schedule)
+            .delete(controllers::delete_schedule)
+            .route_layer(write),
+    );
+
+    Router::new().route("/", schedules).route("/:name", schedule).with_state(ctx.clone())
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Request to schedule a job, or to change its schedule.
+///
+/// * `cron`: The cron expression the job runs on, in UTC (e.g. `0 3 * * *` for 3am every day)
+/// * `enabled`: Whether the job runs. A paused job keeps its schedule but does not run.
+/// * `params`: What the job runs with, which depends on the job. Only `reconciliation` takes any:
+///   the `orgUnit` to reconcile (the default org unit if not set) and whether to `fix` orphans.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveScheduleRequest {
+    pub cron: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub params: Value,
+}
+
+fn default_enabled() -> bool {
+    true
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+use crate::services::storage::entities::JobSchedule;
+
+/// The jobs that are scheduled, with when each last ran and next runs.
+///
+/// * `schedules`: The schedules, by name
+/// * `jobs`: The names of every job that can be scheduled
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchedulesResponse {
+    pub schedules: Vec<JobSchedule>,
+    pub jobs: Vec<String>,
+}
@@ -0,0 +1,147 @@
+//! Controllers for the settings API.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::Response;
+use axum::{Extension, Json};
+
+use super::requests::UpdateSettingsRequest;
+use super::responses::SettingsResponse;
+use super::validate_settings;
+use crate::app::api::v1::data_exports::workspace::DEFAULT_ORG_UNIT;
+use crate::app::api_response;
+use crate::app::errors::AppError;
+use crate::app::state::Services;
+use crate::services::auth::AuthData;
+use crate::services::settings::{
+    DefaultOrgUnit, FeatureToggles, MailRecipientOverride, SenderAddress, SettingsService,
+    SmsRecipientOverride, SETTING_KEYS,
+};
+
+/// Read the settings in effect for the organization.
+///
+/// * `settings`: The settings service
+async fn settings_in_effect(settings: &SettingsService) -> Result<SettingsResponse> {
+    Ok(SettingsResponse {
+        default_org_unit: settings
+            .get::<DefaultOrgUnit>()
+            .await?
+            .unwrap_or_else(|| DEFAULT_ORG_UNIT.to_owned()),
+        sender_address: settings.get::<SenderAddress>().await?,
+        mail_recipient_override: settings.get::<MailRecipientOverride>().await?,
+        sms_recipient_override: settings.get::<SmsRecipientOverride>().await?,
+        feature_toggles: settings.get::<FeatureToggles>().await?.unwrap_or_default(),
+    })
+}
+
+/// Fetch the settings in effect for the organization.
+///
+/// * `ctx`: The application context
+#[utoipa::path(
+    get,
+    path = "",
+    operation_id = "Get settings",
+    responses(
+        (status = 200, description = "Successfully fetched settings"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:settings`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_settings(State(ctx): State<Arc<Services>>) -> Result<Response, AppError> {
+    let settings = SettingsService::new(ctx.storage_layer.clone());
+    Ok(api_response::success(StatusCode::OK, settings_in_effect(&settings).await?)?)
+}
+
+/// Change settings.
+///
+/// * `ctx`: The application context
+/// * `auth`: Auth data about the user
+/// * `request`: The settings to change
+///
+/// Every setting in the request is checked before any is saved, so an invalid request changes
+/// nothing.
+#[utoipa::path(
+    put,
+    path = "",
+    operation_id = "Update settings",
+    responses(
+        (status = 200, description = "Successfully saved settings"),
+        (status = 400, description = "A setting is invalid"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:settings`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn update_settings(
+    State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
+    Json(request): Json<UpdateSettingsRequest>,
+) -> Result<Response, AppError> {
+    if let Err(e) = validate_settings(&request) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &format!("{e:#}")));
+    }
+
+    let principal = Some(auth.email()?);
+    let settings = SettingsService::new(ctx.storage_layer.clone());
+
+    if let Some(org_unit) = request.default_org_unit {
+        settings.set::<DefaultOrgUnit>(&org_unit, principal.clone()).await?;
+    }
+    if let Some(address) = request.sender_address {
+        settings.set::<SenderAddress>(&address, principal.clone()).await?;
+    }
+    if let Some(address) = request.mail_recipient_override {
+        settings.set::<MailRecipientOverride>(&address, principal.clone()).await?;
+    }
+    if let Some(phone) = request.sms_recipient_override {
+        settings.set::<SmsRecipientOverride>(&phone, principal.clone()).await?;
+    }
+    if let Some(toggles) = request.feature_toggles {
+        settings.set::<FeatureToggles>(&toggles, principal).await?;
+    }
+
+    Ok(api_response::success(StatusCode::OK, settings_in_effect(&settings).await?)?)
+}
+
+/// Clear a setting, so its default is used again.
+///
+/// * `ctx`: The application context
+/// * `auth`: Auth data about the user
+/// * `key`: The key of the setting (e.g. `mailRecipientOverride`)
+#[utoipa::path(
+    delete,
+    path = "/{key}",
+    operation_id = "Clear setting",
+    responses(
+        (status = 204, description = "Successfully cleared the setting"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:settings`)"),
+        (status = 404, description = "There is no such setting, or it has not been saved"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn clear_setting(
+    State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
+    Path(key): Path<String>,
+) -> Result<Response, AppError> {
+    if !SETTING_KEYS.contains(&key.as_str()) {
+        return Ok(api_response::error(StatusCode::NOT_FOUND, "Setting not found"));
+    }
+
+    let settings = SettingsService::new(ctx.storage_layer.clone());
+    if !settings.clear(&key, Some(auth.email()?)).await? {
+        return Ok(api_response::error(StatusCode::NOT_FOUND, "Setting has not been saved"));
+    }
+    Ok(api_response::no_content())
+}
@@ -0,0 +1,95 @@
+//! Settings API.
+//!
+//! Staff can change the settings of their organization through this API without a deploy: the org
+//! unit exports create users in by default, the address emails are sent from, the recipients every
+//! onboarding email and text are redirected to while testing, and feature toggles. A setting that
+//! is cleared falls back to its default.
+
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use axum::middleware::from_fn_with_state;
+use axum::{routing, Router};
+use utoipa::OpenApi;
+
+use crate::app::api::middleware::make_rbac;
+use crate::app::api::v1::data_exports::workspace::validate_org_unit_path;
+use crate::app::state::Services;
+
+mod controllers;
+mod requests;
+mod responses;
+#[cfg(test)]
+mod tests;
+
+use requests::UpdateSettingsRequest;
+
+/// The longest name a feature toggle can have.
+pub const MAX_FEATURE_NAME_LENGTH: usize = 64;
+
+/// Check that the settings in a request are usable before any of them are saved.
+///
+/// * `request`: The settings to save
+pub fn validate_settings(request: &UpdateSettingsRequest) -> Result<()> {
+    if let Some(org_unit) = request.default_org_unit.as_deref() {
+        validate_org_unit_path(org_unit)?;
+    }
+
+    let addresses = [
+        ("sender", request.sender_address.as_deref()),
+        ("mail recipient override", request.mail_recipient_override.as_deref()),
+    ];
+    for (label, address) in addresses {
+        if let Some(address) = address {
+            address
+                .parse::<lettre::Address>()
+                .with_context(|| format!("{address:?} is not a valid {label} address"))?;
+        }
+    }
+
+    if let Some(phone) = request.sms_recipient_override.as_deref() {
+        if phone.trim().is_empty() {
+            bail!("The SMS recipient override cannot be empty");
+        }
+    }
+
+    for name in request.feature_toggles.iter().flat_map(|toggles| toggles.keys()) {
+        if name.is_empty() || name.len() > MAX_FEATURE_NAME_LENGTH {
+            bail!("Feature names must be between 1 and {MAX_FEATURE_NAME_LENGTH} characters long");
+        }
+    }
+
+    Ok(())
+}
+
+/// Documents the API for managing settings
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        controllers::fetch_settings,
+        controllers::update_settings,
+        controllers::clear_setting,
+    ),
+    security(("http" = ["JWT"]))
+)]
+pub struct SettingsApi;
+
+/// Builds the settings API.
+///
+/// * `ctx`: The application context
+pub async fn build(ctx: Arc<Services>) -> Router<()> {
+    let read_settings_guard = make_rbac(vec!["read:settings".to_owned()]).await;
+    let write_settings_guard = make_rbac(vec!["write:settings".to_owned()]).await;
+
+    let read = from_fn_with_state(ctx.clone(), read_settings_guard);
+    let write = from_fn_with_state(ctx.clone(), write_settings_guard);
+
+    // Reads and writes share paths, so each method is guarded on its own rather than with a
+    // router-wide layer
+    let settings = routing::get(controllers::fetch_settings)
+        .route_layer(read)
+        .merge(routing::put(controllers::update_settings).route_layer(write.clone()));
+    let setting = routing::delete(controllers::clear_setting).route_layer(write);
+
+    Router::new().route("/", settings).route("/:key", setting).with_state(ctx.clone())
+}
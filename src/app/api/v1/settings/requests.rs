@@ -0,0 +1,23 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Request to change settings. Only the settings that are present are saved; the rest are left as
+/// they are.
+///
+/// * `default_org_unit`: The org unit exports create users in when the request does not name one
+/// * `sender_address`: The address emails are sent from. It takes effect on the next restart.
+/// * `mail_recipient_override`: The address every onboarding email is sent to instead of the
+///   volunteer's
+/// * `sms_recipient_override`: The phone number every temporary password is texted to instead of
+///   the volunteer's
+/// * `feature_toggles`: Features to turn on or off, by name. They replace the saved toggles.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSettingsRequest {
+    pub default_org_unit: Option<String>,
+    pub sender_address: Option<String>,
+    pub mail_recipient_override: Option<String>,
+    pub sms_recipient_override: Option<String>,
+    pub feature_toggles: Option<BTreeMap<String, bool>>,
+}
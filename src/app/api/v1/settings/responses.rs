@@ -0,0 +1,21 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// The settings in effect for the organization, including defaults for those that have not been
+/// saved.
+///
+/// * `default_org_unit`: The org unit exports create users in when the request does not name one
+/// * `sender_address`: The address emails are sent from, if it has been changed
+/// * `mail_recipient_override`: The address every onboarding email is sent to, if any
+/// * `sms_recipient_override`: The phone number every temporary password is texted to, if any
+/// * `feature_toggles`: Features that are turned on or off, by name
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsResponse {
+    pub default_org_unit: String,
+    pub sender_address: Option<String>,
+    pub mail_recipient_override: Option<String>,
+    pub sms_recipient_override: Option<String>,
+    pub feature_toggles: BTreeMap<String, bool>,
+}
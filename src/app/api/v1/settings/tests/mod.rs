@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use crate::app::api::v1::settings::requests::UpdateSettingsRequest;
+use crate::app::api::v1::settings::validate_settings;
+
+#[test]
+fn test_validate_settings() {
+    let request = UpdateSettingsRequest {
+        default_org_unit: Some("/Programs/Fall".to_owned()),
+        sender_address: Some("onboarding@developforgood.org".to_owned()),
+        mail_recipient_override: Some("qa@developforgood.org".to_owned()),
+        sms_recipient_override: Some("+15555550123".to_owned()),
+        feature_toggles: Some(BTreeMap::from([("smsPasswords".to_owned(), true)])),
+    };
+    assert!(validate_settings(&request).is_ok());
+    assert!(validate_settings(&UpdateSettingsRequest::default()).is_ok());
+
+    let invalid = [
+        UpdateSettingsRequest {
+            default_org_unit: Some("Programs/Fall".to_owned()),
+            ..Default::default()
+        },
+        UpdateSettingsRequest {
+            sender_address: Some("developforgood.org".to_owned()),
+            ..Default::default()
+        },
+        UpdateSettingsRequest {
+            mail_recipient_override: Some("qa@".to_owned()),
+            ..Default::default()
+        },
+        UpdateSettingsRequest {
+            sms_recipient_override: Some(" ".to_owned()),
+            ..Default::default()
+        },
+        UpdateSettingsRequest {
+            feature_toggles: Some(BTreeMap::from([(String::new(), true)])),
+            ..Default::default()
+        },
+    ];
+    for request in invalid {
+        assert!(validate_settings(&request).is_err(), "{request:?} should be invalid");
+    }
+}
@@ -7,6 +7,8 @@ use uuid::Uuid;
 use crate::app::errors::AppError;
 use crate::app::state::Services;
 use crate::services::storage::entities::BasicStats;
+use crate::services::storage::pool::PoolStats;
+use crate::services::storage::timing::QueryTimingStats;
 use crate::services::storage::ExecOptsBuilder;
 
 #[utoipa::path(
@@ -33,3 +35,45 @@ pub async fn fetch_basic_stats(
         .await?;
     Ok(Json(stats))
 }
+
+/// Reports how the database connection pools are being used, including how long queries have
+/// waited for connections.
+#[utoipa::path(
+    get,
+    path = "/pool",
+    operation_id = "Get database pool stats",
+    responses(
+        (status = 200, description = "Successfully fetched database pool stats"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:stats`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_pool_stats(
+    State(ctx): State<Arc<Services>>,
+) -> Result<Json<PoolStats>, AppError> {
+    Ok(Json(ctx.storage_layer.pool_stats()))
+}
+
+/// Reports how long each storage operation has taken since startup, with the operations that took
+/// the most time in total first.
+#[utoipa::path(
+    get,
+    path = "/queries",
+    operation_id = "Get storage query timings",
+    responses(
+        (status = 200, description = "Successfully fetched storage query timings"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:stats`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_query_timings(
+    State(ctx): State<Arc<Services>>,
+) -> Result<Json<QueryTimingStats>, AppError> {
+    Ok(Json(ctx.storage_layer.query_timings()))
+}
@@ -14,6 +14,8 @@ use crate::app::state::Services;
 #[openapi(
     paths(
         controllers::fetch_basic_stats,
+        controllers::fetch_pool_stats,
+        controllers::fetch_query_timings,
     ),
     security(("http" = ["JWT"]))
 )]
@@ -23,9 +25,13 @@ pub async fn build(ctx: Arc<Services>) -> Router<()> {
     let read_guard = make_rbac(vec!["read:stats".to_owned()]).await;
 
     let fetch_basic_stats = routing::get(controllers::fetch_basic_stats);
+    let fetch_pool_stats = routing::get(controllers::fetch_pool_stats);
+    let fetch_query_timings = routing::get(controllers::fetch_query_timings);
 
     Router::new()
         .route("/:project_cycle_id/basic", fetch_basic_stats)
+        .route("/pool", fetch_pool_stats)
+        .route("/queries", fetch_query_timings)
         .route_layer(from_fn_with_state(ctx.clone(), read_guard))
         .with_state(ctx.clone())
 }
@@ -0,0 +1,255 @@
+//! Controllers for the email templates API.
+
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::Response;
+use axum::{Extension, Json};
+
+use super::requests::{CreateTemplateRequest, TemplateContentRequest};
+use super::responses::{TemplatePreviewResponse, TemplateVersionsResponse, TemplatesResponse};
+use super::{sample_onboarding_email, validate_template_name};
+use crate::app::api_response;
+use crate::app::errors::AppError;
+use crate::app::state::Services;
+use crate::services::auth::AuthData;
+use crate::services::mail::CustomTemplate;
+use crate::services::storage::templates::CreateEmailTemplateBuilder;
+use crate::services::storage::ExecOptsBuilder;
+
+/// Render a template with sample data, or describe why it cannot be rendered.
+///
+/// * `template`: The template to render
+fn render_preview(template: CustomTemplate) -> Result<TemplatePreviewResponse, String> {
+    let params = sample_onboarding_email(template).map_err(|e| format!("{e:#}"))?;
+    let html = params.render().map_err(|e| format!("Template does not render: {e:#}"))?;
+    let text = params.render_text().map_err(|e| format!("Template does not render: {e:#}"))?;
+    Ok(TemplatePreviewResponse { subject: params.subject().to_owned(), html, text })
+}
+
+/// Fetch the newest version of every email template.
+///
+/// * `ctx`: The application context
+#[utoipa::path(
+    get,
+    path = "",
+    operation_id = "Get email templates",
+    responses(
+        (status = 200, description = "Successfully fetched email templates"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:templates`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_templates(State(ctx): State<Arc<Services>>) -> Result<Response, AppError> {
+    let templates =
+        ctx.storage_layer.fetch_email_templates(&mut ExecOptsBuilder::default().build()?).await?;
+    Ok(api_response::success(StatusCode::OK, TemplatesResponse { templates })?)
+}
+
+/// Fetch the newest version of an email template.
+///
+/// * `ctx`: The application context
+/// * `name`: The name of the template
+#[utoipa::path(
+    get,
+    path = "/{name}",
+    operation_id = "Get email template",
+    responses(
+        (status = 200, description = "Successfully fetched email template"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:templates`)"),
+        (status = 404, description = "The template has never been saved"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_template(
+    State(ctx): State<Arc<Services>>,
+    Path(name): Path<String>,
+) -> Result<Response, AppError> {
+    let template = ctx
+        .storage_layer
+        .fetch_email_template(&name, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    match template {
+        Some(template) => Ok(api_response::success(StatusCode::OK, template)?),
+        None => Ok(api_response::error(StatusCode::NOT_FOUND, "Template not found")),
+    }
+}
+
+/// Fetch every version of an email template, newest first.
+///
+/// * `ctx`: The application context
+/// * `name`: The name of the template
+#[utoipa::path(
+    get,
+    path = "/{name}/versions",
+    operation_id = "Get email template versions",
+    responses(
+        (status = 200, description = "Successfully fetched email template versions"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `read:templates`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn fetch_template_versions(
+    State(ctx): State<Arc<Services>>,
+    Path(name): Path<String>,
+) -> Result<Response, AppError> {
+    let versions = ctx
+        .storage_layer
+        .fetch_email_template_versions(&name, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    Ok(api_response::success(StatusCode::OK, TemplateVersionsResponse { versions })?)
+}
+
+/// Create an email template.
+///
+/// * `ctx`: The application context
+/// * `auth`: Auth data about the user
+/// * `request`: The template to create
+///
+/// The template is rendered with sample data before it is saved, so a template that does not
+/// render is rejected instead of breaking the emails sent from it.
+#[utoipa::path(
+    post,
+    path = "",
+    operation_id = "Create email template",
+    responses(
+        (status = 201, description = "Successfully created email template"),
+        (status = 400, description = "The name is invalid or the template does not render"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:templates`)"),
+        (status = 409, description = "A template with the name already exists"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn create_template(
+    State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
+    Json(request): Json<CreateTemplateRequest>,
+) -> Result<Response, AppError> {
+    if let Err(e) = validate_template_name(&request.name) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string()));
+    }
+
+    let content = CustomTemplate { subject: request.subject, body: request.body };
+    if let Err(e) = render_preview(content.clone()) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e));
+    }
+
+    let existing = ctx
+        .storage_layer
+        .fetch_email_template(&request.name, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    if existing.is_some() {
+        return Ok(api_response::error(StatusCode::CONFLICT, "Template already exists"));
+    }
+
+    let principal = auth.email()?;
+    let data = CreateEmailTemplateBuilder::default()
+        .name(request.name)
+        .subject(content.subject)
+        .body(content.body)
+        .created_by(principal.clone())
+        .build()?;
+    let template = ctx
+        .storage_layer
+        .create_email_template(data, &mut ExecOptsBuilder::default().principal(principal).build()?)
+        .await?;
+
+    Ok(api_response::success(StatusCode::CREATED, template)?)
+}
+
+/// Save a new version of an email template.
+///
+/// * `ctx`: The application context
+/// * `auth`: Auth data about the user
+/// * `name`: The name of the template
+/// * `request`: The new content of the template
+///
+/// Earlier versions are kept, so an edit can be undone by saving an earlier version again.
+#[utoipa::path(
+    put,
+    path = "/{name}",
+    operation_id = "Update email template",
+    responses(
+        (status = 200, description = "Successfully saved a new version of the email template"),
+        (status = 400, description = "The template does not render"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:templates`)"),
+        (status = 404, description = "The template has never been saved"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn update_template(
+    State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
+    Path(name): Path<String>,
+    Json(request): Json<TemplateContentRequest>,
+) -> Result<Response, AppError> {
+    let content = CustomTemplate::from(request);
+    if let Err(e) = render_preview(content.clone()) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e));
+    }
+
+    let existing = ctx
+        .storage_layer
+        .fetch_email_template(&name, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    if existing.is_none() {
+        return Ok(api_response::error(StatusCode::NOT_FOUND, "Template not found"));
+    }
+
+    let principal = auth.email()?;
+    let data = CreateEmailTemplateBuilder::default()
+        .name(name)
+        .subject(content.subject)
+        .body(content.body)
+        .created_by(principal.clone())
+        .build()?;
+    let template = ctx
+        .storage_layer
+        .create_email_template(data, &mut ExecOptsBuilder::default().principal(principal).build()?)
+        .await?;
+
+    Ok(api_response::success(StatusCode::OK, template)?)
+}
+
+/// Render an email template with sample data without saving it.
+///
+/// * `request`: The template to preview
+#[utoipa::path(
+    post,
+    path = "/preview",
+    operation_id = "Preview email template",
+    responses(
+        (status = 200, description = "Successfully rendered the email template"),
+        (status = 400, description = "The template does not render"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `write:templates`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn preview_template(
+    Json(request): Json<TemplateContentRequest>,
+) -> Result<Response, AppError> {
+    match render_preview(request.into()) {
+        Ok(preview) => Ok(api_response::success(StatusCode::OK, preview)?),
+        Err(e) => Ok(api_response::error(StatusCode::BAD_REQUEST, &e)),
+    }
+}
@@ -0,0 +1,126 @@
+//! Email templates API.
+//!
+//! Staff can edit the copy of emails the app sends (e.g. the onboarding email) through this API
+//! without a deploy. Every edit is saved as a new version, and the newest version is the one that
+//! is sent. Templates that have never been edited fall back to the compiled-in templates.
+
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use axum::middleware::from_fn_with_state;
+use axum::{routing, Router};
+use utoipa::OpenApi;
+
+use crate::app::api::middleware::make_rbac;
+use crate::app::state::Services;
+use crate::services::mail::{CustomTemplate, OnboardingEmailParams, OnboardingEmailParamsBuilder};
+
+mod controllers;
+mod requests;
+mod responses;
+#[cfg(test)]
+mod tests;
+
+/// The longest name a template can have.
+pub const MAX_TEMPLATE_NAME_LENGTH: usize = 64;
+
+/// Names that cannot be used for templates because they collide with routes.
+const RESERVED_TEMPLATE_NAMES: &[&str] = &["preview"];
+
+/// Check that a template name is usable in a URL path.
+///
+/// * `name`: The name of the template
+///
+/// Names are 1 to 64 lowercase ASCII letters, digits, dashes, and underscores, optionally followed
+/// by a dot and the language of a translation (e.g. `onboard.es`).
+pub fn validate_template_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > MAX_TEMPLATE_NAME_LENGTH {
+        bail!("Template names must be between 1 and {MAX_TEMPLATE_NAME_LENGTH} characters long");
+    }
+
+    let (base, language) = match name.split_once('.') {
+        Some((base, language)) => (base, Some(language)),
+        None => (name, None),
+    };
+
+    if base.is_empty()
+        || !base
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        bail!("Template names can only contain lowercase letters, digits, dashes, and underscores");
+    }
+
+    if let Some(language) = language {
+        if language.is_empty() || !language.chars().all(|c| c.is_ascii_lowercase()) {
+            bail!("Template languages can only contain lowercase letters");
+        }
+    }
+
+    if RESERVED_TEMPLATE_NAMES.contains(&name) {
+        bail!("{name} is a reserved template name");
+    }
+
+    Ok(())
+}
+
+/// Sample onboarding email data to render a template with.
+///
+/// * `template`: The template to render
+///
+/// This is used to preview templates, and to check that a template renders before it is saved.
+pub fn sample_onboarding_email(template: CustomTemplate) -> Result<OnboardingEmailParams> {
+    Ok(OnboardingEmailParamsBuilder::default()
+        .first_name("Jane")
+        .last_name("Doe")
+        .email("jane.doe@example.com")
+        .workspace_email("jane.doe@developforgood.org")
+        .temporary_password("correct-horse-battery-staple")
+        .template(template)
+        .build()?)
+}
+
+/// Documents the API for managing email templates
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        controllers::fetch_templates,
+        controllers::fetch_template,
+        controllers::fetch_template_versions,
+        controllers::create_template,
+        controllers::update_template,
+        controllers::preview_template,
+    ),
+    security(("http" = ["JWT"]))
+)]
+pub struct TemplatesApi;
+
+/// Builds the email templates API.
+///
+/// * `ctx`: The application context
+pub async fn build(ctx: Arc<Services>) -> Router<()> {
+    let read_templates_guard = make_rbac(vec!["read:templates".to_owned()]).await;
+    let write_templates_guard = make_rbac(vec!["write:templates".to_owned()]).await;
+
+    let read = from_fn_with_state(ctx.clone(), read_templates_guard);
+    let write = from_fn_with_state(ctx.clone(), write_templates_guard);
+
+    // Reads and writes share paths, so each method is guarded on its own rather than with a
+    // router-wide layer
+    let templates = routing::get(controllers::fetch_templates)
+        .route_layer(read.clone())
+        .merge(routing::post(controllers::create_template).route_layer(write.clone()));
+    let template = routing::get(controllers::fetch_template)
+        .route_layer(read.clone())
+        .merge(routing::put(controllers::update_template).route_layer(write.clone()));
+    let template_versions =
+        routing::get(controllers::fetch_template_versions).route_layer(read.clone());
+    let preview_template = routing::post(controllers::preview_template).route_layer(write);
+
+    Router::new()
+        .route("/", templates)
+        .route("/preview", preview_template)
+        .route("/:name", template)
+        .route("/:name/versions", template_versions)
+        .with_state(ctx.clone())
+}
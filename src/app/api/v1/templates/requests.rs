@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use crate::services::mail::CustomTemplate;
+
+/// Request to create an email template.
+///
+/// * `name`: The name of the template (e.g. `onboard`). It must not already exist.
+/// * `subject`: The subject line of emails rendered from the template
+/// * `body`: The Tera source of the email body
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTemplateRequest {
+    pub name: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Request to save a new version of an email template, or to preview one without saving it.
+///
+/// * `subject`: The subject line of emails rendered from the template
+/// * `body`: The Tera source of the email body
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateContentRequest {
+    pub subject: String,
+    pub body: String,
+}
+
+impl From<TemplateContentRequest> for CustomTemplate {
+    fn from(value: TemplateContentRequest) -> Self {
+        Self { subject: value.subject, body: value.body }
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+use crate::services::storage::entities::EmailTemplate;
+
+/// The newest version of every email template.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TemplatesResponse {
+    pub templates: Vec<EmailTemplate>,
+}
+
+/// Every version of an email template, newest first.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TemplateVersionsResponse {
+    pub versions: Vec<EmailTemplate>,
+}
+
+/// An email template rendered with sample data.
+///
+/// * `subject`: The subject line of the email
+/// * `html`: The rendered HTML body of the email
+/// * `text`: The plain text body sent alongside the HTML body
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplatePreviewResponse {
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
@@ -0,0 +1,58 @@
+use rstest::rstest;
+
+use crate::app::api::v1::templates::{sample_onboarding_email, validate_template_name};
+use crate::services::mail::CustomTemplate;
+
+#[rstest]
+#[case("onboard", true)]
+#[case("onboard-reminder_2", true)]
+#[case("onboard.es", true)]
+#[case("onboard.es-mx", false)]
+#[case("onboard.", false)]
+#[case(".es", false)]
+#[case("onboard.es.mx", false)]
+#[case("onboard.es_MX", false)]
+#[case("", false)]
+#[case("Onboard", false)]
+#[case("on board", false)]
+#[case("../onboard", false)]
+#[case("preview", false)]
+#[case(&"a".repeat(65), false)]
+fn test_validate_template_name(#[case] name: &str, #[case] valid: bool) {
+    assert_eq!(validate_template_name(name).is_ok(), valid);
+}
+
+#[test]
+fn test_render_custom_template() {
+    let template = CustomTemplate {
+        subject: "Welcome aboard".to_owned(),
+        body: "<p>Hi {{ name }}, sign in as {{ email }} with {{ temporaryPassword }}</p>"
+            .to_owned(),
+    };
+
+    let params = sample_onboarding_email(template).unwrap();
+    assert_eq!(params.subject(), "Welcome aboard");
+    assert_eq!(
+        params.render().unwrap(),
+        "<p>Hi Jane, sign in as jane.doe@developforgood.org with correct-horse-battery-staple</p>"
+    );
+}
+
+#[test]
+fn test_render_custom_template_escapes_values() {
+    let template =
+        CustomTemplate { subject: "Welcome".to_owned(), body: "<p>{{ name }}</p>".to_owned() };
+
+    let mut params = sample_onboarding_email(template).unwrap();
+    params.first_name = "<script>".to_owned();
+    assert_eq!(params.render().unwrap(), "<p>&lt;script&gt;</p>");
+}
+
+#[test]
+fn test_render_broken_custom_template() {
+    let template =
+        CustomTemplate { subject: "Welcome".to_owned(), body: "<p>{{ name </p>".to_owned() };
+
+    let params = sample_onboarding_email(template).unwrap();
+    assert!(params.render().is_err());
+}
@@ -1,12 +1,18 @@
 use std::sync::Arc;
 
 use axum::extract::{Path, State};
-use axum::Json;
+use axum::http::StatusCode;
+use axum::response::Response;
+use axum::{Extension, Json};
 use uuid::Uuid;
 
-use crate::app::api::v1::volunteers::responses::Volunteers;
+use crate::app::api::v1::volunteers::requests::BatchUpdateVolunteersRequest;
+use crate::app::api::v1::volunteers::responses::{BatchUpdateVolunteersResponse, Volunteers};
+use crate::app::api::v1::volunteers::{validate_volunteer_updates, SOFT_DELETE_RETENTION_DAYS};
+use crate::app::api_response;
 use crate::app::errors::AppError;
 use crate::app::state::Services;
+use crate::services::auth::AuthData;
 use crate::services::storage::ExecOptsBuilder;
 
 #[utoipa::path(
@@ -33,3 +39,130 @@ pub async fn fetch_volunteers_by_cycle(
 
     Ok(Json(Volunteers { volunteers: data }))
 }
+
+/// Delete a volunteer. The volunteer is soft-deleted along with the records of their exports to
+/// workspace, and can be restored for `SOFT_DELETE_RETENTION_DAYS` days.
+///
+/// * `ctx`: The application context extracted as Axum state
+/// * `auth`: Auth data about the user
+/// * `id`: The ID of the volunteer to delete
+#[utoipa::path(
+    delete,
+    path = "/volunteer/{id}",
+    operation_id = "Delete volunteer",
+    responses(
+        (status = 204, description = "Successfully deleted volunteer"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `delete:volunteers`)"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn delete_volunteer(
+    State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
+    Path(id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let storage_layer = &ctx.storage_layer;
+    let mut exec_opts = ExecOptsBuilder::default().principal(auth.email()?).build()?;
+    storage_layer.delete_volunteer(id, &mut exec_opts).await?;
+    Ok(api_response::no_content())
+}
+
+/// Restore a deleted volunteer along with the records of their exports to workspace, if they were
+/// deleted within the last `SOFT_DELETE_RETENTION_DAYS` days.
+///
+/// * `ctx`: The application context extracted as Axum state
+/// * `auth`: Auth data about the user
+/// * `id`: The ID of the volunteer to restore
+#[utoipa::path(
+    post,
+    path = "/volunteer/{id}/restore",
+    operation_id = "Restore volunteer",
+    responses(
+        (status = 204, description = "Successfully restored volunteer"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `delete:volunteers`)"),
+        (status = 404, description = "No volunteer was deleted within the retention window"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn restore_volunteer(
+    State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
+    Path(id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let storage_layer = &ctx.storage_layer;
+    let mut exec_opts = ExecOptsBuilder::default().principal(auth.email()?).build()?;
+    let restored =
+        storage_layer.restore_volunteer(id, SOFT_DELETE_RETENTION_DAYS, &mut exec_opts).await?;
+
+    if !restored {
+        return Ok(api_response::error(
+            StatusCode::NOT_FOUND,
+            "No volunteer was deleted within the retention window",
+        ));
+    }
+
+    Ok(api_response::no_content())
+}
+
+/// Correct the recovery emails, cohorts, or names of several volunteers at once. The corrections
+/// are made in one transaction, so either all of them are made or none are.
+///
+/// * `ctx`: The application context extracted as Axum state
+/// * `auth`: Auth data about the user
+/// * `request`: The corrections, one per volunteer
+///
+/// Volunteers that do not exist or were deleted are skipped and listed in the response. A
+/// volunteer moved to another cohort is unlinked from the nonprofits and mentors of their old one.
+#[utoipa::path(
+    patch,
+    path = "/batch",
+    operation_id = "Batch update volunteers",
+    responses(
+        (status = 200, description = "Successfully updated the volunteers"),
+        (status = 400, description = "The updates are invalid"),
+        (status = 401, description = "Unauthorized: invalid JWT"),
+        (status = 403, description = "Forbidden: insufficient permissions (requires `edit:volunteers`)"),
+        (status = 409, description = "A recovery email is already used by another volunteer"),
+    ),
+    params(
+        ("Authorization" = String, Header, description = "JWT. NOTE: Prefix with Bearer")
+    ),
+)]
+pub async fn batch_update_volunteers(
+    State(ctx): State<Arc<Services>>,
+    Extension(auth): Extension<AuthData>,
+    Json(request): Json<BatchUpdateVolunteersRequest>,
+) -> Result<Response, AppError> {
+    if let Err(e) = validate_volunteer_updates(&request.updates) {
+        return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string()));
+    }
+
+    let storage_layer = &ctx.storage_layer;
+    for update in &request.updates {
+        let Some(email) = &update.email else {
+            continue;
+        };
+        let owner = storage_layer
+            .fetch_volunteer_by_email(email, &mut ExecOptsBuilder::default().build()?)
+            .await?;
+        if owner.is_some_and(|v| v.volunteer_id != update.id) {
+            return Ok(api_response::error(
+                StatusCode::CONFLICT,
+                &format!("{email} is already used by another volunteer"),
+            ));
+        }
+    }
+
+    let ids: Vec<Uuid> = request.updates.iter().map(|u| u.id).collect();
+    let mut exec_opts = ExecOptsBuilder::default().principal(auth.email()?).build()?;
+    let updated = storage_layer.batch_update_volunteers(request.updates, &mut exec_opts).await?;
+    let not_found = ids.into_iter().filter(|id| !updated.contains(id)).collect();
+
+    Ok(api_response::success(StatusCode::OK, BatchUpdateVolunteersResponse { updated, not_found })?)
+}
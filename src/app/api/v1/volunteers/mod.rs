@@ -1,19 +1,76 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use anyhow::{bail, Result};
 use axum::middleware::from_fn_with_state;
 use axum::{routing, Router};
 use utoipa::OpenApi;
 
 use crate::app::api::middleware::make_rbac;
 use crate::app::state::Services;
+use crate::services::storage::volunteers::UpdateVolunteer;
 
 mod controllers;
+mod requests;
 mod responses;
+#[cfg(test)]
+mod tests;
+
+/// How many days a deleted volunteer can still be restored for
+pub const SOFT_DELETE_RETENTION_DAYS: i32 = 30;
+
+/// The most volunteers that can be corrected in one request.
+pub const MAX_BATCH_UPDATE_SIZE: usize = 500;
+
+/// Check that a batch of corrections to volunteers can be applied.
+///
+/// * `updates`: The corrections
+///
+/// A batch must correct between 1 and `MAX_BATCH_UPDATE_SIZE` volunteers, each at most once. Every
+/// correction must change something, recovery emails must be email addresses, and names must not
+/// be blank.
+pub fn validate_volunteer_updates(updates: &[UpdateVolunteer]) -> Result<()> {
+    if updates.is_empty() || updates.len() > MAX_BATCH_UPDATE_SIZE {
+        bail!("Between 1 and {MAX_BATCH_UPDATE_SIZE} volunteers can be updated at once");
+    }
+
+    let mut ids = HashSet::with_capacity(updates.len());
+    for update in updates {
+        if !ids.insert(update.id) {
+            bail!("Volunteer {} is updated more than once", update.id);
+        }
+
+        if update.email.is_none()
+            && update.project_cycle_id.is_none()
+            && update.first_name.is_none()
+            && update.last_name.is_none()
+        {
+            bail!("The update of volunteer {} does not change anything", update.id);
+        }
+
+        if let Some(email) = &update.email {
+            if email.trim() != email || !email.contains('@') {
+                bail!("{email} is not a valid recovery email");
+            }
+        }
+
+        for name in [&update.first_name, &update.last_name].into_iter().flatten() {
+            if name.trim().is_empty() {
+                bail!("The names of volunteer {} cannot be blank", update.id);
+            }
+        }
+    }
+
+    Ok(())
+}
 
 #[derive(OpenApi)]
 #[openapi(
     paths(
-        controllers::fetch_volunteers_by_cycle
+        controllers::fetch_volunteers_by_cycle,
+        controllers::delete_volunteer,
+        controllers::restore_volunteer,
+        controllers::batch_update_volunteers,
     ),
     security(("http" = ["JWT"]))
 )]
@@ -21,11 +78,21 @@ pub struct VolunteersApi;
 
 pub async fn build(ctx: Arc<Services>) -> Router<()> {
     let guard1 = make_rbac(vec!["read:volunteers".to_owned()]).await;
+    let write_volunteers_guard = make_rbac(vec!["delete:volunteers".to_owned()]).await;
+    let edit_volunteers_guard = make_rbac(vec!["edit:volunteers".to_owned()]).await;
 
     let fetch_volunteers_by_cycle = routing::get(controllers::fetch_volunteers_by_cycle);
+    let delete_volunteer = routing::delete(controllers::delete_volunteer);
+    let restore_volunteer = routing::post(controllers::restore_volunteer);
+    let batch_update_volunteers = routing::patch(controllers::batch_update_volunteers)
+        .route_layer(from_fn_with_state(ctx.clone(), edit_volunteers_guard));
 
     Router::new()
         .route("/:project_cycle_id", fetch_volunteers_by_cycle)
         .route_layer(from_fn_with_state(ctx.clone(), guard1))
+        .route("/volunteer/:id", delete_volunteer)
+        .route("/volunteer/:id/restore", restore_volunteer)
+        .route_layer(from_fn_with_state(ctx.clone(), write_volunteers_guard))
+        .route("/batch", batch_update_volunteers)
         .with_state(ctx.clone())
 }
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+use crate::services::storage::volunteers::UpdateVolunteer;
+
+/// Request to correct the details of several volunteers at once.
+///
+/// * `updates`: The corrections, one per volunteer. Each changes only the fields it sets.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchUpdateVolunteersRequest {
+    pub updates: Vec<UpdateVolunteer>,
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::services::storage::entities::VolunteerDetails;
 
@@ -6,3 +7,14 @@ use crate::services::storage::entities::VolunteerDetails;
 pub struct Volunteers {
     pub volunteers: Vec<VolunteerDetails>,
 }
+
+/// The outcome of correcting several volunteers at once.
+///
+/// * `updated`: The IDs of the volunteers that were updated
+/// * `not_found`: The IDs of the volunteers that do not exist or were deleted, which were skipped
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchUpdateVolunteersResponse {
+    pub updated: Vec<Uuid>,
+    pub not_found: Vec<Uuid>,
+}
@@ -0,0 +1,44 @@
+use rstest::rstest;
+use uuid::{uuid, Uuid};
+
+use crate::app::api::v1::volunteers::{validate_volunteer_updates, MAX_BATCH_UPDATE_SIZE};
+use crate::services::storage::volunteers::{UpdateVolunteer, UpdateVolunteerBuilder};
+
+fn update() -> UpdateVolunteerBuilder {
+    let mut builder = UpdateVolunteerBuilder::default();
+    builder.id(uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67"));
+    builder
+}
+
+#[rstest]
+#[case(update().email("rafa.nadal@gmail.com").build().unwrap(), true)]
+#[case(update().first_name("Rafa").last_name("Nadal").build().unwrap(), true)]
+#[case(update().project_cycle_id(Uuid::nil()).build().unwrap(), true)]
+#[case(update().build().unwrap(), false)]
+#[case(update().email("rafa.nadal").build().unwrap(), false)]
+#[case(update().email(" rafa.nadal@gmail.com").build().unwrap(), false)]
+#[case(update().first_name("  ").build().unwrap(), false)]
+fn test_validate_volunteer_update(#[case] update: UpdateVolunteer, #[case] valid: bool) {
+    assert_eq!(validate_volunteer_updates(&[update]).is_ok(), valid);
+}
+
+#[test]
+fn test_validate_volunteer_update_batch() {
+    let rafael = update().last_name("Nadal Parera").build().unwrap();
+    let roger = UpdateVolunteerBuilder::default()
+        .id(uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90"))
+        .last_name("Federer")
+        .build()
+        .unwrap();
+
+    assert!(validate_volunteer_updates(&[rafael.clone(), roger]).is_ok());
+    assert!(validate_volunteer_updates(&[]).is_err());
+
+    // a volunteer can only be updated once per batch
+    assert!(validate_volunteer_updates(&[rafael.clone(), rafael.clone()]).is_err());
+
+    let too_many: Vec<UpdateVolunteer> = (0..=MAX_BATCH_UPDATE_SIZE)
+        .map(|_| UpdateVolunteer { id: Uuid::new_v4(), ..rafael.clone() })
+        .collect();
+    assert!(validate_volunteer_updates(&too_many).is_err());
+}
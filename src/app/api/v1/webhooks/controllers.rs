@@ -0,0 +1,103 @@
+//! Controllers for the webhooks API.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::Response;
+use scipio_sendgrid::webhook::{Event, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use uuid::Uuid;
+
+use super::{delivery_from_event, org_from_event, sendgrid_event_verifier, suppression_from_event};
+use crate::app::api_response;
+use crate::app::errors::AppError;
+use crate::app::state::Services;
+use crate::services::storage::organizations::with_org;
+use crate::services::storage::ExecOptsBuilder;
+
+/// Receive delivery events from SendGrid's event webhook.
+///
+/// * `ctx`: The application context
+/// * `headers`: The headers of the request, which carry its signature
+/// * `body`: The raw body of the request, which the signature covers
+///
+/// Delivered, bounced, dropped, and opened events for onboarding emails are recorded against the
+/// export job and volunteer they were sent for. Hard bounces, unsubscribes, and spam reports for
+/// any email add the recipient to the suppression list. Every other event is acknowledged and
+/// ignored. Requests signed more than a few minutes ago are rejected, so they cannot be replayed.
+#[utoipa::path(
+    post,
+    path = "/sendgrid/events",
+    operation_id = "Receive SendGrid events",
+    responses(
+        (status = 204, description = "Successfully recorded the events"),
+        (status = 400, description = "The events could not be parsed"),
+        (status = 401, description = "The request is not signed by SendGrid, or is stale"),
+        (status = 503, description = "The webhook is not configured"),
+    ),
+)]
+pub async fn receive_sendgrid_events(
+    State(ctx): State<Arc<Services>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, AppError> {
+    let Some(verifier) = sendgrid_event_verifier()? else {
+        return Ok(api_response::error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "The SendGrid event webhook is not configured",
+        ));
+    };
+
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let (Some(signature), Some(timestamp)) = (header(SIGNATURE_HEADER), header(TIMESTAMP_HEADER))
+    else {
+        return Ok(api_response::error(StatusCode::UNAUTHORIZED, "Missing signature"));
+    };
+
+    if let Err(e) = verifier.verify(signature, timestamp, &body) {
+        log::warn!("Rejected SendGrid event webhook: {e}");
+        return Ok(api_response::error(StatusCode::UNAUTHORIZED, "Invalid signature"));
+    }
+
+    let events = match serde_json::from_slice::<Vec<Event>>(&body) {
+        Ok(events) => events,
+        Err(e) => return Ok(api_response::error(StatusCode::BAD_REQUEST, &e.to_string())),
+    };
+
+    // A batch can hold events for emails sent by several organizations
+    let mut events_by_org = BTreeMap::<Uuid, Vec<Event>>::new();
+    for event in events {
+        events_by_org.entry(org_from_event(&event)).or_default().push(event);
+    }
+
+    for (org_id, events) in events_by_org {
+        with_org(org_id, record_events(&ctx, events)).await?;
+    }
+
+    Ok(api_response::no_content())
+}
+
+/// Record the suppressions and deliveries in events for emails sent by the current organization.
+///
+/// * `ctx`: The application context
+/// * `events`: The events
+async fn record_events(ctx: &Services, events: Vec<Event>) -> Result<(), AppError> {
+    let suppressions = events.iter().filter_map(suppression_from_event).collect::<Vec<_>>();
+    if !suppressions.is_empty() {
+        log::info!("Suppressing {} addresses reported by SendGrid", suppressions.len());
+        ctx.storage_layer
+            .suppress_emails(suppressions, &mut ExecOptsBuilder::default().build()?)
+            .await?;
+    }
+
+    let deliveries = events.into_iter().filter_map(delivery_from_event).collect::<Vec<_>>();
+    log::info!("Recording {} onboarding email deliveries from SendGrid", deliveries.len());
+
+    ctx.storage_layer
+        .record_onboarding_email_deliveries(deliveries, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+//! Webhooks API.
+//!
+//! These endpoints are called by third party services rather than by users, so they are not
+//! guarded by a JWT. Each one verifies the signature of the request instead.
+
+use std::env;
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{routing, Router};
+use chrono::DateTime;
+use scipio_sendgrid::webhook::{Event, EventType, EventWebhookVerifier};
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use crate::app::state::Services;
+use crate::services::mail::{JOB_ID_ARG, ORG_ID_ARG, VOLUNTEER_ID_ARG};
+use crate::services::storage::emails::CreateEmailSuppression;
+use crate::services::storage::exports::RecordOnboardingEmailDelivery;
+use crate::services::storage::organizations::DEFAULT_ORG_ID;
+use crate::services::storage::types::{EmailDeliveryStatus, EmailSuppressionReason};
+
+mod controllers;
+#[cfg(test)]
+mod tests;
+
+/// The environment variable holding the public key SendGrid signs event webhooks with.
+pub const SENDGRID_WEBHOOK_PUBLIC_KEY_ENV_VAR: &str = "SENDGRID_WEBHOOK_PUBLIC_KEY";
+
+/// Documents the API for webhooks
+#[derive(OpenApi)]
+#[openapi(paths(controllers::receive_sendgrid_events))]
+pub struct WebhooksApi;
+
+/// Build the verifier for SendGrid event webhooks from the environment.
+///
+/// Returns `None` if no public key is configured, in which case events are not accepted.
+pub fn sendgrid_event_verifier() -> Result<Option<EventWebhookVerifier>> {
+    match env::var(SENDGRID_WEBHOOK_PUBLIC_KEY_ENV_VAR) {
+        Ok(key) => Ok(Some(EventWebhookVerifier::new(&key)?)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// The organization that sent the email a SendGrid event is about.
+///
+/// * `event`: The event
+///
+/// Emails sent without an organization ID were sent for the default organization.
+pub fn org_from_event(event: &Event) -> Uuid {
+    event
+        .custom_args
+        .get(ORG_ID_ARG)
+        .and_then(|v| v.as_str())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .unwrap_or(DEFAULT_ORG_ID)
+}
+
+/// Convert a SendGrid event into a delivery to record.
+///
+/// * `event`: The event
+///
+/// Returns `None` for events that are not about delivery (e.g. clicks), and for emails that were
+/// not sent by an export job, which carry no job or volunteer ID.
+pub fn delivery_from_event(event: Event) -> Option<RecordOnboardingEmailDelivery> {
+    let status = match event.event {
+        EventType::Delivered => EmailDeliveryStatus::Delivered,
+        EventType::Bounce => EmailDeliveryStatus::Bounced,
+        EventType::Dropped => EmailDeliveryStatus::Dropped,
+        EventType::Open => EmailDeliveryStatus::Opened,
+        _ => return None,
+    };
+
+    let uuid_arg = |name: &str| {
+        event.custom_args.get(name).and_then(|v| v.as_str()).and_then(|v| Uuid::parse_str(v).ok())
+    };
+
+    Some(RecordOnboardingEmailDelivery {
+        job_id: uuid_arg(JOB_ID_ARG)?,
+        volunteer_id: uuid_arg(VOLUNTEER_ID_ARG)?,
+        email: event.email,
+        status,
+        reason: event.reason,
+        message_id: event.sg_message_id,
+        event_at: DateTime::from_timestamp(event.timestamp, 0)?,
+    })
+}
+
+/// Convert a SendGrid event into an address to add to the suppression list.
+///
+/// * `event`: The event
+///
+/// Returns `None` unless the event is a hard bounce, an unsubscribe, or a spam report. Bounces
+/// SendGrid reports as blocked are temporary, so the address is not suppressed.
+pub fn suppression_from_event(event: &Event) -> Option<CreateEmailSuppression> {
+    let reason = match event.event {
+        EventType::Bounce if event.bounce_type.as_deref() != Some("blocked") => {
+            EmailSuppressionReason::Bounced
+        }
+        EventType::Unsubscribe | EventType::GroupUnsubscribe => {
+            EmailSuppressionReason::Unsubscribed
+        }
+        EventType::SpamReport => EmailSuppressionReason::SpamReport,
+        _ => return None,
+    };
+
+    Some(CreateEmailSuppression {
+        email: event.email.clone(),
+        reason,
+        provider: Some("sendgrid".to_owned()),
+        detail: event.reason.clone(),
+        suppressed_at: DateTime::from_timestamp(event.timestamp, 0)?,
+    })
+}
+
+/// Builds the webhooks API.
+///
+/// * `ctx`: The application context
+pub async fn build(ctx: Arc<Services>) -> Router<()> {
+    let receive_sendgrid_events = routing::post(controllers::receive_sendgrid_events);
+
+    Router::new().route("/sendgrid/events", receive_sendgrid_events).with_state(ctx.clone())
+}
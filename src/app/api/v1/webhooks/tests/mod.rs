@@ -0,0 +1,73 @@
+use rstest::rstest;
+use scipio_sendgrid::webhook::Event;
+use uuid::uuid;
+
+use crate::app::api::v1::webhooks::{delivery_from_event, org_from_event, suppression_from_event};
+use crate::services::storage::organizations::DEFAULT_ORG_ID;
+use crate::services::storage::types::{EmailDeliveryStatus, EmailSuppressionReason};
+
+fn event(event: &str, custom_args: &str) -> Event {
+    serde_json::from_str(&format!(
+        r#"{{"email":"mary@example.com","timestamp":1729000000,"event":"{event}",
+            "sg_message_id":"abc.123","reason":"550 mailbox unavailable"{custom_args}}}"#
+    ))
+    .unwrap()
+}
+
+const CUSTOM_ARGS: &str = r#","job_id":"413eed73-3c6f-456a-b9f0-ae72d136c742",
+    "volunteer_id":"1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67""#;
+
+#[rstest]
+#[case("delivered", EmailDeliveryStatus::Delivered)]
+#[case("bounce", EmailDeliveryStatus::Bounced)]
+#[case("dropped", EmailDeliveryStatus::Dropped)]
+#[case("open", EmailDeliveryStatus::Opened)]
+fn test_delivery_from_event(#[case] name: &str, #[case] status: EmailDeliveryStatus) {
+    let delivery = delivery_from_event(event(name, CUSTOM_ARGS)).unwrap();
+
+    assert_eq!(delivery.job_id, uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742"));
+    assert_eq!(delivery.volunteer_id, uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67"));
+    assert_eq!(delivery.status, status);
+    assert_eq!(delivery.message_id.as_deref(), Some("abc.123"));
+    assert_eq!(delivery.event_at.timestamp(), 1_729_000_000);
+}
+
+#[rstest]
+#[case("click", CUSTOM_ARGS)]
+#[case("processed", CUSTOM_ARGS)]
+#[case("delivered", "")]
+#[case("delivered", r#","job_id":"not-a-uuid","volunteer_id":"also-not-a-uuid""#)]
+fn test_ignore_event(#[case] name: &str, #[case] custom_args: &str) {
+    assert!(delivery_from_event(event(name, custom_args)).is_none());
+}
+
+#[rstest]
+#[case("bounce", "", Some(EmailSuppressionReason::Bounced))]
+#[case("bounce", r#","type":"bounce""#, Some(EmailSuppressionReason::Bounced))]
+#[case("bounce", r#","type":"blocked""#, None)]
+#[case("unsubscribe", "", Some(EmailSuppressionReason::Unsubscribed))]
+#[case("group_unsubscribe", "", Some(EmailSuppressionReason::Unsubscribed))]
+#[case("spamreport", "", Some(EmailSuppressionReason::SpamReport))]
+#[case("delivered", "", None)]
+#[case("dropped", "", None)]
+fn test_suppression_from_event(
+    #[case] name: &str,
+    #[case] custom_args: &str,
+    #[case] reason: Option<EmailSuppressionReason>,
+) {
+    let suppression = suppression_from_event(&event(name, custom_args));
+
+    assert_eq!(suppression.as_ref().map(|s| s.reason), reason);
+    if let Some(suppression) = suppression {
+        assert_eq!(suppression.email, "mary@example.com");
+        assert_eq!(suppression.detail.as_deref(), Some("550 mailbox unavailable"));
+    }
+}
+
+#[rstest]
+#[case(r#","org_id":"5b6c0d1e-3f4a-4b8c-9d2e-7f1a2b3c4d5e""#, uuid!("5b6c0d1e-3f4a-4b8c-9d2e-7f1a2b3c4d5e"))]
+#[case(CUSTOM_ARGS, DEFAULT_ORG_ID)]
+#[case(r#","org_id":"not-a-uuid""#, DEFAULT_ORG_ID)]
+fn test_org_from_event(#[case] custom_args: &str, #[case] org_id: uuid::Uuid) {
+    assert_eq!(org_from_event(&event("delivered", custom_args)), org_id);
+}
@@ -7,6 +7,9 @@ use sqlx::{Database, Postgres};
 use crate::services::airtable::AirtableService;
 use crate::services::auth::AuthenticatorService;
 use crate::services::mail::MailService;
+use crate::services::objects::ObjectStoreService;
+use crate::services::slack::SlackService;
+use crate::services::sms::SmsService;
 use crate::services::storage::StorageService;
 use crate::services::workspace::WorkspaceService;
 
@@ -18,6 +21,9 @@ pub struct Services<DB: Database = Postgres> {
     pub airtable: Arc<dyn AirtableService>,
     pub workspace: Arc<dyn WorkspaceService>,
     pub mail: Arc<dyn MailService>,
+    pub sms: Arc<dyn SmsService>,
+    pub slack: Arc<dyn SlackService>,
+    pub objects: Arc<dyn ObjectStoreService>,
 }
 
 // pub struct ServiceInfo {
@@ -32,6 +38,9 @@ pub struct ConfiguredServices<'a> {
     pub storage: &'a str,
     pub workspace: &'a str,
     pub mail: &'a str,
+    pub sms: &'a str,
+    pub slack: &'a str,
+    pub objects: &'a str,
 }
 
 #[derive(Debug, Serialize)]
@@ -49,6 +58,9 @@ impl Services {
                 storage: self.storage_layer.get_id(),
                 workspace: self.workspace.get_id(),
                 mail: self.mail.get_id(),
+                sms: self.sms.get_id(),
+                slack: self.slack.get_id(),
+                objects: self.objects.get_id(),
             },
         }
     }
@@ -15,12 +15,15 @@
 //! - An authentication provider (currently Auth0, although anything which implements
 //!   `Authenticator` will work).
 //! - A database (currently PostgreSQL, although anything which implements `StorageLayer` will work).
-//! - An email provider (currently Sendgrid, although anything which implements `EmailClient` will
-//!   suffice.
+//! - An email provider (currently Sendgrid, Mailgun, Amazon SES, or any SMTP relay, although
+//!   anything which implements `EmailClient` will suffice.
+//! - An SMS provider (currently Twilio, although anything which implements `SmsClient` will work).
+//!   This is only used when an export texts temporary passwords to volunteers.
 //! - A Workspace client. This is a custom service that interacts with the Google Workspace API,
 //!   however the underlying implementation of this service can be swapped out, as long as it
 //!   implements `WorkspaceClient`. The current implementation uses a service account and there may
-//!   be a better way to handle this in the future, but that's for you to find out.
+//!   be a better way to handle this in the future, but that's for you to find out. For local
+//!   development and tests, an in-memory emulator of Google Workspace can be used instead.
 //! - An Airtable client. This is a custom service that interacts with the Airtable API. This is
 //!   unlikely to change, unless Airtable dramatically changes their API. However, this service is
 //!   also swappable as long as it implements `AirtableClient`.
@@ -44,7 +47,8 @@ use anyhow::Result;
 use clap::Parser;
 use tokio::net::TcpListener;
 
-use crate::cli::Args;
+use crate::cli::{Args, Command};
+use crate::services::storage::seed::SeedOptions;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -56,21 +60,29 @@ async fn main() -> Result<()> {
     };
 
     let templates_dir = env::var("MAIL_TEMPLATES_DIR").unwrap_or_else(|_| "templates".to_owned());
-     log::info!("Loading templates from {}", templates_dir);
+    log::info!("Loading templates from {}", templates_dir);
 
     let args = Args::parse();
-    log::info!(
-        "MAIL RECIPIENT OVERRIDE: {}",
-        env::var("MAIL_RECIPIENT_OVERRIDE").unwrap_or_default()
-    );
+    match args.command.clone() {
+        Some(Command::Migrate { status }) => return args.run_migrations(status).await,
+        Some(Command::CreateOrg { name }) => return args.create_org(name).await,
+        Some(Command::RestoreBackup { key }) => return args.restore_backup(key).await,
+        Some(Command::Seed { volunteers, rng_seed, org_id }) => {
+            return args.seed(SeedOptions { volunteers, rng_seed }, org_id).await
+        }
+        None => {}
+    }
 
     let addr = format!("{}:{}", args.host, args.port);
 
     let services = args.init_services().await?;
 
-    services.storage_layer.migrate().await?;
-
-    log::info!("successfully ran database migrations");
+    if args.skip_migrations {
+        log::info!("skipping database migrations");
+    } else {
+        services.storage_layer.migrate().await?;
+        log::info!("successfully ran database migrations");
+    }
 
     log::info!("{:?}", services.get_info());
 
@@ -8,6 +8,7 @@ use jsonwebtoken::{DecodingKey, Validation};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use uuid::Uuid;
 
 use super::Authenticator;
 use crate::services::auth::{AuthData, UserData};
@@ -109,6 +110,9 @@ pub struct AccessTokenClaims {
     pub azp: String,
     // NOTE: Custom claim added by Auth0 rule
     pub permissions: Vec<String>,
+    // NOTE: Custom claim added by Auth0 rule for users of organizations other than the default one
+    #[serde(default)]
+    pub org_id: Option<Uuid>,
 }
 
 /// Auth0 authentication data.
@@ -116,11 +120,13 @@ pub struct AccessTokenClaims {
 /// * `email`: The email of the user
 /// * `token`: The user's JWT
 /// * `permissions`: The user's permissions
+/// * `org_id`: The ID of the organization the user belongs to, if not the default one
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Auth0AuthData {
     pub email: String,
     pub token: String,
     pub permissions: Vec<String>,
+    pub org_id: Option<Uuid>,
 }
 
 impl Auth0 {
@@ -196,6 +202,7 @@ impl Authenticator for Auth0 {
             token: token.to_owned(),
             permissions: token_claims.permissions,
             email: token_claims.email,
+            org_id: token_claims.org_id,
         }))
     }
 
@@ -8,8 +8,10 @@ use async_trait::async_trait;
 #[cfg(test)]
 use mockall::automock;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use super::Service;
+use crate::services::storage::organizations::DEFAULT_ORG_ID;
 
 /// User data returned by the authenticator.
 ///
@@ -42,6 +44,14 @@ impl AuthData {
             AuthData::Noop => bail!("noop"),
         }
     }
+
+    /// The organization the user acts for. Users without one act for the default organization.
+    pub fn org_id(&self) -> Uuid {
+        match self {
+            AuthData::Auth0(data) => data.org_id.unwrap_or(DEFAULT_ORG_ID),
+            AuthData::Noop => DEFAULT_ORG_ID,
+        }
+    }
 }
 
 /// The `Authenticator` trait defines the interface for authenticating users.
@@ -0,0 +1,311 @@
+//! An `EmailClient` that falls through an ordered list of providers.
+//!
+//! Each provider tracks its own health. A provider that fails several times in a row, or that
+//! rate limits us, is benched for a while so that later emails go straight to the next provider
+//! instead of waiting on one that is known to be struggling. If every provider is benched, they
+//! are all tried anyway, in order, rather than dropping the email.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Serialize;
+
+use super::{
+    DigestEmailParams, EmailClient, MailService, OffboardingEmailParams, OnboardingEmailParams,
+    SentEmail,
+};
+use crate::services::storage::emails::CreateEmailSuppression;
+use crate::services::Service;
+
+/// The number of consecutive failures after which a provider is benched.
+pub const FAILURES_BEFORE_BENCHED: u32 = 3;
+
+/// How long a provider is benched after failing repeatedly.
+pub const FAILURE_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// How long a provider is benched after rate limiting us.
+pub const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// The health of a single provider.
+///
+/// * `consecutive_failures`: The number of sends that failed since the last one that succeeded
+/// * `benched_until`: When the provider may be tried again, if it is benched
+#[derive(Debug, Default)]
+struct ProviderHealth {
+    consecutive_failures: u32,
+    benched_until: Option<Instant>,
+}
+
+/// A provider in the failover chain, along with its health and metrics.
+struct Provider {
+    client: Arc<dyn MailService>,
+    health: Mutex<ProviderHealth>,
+    sent: AtomicU64,
+    failed: AtomicU64,
+    rate_limited: AtomicU64,
+}
+
+/// A snapshot of a provider's health and metrics.
+///
+/// * `id`: The ID of the provider
+/// * `healthy`: Whether the provider is currently being tried first in its turn
+/// * `consecutive_failures`: The number of sends that failed since the last one that succeeded
+/// * `sent`: The number of emails the provider sent
+/// * `failed`: The number of sends that failed, including rate limited ones
+/// * `rate_limited`: The number of sends the provider rejected because of rate limiting
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderStats {
+    pub id: &'static str,
+    pub healthy: bool,
+    pub consecutive_failures: u32,
+    pub sent: u64,
+    pub failed: u64,
+    pub rate_limited: u64,
+}
+
+impl Provider {
+    fn is_benched(&self, now: Instant) -> bool {
+        let health = self.health.lock().expect("provider health lock poisoned");
+        health.benched_until.is_some_and(|until| until > now)
+    }
+
+    fn record_success(&self) {
+        self.sent.fetch_add(1, Ordering::Relaxed);
+        let mut health = self.health.lock().expect("provider health lock poisoned");
+        *health = ProviderHealth::default();
+    }
+
+    fn record_failure(&self, rate_limited: bool) {
+        self.failed.fetch_add(1, Ordering::Relaxed);
+        if rate_limited {
+            self.rate_limited.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let mut health = self.health.lock().expect("provider health lock poisoned");
+        health.consecutive_failures += 1;
+        if rate_limited {
+            health.benched_until = Some(Instant::now() + RATE_LIMIT_COOLDOWN);
+        } else if health.consecutive_failures >= FAILURES_BEFORE_BENCHED {
+            health.benched_until = Some(Instant::now() + FAILURE_COOLDOWN);
+        }
+    }
+
+    fn stats(&self) -> ProviderStats {
+        let health = self.health.lock().expect("provider health lock poisoned");
+        ProviderStats {
+            id: self.client.get_id(),
+            healthy: health.benched_until.map_or(true, |until| until <= Instant::now()),
+            consecutive_failures: health.consecutive_failures,
+            sent: self.sent.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+            rate_limited: self.rate_limited.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Whether an error means the provider rate limited us.
+///
+/// * `e`: The error returned by the provider
+fn is_rate_limited(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .and_then(reqwest::Error::status)
+            .is_some_and(|status| status == StatusCode::TOO_MANY_REQUESTS)
+    })
+}
+
+/// An email client that tries each of its providers in order until one sends the email.
+pub struct FailoverEmailClient {
+    providers: Vec<Provider>,
+}
+
+impl FailoverEmailClient {
+    /// Create a client.
+    ///
+    /// * `providers`: The providers to try, in order of preference
+    pub fn new(providers: Vec<Arc<dyn MailService>>) -> Self {
+        Self {
+            providers: providers
+                .into_iter()
+                .map(|client| Provider {
+                    client,
+                    health: Mutex::new(ProviderHealth::default()),
+                    sent: AtomicU64::new(0),
+                    failed: AtomicU64::new(0),
+                    rate_limited: AtomicU64::new(0),
+                })
+                .collect(),
+        }
+    }
+
+    /// A snapshot of the health and metrics of every provider, in order of preference.
+    pub fn stats(&self) -> Vec<ProviderStats> {
+        self.providers.iter().map(Provider::stats).collect()
+    }
+
+    /// Send a single email with each provider in turn until one sends it.
+    ///
+    /// * `kind`: What kind of email it is, for logs (e.g. `onboarding`)
+    /// * `recipient`: The recipient of the email, for logs
+    /// * `send`: Sends the email with a provider
+    async fn send_with_failover<F, Fut>(
+        &self,
+        kind: &str,
+        recipient: &str,
+        send: F,
+    ) -> Result<SentEmail>
+    where
+        F: Fn(Arc<dyn MailService>) -> Fut,
+        Fut: Future<Output = Result<SentEmail>>,
+    {
+        let now = Instant::now();
+        let (healthy, benched) =
+            self.providers.iter().partition::<Vec<&Provider>, _>(|p| !p.is_benched(now));
+
+        let mut errors = Vec::<String>::with_capacity(self.providers.len());
+        for provider in healthy.into_iter().chain(benched) {
+            let id = provider.client.get_id();
+            match send(provider.client.clone()).await {
+                Ok(sent) => {
+                    provider.record_success();
+                    return Ok(sent);
+                }
+                Err(e) => {
+                    let rate_limited = is_rate_limited(&e);
+                    provider.record_failure(rate_limited);
+                    log::warn!(
+                        "{id} failed to send {kind} email to {recipient}{}: {e}",
+                        if rate_limited { " (rate limited)" } else { "" }
+                    );
+                    errors.push(format!("{id}: {e}"));
+                }
+            }
+        }
+
+        log::error!(
+            "Every mail provider failed to send {kind} email to {recipient}. Provider stats: {}",
+            serde_json::to_string(&self.stats()).unwrap_or_default()
+        );
+        Err(anyhow!("Every mail provider failed to send the email: {}", errors.join("; ")))
+    }
+}
+
+#[async_trait]
+impl EmailClient for FailoverEmailClient {
+    async fn send_onboarding_email(&self, params: OnboardingEmailParams) -> Result<SentEmail> {
+        let recipient = params.email.clone();
+        self.send_with_failover("onboarding", &recipient, |client| {
+            let params = params.clone();
+            async move { client.send_onboarding_email(params).await }
+        })
+        .await
+    }
+
+    async fn send_offboarding_email(&self, params: OffboardingEmailParams) -> Result<SentEmail> {
+        let recipient = params.email.clone();
+        self.send_with_failover("offboarding", &recipient, |client| {
+            let params = params.clone();
+            async move { client.send_offboarding_email(params).await }
+        })
+        .await
+    }
+
+    async fn send_digest_email(&self, params: DigestEmailParams) -> Result<SentEmail> {
+        let recipient = params.email.clone();
+        self.send_with_failover("digest", &recipient, |client| {
+            let params = params.clone();
+            async move { client.send_digest_email(params).await }
+        })
+        .await
+    }
+
+    /// Sends a batch of onboarding emails, handing each provider the emails the providers before
+    /// it failed to send.
+    async fn send_onboarding_emails(
+        &self,
+        params: Vec<OnboardingEmailParams>,
+    ) -> Vec<Result<SentEmail>> {
+        let now = Instant::now();
+        let (healthy, benched) =
+            self.providers.iter().partition::<Vec<&Provider>, _>(|p| !p.is_benched(now));
+
+        let mut results = params.iter().map(|_| None).collect::<Vec<Option<Result<SentEmail>>>>();
+        let mut errors = params.iter().map(|_| Vec::new()).collect::<Vec<Vec<String>>>();
+        let mut pending = (0..params.len()).collect::<Vec<usize>>();
+        for provider in healthy.into_iter().chain(benched) {
+            if pending.is_empty() {
+                break;
+            }
+
+            let id = provider.client.get_id();
+            let batch = pending.iter().map(|&i| params[i].clone()).collect();
+            let sent = provider.client.send_onboarding_emails(batch).await;
+
+            let mut still_pending = Vec::new();
+            for (i, result) in pending.into_iter().zip(sent) {
+                match result {
+                    Ok(sent) => {
+                        provider.record_success();
+                        results[i] = Some(Ok(sent));
+                    }
+                    Err(e) => {
+                        let rate_limited = is_rate_limited(&e);
+                        provider.record_failure(rate_limited);
+                        log::warn!(
+                            "{id} failed to send onboarding email to {}{}: {e}",
+                            params[i].email,
+                            if rate_limited { " (rate limited)" } else { "" }
+                        );
+                        errors[i].push(format!("{id}: {e}"));
+                        still_pending.push(i);
+                    }
+                }
+            }
+            pending = still_pending;
+        }
+
+        if !pending.is_empty() {
+            log::error!(
+                "Every mail provider failed to send {} onboarding emails. Provider stats: {}",
+                pending.len(),
+                serde_json::to_string(&self.stats()).unwrap_or_default()
+            );
+        }
+
+        results
+            .into_iter()
+            .zip(errors)
+            .map(|(result, errors)| {
+                result.unwrap_or_else(|| {
+                    Err(anyhow!(
+                        "Every mail provider failed to send the email: {}",
+                        errors.join("; ")
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Fetches the suppression lists of every provider, since an address suppressed by one would
+    /// likely bounce from the others too.
+    async fn fetch_suppressions(&self) -> Result<Vec<CreateEmailSuppression>> {
+        let mut suppressions = Vec::new();
+        for provider in &self.providers {
+            suppressions.extend(provider.client.fetch_suppressions().await?);
+        }
+        Ok(suppressions)
+    }
+}
+
+impl Service for FailoverEmailClient {
+    fn get_id(&self) -> &'static str {
+        "failover"
+    }
+}
@@ -0,0 +1,137 @@
+//! An `EmailClient` backed by Mailgun's Messages API.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::DateTime;
+use reqwest::Client;
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::policies::ExponentialBackoff;
+use reqwest_retry::RetryTransientMiddleware;
+
+use super::{
+    DigestEmailParams, EmailClient, OffboardingEmailParams, OnboardingEmailParams, OutgoingEmail,
+    SenderIdentity, SentEmail,
+};
+use crate::services::Service;
+
+/// The base URL of Mailgun's API for domains in the US region.
+pub const MAILGUN_US_API_URL: &str = "https://api.mailgun.net";
+
+/// The base URL of Mailgun's API for domains in the EU region.
+pub const MAILGUN_EU_API_URL: &str = "https://api.eu.mailgun.net";
+
+/// A Mailgun client.
+///
+/// * `http`: The HTTP client, which retries transient failures
+/// * `api_url`: The base URL of the API, which depends on the region of the sending domain
+/// * `api_key`: The Mailgun API key
+/// * `domain`: The Mailgun sending domain
+/// * `sender`: Who emails are sent from
+pub struct MailgunEmailClient {
+    http: ClientWithMiddleware,
+    api_url: String,
+    api_key: String,
+    domain: String,
+    sender: SenderIdentity,
+}
+
+impl MailgunEmailClient {
+    pub fn new(
+        api_url: &str,
+        api_key: &str,
+        domain: &str,
+        sender: SenderIdentity,
+        max_retries: u32,
+    ) -> Result<Self> {
+        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(max_retries);
+
+        let http = ClientBuilder::new(Client::builder().build()?)
+            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+            .build();
+
+        Ok(Self {
+            http,
+            api_url: api_url.trim_end_matches('/').to_owned(),
+            api_key: api_key.to_owned(),
+            domain: domain.to_owned(),
+            sender,
+        })
+    }
+
+    /// Send a rendered email.
+    ///
+    /// * `email`: The email
+    async fn send(&self, email: OutgoingEmail) -> Result<SentEmail> {
+        // Attachments must be sent as a multipart form, which cannot be retried
+        if !email.attachments.is_empty() {
+            bail!("The Mailgun backend does not support attachments");
+        }
+
+        let mut form = vec![
+            ("from", self.sender.from_mailbox()),
+            ("to", email.to()),
+            ("subject", email.subject),
+            ("text", email.text),
+            ("html", email.html),
+        ];
+
+        if let Some(reply_to) = &self.sender.reply_to {
+            form.push(("h:Reply-To", reply_to.clone()));
+        }
+        if let Some(bcc) = &self.sender.bcc {
+            form.push(("bcc", bcc.clone()));
+        }
+
+        // Mailgun expects an RFC 2822 date. It only schedules up to a few days ahead (depending
+        // on the plan) and rejects anything further out.
+        if let Some(send_at) = email.send_at {
+            let delivery_time = DateTime::from_timestamp(send_at as i64, 0)
+                .with_context(|| format!("{send_at} is not a valid send time"))?;
+            form.push(("o:deliverytime", delivery_time.to_rfc2822()));
+        }
+
+        let res = self
+            .http
+            .post(format!("{}/v3/{}/messages", self.api_url, self.domain))
+            .basic_auth("api", Some(&self.api_key))
+            .form(&form)
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            bail!("Mailgun rejected the message with status {status}: {text}");
+        }
+
+        // The ID is only used for the audit log, so a response without one is not an error
+        let message_id = res
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|body| body.get("id").and_then(|id| id.as_str()).map(str::to_owned));
+
+        Ok(SentEmail { provider: self.get_id().to_owned(), message_id })
+    }
+}
+
+#[async_trait]
+impl EmailClient for MailgunEmailClient {
+    async fn send_onboarding_email(&self, params: OnboardingEmailParams) -> Result<SentEmail> {
+        self.send(OutgoingEmail::try_from(&params)?).await
+    }
+
+    async fn send_offboarding_email(&self, params: OffboardingEmailParams) -> Result<SentEmail> {
+        self.send(OutgoingEmail::try_from(&params)?).await
+    }
+
+    async fn send_digest_email(&self, params: DigestEmailParams) -> Result<SentEmail> {
+        self.send(OutgoingEmail::try_from(&params)?).await
+    }
+}
+
+impl Service for MailgunEmailClient {
+    fn get_id(&self) -> &'static str {
+        "mailgun"
+    }
+}
@@ -1,21 +1,41 @@
-//! This module contains traits for sending emails, as well as one concrete implementation (SendGrid).
+//! This module contains traits for sending emails, as well as concrete implementations for SendGrid,
+//! Mailgun, Amazon SES, and SMTP.
 
+pub mod failover;
+pub mod mailgun;
 pub mod noop;
+pub mod preflight;
 pub mod sendgrid;
+pub mod ses;
+pub mod smtp;
+pub mod suppression;
 #[cfg(test)]
 mod tests;
+pub mod throttle;
 
 use std::env;
+use std::fmt::{self, Debug};
+use std::fs;
+use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{bail, Context as _, Result};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use derive_builder::Builder;
 use lazy_static::lazy_static;
+#[cfg(test)]
+use mockall::automock;
 use scipio_sendgrid::entities::{
-    AddressBuilder, Mail, MailBuilder, MailContentBuilder, MailContentMime, PersonalizationBuilder,
+    AddressBuilder, Attachment, Mail, MailBuilder, MailContentBuilder, MailContentMime,
+    Personalization, PersonalizationBuilder,
 };
+use serde_json::json;
 use tera::{Context, Tera};
+use uuid::Uuid;
 
+use super::storage::emails::CreateEmailSuppression;
+use super::storage::entities::{EmailTemplate, ExportDigest, Job};
+use super::storage::organizations::{current_org, DEFAULT_ORG_ID};
 use super::Service;
 
 lazy_static! {
@@ -40,9 +60,24 @@ lazy_static! {
 /// * `last_name`: The recipient's last name
 /// * `email`: The recipient's email address
 /// * `workspace_email`: The recipient's workspace email address
-/// * `temporary_password`: The recipient's temporary password for their workspace email address
+/// * `temporary_password`: The recipient's temporary password for their workspace email address.
+///   If `None`, the password is delivered some other way (e.g. by text message) and the email only
+///   contains the workspace email address.
 /// * `send_at`: The time to send the email. If `None`, the email will be sent immediately.
 ///   Otherwise, it will be interpreted as a UNIX timestamp in seconds.
+/// * `template`: A template edited by staff to use instead of the compiled-in one. If `None`, the
+///   email is rendered from the compiled-in template for `kind` (e.g.
+///   `email/onboard.{locale}.html`) with the subject for the locale.
+/// * `locale`: The locale to render the email in, e.g. `es` or `es-MX`. If there is no translation
+///   for the locale, the email falls back to the locale's language and then to `DEFAULT_LOCALE`.
+/// * `attachments`: Files to attach to the email, such as the program's welcome packet
+/// * `job_id`: The export job the email is sent for, if any. Providers that report delivery events
+///   echo it back, so events can be matched to the volunteer.
+/// * `volunteer_id`: The volunteer the email is sent to, if known
+/// * `kind`: Which onboarding email to send. Defaults to the welcome email with the recipient's
+///   credentials.
+/// * `onboarding_session_link`: A link to the onboarding session the recipient was invited to, if
+///   any
 #[derive(Debug, Clone, Builder)]
 pub struct OnboardingEmailParams {
     #[builder(setter(into))]
@@ -53,60 +88,842 @@ pub struct OnboardingEmailParams {
     pub email: String,
     #[builder(setter(into))]
     pub workspace_email: String,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub temporary_password: Option<String>,
+    #[builder(setter(into), default = "None")]
+    pub send_at: Option<u64>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub template: Option<CustomTemplate>,
+    #[builder(setter(into), default = "DEFAULT_LOCALE.to_owned()")]
+    pub locale: String,
+    #[builder(default = "Vec::new()")]
+    pub attachments: Vec<EmailAttachment>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub job_id: Option<Uuid>,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub volunteer_id: Option<Uuid>,
+    #[builder(default)]
+    pub kind: OnboardingEmailKind,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub onboarding_session_link: Option<String>,
+}
+
+/// The emails sent to volunteers while they are onboarded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OnboardingEmailKind {
+    /// The email with the volunteer's workspace credentials
+    #[default]
+    Welcome,
+    /// A reminder to sign in to a workspace account that has not been used yet
+    ActivationReminder,
+}
+
+impl OnboardingEmailKind {
+    /// Every kind of onboarding email.
+    pub const ALL: [Self; 2] = [Self::Welcome, Self::ActivationReminder];
+
+    /// The name of the compiled-in template for the email, without its locale or extension.
+    pub fn template(self) -> &'static str {
+        match self {
+            Self::Welcome => "email/onboard",
+            Self::ActivationReminder => "email/reminder",
+        }
+    }
+
+    /// The subject of the email in each locale with a translation.
+    fn subjects(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Welcome => ONBOARDING_SUBJECTS,
+            Self::ActivationReminder => REMINDER_SUBJECTS,
+        }
+    }
+
+    /// The subject of the email in a locale.
+    ///
+    /// * `locale`: The requested locale
+    pub fn subject(self, locale: &str) -> &'static str {
+        localized_subject(self.subjects(), locale)
+    }
+}
+
+/// Data needed to send an offboarding email, which tells a volunteer that their workspace account
+/// is about to be suspended and how to export their data before then.
+///
+/// * `first_name`: The recipient's first name
+/// * `last_name`: The recipient's last name
+/// * `email`: The recipient's email address. This should be their personal address, since the
+///   workspace account is the one being suspended.
+/// * `workspace_email`: The recipient's workspace email address
+/// * `suspend_at`: When the workspace account will be suspended
+/// * `send_at`: The time to send the email. If `None`, the email will be sent immediately.
+///   Otherwise, it will be interpreted as a UNIX timestamp in seconds.
+/// * `locale`: The locale to render the email in, with the same fallbacks as onboarding emails
+/// * `volunteer_id`: The volunteer the email is sent to, if known
+#[derive(Debug, Clone, Builder)]
+pub struct OffboardingEmailParams {
+    #[builder(setter(into))]
+    pub first_name: String,
+    #[builder(setter(into))]
+    pub last_name: String,
+    #[builder(setter(into))]
+    pub email: String,
     #[builder(setter(into))]
-    pub temporary_password: String,
+    pub workspace_email: String,
+    pub suspend_at: DateTime<Utc>,
     #[builder(setter(into), default = "None")]
     pub send_at: Option<u64>,
+    #[builder(setter(into), default = "DEFAULT_LOCALE.to_owned()")]
+    pub locale: String,
+    #[builder(setter(into, strip_option), default = "None")]
+    pub volunteer_id: Option<Uuid>,
 }
 
-impl TryFrom<OnboardingEmailParams> for Mail {
-    type Error = anyhow::Error;
+/// A file attached to an email.
+///
+/// * `filename`: The name of the file as the recipient sees it
+/// * `mime_type`: The MIME type of the file (e.g. `application/pdf`)
+/// * `content`: The raw contents of the file
+#[derive(Clone, PartialEq)]
+pub struct EmailAttachment {
+    pub filename: String,
+    pub mime_type: String,
+    pub content: Vec<u8>,
+}
+
+impl EmailAttachment {
+    /// Read an attachment from a file. Its MIME type is guessed from its extension.
+    ///
+    /// * `path`: The path of the file
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let content =
+            fs::read(path).with_context(|| format!("Could not read {}", path.display()))?;
+        let filename = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .with_context(|| format!("{} is not a file", path.display()))?
+            .to_owned();
+
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+        let mime_type = match extension.to_lowercase().as_str() {
+            "pdf" => "application/pdf",
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "txt" => "text/plain",
+            "html" => "text/html",
+            "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            _ => "application/octet-stream",
+        };
+
+        Ok(Self { filename, mime_type: mime_type.to_owned(), content })
+    }
+}
+
+// The contents of an attachment would drown out everything else in logs
+impl Debug for EmailAttachment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmailAttachment")
+            .field("filename", &self.filename)
+            .field("mime_type", &self.mime_type)
+            .field("content", &format_args!("<{} bytes>", self.content.len()))
+            .finish()
+    }
+}
+
+impl From<&EmailAttachment> for Attachment {
+    fn from(value: &EmailAttachment) -> Self {
+        Attachment::from_bytes(&value.filename, &value.mime_type, &value.content)
+    }
+}
+
+/// An email template edited by staff, which takes the place of a compiled-in template.
+///
+/// * `subject`: The subject line of the email
+/// * `body`: The Tera source of the email body. It has access to the same variables as the
+///   compiled-in template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomTemplate {
+    pub subject: String,
+    pub body: String,
+}
+
+impl From<EmailTemplate> for CustomTemplate {
+    fn from(value: EmailTemplate) -> Self {
+        Self { subject: value.subject, body: value.body }
+    }
+}
+
+/// The address emails are sent from, unless another is configured.
+pub const ONBOARDING_FROM_EMAIL: &str = "onboarding@developforgood.org";
+
+/// The display name emails are sent from, unless another is configured.
+pub const ONBOARDING_FROM_NAME: &str = "Develop for Good";
+
+/// Who emails are sent from, and where replies and archived copies go.
+///
+/// * `from_email`: The address emails are sent from
+/// * `from_name`: The display name emails are sent from
+/// * `reply_to`: The address replies go to, if not `from_email`
+/// * `bcc`: An address that is blind copied on every email, to keep an archive of what was sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SenderIdentity {
+    pub from_email: String,
+    pub from_name: String,
+    pub reply_to: Option<String>,
+    pub bcc: Option<String>,
+}
+
+impl Default for SenderIdentity {
+    fn default() -> Self {
+        Self {
+            from_email: ONBOARDING_FROM_EMAIL.to_owned(),
+            from_name: ONBOARDING_FROM_NAME.to_owned(),
+            reply_to: None,
+            bcc: None,
+        }
+    }
+}
+
+impl SenderIdentity {
+    /// Create a sender identity, checking that every address is valid.
+    ///
+    /// * `from_email`: The address emails are sent from
+    /// * `from_name`: The display name emails are sent from
+    /// * `reply_to`: The address replies go to, if not `from_email`
+    /// * `bcc`: An address to blind copy on every email, if any
+    pub fn new(
+        from_email: &str,
+        from_name: &str,
+        reply_to: Option<&str>,
+        bcc: Option<&str>,
+    ) -> Result<Self> {
+        let from_name = from_name.trim();
+        if from_name.is_empty() || from_name.contains(|c: char| c.is_control() || c == '<') {
+            bail!("{from_name:?} is not a valid sender name");
+        }
+
+        let address = |label: &str, email: &str| -> Result<String> {
+            let email = email.trim();
+            email
+                .parse::<lettre::Address>()
+                .with_context(|| format!("{email:?} is not a valid {label} address"))?;
+            Ok(email.to_owned())
+        };
+
+        Ok(Self {
+            from_email: address("from", from_email)?,
+            from_name: from_name.to_owned(),
+            reply_to: reply_to.map(|r| address("reply-to", r)).transpose()?,
+            bcc: bcc.map(|b| address("BCC", b)).transpose()?,
+        })
+    }
+
+    /// The sender as a mailbox (e.g. `Develop for Good <onboarding@developforgood.org>`).
+    pub fn from_mailbox(&self) -> String {
+        format!("{} <{}>", self.from_name, self.from_email)
+    }
+}
+
+/// The locale emails are rendered in when there is no translation for the requested one.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// The subject of onboarding emails in each locale with a translation.
+const ONBOARDING_SUBJECTS: &[(&str, &str)] = &[
+    ("en", "Develop for Good: Onboarding instructions"),
+    ("es", "Develop for Good: Instrucciones de incorporación"),
+];
+
+/// The subject of activation reminder emails in each locale with a translation.
+const REMINDER_SUBJECTS: &[(&str, &str)] = &[
+    ("en", "Develop for Good: Reminder to activate your account"),
+    ("es", "Develop for Good: Recordatorio para activar tu cuenta"),
+];
+
+/// The name of the compiled-in offboarding template, without its locale or extension.
+pub const OFFBOARDING_EMAIL_TEMPLATE: &str = "email/offboard";
+
+/// The name of the compiled-in admin digest template, without its locale or extension.
+pub const DIGEST_EMAIL_TEMPLATE: &str = "email/digest";
+
+/// The subject of offboarding emails in each locale with a translation.
+const OFFBOARDING_SUBJECTS: &[(&str, &str)] = &[
+    ("en", "Develop for Good: Your account is being suspended"),
+    ("es", "Develop for Good: Tu cuenta será suspendida"),
+];
+
+/// Pick the subject of an email for a locale, with the same fallbacks as its template.
+///
+/// * `subjects`: The subject in each locale with a translation
+/// * `locale`: The requested locale
+fn localized_subject(subjects: &[(&str, &'static str)], locale: &str) -> &'static str {
+    locale_fallbacks(locale)
+        .iter()
+        .find_map(|l| subjects.iter().find(|(locale, _)| locale == l))
+        .map(|(_, subject)| *subject)
+        .unwrap_or_default()
+}
+
+/// The locales to try, in order, when rendering an email for `locale`: the locale itself, its
+/// language (`es` for `es-MX`), and finally `DEFAULT_LOCALE`.
+///
+/// * `locale`: The requested locale
+fn locale_fallbacks(locale: &str) -> Vec<String> {
+    let locale = locale.trim().replace('_', "-").to_lowercase();
+    let language = locale.split('-').next().unwrap_or_default().to_owned();
+
+    let mut fallbacks = Vec::new();
+    for l in [locale, language, DEFAULT_LOCALE.to_owned()] {
+        if !l.is_empty() && !fallbacks.contains(&l) {
+            fallbacks.push(l);
+        }
+    }
+    fallbacks
+}
+
+/// Find the compiled-in template to render for a locale.
+///
+/// * `name`: The name of the template without its locale or extension (e.g. `email/onboard`)
+/// * `locale`: The requested locale
+/// * `extension`: The extension of the template (`html` or `txt`)
+pub fn localized_template(name: &str, locale: &str, extension: &str) -> String {
+    let names = TEMPLATES.get_template_names().collect::<Vec<_>>();
+    locale_fallbacks(locale)
+        .into_iter()
+        .map(|l| format!("{name}.{l}.{extension}"))
+        .find(|template| names.contains(&template.as_str()))
+        .unwrap_or_else(|| format!("{name}.{DEFAULT_LOCALE}.{extension}"))
+}
+
+/// An email rendered from a compiled-in template.
+///
+/// * `template`: The name of the template the email was rendered from
+/// * `subject`: The subject line emails sent from the template use, if the template is sent as an
+///   email on its own (rather than included in other templates)
+/// * `html`: The rendered HTML body
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedEmail {
+    pub template: String,
+    pub subject: Option<String>,
+    pub html: String,
+}
+
+/// Find a compiled-in HTML template by name.
+///
+/// * `name`: The full name of the template (e.g. `email/onboard.es.html`), or its name without
+///   its locale or extension (e.g. `email/onboard`) to pick the translation for `locale`
+/// * `locale`: The locale to pick a translation for
+///
+/// Returns `None` if there is no such template.
+pub fn find_template(name: &str, locale: &str) -> Option<String> {
+    let names = TEMPLATES.get_template_names().collect::<Vec<_>>();
+    if names.contains(&name) {
+        return Some(name.to_owned());
+    }
+
+    let localized = localized_template(name, locale, "html");
+    names.contains(&localized.as_str()).then_some(localized)
+}
+
+/// Render a compiled-in template with the given variables, without sending it.
+///
+/// * `template`: The full name of the template, as returned by `find_template`
+/// * `locale`: The locale the email is rendered in. It is available to the template as `locale`
+///   unless `context` sets it, and is replaced by the template's own locale if it is a
+///   translation.
+/// * `context`: The variables to render the template with
+pub fn render_template(
+    template: &str,
+    locale: &str,
+    context: &serde_json::Map<String, serde_json::Value>,
+) -> Result<RenderedEmail> {
+    // Translations are named `{name}.{locale}.html`, and a fallback translation may have been
+    // picked for the requested locale
+    let mut parts = template.rsplitn(3, '.');
+    let (base, locale) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(_), Some(l), Some(base)) => (base, l),
+        _ => (template, locale),
+    };
+
+    let mut ctx = Context::from_serialize(context)?;
+    if !context.contains_key("locale") {
+        ctx.insert("locale", locale);
+    }
+
+    let html = TEMPLATES.render(template, &ctx)?;
+    let subject = match base {
+        OFFBOARDING_EMAIL_TEMPLATE => Some(localized_subject(OFFBOARDING_SUBJECTS, locale)),
+        _ => OnboardingEmailKind::ALL
+            .into_iter()
+            .find(|kind| kind.template() == base)
+            .map(|kind| kind.subject(locale)),
+    }
+    .map(str::to_owned);
+
+    Ok(RenderedEmail { template: template.to_owned(), subject, html })
+}
+
+/// The name a template edited by staff is stored under for a locale.
+///
+/// * `name`: The name of the template (e.g. `onboard`)
+/// * `locale`: The locale of the template
+///
+/// Templates in the default locale are stored under their plain name, and translations under the
+/// name and the language of the locale (e.g. `onboard.es`, which is used for `es-MX` too).
+pub fn localized_template_name(name: &str, locale: &str) -> String {
+    let locale = locale.trim().to_lowercase();
+    let language = locale.split(['-', '_']).next().unwrap_or_default();
+    if language.is_empty() || language == DEFAULT_LOCALE {
+        name.to_owned()
+    } else {
+        format!("{name}.{language}")
+    }
+}
+
+/// The custom argument SendGrid echoes the export job ID back in.
+pub const JOB_ID_ARG: &str = "job_id";
+
+/// The custom argument SendGrid echoes the volunteer ID back in.
+pub const VOLUNTEER_ID_ARG: &str = "volunteer_id";
+
+/// The custom argument SendGrid echoes the ID of the sending organization back in.
+pub const ORG_ID_ARG: &str = "org_id";
+
+/// The name onboarding templates edited by staff are stored under.
+pub const ONBOARDING_TEMPLATE: &str = "onboard";
+
+/// The name a template edited by staff is registered under while it is rendered.
+const CUSTOM_TEMPLATE_NAME: &str = "custom";
+
+impl OnboardingEmailParams {
+    /// The variables onboarding templates are rendered with.
+    fn context(&self) -> Context {
+        let mut context = Context::new();
+
+        context.insert("name", &self.first_name);
+        context.insert("email", &self.workspace_email);
+        context.insert("temporaryPassword", &self.temporary_password);
+        context.insert("onboardingSessionLink", &self.onboarding_session_link);
+        context.insert("locale", &self.locale);
+
+        context
+    }
+
+    /// Render the HTML body of the onboarding email.
+    ///
+    /// Fails without rendering if the template prints a variable that is missing or empty, rather
+    /// than leaving a blank placeholder in the email.
+    pub fn render(&self) -> Result<String> {
+        let context = self.context();
+
+        match &self.template {
+            Some(template) => {
+                let mut tera = Tera::default();
+                tera.add_raw_template(CUSTOM_TEMPLATE_NAME, &template.body)?;
+                tera.autoescape_on(vec![CUSTOM_TEMPLATE_NAME]);
+                preflight::render_checked(&tera, CUSTOM_TEMPLATE_NAME, &context)
+            }
+            None => {
+                let name = localized_template(self.kind.template(), &self.locale, "html");
+                preflight::render_checked(&TEMPLATES, &name, &context)
+            }
+        }
+    }
+
+    /// Render the plain text body of the onboarding email, which is sent alongside the HTML body
+    /// for clients that cannot (or prefer not to) display HTML.
+    ///
+    /// Templates edited by staff only have an HTML body, so their plain text body is generated
+    /// from the rendered HTML.
+    pub fn render_text(&self) -> Result<String> {
+        match &self.template {
+            Some(_) => Ok(html_to_text(&self.render()?)),
+            None => {
+                let name = localized_template(self.kind.template(), &self.locale, "txt");
+                preflight::render_checked(&TEMPLATES, &name, &self.context())
+            }
+        }
+    }
+
+    /// The subject line of the onboarding email.
+    pub fn subject(&self) -> &str {
+        if let Some(template) = &self.template {
+            return &template.subject;
+        }
+
+        self.kind.subject(&self.locale)
+    }
+
+    /// The SendGrid personalization addressing the onboarding email to its recipient.
+    pub fn personalization(&self) -> Result<Personalization> {
+        // SendGrid echoes custom arguments back in its event webhook
+        let custom_args = match (self.job_id, self.volunteer_id) {
+            (Some(job_id), Some(volunteer_id)) => Some(json!({
+                JOB_ID_ARG: job_id.to_string(),
+                VOLUNTEER_ID_ARG: volunteer_id.to_string(),
+                ORG_ID_ARG: current_org().unwrap_or(DEFAULT_ORG_ID).to_string(),
+            })),
+            _ => None,
+        };
 
-    fn try_from(value: OnboardingEmailParams) -> std::result::Result<Self, Self::Error> {
         let personalization = PersonalizationBuilder::default()
             .to(vec![AddressBuilder::default()
-                .email(value.email)
-                .name(format!("{} {}", value.first_name, value.last_name))
+                .email(self.email.clone())
+                .name(format!("{} {}", self.first_name, self.last_name))
                 .build()?])
+            .custom_args(custom_args)
             .build()?;
 
-        let from = AddressBuilder::default()
-            .email("onboarding@developforgood.org")
-            .name("Develop for Good".to_owned())
-            .build()?;
+        Ok(personalization)
+    }
+}
 
-        let subject = "Develop for Good: Onboarding instructions".to_owned();
+impl OffboardingEmailParams {
+    /// The variables the offboarding template is rendered with.
+    fn context(&self) -> Context {
         let mut context = Context::new();
 
-        context.insert("name", &value.first_name);
-        context.insert("email", &value.workspace_email);
-        context.insert("temporaryPassword", &value.temporary_password);
+        context.insert("name", &self.first_name);
+        context.insert("email", &self.workspace_email);
+        context.insert("suspendAt", &self.suspend_at.format("%Y-%m-%d").to_string());
+        context.insert("locale", &self.locale);
 
-        let template = TEMPLATES.render("email/onboard.html", &context)?;
+        context
+    }
+
+    /// Render the HTML body of the offboarding email.
+    pub fn render(&self) -> Result<String> {
+        let name = localized_template(OFFBOARDING_EMAIL_TEMPLATE, &self.locale, "html");
+        Ok(TEMPLATES.render(&name, &self.context())?)
+    }
+
+    /// Render the plain text body of the offboarding email.
+    pub fn render_text(&self) -> Result<String> {
+        let name = localized_template(OFFBOARDING_EMAIL_TEMPLATE, &self.locale, "txt");
+        Ok(TEMPLATES.render(&name, &self.context())?)
+    }
+
+    /// The subject line of the offboarding email.
+    pub fn subject(&self) -> &str {
+        localized_subject(OFFBOARDING_SUBJECTS, &self.locale)
+    }
 
-        let content = MailContentBuilder::default()
-            .value(template)
-            .mime_type(MailContentMime::Html)
+    /// The SendGrid personalization addressing the offboarding email to its recipient.
+    pub fn personalization(&self) -> Result<Personalization> {
+        let personalization = PersonalizationBuilder::default()
+            .to(vec![AddressBuilder::default()
+                .email(self.email.clone())
+                .name(format!("{} {}", self.first_name, self.last_name))
+                .build()?])
             .build()?;
 
-        let mail = MailBuilder::default()
-            .from(from)
-            .personalizations(vec![personalization])
-            .subject(subject)
-            .content(vec![content])
-            .send_at(value.send_at)
+        Ok(personalization)
+    }
+}
+
+/// Data needed to send an admin digest, which summarizes the exports to Google Workspace over a
+/// period of time.
+///
+/// * `email`: The admin's email address
+/// * `period_start`: The start of the period the digest covers
+/// * `period_end`: The end of the period the digest covers
+/// * `digest`: The summary of the exports over the period
+/// * `failed_jobs`: The export jobs that ended with an error over the period
+#[derive(Debug, Clone, Builder)]
+pub struct DigestEmailParams {
+    #[builder(setter(into))]
+    pub email: String,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub digest: ExportDigest,
+    #[builder(default = "Vec::new()")]
+    pub failed_jobs: Vec<Job>,
+}
+
+impl DigestEmailParams {
+    /// The variables the digest template is rendered with.
+    fn context(&self) -> Context {
+        let failed_jobs = self
+            .failed_jobs
+            .iter()
+            .map(|job| {
+                json!({
+                    "id": job.id,
+                    "label": job.label,
+                    "error": job.details.get("error").and_then(|e| e.as_str()),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut context = Context::new();
+        context.insert("periodStart", &self.period_start.format("%Y-%m-%d %H:%M UTC").to_string());
+        context.insert("periodEnd", &self.period_end.format("%Y-%m-%d %H:%M UTC").to_string());
+        context.insert("digest", &self.digest);
+        context.insert("failedJobs", &failed_jobs);
+        context.insert("locale", DEFAULT_LOCALE);
+
+        context
+    }
+
+    /// Render the HTML body of the digest.
+    pub fn render(&self) -> Result<String> {
+        let name = localized_template(DIGEST_EMAIL_TEMPLATE, DEFAULT_LOCALE, "html");
+        Ok(TEMPLATES.render(&name, &self.context())?)
+    }
+
+    /// Render the plain text body of the digest.
+    pub fn render_text(&self) -> Result<String> {
+        let name = localized_template(DIGEST_EMAIL_TEMPLATE, DEFAULT_LOCALE, "txt");
+        Ok(TEMPLATES.render(&name, &self.context())?)
+    }
+
+    /// The subject line of the digest, which names the period it covers so digests do not thread
+    /// together.
+    pub fn subject(&self) -> String {
+        format!(
+            "Scipio: Export digest for {} to {}",
+            self.period_start.format("%Y-%m-%d"),
+            self.period_end.format("%Y-%m-%d")
+        )
+    }
+}
+
+impl TryFrom<DigestEmailParams> for Mail {
+    type Error = anyhow::Error;
+
+    fn try_from(value: DigestEmailParams) -> std::result::Result<Self, Self::Error> {
+        let personalization = PersonalizationBuilder::default()
+            .to(vec![AddressBuilder::default().email(value.email.clone()).build()?])
             .build()?;
 
-        Ok(mail)
+        onboarding_mail(
+            vec![personalization],
+            value.subject(),
+            value.render()?,
+            value.render_text()?,
+            &[],
+            None,
+        )
+    }
+}
+
+/// The width plain text email bodies are wrapped at.
+const TEXT_WIDTH: usize = 78;
+
+/// Convert an HTML email body to plain text.
+///
+/// * `html`: The HTML to convert
+///
+/// Links are kept as footnotes, and lines are wrapped at 78 characters as recommended by RFC 5322.
+fn html_to_text(html: &str) -> String {
+    html2text::from_read(html.as_bytes(), TEXT_WIDTH)
+}
+
+/// Build a SendGrid onboarding email.
+///
+/// * `personalizations`: The recipients of the email
+/// * `subject`: The subject line of the email
+/// * `html`: The HTML body of the email
+/// * `text`: The plain text body of the email
+/// * `attachments`: Files to attach to the email
+/// * `send_at`: The time to send the email, as a UNIX timestamp in seconds
+pub fn onboarding_mail(
+    personalizations: Vec<Personalization>,
+    subject: String,
+    html: String,
+    text: String,
+    attachments: &[EmailAttachment],
+    send_at: Option<u64>,
+) -> Result<Mail> {
+    let from = AddressBuilder::default()
+        .email(ONBOARDING_FROM_EMAIL)
+        .name(ONBOARDING_FROM_NAME.to_owned())
+        .build()?;
+
+    // SendGrid requires the plain text content to come before the HTML content
+    let content = vec![
+        MailContentBuilder::default().value(text).mime_type(MailContentMime::Plain).build()?,
+        MailContentBuilder::default().value(html).mime_type(MailContentMime::Html).build()?,
+    ];
+
+    let attachments = if attachments.is_empty() {
+        None
+    } else {
+        Some(attachments.iter().map(Attachment::from).collect())
+    };
+
+    let mail = MailBuilder::default()
+        .from(from)
+        .personalizations(personalizations)
+        .subject(subject)
+        .content(content)
+        .attachments(attachments)
+        .send_at(send_at)
+        .build()?;
+
+    Ok(mail)
+}
+
+impl TryFrom<OnboardingEmailParams> for Mail {
+    type Error = anyhow::Error;
+
+    fn try_from(value: OnboardingEmailParams) -> std::result::Result<Self, Self::Error> {
+        onboarding_mail(
+            vec![value.personalization()?],
+            value.subject().to_owned(),
+            value.render()?,
+            value.render_text()?,
+            &value.attachments,
+            value.send_at,
+        )
+    }
+}
+
+impl TryFrom<OffboardingEmailParams> for Mail {
+    type Error = anyhow::Error;
+
+    fn try_from(value: OffboardingEmailParams) -> std::result::Result<Self, Self::Error> {
+        onboarding_mail(
+            vec![value.personalization()?],
+            value.subject().to_owned(),
+            value.render()?,
+            value.render_text()?,
+            &[],
+            value.send_at,
+        )
+    }
+}
+
+/// An email rendered for a single recipient, for providers that send one email per request.
+///
+/// * `recipient_name`: The recipient's full name, if the email is addressed to a person
+/// * `recipient_email`: The recipient's email address
+/// * `subject`: The subject line of the email
+/// * `html`: The HTML body of the email
+/// * `text`: The plain text body of the email
+/// * `attachments`: Files to attach to the email
+/// * `send_at`: The time to send the email, as a UNIX timestamp in seconds
+#[derive(Debug, Clone)]
+pub struct OutgoingEmail {
+    pub recipient_name: Option<String>,
+    pub recipient_email: String,
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+    pub attachments: Vec<EmailAttachment>,
+    pub send_at: Option<u64>,
+}
+
+impl OutgoingEmail {
+    /// The recipient as a mailbox (e.g. `Mary Zhu <mary@example.com>`).
+    pub fn to(&self) -> String {
+        match &self.recipient_name {
+            Some(name) => format!("{name} <{}>", self.recipient_email),
+            None => self.recipient_email.clone(),
+        }
     }
 }
 
+impl TryFrom<&OnboardingEmailParams> for OutgoingEmail {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &OnboardingEmailParams) -> std::result::Result<Self, Self::Error> {
+        Ok(Self {
+            recipient_name: Some(format!("{} {}", value.first_name, value.last_name)),
+            recipient_email: value.email.clone(),
+            subject: value.subject().to_owned(),
+            html: value.render()?,
+            text: value.render_text()?,
+            attachments: value.attachments.clone(),
+            send_at: value.send_at,
+        })
+    }
+}
+
+impl TryFrom<&OffboardingEmailParams> for OutgoingEmail {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &OffboardingEmailParams) -> std::result::Result<Self, Self::Error> {
+        Ok(Self {
+            recipient_name: Some(format!("{} {}", value.first_name, value.last_name)),
+            recipient_email: value.email.clone(),
+            subject: value.subject().to_owned(),
+            html: value.render()?,
+            text: value.render_text()?,
+            attachments: Vec::new(),
+            send_at: value.send_at,
+        })
+    }
+}
+
+impl TryFrom<&DigestEmailParams> for OutgoingEmail {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &DigestEmailParams) -> std::result::Result<Self, Self::Error> {
+        Ok(Self {
+            recipient_name: None,
+            recipient_email: value.email.clone(),
+            subject: value.subject(),
+            html: value.render()?,
+            text: value.render_text()?,
+            attachments: Vec::new(),
+            send_at: None,
+        })
+    }
+}
+
+/// An email a provider accepted for delivery.
+///
+/// * `provider`: The ID of the provider that accepted the email
+/// * `message_id`: The provider's ID for the email, if it returned one
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SentEmail {
+    pub provider: String,
+    pub message_id: Option<String>,
+}
+
+#[cfg_attr(test, automock)]
 #[async_trait]
 pub trait EmailClient: Send + Sync {
     /// Sends an onboarding email.
     ///
     /// * `params`: Data needed to send the onboarding email
-    async fn send_onboarding_email(&self, params: OnboardingEmailParams) -> Result<()>;
+    async fn send_onboarding_email(&self, params: OnboardingEmailParams) -> Result<SentEmail>;
+
+    /// Sends a batch of onboarding emails.
+    ///
+    /// * `params`: Data needed to send each onboarding email
+    ///
+    /// Returns the result of sending each email, in the same order as `params`. By default the
+    /// emails are sent one at a time. Providers that can send many emails in one request override
+    /// this.
+    async fn send_onboarding_emails(
+        &self,
+        params: Vec<OnboardingEmailParams>,
+    ) -> Vec<Result<SentEmail>> {
+        let mut results = Vec::with_capacity(params.len());
+        for p in params {
+            results.push(self.send_onboarding_email(p).await);
+        }
+        results
+    }
+
+    /// Sends an offboarding email.
+    ///
+    /// * `params`: Data needed to send the offboarding email
+    async fn send_offboarding_email(&self, params: OffboardingEmailParams) -> Result<SentEmail>;
+
+    /// Sends an admin digest.
+    ///
+    /// * `params`: Data needed to send the digest
+    async fn send_digest_email(&self, params: DigestEmailParams) -> Result<SentEmail>;
+
+    /// Fetches the addresses the provider refuses to send email to, such as addresses that hard
+    /// bounced or unsubscribed.
+    ///
+    /// By default a provider reports none. Providers that keep their own suppression list override
+    /// this.
+    async fn fetch_suppressions(&self) -> Result<Vec<CreateEmailSuppression>> {
+        Ok(Vec::new())
+    }
 }
 
 pub trait MailService: EmailClient + Service + Send + Sync {}
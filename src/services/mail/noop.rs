@@ -1,15 +1,25 @@
 use anyhow::Result;
 use async_trait::async_trait;
 
-use super::{EmailClient, OnboardingEmailParams};
+use super::{
+    DigestEmailParams, EmailClient, OffboardingEmailParams, OnboardingEmailParams, SentEmail,
+};
 use crate::services::Service;
 
 pub struct NoopEmailClient;
 
 #[async_trait]
 impl EmailClient for NoopEmailClient {
-    async fn send_onboarding_email(&self, _params: OnboardingEmailParams) -> Result<()> {
-        Ok(())
+    async fn send_onboarding_email(&self, _params: OnboardingEmailParams) -> Result<SentEmail> {
+        Ok(SentEmail { provider: self.get_id().to_owned(), message_id: None })
+    }
+
+    async fn send_offboarding_email(&self, _params: OffboardingEmailParams) -> Result<SentEmail> {
+        Ok(SentEmail { provider: self.get_id().to_owned(), message_id: None })
+    }
+
+    async fn send_digest_email(&self, _params: DigestEmailParams) -> Result<SentEmail> {
+        Ok(SentEmail { provider: self.get_id().to_owned(), message_id: None })
     }
 }
 
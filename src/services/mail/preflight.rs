@@ -0,0 +1,199 @@
+//! Checks that an email's template has every variable it needs before the email is rendered.
+//!
+//! Tera only fails on variables that are missing from the context entirely, so an email rendered
+//! from params with an empty name or address would go out with blank placeholders. Instead, the
+//! variables a template prints are found by walking its syntax tree, and the email is refused if
+//! any of them are missing or empty.
+//!
+//! A variable is not required if the template checks it first (e.g. `{% if temporaryPassword %}`),
+//! gives it a default (e.g. `{{ locale | default(value="en") }}`), or sets it itself (e.g. a loop
+//! variable).
+
+use std::collections::{BTreeSet, HashSet};
+
+use anyhow::Result;
+use serde_json::Value;
+use tera::ast::{Expr, ExprVal, Node};
+use tera::{Context, Tera};
+use thiserror::Error;
+
+/// The error returned when an email is not rendered because its template needs variables that are
+/// missing or empty.
+///
+/// * `template`: The name of the template
+/// * `variables`: The missing or empty variables
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("the {template} template needs {} but they are missing or empty", variables.join(", "))]
+pub struct MissingTemplateVariablesError {
+    pub template: String,
+    pub variables: Vec<String>,
+}
+
+/// Find the variables a template prints without checking them first, including those printed by
+/// the templates it extends and includes.
+///
+/// * `tera`: The Tera instance the template is registered with
+/// * `template`: The name of the template
+pub fn required_variables(tera: &Tera, template: &str) -> Result<BTreeSet<String>> {
+    let mut walker = Walker { tera, required: BTreeSet::new(), visited: HashSet::new() };
+    walker.walk_template(template)?;
+    Ok(walker.required)
+}
+
+/// Check that the context has a value for every variable a template requires.
+///
+/// * `tera`: The Tera instance the template is registered with
+/// * `template`: The name of the template
+/// * `context`: The variables the template is about to be rendered with
+///
+/// Fails with `MissingTemplateVariablesError` if a required variable is missing, null, or an empty
+/// string.
+pub fn check_context(tera: &Tera, template: &str, context: &Context) -> Result<()> {
+    let variables = required_variables(tera, template)?
+        .into_iter()
+        .filter(|name| match context.get(name) {
+            None | Some(Value::Null) => true,
+            Some(Value::String(s)) => s.trim().is_empty(),
+            Some(_) => false,
+        })
+        .collect::<Vec<_>>();
+
+    if !variables.is_empty() {
+        return Err(
+            MissingTemplateVariablesError { template: template.to_owned(), variables }.into()
+        );
+    }
+
+    Ok(())
+}
+
+/// Check a template's context, then render it.
+///
+/// * `tera`: The Tera instance the template is registered with
+/// * `template`: The name of the template
+/// * `context`: The variables to render the template with
+pub fn render_checked(tera: &Tera, template: &str, context: &Context) -> Result<String> {
+    check_context(tera, template, context)?;
+    Ok(tera.render(template, context)?)
+}
+
+/// Walks templates, collecting the variables they require.
+///
+/// * `tera`: The Tera instance the templates are registered with
+/// * `required`: The variables found so far
+/// * `visited`: The templates already walked, so a template included twice is only walked once
+struct Walker<'a> {
+    tera: &'a Tera,
+    required: BTreeSet<String>,
+    visited: HashSet<String>,
+}
+
+impl Walker<'_> {
+    fn walk_template(&mut self, name: &str) -> Result<()> {
+        if !self.visited.insert(name.to_owned()) {
+            return Ok(());
+        }
+
+        let template = self.tera.get_template(name)?;
+        self.walk_nodes(&template.ast, BTreeSet::new())
+    }
+
+    /// Walk a list of nodes.
+    ///
+    /// * `nodes`: The nodes
+    /// * `optional`: The variables that are checked or set by the enclosing nodes
+    fn walk_nodes(&mut self, nodes: &[Node], mut optional: BTreeSet<String>) -> Result<()> {
+        for node in nodes {
+            match node {
+                Node::VariableBlock(_, expr) => self.require(expr, &optional),
+                Node::Set(_, set) => {
+                    optional.insert(set.key.clone());
+                }
+                Node::Block(_, block, _) => self.walk_nodes(&block.body, optional.clone())?,
+                Node::FilterSection(_, section, _) => {
+                    self.walk_nodes(&section.body, optional.clone())?
+                }
+                Node::Forloop(_, forloop, _) => {
+                    let mut scope = optional.clone();
+                    scope.insert(forloop.value.clone());
+                    scope.extend(forloop.key.clone());
+                    self.walk_nodes(&forloop.body, scope)?;
+                    if let Some(body) = &forloop.empty_body {
+                        self.walk_nodes(body, optional.clone())?;
+                    }
+                }
+                Node::If(conditional, _) => {
+                    // Every branch after a condition is only reached once it has been checked
+                    let mut scope = optional.clone();
+                    for (_, condition, body) in &conditional.conditions {
+                        identifiers(condition, &mut scope);
+                        self.walk_nodes(body, scope.clone())?;
+                    }
+                    if let Some((_, body)) = &conditional.otherwise {
+                        self.walk_nodes(body, scope)?;
+                    }
+                }
+                Node::Extends(_, parent) => self.walk_template(parent)?,
+                Node::Include(_, names, ignore_missing) => {
+                    for name in names {
+                        match self.walk_template(name) {
+                            Err(_) if *ignore_missing => {}
+                            result => result?,
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Require the variable an expression prints, unless it is optional.
+    ///
+    /// * `expr`: The printed expression
+    /// * `optional`: The variables that are checked or set by the enclosing nodes
+    fn require(&mut self, expr: &Expr, optional: &BTreeSet<String>) {
+        if expr.filters.iter().any(|f| f.name == "default") {
+            return;
+        }
+
+        if let ExprVal::Ident(ident) = &expr.val {
+            let root = root_identifier(ident);
+            if !optional.contains(root) {
+                self.required.insert(root.to_owned());
+            }
+        }
+    }
+}
+
+/// Collect the variables an expression checks (e.g. the condition of an `if`).
+///
+/// * `expr`: The expression
+/// * `found`: The variables found so far
+fn identifiers(expr: &Expr, found: &mut BTreeSet<String>) {
+    match &expr.val {
+        ExprVal::Ident(ident) => {
+            found.insert(root_identifier(ident).to_owned());
+        }
+        ExprVal::Test(test) => {
+            found.insert(root_identifier(&test.ident).to_owned());
+        }
+        ExprVal::Logic(logic) => {
+            identifiers(&logic.lhs, found);
+            identifiers(&logic.rhs, found);
+        }
+        ExprVal::In(membership) => {
+            identifiers(&membership.lhs, found);
+            identifiers(&membership.rhs, found);
+        }
+        _ => {}
+    }
+}
+
+/// The top level variable an identifier refers to (e.g. `digest` for `digest.jobsRun`).
+///
+/// * `ident`: The identifier
+fn root_identifier(ident: &str) -> &str {
+    ident.split(['.', '[']).next().unwrap_or(ident)
+}
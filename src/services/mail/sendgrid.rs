@@ -1,21 +1,326 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use scipio_sendgrid::entities::Mail;
+use chrono::DateTime;
+use scipio_sendgrid::entities::{AddressBuilder, Mail};
+use scipio_sendgrid::suppressions::SuppressionList;
 use scipio_sendgrid::Sendgrid;
+use serde_json::{Map, Value};
 
-use super::{EmailClient, OnboardingEmailParams};
+use super::{
+    onboarding_mail, DigestEmailParams, EmailAttachment, EmailClient, OffboardingEmailParams,
+    OnboardingEmailParams, SenderIdentity, SentEmail,
+};
+use crate::services::storage::emails::CreateEmailSuppression;
+use crate::services::storage::types::EmailSuppressionReason;
 use crate::services::Service;
 
+/// The most personalizations SendGrid accepts in a single request.
+pub const MAX_PERSONALIZATIONS: usize = 1000;
+
+/// The tag SendGrid replaces with a recipient's value for a template variable.
+///
+/// * `kind`: The body the tag appears in (`html` or `text`), since values are escaped differently
+///   in each
+/// * `variable`: The template variable
+fn tag(kind: &str, variable: &str) -> String {
+    format!("%{kind}_{variable}%")
+}
+
+/// Replace the recipient's details in an onboarding email with substitution tags.
+///
+/// * `params`: The onboarding email
+/// * `kind`: The body the tags will appear in (`html` or `text`)
+///
+/// The temporary password is only tagged if the recipient has one, so templates that check for it
+/// render the same way.
+fn with_tags(params: &OnboardingEmailParams, kind: &str) -> OnboardingEmailParams {
+    let mut tagged = params.clone();
+    tagged.first_name = tag(kind, "name");
+    tagged.workspace_email = tag(kind, "email");
+    tagged.temporary_password =
+        params.temporary_password.as_ref().map(|_| tag(kind, "temporaryPassword"));
+    tagged
+}
+
+/// The substitutions that turn a tagged onboarding email back into the recipient's, as
+/// `(tag, value)` pairs.
+///
+/// * `params`: The onboarding email
+pub(super) fn substitutions(params: &OnboardingEmailParams) -> Vec<(String, String)> {
+    let values = [
+        ("name", Some(&params.first_name)),
+        ("email", Some(&params.workspace_email)),
+        ("temporaryPassword", params.temporary_password.as_ref()),
+    ];
+
+    let mut substitutions = Vec::new();
+    for (variable, value) in values {
+        if let Some(value) = value {
+            substitutions.push((tag("html", variable), tera::escape_html(value)));
+            substitutions.push((tag("text", variable), value.clone()));
+        }
+    }
+    substitutions
+}
+
+/// Apply substitutions to content the way SendGrid does.
+///
+/// * `content`: The tagged content
+/// * `substitutions`: The `(tag, value)` pairs to substitute
+pub(super) fn substitute(content: &str, substitutions: &[(String, String)]) -> String {
+    substitutions
+        .iter()
+        .fold(content.to_owned(), |content, (tag, value)| content.replace(tag, value))
+}
+
+/// An onboarding email that can be sent in a batch.
+///
+/// * `index`: The position of the email in the batch send
+/// * `params`: The onboarding email
+/// * `substitutions`: The substitutions that turn the tagged content into the recipient's email
+struct BatchedEmail {
+    index: usize,
+    params: OnboardingEmailParams,
+    substitutions: Vec<(String, String)>,
+}
+
+/// Onboarding emails whose content is identical once their recipients' details are replaced with
+/// substitution tags, so they can be sent in one request.
+///
+/// * `subject`: The subject line of the emails
+/// * `html`: The tagged HTML body of the emails
+/// * `text`: The tagged plain text body of the emails
+/// * `attachments`: Files attached to the emails
+/// * `send_at`: The time to send the emails
+/// * `emails`: The emails in the batch
+struct Batch {
+    subject: String,
+    html: String,
+    text: String,
+    attachments: Vec<EmailAttachment>,
+    send_at: Option<u64>,
+    emails: Vec<BatchedEmail>,
+}
+
+impl Batch {
+    /// Whether an email with this content belongs in the batch.
+    fn matches(&self, params: &OnboardingEmailParams, html: &str, text: &str) -> bool {
+        self.subject == params.subject()
+            && self.html == html
+            && self.text == text
+            && self.send_at == params.send_at
+            && self.attachments == params.attachments
+    }
+
+    /// Build a request sending some of the batch's emails.
+    ///
+    /// * `emails`: The emails to send
+    fn mail(&self, emails: &[BatchedEmail]) -> Result<Mail> {
+        let personalizations = emails
+            .iter()
+            .map(|e| {
+                let mut personalization = e.params.personalization()?;
+                let substitutions = e
+                    .substitutions
+                    .iter()
+                    .map(|(tag, value)| (tag.clone(), Value::String(value.clone())))
+                    .collect::<Map<String, Value>>();
+                personalization.substitutions = Some(Value::Object(substitutions));
+                Ok(personalization)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        onboarding_mail(
+            personalizations,
+            self.subject.clone(),
+            self.html.clone(),
+            self.text.clone(),
+            &self.attachments,
+            self.send_at,
+        )
+    }
+}
+
+/// Render an onboarding email with its recipient's details replaced by substitution tags.
+///
+/// * `params`: The onboarding email
+///
+/// Returns the tagged HTML and plain text bodies, or `None` if the email cannot be sent in a batch:
+/// it does not render, or its template transforms the recipient's details (e.g. with a filter), so
+/// substituting them back in would not reproduce the email.
+pub(super) fn tagged_bodies(params: &OnboardingEmailParams) -> Option<(String, String)> {
+    let substitutions = substitutions(params);
+    let html = with_tags(params, "html").render().ok()?;
+    let text = with_tags(params, "text").render_text().ok()?;
+
+    let reproduced = substitute(&html, &substitutions) == params.render().ok()?
+        && substitute(&text, &substitutions) == params.render_text().ok()?;
+    reproduced.then_some((html, text))
+}
+
+/// A SendGrid client that sends email as a configured sender.
+pub struct SendgridEmailClient {
+    client: Sendgrid,
+    sender: SenderIdentity,
+}
+
+impl SendgridEmailClient {
+    /// Create a client.
+    ///
+    /// * `client`: The SendGrid API client
+    /// * `sender`: Who emails are sent from
+    pub fn new(client: Sendgrid, sender: SenderIdentity) -> Self {
+        Self { client, sender }
+    }
+
+    /// Address an email from the configured sender and send it.
+    ///
+    /// * `mail`: The email
+    ///
+    /// Returns SendGrid's ID for the email, if it returned one.
+    async fn send(&self, mut mail: Mail) -> Result<Option<String>> {
+        mail.from = AddressBuilder::default()
+            .email(self.sender.from_email.clone())
+            .name(self.sender.from_name.clone())
+            .build()?;
+        mail.reply_to = match &self.sender.reply_to {
+            Some(reply_to) => Some(AddressBuilder::default().email(reply_to.clone()).build()?),
+            None => None,
+        };
+
+        // Blind copies are addressed per personalization, so the archive gets every recipient's
+        // copy of a batch
+        if let Some(bcc) = &self.sender.bcc {
+            let address = AddressBuilder::default().email(bcc.clone()).build()?;
+            for personalization in &mut mail.personalizations {
+                personalization.bcc = Some(vec![address.clone()]);
+            }
+        }
+
+        self.client.send_mail(mail).await
+    }
+}
+
 #[async_trait]
-impl EmailClient for Sendgrid {
-    async fn send_onboarding_email(&self, params: OnboardingEmailParams) -> Result<()> {
+impl EmailClient for SendgridEmailClient {
+    async fn send_onboarding_email(&self, params: OnboardingEmailParams) -> Result<SentEmail> {
         let mail = Mail::try_from(params)?;
-        self.send_mail(mail).await?;
-        Ok(())
+        let message_id = self.send(mail).await?;
+        Ok(SentEmail { provider: self.get_id().to_owned(), message_id })
+    }
+
+    /// Sends a batch of onboarding emails, up to `MAX_PERSONALIZATIONS` per request.
+    ///
+    /// Emails are grouped by their content with the recipients' details replaced by substitution
+    /// tags, which SendGrid fills in for each recipient. Emails that cannot be tagged, or have no
+    /// other email to share a request with, are sent one at a time.
+    async fn send_onboarding_emails(
+        &self,
+        params: Vec<OnboardingEmailParams>,
+    ) -> Vec<Result<SentEmail>> {
+        let mut batches = Vec::<Batch>::new();
+        let mut singles = Vec::<(usize, OnboardingEmailParams)>::new();
+        for (index, p) in params.into_iter().enumerate() {
+            let Some((html, text)) = tagged_bodies(&p) else {
+                singles.push((index, p));
+                continue;
+            };
+
+            let substitutions = substitutions(&p);
+            match batches.iter_mut().find(|b| b.matches(&p, &html, &text)) {
+                Some(batch) => batch.emails.push(BatchedEmail { index, params: p, substitutions }),
+                None => batches.push(Batch {
+                    subject: p.subject().to_owned(),
+                    html,
+                    text,
+                    attachments: p.attachments.clone(),
+                    send_at: p.send_at,
+                    emails: vec![BatchedEmail { index, params: p, substitutions }],
+                }),
+            }
+        }
+
+        let mut results = Vec::<(usize, Result<SentEmail>)>::new();
+        for batch in &mut batches {
+            if batch.emails.len() == 1 {
+                let email = batch.emails.remove(0);
+                singles.push((email.index, email.params));
+                continue;
+            }
+
+            for chunk in batch.emails.chunks(MAX_PERSONALIZATIONS) {
+                let sent = match batch.mail(chunk) {
+                    Ok(mail) => self.send(mail).await,
+                    Err(e) => Err(e),
+                };
+
+                match sent {
+                    Ok(message_id) => results.extend(chunk.iter().map(|e| {
+                        let sent = SentEmail {
+                            provider: self.get_id().to_owned(),
+                            message_id: message_id.clone(),
+                        };
+                        (e.index, Ok(sent))
+                    })),
+                    Err(e) => {
+                        // The original error goes to the first email, so callers can still tell
+                        // what kind of failure it was
+                        let message = format!("{e:#}");
+                        let mut error = Some(e);
+                        for email in chunk {
+                            let e = error.take().unwrap_or_else(|| anyhow!("{message}"));
+                            results.push((email.index, Err(e)));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (index, p) in singles {
+            results.push((index, self.send_onboarding_email(p).await));
+        }
+
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    async fn send_offboarding_email(&self, params: OffboardingEmailParams) -> Result<SentEmail> {
+        let mail = Mail::try_from(params)?;
+        let message_id = self.send(mail).await?;
+        Ok(SentEmail { provider: self.get_id().to_owned(), message_id })
+    }
+
+    async fn send_digest_email(&self, params: DigestEmailParams) -> Result<SentEmail> {
+        let mail = Mail::try_from(params)?;
+        let message_id = self.send(mail).await?;
+        Ok(SentEmail { provider: self.get_id().to_owned(), message_id })
+    }
+
+    /// Fetches SendGrid's bounce, unsubscribe, and spam report lists.
+    async fn fetch_suppressions(&self) -> Result<Vec<CreateEmailSuppression>> {
+        let lists = [
+            (SuppressionList::Bounces, EmailSuppressionReason::Bounced),
+            (SuppressionList::Unsubscribes, EmailSuppressionReason::Unsubscribed),
+            (SuppressionList::SpamReports, EmailSuppressionReason::SpamReport),
+        ];
+
+        let mut suppressions = Vec::new();
+        for (list, reason) in lists {
+            for s in self.client.fetch_suppressions(list, None).await? {
+                suppressions.push(CreateEmailSuppression {
+                    email: s.email,
+                    reason,
+                    provider: Some(self.get_id().to_owned()),
+                    detail: s.reason,
+                    suppressed_at: DateTime::from_timestamp(s.created, 0).unwrap_or_default(),
+                });
+            }
+        }
+        Ok(suppressions)
     }
 }
 
-impl Service for Sendgrid {
+impl Service for SendgridEmailClient {
     fn get_id(&self) -> &'static str {
         "sendgrid"
     }
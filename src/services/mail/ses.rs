@@ -0,0 +1,113 @@
+//! An `EmailClient` backed by Amazon SES, for deployments that already run in AWS.
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_sesv2::types::{Body, Content, Destination, EmailContent, Message};
+use aws_sdk_sesv2::Client;
+
+use super::{
+    DigestEmailParams, EmailClient, OffboardingEmailParams, OnboardingEmailParams, OutgoingEmail,
+    SenderIdentity, SentEmail,
+};
+use crate::services::Service;
+
+/// An Amazon SES client.
+///
+/// Credentials are resolved through the standard AWS provider chain (environment variables, the
+/// shared config files, or the instance/task role), so no keys need to be passed to Scipio.
+///
+/// * `client`: The SES client
+/// * `sender`: Who emails are sent from
+pub struct SesEmailClient {
+    client: Client,
+    sender: SenderIdentity,
+}
+
+impl SesEmailClient {
+    /// Create a client.
+    ///
+    /// * `region`: The AWS region to send from. If `None`, the region is resolved through the
+    ///   standard AWS provider chain.
+    /// * `sender`: Who emails are sent from
+    pub async fn new(region: Option<String>, sender: SenderIdentity) -> Self {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+        if let Some(region) = region {
+            loader = loader.region(Region::new(region));
+        }
+        let config = loader.load().await;
+
+        Self { client: Client::new(&config), sender }
+    }
+
+    /// Send a rendered email.
+    ///
+    /// * `email`: The email
+    async fn send(&self, email: OutgoingEmail) -> Result<SentEmail> {
+        if email.send_at.is_some() {
+            bail!("SES does not support scheduling emails");
+        }
+
+        if !email.attachments.is_empty() {
+            bail!("The SES backend does not support attachments");
+        }
+
+        let message = Message::builder()
+            .subject(utf8_content(email.subject)?)
+            .body(
+                Body::builder()
+                    .text(utf8_content(email.text)?)
+                    .html(utf8_content(email.html)?)
+                    .build(),
+            )
+            .build()?;
+
+        let destination = Destination::builder()
+            .to_addresses(email.to())
+            .set_bcc_addresses(self.sender.bcc.clone().map(|bcc| vec![bcc]))
+            .build();
+
+        let output = self
+            .client
+            .send_email()
+            .from_email_address(self.sender.from_mailbox())
+            .set_reply_to_addresses(self.sender.reply_to.clone().map(|reply_to| vec![reply_to]))
+            .destination(destination)
+            .content(EmailContent::builder().simple(message).build())
+            .send()
+            .await?;
+
+        Ok(SentEmail {
+            provider: self.get_id().to_owned(),
+            message_id: output.message_id().map(str::to_owned),
+        })
+    }
+}
+
+/// Build UTF-8 message content for SES.
+///
+/// * `data`: The content
+fn utf8_content(data: String) -> Result<Content> {
+    Ok(Content::builder().data(data).charset("UTF-8").build()?)
+}
+
+#[async_trait]
+impl EmailClient for SesEmailClient {
+    async fn send_onboarding_email(&self, params: OnboardingEmailParams) -> Result<SentEmail> {
+        self.send(OutgoingEmail::try_from(&params)?).await
+    }
+
+    async fn send_offboarding_email(&self, params: OffboardingEmailParams) -> Result<SentEmail> {
+        self.send(OutgoingEmail::try_from(&params)?).await
+    }
+
+    async fn send_digest_email(&self, params: DigestEmailParams) -> Result<SentEmail> {
+        self.send(OutgoingEmail::try_from(&params)?).await
+    }
+}
+
+impl Service for SesEmailClient {
+    fn get_id(&self) -> &'static str {
+        "ses"
+    }
+}
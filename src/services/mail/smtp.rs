@@ -0,0 +1,134 @@
+//! An `EmailClient` that sends mail through any SMTP relay.
+//!
+//! This is useful in development (pointed at a local catcher such as Mailpit, which
+//! `compose.yaml` runs on port 1025) and for organizations that run their own relay.
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use clap::ValueEnum;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment as LettreAttachment, Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::Serialize;
+
+use super::{
+    DigestEmailParams, EmailClient, OffboardingEmailParams, OnboardingEmailParams, OutgoingEmail,
+    SenderIdentity, SentEmail,
+};
+use crate::services::Service;
+
+/// How to secure the connection to the SMTP relay.
+#[derive(ValueEnum, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SmtpTls {
+    /// Send in plain text. Only use this with a relay on a trusted network (e.g. localhost).
+    None,
+    /// Connect in plain text and upgrade with STARTTLS (usually port 587)
+    StartTls,
+    /// Connect over TLS (usually port 465)
+    Tls,
+}
+
+/// An SMTP client.
+///
+/// * `transport`: The connection to the SMTP relay
+/// * `sender`: Who emails are sent from
+pub struct SmtpEmailClient {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    sender: SenderIdentity,
+}
+
+impl SmtpEmailClient {
+    /// Create a client.
+    ///
+    /// * `host`: The host of the SMTP relay
+    /// * `port`: The port of the SMTP relay. If `None`, the default port for `tls` is used.
+    /// * `tls`: How to secure the connection
+    /// * `credentials`: The username and password to authenticate with, if the relay requires them
+    /// * `sender`: Who emails are sent from
+    pub fn new(
+        host: &str,
+        port: Option<u16>,
+        tls: SmtpTls,
+        credentials: Option<(String, String)>,
+        sender: SenderIdentity,
+    ) -> Result<Self> {
+        let mut builder = match tls {
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+            SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+        };
+
+        if let Some(port) = port {
+            builder = builder.port(port);
+        }
+        if let Some((username, password)) = credentials {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        Ok(Self { transport: builder.build(), sender })
+    }
+
+    /// Send a rendered email.
+    ///
+    /// * `email`: The email
+    async fn send(&self, email: OutgoingEmail) -> Result<SentEmail> {
+        if email.send_at.is_some() {
+            bail!("SMTP does not support scheduling emails");
+        }
+
+        let from =
+            Mailbox::new(Some(self.sender.from_name.clone()), self.sender.from_email.parse()?);
+        let to = Mailbox::new(email.recipient_name, email.recipient_email.parse()?);
+
+        // Attachments go alongside the alternative bodies in a mixed multipart
+        let body = MultiPart::alternative_plain_html(email.text, email.html);
+        let body = email.attachments.iter().try_fold(
+            MultiPart::mixed().multipart(body),
+            |mixed, a| -> Result<MultiPart> {
+                let content_type = ContentType::parse(&a.mime_type)?;
+                let attachment =
+                    LettreAttachment::new(a.filename.clone()).body(a.content.clone(), content_type);
+                Ok(mixed.singlepart(attachment))
+            },
+        )?;
+
+        let mut message = Message::builder().from(from).to(to).subject(email.subject);
+        if let Some(reply_to) = &self.sender.reply_to {
+            message = message.reply_to(Mailbox::new(None, reply_to.parse()?));
+        }
+        // The Bcc header is left out of the message itself, so recipients do not see the archive
+        if let Some(bcc) = &self.sender.bcc {
+            message = message.bcc(Mailbox::new(None, bcc.parse()?));
+        }
+        let message = message.multipart(body)?;
+
+        // Relays don't return an ID, so use the Message-ID lettre generated for the message
+        let message_id = message.headers().get_raw("Message-ID").map(str::to_owned);
+        self.transport.send(message).await?;
+
+        Ok(SentEmail { provider: self.get_id().to_owned(), message_id })
+    }
+}
+
+#[async_trait]
+impl EmailClient for SmtpEmailClient {
+    async fn send_onboarding_email(&self, params: OnboardingEmailParams) -> Result<SentEmail> {
+        self.send(OutgoingEmail::try_from(&params)?).await
+    }
+
+    async fn send_offboarding_email(&self, params: OffboardingEmailParams) -> Result<SentEmail> {
+        self.send(OutgoingEmail::try_from(&params)?).await
+    }
+
+    async fn send_digest_email(&self, params: DigestEmailParams) -> Result<SentEmail> {
+        self.send(OutgoingEmail::try_from(&params)?).await
+    }
+}
+
+impl Service for SmtpEmailClient {
+    fn get_id(&self) -> &'static str {
+        "smtp"
+    }
+}
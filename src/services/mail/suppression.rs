@@ -0,0 +1,168 @@
+//! An email client that refuses to send email to addresses on the suppression list.
+//!
+//! Sending to an address that hard bounced or unsubscribed hurts the sender's reputation with
+//! every mail provider, so suppressed addresses are checked before an email reaches a provider.
+
+use std::slice;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use thiserror::Error;
+
+use super::{
+    DigestEmailParams, EmailClient, MailService, OffboardingEmailParams, OnboardingEmailParams,
+    SentEmail,
+};
+use crate::services::storage::emails::CreateEmailSuppression;
+use crate::services::storage::types::EmailSuppressionReason;
+use crate::services::storage::{ExecOptsBuilder, StorageService};
+use crate::services::Service;
+
+/// The error returned when an email is not sent because its recipient is on the suppression list.
+///
+/// * `email`: The suppressed address
+/// * `reason`: Why the address is suppressed
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{email} is on the suppression list ({reason})")]
+pub struct SuppressedEmailError {
+    pub email: String,
+    pub reason: EmailSuppressionReason,
+}
+
+/// Why an email was not sent, if it was because its recipient is on the suppression list.
+///
+/// * `e`: The error returned when sending the email
+pub fn suppression_reason(e: &anyhow::Error) -> Option<EmailSuppressionReason> {
+    e.chain().find_map(|cause| cause.downcast_ref::<SuppressedEmailError>()).map(|e| e.reason)
+}
+
+/// An email client that checks the suppression list before sending email.
+pub struct SuppressionListEmailClient {
+    inner: Arc<dyn MailService>,
+    storage: Arc<dyn StorageService>,
+}
+
+impl SuppressionListEmailClient {
+    /// Create a client.
+    ///
+    /// * `inner`: The client that sends the emails
+    /// * `storage`: The storage layer holding the suppression list
+    pub fn new(inner: Arc<dyn MailService>, storage: Arc<dyn StorageService>) -> Self {
+        Self { inner, storage }
+    }
+
+    /// Look up the recipients of emails on the suppression list.
+    ///
+    /// * `params`: The emails
+    ///
+    /// Returns, for each email, why its recipient is suppressed, or `None` if they are not.
+    async fn suppressed(
+        &self,
+        params: &[OnboardingEmailParams],
+    ) -> Result<Vec<Option<EmailSuppressionReason>>> {
+        let emails = params.iter().map(|p| p.email.clone()).collect::<Vec<_>>();
+        let suppressions = self
+            .storage
+            .fetch_suppressed_emails(emails, &mut ExecOptsBuilder::default().build()?)
+            .await?;
+
+        Ok(params
+            .iter()
+            .map(|p| {
+                suppressions
+                    .iter()
+                    .find(|s| s.email.eq_ignore_ascii_case(&p.email))
+                    .map(|s| s.reason)
+            })
+            .collect())
+    }
+
+    /// Refuse to send an email if its recipient is on the suppression list.
+    ///
+    /// * `email`: The recipient
+    async fn check(&self, email: &str) -> Result<()> {
+        let suppressions = self
+            .storage
+            .fetch_suppressed_emails(
+                vec![email.to_owned()],
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?;
+        match suppressions.into_iter().next() {
+            Some(s) => {
+                Err(SuppressedEmailError { email: email.to_owned(), reason: s.reason }.into())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl EmailClient for SuppressionListEmailClient {
+    async fn send_onboarding_email(&self, params: OnboardingEmailParams) -> Result<SentEmail> {
+        if let Some(reason) = self.suppressed(slice::from_ref(&params)).await?.remove(0) {
+            return Err(SuppressedEmailError { email: params.email, reason }.into());
+        }
+        self.inner.send_onboarding_email(params).await
+    }
+
+    /// Sends the emails whose recipients are not suppressed in one batch.
+    async fn send_onboarding_emails(
+        &self,
+        params: Vec<OnboardingEmailParams>,
+    ) -> Vec<Result<SentEmail>> {
+        let suppressed = match self.suppressed(&params).await {
+            Ok(suppressed) => suppressed,
+            Err(e) => {
+                // Nothing is sent if the list cannot be checked, so the emails can be retried
+                let message = format!("Failed to check the suppression list: {e:#}");
+                return params.iter().map(|_| Err(anyhow!("{message}"))).collect();
+            }
+        };
+
+        let mut results = params.iter().map(|_| None).collect::<Vec<Option<Result<SentEmail>>>>();
+        let mut allowed = Vec::with_capacity(params.len());
+        for (i, (p, reason)) in params.into_iter().zip(suppressed).enumerate() {
+            match reason {
+                Some(reason) => {
+                    log::warn!("Not sending onboarding email to {}, which is suppressed", p.email);
+                    results[i] = Some(Err(SuppressedEmailError { email: p.email, reason }.into()));
+                }
+                None => allowed.push((i, p)),
+            }
+        }
+
+        let (indices, allowed): (Vec<usize>, Vec<OnboardingEmailParams>) =
+            allowed.into_iter().unzip();
+        let sent = self.inner.send_onboarding_emails(allowed).await;
+        for (i, result) in indices.into_iter().zip(sent) {
+            results[i] = Some(result);
+        }
+
+        results
+            .into_iter()
+            .map(|result| result.unwrap_or_else(|| Err(anyhow!("The email was not sent"))))
+            .collect()
+    }
+
+    async fn send_offboarding_email(&self, params: OffboardingEmailParams) -> Result<SentEmail> {
+        self.check(&params.email).await?;
+        self.inner.send_offboarding_email(params).await
+    }
+
+    async fn send_digest_email(&self, params: DigestEmailParams) -> Result<SentEmail> {
+        self.check(&params.email).await?;
+        self.inner.send_digest_email(params).await
+    }
+
+    async fn fetch_suppressions(&self) -> Result<Vec<CreateEmailSuppression>> {
+        self.inner.fetch_suppressions().await
+    }
+}
+
+impl Service for SuppressionListEmailClient {
+    fn get_id(&self) -> &'static str {
+        self.inner.get_id()
+    }
+}
@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+
+use crate::services::mail::failover::{FailoverEmailClient, FAILURES_BEFORE_BENCHED};
+use crate::services::mail::{
+    EmailClient, MailService, MockEmailClient, OnboardingEmailParams, OnboardingEmailParamsBuilder,
+    SentEmail,
+};
+use crate::services::Service;
+
+impl Service for MockEmailClient {
+    fn get_id(&self) -> &'static str {
+        "mock"
+    }
+}
+
+fn params() -> OnboardingEmailParams {
+    OnboardingEmailParamsBuilder::default()
+        .first_name("Mary")
+        .last_name("Zhu")
+        .email("mary@developforgood.org")
+        .workspace_email("maryzhu@developforgood.org")
+        .temporary_password("password123")
+        .build()
+        .expect("error building params")
+}
+
+fn provider(succeeds: bool, times: usize) -> Arc<dyn MailService> {
+    let mut client = MockEmailClient::new();
+    client.expect_send_onboarding_email().times(times).returning(move |_| {
+        if succeeds {
+            Ok(SentEmail { provider: "mock".to_owned(), message_id: None })
+        } else {
+            Err(anyhow!("provider is down"))
+        }
+    });
+    Arc::new(client)
+}
+
+#[tokio::test]
+pub async fn test_failover_uses_first_healthy_provider() -> Result<()> {
+    let client = FailoverEmailClient::new(vec![provider(true, 2), provider(true, 0)]);
+
+    client.send_onboarding_email(params()).await?;
+    client.send_onboarding_email(params()).await?;
+
+    let stats = client.stats();
+    assert_eq!(stats[0].sent, 2);
+    assert_eq!(stats[1].sent, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_failover_falls_through_and_benches_failing_provider() -> Result<()> {
+    let failures = FAILURES_BEFORE_BENCHED as usize;
+    let client =
+        FailoverEmailClient::new(vec![provider(false, failures), provider(true, failures + 1)]);
+
+    for _ in 0..failures + 1 {
+        client.send_onboarding_email(params()).await?;
+    }
+
+    // the first provider is benched after failing repeatedly, so the last email skips it
+    let stats = client.stats();
+    assert!(!stats[0].healthy);
+    assert_eq!(stats[0].failed, failures as u64);
+    assert_eq!(stats[0].consecutive_failures, FAILURES_BEFORE_BENCHED);
+    assert!(stats[1].healthy);
+    assert_eq!(stats[1].sent, failures as u64 + 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_failover_fails_when_every_provider_fails() {
+    let client = FailoverEmailClient::new(vec![provider(false, 1), provider(false, 1)]);
+
+    assert!(client.send_onboarding_email(params()).await.is_err());
+    assert!(client.stats().iter().all(|s| s.failed == 1 && s.sent == 0));
+}
+
+#[tokio::test]
+pub async fn test_failover_retries_only_failed_emails_in_batch() {
+    let mut first = MockEmailClient::new();
+    first.expect_send_onboarding_emails().times(1).returning(|params| {
+        params
+            .iter()
+            .enumerate()
+            .map(|(i, _)| {
+                if i == 1 {
+                    Err(anyhow!("recipient rejected"))
+                } else {
+                    Ok(SentEmail { provider: "first".to_owned(), message_id: None })
+                }
+            })
+            .collect()
+    });
+    let mut second = MockEmailClient::new();
+    second.expect_send_onboarding_emails().times(1).returning(|params| {
+        assert_eq!(params.len(), 1);
+        vec![Ok(SentEmail { provider: "second".to_owned(), message_id: None })]
+    });
+
+    let client = FailoverEmailClient::new(vec![Arc::new(first), Arc::new(second)]);
+    let results = client.send_onboarding_emails(vec![params(), params(), params()]).await;
+
+    let providers = results
+        .into_iter()
+        .map(|r| r.expect("every email should be sent").provider)
+        .collect::<Vec<String>>();
+    assert_eq!(providers, vec!["first", "second", "first"]);
+
+    let stats = client.stats();
+    assert_eq!(stats[0].sent, 2);
+    assert_eq!(stats[0].failed, 1);
+    assert_eq!(stats[1].sent, 1);
+}
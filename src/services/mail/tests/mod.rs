@@ -1,21 +1,69 @@
+mod failover;
+mod preflight;
+mod sendgrid;
+mod suppression;
+mod throttle;
+
 use std::env;
+use std::fs;
 
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, TimeDelta, Utc};
 use rstest::{fixture, rstest};
+use scipio_sendgrid::entities::Mail;
 use scipio_sendgrid::Sendgrid;
 use tera::Context;
+use uuid::Uuid;
 
+use crate::services::mail::sendgrid::SendgridEmailClient;
 use crate::services::mail::{
-    EmailClient, OnboardingEmailParams, OnboardingEmailParamsBuilder, TEMPLATES,
+    find_template, localized_template_name, render_template, CustomTemplate,
+    DigestEmailParamsBuilder, EmailAttachment, EmailClient, OffboardingEmailParamsBuilder,
+    OnboardingEmailKind, OnboardingEmailParams, OnboardingEmailParamsBuilder, SenderIdentity,
+    TEMPLATES,
 };
+use crate::services::storage::entities::{ExportDigest, Job};
+use crate::services::storage::types::JobStatus;
 
 #[fixture]
-pub fn sendgrid() -> Sendgrid {
+pub fn sendgrid() -> SendgridEmailClient {
     dotenvy::dotenv().expect("error loading environment variables");
     let api_key = env::var("SENDGRID_API_KEY").expect("missing SENDGRID_API_KEY variable");
 
-    Sendgrid::new(&api_key, 8).expect("error constructing client")
+    let client = Sendgrid::new(&api_key, 8).expect("error constructing client");
+    SendgridEmailClient::new(client, SenderIdentity::default())
+}
+
+#[rstest]
+#[case("scipio@example.com", "Scipio", Some("help@example.com"), Some("archive@example.com"))]
+#[case(" scipio@example.com ", " Scipio ", None, None)]
+pub fn test_sender_identity(
+    #[case] from_email: &str,
+    #[case] from_name: &str,
+    #[case] reply_to: Option<&str>,
+    #[case] bcc: Option<&str>,
+) {
+    let sender = SenderIdentity::new(from_email, from_name, reply_to, bcc).unwrap();
+
+    assert_eq!(sender.from_mailbox(), "Scipio <scipio@example.com>");
+    assert_eq!(sender.reply_to.as_deref(), reply_to);
+    assert_eq!(sender.bcc.as_deref(), bcc);
+}
+
+#[rstest]
+#[case("not-an-address", "Scipio", None, None)]
+#[case("scipio@example.com", "", None, None)]
+#[case("scipio@example.com", "Scipio <admin@example.com>", None, None)]
+#[case("scipio@example.com", "Scipio\r\nBcc: admin@example.com", None, None)]
+#[case("scipio@example.com", "Scipio", Some("help"), None)]
+#[case("scipio@example.com", "Scipio", None, Some("archive@"))]
+pub fn test_invalid_sender_identity(
+    #[case] from_email: &str,
+    #[case] from_name: &str,
+    #[case] reply_to: Option<&str>,
+    #[case] bcc: Option<&str>,
+) {
+    assert!(SenderIdentity::new(from_email, from_name, reply_to, bcc).is_err());
 }
 
 #[test]
@@ -25,20 +73,258 @@ pub fn test_render_template() {
     context.insert("email", "anish@developforgood.org");
     context.insert("temporaryPassword", "password123");
 
-    let template = TEMPLATES.render("email/onboard.html", &context).unwrap();
+    let template = TEMPLATES.render("email/onboard.en.html", &context).unwrap();
     println!("{template}");
 }
 
+#[test]
+pub fn test_render_template_without_password() {
+    let mut context = Context::new();
+    context.insert("name", "Anish");
+    context.insert("email", "anish@developforgood.org");
+    context.insert("temporaryPassword", &None::<String>);
+
+    let template = TEMPLATES.render("email/onboard.en.html", &context).unwrap();
+    assert!(template.contains("anish@developforgood.org"));
+    assert!(template.contains("text message"));
+}
+
+#[test]
+pub fn test_render_text_template() {
+    let params = OnboardingEmailParamsBuilder::default()
+        .first_name("Anish")
+        .last_name("Sinha")
+        .email("anish@example.com")
+        .workspace_email("anish@developforgood.org")
+        .temporary_password("password123")
+        .build()
+        .unwrap();
+
+    let text = params.render_text().unwrap();
+    assert!(text.starts_with("Dear Anish,"));
+    assert!(text.contains("Your temporary password is: password123"));
+    assert!(!text.contains('<'));
+}
+
+#[test]
+pub fn test_render_onboarding_session_link() {
+    let link = "https://www.google.com/calendar/event?eid=b25ib2FyZGluZw";
+    let mut params = OnboardingEmailParamsBuilder::default()
+        .first_name("Anish")
+        .last_name("Sinha")
+        .email("anish@example.com")
+        .workspace_email("anish@developforgood.org")
+        .temporary_password("password123")
+        .build()
+        .unwrap();
+
+    assert!(!params.render().unwrap().contains("onboarding session"));
+    assert!(!params.render_text().unwrap().contains("onboarding session"));
+
+    params.onboarding_session_link = Some(link.to_owned());
+    assert!(params.render().unwrap().contains(&format!("href=\"{link}\"")));
+    assert!(params.render_text().unwrap().contains(link));
+}
+
+#[rstest]
+#[case("en", "Dear Anish,", "Develop for Good: Your account is being suspended")]
+#[case("es-MX", "Hola, Anish:", "Develop for Good: Tu cuenta será suspendida")]
+pub fn test_render_offboarding_email(
+    #[case] locale: &str,
+    #[case] greeting: &str,
+    #[case] subject: &str,
+) {
+    let params = OffboardingEmailParamsBuilder::default()
+        .first_name("Anish")
+        .last_name("Sinha")
+        .email("anish@example.com")
+        .workspace_email("anish@developforgood.org")
+        .suspend_at(DateTime::from_timestamp(1_735_603_200, 0).unwrap())
+        .locale(locale)
+        .build()
+        .unwrap();
+
+    let text = params.render_text().unwrap();
+    assert!(text.starts_with(greeting));
+    assert!(text.contains("anish@developforgood.org"));
+    assert!(text.contains("2024-12-31"));
+    assert!(text.contains("https://takeout.google.com"));
+    assert!(params.render().unwrap().contains("https://takeout.google.com"));
+    assert_eq!(params.subject(), subject);
+
+    let context = serde_json::json!({
+        "name": "Anish",
+        "email": "anish@developforgood.org",
+        "suspendAt": "2024-12-31",
+    });
+    let template = find_template("email/offboard", locale).unwrap();
+    let rendered = render_template(&template, locale, context.as_object().unwrap()).unwrap();
+    assert_eq!(rendered.subject.as_deref(), Some(subject));
+}
+
+#[test]
+pub fn test_render_digest_email() {
+    let period_end = DateTime::from_timestamp(1_735_603_200, 0).unwrap();
+    let params = DigestEmailParamsBuilder::default()
+        .email("admin@developforgood.org")
+        .period_start(period_end - TimeDelta::days(1))
+        .period_end(period_end)
+        .digest(ExportDigest {
+            jobs_run: 3,
+            users_exported: 42,
+            emails_bounced: 2,
+            ..Default::default()
+        })
+        .failed_jobs(vec![Job {
+            id: Uuid::nil(),
+            created_at: period_end,
+            updated_at: None,
+            project_cycle_id: None,
+            status: JobStatus::Error,
+            label: "Export Fall 2024".to_owned(),
+            description: None,
+            details: serde_json::json!({ "jobType": "airtable_export_users", "error": "quota" }),
+            version: 1,
+        }])
+        .build()
+        .unwrap();
+
+    let text = params.render_text().unwrap();
+    assert!(text.contains("Export jobs run: 3"));
+    assert!(text.contains("Users exported: 42"));
+    assert!(text.contains("Emails bounced: 2"));
+    assert!(text.contains("- Export Fall 2024 (00000000-0000-0000-0000-000000000000): quota"));
+    assert!(params.render().unwrap().contains("Export Fall 2024"));
+    assert_eq!(params.subject(), "Scipio: Export digest for 2024-12-30 to 2024-12-31");
+}
+
+#[test]
+pub fn test_render_text_from_custom_template() {
+    let params = OnboardingEmailParamsBuilder::default()
+        .first_name("Anish")
+        .last_name("Sinha")
+        .email("anish@example.com")
+        .workspace_email("anish@developforgood.org")
+        .template(CustomTemplate {
+            subject: "Welcome".to_owned(),
+            body: "<h2>Dear {{ name }},</h2><p>Your new email is <b>{{ email }}</b></p>".to_owned(),
+        })
+        .build()
+        .unwrap();
+
+    let text = params.render_text().unwrap();
+    assert!(text.contains("Dear Anish,"));
+    assert!(text.contains("anish@developforgood.org"));
+    assert!(!text.contains("<p>"));
+}
+
+#[rstest]
+#[case("es", "Hola, Anish:", "Instrucciones de incorporación")]
+#[case("es-MX", "Hola, Anish:", "Instrucciones de incorporación")]
+#[case("es_mx", "Hola, Anish:", "Instrucciones de incorporación")]
+#[case("en", "Dear Anish,", "Onboarding instructions")]
+#[case("fr", "Dear Anish,", "Onboarding instructions")]
+#[case("", "Dear Anish,", "Onboarding instructions")]
+pub fn test_render_localized_template(
+    #[case] locale: &str,
+    #[case] greeting: &str,
+    #[case] subject: &str,
+) {
+    let params = OnboardingEmailParamsBuilder::default()
+        .first_name("Anish")
+        .last_name("Sinha")
+        .email("anish@example.com")
+        .workspace_email("anish@developforgood.org")
+        .temporary_password("password123")
+        .locale(locale)
+        .build()
+        .unwrap();
+
+    assert!(params.render().unwrap().contains(greeting));
+    assert!(params.render_text().unwrap().starts_with(greeting));
+    assert!(params.subject().ends_with(subject));
+}
+
+#[rstest]
+#[case("en", "onboard")]
+#[case("EN-us", "onboard")]
+#[case("es", "onboard.es")]
+#[case("es_MX", "onboard.es")]
+pub fn test_localized_template_name(#[case] locale: &str, #[case] name: &str) {
+    assert_eq!(localized_template_name("onboard", locale), name);
+}
+
+#[rstest]
+#[case("email/onboard", "es-MX", Some("email/onboard.es.html"))]
+#[case("email/onboard", "fr", Some("email/onboard.en.html"))]
+#[case("email/onboard.es.html", "en", Some("email/onboard.es.html"))]
+#[case("email/missing", "en", None)]
+pub fn test_find_template(#[case] name: &str, #[case] locale: &str, #[case] found: Option<&str>) {
+    assert_eq!(find_template(name, locale).as_deref(), found);
+}
+
+#[test]
+pub fn test_render_named_template() {
+    let context = serde_json::json!({
+        "name": "Anish",
+        "email": "anish@developforgood.org",
+        "temporaryPassword": "password123",
+    });
+
+    let email =
+        render_template("email/onboard.es.html", "en", context.as_object().unwrap()).unwrap();
+    assert!(email.html.contains("Hola, Anish:"));
+    assert!(email.html.contains("lang=\"es\""));
+    assert_eq!(email.subject.as_deref(), Some("Develop for Good: Instrucciones de incorporación"));
+
+    let header = render_template("email/header.html", "en", context.as_object().unwrap()).unwrap();
+    assert_eq!(header.subject, None);
+}
+
+#[test]
+pub fn test_attach_welcome_packet() {
+    let path = env::temp_dir().join("scipio-welcome-packet.pdf");
+    fs::write(&path, b"%PDF-1.7").unwrap();
+    let attachment = EmailAttachment::from_path(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(attachment.filename, "scipio-welcome-packet.pdf");
+    assert_eq!(attachment.mime_type, "application/pdf");
+    assert_eq!(attachment.content, b"%PDF-1.7");
+
+    let params = OnboardingEmailParamsBuilder::default()
+        .first_name("Anish")
+        .last_name("Sinha")
+        .email("anish@example.com")
+        .workspace_email("anish@developforgood.org")
+        .attachments(vec![attachment])
+        .build()
+        .unwrap();
+
+    let mail = Mail::try_from(params).unwrap();
+    let attachments = mail.attachments.unwrap();
+    assert_eq!(attachments.len(), 1);
+    assert_eq!(attachments[0].filename, "scipio-welcome-packet.pdf");
+    assert_eq!(attachments[0].content, "JVBERi0xLjc=");
+}
+
 #[rstest]
 #[tokio::test]
-pub async fn test_send_onboarding_email(sendgrid: Sendgrid) -> Result<()> {
+pub async fn test_send_onboarding_email(sendgrid: SendgridEmailClient) -> Result<()> {
     let params = OnboardingEmailParams {
         first_name: "Mary".to_owned(),
         last_name: "Zhu".to_owned(),
         email: "mary@developforgood.org".to_owned(),
         workspace_email: "maryzhu2@developforgood.org".to_owned(),
-        temporary_password: "password123".to_owned(),
+        temporary_password: Some("password123".to_owned()),
         send_at: None,
+        template: None,
+        locale: "en".to_owned(),
+        attachments: vec![],
+        job_id: None,
+        volunteer_id: None,
+        kind: OnboardingEmailKind::Welcome,
+        onboarding_session_link: None,
     };
 
     sendgrid.send_onboarding_email(params).await?;
@@ -48,7 +334,7 @@ pub async fn test_send_onboarding_email(sendgrid: Sendgrid) -> Result<()> {
 
 #[rstest]
 #[tokio::test]
-pub async fn test_send_scheduled_onboarding_email(sendgrid: Sendgrid) -> Result<()> {
+pub async fn test_send_scheduled_onboarding_email(sendgrid: SendgridEmailClient) -> Result<()> {
     let now = Utc::now().timestamp() as u64;
 
     let params = OnboardingEmailParamsBuilder::default()
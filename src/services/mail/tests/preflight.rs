@@ -0,0 +1,81 @@
+use std::collections::BTreeSet;
+
+use rstest::rstest;
+use tera::{Context, Tera};
+
+use crate::services::mail::preflight::{
+    check_context, required_variables, MissingTemplateVariablesError,
+};
+use crate::services::mail::{CustomTemplate, OnboardingEmailParamsBuilder, TEMPLATES};
+
+fn tera(body: &str) -> Tera {
+    let mut tera = Tera::default();
+    tera.add_raw_template("test", body).unwrap();
+    tera
+}
+
+#[rstest]
+#[case("Dear {{ name }}, your email is {{ email }}", &["email", "name"])]
+#[case("{% if temporaryPassword %}{{ temporaryPassword }}{% endif %}", &[])]
+#[case("{% if a %}{% elif b %}{{ a }}{{ b }}{% else %}{{ c }}{% endif %}", &["c"])]
+#[case("{{ locale | default(value=\"en\") }}", &[])]
+#[case("{% for job in jobs %}{{ job.id }}{% endfor %}", &[])]
+#[case("{% set greeting = \"Hi\" %}{{ greeting }} {{ user.name }}", &["user"])]
+pub fn test_required_variables(#[case] body: &str, #[case] expected: &[&str]) {
+    let expected = expected.iter().map(|v| v.to_string()).collect::<BTreeSet<_>>();
+    assert_eq!(required_variables(&tera(body), "test").unwrap(), expected);
+}
+
+#[test]
+pub fn test_required_variables_of_onboarding_template() {
+    let required = required_variables(&TEMPLATES, "email/onboard.en.html").unwrap();
+    assert_eq!(required, BTreeSet::from(["email".to_owned(), "name".to_owned()]));
+}
+
+#[test]
+pub fn test_check_context() {
+    let tera = tera("Dear {{ name }}, your email is {{ email }}");
+
+    let mut context = Context::new();
+    context.insert("name", "Anish");
+    context.insert("email", " ");
+    let err = check_context(&tera, "test", &context).unwrap_err();
+    let err = err.downcast::<MissingTemplateVariablesError>().unwrap();
+    assert_eq!(err.variables, vec!["email".to_owned()]);
+
+    context.insert("email", "anish@developforgood.org");
+    check_context(&tera, "test", &context).unwrap();
+}
+
+#[test]
+pub fn test_refuse_onboarding_email_with_blank_placeholders() {
+    let params = OnboardingEmailParamsBuilder::default()
+        .first_name("")
+        .last_name("Sinha")
+        .email("anish@example.com")
+        .workspace_email("anish@developforgood.org")
+        .build()
+        .unwrap();
+
+    assert!(params.render().is_err());
+    assert!(params.render_text().is_err());
+}
+
+#[test]
+pub fn test_refuse_custom_template_with_unknown_variable() {
+    let params = OnboardingEmailParamsBuilder::default()
+        .first_name("Anish")
+        .last_name("Sinha")
+        .email("anish@example.com")
+        .workspace_email("anish@developforgood.org")
+        .template(CustomTemplate {
+            subject: "Welcome".to_owned(),
+            body: "<p>Dear {{ name }}, your mentor is {{ mentor }}</p>".to_owned(),
+        })
+        .build()
+        .unwrap();
+
+    let err = params.render().unwrap_err();
+    let err = err.downcast::<MissingTemplateVariablesError>().unwrap();
+    assert_eq!(err.variables, vec!["mentor".to_owned()]);
+}
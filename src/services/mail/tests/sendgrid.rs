@@ -0,0 +1,51 @@
+use crate::services::mail::sendgrid::{substitute, substitutions, tagged_bodies};
+use crate::services::mail::{CustomTemplate, OnboardingEmailParams, OnboardingEmailParamsBuilder};
+
+fn params(first_name: &str, temporary_password: Option<&str>) -> OnboardingEmailParams {
+    let mut builder = OnboardingEmailParamsBuilder::default();
+    builder
+        .first_name(first_name)
+        .last_name("Zhu")
+        .email("mary@example.com")
+        .workspace_email("maryzhu@developforgood.org");
+    if let Some(password) = temporary_password {
+        builder.temporary_password(password);
+    }
+    builder.build().expect("error building params")
+}
+
+#[test]
+pub fn test_tagged_bodies_reproduce_each_email() {
+    let mary = params("Mary", Some("password123"));
+    let tom = params("Tom & Jerry", Some("<secret>"));
+
+    let (mary_html, mary_text) = tagged_bodies(&mary).expect("email should be batchable");
+    let (tom_html, tom_text) = tagged_bodies(&tom).expect("email should be batchable");
+
+    // both emails share the same tagged content, so they can be sent in one request
+    assert_eq!(mary_html, tom_html);
+    assert_eq!(mary_text, tom_text);
+
+    let substitutions = substitutions(&tom);
+    assert_eq!(substitute(&tom_html, &substitutions), tom.render().unwrap());
+    assert_eq!(substitute(&tom_text, &substitutions), tom.render_text().unwrap());
+}
+
+#[test]
+pub fn test_tagged_bodies_depend_on_password_delivery() {
+    let (with_password, _) = tagged_bodies(&params("Mary", Some("password123"))).unwrap();
+    let (without_password, _) = tagged_bodies(&params("Mary", None)).unwrap();
+
+    assert_ne!(with_password, without_password);
+}
+
+#[test]
+pub fn test_tagged_bodies_reject_transformed_variables() {
+    let mut mary = params("Mary", None);
+    mary.template = Some(CustomTemplate {
+        subject: "Welcome".to_owned(),
+        body: "<p>Dear {{ name | upper }},</p>".to_owned(),
+    });
+
+    assert!(tagged_bodies(&mary).is_none());
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use sqlx::PgPool;
+
+use crate::services::mail::suppression::{
+    suppression_reason, SuppressedEmailError, SuppressionListEmailClient,
+};
+use crate::services::mail::{
+    EmailClient, MockEmailClient, OnboardingEmailParams, OnboardingEmailParamsBuilder, SentEmail,
+};
+use crate::services::storage::emails::{CreateEmailSuppressionBuilder, QueryEmails};
+use crate::services::storage::types::EmailSuppressionReason;
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+fn params(email: &str) -> OnboardingEmailParams {
+    OnboardingEmailParamsBuilder::default()
+        .first_name("Mary")
+        .last_name("Zhu")
+        .email(email)
+        .workspace_email("maryzhu@developforgood.org")
+        .build()
+        .expect("error building params")
+}
+
+#[sqlx::test]
+pub async fn test_suppressed_emails_are_not_sent(pool: PgPool) -> Result<()> {
+    let storage = Arc::new(PgBackend {
+        pool,
+        replica: None,
+        metrics: Default::default(),
+        cache: None,
+        cipher: None,
+    });
+    storage
+        .suppress_emails(
+            vec![CreateEmailSuppressionBuilder::default()
+                .email("bounced@example.com")
+                .reason(EmailSuppressionReason::Bounced)
+                .build()?],
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    let mut inner = MockEmailClient::new();
+    inner
+        .expect_send_onboarding_emails()
+        .withf(|params| params.len() == 1 && params[0].email == "mary@example.com")
+        .times(1)
+        .returning(|params| params.iter().map(|_| Ok(SentEmail::default())).collect());
+    let client = SuppressionListEmailClient::new(Arc::new(inner), storage);
+
+    let results = client
+        .send_onboarding_emails(vec![params("Bounced@example.com"), params("mary@example.com")])
+        .await;
+
+    let e = results[0].as_ref().unwrap_err();
+    assert_eq!(
+        e.downcast_ref::<SuppressedEmailError>(),
+        Some(&SuppressedEmailError {
+            email: "Bounced@example.com".to_owned(),
+            reason: EmailSuppressionReason::Bounced,
+        })
+    );
+    assert!(results[1].is_ok());
+
+    Ok(())
+}
+
+#[test]
+pub fn test_suppression_reason() {
+    let suppressed = anyhow::Error::from(SuppressedEmailError {
+        email: "mary@example.com".to_owned(),
+        reason: EmailSuppressionReason::Unsubscribed,
+    })
+    .context("Failed to send onboarding email");
+    let other = anyhow!("provider is down").context("Failed to send onboarding email");
+
+    assert_eq!(suppression_reason(&suppressed), Some(EmailSuppressionReason::Unsubscribed));
+    assert_eq!(suppression_reason(&other), None);
+}
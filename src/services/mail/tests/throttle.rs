@@ -0,0 +1,43 @@
+use std::time::{Duration, Instant};
+
+use crate::services::mail::throttle::TokenBucket;
+
+#[test]
+pub fn test_token_bucket_allows_burst_then_throttles() {
+    let bucket = TokenBucket::new(2.0, 3).unwrap();
+    let start = Instant::now();
+
+    for _ in 0..3 {
+        assert_eq!(bucket.take(1, start), Duration::ZERO);
+    }
+    assert_eq!(bucket.take(1, start), Duration::from_millis(500));
+    // a later caller waits behind the earlier one
+    assert_eq!(bucket.take(1, start), Duration::from_secs(1));
+}
+
+#[test]
+pub fn test_token_bucket_refills_up_to_capacity() {
+    let bucket = TokenBucket::new(2.0, 3).unwrap();
+    let start = Instant::now();
+
+    assert_eq!(bucket.take(3, start), Duration::ZERO);
+    assert_eq!(bucket.take(1, start + Duration::from_millis(500)), Duration::ZERO);
+    // idle long enough to refill many times over, but the bucket only holds 3
+    let later = start + Duration::from_secs(60);
+    assert_eq!(bucket.take(3, later), Duration::ZERO);
+    assert_eq!(bucket.take(1, later), Duration::from_millis(500));
+}
+
+#[test]
+pub fn test_token_bucket_takes_batches_larger_than_capacity() {
+    let bucket = TokenBucket::new(10.0, 5).unwrap();
+
+    assert_eq!(bucket.take(25, Instant::now()), Duration::from_secs(2));
+}
+
+#[test]
+pub fn test_token_bucket_rejects_invalid_config() {
+    assert!(TokenBucket::new(0.0, 3).is_err());
+    assert!(TokenBucket::new(f64::NAN, 3).is_err());
+    assert!(TokenBucket::new(1.0, 0).is_err());
+}
@@ -0,0 +1,152 @@
+//! An email client that limits how fast emails are sent.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+
+use super::{
+    DigestEmailParams, EmailClient, MailService, OffboardingEmailParams, OnboardingEmailParams,
+    SentEmail,
+};
+use crate::services::storage::emails::CreateEmailSuppression;
+use crate::services::Service;
+
+/// The number of emails that may be sent at once before the rate limit kicks in, if none is
+/// configured.
+pub const DEFAULT_MAIL_BURST: u32 = 10;
+
+/// The state of a token bucket.
+///
+/// * `tokens`: The number of tokens in the bucket. It is negative while senders are waiting on
+///   tokens they have already taken.
+/// * `updated_at`: When `tokens` was last brought up to date
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// A token bucket, which refills at a steady rate up to its capacity.
+///
+/// * `rate`: The number of tokens added per second
+/// * `capacity`: The most tokens the bucket holds
+/// * `state`: The current state of the bucket
+#[derive(Debug)]
+pub struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    state: Mutex<BucketState>,
+}
+
+impl TokenBucket {
+    /// Create a full bucket.
+    ///
+    /// * `rate`: The number of tokens added per second
+    /// * `capacity`: The most tokens the bucket holds
+    pub fn new(rate: f64, capacity: u32) -> Result<Self> {
+        if !rate.is_finite() || rate <= 0.0 {
+            bail!("The mail rate limit must be a positive number, not {rate}");
+        }
+        if capacity == 0 {
+            bail!("The mail burst must be at least 1");
+        }
+
+        let capacity = f64::from(capacity);
+        Ok(Self {
+            rate,
+            capacity,
+            state: Mutex::new(BucketState { tokens: capacity, updated_at: Instant::now() }),
+        })
+    }
+
+    /// Take tokens from the bucket, returning how long to wait before using them.
+    ///
+    /// * `count`: The number of tokens to take
+    /// * `now`: The current time
+    ///
+    /// Tokens are handed out in the order they are asked for. If there are not enough, the bucket
+    /// goes into debt, so later callers wait behind earlier ones.
+    pub fn take(&self, count: u32, now: Instant) -> Duration {
+        let mut state = self.state.lock().expect("token bucket lock poisoned");
+
+        let elapsed = now.saturating_duration_since(state.updated_at).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.rate).min(self.capacity);
+        state.updated_at = now.max(state.updated_at);
+        state.tokens -= f64::from(count);
+
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.rate)
+        }
+    }
+
+    /// Wait until the tokens taken are available.
+    ///
+    /// * `count`: The number of tokens to take
+    pub async fn acquire(&self, count: u32) {
+        let wait = self.take(count, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// An email client that sends emails no faster than a configured rate.
+///
+/// There is one client per process, so the limit is shared by every job sending email at once.
+pub struct ThrottledEmailClient {
+    inner: Arc<dyn MailService>,
+    bucket: TokenBucket,
+}
+
+impl ThrottledEmailClient {
+    /// Create a client.
+    ///
+    /// * `inner`: The client that sends the emails
+    /// * `rate`: The most emails to send per second, on average
+    /// * `burst`: The most emails to send at once after sending none for a while
+    pub fn new(inner: Arc<dyn MailService>, rate: f64, burst: u32) -> Result<Self> {
+        Ok(Self { inner, bucket: TokenBucket::new(rate, burst)? })
+    }
+}
+
+#[async_trait]
+impl EmailClient for ThrottledEmailClient {
+    async fn send_onboarding_email(&self, params: OnboardingEmailParams) -> Result<SentEmail> {
+        self.bucket.acquire(1).await;
+        self.inner.send_onboarding_email(params).await
+    }
+
+    /// Sends a batch of onboarding emails once there is room in the rate limit for all of them.
+    async fn send_onboarding_emails(
+        &self,
+        params: Vec<OnboardingEmailParams>,
+    ) -> Vec<Result<SentEmail>> {
+        let count = u32::try_from(params.len()).unwrap_or(u32::MAX);
+        self.bucket.acquire(count).await;
+        self.inner.send_onboarding_emails(params).await
+    }
+
+    async fn send_offboarding_email(&self, params: OffboardingEmailParams) -> Result<SentEmail> {
+        self.bucket.acquire(1).await;
+        self.inner.send_offboarding_email(params).await
+    }
+
+    async fn send_digest_email(&self, params: DigestEmailParams) -> Result<SentEmail> {
+        self.bucket.acquire(1).await;
+        self.inner.send_digest_email(params).await
+    }
+
+    async fn fetch_suppressions(&self) -> Result<Vec<CreateEmailSuppression>> {
+        self.inner.fetch_suppressions().await
+    }
+}
+
+impl Service for ThrottledEmailClient {
+    fn get_id(&self) -> &'static str {
+        self.inner.get_id()
+    }
+}
@@ -1,6 +1,10 @@
 pub mod airtable;
 pub mod auth;
 pub mod mail;
+pub mod objects;
+pub mod settings;
+pub mod slack;
+pub mod sms;
 pub mod storage;
 pub mod workspace;
 
@@ -0,0 +1,52 @@
+//! An object store that encrypts objects before they leave the process.
+//!
+//! Objects are encrypted with the same envelope encryption as the columns the storage layer
+//! encrypts (see `storage::encryption`), under keys of their own, so a copy of the bucket alone
+//! does not expose what it holds. Objects that are not encrypted are refused when they are read,
+//! since they were not written by this store.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+use super::{ObjectStore, ObjectStoreService};
+use crate::services::storage::encryption::FieldCipher;
+use crate::services::Service;
+
+/// An object store that encrypts the objects it stores in another.
+pub struct EncryptedObjectStore {
+    inner: Arc<dyn ObjectStoreService>,
+    cipher: FieldCipher,
+}
+
+impl EncryptedObjectStore {
+    /// Create a store.
+    ///
+    /// * `inner`: The store that holds the encrypted objects
+    /// * `cipher`: The cipher that encrypts and decrypts the objects
+    pub fn new(inner: Arc<dyn ObjectStoreService>, cipher: FieldCipher) -> Self {
+        Self { inner, cipher }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for EncryptedObjectStore {
+    async fn put_object(&self, key: &str, body: Vec<u8>) -> Result<()> {
+        let encrypted = self.cipher.encrypt_bytes(&body)?;
+        self.inner.put_object(key, encrypted.into_bytes()).await
+    }
+
+    async fn get_object(&self, key: &str) -> Result<Vec<u8>> {
+        let stored = self.inner.get_object(key).await?;
+        let stored =
+            String::from_utf8(stored).with_context(|| format!("object {key} is not encrypted"))?;
+        self.cipher.decrypt_bytes(&stored).with_context(|| format!("error decrypting object {key}"))
+    }
+}
+
+impl Service for EncryptedObjectStore {
+    fn get_id(&self) -> &'static str {
+        self.inner.get_id()
+    }
+}
@@ -0,0 +1,36 @@
+//! An `ObjectStore` that keeps objects in memory, for local development and tests. Objects are
+//! lost when the process exits.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+
+use super::ObjectStore;
+use crate::services::Service;
+
+#[derive(Default)]
+pub struct MemoryObjectStore {
+    objects: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+#[async_trait]
+impl ObjectStore for MemoryObjectStore {
+    async fn put_object(&self, key: &str, body: Vec<u8>) -> Result<()> {
+        let mut objects = self.objects.lock().map_err(|_| anyhow!("object store lock poisoned"))?;
+        objects.insert(key.to_owned(), body);
+        Ok(())
+    }
+
+    async fn get_object(&self, key: &str) -> Result<Vec<u8>> {
+        let objects = self.objects.lock().map_err(|_| anyhow!("object store lock poisoned"))?;
+        objects.get(key).cloned().ok_or_else(|| anyhow!("object {key} does not exist"))
+    }
+}
+
+impl Service for MemoryObjectStore {
+    fn get_id(&self) -> &'static str {
+        "memory"
+    }
+}
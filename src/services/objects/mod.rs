@@ -0,0 +1,33 @@
+//! This module contains traits for storing files in object storage, as well as concrete
+//! implementations (any S3 compatible service, including Google Cloud Storage through its
+//! interoperability API).
+
+pub mod encrypted;
+pub mod memory;
+pub mod noop;
+pub mod s3;
+#[cfg(test)]
+mod tests;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::Service;
+
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Stores an object, replacing any object already stored under its key.
+    ///
+    /// * `key`: The key to store the object under
+    /// * `body`: The contents of the object
+    async fn put_object(&self, key: &str, body: Vec<u8>) -> Result<()>;
+
+    /// Fetches the contents of an object.
+    ///
+    /// * `key`: The key the object is stored under
+    async fn get_object(&self, key: &str) -> Result<Vec<u8>>;
+}
+
+pub trait ObjectStoreService: ObjectStore + Service + Send + Sync {}
+
+impl<T> ObjectStoreService for T where T: ObjectStore + Service + Send + Sync {}
@@ -0,0 +1,24 @@
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+
+use super::ObjectStore;
+use crate::services::Service;
+
+pub struct NoopObjectStore;
+
+#[async_trait]
+impl ObjectStore for NoopObjectStore {
+    async fn put_object(&self, _key: &str, _body: Vec<u8>) -> Result<()> {
+        bail!("No object store is configured")
+    }
+
+    async fn get_object(&self, _key: &str) -> Result<Vec<u8>> {
+        bail!("No object store is configured")
+    }
+}
+
+impl Service for NoopObjectStore {
+    fn get_id(&self) -> &'static str {
+        "noop"
+    }
+}
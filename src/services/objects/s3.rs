@@ -0,0 +1,88 @@
+//! An `ObjectStore` backed by Amazon S3 or any service with an S3 compatible API.
+//!
+//! Google Cloud Storage is supported through its interoperability API: set the endpoint URL to
+//! `https://storage.googleapis.com` and provide an HMAC key as the AWS access key ID and secret.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::Client;
+
+use super::ObjectStore;
+use crate::services::Service;
+
+/// An S3 client for a single bucket.
+///
+/// Credentials are resolved through the standard AWS provider chain (environment variables, the
+/// shared config files, or the instance/task role), so no keys need to be passed to Scipio.
+///
+/// * `client`: The S3 client
+/// * `bucket`: The bucket objects are stored in
+pub struct S3ObjectStore {
+    client: Client,
+    bucket: String,
+}
+
+impl S3ObjectStore {
+    /// Create a client.
+    ///
+    /// * `bucket`: The bucket objects are stored in
+    /// * `region`: The region of the bucket. If `None`, the region is resolved through the standard
+    ///   AWS provider chain.
+    /// * `endpoint_url`: The URL of an S3 compatible service to use instead of S3. Buckets are
+    ///   addressed by path rather than by subdomain when it is set.
+    pub async fn new(bucket: &str, region: Option<String>, endpoint_url: Option<String>) -> Self {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+        if let Some(region) = region {
+            loader = loader.region(Region::new(region));
+        }
+        let config = loader.load().await;
+
+        let mut builder = aws_sdk_s3::config::Builder::from(&config);
+        if let Some(endpoint_url) = endpoint_url {
+            builder = builder.endpoint_url(endpoint_url).force_path_style(true);
+        }
+
+        Self { client: Client::from_conf(builder.build()), bucket: bucket.to_owned() }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3ObjectStore {
+    async fn put_object(&self, key: &str, body: Vec<u8>) -> Result<()> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(ByteStream::from(body))
+            .send()
+            .await
+            .with_context(|| format!("error storing object {key} in bucket {}", self.bucket))?;
+        Ok(())
+    }
+
+    async fn get_object(&self, key: &str) -> Result<Vec<u8>> {
+        let bucket = &self.bucket;
+        let output = self
+            .client
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await
+            .with_context(|| format!("error fetching object {key} from bucket {bucket}"))?;
+        let body = output
+            .body
+            .collect()
+            .await
+            .with_context(|| format!("error reading object {key} from bucket {bucket}"))?;
+        Ok(body.into_bytes().to_vec())
+    }
+}
+
+impl Service for S3ObjectStore {
+    fn get_id(&self) -> &'static str {
+        "s3"
+    }
+}
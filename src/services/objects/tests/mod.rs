@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+use crate::services::objects::encrypted::EncryptedObjectStore;
+use crate::services::objects::memory::MemoryObjectStore;
+use crate::services::objects::ObjectStore;
+use crate::services::storage::encryption::FieldCipher;
+
+fn cipher() -> Result<FieldCipher> {
+    FieldCipher::new(&[format!("k1:{}", BASE64.encode([1u8; 32]))])
+}
+
+#[tokio::test]
+pub async fn test_encrypted_object_store() -> Result<()> {
+    let inner = Arc::new(MemoryObjectStore::default());
+    let store = EncryptedObjectStore::new(inner.clone(), cipher()?);
+
+    store.put_object("backups/latest.json", b"{\"volunteers\":[]}".to_vec()).await?;
+    assert_eq!(store.get_object("backups/latest.json").await?, b"{\"volunteers\":[]}");
+
+    // the store behind it only ever sees the encrypted object
+    let stored = inner.get_object("backups/latest.json").await?;
+    assert!(stored.starts_with(b"enc:v1:k1:"));
+    assert!(!String::from_utf8(stored)?.contains("volunteers"));
+
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_encrypted_object_store_refuses_plaintext() -> Result<()> {
+    let inner = Arc::new(MemoryObjectStore::default());
+    inner.put_object("backups/plain.json", b"{\"volunteers\":[]}".to_vec()).await?;
+
+    let store = EncryptedObjectStore::new(inner, cipher()?);
+    assert!(store.get_object("backups/plain.json").await.is_err());
+    assert!(store.get_object("backups/missing.json").await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! This module contains the settings staff can change without a deploy, and a service for reading
+//! and writing them with their types.
+//!
+//! Each setting is stored as a JSON value under its key, for each organization. A setting that has
+//! not been saved falls back to its default, which for the recipient overrides is still read from
+//! the environment so existing deployments keep working.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::services::storage::{ExecOptsBuilder, StorageService};
+
+/// A setting, along with the type of its value.
+pub trait Setting {
+    /// The key the setting is stored under
+    const KEY: &'static str;
+
+    /// The type of the setting's value
+    type Value: Serialize + DeserializeOwned + Send;
+
+    /// The value of the setting when it has not been saved, if it has one.
+    fn fallback() -> Option<Self::Value> {
+        None
+    }
+}
+
+/// The org unit exports create users in when the request does not name one.
+pub struct DefaultOrgUnit;
+
+impl Setting for DefaultOrgUnit {
+    const KEY: &'static str = "defaultOrgUnit";
+    type Value = String;
+}
+
+/// The address emails are sent from. It is read when the mail service starts, so a change takes
+/// effect on the next restart.
+pub struct SenderAddress;
+
+impl Setting for SenderAddress {
+    const KEY: &'static str = "senderAddress";
+    type Value = String;
+}
+
+/// The address every onboarding email is sent to instead of the volunteer's, for testing exports
+/// without emailing volunteers.
+pub struct MailRecipientOverride;
+
+impl Setting for MailRecipientOverride {
+    const KEY: &'static str = "mailRecipientOverride";
+    type Value = String;
+
+    fn fallback() -> Option<Self::Value> {
+        env::var("MAIL_RECIPIENT_OVERRIDE").ok()
+    }
+}
+
+/// The phone number every temporary password is texted to instead of the volunteer's, for testing
+/// exports without texting volunteers.
+pub struct SmsRecipientOverride;
+
+impl Setting for SmsRecipientOverride {
+    const KEY: &'static str = "smsRecipientOverride";
+    type Value = String;
+
+    fn fallback() -> Option<Self::Value> {
+        env::var("SMS_RECIPIENT_OVERRIDE").ok()
+    }
+}
+
+/// Features that can be turned on or off, by name.
+pub struct FeatureToggles;
+
+impl Setting for FeatureToggles {
+    const KEY: &'static str = "featureToggles";
+    type Value = BTreeMap<String, bool>;
+}
+
+/// The keys of every setting.
+pub const SETTING_KEYS: [&str; 5] = [
+    DefaultOrgUnit::KEY,
+    SenderAddress::KEY,
+    MailRecipientOverride::KEY,
+    SmsRecipientOverride::KEY,
+    FeatureToggles::KEY,
+];
+
+/// Reads and writes the settings of the current organization.
+pub struct SettingsService {
+    storage: Arc<dyn StorageService>,
+}
+
+impl SettingsService {
+    /// Create a service.
+    ///
+    /// * `storage`: The storage layer the settings are kept in
+    pub fn new(storage: Arc<dyn StorageService>) -> Self {
+        Self { storage }
+    }
+
+    /// Read a setting, or its default if it has not been saved.
+    pub async fn get<S: Setting>(&self) -> Result<Option<S::Value>> {
+        let saved =
+            self.storage.fetch_setting(S::KEY, &mut ExecOptsBuilder::default().build()?).await?;
+
+        match saved {
+            Some(setting) => serde_json::from_value(setting.value)
+                .map(Some)
+                .with_context(|| format!("invalid value saved for setting {}", S::KEY)),
+            None => Ok(S::fallback()),
+        }
+    }
+
+    /// Save a setting.
+    ///
+    /// * `value`: The setting's new value
+    /// * `principal`: The user changing the setting, if any
+    pub async fn set<S: Setting>(&self, value: &S::Value, principal: Option<String>) -> Result<()> {
+        let value = serde_json::to_value(value)?;
+        let mut exec_opts = ExecOptsBuilder::default().build()?;
+        exec_opts.principal = principal;
+        self.storage.save_setting(S::KEY, value, &mut exec_opts).await?;
+        Ok(())
+    }
+
+    /// Delete a setting, so its default is used again.
+    ///
+    /// * `key`: The key of the setting
+    /// * `principal`: The user clearing the setting, if any
+    ///
+    /// Returns whether the setting had been saved.
+    pub async fn clear(&self, key: &str, principal: Option<String>) -> Result<bool> {
+        let mut exec_opts = ExecOptsBuilder::default().build()?;
+        exec_opts.principal = principal;
+        self.storage.delete_setting(key, &mut exec_opts).await
+    }
+}
@@ -0,0 +1,44 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::services::settings::{
+    DefaultOrgUnit, FeatureToggles, SenderAddress, Setting, SettingsService,
+};
+use crate::services::storage::settings::QuerySettings;
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test]
+pub async fn test_settings_service(pool: PgPool) -> Result<()> {
+    let storage = Arc::new(PgBackend {
+        pool,
+        replica: None,
+        metrics: Default::default(),
+        cache: None,
+        cipher: None,
+    });
+    let settings = SettingsService::new(storage.clone());
+
+    assert_eq!(settings.get::<DefaultOrgUnit>().await?, None);
+
+    settings.set::<DefaultOrgUnit>(&"/Programs/Fall".to_owned(), None).await?;
+    assert_eq!(settings.get::<DefaultOrgUnit>().await?.as_deref(), Some("/Programs/Fall"));
+
+    let toggles = BTreeMap::from([("smsPasswords".to_owned(), true)]);
+    settings.set::<FeatureToggles>(&toggles, Some("admin".to_owned())).await?;
+    assert_eq!(settings.get::<FeatureToggles>().await?, Some(toggles));
+
+    assert!(settings.clear(DefaultOrgUnit::KEY, None).await?);
+    assert_eq!(settings.get::<DefaultOrgUnit>().await?, None);
+
+    // a value of the wrong type is reported rather than ignored
+    storage
+        .save_setting(SenderAddress::KEY, json!(42), &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    assert!(settings.get::<SenderAddress>().await.is_err());
+
+    Ok(())
+}
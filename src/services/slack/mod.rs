@@ -0,0 +1,47 @@
+//! This module contains traits for inviting volunteers to a program's Slack workspace, as well as
+//! one concrete implementation (Slack's Web API).
+
+pub mod noop;
+#[cfg(test)]
+mod tests;
+pub mod web_api;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use derive_builder::Builder;
+
+use super::Service;
+
+/// Data needed to invite a volunteer to Slack.
+///
+/// * `email`: The address to invite, which is the volunteer's workspace email
+/// * `channel_ids`: The IDs of the channels to add the volunteer to (e.g. `C0123456789`)
+#[derive(Debug, Clone, Builder)]
+pub struct SlackInviteParams {
+    #[builder(setter(into))]
+    pub email: String,
+    pub channel_ids: Vec<String>,
+}
+
+/// What inviting a volunteer to Slack did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlackInviteOutcome {
+    /// The volunteer was invited to the Slack workspace and its channels.
+    Invited,
+    /// The volunteer was already a member of the Slack workspace, so they were only added to the
+    /// channels.
+    AlreadyMember,
+}
+
+#[async_trait]
+pub trait SlackClient: Send + Sync {
+    /// Invites a volunteer to the Slack workspace, and adds them to channels once they join. A
+    /// volunteer who is already a member is added to the channels straight away.
+    ///
+    /// * `params`: Data needed to invite the volunteer
+    async fn invite_user(&self, params: SlackInviteParams) -> Result<SlackInviteOutcome>;
+}
+
+pub trait SlackService: SlackClient + Service + Send + Sync {}
+
+impl<T> SlackService for T where T: SlackClient + Service + Send + Sync {}
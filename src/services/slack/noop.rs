@@ -0,0 +1,20 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::{SlackClient, SlackInviteOutcome, SlackInviteParams};
+use crate::services::Service;
+
+pub struct NoopSlackClient;
+
+#[async_trait]
+impl SlackClient for NoopSlackClient {
+    async fn invite_user(&self, _params: SlackInviteParams) -> Result<SlackInviteOutcome> {
+        Ok(SlackInviteOutcome::Invited)
+    }
+}
+
+impl Service for NoopSlackClient {
+    fn get_id(&self) -> &'static str {
+        "noop"
+    }
+}
@@ -0,0 +1,29 @@
+use serde_json::json;
+
+use crate::services::slack::web_api::{check_response, is_already_member, SlackError};
+
+#[test]
+pub fn test_check_response() {
+    let body = json!({ "ok": true, "user": { "id": "U0123456789" } });
+    assert_eq!(check_response("users.lookupByEmail", body.clone()).ok(), Some(body));
+
+    let err =
+        check_response("admin.users.invite", json!({ "ok": false, "error": "already_in_team" }))
+            .expect_err("the call failed");
+    assert!(is_already_member(&err));
+    assert_eq!(
+        err.downcast_ref::<SlackError>(),
+        Some(&SlackError {
+            method: "admin.users.invite".to_owned(),
+            error: "already_in_team".to_owned(),
+        })
+    );
+
+    let err =
+        check_response("admin.users.invite", json!({ "ok": false, "error": "channel_not_found" }))
+            .expect_err("the call failed");
+    assert!(!is_already_member(&err));
+
+    // A body without `ok` is not a success
+    assert!(check_response("admin.users.invite", json!({})).is_err());
+}
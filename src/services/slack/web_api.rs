@@ -0,0 +1,171 @@
+//! A client for inviting users to a Slack workspace with Slack's Web API.
+//!
+//! Inviting people by email needs the `admin.users:write` scope, which is only available to
+//! Enterprise Grid organizations, so the token must be an org admin's user token. Adding existing
+//! members to channels also needs the `users:read.email` and `channels:write` scopes.
+
+use std::fmt;
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::policies::ExponentialBackoff;
+use reqwest_retry::RetryTransientMiddleware;
+use serde_json::Value;
+
+use super::{SlackClient, SlackInviteOutcome, SlackInviteParams};
+use crate::services::Service;
+
+/// The base URL of Slack's Web API.
+const SLACK_API_URL: &str = "https://slack.com/api";
+
+/// An error Slack returned for a Web API call.
+///
+/// Slack answers most failed calls with a 200 and `"ok": false`, naming the problem in `error`.
+///
+/// * `method`: The Web API method that was called (e.g. `admin.users.invite`)
+/// * `error`: The error code Slack returned (e.g. `already_in_team`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlackError {
+    pub method: String,
+    pub error: String,
+}
+
+impl fmt::Display for SlackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Slack rejected {}: {}", self.method, self.error)
+    }
+}
+
+impl std::error::Error for SlackError {}
+
+/// Check the body of a Web API response, failing with a `SlackError` if the call did not succeed.
+///
+/// * `method`: The Web API method that was called
+/// * `body`: The body of the response
+pub fn check_response(method: &str, body: Value) -> Result<Value> {
+    if body.get("ok").and_then(Value::as_bool) == Some(true) {
+        return Ok(body);
+    }
+
+    let error = body.get("error").and_then(Value::as_str).unwrap_or("unknown_error").to_owned();
+    Err(SlackError { method: method.to_owned(), error }.into())
+}
+
+/// Whether an error is Slack saying that the user being invited is already a member.
+///
+/// * `err`: The error to inspect
+pub fn is_already_member(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<SlackError>().is_some_and(|e| e.error == "already_in_team")
+}
+
+/// A Slack Web API client.
+///
+/// * `http`: The HTTP client, which retries transient failures (including Slack's rate limits)
+/// * `token`: The user token of an org admin
+/// * `team_id`: The ID of the Slack workspace to invite volunteers to (e.g. `T0123456789`)
+pub struct SlackWebApi {
+    http: ClientWithMiddleware,
+    token: String,
+    team_id: String,
+}
+
+impl SlackWebApi {
+    pub fn new(token: &str, team_id: &str, max_retries: u32) -> Result<Self> {
+        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(max_retries);
+
+        let http = ClientBuilder::new(Client::builder().build()?)
+            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+            .build();
+
+        Ok(Self { http, token: token.to_owned(), team_id: team_id.to_owned() })
+    }
+
+    /// Call a Web API method.
+    ///
+    /// * `method`: The method to call (e.g. `admin.users.invite`)
+    /// * `form`: The arguments of the call
+    async fn call(&self, method: &str, form: &[(&str, &str)]) -> Result<Value> {
+        let res = self
+            .http
+            .post(format!("{SLACK_API_URL}/{method}"))
+            .bearer_auth(&self.token)
+            .form(form)
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            bail!("Slack rejected {method} with status {status}: {text}");
+        }
+
+        check_response(method, res.json::<Value>().await?)
+    }
+
+    /// Add a member of the Slack workspace to channels.
+    ///
+    /// * `email`: The email address of the member
+    /// * `channel_ids`: The IDs of the channels
+    async fn add_to_channels(&self, email: &str, channel_ids: &[String]) -> Result<()> {
+        let user = self.call("users.lookupByEmail", &[("email", email)]).await?;
+        let Some(user_id) = user.pointer("/user/id").and_then(Value::as_str) else {
+            bail!("Slack did not return an ID for {email}");
+        };
+
+        for channel_id in channel_ids {
+            let added = self
+                .call(
+                    "conversations.invite",
+                    &[("channel", channel_id.as_str()), ("users", user_id)],
+                )
+                .await;
+
+            match added {
+                Ok(_) => {}
+                Err(e)
+                    if e.downcast_ref::<SlackError>()
+                        .is_some_and(|e| e.error == "already_in_channel") => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SlackClient for SlackWebApi {
+    async fn invite_user(&self, params: SlackInviteParams) -> Result<SlackInviteOutcome> {
+        let channel_ids = params.channel_ids.join(",");
+
+        // Anyone who was invited but has not joined yet is sent the invitation again
+        let invited = self
+            .call(
+                "admin.users.invite",
+                &[
+                    ("team_id", self.team_id.as_str()),
+                    ("email", params.email.as_str()),
+                    ("channel_ids", channel_ids.as_str()),
+                    ("resend", "true"),
+                ],
+            )
+            .await;
+
+        match invited {
+            Ok(_) => Ok(SlackInviteOutcome::Invited),
+            Err(e) if is_already_member(&e) => {
+                self.add_to_channels(&params.email, &params.channel_ids).await?;
+                Ok(SlackInviteOutcome::AlreadyMember)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Service for SlackWebApi {
+    fn get_id(&self) -> &'static str {
+        "slack"
+    }
+}
@@ -0,0 +1,54 @@
+//! This module contains traits for sending text messages, as well as one concrete implementation
+//! (Twilio).
+
+pub mod noop;
+#[cfg(test)]
+mod tests;
+pub mod twilio;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use derive_builder::Builder;
+
+use super::Service;
+
+/// Data needed to text a volunteer their temporary password.
+///
+/// * `first_name`: The recipient's first name
+/// * `phone`: The recipient's phone number, in E.164 format (e.g. `+12025550123`)
+/// * `workspace_email`: The recipient's workspace email address
+/// * `temporary_password`: The recipient's temporary password for their workspace email address
+#[derive(Debug, Clone, Builder)]
+pub struct TemporaryPasswordSmsParams {
+    #[builder(setter(into))]
+    pub first_name: String,
+    #[builder(setter(into))]
+    pub phone: String,
+    #[builder(setter(into))]
+    pub workspace_email: String,
+    #[builder(setter(into))]
+    pub temporary_password: String,
+}
+
+impl TemporaryPasswordSmsParams {
+    /// The body of the text message.
+    pub fn body(&self) -> String {
+        format!(
+            "Hi {}, your temporary password for {} is: {}\n\nSign in at https://accounts.google.com \
+             to set a new one. - Develop for Good",
+            self.first_name, self.workspace_email, self.temporary_password
+        )
+    }
+}
+
+#[async_trait]
+pub trait SmsClient: Send + Sync {
+    /// Sends a volunteer their temporary workspace password.
+    ///
+    /// * `params`: Data needed to send the text message
+    async fn send_temporary_password(&self, params: TemporaryPasswordSmsParams) -> Result<()>;
+}
+
+pub trait SmsService: SmsClient + Service + Send + Sync {}
+
+impl<T> SmsService for T where T: SmsClient + Service + Send + Sync {}
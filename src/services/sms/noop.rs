@@ -0,0 +1,20 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use super::{SmsClient, TemporaryPasswordSmsParams};
+use crate::services::Service;
+
+pub struct NoopSmsClient;
+
+#[async_trait]
+impl SmsClient for NoopSmsClient {
+    async fn send_temporary_password(&self, _params: TemporaryPasswordSmsParams) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Service for NoopSmsClient {
+    fn get_id(&self) -> &'static str {
+        "noop"
+    }
+}
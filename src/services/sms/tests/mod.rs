@@ -0,0 +1,18 @@
+use crate::services::sms::TemporaryPasswordSmsParamsBuilder;
+
+#[test]
+pub fn test_temporary_password_body() {
+    let params = TemporaryPasswordSmsParamsBuilder::default()
+        .first_name("Mary")
+        .phone("+12025550123")
+        .workspace_email("maryzhu@developforgood.org")
+        .temporary_password("maple-otter-canyon-piano-42")
+        .build()
+        .expect("error building params");
+
+    let body = params.body();
+    assert!(body.starts_with("Hi Mary,"));
+    assert!(body.contains("maryzhu@developforgood.org"));
+    assert!(body.contains("maple-otter-canyon-piano-42"));
+    assert!(!body.contains("+12025550123"));
+}
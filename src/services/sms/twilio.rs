@@ -0,0 +1,86 @@
+//! A client for sending text messages with Twilio's Programmable Messaging API.
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::policies::ExponentialBackoff;
+use reqwest_retry::RetryTransientMiddleware;
+
+use super::{SmsClient, TemporaryPasswordSmsParams};
+use crate::services::Service;
+
+/// A Twilio client.
+///
+/// * `http`: The HTTP client, which retries transient failures
+/// * `account_sid`: The SID of the Twilio account
+/// * `auth_token`: The auth token of the Twilio account
+/// * `from_number`: The Twilio phone number messages are sent from, in E.164 format
+pub struct Twilio {
+    http: ClientWithMiddleware,
+    account_sid: String,
+    auth_token: String,
+    from_number: String,
+}
+
+impl Twilio {
+    pub fn new(
+        account_sid: &str,
+        auth_token: &str,
+        from_number: &str,
+        max_retries: u32,
+    ) -> Result<Self> {
+        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(max_retries);
+
+        let http = ClientBuilder::new(Client::builder().build()?)
+            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+            .build();
+
+        Ok(Self {
+            http,
+            account_sid: account_sid.to_owned(),
+            auth_token: auth_token.to_owned(),
+            from_number: from_number.to_owned(),
+        })
+    }
+
+    /// Send a text message.
+    ///
+    /// * `to`: The recipient's phone number, in E.164 format
+    /// * `body`: The text of the message
+    pub async fn send_message(&self, to: &str, body: &str) -> Result<()> {
+        let url = format!(
+            "https://api.twilio.com/2010-04-01/Accounts/{}/Messages.json",
+            self.account_sid
+        );
+
+        let res = self
+            .http
+            .post(url)
+            .basic_auth(&self.account_sid, Some(&self.auth_token))
+            .form(&[("To", to), ("From", &self.from_number), ("Body", body)])
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            bail!("Twilio rejected the message with status {status}: {text}");
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SmsClient for Twilio {
+    async fn send_temporary_password(&self, params: TemporaryPasswordSmsParams) -> Result<()> {
+        self.send_message(&params.phone, &params.body()).await
+    }
+}
+
+impl Service for Twilio {
+    fn get_id(&self) -> &'static str {
+        "twilio"
+    }
+}
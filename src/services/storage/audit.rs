@@ -0,0 +1,136 @@
+//! This module contains the definition of the `QueryAudit` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+//!
+//! The audit log is written by database triggers, so every change to the tables that hold
+//! volunteers' data is recorded no matter which query made it. This trait only reads it.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use derive_builder::Builder;
+use sqlx::{Database, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::entities::AuditLogEntry;
+use super::exec_read_with_tx;
+use super::types::AuditOperation;
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// Filters for listing the changes recorded in the audit log. Every filter that is set must match.
+///
+/// * `entity`: Only list changes to rows of this table
+/// * `entity_id`: Only list changes to the row with this id
+/// * `principal`: Only list changes made by this user, ignoring case
+/// * `operation`: Only list inserts, updates, or deletes
+/// * `changed_after`: Only list changes made at or after this time
+/// * `changed_before`: Only list changes made before this time
+#[derive(Builder, Debug, Clone, Default)]
+#[builder(default)]
+pub struct AuditLogFilter {
+    pub entity: Option<String>,
+    pub entity_id: Option<String>,
+    pub principal: Option<String>,
+    pub operation: Option<AuditOperation>,
+    pub changed_after: Option<DateTime<Utc>>,
+    pub changed_before: Option<DateTime<Utc>>,
+}
+
+/// Where a page of the audit log starts. Changes are listed newest first, so a page starts with
+/// the change made just before the last change of the previous page.
+///
+/// * `created_at`: When the last change of the previous page was made
+/// * `id`: The ID of the last change of the previous page, which breaks ties between changes made
+///   at the same time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditLogCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl AuditLogCursor {
+    /// The cursor of the page that follows a change.
+    ///
+    /// * `entry`: The last change of a page
+    pub fn after(entry: &AuditLogEntry) -> Self {
+        Self { created_at: entry.created_at, id: entry.id }
+    }
+
+    /// Encode the cursor as an opaque token that can be handed to API clients.
+    pub fn encode(&self) -> String {
+        format!("{}_{}", self.created_at.timestamp_micros(), self.id.simple())
+    }
+
+    /// Decode a token made by `encode`.
+    ///
+    /// * `token`: The token
+    pub fn decode(token: &str) -> Result<Self> {
+        let (micros, id) = token.split_once('_').context("malformed cursor")?;
+        let created_at = micros
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .context("malformed cursor")?;
+        let id = Uuid::parse_str(id).context("malformed cursor")?;
+        Ok(Self { created_at, id })
+    }
+}
+
+/// A trait for querying the audit log of changes made through the storage layer.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryAudit<DB: Database> {
+    /// Fetch a page of the changes recorded in the audit log, newest first.
+    ///
+    /// * `filter`: Which changes to list
+    /// * `cursor`: Where the page starts, or `None` for the first page
+    /// * `limit`: The maximum number of changes to fetch
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_audit_log(
+        &self,
+        filter: AuditLogFilter,
+        cursor: Option<AuditLogCursor>,
+        limit: i64,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<AuditLogEntry>> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryAudit<Postgres> for PgBackend {
+    async fn fetch_audit_log(
+        &self,
+        filter: AuditLogFilter,
+        cursor: Option<AuditLogCursor>,
+        limit: i64,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<AuditLogEntry>> {
+        async fn exec(
+            filter: AuditLogFilter,
+            cursor: Option<AuditLogCursor>,
+            limit: i64,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<AuditLogEntry>> {
+            let query = include_str!("queries/audit/fetch_audit_log.sql");
+            let entries = sqlx::query_as::<_, AuditLogEntry>(query)
+                .bind(filter.entity)
+                .bind(filter.entity_id)
+                .bind(filter.principal)
+                .bind(filter.operation)
+                .bind(filter.changed_after)
+                .bind(filter.changed_before)
+                .bind(cursor.map(|c| c.created_at))
+                .bind(cursor.map(|c| c.id))
+                .bind(limit)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching audit log")?;
+            Ok(entries)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, filter, cursor, limit)
+    }
+}
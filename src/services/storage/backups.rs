@@ -0,0 +1,130 @@
+//! This module contains the definition of the `QueryBackups` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+//!
+//! A backup is a logical export of an organization's project cycles, volunteers, the records of
+//! their Workspace exports, and jobs (including archived job details), as JSON. Rows are exported
+//! as they are stored, so encrypted columns stay encrypted and a backup can be restored without the
+//! storage encryption keys. Restoring inserts the rows that are missing and leaves the rest alone,
+//! so it can be run against a fresh database or one that lost some of its data.
+
+use anyhow::{ensure, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Database, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::entities::BackupCounts;
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// The version of the backup format written by this build. Backups in other formats are refused.
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// A backup of an organization's data, as it is stored in object storage.
+///
+/// * `format_version`: The version of the backup format
+/// * `org_id`: The organization the data belongs to
+/// * `created_at`: When the backup was taken
+/// * `data`: The organization's rows, by table
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Backup {
+    pub format_version: u32,
+    pub org_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub data: serde_json::Value,
+}
+
+impl Backup {
+    /// Serialize the backup to the bytes stored in object storage.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).context("error serializing backup")
+    }
+
+    /// Read a backup from the bytes stored in object storage.
+    ///
+    /// * `bytes`: The stored backup
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let backup: Self = serde_json::from_slice(bytes).context("backup is malformed")?;
+        ensure!(
+            backup.format_version == BACKUP_FORMAT_VERSION,
+            "backup format version {} is not supported, only version {BACKUP_FORMAT_VERSION} is",
+            backup.format_version
+        );
+        Ok(backup)
+    }
+}
+
+/// A trait for backing up and restoring an organization's data.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryBackups<DB: Database> {
+    /// Fetches the current organization's data to back up, by table.
+    ///
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_backup_data(&self, exec_opts: &mut ExecOpts<DB>) -> Result<serde_json::Value> {
+        unimplemented!()
+    }
+
+    /// Restores data fetched by `fetch_backup_data` into the current organization, skipping rows
+    /// that already exist. The organization itself is created if it does not exist.
+    ///
+    /// * `data`: The data to restore
+    /// * `exec_opts`: Execution options for the query
+    async fn restore_backup_data(
+        &self,
+        data: serde_json::Value,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<BackupCounts> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryBackups<Postgres> for PgBackend {
+    async fn fetch_backup_data(
+        &self,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<serde_json::Value> {
+        async fn exec(tx: &mut Transaction<'_, Postgres>) -> Result<serde_json::Value> {
+            let query = include_str!("queries/backups/fetch_backup_data.sql");
+            let data = sqlx::query_scalar::<_, serde_json::Value>(query)
+                .fetch_one(&mut **tx)
+                .await
+                .context("error fetching backup data")?;
+            Ok(data)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec)
+    }
+
+    async fn restore_backup_data(
+        &self,
+        data: serde_json::Value,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<BackupCounts> {
+        async fn exec(
+            data: serde_json::Value,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<BackupCounts> {
+            let query = include_str!("queries/backups/restore_backup_data.sql");
+            let counts = sqlx::query_as::<_, BackupCounts>(query)
+                .bind(data)
+                .fetch_one(&mut **tx)
+                .await
+                .context("error restoring backup data")?;
+            Ok(counts)
+        }
+
+        let res = exec_with_tx!(self, exec_opts, exec, data);
+        self.invalidate_cache(|cache| {
+            cache.volunteers.invalidate_all();
+            cache.jobs.invalidate_all();
+        });
+        res
+    }
+}
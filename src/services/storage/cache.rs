@@ -0,0 +1,160 @@
+//! An in-process cache in front of the reads that the UI polls most often.
+//!
+//! The UI polls the status and progress of running jobs, and looks up the same volunteers over and
+//! over while an export runs. These reads are cached for a short time so that polling does not add
+//! a query per request. Writes made through the storage layer invalidate the entries they change.
+//! Changes made any other way, by another instance of the app or by a transaction that is still
+//! open when the entry is read again, are picked up when the entry expires.
+//!
+//! Reads made within a transaction always go to the database, so that they see the transaction's
+//! own writes. Entries are cached separately for each organization, so one organization never reads
+//! another's entries.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use derive_builder::Builder;
+use sqlx::Database;
+use uuid::Uuid;
+
+use crate::services::storage::entities::{Job, JobProgress, VolunteerDetails};
+use crate::services::storage::organizations::{current_org, DEFAULT_ORG_ID};
+use crate::services::storage::{ExecOpts, PgBackend};
+
+/// How long entries are cached and how many are kept.
+///
+/// * `ttl`: How long an entry is served from the cache before it is read from the database again
+/// * `capacity`: The most entries each kind of read keeps
+#[derive(Debug, Clone, Builder)]
+pub struct CacheConfig {
+    #[builder(default = "Duration::from_secs(5)")]
+    pub ttl: Duration,
+    #[builder(default = "10_000")]
+    pub capacity: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfigBuilder::default().build().expect("every cache config field has a default")
+    }
+}
+
+/// A map whose entries expire a fixed time after they are inserted. Keys are scoped to the
+/// organization of the current task.
+pub struct TtlCache<K, V> {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<(Uuid, K), (Instant, V)>>,
+}
+
+/// The organization cached entries are scoped to.
+fn org() -> Uuid {
+    current_org().unwrap_or(DEFAULT_ORG_ID)
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> TtlCache<K, V> {
+    /// Create an empty cache.
+    ///
+    /// * `config`: How long entries are kept and how many
+    pub fn new(config: &CacheConfig) -> Self {
+        Self { ttl: config.ttl, capacity: config.capacity, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// The value cached for a key, if it has not expired.
+    ///
+    /// * `key`: The key
+    pub fn get(&self, key: &K) -> Option<V> {
+        let entries = self.entries.lock().expect("cache lock poisoned");
+        entries
+            .get(&(org(), key.clone()))
+            .filter(|(expires_at, _)| *expires_at > Instant::now())
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Cache a value. If the cache is full, expired entries are dropped to make room, and if none
+    /// have expired, every entry is.
+    ///
+    /// * `key`: The key
+    /// * `value`: The value
+    pub fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.lock().expect("cache lock poisoned");
+        let now = Instant::now();
+        if entries.len() >= self.capacity {
+            entries.retain(|_, (expires_at, _)| *expires_at > now);
+        }
+        if entries.len() >= self.capacity {
+            entries.clear();
+        }
+        entries.insert((org(), key), (now + self.ttl, value));
+    }
+
+    /// Drop the value cached for a key.
+    ///
+    /// * `key`: The key
+    pub fn invalidate(&self, key: &K) {
+        self.entries.lock().expect("cache lock poisoned").remove(&(org(), key.clone()));
+    }
+
+    /// Drop every cached value, for every organization.
+    pub fn invalidate_all(&self) {
+        self.entries.lock().expect("cache lock poisoned").clear();
+    }
+}
+
+/// The cached reads of a backend.
+///
+/// * `jobs`: Jobs, by ID
+/// * `job_progress`: The progress of jobs, by the ID of the job
+/// * `volunteers`: The details of volunteers, by ID
+pub struct StorageCache {
+    pub jobs: TtlCache<Uuid, Job>,
+    pub job_progress: TtlCache<Uuid, Option<JobProgress>>,
+    pub volunteers: TtlCache<Uuid, Option<VolunteerDetails>>,
+}
+
+impl StorageCache {
+    /// Create an empty cache.
+    ///
+    /// * `config`: How long entries are kept and how many
+    pub fn new(config: &CacheConfig) -> Self {
+        Self {
+            jobs: TtlCache::new(config),
+            job_progress: TtlCache::new(config),
+            volunteers: TtlCache::new(config),
+        }
+    }
+
+    /// Drop everything cached about a job.
+    ///
+    /// * `id`: The ID of the job
+    pub fn invalidate_job(&self, id: &Uuid) {
+        self.jobs.invalidate(id);
+        self.job_progress.invalidate(id);
+    }
+}
+
+impl PgBackend {
+    /// The cache to read through, if there is one and the query does not run in a transaction.
+    ///
+    /// * `exec_opts`: The execution options of the query
+    pub(in crate::services::storage) fn read_cache<DB: Database>(
+        &self,
+        exec_opts: &ExecOpts<DB>,
+    ) -> Option<&StorageCache> {
+        self.cache.as_ref().filter(|_| exec_opts.tx.is_none())
+    }
+
+    /// Drop cached entries that a write may have changed, if there is a cache.
+    ///
+    /// * `invalidate`: Drops the entries
+    pub(in crate::services::storage) fn invalidate_cache(
+        &self,
+        invalidate: impl FnOnce(&StorageCache),
+    ) {
+        if let Some(cache) = &self.cache {
+            invalidate(cache);
+        }
+    }
+}
@@ -7,7 +7,7 @@ use derive_builder::Builder;
 use sqlx::{Database, Postgres, Transaction};
 use uuid::Uuid;
 
-use super::exec_with_tx;
+use super::{exec_read_with_tx, exec_with_tx};
 use crate::services::storage::entities::ProjectCycle;
 use crate::services::storage::{Acquire, ExecOpts, PgBackend};
 
@@ -107,7 +107,7 @@ impl QueryCycles<Postgres> for PgBackend {
             Ok(cycles)
         }
 
-        exec_with_tx!(self, exec_opts, exec)
+        exec_read_with_tx!(self, exec_opts, exec)
     }
 
     async fn fetch_cycle_by_id(
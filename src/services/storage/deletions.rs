@@ -0,0 +1,262 @@
+//! This module contains the definition of the `QueryDeletions` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use derive_builder::Builder;
+use sqlx::{Database, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+
+use super::entities::WorkspaceDeletion;
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// Data needed to schedule the deletion of a volunteer's workspace account.
+///
+/// * `job_id`: The ID of the deprovisioning job
+/// * `volunteer_id`: The ID of the volunteer
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `delete_after`: When the grace period is over and the account may be deleted
+/// * `principal`: The email of the staff member who started the job
+#[derive(Builder, Debug, Clone)]
+pub struct ScheduleWorkspaceDeletion {
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    #[builder(setter(into))]
+    pub workspace_email: String,
+    pub delete_after: DateTime<Utc>,
+    #[builder(setter(into))]
+    pub principal: String,
+}
+
+/// A trait for querying the scheduled deletion of volunteers' workspace accounts.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryDeletions<DB: Database> {
+    /// Schedule volunteers' workspace accounts to be deleted. Accounts that are already scheduled
+    /// to be deleted keep their original schedule.
+    ///
+    /// * `data`: The deletions to schedule
+    /// * `exec_opts`: Execution options for the query
+    async fn schedule_workspace_deletions(
+        &self,
+        data: Vec<ScheduleWorkspaceDeletion>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Fetch the scheduled deletions whose grace period is over, oldest first.
+    ///
+    /// * `limit`: The most deletions to fetch
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_due_workspace_deletions(
+        &self,
+        limit: i64,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<WorkspaceDeletion>> {
+        unimplemented!()
+    }
+
+    /// Fetch every deletion scheduled for the workspace accounts of a project cycle's volunteers,
+    /// newest first.
+    ///
+    /// * `project_cycle_id`: The ID of the project cycle
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_workspace_deletions(
+        &self,
+        project_cycle_id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<WorkspaceDeletion>> {
+        unimplemented!()
+    }
+
+    /// Cancel a scheduled deletion. Returns `None` if there is no such deletion, or it is no
+    /// longer scheduled.
+    ///
+    /// * `id`: The ID of the deletion
+    /// * `cancelled_by`: The email of the staff member cancelling it
+    /// * `exec_opts`: Execution options for the query
+    async fn cancel_workspace_deletion(
+        &self,
+        id: Uuid,
+        cancelled_by: String,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Option<WorkspaceDeletion>> {
+        unimplemented!()
+    }
+
+    /// Record that a workspace account was deleted. The volunteer's export to that account is
+    /// removed too, so they can be exported again.
+    ///
+    /// * `id`: The ID of the deletion
+    /// * `exec_opts`: Execution options for the query
+    async fn complete_workspace_deletion(
+        &self,
+        id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Record that a workspace account could not be deleted.
+    ///
+    /// * `id`: The ID of the deletion
+    /// * `error`: Why the account could not be deleted
+    /// * `exec_opts`: Execution options for the query
+    async fn fail_workspace_deletion(
+        &self,
+        id: Uuid,
+        error: String,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryDeletions<Postgres> for PgBackend {
+    async fn schedule_workspace_deletions(
+        &self,
+        data: Vec<ScheduleWorkspaceDeletion>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            data: Vec<ScheduleWorkspaceDeletion>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            if data.is_empty() {
+                return Ok(());
+            }
+
+            let fragment =
+                include_str!("queries/deletions/schedule_workspace_deletions.fragment.sql");
+
+            QueryBuilder::<Postgres>::new(fragment)
+                .push_values(data, |mut b, d| {
+                    b.push_bind(d.job_id)
+                        .push_bind(d.volunteer_id)
+                        .push_bind(d.workspace_email)
+                        .push_bind(d.delete_after)
+                        .push_bind(d.principal);
+                })
+                .push(" on conflict (workspace_email) where status = 'scheduled' do nothing")
+                .build()
+                .execute(&mut **tx)
+                .await
+                .context("error scheduling workspace deletions")?;
+
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn fetch_due_workspace_deletions(
+        &self,
+        limit: i64,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<WorkspaceDeletion>> {
+        async fn exec(
+            limit: i64,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<WorkspaceDeletion>> {
+            let query = include_str!("queries/deletions/fetch_due_workspace_deletions.sql");
+            let deletions = sqlx::query_as::<_, WorkspaceDeletion>(query)
+                .bind(limit)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching due workspace deletions")?;
+            Ok(deletions)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, limit)
+    }
+
+    async fn fetch_workspace_deletions(
+        &self,
+        project_cycle_id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<WorkspaceDeletion>> {
+        async fn exec(
+            project_cycle_id: Uuid,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<WorkspaceDeletion>> {
+            let query = include_str!("queries/deletions/fetch_workspace_deletions.sql");
+            let deletions = sqlx::query_as::<_, WorkspaceDeletion>(query)
+                .bind(project_cycle_id)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching workspace deletions")?;
+            Ok(deletions)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, project_cycle_id)
+    }
+
+    async fn cancel_workspace_deletion(
+        &self,
+        id: Uuid,
+        cancelled_by: String,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Option<WorkspaceDeletion>> {
+        async fn exec(
+            id: Uuid,
+            cancelled_by: String,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Option<WorkspaceDeletion>> {
+            let query = include_str!("queries/deletions/cancel_workspace_deletion.sql");
+            let deletion = sqlx::query_as::<_, WorkspaceDeletion>(query)
+                .bind(id)
+                .bind(cancelled_by)
+                .fetch_optional(&mut **tx)
+                .await
+                .context("error cancelling workspace deletion")?;
+            Ok(deletion)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, id, cancelled_by)
+    }
+
+    async fn complete_workspace_deletion(
+        &self,
+        id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(id: Uuid, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+            let query = include_str!("queries/deletions/complete_workspace_deletion.sql");
+            sqlx::query(query)
+                .bind(id)
+                .execute(&mut **tx)
+                .await
+                .context("error completing workspace deletion")?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, id)
+    }
+
+    async fn fail_workspace_deletion(
+        &self,
+        id: Uuid,
+        error: String,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(id: Uuid, error: String, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+            let query = include_str!("queries/deletions/fail_workspace_deletion.sql");
+            sqlx::query(query)
+                .bind(id)
+                .bind(error)
+                .execute(&mut **tx)
+                .await
+                .context("error recording workspace deletion failure")?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, id, error)
+    }
+}
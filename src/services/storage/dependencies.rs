@@ -0,0 +1,128 @@
+//! This module contains the definition of the `QueryJobDependencies` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+//!
+//! A job can depend on another, in which case the queue does not run it until the other has
+//! completed. Jobs that depend on each other form a chain (e.g. an export, then a report on it). If
+//! a job in the chain fails for good, every job after it is cancelled.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::{Database, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::entities::ChainedJob;
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// A trait for querying the dependencies between jobs.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryJobDependencies<DB: Database> {
+    /// Make a job wait for another to complete before it runs.
+    ///
+    /// * `job_id`: The id of the job
+    /// * `depends_on`: The id of the job it waits for
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the job exists.
+    async fn set_job_dependency(
+        &self,
+        job_id: Uuid,
+        depends_on: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Fetch every job in the chain a job belongs to, from the first one, oldest first. A job that
+    /// depends on nothing and that nothing depends on is a chain of its own.
+    ///
+    /// * `job_id`: The id of any job in the chain
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_job_chain(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<ChainedJob>> {
+        unimplemented!()
+    }
+
+    /// Cancel the pending jobs whose dependency failed for good (it errored with no attempts left,
+    /// was cancelled, or was dead-lettered), and take them out of the queue. The jobs that depend
+    /// on the cancelled ones are cancelled the next time this is called.
+    ///
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns the ids of the jobs that were cancelled.
+    async fn cancel_jobs_with_failed_dependencies(
+        &self,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<Uuid>> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryJobDependencies<Postgres> for PgBackend {
+    async fn set_job_dependency(
+        &self,
+        job_id: Uuid,
+        depends_on: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<bool> {
+        async fn exec(
+            job_id: Uuid,
+            depends_on: Uuid,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<bool> {
+            let query = include_str!("queries/dependencies/set_job_dependency.sql");
+            let res = sqlx::query(query)
+                .bind(job_id)
+                .bind(depends_on)
+                .execute(&mut **tx)
+                .await
+                .with_context(|| format!("error making job {job_id} depend on job {depends_on}"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id, depends_on)
+    }
+
+    async fn fetch_job_chain(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<ChainedJob>> {
+        async fn exec(job_id: Uuid, tx: &mut Transaction<'_, Postgres>) -> Result<Vec<ChainedJob>> {
+            let query = include_str!("queries/dependencies/fetch_job_chain.sql");
+            let jobs = sqlx::query_as::<_, ChainedJob>(query)
+                .bind(job_id)
+                .fetch_all(&mut **tx)
+                .await
+                .with_context(|| format!("error fetching the chain of job {job_id}"))?;
+            Ok(jobs)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, job_id)
+    }
+
+    async fn cancel_jobs_with_failed_dependencies(
+        &self,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<Uuid>> {
+        async fn exec(tx: &mut Transaction<'_, Postgres>) -> Result<Vec<Uuid>> {
+            let query =
+                include_str!("queries/dependencies/cancel_jobs_with_failed_dependencies.sql");
+            let ids = sqlx::query_scalar::<_, Uuid>(query)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error cancelling jobs with failed dependencies")?;
+            Ok(ids)
+        }
+
+        exec_with_tx!(self, exec_opts, exec)
+    }
+}
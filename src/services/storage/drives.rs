@@ -0,0 +1,111 @@
+//! This module contains the definition of the `QuerySharedDrives` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use derive_builder::Builder;
+use sqlx::{Database, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::entities::WorkspaceSharedDrive;
+use super::exec_with_tx;
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// Data needed to record the shared drive provisioned for a cohort.
+///
+/// * `project_cycle_id`: The ID of the project cycle the cohort belongs to
+/// * `job_id`: The ID of the export that created the drive
+/// * `drive_id`: The ID Google gave the drive
+/// * `name`: The name of the drive
+#[derive(Builder, Debug, Clone)]
+pub struct RecordSharedDrive {
+    pub project_cycle_id: Uuid,
+    pub job_id: Uuid,
+    #[builder(setter(into))]
+    pub drive_id: String,
+    #[builder(setter(into))]
+    pub name: String,
+}
+
+/// A trait for querying the shared drives provisioned for cohorts.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QuerySharedDrives<DB: Database> {
+    /// Fetch the shared drive provisioned for a cohort, if there is one.
+    ///
+    /// * `project_cycle_id`: The ID of the project cycle the cohort belongs to
+    /// * `name`: The name of the drive
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_shared_drive(
+        &self,
+        project_cycle_id: Uuid,
+        name: &str,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Option<WorkspaceSharedDrive>> {
+        unimplemented!()
+    }
+
+    /// Record the shared drive provisioned for a cohort. If a drive with the same name was already
+    /// recorded for the project cycle, the existing record is kept.
+    ///
+    /// * `data`: The drive to record
+    /// * `exec_opts`: Execution options for the query
+    async fn record_shared_drive(
+        &self,
+        data: RecordSharedDrive,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QuerySharedDrives<Postgres> for PgBackend {
+    async fn fetch_shared_drive(
+        &self,
+        project_cycle_id: Uuid,
+        name: &str,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Option<WorkspaceSharedDrive>> {
+        async fn exec(
+            project_cycle_id: Uuid,
+            name: &str,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Option<WorkspaceSharedDrive>> {
+            let query = include_str!("queries/drives/fetch_shared_drive.sql");
+            let drive = sqlx::query_as::<_, WorkspaceSharedDrive>(query)
+                .bind(project_cycle_id)
+                .bind(name)
+                .fetch_optional(&mut **tx)
+                .await
+                .context("error fetching shared drive")?;
+            Ok(drive)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, project_cycle_id, name)
+    }
+
+    async fn record_shared_drive(
+        &self,
+        data: RecordSharedDrive,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(data: RecordSharedDrive, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+            let query = include_str!("queries/drives/record_shared_drive.sql");
+            sqlx::query(query)
+                .bind(data.project_cycle_id)
+                .bind(data.job_id)
+                .bind(data.drive_id)
+                .bind(data.name)
+                .execute(&mut **tx)
+                .await
+                .context("error recording shared drive")?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+}
@@ -0,0 +1,798 @@
+//! This module contains the definition of the `QueryEmails` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use derive_builder::Builder;
+use sqlx::{Database, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+
+use super::entities::{
+    EmailDeliveryCounts, EmailDeliveryEvent, EmailRetry, EmailSend, EmailSuppression,
+};
+use super::types::{EmailEventType, EmailSendStatus, EmailSuppressionReason};
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// Data needed to record an attempt to send an email.
+///
+/// * `job_id`: The ID of the job that sent the email, if a job sent it
+/// * `volunteer_id`: The ID of the volunteer the email was sent to, if it was sent to a volunteer
+/// * `recipient`: The address the email was sent to
+/// * `template`: The template the email was rendered from
+/// * `provider`: The mail provider that accepted the email, if one did
+/// * `message_id`: The mail provider's ID for the email, if it returned one
+/// * `status`: Whether a mail provider accepted the email
+/// * `error`: Why the email could not be sent, if it could not
+#[derive(Builder, Debug, Clone)]
+pub struct RecordEmailSend {
+    #[builder(default = "None")]
+    pub job_id: Option<Uuid>,
+    #[builder(default = "None")]
+    pub volunteer_id: Option<Uuid>,
+    #[builder(setter(into))]
+    pub recipient: String,
+    #[builder(setter(into))]
+    pub template: String,
+    #[builder(setter(into), default = "None")]
+    pub provider: Option<String>,
+    #[builder(setter(into), default = "None")]
+    pub message_id: Option<String>,
+    pub status: EmailSendStatus,
+    #[builder(setter(into), default = "None")]
+    pub error: Option<String>,
+}
+
+/// Data needed to queue an onboarding email to be sent again.
+///
+/// * `job_id`: The ID of the export job that sent the email
+/// * `volunteer_id`: The ID of the volunteer the email is sent to
+/// * `first_name`: The volunteer's first name
+/// * `last_name`: The volunteer's last name
+/// * `email`: The address the email is sent to
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `temporary_password`: The volunteer's temporary password, if the email delivers it
+/// * `locale`: The locale to render the email in
+#[derive(Builder, Debug, Clone)]
+pub struct CreateEmailRetry {
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    #[builder(setter(into))]
+    pub first_name: String,
+    #[builder(setter(into))]
+    pub last_name: String,
+    #[builder(setter(into))]
+    pub email: String,
+    #[builder(setter(into))]
+    pub workspace_email: String,
+    #[builder(setter(into), default = "None")]
+    pub temporary_password: Option<String>,
+    #[builder(setter(into))]
+    pub locale: String,
+}
+
+/// Data needed to add an address to the suppression list.
+///
+/// * `email`: The address to suppress. It is stored in lowercase.
+/// * `reason`: Why email should not be sent to the address
+/// * `provider`: The mail provider that reported the address, if one did
+/// * `detail`: What the provider said about the address (e.g. the bounce message), if anything
+/// * `suppressed_at`: When the address was suppressed
+#[derive(Builder, Debug, Clone, PartialEq)]
+pub struct CreateEmailSuppression {
+    #[builder(setter(into))]
+    pub email: String,
+    pub reason: EmailSuppressionReason,
+    #[builder(setter(into), default = "None")]
+    pub provider: Option<String>,
+    #[builder(setter(into), default = "None")]
+    pub detail: Option<String>,
+    #[builder(default = "Utc::now()")]
+    pub suppressed_at: DateTime<Utc>,
+}
+
+/// Data needed to record an event in the life of an email.
+///
+/// * `job_id`: The ID of the job that sent the email, if a job sent it. If it is not set, it is
+///   taken from an earlier event for the same message.
+/// * `volunteer_id`: The ID of the volunteer the email was sent to, if it was sent to a volunteer.
+///   If it is not set, it is taken from an earlier event for the same message.
+/// * `provider`: The mail provider that sent the email, if one has accepted it. If it is not set,
+///   it is taken from an earlier event for the same message.
+/// * `message_id`: The mail provider's ID for the email, if one has accepted it
+/// * `recipient`: The address the email was sent to
+/// * `event`: What happened to the email
+/// * `reason`: Why the email bounced or was dropped, if the provider said
+/// * `event_at`: When the event happened
+#[derive(Builder, Debug, Clone)]
+pub struct RecordEmailEvent {
+    #[builder(default = "None")]
+    pub job_id: Option<Uuid>,
+    #[builder(default = "None")]
+    pub volunteer_id: Option<Uuid>,
+    #[builder(setter(into), default = "None")]
+    pub provider: Option<String>,
+    #[builder(setter(into), default = "None")]
+    pub message_id: Option<String>,
+    #[builder(setter(into))]
+    pub recipient: String,
+    pub event: EmailEventType,
+    #[builder(setter(into), default = "None")]
+    pub reason: Option<String>,
+    #[builder(default = "Utc::now()")]
+    pub event_at: DateTime<Utc>,
+}
+
+/// A trait for querying the emails the application has sent.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryEmails<DB: Database> {
+    /// Record an attempt to send an email.
+    ///
+    /// * `data`: The attempt to record
+    /// * `exec_opts`: Execution options for the query
+    async fn record_email_send(
+        &self,
+        data: RecordEmailSend,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<EmailSend> {
+        unimplemented!()
+    }
+
+    /// Fetch every attempt a job made to send an email, oldest first.
+    ///
+    /// * `job_id`: The ID of the job
+    /// * `recipient`: Only fetch attempts to send to this address, if set
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_email_sends(
+        &self,
+        job_id: Uuid,
+        recipient: Option<String>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<EmailSend>> {
+        unimplemented!()
+    }
+
+    /// When an email was last sent from a compiled-in template, in any locale.
+    ///
+    /// * `template`: The name of the template without its locale or extension (e.g.
+    ///   `email/digest`)
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Only attempts a mail provider accepted are considered. Returns `None` if no email was ever
+    /// sent from the template.
+    async fn fetch_last_email_sent_at(
+        &self,
+        template: String,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Option<DateTime<Utc>>> {
+        unimplemented!()
+    }
+
+    /// Queue onboarding emails to be sent again. An email already queued for the same volunteer
+    /// and job is replaced, and its attempts start over.
+    ///
+    /// * `data`: The emails to queue
+    /// * `exec_opts`: Execution options for the query
+    async fn enqueue_email_retries(
+        &self,
+        data: Vec<CreateEmailRetry>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Fetch the queued emails that are due to be sent again, oldest first.
+    ///
+    /// * `job_id`: Only fetch emails sent by this job, if set
+    /// * `limit`: The most emails to fetch
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_due_email_retries(
+        &self,
+        job_id: Option<Uuid>,
+        limit: i64,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<EmailRetry>> {
+        unimplemented!()
+    }
+
+    /// Fetch every queued email sent by a job, including ones that have been given up on.
+    ///
+    /// * `job_id`: The ID of the job
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_email_retries(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<EmailRetry>> {
+        unimplemented!()
+    }
+
+    /// Record that sending a queued email failed again.
+    ///
+    /// * `id`: The ID of the queued email
+    /// * `error`: Why the email could not be sent
+    /// * `next_attempt_at`: When to try again. If `None`, the email is given up on until it is
+    ///   retried manually.
+    /// * `exec_opts`: Execution options for the query
+    async fn record_email_retry_failure(
+        &self,
+        id: Uuid,
+        error: String,
+        next_attempt_at: Option<DateTime<Utc>>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Remove an email from the queue, once it has been sent.
+    ///
+    /// * `id`: The ID of the queued email
+    /// * `exec_opts`: Execution options for the query
+    async fn delete_email_retry(&self, id: Uuid, exec_opts: &mut ExecOpts<DB>) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Make every queued email sent by a job due now, including ones that have been given up on,
+    /// and start their attempts over.
+    ///
+    /// * `job_id`: The ID of the job
+    /// * `exec_opts`: Execution options for the query
+    async fn reset_email_retries(&self, job_id: Uuid, exec_opts: &mut ExecOpts<DB>) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Add addresses to the suppression list. An address already on the list has its entry
+    /// replaced.
+    ///
+    /// * `data`: The addresses to suppress
+    /// * `exec_opts`: Execution options for the query
+    async fn suppress_emails(
+        &self,
+        data: Vec<CreateEmailSuppression>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Fetch the suppression list, most recently suppressed first.
+    ///
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_email_suppressions(
+        &self,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<EmailSuppression>> {
+        unimplemented!()
+    }
+
+    /// Fetch the entries on the suppression list for the given addresses. Addresses are matched
+    /// without regard to case.
+    ///
+    /// * `emails`: The addresses to look up
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_suppressed_emails(
+        &self,
+        emails: Vec<String>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<EmailSuppression>> {
+        unimplemented!()
+    }
+
+    /// Remove an address from the suppression list, so email is sent to it again.
+    ///
+    /// * `email`: The address to remove
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the address was on the list.
+    async fn delete_email_suppression(
+        &self,
+        email: String,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Record events in the lives of emails. Events that were already recorded for a message
+    /// (e.g. because the provider reported them twice) are skipped.
+    ///
+    /// * `data`: The events to record
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns how many events were recorded.
+    async fn record_email_events(
+        &self,
+        data: Vec<RecordEmailEvent>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<u64> {
+        unimplemented!()
+    }
+
+    /// Fetch the events in the life of an email, oldest first.
+    ///
+    /// * `message_id`: The mail provider's ID for the email
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_message_email_events(
+        &self,
+        message_id: String,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<EmailDeliveryEvent>> {
+        unimplemented!()
+    }
+
+    /// Fetch the events in the lives of the emails a job sent, oldest first.
+    ///
+    /// * `job_id`: The ID of the job
+    /// * `recipient`: Only fetch the events of emails sent to this address, if set
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_job_email_events(
+        &self,
+        job_id: Uuid,
+        recipient: Option<String>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<EmailDeliveryEvent>> {
+        unimplemented!()
+    }
+
+    /// Count a job's recipients by how far the delivery of their email got.
+    ///
+    /// * `job_id`: The ID of the job
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_email_delivery_counts(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<EmailDeliveryCounts> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryEmails<Postgres> for PgBackend {
+    async fn record_email_send(
+        &self,
+        data: RecordEmailSend,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<EmailSend> {
+        async fn exec(
+            data: RecordEmailSend,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<EmailSend> {
+            let query = include_str!("queries/emails/record_email_send.sql");
+            let send = sqlx::query_as::<_, EmailSend>(query)
+                .bind(data.job_id)
+                .bind(data.volunteer_id)
+                .bind(data.recipient)
+                .bind(data.template)
+                .bind(data.provider)
+                .bind(data.message_id)
+                .bind(data.status)
+                .bind(data.error)
+                .fetch_one(&mut **tx)
+                .await
+                .context("error recording email send")?;
+            Ok(send)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn fetch_email_sends(
+        &self,
+        job_id: Uuid,
+        recipient: Option<String>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<EmailSend>> {
+        async fn exec(
+            job_id: Uuid,
+            recipient: Option<String>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<EmailSend>> {
+            let query = include_str!("queries/emails/fetch_email_sends.sql");
+            let sends = sqlx::query_as::<_, EmailSend>(query)
+                .bind(job_id)
+                .bind(recipient)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching email sends")?;
+            Ok(sends)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, job_id, recipient)
+    }
+
+    async fn fetch_last_email_sent_at(
+        &self,
+        template: String,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Option<DateTime<Utc>>> {
+        async fn exec(
+            template: String,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Option<DateTime<Utc>>> {
+            let query = include_str!("queries/emails/fetch_last_email_sent_at.sql");
+            let sent_at = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(query)
+                .bind(template)
+                .fetch_one(&mut **tx)
+                .await
+                .context("error fetching when an email was last sent")?;
+            Ok(sent_at)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, template)
+    }
+
+    async fn enqueue_email_retries(
+        &self,
+        data: Vec<CreateEmailRetry>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            data: Vec<CreateEmailRetry>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            if data.is_empty() {
+                return Ok(());
+            }
+
+            let fragment = include_str!("queries/emails/enqueue_email_retries.fragment.sql");
+
+            QueryBuilder::<Postgres>::new(fragment)
+                .push_values(data, |mut b, r| {
+                    b.push_bind(r.job_id)
+                        .push_bind(r.volunteer_id)
+                        .push_bind(r.first_name)
+                        .push_bind(r.last_name)
+                        .push_bind(r.email)
+                        .push_bind(r.workspace_email)
+                        .push_bind(r.temporary_password)
+                        .push_bind(r.locale);
+                })
+                .push(
+                    " on conflict (job_id, volunteer_id) do update set first_name = \
+                     excluded.first_name, last_name = excluded.last_name, email = excluded.email, \
+                     workspace_email = excluded.workspace_email, temporary_password = \
+                     excluded.temporary_password, locale = excluded.locale, attempts = 0, \
+                     next_attempt_at = now(), exhausted = false, last_error = null",
+                )
+                .build()
+                .execute(&mut **tx)
+                .await
+                .context("error enqueueing email retries")?;
+
+            Ok(())
+        }
+
+        // The recovery email and temporary password are encrypted at rest
+        let data = data
+            .into_iter()
+            .map(|r| {
+                Ok(CreateEmailRetry {
+                    email: self.encrypt_field(r.email)?,
+                    temporary_password: r
+                        .temporary_password
+                        .map(|password| self.encrypt_field(password))
+                        .transpose()?,
+                    ..r
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn fetch_due_email_retries(
+        &self,
+        job_id: Option<Uuid>,
+        limit: i64,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<EmailRetry>> {
+        async fn exec(
+            job_id: Option<Uuid>,
+            limit: i64,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<EmailRetry>> {
+            let query = include_str!("queries/emails/fetch_due_email_retries.sql");
+            let retries = sqlx::query_as::<_, EmailRetry>(query)
+                .bind(job_id)
+                .bind(limit)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching due email retries")?;
+            Ok(retries)
+        }
+
+        let retries = exec_with_tx!(self, exec_opts, exec, job_id, limit)?;
+        retries.into_iter().map(|r| self.decrypt_email_retry(r)).collect()
+    }
+
+    async fn fetch_email_retries(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<EmailRetry>> {
+        async fn exec(job_id: Uuid, tx: &mut Transaction<'_, Postgres>) -> Result<Vec<EmailRetry>> {
+            let query = include_str!("queries/emails/fetch_email_retries.sql");
+            let retries = sqlx::query_as::<_, EmailRetry>(query)
+                .bind(job_id)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching email retries")?;
+            Ok(retries)
+        }
+
+        let retries = exec_read_with_tx!(self, exec_opts, exec, job_id)?;
+        retries.into_iter().map(|r| self.decrypt_email_retry(r)).collect()
+    }
+
+    async fn record_email_retry_failure(
+        &self,
+        id: Uuid,
+        error: String,
+        next_attempt_at: Option<DateTime<Utc>>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            id: Uuid,
+            error: String,
+            next_attempt_at: Option<DateTime<Utc>>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            let query = include_str!("queries/emails/record_email_retry_failure.sql");
+            sqlx::query(query)
+                .bind(id)
+                .bind(error)
+                .bind(next_attempt_at)
+                .execute(&mut **tx)
+                .await
+                .context("error recording email retry failure")?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, id, error, next_attempt_at)
+    }
+
+    async fn delete_email_retry(&self, id: Uuid, exec_opts: &mut ExecOpts<Postgres>) -> Result<()> {
+        async fn exec(id: Uuid, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+            let query = include_str!("queries/emails/delete_email_retry.sql");
+            sqlx::query(query)
+                .bind(id)
+                .execute(&mut **tx)
+                .await
+                .context("error deleting email retry")?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, id)
+    }
+
+    async fn reset_email_retries(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(job_id: Uuid, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+            let query = include_str!("queries/emails/reset_email_retries.sql");
+            sqlx::query(query)
+                .bind(job_id)
+                .execute(&mut **tx)
+                .await
+                .context("error resetting email retries")?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id)
+    }
+
+    async fn suppress_emails(
+        &self,
+        data: Vec<CreateEmailSuppression>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            data: Vec<CreateEmailSuppression>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            if data.is_empty() {
+                return Ok(());
+            }
+
+            let fragment = include_str!("queries/emails/suppress_emails.fragment.sql");
+
+            QueryBuilder::<Postgres>::new(fragment)
+                .push_values(data, |mut b, s| {
+                    b.push_bind(s.email.to_lowercase())
+                        .push_bind(s.reason)
+                        .push_bind(s.provider)
+                        .push_bind(s.detail)
+                        .push_bind(s.suppressed_at);
+                })
+                .push(
+                    " on conflict (org_id, email) do update set reason = excluded.reason, \
+                     provider = excluded.provider, detail = excluded.detail, suppressed_at = \
+                     excluded.suppressed_at",
+                )
+                .build()
+                .execute(&mut **tx)
+                .await
+                .context("error suppressing emails")?;
+
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn fetch_email_suppressions(
+        &self,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<EmailSuppression>> {
+        async fn exec(tx: &mut Transaction<'_, Postgres>) -> Result<Vec<EmailSuppression>> {
+            let query = include_str!("queries/emails/fetch_email_suppressions.sql");
+            let suppressions = sqlx::query_as::<_, EmailSuppression>(query)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching email suppressions")?;
+            Ok(suppressions)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec)
+    }
+
+    async fn fetch_suppressed_emails(
+        &self,
+        emails: Vec<String>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<EmailSuppression>> {
+        async fn exec(
+            emails: Vec<String>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<EmailSuppression>> {
+            let emails = emails.iter().map(|e| e.to_lowercase()).collect::<Vec<_>>();
+            let query = include_str!("queries/emails/fetch_suppressed_emails.sql");
+            let suppressions = sqlx::query_as::<_, EmailSuppression>(query)
+                .bind(emails)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching suppressed emails")?;
+            Ok(suppressions)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, emails)
+    }
+
+    async fn delete_email_suppression(
+        &self,
+        email: String,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<bool> {
+        async fn exec(email: String, tx: &mut Transaction<'_, Postgres>) -> Result<bool> {
+            let query = include_str!("queries/emails/delete_email_suppression.sql");
+            let deleted = sqlx::query(query)
+                .bind(email.to_lowercase())
+                .execute(&mut **tx)
+                .await
+                .context("error deleting email suppression")?;
+            Ok(deleted.rows_affected() > 0)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, email)
+    }
+
+    async fn record_email_events(
+        &self,
+        data: Vec<RecordEmailEvent>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<u64> {
+        async fn exec(
+            data: Vec<RecordEmailEvent>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<u64> {
+            let query = include_str!("queries/emails/record_email_event.sql");
+            let mut recorded = 0;
+            for e in data {
+                let res = sqlx::query(query)
+                    .bind(e.job_id)
+                    .bind(e.volunteer_id)
+                    .bind(e.provider)
+                    .bind(e.message_id)
+                    .bind(e.recipient)
+                    .bind(e.event)
+                    .bind(e.reason)
+                    .bind(e.event_at)
+                    .execute(&mut **tx)
+                    .await
+                    .context("error recording email event")?;
+                recorded += res.rows_affected();
+            }
+            Ok(recorded)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn fetch_message_email_events(
+        &self,
+        message_id: String,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<EmailDeliveryEvent>> {
+        async fn exec(
+            message_id: String,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<EmailDeliveryEvent>> {
+            let query = include_str!("queries/emails/fetch_message_email_events.sql");
+            let events = sqlx::query_as::<_, EmailDeliveryEvent>(query)
+                .bind(message_id)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching the events of an email")?;
+            Ok(events)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, message_id)
+    }
+
+    async fn fetch_job_email_events(
+        &self,
+        job_id: Uuid,
+        recipient: Option<String>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<EmailDeliveryEvent>> {
+        async fn exec(
+            job_id: Uuid,
+            recipient: Option<String>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<EmailDeliveryEvent>> {
+            let query = include_str!("queries/emails/fetch_job_email_events.sql");
+            let events = sqlx::query_as::<_, EmailDeliveryEvent>(query)
+                .bind(job_id)
+                .bind(recipient)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching the email events of a job")?;
+            Ok(events)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, job_id, recipient)
+    }
+
+    async fn fetch_email_delivery_counts(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<EmailDeliveryCounts> {
+        async fn exec(
+            job_id: Uuid,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<EmailDeliveryCounts> {
+            let query = include_str!("queries/emails/fetch_email_delivery_counts.sql");
+            let counts = sqlx::query_as::<_, EmailDeliveryCounts>(query)
+                .bind(job_id)
+                .fetch_one(&mut **tx)
+                .await
+                .context("error counting the email deliveries of a job")?;
+            Ok(counts)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, job_id)
+    }
+}
+
+impl PgBackend {
+    /// Decrypt the recovery email and temporary password of a queued onboarding email.
+    ///
+    /// * `retry`: The queued email as it was read from the database
+    fn decrypt_email_retry(&self, retry: EmailRetry) -> Result<EmailRetry> {
+        Ok(EmailRetry {
+            email: self.decrypt_field(retry.email)?,
+            temporary_password: retry
+                .temporary_password
+                .map(|password| self.decrypt_field(password))
+                .transpose()?,
+            ..retry
+        })
+    }
+}
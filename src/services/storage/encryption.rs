@@ -0,0 +1,237 @@
+//! Application-level encryption of personal data at rest.
+//!
+//! The copies of a volunteer's recovery email and temporary password that are kept for onboarding
+//! emails that have not been sent yet are encrypted before they are written and decrypted when
+//! they are read, so a copy of the database alone does not expose them. These are the only
+//! encrypted columns:
+//!
+//! * `onboarding_email_retries.email` and `onboarding_email_retries.temporary_password`
+//! * `email_outbox.email` and `email_outbox.temporary_password`
+//!
+//! Every other column is written in plaintext, including the other ones that hold recovery emails:
+//! `volunteers.email`, `email_sends.recipient`, `email_delivery_events.recipient`,
+//! `onboarding_email_deliveries.email`, and `email_suppressions.email`. Queries filter and join on
+//! them, which they could not do on encrypted values.
+//!
+//! Values are encrypted with envelope encryption. Each value is encrypted with its own random data
+//! key, and the data key is encrypted (wrapped) with a key encryption key from the app's
+//! configuration. Both use AES-256-GCM. An encrypted value is stored as
+//! `enc:v1:<key id>:<wrapped data key>:<ciphertext>`, where the last two parts are base64 and
+//! start with their nonce. The key id names the key encryption key that wrapped the data key, so
+//! keys can be rotated by configuring a new key first: new values are encrypted with it, and values
+//! written with the older keys can still be read.
+//!
+//! Values without the `enc:v1:` prefix were written before encryption was configured, and are
+//! read as they are.
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rand::RngCore;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+
+use crate::services::storage::PgBackend;
+
+/// The prefix of every encrypted value.
+pub const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// The length of keys, in bytes.
+pub const KEY_LEN: usize = 32;
+
+/// A key encryption key.
+///
+/// * `id`: The name the key is stored under in encrypted values
+/// * `key`: The key
+struct KeyEncryptionKey {
+    id: String,
+    key: LessSafeKey,
+}
+
+/// Encrypts and decrypts column values with a set of key encryption keys.
+///
+/// The first key encrypts new values. Every key decrypts the values it encrypted.
+pub struct FieldCipher {
+    keys: Vec<KeyEncryptionKey>,
+}
+
+impl std::fmt::Debug for FieldCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ids: Vec<&str> = self.keys.iter().map(|k| k.id.as_str()).collect();
+        f.debug_struct("FieldCipher").field("keys", &ids).finish()
+    }
+}
+
+impl FieldCipher {
+    /// Create a cipher from keys configured as `<id>:<base64 encoded 32 byte key>`.
+    ///
+    /// * `keys`: The keys, newest first
+    pub fn new(keys: &[String]) -> Result<Self> {
+        ensure!(!keys.is_empty(), "at least one encryption key is required");
+
+        let keys = keys
+            .iter()
+            .map(|raw| {
+                let (id, key) = raw
+                    .split_once(':')
+                    .ok_or_else(|| anyhow!("encryption keys must look like <id>:<base64 key>"))?;
+                let id = id.trim();
+                ensure!(
+                    !id.is_empty() && !id.contains(':'),
+                    "encryption key ids must not be empty or contain colons"
+                );
+                let key = BASE64
+                    .decode(key.trim())
+                    .with_context(|| format!("encryption key {id} is not valid base64"))?;
+                ensure!(key.len() == KEY_LEN, "encryption key {id} must be {KEY_LEN} bytes");
+                Ok(KeyEncryptionKey { id: id.to_owned(), key: aead_key(&key)? })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for (i, key) in keys.iter().enumerate() {
+            ensure!(
+                !keys[..i].iter().any(|k| k.id == key.id),
+                "encryption key {} is configured more than once",
+                key.id
+            );
+        }
+
+        Ok(Self { keys })
+    }
+
+    /// Encrypt a value with a new data key, wrapped with the newest key encryption key.
+    ///
+    /// * `plaintext`: The value to encrypt
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        self.encrypt_bytes(plaintext.as_bytes())
+    }
+
+    /// Encrypt bytes the same way as a value, e.g. a file that is stored outside the database.
+    ///
+    /// * `plaintext`: The bytes to encrypt
+    pub fn encrypt_bytes(&self, plaintext: &[u8]) -> Result<String> {
+        let kek = &self.keys[0];
+
+        let mut data_key = [0u8; KEY_LEN];
+        rand::thread_rng().fill_bytes(&mut data_key);
+
+        let wrapped = seal(&kek.key, kek.id.as_bytes(), &data_key)?;
+        let ciphertext = seal(&aead_key(&data_key)?, kek.id.as_bytes(), plaintext)?;
+
+        Ok(format!(
+            "{ENCRYPTED_PREFIX}{}:{}:{}",
+            kek.id,
+            BASE64.encode(wrapped),
+            BASE64.encode(ciphertext)
+        ))
+    }
+
+    /// Decrypt a value. Values that are not encrypted are returned as they are.
+    ///
+    /// * `stored`: The value as it is stored in the database
+    pub fn decrypt(&self, stored: &str) -> Result<String> {
+        if !stored.starts_with(ENCRYPTED_PREFIX) {
+            return Ok(stored.to_owned());
+        }
+
+        let plaintext = self.decrypt_bytes(stored)?;
+        String::from_utf8(plaintext).context("decrypted value is not valid UTF-8")
+    }
+
+    /// Decrypt bytes encrypted by `encrypt_bytes`. Unlike `decrypt`, this fails if they are not
+    /// encrypted.
+    ///
+    /// * `stored`: The encrypted bytes, as `encrypt_bytes` returned them
+    pub fn decrypt_bytes(&self, stored: &str) -> Result<Vec<u8>> {
+        let Some(envelope) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+            bail!("value is not encrypted");
+        };
+
+        let mut parts = envelope.splitn(3, ':');
+        let (Some(id), Some(wrapped), Some(ciphertext)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            bail!("encrypted value is malformed");
+        };
+
+        let kek = self
+            .keys
+            .iter()
+            .find(|k| k.id == id)
+            .ok_or_else(|| anyhow!("encryption key {id} is not configured"))?;
+
+        let wrapped = BASE64.decode(wrapped).context("encrypted data key is not valid base64")?;
+        let data_key = open(&kek.key, id.as_bytes(), wrapped)
+            .with_context(|| format!("error unwrapping data key with encryption key {id}"))?;
+
+        let ciphertext = BASE64.decode(ciphertext).context("ciphertext is not valid base64")?;
+        open(&aead_key(&data_key)?, id.as_bytes(), ciphertext).context("error decrypting value")
+    }
+}
+
+impl PgBackend {
+    /// Encrypt a column value, if encryption is configured.
+    ///
+    /// * `value`: The value to write
+    pub(in crate::services::storage) fn encrypt_field(&self, value: String) -> Result<String> {
+        match &self.cipher {
+            Some(cipher) => cipher.encrypt(&value),
+            None => Ok(value),
+        }
+    }
+
+    /// Decrypt a column value that may have been encrypted.
+    ///
+    /// * `stored`: The value as it is stored in the database
+    pub(in crate::services::storage) fn decrypt_field(&self, stored: String) -> Result<String> {
+        match &self.cipher {
+            Some(cipher) => cipher.decrypt(&stored),
+            None if stored.starts_with(ENCRYPTED_PREFIX) => {
+                bail!("an encrypted value was read, but no encryption keys are configured")
+            }
+            None => Ok(stored),
+        }
+    }
+}
+
+/// Build an AES-256-GCM key.
+///
+/// * `key`: The raw key
+fn aead_key(key: &[u8]) -> Result<LessSafeKey> {
+    let key = UnboundKey::new(&AES_256_GCM, key).map_err(|_| anyhow!("invalid encryption key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+/// Encrypt bytes under a random nonce, which is prepended to the ciphertext.
+///
+/// * `key`: The key to encrypt with
+/// * `aad`: Data that is authenticated along with the plaintext but not encrypted
+/// * `plaintext`: The bytes to encrypt
+fn seal(key: &LessSafeKey, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let mut in_out = plaintext.to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(aad), &mut in_out)
+        .map_err(|_| anyhow!("encryption failed"))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend(in_out);
+    Ok(sealed)
+}
+
+/// Decrypt bytes sealed by `seal`.
+///
+/// * `key`: The key to decrypt with
+/// * `aad`: The data that was authenticated along with the plaintext
+/// * `sealed`: The nonce followed by the ciphertext
+fn open(key: &LessSafeKey, aad: &[u8], sealed: Vec<u8>) -> Result<Vec<u8>> {
+    ensure!(sealed.len() > NONCE_LEN, "encrypted value is too short");
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| anyhow!("invalid nonce"))?;
+
+    let mut in_out = ciphertext.to_vec();
+    let plaintext = key
+        .open_in_place(nonce, Aad::from(aad), &mut in_out)
+        .map_err(|_| anyhow!("decryption failed, the value or key is wrong"))?;
+    Ok(plaintext.to_vec())
+}
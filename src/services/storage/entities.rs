@@ -7,8 +7,11 @@ use sqlx::FromRow;
 use uuid::Uuid;
 
 use super::types::{
-    AgeRange, ClientSize, Ethnicity, Fli, Gender, ImpactCause, JobStatus, Lgbt,
-    MentorExperienceLevel, MentorYearsExperience, StudentStage, VolunteerHearAbout,
+    ActivationReminderStatus, AgeRange, AuditOperation, ClientSize, EmailDeliveryStatus,
+    EmailEventType, EmailSendStatus, EmailSuppressionReason, Ethnicity, Fli, Gender, ImpactCause,
+    JobPhase, JobPriority, JobStatus, Lgbt, MentorExperienceLevel, MentorYearsExperience,
+    StudentStage, VolunteerHearAbout, WorkspaceDeletionStatus, WorkspaceExportStatus,
+    WorkspaceSuspensionAction, WorkspaceTransferStatus,
 };
 
 /// How a project cycle is represented in the database.
@@ -30,6 +33,21 @@ pub struct ProjectCycle {
     pub archived: bool,
 }
 
+/// An organization that shares the deployment.
+///
+/// * `id`: The ID of the organization
+/// * `created_at`: When the organization was created
+/// * `updated_at`: When the organization was last updated, if it was ever updated
+/// * `name`: The name of the organization
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Organization {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub name: String,
+}
+
 /// How a volunteer is represented in the database.
 ///
 /// * `id`: The id of the volunteer
@@ -295,6 +313,7 @@ pub struct NonprofitClient {
 /// * `description`: A friendly description of the job, if it exists
 /// * `details`: Details about the job, stored as a JSON object. The `types` module contains more
 ///   information about the possible values of this field.
+/// * `version`: How many times the job's status has changed
 #[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Job {
@@ -306,6 +325,36 @@ pub struct Job {
     pub label: String,
     pub description: Option<String>,
     pub details: Value,
+    pub version: i32,
+}
+
+/// A summary of an archived job, which is all that is left of it in the `jobs` table. Its full
+/// details are in the archive.
+///
+/// * `id`: The id of the job
+/// * `created_at`: When the job was created
+/// * `updated_at`: When the job was last updated, if it was ever updated
+/// * `archived_at`: When the job was archived
+/// * `project_cycle_id`: The id of project cycle this job is associated with, if it is associated
+///    with a project cycle.
+/// * `status`: The status the job finished with
+/// * `label`: A friendly label for the job
+/// * `description`: A friendly description of the job, if it exists
+/// * `job_type`: The type of the job
+/// * `error`: An error message if the job failed
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedJob {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub archived_at: DateTime<Utc>,
+    pub project_cycle_id: Option<Uuid>,
+    pub status: JobStatus,
+    pub label: String,
+    pub description: Option<String>,
+    pub job_type: Option<String>,
+    pub error: Option<String>,
 }
 
 /// How a `mentor_details` view is represented in the database.
@@ -363,6 +412,27 @@ pub struct BasicStats {
     pub num_mentors: i64,
 }
 
+/// A summary of the exports to Google Workspace over a period of time
+///
+/// * `jobs_run`: The number of export jobs started
+/// * `jobs_completed`: The number of export jobs that finished
+/// * `jobs_failed`: The number of export jobs that ended with an error
+/// * `users_exported`: The number of workspace accounts created for volunteers
+/// * `emails_sent`: The number of emails export jobs sent
+/// * `emails_failed`: The number of emails export jobs failed to send
+/// * `emails_bounced`: The number of onboarding emails the mail provider reported as bounced
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportDigest {
+    pub jobs_run: i64,
+    pub jobs_completed: i64,
+    pub jobs_failed: i64,
+    pub users_exported: i64,
+    pub emails_sent: i64,
+    pub emails_failed: i64,
+    pub emails_bounced: i64,
+}
+
 #[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportedVolunteerDetails {
@@ -376,3 +446,717 @@ pub struct ExportedVolunteerDetails {
     pub project_cycle_id: Uuid,
     pub status: JobStatus,
 }
+
+/// An extra address an export added to a volunteer's workspace account.
+///
+/// * `id`: The ID of the record
+/// * `created_at`: When the alias was added
+/// * `volunteer_id`: The ID of the volunteer
+/// * `job_id`: The ID of the export job that added the alias
+/// * `workspace_email`: The primary email of the account the alias belongs to
+/// * `alias`: The alias (e.g. `jane@developforgood.org` for `jane.doe@developforgood.org`)
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceAlias {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub volunteer_id: Uuid,
+    pub job_id: Uuid,
+    pub workspace_email: String,
+    pub alias: String,
+}
+
+/// How a volunteer's progress within a workspace export job is represented in the database.
+///
+/// * `job_id`: The id of the export job
+/// * `volunteer_id`: The id of the volunteer being exported
+/// * `created_at`: When the checkpoint was created
+/// * `updated_at`: When the checkpoint was last updated, if it was ever updated
+/// * `workspace_email`: The workspace email the volunteer is being issued
+/// * `org_unit`: The org unit the volunteer is being exported to
+/// * `status`: How far the volunteer has gotten in the export
+/// * `error`: Why creating the volunteer failed, if it failed
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportCheckpoint {
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub workspace_email: String,
+    pub org_unit: String,
+    pub status: WorkspaceExportStatus,
+    pub error: Option<String>,
+}
+
+/// How the progress of a running job is represented in the database.
+///
+/// * `job_id`: The id of the job
+/// * `created_at`: When the job first reported progress
+/// * `updated_at`: When the job last reported progress, if it reported progress more than once
+/// * `phase`: The phase the job is in
+/// * `processed`: How many items the job has processed in the current phase
+/// * `total`: How many items the job has to process in the current phase
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JobProgress {
+    pub job_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub phase: JobPhase,
+    pub processed: i32,
+    pub total: i32,
+}
+
+/// How a version of an email template is represented in the database.
+///
+/// * `id`: The id of the template version
+/// * `created_at`: When the version was saved
+/// * `name`: The name of the template (e.g. `onboard`)
+/// * `version`: The version number, starting at 1 and increasing with every edit
+/// * `subject`: The subject line of emails rendered from the template
+/// * `body`: The Tera source of the email body
+/// * `created_by`: The email of the user who saved the version
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailTemplate {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub name: String,
+    pub version: i32,
+    pub subject: String,
+    pub body: String,
+    pub created_by: String,
+}
+
+/// How the delivery of an onboarding email is represented in the database.
+///
+/// * `job_id`: The id of the export job that sent the email
+/// * `volunteer_id`: The id of the volunteer the email was sent to
+/// * `created_at`: When the first event about the email was recorded
+/// * `updated_at`: When the latest event about the email was recorded, if there was more than one
+/// * `email`: The address the email was sent to
+/// * `status`: What the mail provider last reported about the email
+/// * `reason`: Why the email bounced or was dropped, if it was
+/// * `message_id`: The mail provider's ID for the email, if it reported one
+/// * `event_at`: When the latest event happened, according to the mail provider
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingEmailDelivery {
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub email: String,
+    pub status: EmailDeliveryStatus,
+    pub reason: Option<String>,
+    pub message_id: Option<String>,
+    pub event_at: DateTime<Utc>,
+}
+
+/// How an attempt to send an email is represented in the database.
+///
+/// * `id`: The id of the attempt
+/// * `created_at`: When the attempt was made
+/// * `job_id`: The id of the job that sent the email, if a job sent it
+/// * `volunteer_id`: The id of the volunteer the email was sent to, if it was sent to a volunteer
+/// * `recipient`: The address the email was sent to
+/// * `template`: The template the email was rendered from
+/// * `provider`: The mail provider that accepted the email, if one did
+/// * `message_id`: The mail provider's ID for the email, if it returned one
+/// * `status`: Whether a mail provider accepted the email
+/// * `error`: Why the email could not be sent, if it could not
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailSend {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub job_id: Option<Uuid>,
+    pub volunteer_id: Option<Uuid>,
+    pub recipient: String,
+    pub template: String,
+    pub provider: Option<String>,
+    pub message_id: Option<String>,
+    pub status: EmailSendStatus,
+    pub error: Option<String>,
+}
+
+/// How an event in the life of an email is represented in the database.
+///
+/// * `id`: The id of the event
+/// * `created_at`: When the event was recorded
+/// * `job_id`: The id of the job that sent the email, if a job sent it
+/// * `volunteer_id`: The id of the volunteer the email was sent to, if it was sent to a volunteer
+/// * `provider`: The mail provider that sent the email, once one accepted it
+/// * `message_id`: The mail provider's ID for the email, once one accepted it
+/// * `recipient`: The address the email was sent to
+/// * `event`: What happened to the email
+/// * `reason`: Why the email bounced or was dropped, if the provider said
+/// * `event_at`: When the event happened
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailDeliveryEvent {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub job_id: Option<Uuid>,
+    pub volunteer_id: Option<Uuid>,
+    pub provider: Option<String>,
+    pub message_id: Option<String>,
+    pub recipient: String,
+    pub event: EmailEventType,
+    pub reason: Option<String>,
+    pub event_at: DateTime<Utc>,
+}
+
+/// How many of a job's recipients are at each step of the delivery of their email, by the latest
+/// event recorded for each. An email that was queued again (e.g. retried) counts once.
+///
+/// * `queued`: The number of recipients whose email was queued but not yet accepted by a provider
+/// * `sent`: The number of recipients whose email a provider accepted, with no later news
+/// * `delivered`: The number of recipients whose email was delivered
+/// * `bounced`: The number of recipients whose email bounced
+/// * `dropped`: The number of recipients whose email the provider refused to send
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailDeliveryCounts {
+    pub queued: i64,
+    pub sent: i64,
+    pub delivered: i64,
+    pub bounced: i64,
+    pub dropped: i64,
+}
+
+/// How a queued onboarding email is represented in the database.
+///
+/// * `id`: The id of the queued email
+/// * `created_at`: When the email was queued
+/// * `updated_at`: When the email was last attempted, if it has been
+/// * `job_id`: The id of the export job that sent the email
+/// * `volunteer_id`: The id of the volunteer the email is sent to
+/// * `first_name`: The volunteer's first name
+/// * `last_name`: The volunteer's last name
+/// * `email`: The address the email is sent to
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `temporary_password`: The volunteer's temporary password, if the email delivers it
+/// * `locale`: The locale to render the email in
+/// * `attempts`: How many times the email has been sent again and failed
+/// * `next_attempt_at`: When the email is next due to be sent
+/// * `exhausted`: Whether the email has been given up on until it is retried manually
+/// * `last_error`: Why the last attempt failed, if one has
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailRetry {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub workspace_email: String,
+    #[serde(skip_serializing)]
+    pub temporary_password: Option<String>,
+    pub locale: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub exhausted: bool,
+    pub last_error: Option<String>,
+}
+
+/// How an address on the suppression list is represented in the database.
+///
+/// * `email`: The suppressed address, in lowercase
+/// * `created_at`: When the address was added to the suppression list
+/// * `updated_at`: When the entry was last updated, if it has been
+/// * `reason`: Why email is not sent to the address
+/// * `provider`: The mail provider that reported the address, if one did
+/// * `detail`: What the provider said about the address (e.g. the bounce message), if anything
+/// * `suppressed_at`: When the address was suppressed, which may be before it was added to the list
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailSuppression {
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub reason: EmailSuppressionReason,
+    pub provider: Option<String>,
+    pub detail: Option<String>,
+    pub suppressed_at: DateTime<Utc>,
+}
+
+/// How a project cycle's activation reminder settings are represented in the database.
+///
+/// * `project_cycle_id`: The id of the project cycle
+/// * `created_at`: When reminders were first configured for the cycle
+/// * `updated_at`: When the settings were last updated, if they have been
+/// * `enabled`: Whether reminders are sent
+/// * `remind_after_days`: How many days after a volunteer's workspace account is created to remind
+///   them, if they have not signed in
+/// * `locale`: The locale to send reminders in
+/// * `principal`: The email of the staff member who configured reminders. Workspace lookups for
+///   the cycle are made as them.
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivationReminderSettings {
+    pub project_cycle_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub enabled: bool,
+    pub remind_after_days: i32,
+    pub locale: String,
+    pub principal: String,
+}
+
+/// An exported volunteer who is due to be checked for an activation reminder.
+///
+/// * `project_cycle_id`: The id of the volunteer's project cycle
+/// * `job_id`: The id of the export job that created the volunteer's workspace account
+/// * `volunteer_id`: The id of the volunteer
+/// * `first_name`: The volunteer's first name
+/// * `last_name`: The volunteer's last name
+/// * `email`: The volunteer's personal email address, which the reminder is sent to
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `exported_at`: When the volunteer's workspace account was recorded
+/// * `locale`: The locale to send the reminder in
+/// * `principal`: The email of the staff member workspace lookups are made as
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DueActivationReminder {
+    pub project_cycle_id: Uuid,
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub workspace_email: String,
+    pub exported_at: DateTime<Utc>,
+    pub locale: String,
+    pub principal: String,
+}
+
+/// An exported volunteer whose workspace account an offboarding job acts on.
+///
+/// * `volunteer_id`: The id of the volunteer
+/// * `first_name`: The volunteer's first name
+/// * `last_name`: The volunteer's last name
+/// * `workspace_email`: The volunteer's workspace email address
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SuspensionCandidate {
+    pub volunteer_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub workspace_email: String,
+}
+
+/// An exported volunteer whose Pantheon profile a sync job pushes to their workspace account.
+///
+/// * `volunteer_id`: The id of the volunteer
+/// * `first_name`: The volunteer's first name
+/// * `last_name`: The volunteer's last name
+/// * `email`: The volunteer's personal email, which is their account's recovery email
+/// * `workspace_email`: The volunteer's workspace email address
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSyncCandidate {
+    pub volunteer_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub workspace_email: String,
+}
+
+/// How an offboarding job's action on a volunteer's workspace account is represented in the
+/// database.
+///
+/// * `id`: The id of the record
+/// * `created_at`: When the action was taken
+/// * `job_id`: The id of the offboarding job
+/// * `volunteer_id`: The id of the volunteer
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `action`: Whether the account was suspended or unsuspended
+/// * `succeeded`: Whether the action worked
+/// * `error`: Why the action failed, if it did
+/// * `principal`: The email of the staff member who started the job
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceSuspension {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    pub workspace_email: String,
+    pub action: WorkspaceSuspensionAction,
+    pub succeeded: bool,
+    pub error: Option<String>,
+    pub principal: String,
+}
+
+/// How a scheduled deletion of a volunteer's workspace account is represented in the database.
+///
+/// * `id`: The id of the record
+/// * `created_at`: When the deletion was scheduled
+/// * `updated_at`: When the deletion was last updated, if it was ever updated
+/// * `job_id`: The id of the deprovisioning job that scheduled it
+/// * `volunteer_id`: The id of the volunteer
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `delete_after`: When the grace period is over and the account may be deleted
+/// * `status`: Whether the deletion is still scheduled, or was cancelled, carried out, or failed
+/// * `error`: Why the account could not be deleted, if it could not
+/// * `principal`: The email of the staff member who started the job
+/// * `cancelled_by`: The email of the staff member who cancelled the deletion, if anyone did
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceDeletion {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    pub workspace_email: String,
+    pub delete_after: DateTime<Utc>,
+    pub status: WorkspaceDeletionStatus,
+    pub error: Option<String>,
+    pub principal: String,
+    pub cancelled_by: Option<String>,
+}
+
+/// How a transfer of a volunteer's Drive files to an archive account is represented in the
+/// database.
+///
+/// * `id`: The id of the record
+/// * `created_at`: When the transfer was recorded
+/// * `updated_at`: When the transfer was last updated, if it was ever updated
+/// * `job_id`: The id of the job that started the transfer
+/// * `volunteer_id`: The id of the volunteer
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `archive_email`: The email of the account the files are transferred to
+/// * `transfer_id`: The id Google gave the transfer, once it has been started
+/// * `status`: Whether the transfer is pending, in progress, completed, or failed
+/// * `error`: Why the transfer failed, if it did
+/// * `suspended`: Whether the volunteer's account was suspended once the transfer completed
+/// * `principal`: The email of the staff member who started the job
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceDriveTransfer {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    pub workspace_email: String,
+    pub archive_email: String,
+    pub transfer_id: Option<String>,
+    pub status: WorkspaceTransferStatus,
+    pub error: Option<String>,
+    pub suspended: bool,
+    pub principal: String,
+}
+
+/// How the shared drive provisioned for a cohort is represented in the database.
+///
+/// * `id`: The id of the record
+/// * `created_at`: When the drive was created
+/// * `project_cycle_id`: The id of the project cycle the cohort belongs to
+/// * `job_id`: The id of the export that created the drive
+/// * `drive_id`: The id Google gave the drive
+/// * `name`: The name of the drive
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceSharedDrive {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub project_cycle_id: Uuid,
+    pub job_id: Uuid,
+    pub drive_id: String,
+    pub name: String,
+}
+
+/// How the check of an exported volunteer for an activation reminder is represented in the
+/// database.
+///
+/// * `id`: The id of the check
+/// * `created_at`: When the volunteer was checked
+/// * `job_id`: The id of the export job that created the volunteer's workspace account
+/// * `volunteer_id`: The id of the volunteer
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `status`: What happened when the volunteer was checked
+/// * `detail`: More about what happened (e.g. why the volunteer was skipped), if anything
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivationReminder {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    pub workspace_email: String,
+    pub status: ActivationReminderStatus,
+    pub detail: Option<String>,
+}
+
+/// When an exported volunteer last signed in to their workspace account, as of the last check.
+///
+/// * `volunteer_id`: The id of the volunteer
+/// * `first_name`: The volunteer's first name
+/// * `last_name`: The volunteer's last name
+/// * `email`: The volunteer's personal email address
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `exported_at`: When the volunteer's workspace account was recorded
+/// * `last_login_at`: When the volunteer last signed in, or `None` if they had not when checked
+/// * `checked_at`: When the account was last checked, or `None` if it never has been
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceLogin {
+    pub volunteer_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub workspace_email: String,
+    pub exported_at: DateTime<Utc>,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+/// How a change recorded in the audit log is represented in the database.
+///
+/// * `id`: The id of the record
+/// * `created_at`: When the change was made
+/// * `principal`: The email of the user who made the change, if it was made on a user's behalf
+/// * `entity`: The table the changed row belongs to
+/// * `entity_id`: The id of the changed row, if its table has one
+/// * `operation`: Whether the row was inserted, updated, or deleted
+/// * `before`: The row before the change, unless it was inserted
+/// * `after`: The row after the change, unless it was deleted
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogEntry {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub principal: Option<String>,
+    pub entity: String,
+    pub entity_id: Option<String>,
+    pub operation: AuditOperation,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// How many rows of each kind a restore brought back. Rows that already existed are not counted.
+///
+/// * `project_cycles`: The number of project cycles restored
+/// * `volunteers`: The number of volunteers restored
+/// * `exported_volunteers`: The number of records of volunteers exported to Workspace restored
+/// * `jobs`: The number of jobs restored
+/// * `job_archives`: The number of archived job details restored
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupCounts {
+    pub project_cycles: i64,
+    pub volunteers: i64,
+    pub exported_volunteers: i64,
+    pub jobs: i64,
+    pub job_archives: i64,
+}
+
+/// How an onboarding email waiting in the outbox is represented in the database.
+///
+/// * `id`: The id of the email
+/// * `created_at`: When the email was written to the outbox
+/// * `updated_at`: When the email was last claimed, if it has been since it was written
+/// * `job_id`: The id of the export job that sends the email
+/// * `volunteer_id`: The id of the volunteer the email is sent to
+/// * `first_name`: The volunteer's first name
+/// * `last_name`: The volunteer's last name
+/// * `email`: The address the email is sent to
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `temporary_password`: The volunteer's temporary password, if the email delivers it
+/// * `locale`: The locale to render the email in
+/// * `onboarding_session_link`: The link to the onboarding session the volunteer is invited to, if
+///   any
+/// * `claimed_by`: The ID of the claim of whoever is sending the email, if anyone has claimed it
+/// * `claimed_until`: When the claim on the email runs out, and the relay may send it
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxEmail {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub workspace_email: String,
+    #[serde(skip_serializing)]
+    pub temporary_password: Option<String>,
+    pub locale: String,
+    pub onboarding_session_link: Option<String>,
+    pub claimed_by: Option<Uuid>,
+    pub claimed_until: DateTime<Utc>,
+}
+
+/// How a setting is represented in the database.
+///
+/// * `key`: The name of the setting (e.g. `defaultOrgUnit`)
+/// * `value`: The value of the setting
+/// * `created_at`: When the setting was first saved
+/// * `updated_at`: When the setting was last changed, if it has been
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredSetting {
+    pub key: String,
+    pub value: Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// How a snapshot of a job is represented in the database.
+///
+/// * `id`: The id of the snapshot
+/// * `job_id`: The id of the job the snapshot was taken of
+/// * `created_at`: When the snapshot was taken
+/// * `created_by`: Who took the snapshot, if it was taken by a user
+/// * `snapshot`: The state of the job when the snapshot was taken
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JobSnapshot {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Option<String>,
+    pub snapshot: Value,
+}
+
+/// A snapshot of a job without its contents, for listing the snapshots of a job.
+///
+/// * `id`: The id of the snapshot
+/// * `job_id`: The id of the job the snapshot was taken of
+/// * `created_at`: When the snapshot was taken
+/// * `created_by`: Who took the snapshot, if it was taken by a user
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JobSnapshotSummary {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub created_by: Option<String>,
+}
+
+/// How an event emitted by a job is represented in the database.
+///
+/// * `id`: The id of the event, which increases with every event emitted
+/// * `job_id`: The id of the job that emitted the event
+/// * `created_at`: When the event was emitted
+/// * `event`: What happened
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JobEvent {
+    pub id: i64,
+    pub job_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub event: Value,
+}
+
+/// How a job in the job queue is represented in the database.
+///
+/// * `job_id`: The id of the job
+/// * `created_at`: When the job was queued
+/// * `updated_at`: When the job was last claimed or finished, if it has been since it was queued
+/// * `payload`: What the job needs to run
+/// * `priority`: How urgently the job should run
+/// * `run_at`: When the job may run at the earliest
+/// * `attempts`: How many times a worker has claimed the job
+/// * `claimed_by`: The ID of the worker running the job, if one is
+/// * `claimed_until`: When the claim on the job runs out, and another worker may run it
+/// * `finished_at`: When the job finished, if it has
+/// * `error`: Why the job failed, if it did
+/// * `attempt_errors`: The error chain of every failed attempt, oldest first
+/// * `dead_lettered_at`: When the job was dead-lettered after failing on every attempt, if it was
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedJob {
+    pub job_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub payload: Value,
+    pub priority: JobPriority,
+    pub run_at: DateTime<Utc>,
+    pub attempts: i32,
+    pub claimed_by: Option<Uuid>,
+    pub claimed_until: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+    pub attempt_errors: Value,
+    pub dead_lettered_at: Option<DateTime<Utc>>,
+}
+
+/// How a webhook call made when a job finished is represented in the database.
+///
+/// * `id`: The id of the call
+/// * `job_id`: The id of the job that finished
+/// * `status`: The status the job finished with
+/// * `created_at`: When the job finished, and the call was queued
+/// * `attempts`: How many times the call has been made
+/// * `next_attempt_at`: When the call is next due
+/// * `finished_at`: When the call succeeded or was given up on, if it has been
+/// * `error`: Why the call last failed, if it did
+/// * `callback_url`: The URL registered for the job, if one was
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JobWebhook {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub status: JobStatus,
+    pub created_at: DateTime<Utc>,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+    pub callback_url: Option<String>,
+}
+
+/// A job in a chain of jobs that depend on each other.
+///
+/// * `id`: The id of the job
+/// * `created_at`: When the job was created
+/// * `label`: A friendly label for the job
+/// * `status`: The status of the job
+/// * `depends_on`: The id of the job that has to complete before this one runs, if there is one
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainedJob {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub label: String,
+    pub status: JobStatus,
+    pub depends_on: Option<Uuid>,
+}
+
+/// How a job that runs on a cron schedule is represented in the database.
+///
+/// * `name`: The name of the scheduled job (e.g. `reconciliation`)
+/// * `cron`: The cron expression the job runs on, in UTC
+/// * `enabled`: Whether the job runs, which it does not while it is paused
+/// * `params`: What the job needs to run, which depends on the job
+/// * `created_at`: When the job was first scheduled
+/// * `updated_at`: When the schedule was last changed or run, if it has been
+/// * `updated_by`: Who last changed the schedule, if a user did
+/// * `next_run_at`: When the job next runs
+/// * `last_run_at`: When the job last started running, if it has
+/// * `last_job_id`: The ID of the job the last run started, if it started one
+/// * `last_error`: Why the last run failed, if it did
+#[derive(FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct JobSchedule {
+    pub name: String,
+    pub cron: String,
+    pub enabled: bool,
+    pub params: Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub updated_by: Option<String>,
+    pub next_run_at: DateTime<Utc>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_job_id: Option<Uuid>,
+    pub last_error: Option<String>,
+}
@@ -0,0 +1,101 @@
+//! This module contains the definition of the `QueryJobEvents` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+//!
+//! A job event is a JSON document describing something that happened while a job ran. What goes
+//! into it is decided by `app::api::v1::jobs::events`, the storage layer only keeps it.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::{Database, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::entities::JobEvent;
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// A trait for querying the events emitted by jobs.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryJobEvents<DB: Database> {
+    /// Record an event emitted by a job.
+    ///
+    /// * `job_id`: The id of the job that emitted the event
+    /// * `event`: What happened
+    /// * `exec_opts`: Execution options for the query
+    async fn record_job_event(
+        &self,
+        job_id: Uuid,
+        event: serde_json::Value,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<JobEvent> {
+        unimplemented!()
+    }
+
+    /// Fetch the events a job emitted after a given event, oldest first.
+    ///
+    /// * `job_id`: The id of the job
+    /// * `after`: The id of the last event already seen, or `0` to fetch every event
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_job_events(
+        &self,
+        job_id: Uuid,
+        after: i64,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<JobEvent>> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryJobEvents<Postgres> for PgBackend {
+    async fn record_job_event(
+        &self,
+        job_id: Uuid,
+        event: serde_json::Value,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<JobEvent> {
+        async fn exec(
+            job_id: Uuid,
+            event: serde_json::Value,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<JobEvent> {
+            let query = include_str!("queries/events/record_job_event.sql");
+            let event = sqlx::query_as::<_, JobEvent>(query)
+                .bind(job_id)
+                .bind(event)
+                .fetch_one(&mut **tx)
+                .await
+                .with_context(|| format!("error recording event of job {job_id}"))?;
+            Ok(event)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id, event)
+    }
+
+    async fn fetch_job_events(
+        &self,
+        job_id: Uuid,
+        after: i64,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<JobEvent>> {
+        async fn exec(
+            job_id: Uuid,
+            after: i64,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<JobEvent>> {
+            let query = include_str!("queries/events/fetch_job_events.sql");
+            let events = sqlx::query_as::<_, JobEvent>(query)
+                .bind(job_id)
+                .bind(after)
+                .fetch_all(&mut **tx)
+                .await
+                .with_context(|| format!("error fetching events of job {job_id}"))?;
+            Ok(events)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, job_id, after)
+    }
+}
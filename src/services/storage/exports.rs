@@ -0,0 +1,322 @@
+//! This module contains the definition of the `QueryExports` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use derive_builder::Builder;
+use sqlx::{Database, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+
+use super::entities::{ExportCheckpoint, OnboardingEmailDelivery};
+use super::types::{EmailDeliveryStatus, WorkspaceExportStatus};
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// Data needed to record a volunteer as part of a workspace export job.
+///
+/// * `volunteer_id`: The ID of the volunteer
+/// * `workspace_email`: The workspace email the volunteer will be issued
+/// * `org_unit`: The org unit the volunteer will be exported to
+#[derive(Builder, Debug, Clone)]
+pub struct CreateExportCheckpoint {
+    pub volunteer_id: Uuid,
+    #[builder(setter(into))]
+    pub workspace_email: String,
+    #[builder(setter(into))]
+    pub org_unit: String,
+}
+
+/// A delivery event reported by the mail provider for an onboarding email.
+///
+/// * `job_id`: The ID of the export job that sent the email
+/// * `volunteer_id`: The ID of the volunteer the email was sent to
+/// * `email`: The address the email was sent to
+/// * `status`: What the mail provider reported
+/// * `reason`: Why the email bounced or was dropped, if it was
+/// * `message_id`: The mail provider's ID for the email, if it reported one
+/// * `event_at`: When the event happened, according to the mail provider
+#[derive(Builder, Debug, Clone)]
+pub struct RecordOnboardingEmailDelivery {
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    #[builder(setter(into))]
+    pub email: String,
+    pub status: EmailDeliveryStatus,
+    #[builder(setter(into), default = "None")]
+    pub reason: Option<String>,
+    #[builder(setter(into), default = "None")]
+    pub message_id: Option<String>,
+    pub event_at: DateTime<Utc>,
+}
+
+/// A trait for querying the progress of export jobs.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryExports<DB: Database> {
+    /// Record volunteers as part of a workspace export job.
+    ///
+    /// * `job_id`: The ID of the export job
+    /// * `data`: The volunteers to record
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Every volunteer starts out as `Pending`. Volunteers that are already recorded for the job
+    /// are left untouched.
+    async fn create_export_checkpoints(
+        &self,
+        job_id: Uuid,
+        data: Vec<CreateExportCheckpoint>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Fetch the progress of every volunteer in a workspace export job.
+    ///
+    /// * `job_id`: The ID of the export job
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_export_checkpoints(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<ExportCheckpoint>> {
+        unimplemented!()
+    }
+
+    /// Update the progress of a volunteer in a workspace export job.
+    ///
+    /// * `job_id`: The ID of the export job
+    /// * `volunteer_id`: The ID of the volunteer
+    /// * `status`: The new status
+    /// * `error`: Why the export failed, if the new status is `Failed`
+    /// * `exec_opts`: Execution options for the query
+    async fn update_export_checkpoint(
+        &self,
+        job_id: Uuid,
+        volunteer_id: Uuid,
+        status: WorkspaceExportStatus,
+        error: Option<String>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Update the progress of several volunteers in a workspace export job at once.
+    ///
+    /// * `job_id`: The ID of the export job
+    /// * `volunteer_ids`: The IDs of the volunteers
+    /// * `status`: The new status
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Any error previously recorded for the volunteers is cleared.
+    async fn update_export_checkpoints(
+        &self,
+        job_id: Uuid,
+        volunteer_ids: Vec<Uuid>,
+        status: WorkspaceExportStatus,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Record delivery events reported by the mail provider for onboarding emails.
+    ///
+    /// * `data`: The events to record
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Only the latest event for each email is kept, so events that arrive out of order do not
+    /// overwrite newer ones. Events for jobs or volunteers that do not exist are ignored.
+    async fn record_onboarding_email_deliveries(
+        &self,
+        data: Vec<RecordOnboardingEmailDelivery>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Fetch the delivery status of every onboarding email sent by a workspace export job.
+    ///
+    /// * `job_id`: The ID of the export job
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_onboarding_email_deliveries(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<OnboardingEmailDelivery>> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryExports<Postgres> for PgBackend {
+    async fn create_export_checkpoints(
+        &self,
+        job_id: Uuid,
+        data: Vec<CreateExportCheckpoint>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            job_id: Uuid,
+            data: Vec<CreateExportCheckpoint>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            if data.is_empty() {
+                return Ok(());
+            }
+
+            let fragment = include_str!("queries/exports/create_export_checkpoints.fragment.sql");
+
+            QueryBuilder::<Postgres>::new(fragment)
+                .push_values(data, |mut b, c| {
+                    b.push_bind(job_id)
+                        .push_bind(c.volunteer_id)
+                        .push_bind(c.workspace_email)
+                        .push_bind(c.org_unit);
+                })
+                .push(" on conflict (job_id, volunteer_id) do nothing")
+                .build()
+                .execute(&mut **tx)
+                .await
+                .context("error creating export checkpoints")?;
+
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id, data)
+    }
+
+    async fn fetch_export_checkpoints(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<ExportCheckpoint>> {
+        async fn exec(
+            job_id: Uuid,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<ExportCheckpoint>> {
+            let query = include_str!("queries/exports/fetch_export_checkpoints.sql");
+            let checkpoints = sqlx::query_as::<_, ExportCheckpoint>(query)
+                .bind(job_id)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching export checkpoints")?;
+            Ok(checkpoints)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id)
+    }
+
+    async fn update_export_checkpoint(
+        &self,
+        job_id: Uuid,
+        volunteer_id: Uuid,
+        status: WorkspaceExportStatus,
+        error: Option<String>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            job_id: Uuid,
+            volunteer_id: Uuid,
+            status: WorkspaceExportStatus,
+            error: Option<String>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            let query = include_str!("queries/exports/update_export_checkpoint.sql");
+            sqlx::query(query)
+                .bind(job_id)
+                .bind(volunteer_id)
+                .bind(status)
+                .bind(error)
+                .execute(&mut **tx)
+                .await
+                .context("error updating export checkpoint")?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id, volunteer_id, status, error)
+    }
+
+    async fn update_export_checkpoints(
+        &self,
+        job_id: Uuid,
+        volunteer_ids: Vec<Uuid>,
+        status: WorkspaceExportStatus,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            job_id: Uuid,
+            volunteer_ids: Vec<Uuid>,
+            status: WorkspaceExportStatus,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            if volunteer_ids.is_empty() {
+                return Ok(());
+            }
+
+            let query = include_str!("queries/exports/update_export_checkpoints.sql");
+            sqlx::query(query)
+                .bind(job_id)
+                .bind(volunteer_ids)
+                .bind(status)
+                .execute(&mut **tx)
+                .await
+                .context("error updating export checkpoints")?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id, volunteer_ids, status)
+    }
+
+    async fn record_onboarding_email_deliveries(
+        &self,
+        data: Vec<RecordOnboardingEmailDelivery>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            data: Vec<RecordOnboardingEmailDelivery>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            let query = include_str!("queries/exports/record_onboarding_email_delivery.sql");
+            for d in data {
+                sqlx::query(query)
+                    .bind(d.job_id)
+                    .bind(d.volunteer_id)
+                    .bind(d.email)
+                    .bind(d.status)
+                    .bind(d.reason)
+                    .bind(d.message_id)
+                    .bind(d.event_at)
+                    .execute(&mut **tx)
+                    .await
+                    .context("error recording onboarding email delivery")?;
+            }
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn fetch_onboarding_email_deliveries(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<OnboardingEmailDelivery>> {
+        async fn exec(
+            job_id: Uuid,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<OnboardingEmailDelivery>> {
+            let query = include_str!("queries/exports/fetch_onboarding_email_deliveries.sql");
+            let deliveries = sqlx::query_as::<_, OnboardingEmailDelivery>(query)
+                .bind(job_id)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching onboarding email deliveries")?;
+            Ok(deliveries)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, job_id)
+    }
+}
@@ -1,15 +1,17 @@
 //! This module contains the definition of the `QueryJobs` trait as well as the default
 //! implementation of the trait for the `PgBackend` struct.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use derive_builder::Builder;
 use sqlx::{Database, Postgres, Transaction};
+use thiserror::Error;
 use uuid::Uuid;
 
-use super::exec_with_tx;
-use crate::services::storage::entities::Job;
-use crate::services::storage::types::{JobDetails, JobStatus};
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::entities::{ArchivedJob, Job, JobProgress};
+use crate::services::storage::types::{JobDetails, JobPhase, JobStatus};
 use crate::services::storage::{Acquire, ExecOpts, PgBackend};
 
 /// Data needed to record a new asynchronous job.
@@ -36,6 +38,19 @@ pub struct UpdateJobStatus {
     pub error: Option<String>,
 }
 
+/// Data needed to report the progress of a job.
+///
+/// * `phase`: The phase the job is in
+/// * `processed`: How many items the job has processed in the current phase
+/// * `total`: How many items the job has to process in the current phase
+#[derive(Builder, Debug)]
+pub struct UpdateJobProgress {
+    pub phase: JobPhase,
+    #[builder(default)]
+    pub processed: i32,
+    pub total: i32,
+}
+
 /// Data needed to edit a job.
 ///
 /// * `label`: The new label for the job
@@ -48,6 +63,27 @@ pub struct EditJob {
     pub description: Option<String>,
 }
 
+/// The error returned when a job's status is not changed because another worker changed it first,
+/// e.g. a job that was already finished is finished again.
+///
+/// * `job_id`: The ID of the job
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("job {job_id} was changed by another worker")]
+pub struct JobConflictError {
+    pub job_id: Uuid,
+}
+
+/// The version of a job when it is created. A job that is run as soon as it is created is finished
+/// at this version.
+pub const NEW_JOB_VERSION: i32 = 0;
+
+/// Whether an error was returned because another worker changed a job's status first.
+///
+/// * `e`: The error
+pub fn is_job_conflict(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| cause.downcast_ref::<JobConflictError>().is_some())
+}
+
 /// A trait for querying jobs.
 ///
 /// If you implement a new storage backend, this trait is required for it to implement
@@ -85,6 +121,15 @@ pub trait QueryJobs<DB: Database> {
         unimplemented!()
     }
 
+    /// Fetch the status of a job. Unlike `fetch_job`, this never reads through the cache, so a
+    /// running job can find out that it was cancelled by another process.
+    ///
+    /// * `id`: The id of the job
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_job_status(&self, id: Uuid, exec_opts: &mut ExecOpts<DB>) -> Result<JobStatus> {
+        unimplemented!()
+    }
+
     /// Update the status of a job.
     ///
     /// * `id`: The ID of the job to update
@@ -99,19 +144,77 @@ pub trait QueryJobs<DB: Database> {
         unimplemented!()
     }
 
-    async fn mark_job_complete(&self, id: Uuid, exec_opts: &mut ExecOpts<DB>) -> Result<()> {
+    /// Mark a pending job complete. If the job is no longer pending or its status changed since it
+    /// was read, because another worker finished or resumed it or it was cancelled, it is left as
+    /// it is and a `JobConflictError` is returned.
+    ///
+    /// * `id`: The ID of the job
+    /// * `version`: The version of the job when it was claimed or read
+    /// * `exec_opts`: Execution options for the query
+    async fn mark_job_complete(
+        &self,
+        id: Uuid,
+        version: i32,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
         unimplemented!()
     }
 
+    /// Mark a pending job errored. If the job is no longer pending or its status changed since it
+    /// was read, because another worker finished or resumed it or it was cancelled, it is left as
+    /// it is and a `JobConflictError` is returned.
+    ///
+    /// * `id`: The ID of the job
+    /// * `version`: The version of the job when it was claimed or read
+    /// * `error`: Why the job failed
+    /// * `exec_opts`: Execution options for the query
     async fn mark_job_errored(
         &self,
         id: Uuid,
+        version: i32,
         error: String,
         exec_opts: &mut ExecOpts<DB>,
     ) -> Result<()> {
         unimplemented!()
     }
 
+    /// Set a job that stopped (or errored, or was dead-lettered) back to pending so that it can be
+    /// resumed. If its status changed since it was read, because another worker resumed or finished
+    /// it, it is left as it is and a `JobConflictError` is returned.
+    ///
+    /// * `id`: The ID of the job
+    /// * `version`: The version of the job when it was read
+    /// * `exec_opts`: Execution options for the query
+    async fn resume_job(&self, id: Uuid, version: i32, exec_opts: &mut ExecOpts<DB>) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Mark a job dead-lettered, once it has failed on every attempt it was given. If the job was
+    /// finished or cancelled in the meantime, it is left as it is and a `JobConflictError` is
+    /// returned.
+    ///
+    /// * `id`: The ID of the job
+    /// * `error`: Why the job's last attempt failed
+    /// * `exec_opts`: Execution options for the query
+    async fn mark_job_dead_lettered(
+        &self,
+        id: Uuid,
+        error: String,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Set a dead-lettered job back to pending so that it can be queued again.
+    ///
+    /// * `id`: The ID of the job
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the job was requeued, which it is not if it is not dead-lettered.
+    async fn requeue_job(&self, id: Uuid, exec_opts: &mut ExecOpts<DB>) -> Result<bool> {
+        unimplemented!()
+    }
+
     /// Set the project cycle that a job is associated with.
     ///
     /// This may be useful if a job is started to import data for a project cycle. The job is
@@ -142,7 +245,103 @@ pub trait QueryJobs<DB: Database> {
     ///
     /// * `id`: The id of the job to cancel
     /// * `opts`: Execution options for the query
-    async fn cancel_job(&self, id: Uuid, opts: &mut ExecOpts<DB>) -> Result<()> {
+    ///
+    /// Returns whether the job was cancelled, which it is not if it is no longer pending.
+    async fn cancel_job(&self, id: Uuid, opts: &mut ExecOpts<DB>) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Record the result of a job in its details.
+    ///
+    /// * `id`: The id of the job
+    /// * `result`: The result of the job. Any previously recorded result is replaced.
+    /// * `exec_opts`: Execution options for the query
+    async fn set_job_result(
+        &self,
+        id: Uuid,
+        result: serde_json::Value,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Report the progress of a job, replacing any previously reported progress.
+    ///
+    /// * `id`: The id of the job
+    /// * `data`: Data required to report the progress
+    /// * `exec_opts`: Execution options for the query
+    async fn update_job_progress(
+        &self,
+        id: Uuid,
+        data: UpdateJobProgress,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Fetch the progress of a job, if it has reported any.
+    ///
+    /// * `id`: The id of the job
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_job_progress(
+        &self,
+        id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Option<JobProgress>> {
+        unimplemented!()
+    }
+
+    /// Fetch the export jobs that ended with an error over a period of time, oldest first.
+    ///
+    /// * `since`: The start of the period
+    /// * `until`: The end of the period, exclusive
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_failed_export_jobs(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<Job>> {
+        unimplemented!()
+    }
+
+    /// Archive the jobs that finished before a time, oldest first. Each job's full details move to
+    /// the archive, and only a summary is left in its place, which `fetch_jobs` no longer lists.
+    /// Returns how many jobs were archived.
+    ///
+    /// * `finished_before`: Archive the jobs that were last updated before this time
+    /// * `limit`: The most jobs to archive
+    /// * `exec_opts`: Execution options for the query
+    async fn archive_jobs(
+        &self,
+        finished_before: DateTime<Utc>,
+        limit: i64,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<u64> {
+        unimplemented!()
+    }
+
+    /// Fetch the summaries of archived jobs, newest first.
+    ///
+    /// * `project_cycle_id`: Only fetch the jobs of this project cycle, if given
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_archived_jobs(
+        &self,
+        project_cycle_id: Option<Uuid>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<ArchivedJob>> {
+        unimplemented!()
+    }
+
+    /// Fetch the full details of an archived job, if it was archived.
+    ///
+    /// * `id`: The id of the job
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_job_archive(
+        &self,
+        id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Option<serde_json::Value>> {
         unimplemented!()
     }
 }
@@ -179,7 +378,7 @@ impl QueryJobs<Postgres> for PgBackend {
             let jobs = sqlx::query_as::<_, Job>(query).fetch_all(&mut **tx).await?;
             Ok(jobs)
         }
-        exec_with_tx!(self, exec_opts, exec)
+        exec_read_with_tx!(self, exec_opts, exec)
     }
 
     async fn fetch_job(&self, id: Uuid, exec_opts: &mut ExecOpts) -> Result<Job> {
@@ -188,6 +387,30 @@ impl QueryJobs<Postgres> for PgBackend {
             let job = sqlx::query_as::<_, Job>(query).bind(id).fetch_one(&mut **tx).await?;
             Ok(job)
         }
+
+        let cache = self.read_cache(exec_opts);
+        if let Some(cached) = cache.and_then(|cache| cache.jobs.get(&id)) {
+            return Ok(cached);
+        }
+
+        let job = exec_with_tx!(self, exec_opts, exec, id)?;
+        if let Some(cache) = cache {
+            cache.jobs.insert(id, job.clone());
+        }
+        Ok(job)
+    }
+
+    async fn fetch_job_status(&self, id: Uuid, exec_opts: &mut ExecOpts) -> Result<JobStatus> {
+        async fn exec(id: Uuid, tx: &mut Transaction<'_, Postgres>) -> Result<JobStatus> {
+            let query = include_str!("queries/jobs/fetch_job_status.sql");
+            let status = sqlx::query_scalar::<_, JobStatus>(query)
+                .bind(id)
+                .fetch_one(&mut **tx)
+                .await
+                .with_context(|| format!("error fetching status of job {id}"))?;
+            Ok(status)
+        }
+
         exec_with_tx!(self, exec_opts, exec, id)
     }
 
@@ -211,7 +434,9 @@ impl QueryJobs<Postgres> for PgBackend {
                 .await?;
             Ok(())
         }
-        exec_with_tx!(self, exec_opts, exec, id, data)
+        let res = exec_with_tx!(self, exec_opts, exec, id, data);
+        self.invalidate_cache(|cache| cache.invalidate_job(&id));
+        res
     }
 
     async fn set_job_project_cycle(
@@ -229,7 +454,9 @@ impl QueryJobs<Postgres> for PgBackend {
             sqlx::query(query).bind(id).bind(project_cycle_id).execute(&mut **tx).await?;
             Ok(())
         }
-        exec_with_tx!(self, exec_opts, exec, id, project_cycle_id)
+        let res = exec_with_tx!(self, exec_opts, exec, id, project_cycle_id);
+        self.invalidate_cache(|cache| cache.invalidate_job(&id));
+        res
     }
 
     async fn edit_job(&self, id: Uuid, data: EditJob, opts: &mut ExecOpts) -> Result<()> {
@@ -243,48 +470,278 @@ impl QueryJobs<Postgres> for PgBackend {
                 .await?;
             Ok(())
         }
-        exec_with_tx!(self, opts, exec, id, data)
+        let res = exec_with_tx!(self, opts, exec, id, data);
+        self.invalidate_cache(|cache| cache.invalidate_job(&id));
+        res
     }
 
-    async fn cancel_job(&self, id: Uuid, exec_opts: &mut ExecOpts) -> Result<()> {
-        async fn exec(id: Uuid, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+    async fn cancel_job(&self, id: Uuid, exec_opts: &mut ExecOpts) -> Result<bool> {
+        async fn exec(id: Uuid, tx: &mut Transaction<'_, Postgres>) -> Result<bool> {
             let query = include_str!("queries/jobs/cancel_job.sql");
-            sqlx::query(query).bind(id).execute(&mut **tx).await?;
-            Ok(())
+            let res = sqlx::query(query).bind(id).execute(&mut **tx).await?;
+            Ok(res.rows_affected() > 0)
         }
-        exec_with_tx!(self, exec_opts, exec, id)
+        let res = exec_with_tx!(self, exec_opts, exec, id);
+        self.invalidate_cache(|cache| cache.invalidate_job(&id));
+        res
     }
 
-    async fn mark_job_complete(&self, id: Uuid, exec_opts: &mut ExecOpts) -> Result<()> {
-        async fn exec(id: Uuid, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
-            let query = include_str!("queries/jobs/update_job_status.sql");
-            sqlx::query(query)
+    async fn mark_job_complete(
+        &self,
+        id: Uuid,
+        version: i32,
+        exec_opts: &mut ExecOpts,
+    ) -> Result<()> {
+        async fn exec(id: Uuid, version: i32, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+            let query = include_str!("queries/jobs/finish_job.sql");
+            let res = sqlx::query(query)
                 .bind(id)
                 .bind(JobStatus::Complete)
                 .bind(Option::<String>::None)
+                .bind(version)
                 .execute(&mut **tx)
                 .await?;
+            if res.rows_affected() == 0 {
+                return Err(JobConflictError { job_id: id }.into());
+            }
             Ok(())
         }
-        exec_with_tx!(self, exec_opts, exec, id)
+        let res = exec_with_tx!(self, exec_opts, exec, id, version);
+        self.invalidate_cache(|cache| cache.invalidate_job(&id));
+        res
     }
 
     async fn mark_job_errored(
         &self,
         id: Uuid,
+        version: i32,
         error: String,
         exec_opts: &mut ExecOpts,
     ) -> Result<()> {
-        async fn exec(id: Uuid, error: String, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
-            let query = include_str!("queries/jobs/update_job_status.sql");
-            sqlx::query(query)
+        async fn exec(
+            id: Uuid,
+            version: i32,
+            error: String,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            let query = include_str!("queries/jobs/finish_job.sql");
+            let res = sqlx::query(query)
                 .bind(id)
                 .bind(JobStatus::Error)
                 .bind(Some(error))
+                .bind(version)
+                .execute(&mut **tx)
+                .await?;
+            if res.rows_affected() == 0 {
+                return Err(JobConflictError { job_id: id }.into());
+            }
+            Ok(())
+        }
+        let res = exec_with_tx!(self, exec_opts, exec, id, version, error);
+        self.invalidate_cache(|cache| cache.invalidate_job(&id));
+        res
+    }
+
+    async fn resume_job(&self, id: Uuid, version: i32, exec_opts: &mut ExecOpts) -> Result<()> {
+        async fn exec(id: Uuid, version: i32, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+            let query = include_str!("queries/jobs/resume_job.sql");
+            let res = sqlx::query(query).bind(id).bind(version).execute(&mut **tx).await?;
+            if res.rows_affected() == 0 {
+                return Err(JobConflictError { job_id: id }.into());
+            }
+            Ok(())
+        }
+        let res = exec_with_tx!(self, exec_opts, exec, id, version);
+        self.invalidate_cache(|cache| cache.invalidate_job(&id));
+        res
+    }
+
+    async fn mark_job_dead_lettered(
+        &self,
+        id: Uuid,
+        error: String,
+        exec_opts: &mut ExecOpts,
+    ) -> Result<()> {
+        async fn exec(id: Uuid, error: String, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+            let query = include_str!("queries/jobs/dead_letter_job.sql");
+            let res = sqlx::query(query).bind(id).bind(error).execute(&mut **tx).await?;
+            if res.rows_affected() == 0 {
+                return Err(JobConflictError { job_id: id }.into());
+            }
+            Ok(())
+        }
+        let res = exec_with_tx!(self, exec_opts, exec, id, error);
+        self.invalidate_cache(|cache| cache.invalidate_job(&id));
+        res
+    }
+
+    async fn requeue_job(&self, id: Uuid, exec_opts: &mut ExecOpts) -> Result<bool> {
+        async fn exec(id: Uuid, tx: &mut Transaction<'_, Postgres>) -> Result<bool> {
+            let query = include_str!("queries/jobs/requeue_job.sql");
+            let res = sqlx::query(query).bind(id).execute(&mut **tx).await?;
+            Ok(res.rows_affected() > 0)
+        }
+        let res = exec_with_tx!(self, exec_opts, exec, id);
+        self.invalidate_cache(|cache| cache.invalidate_job(&id));
+        res
+    }
+
+    async fn set_job_result(
+        &self,
+        id: Uuid,
+        result: serde_json::Value,
+        exec_opts: &mut ExecOpts,
+    ) -> Result<()> {
+        async fn exec(
+            id: Uuid,
+            result: serde_json::Value,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            let query = include_str!("queries/jobs/set_job_result.sql");
+            sqlx::query(query).bind(id).bind(result).execute(&mut **tx).await?;
+            Ok(())
+        }
+        let res = exec_with_tx!(self, exec_opts, exec, id, result);
+        self.invalidate_cache(|cache| cache.invalidate_job(&id));
+        res
+    }
+
+    async fn update_job_progress(
+        &self,
+        id: Uuid,
+        data: UpdateJobProgress,
+        exec_opts: &mut ExecOpts,
+    ) -> Result<()> {
+        async fn exec(
+            id: Uuid,
+            data: UpdateJobProgress,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            let query = include_str!("queries/jobs/update_job_progress.sql");
+            sqlx::query(query)
+                .bind(id)
+                .bind(data.phase)
+                .bind(data.processed)
+                .bind(data.total)
                 .execute(&mut **tx)
                 .await?;
             Ok(())
         }
-        exec_with_tx!(self, exec_opts, exec, id, error)
+        let res = exec_with_tx!(self, exec_opts, exec, id, data);
+        self.invalidate_cache(|cache| cache.invalidate_job(&id));
+        res
+    }
+
+    async fn fetch_job_progress(
+        &self,
+        id: Uuid,
+        exec_opts: &mut ExecOpts,
+    ) -> Result<Option<JobProgress>> {
+        async fn exec(id: Uuid, tx: &mut Transaction<'_, Postgres>) -> Result<Option<JobProgress>> {
+            let query = include_str!("queries/jobs/fetch_job_progress.sql");
+            let progress =
+                sqlx::query_as::<_, JobProgress>(query).bind(id).fetch_optional(&mut **tx).await?;
+            Ok(progress)
+        }
+
+        let cache = self.read_cache(exec_opts);
+        if let Some(cached) = cache.and_then(|cache| cache.job_progress.get(&id)) {
+            return Ok(cached);
+        }
+
+        let progress = exec_with_tx!(self, exec_opts, exec, id)?;
+        if let Some(cache) = cache {
+            cache.job_progress.insert(id, progress.clone());
+        }
+        Ok(progress)
+    }
+
+    async fn fetch_failed_export_jobs(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts,
+    ) -> Result<Vec<Job>> {
+        async fn exec(
+            since: DateTime<Utc>,
+            until: DateTime<Utc>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<Job>> {
+            let query = include_str!("queries/jobs/fetch_failed_export_jobs.sql");
+            let jobs = sqlx::query_as::<_, Job>(query)
+                .bind(since)
+                .bind(until)
+                .fetch_all(&mut **tx)
+                .await?;
+            Ok(jobs)
+        }
+        exec_read_with_tx!(self, exec_opts, exec, since, until)
+    }
+    async fn archive_jobs(
+        &self,
+        finished_before: DateTime<Utc>,
+        limit: i64,
+        exec_opts: &mut ExecOpts,
+    ) -> Result<u64> {
+        async fn exec(
+            finished_before: DateTime<Utc>,
+            limit: i64,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<u64> {
+            let query = include_str!("queries/jobs/archive_jobs.sql");
+            let res = sqlx::query(query)
+                .bind(finished_before)
+                .bind(limit)
+                .execute(&mut **tx)
+                .await
+                .context("error archiving jobs")?;
+            Ok(res.rows_affected())
+        }
+        let res = exec_with_tx!(self, exec_opts, exec, finished_before, limit);
+        self.invalidate_cache(|cache| {
+            cache.jobs.invalidate_all();
+            cache.job_progress.invalidate_all();
+        });
+        res
+    }
+
+    async fn fetch_archived_jobs(
+        &self,
+        project_cycle_id: Option<Uuid>,
+        exec_opts: &mut ExecOpts,
+    ) -> Result<Vec<ArchivedJob>> {
+        async fn exec(
+            project_cycle_id: Option<Uuid>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<ArchivedJob>> {
+            let query = include_str!("queries/jobs/fetch_archived_jobs.sql");
+            let jobs = sqlx::query_as::<_, ArchivedJob>(query)
+                .bind(project_cycle_id)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching archived jobs")?;
+            Ok(jobs)
+        }
+        exec_read_with_tx!(self, exec_opts, exec, project_cycle_id)
+    }
+
+    async fn fetch_job_archive(
+        &self,
+        id: Uuid,
+        exec_opts: &mut ExecOpts,
+    ) -> Result<Option<serde_json::Value>> {
+        async fn exec(
+            id: Uuid,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Option<serde_json::Value>> {
+            let query = include_str!("queries/jobs/fetch_job_archive.sql");
+            let details = sqlx::query_scalar::<_, serde_json::Value>(query)
+                .bind(id)
+                .fetch_optional(&mut **tx)
+                .await
+                .context("error fetching job archive")?;
+            Ok(details)
+        }
+        exec_read_with_tx!(self, exec_opts, exec, id)
     }
 }
@@ -0,0 +1,124 @@
+//! This module contains the definition of the `QueryLogins` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Database, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+
+use super::entities::WorkspaceLogin;
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// Data needed to record when a volunteer last signed in to their workspace account.
+///
+/// * `volunteer_id`: The ID of the volunteer
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `last_login_at`: When the volunteer last signed in, or `None` if they never have
+#[derive(Debug, Clone)]
+pub struct RecordWorkspaceLogin {
+    pub volunteer_id: Uuid,
+    pub workspace_email: String,
+    pub last_login_at: Option<DateTime<Utc>>,
+}
+
+/// A trait for querying when volunteers last signed in to their workspace accounts.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryLogins<DB: Database> {
+    /// Record when volunteers last signed in, as of now. An account's recorded sign in is never
+    /// moved back, so a check that comes back without one keeps the last one seen.
+    ///
+    /// * `data`: The sign ins to record
+    /// * `exec_opts`: Execution options for the query
+    async fn record_workspace_logins(
+        &self,
+        data: Vec<RecordWorkspaceLogin>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Fetch when each of a project cycle's exported volunteers last signed in. Volunteers whose
+    /// accounts have not been checked yet are included, without a check time.
+    ///
+    /// * `project_cycle_id`: The ID of the project cycle
+    /// * `never_activated`: Only fetch the volunteers who had never signed in when last checked
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_workspace_logins(
+        &self,
+        project_cycle_id: Uuid,
+        never_activated: bool,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<WorkspaceLogin>> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryLogins<Postgres> for PgBackend {
+    async fn record_workspace_logins(
+        &self,
+        data: Vec<RecordWorkspaceLogin>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            data: Vec<RecordWorkspaceLogin>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            if data.is_empty() {
+                return Ok(());
+            }
+
+            let fragment = include_str!("queries/logins/record_workspace_logins.fragment.sql");
+
+            QueryBuilder::<Postgres>::new(fragment)
+                .push_values(data, |mut b, d| {
+                    b.push_bind(d.volunteer_id)
+                        .push_bind(d.workspace_email)
+                        .push_bind(d.last_login_at);
+                })
+                .push(
+                    " on conflict (workspace_email) do update set volunteer_id = \
+                     excluded.volunteer_id, last_login_at = greatest(excluded.last_login_at, \
+                     workspace_logins.last_login_at), checked_at = now()",
+                )
+                .build()
+                .execute(&mut **tx)
+                .await
+                .context("error recording workspace logins")?;
+
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn fetch_workspace_logins(
+        &self,
+        project_cycle_id: Uuid,
+        never_activated: bool,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<WorkspaceLogin>> {
+        async fn exec(
+            project_cycle_id: Uuid,
+            never_activated: bool,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<WorkspaceLogin>> {
+            let query = include_str!("queries/logins/fetch_workspace_logins.sql");
+            let logins = sqlx::query_as::<_, WorkspaceLogin>(query)
+                .bind(project_cycle_id)
+                .bind(never_activated)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching workspace logins")?;
+            Ok(logins)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, project_cycle_id, never_activated)
+    }
+}
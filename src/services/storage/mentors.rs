@@ -8,8 +8,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::{Database, Postgres, QueryBuilder, Transaction};
 use uuid::Uuid;
 
-use super::exec_with_tx;
 use super::types::{MentorExperienceLevel, MentorYearsExperience, VolunteerHearAbout};
+use super::{exec_read_with_tx, exec_with_tx};
 use crate::services::storage::entities::MentorDetails;
 use crate::services::storage::{Acquire, ExecOpts, PgBackend};
 
@@ -272,7 +272,7 @@ impl QueryMentors<Postgres> for PgBackend {
             Ok(mentors)
         }
 
-        exec_with_tx!(self, exec_opts, exec)
+        exec_read_with_tx!(self, exec_opts, exec)
     }
 
     async fn fetch_mentor_by_id(
@@ -10,7 +10,7 @@ use uuid::Uuid;
 
 use super::entities::NonprofitClientDetails;
 use super::types::{ClientSize, ImpactCause};
-use super::{exec_with_tx, PgBackend};
+use super::{exec_read_with_tx, exec_with_tx, PgBackend};
 use crate::services::storage::{Acquire, ExecOpts};
 
 /// Data needed to create a new nonprofit.
@@ -283,7 +283,7 @@ impl QueryNonprofits<Postgres> for PgBackend {
             Ok(nonprofits)
         }
 
-        exec_with_tx!(self, exec_opts, exec)
+        exec_read_with_tx!(self, exec_opts, exec)
     }
 
     async fn fetch_nonprofit_by_id(
@@ -0,0 +1,138 @@
+//! This module contains the definition of the `QueryOrganizations` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+//!
+//! Every query the storage layer runs acts for a single organization. The organization is taken
+//! from the task the query runs in, which `with_org` sets, and the database only lets the query
+//! see and change that organization's rows. Tasks that are not scoped to an organization act for
+//! the default one.
+
+use std::future::Future;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use derive_builder::Builder;
+use sqlx::{Database, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::exec_with_tx;
+use crate::services::storage::entities::Organization;
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// The organization that data created before organizations existed belongs to, and that tasks not
+/// scoped to an organization act for.
+pub const DEFAULT_ORG_ID: Uuid = Uuid::nil();
+
+tokio::task_local! {
+    static CURRENT_ORG: Uuid;
+}
+
+/// Run a future with every query it makes scoped to an organization.
+///
+/// * `org_id`: The ID of the organization
+/// * `f`: The future
+///
+/// Tasks spawned by the future are not scoped to the organization, so they have to be scoped
+/// again.
+pub async fn with_org<F: Future>(org_id: Uuid, f: F) -> F::Output {
+    CURRENT_ORG.scope(org_id, f).await
+}
+
+/// The organization the current task is scoped to, if it is scoped to one.
+pub fn current_org() -> Option<Uuid> {
+    CURRENT_ORG.try_with(|org_id| *org_id).ok()
+}
+
+/// Scope a transaction to the organization the current task is scoped to, if any.
+///
+/// * `tx`: The transaction
+pub(in crate::services::storage) async fn set_org(
+    tx: &mut Transaction<'_, Postgres>,
+) -> Result<()> {
+    let Some(org_id) = current_org() else {
+        return Ok(());
+    };
+
+    sqlx::query("select set_config('scipio.org_id', $1, true)")
+        .bind(org_id.to_string())
+        .execute(&mut **tx)
+        .await
+        .context("error setting organization")?;
+    Ok(())
+}
+
+/// Data needed to create an organization.
+///
+/// * `name`: The name of the organization
+#[derive(Builder)]
+pub struct CreateOrganization {
+    #[builder(setter(into))]
+    pub name: String,
+}
+
+/// A trait for querying organizations.
+///
+/// Organizations are not scoped to an organization themselves, so every organization can be
+/// listed from any task. Only administrative tasks (such as those that run for every organization
+/// in turn) should use this trait.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryOrganizations<DB: Database> {
+    /// Creates an organization.
+    ///
+    /// * `data`: The data needed to create the organization
+    /// * `exec_opts`: Execution options for the query
+    async fn create_organization(
+        &self,
+        data: CreateOrganization,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Uuid> {
+        unimplemented!()
+    }
+
+    /// Fetches every organization, oldest first.
+    ///
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_organizations(&self, exec_opts: &mut ExecOpts<DB>) -> Result<Vec<Organization>> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryOrganizations<Postgres> for PgBackend {
+    async fn create_organization(
+        &self,
+        data: CreateOrganization,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Uuid> {
+        async fn exec(
+            data: CreateOrganization,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Uuid> {
+            let query = include_str!("queries/organizations/create_organization.sql");
+            let id = sqlx::query_scalar::<_, Uuid>(query)
+                .bind(data.name)
+                .fetch_one(&mut **tx)
+                .await
+                .context("error creating organization")?;
+            Ok(id)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn fetch_organizations(
+        &self,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<Organization>> {
+        async fn exec(tx: &mut Transaction<'_, Postgres>) -> Result<Vec<Organization>> {
+            let query = include_str!("queries/organizations/fetch_organizations.sql");
+            let organizations = sqlx::query_as::<_, Organization>(query)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching organizations")?;
+            Ok(organizations)
+        }
+
+        exec_with_tx!(self, exec_opts, exec)
+    }
+}
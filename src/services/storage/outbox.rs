@@ -0,0 +1,295 @@
+//! This module contains the definition of the `QueryOutbox` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+//!
+//! The outbox holds the onboarding emails an export has committed to sending. An export writes its
+//! emails to the outbox in the same transaction that records its volunteers, holding a claim on
+//! them while it sends them itself. Each email is deleted once a mail provider has accepted or
+//! refused it. Emails whose claim runs out before they are deleted were left behind by an export
+//! that stopped, and are claimed and sent by the relay instead.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use derive_builder::Builder;
+use sqlx::{Database, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+
+use super::entities::OutboxEmail;
+use super::exec_with_tx;
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// Data needed to write an onboarding email to the outbox.
+///
+/// * `id`: The ID of the email. It is chosen by the sender so it can refer to the email without
+///   reading it back.
+/// * `job_id`: The ID of the export job that sends the email
+/// * `volunteer_id`: The ID of the volunteer the email is sent to
+/// * `first_name`: The volunteer's first name
+/// * `last_name`: The volunteer's last name
+/// * `email`: The address the email is sent to
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `temporary_password`: The volunteer's temporary password, if the email delivers it
+/// * `locale`: The locale to render the email in
+/// * `onboarding_session_link`: The link to the onboarding session the volunteer is invited to, if
+///   any
+#[derive(Builder, Debug, Clone)]
+pub struct CreateOutboxEmail {
+    #[builder(default = "Uuid::new_v4()")]
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    #[builder(setter(into))]
+    pub first_name: String,
+    #[builder(setter(into))]
+    pub last_name: String,
+    #[builder(setter(into))]
+    pub email: String,
+    #[builder(setter(into))]
+    pub workspace_email: String,
+    #[builder(setter(into), default = "None")]
+    pub temporary_password: Option<String>,
+    #[builder(setter(into))]
+    pub locale: String,
+    #[builder(setter(into), default = "None")]
+    pub onboarding_session_link: Option<String>,
+}
+
+/// A trait for querying the outbox of onboarding emails.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryOutbox<DB: Database> {
+    /// Write onboarding emails to the outbox, claimed by the writer.
+    ///
+    /// * `data`: The emails to write
+    /// * `claimed_by`: The ID of the writer's claim
+    /// * `claimed_until`: When the writer's claim runs out
+    /// * `exec_opts`: Execution options for the query
+    async fn enqueue_outbox_emails(
+        &self,
+        data: Vec<CreateOutboxEmail>,
+        claimed_by: Uuid,
+        claimed_until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Claim the emails in the outbox that no one holds a claim on, oldest first. Emails another
+    /// sender is claiming at the same time are skipped rather than waited for.
+    ///
+    /// * `claimed_by`: The ID of the new claim
+    /// * `job_id`: Only claim emails sent by this job, if set
+    /// * `limit`: The most emails to claim
+    /// * `claimed_until`: When the new claim runs out
+    /// * `exec_opts`: Execution options for the query
+    async fn claim_outbox_emails(
+        &self,
+        claimed_by: Uuid,
+        job_id: Option<Uuid>,
+        limit: i64,
+        claimed_until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<OutboxEmail>> {
+        unimplemented!()
+    }
+
+    /// Extend a claim on emails in the outbox.
+    ///
+    /// * `claimed_by`: The ID of the claim
+    /// * `ids`: The IDs of the emails
+    /// * `claimed_until`: When the claim now runs out
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns the IDs of the emails that are still held by the claim. An email whose claim ran
+    /// out may have been claimed by the relay, and must be left to it.
+    async fn renew_outbox_claims(
+        &self,
+        claimed_by: Uuid,
+        ids: Vec<Uuid>,
+        claimed_until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<Uuid>> {
+        unimplemented!()
+    }
+
+    /// Remove an email from the outbox, once a mail provider has accepted or refused it.
+    ///
+    /// * `id`: The ID of the email
+    /// * `claimed_by`: The ID of the claim the email was sent under
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the email was still held by the claim.
+    async fn delete_outbox_email(
+        &self,
+        id: Uuid,
+        claimed_by: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<bool> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryOutbox<Postgres> for PgBackend {
+    async fn enqueue_outbox_emails(
+        &self,
+        data: Vec<CreateOutboxEmail>,
+        claimed_by: Uuid,
+        claimed_until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            data: Vec<CreateOutboxEmail>,
+            claimed_by: Uuid,
+            claimed_until: DateTime<Utc>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            if data.is_empty() {
+                return Ok(());
+            }
+
+            let fragment = include_str!("queries/outbox/enqueue_outbox_emails.fragment.sql");
+
+            QueryBuilder::<Postgres>::new(fragment)
+                .push_values(data, |mut b, e| {
+                    b.push_bind(e.id)
+                        .push_bind(e.job_id)
+                        .push_bind(e.volunteer_id)
+                        .push_bind(e.first_name)
+                        .push_bind(e.last_name)
+                        .push_bind(e.email)
+                        .push_bind(e.workspace_email)
+                        .push_bind(e.temporary_password)
+                        .push_bind(e.locale)
+                        .push_bind(e.onboarding_session_link)
+                        .push_bind(claimed_by)
+                        .push_bind(claimed_until);
+                })
+                .build()
+                .execute(&mut **tx)
+                .await
+                .context("error writing emails to the outbox")?;
+
+            Ok(())
+        }
+
+        // The recovery email and temporary password are encrypted at rest
+        let data = data
+            .into_iter()
+            .map(|e| {
+                Ok(CreateOutboxEmail {
+                    email: self.encrypt_field(e.email)?,
+                    temporary_password: e
+                        .temporary_password
+                        .map(|password| self.encrypt_field(password))
+                        .transpose()?,
+                    ..e
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        exec_with_tx!(self, exec_opts, exec, data, claimed_by, claimed_until)
+    }
+
+    async fn claim_outbox_emails(
+        &self,
+        claimed_by: Uuid,
+        job_id: Option<Uuid>,
+        limit: i64,
+        claimed_until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<OutboxEmail>> {
+        async fn exec(
+            claimed_by: Uuid,
+            job_id: Option<Uuid>,
+            limit: i64,
+            claimed_until: DateTime<Utc>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<OutboxEmail>> {
+            let query = include_str!("queries/outbox/claim_outbox_emails.sql");
+            let emails = sqlx::query_as::<_, OutboxEmail>(query)
+                .bind(claimed_by)
+                .bind(job_id)
+                .bind(limit)
+                .bind(claimed_until)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error claiming emails in the outbox")?;
+            Ok(emails)
+        }
+
+        let emails =
+            exec_with_tx!(self, exec_opts, exec, claimed_by, job_id, limit, claimed_until)?;
+        emails.into_iter().map(|e| self.decrypt_outbox_email(e)).collect()
+    }
+
+    async fn renew_outbox_claims(
+        &self,
+        claimed_by: Uuid,
+        ids: Vec<Uuid>,
+        claimed_until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<Uuid>> {
+        async fn exec(
+            claimed_by: Uuid,
+            ids: Vec<Uuid>,
+            claimed_until: DateTime<Utc>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<Uuid>> {
+            let query = include_str!("queries/outbox/renew_outbox_claims.sql");
+            let held = sqlx::query_scalar::<_, Uuid>(query)
+                .bind(claimed_by)
+                .bind(ids)
+                .bind(claimed_until)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error renewing claims on emails in the outbox")?;
+            Ok(held)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, claimed_by, ids, claimed_until)
+    }
+
+    async fn delete_outbox_email(
+        &self,
+        id: Uuid,
+        claimed_by: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<bool> {
+        async fn exec(
+            id: Uuid,
+            claimed_by: Uuid,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<bool> {
+            let query = include_str!("queries/outbox/delete_outbox_email.sql");
+            let res = sqlx::query(query)
+                .bind(id)
+                .bind(claimed_by)
+                .execute(&mut **tx)
+                .await
+                .context("error deleting email from the outbox")?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, id, claimed_by)
+    }
+}
+
+impl PgBackend {
+    /// Decrypt the recovery email and temporary password of an email in the outbox.
+    ///
+    /// * `email`: The email as it was read from the database
+    fn decrypt_outbox_email(&self, email: OutboxEmail) -> Result<OutboxEmail> {
+        Ok(OutboxEmail {
+            email: self.decrypt_field(email.email)?,
+            temporary_password: email
+                .temporary_password
+                .map(|password| self.decrypt_field(password))
+                .transpose()?,
+            ..email
+        })
+    }
+}
@@ -0,0 +1,188 @@
+//! Configuration and metrics for the database connection pools.
+//!
+//! Every transaction the storage layer runs waits for a connection from a pool first. When many
+//! large exports run at once they can hold every connection, so other queries wait until they
+//! time out. The pools record how long each wait took so that this shows up in the pool stats and
+//! the logs instead of only as slow requests.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use derive_builder::Builder;
+use serde::Serialize;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::{Database, PgPool, Pool};
+
+use crate::services::storage::timing::QueryTimings;
+
+/// Waiting longer than this for a connection is logged as a warning.
+pub const SLOW_ACQUIRE_THRESHOLD: Duration = Duration::from_secs(1);
+
+/// How a connection pool is sized and how long connections are waited for and kept.
+///
+/// * `max_connections`: The most connections the pool opens at once
+/// * `acquire_timeout`: How long to wait for a connection before giving up
+/// * `idle_timeout`: How long a connection may sit unused before it is closed. If `None`, idle
+///   connections are kept open.
+#[derive(Debug, Clone, Builder)]
+pub struct PoolConfig {
+    #[builder(default = "100")]
+    pub max_connections: u32,
+    #[builder(default = "Duration::from_secs(30)")]
+    pub acquire_timeout: Duration,
+    #[builder(default = "Some(Duration::from_secs(600))")]
+    pub idle_timeout: Option<Duration>,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        PoolConfigBuilder::default().build().expect("every pool config field has a default")
+    }
+}
+
+impl PoolConfig {
+    /// Connect a pool with this configuration.
+    ///
+    /// * `url`: A string slice that holds a valid Postgres URL
+    pub async fn connect(&self, url: &str) -> Result<PgPool> {
+        PgPoolOptions::new()
+            .max_connections(self.max_connections)
+            .acquire_timeout(self.acquire_timeout)
+            .idle_timeout(self.idle_timeout)
+            .connect(url)
+            .await
+            .context("create pgpool")
+    }
+}
+
+/// Counters for the connections acquired from a single pool.
+///
+/// * `acquired`: The number of connections acquired
+/// * `failed`: The number of acquires that failed, most often because they timed out
+/// * `wait_micros`: The total time spent waiting for connections, in microseconds
+/// * `max_wait_micros`: The longest time spent waiting for a connection, in microseconds
+#[derive(Debug, Default)]
+pub struct AcquireMetrics {
+    acquired: AtomicU64,
+    failed: AtomicU64,
+    wait_micros: AtomicU64,
+    max_wait_micros: AtomicU64,
+}
+
+impl AcquireMetrics {
+    /// Record an attempt to acquire a connection, and warn if it was slow or failed.
+    ///
+    /// * `pool`: The pool the connection was acquired from
+    /// * `name`: The name of the pool, for logs (e.g. `primary`)
+    /// * `waited`: How long the attempt took
+    /// * `succeeded`: Whether a connection was acquired
+    pub fn record<DB: Database>(
+        &self,
+        pool: &Pool<DB>,
+        name: &str,
+        waited: Duration,
+        succeeded: bool,
+    ) {
+        let micros = u64::try_from(waited.as_micros()).unwrap_or(u64::MAX);
+        if succeeded {
+            self.acquired.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+        self.wait_micros.fetch_add(micros, Ordering::Relaxed);
+        self.max_wait_micros.fetch_max(micros, Ordering::Relaxed);
+
+        if !succeeded || waited >= SLOW_ACQUIRE_THRESHOLD {
+            let utilization = PoolUtilization::new(pool, self);
+            log::warn!(
+                "{} acquiring a {name} database connection after {}ms ({} of {} connections in use)",
+                if succeeded { "Slow" } else { "Failed" },
+                waited.as_millis(),
+                utilization.in_use,
+                utilization.max_connections,
+            );
+        }
+    }
+}
+
+/// Metrics for the pools of a backend.
+///
+/// * `primary`: Metrics for the pool of the primary database
+/// * `replica`: Metrics for the pool of the read-only replica, if there is one
+/// * `queries`: How long the storage operations run against the pools took
+#[derive(Debug, Default)]
+pub struct PoolMetrics {
+    pub primary: AcquireMetrics,
+    pub replica: AcquireMetrics,
+    pub queries: QueryTimings,
+}
+
+/// A snapshot of how a single pool is being used.
+///
+/// * `max_connections`: The most connections the pool opens at once
+/// * `open`: The number of connections currently open
+/// * `idle`: The number of open connections not in use
+/// * `in_use`: The number of open connections in use
+/// * `acquired`: The number of connections acquired since startup
+/// * `failed`: The number of acquires that failed since startup
+/// * `avg_acquire_ms`: The average time spent waiting for a connection, in milliseconds
+/// * `max_acquire_ms`: The longest time spent waiting for a connection, in milliseconds
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolUtilization {
+    pub max_connections: u32,
+    pub open: u32,
+    pub idle: u32,
+    pub in_use: u32,
+    pub acquired: u64,
+    pub failed: u64,
+    pub avg_acquire_ms: f64,
+    pub max_acquire_ms: f64,
+}
+
+impl PoolUtilization {
+    /// Take a snapshot of a pool.
+    ///
+    /// * `pool`: The pool
+    /// * `metrics`: The metrics recorded for the pool
+    pub fn new<DB: Database>(pool: &Pool<DB>, metrics: &AcquireMetrics) -> Self {
+        let open = pool.size();
+        let idle = u32::try_from(pool.num_idle()).unwrap_or(open);
+        let acquired = metrics.acquired.load(Ordering::Relaxed);
+        let failed = metrics.failed.load(Ordering::Relaxed);
+        let wait_micros = metrics.wait_micros.load(Ordering::Relaxed);
+        let attempts = acquired + failed;
+
+        Self {
+            max_connections: pool.options().get_max_connections(),
+            open,
+            idle,
+            in_use: open.saturating_sub(idle),
+            acquired,
+            failed,
+            avg_acquire_ms: if attempts == 0 {
+                0.0
+            } else {
+                wait_micros as f64 / attempts as f64 / 1000.0
+            },
+            max_acquire_ms: metrics.max_wait_micros.load(Ordering::Relaxed) as f64 / 1000.0,
+        }
+    }
+}
+
+/// A snapshot of how the pools of a backend are being used.
+///
+/// * `primary`: The pool of the primary database
+/// * `replica`: The pool of the read-only replica, if there is one
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolStats {
+    pub primary: PoolUtilization,
+    pub replica: Option<PoolUtilization>,
+}
+
+/// `PoolMonitor` is a trait for reporting how a backend's connection pools are being used.
+pub trait PoolMonitor {
+    fn pool_stats(&self) -> PoolStats;
+}
@@ -0,0 +1,29 @@
+select
+  id,
+  created_at,
+  principal,
+  entity,
+  entity_id,
+  operation,
+  before,
+  after
+from
+  audit_log
+where ($1::text is null
+  or entity = $1)
+and ($2::text is null
+  or entity_id = $2)
+and ($3::text is null
+  or lower(principal) = lower($3))
+and ($4::audit_operation is null
+  or operation = $4)
+and ($5::timestamptz is null
+  or created_at >= $5)
+and ($6::timestamptz is null
+  or created_at < $6)
+and ($7::timestamptz is null
+  or (created_at, id) < ($7, $8::uuid))
+order by
+  created_at desc,
+  id desc
+limit $9;
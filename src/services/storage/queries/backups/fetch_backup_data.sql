@@ -0,0 +1,23 @@
+-- Row level security limits every table to the current organization, so this reads only its data
+select
+  jsonb_build_object('organization', (
+      select
+        to_jsonb(o)
+      from organizations o
+      where
+        o.id = current_org_id()), 'project_cycles', (
+      select
+        coalesce(jsonb_agg(to_jsonb(pc)), '[]'::jsonb)
+      from project_cycles pc), 'volunteers', (
+      select
+        coalesce(jsonb_agg(to_jsonb(v)), '[]'::jsonb)
+      from volunteers v), 'volunteers_exported_to_workspace', (
+      select
+        coalesce(jsonb_agg(to_jsonb(e)), '[]'::jsonb)
+      from volunteers_exported_to_workspace e), 'jobs', (
+      select
+        coalesce(jsonb_agg(to_jsonb(j)), '[]'::jsonb)
+      from jobs j), 'job_archives', (
+      select
+        coalesce(jsonb_agg(to_jsonb(a)), '[]'::jsonb)
+      from job_archives a));
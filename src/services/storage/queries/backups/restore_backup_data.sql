@@ -0,0 +1,89 @@
+-- Rows that already exist are left as they are, so restoring only brings back what is missing.
+-- Foreign keys are checked once the whole statement has run, so the order of the inserts does not
+-- matter.
+with restored_organization as (
+  insert into organizations
+  select
+    *
+  from
+    jsonb_populate_record(null::organizations, $1 -> 'organization')
+  where
+    $1 -> 'organization' is not null
+  on conflict do nothing
+),
+restored_project_cycles as (
+  insert into project_cycles
+  select
+    *
+  from
+    jsonb_populate_recordset(null::project_cycles, $1 -> 'project_cycles')
+  on conflict do nothing
+returning
+  1
+),
+restored_volunteers as (
+  insert into volunteers
+  select
+    *
+  from
+    jsonb_populate_recordset(null::volunteers, $1 -> 'volunteers')
+  on conflict do nothing
+returning
+  1
+),
+restored_exported_volunteers as (
+  insert into volunteers_exported_to_workspace
+  select
+    *
+  from
+    jsonb_populate_recordset(null::volunteers_exported_to_workspace, $1 -> 'volunteers_exported_to_workspace')
+  on conflict do nothing
+returning
+  1
+),
+restored_jobs as (
+  insert into jobs
+  select
+    *
+  from
+    jsonb_populate_recordset(null::jobs, $1 -> 'jobs')
+  on conflict do nothing
+returning
+  1
+),
+restored_job_archives as (
+  insert into job_archives
+  select
+    *
+  from
+    jsonb_populate_recordset(null::job_archives, $1 -> 'job_archives')
+  on conflict do nothing
+returning
+  1
+)
+select
+  (
+    select
+      count(*)
+    from
+      restored_project_cycles) as project_cycles,
+  (
+    select
+      count(*)
+    from
+      restored_volunteers) as volunteers,
+  (
+    select
+      count(*)
+    from
+      restored_exported_volunteers) as exported_volunteers,
+  (
+    select
+      count(*)
+    from
+      restored_jobs) as jobs,
+  (
+    select
+      count(*)
+    from
+      restored_job_archives) as job_archives;
@@ -0,0 +1,20 @@
+update
+  workspace_deletions
+set
+  status = 'cancelled',
+  cancelled_by = $2
+where
+  id = $1
+  and status = 'scheduled'
+returning
+  id,
+  created_at,
+  updated_at,
+  job_id,
+  volunteer_id,
+  workspace_email,
+  delete_after,
+  status,
+  error,
+  principal,
+  cancelled_by;
@@ -0,0 +1,14 @@
+with deletion as (
+  update
+    workspace_deletions
+  set
+    status = 'deleted',
+    error = null
+  where
+    id = $1
+  returning
+    volunteer_id,
+    workspace_email)
+delete from volunteers_exported_to_workspace vew using deletion
+where vew.volunteer_id = deletion.volunteer_id
+  and vew.workspace_email = deletion.workspace_email;
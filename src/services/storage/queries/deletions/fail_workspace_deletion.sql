@@ -0,0 +1,7 @@
+update
+  workspace_deletions
+set
+  status = 'failed',
+  error = $2
+where
+  id = $1;
@@ -0,0 +1,20 @@
+select
+  id,
+  created_at,
+  updated_at,
+  job_id,
+  volunteer_id,
+  workspace_email,
+  delete_after,
+  status,
+  error,
+  principal,
+  cancelled_by
+from
+  workspace_deletions
+where
+  status = 'scheduled'
+  and delete_after <= now()
+order by
+  delete_after
+limit $1;
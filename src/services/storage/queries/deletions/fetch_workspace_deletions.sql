@@ -0,0 +1,19 @@
+select
+  d.id,
+  d.created_at,
+  d.updated_at,
+  d.job_id,
+  d.volunteer_id,
+  d.workspace_email,
+  d.delete_after,
+  d.status,
+  d.error,
+  d.principal,
+  d.cancelled_by
+from
+  workspace_deletions d
+  join volunteers v on v.id = d.volunteer_id
+where
+  v.project_cycle_id = $1
+order by
+  d.created_at desc;
@@ -0,0 +1 @@
+insert into workspace_deletions(job_id, volunteer_id, workspace_email, delete_after, principal)
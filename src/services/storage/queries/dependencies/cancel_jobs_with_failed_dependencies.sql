@@ -0,0 +1,45 @@
+-- A dependency has failed for good once it is cancelled or dead-lettered, or once it has errored
+-- without an attempt left in the queue (a queued job is errored between its attempts)
+with cancelled as (
+  update
+    jobs
+  set
+    status = 'cancelled',
+    version = jobs.version + 1,
+    details = jsonb_set(jobs.details, '{error}', to_jsonb('job ' || dependency.id || ' it depends on did not complete'), true)
+  from
+    jobs dependency
+  where
+    jobs.status = 'pending'
+    and dependency.id = jobs.depends_on
+    and (dependency.status in ('cancelled', 'dead_lettered')
+      or (dependency.status = 'error'
+        and not exists (
+          select
+            1
+          from
+            job_queue
+          where
+            job_queue.job_id = dependency.id
+            and job_queue.finished_at is null)))
+  returning
+    jobs.id,
+    jobs.details ->> 'error' as error
+),
+finished as (
+  update
+    job_queue
+  set
+    claimed_by = null,
+    finished_at = now(),
+    error = cancelled.error
+  from
+    cancelled
+  where
+    job_queue.job_id = cancelled.id
+    and job_queue.finished_at is null
+)
+select
+  id
+from
+  cancelled;
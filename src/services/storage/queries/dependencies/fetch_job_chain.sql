@@ -0,0 +1,43 @@
+-- Walk up to the first job of the chain, then down to every job that depends on it, directly or
+-- not. A job only ever depends on one that existed before it, so the chain has no cycles.
+with recursive ancestors as (
+  select
+    id,
+    depends_on
+  from
+    jobs
+  where
+    id = $1
+  union
+  select
+    jobs.id,
+    jobs.depends_on
+  from
+    jobs
+    join ancestors on jobs.id = ancestors.depends_on
+),
+chain as (
+  select
+    id
+  from
+    ancestors
+  where
+    depends_on is null
+  union
+  select
+    jobs.id
+  from
+    jobs
+    join chain on jobs.depends_on = chain.id
+)
+select
+  jobs.id,
+  jobs.created_at,
+  jobs.label,
+  jobs.status,
+  jobs.depends_on
+from
+  jobs
+  join chain on jobs.id = chain.id
+order by
+  jobs.created_at;
@@ -0,0 +1,6 @@
+update
+  jobs
+set
+  depends_on = $2
+where
+  id = $1;
@@ -0,0 +1,12 @@
+select
+  id,
+  created_at,
+  project_cycle_id,
+  job_id,
+  drive_id,
+  name
+from
+  workspace_shared_drives
+where
+  project_cycle_id = $1
+  and name = $2;
@@ -0,0 +1,4 @@
+insert into workspace_shared_drives(project_cycle_id, job_id, drive_id, name)
+  values ($1, $2, $3, $4)
+on conflict (project_cycle_id, name)
+  do nothing;
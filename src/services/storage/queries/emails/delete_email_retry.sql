@@ -0,0 +1,2 @@
+delete from onboarding_email_retries
+where id = $1;
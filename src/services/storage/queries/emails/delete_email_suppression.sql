@@ -0,0 +1,2 @@
+delete from email_suppressions
+where email = $1;
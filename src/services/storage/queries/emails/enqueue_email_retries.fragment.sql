@@ -0,0 +1 @@
+insert into onboarding_email_retries(job_id, volunteer_id, first_name, last_name, email, workspace_email, temporary_password, locale)
@@ -0,0 +1,26 @@
+select
+  id,
+  created_at,
+  updated_at,
+  job_id,
+  volunteer_id,
+  first_name,
+  last_name,
+  email,
+  workspace_email,
+  temporary_password,
+  locale,
+  attempts,
+  next_attempt_at,
+  exhausted,
+  last_error
+from
+  onboarding_email_retries
+where
+  not exhausted
+  and next_attempt_at <= now()
+  and ($1::uuid is null
+    or job_id = $1)
+order by
+  next_attempt_at
+limit $2;
@@ -0,0 +1,20 @@
+with latest as (
+  select distinct on (recipient)
+    event
+  from
+    email_delivery_events
+  where
+    job_id = $1
+  order by
+    recipient,
+    event_at desc,
+    created_at desc
+)
+select
+  count(*) filter (where event = 'queued') as queued,
+  count(*) filter (where event = 'sent') as sent,
+  count(*) filter (where event = 'delivered') as delivered,
+  count(*) filter (where event = 'bounced') as bounced,
+  count(*) filter (where event = 'dropped') as dropped
+from
+  latest;
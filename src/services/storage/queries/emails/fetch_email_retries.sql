@@ -0,0 +1,22 @@
+select
+  id,
+  created_at,
+  updated_at,
+  job_id,
+  volunteer_id,
+  first_name,
+  last_name,
+  email,
+  workspace_email,
+  temporary_password,
+  locale,
+  attempts,
+  next_attempt_at,
+  exhausted,
+  last_error
+from
+  onboarding_email_retries
+where
+  job_id = $1
+order by
+  created_at;
@@ -0,0 +1,19 @@
+select
+  id,
+  created_at,
+  job_id,
+  volunteer_id,
+  recipient,
+  template,
+  provider,
+  message_id,
+  status,
+  error
+from
+  email_sends
+where
+  job_id = $1
+  and ($2::text is null
+    or recipient = $2)
+order by
+  created_at;
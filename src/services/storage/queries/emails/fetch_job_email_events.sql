@@ -0,0 +1,20 @@
+select
+  id,
+  created_at,
+  job_id,
+  volunteer_id,
+  provider,
+  message_id,
+  recipient,
+  event,
+  reason,
+  event_at
+from
+  email_delivery_events
+where
+  job_id = $1
+  and ($2::text is null
+    or recipient = $2)
+order by
+  event_at,
+  created_at;
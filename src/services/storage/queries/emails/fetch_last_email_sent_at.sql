@@ -0,0 +1,7 @@
+select
+  max(created_at)
+from
+  email_sends
+where
+  template like $1 || '.%'
+  and status = 'sent';
@@ -0,0 +1,18 @@
+select
+  id,
+  created_at,
+  job_id,
+  volunteer_id,
+  provider,
+  message_id,
+  recipient,
+  event,
+  reason,
+  event_at
+from
+  email_delivery_events
+where
+  message_id = $1
+order by
+  event_at,
+  created_at;
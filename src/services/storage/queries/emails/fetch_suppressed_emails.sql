@@ -0,0 +1,12 @@
+select
+  email,
+  created_at,
+  updated_at,
+  reason,
+  provider,
+  detail,
+  suppressed_at
+from
+  email_suppressions
+where
+  email = any ($1);
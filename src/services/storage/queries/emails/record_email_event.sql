@@ -0,0 +1,28 @@
+-- Events the provider reports may not say which job or volunteer the email was sent for, so they
+-- are taken from an earlier event for the same message
+insert into email_delivery_events(job_id, volunteer_id, provider, message_id, recipient, event, reason, event_at)
+select
+  coalesce($1, earlier.job_id),
+  coalesce($2, earlier.volunteer_id),
+  coalesce($3, earlier.provider),
+  $4,
+  $5,
+  $6,
+  $7,
+  $8
+from (
+  select
+    1) as event
+  left join lateral (
+    select
+      e.job_id,
+      e.volunteer_id,
+      e.provider
+    from
+      email_delivery_events e
+    where
+      e.message_id = $4
+    order by
+      e.event_at
+    limit 1) as earlier on true
+on conflict do nothing;
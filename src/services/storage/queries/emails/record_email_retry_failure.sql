@@ -0,0 +1,9 @@
+update
+  onboarding_email_retries
+set
+  attempts = attempts + 1,
+  last_error = $2,
+  next_attempt_at = coalesce($3::timestamptz, next_attempt_at),
+  exhausted = $3::timestamptz is null
+where
+  id = $1;
@@ -0,0 +1,13 @@
+insert into email_sends(job_id, volunteer_id, recipient, template, provider, message_id, status, error)
+  values ($1, $2, $3, $4, $5, $6, $7, $8)
+returning
+  id,
+  created_at,
+  job_id,
+  volunteer_id,
+  recipient,
+  template,
+  provider,
+  message_id,
+  status,
+  error;
@@ -0,0 +1,8 @@
+update
+  onboarding_email_retries
+set
+  attempts = 0,
+  next_attempt_at = now(),
+  exhausted = false
+where
+  job_id = $1;
@@ -0,0 +1 @@
+insert into email_suppressions(email, reason, provider, detail, suppressed_at)
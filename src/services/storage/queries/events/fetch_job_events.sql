@@ -0,0 +1,12 @@
+select
+  id,
+  job_id,
+  created_at,
+  event
+from
+  job_events
+where
+  job_id = $1
+  and id > $2
+order by
+  id;
@@ -0,0 +1,7 @@
+insert into job_events(job_id, event)
+  values ($1, $2)
+returning
+  id,
+  job_id,
+  created_at,
+  event;
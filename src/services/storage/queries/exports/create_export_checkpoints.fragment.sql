@@ -0,0 +1 @@
+insert into workspace_export_checkpoints(job_id, volunteer_id, workspace_email, org_unit)
@@ -0,0 +1,13 @@
+select
+  job_id,
+  volunteer_id,
+  created_at,
+  updated_at,
+  workspace_email,
+  org_unit,
+  status,
+  error
+from
+  workspace_export_checkpoints
+where
+  job_id = $1;
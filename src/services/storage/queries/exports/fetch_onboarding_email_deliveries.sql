@@ -0,0 +1,14 @@
+select
+  job_id,
+  volunteer_id,
+  created_at,
+  updated_at,
+  email,
+  status,
+  reason,
+  message_id,
+  event_at
+from
+  onboarding_email_deliveries
+where
+  job_id = $1;
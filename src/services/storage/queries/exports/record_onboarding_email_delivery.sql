@@ -0,0 +1,31 @@
+insert into onboarding_email_deliveries(job_id, volunteer_id, email, status, reason, message_id, event_at)
+select
+  $1,
+  $2,
+  $3,
+  $4,
+  $5,
+  $6,
+  $7
+where
+  exists (
+    select
+      1
+    from
+      jobs
+    where
+      id = $1)
+  and exists (
+    select
+      1
+    from
+      volunteers
+    where
+      id = $2)
+on conflict (job_id, volunteer_id)
+  do update set
+    email = excluded.email, status = excluded.status, reason = excluded.reason, message_id =
+      coalesce(excluded.message_id, onboarding_email_deliveries.message_id), event_at =
+      excluded.event_at
+  where
+    excluded.event_at >= onboarding_email_deliveries.event_at;
@@ -0,0 +1,8 @@
+update
+  workspace_export_checkpoints
+set
+  status = $3,
+  error = $4
+where
+  job_id = $1
+  and volunteer_id = $2;
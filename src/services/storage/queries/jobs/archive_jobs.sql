@@ -0,0 +1,41 @@
+-- Finished jobs are archived oldest first, a batch at a time. Their progress is only useful while
+-- they run, so it is deleted.
+with archived as (
+  select
+    id,
+    details
+  from
+    jobs
+  where
+    archived_at is null
+    and status <> 'pending'
+    and coalesce(updated_at, created_at) < $1
+  order by
+    created_at
+  limit $2
+  for update
+    skip locked
+),
+stored as (
+  insert into job_archives(job_id, details)
+  select
+    id,
+    details
+  from
+    archived),
+progress as (
+  delete from job_progress
+  where job_id in (
+      select
+        id
+      from
+        archived))
+update
+  jobs j
+set
+  archived_at = now(),
+  details = jsonb_strip_nulls(jsonb_build_object('jobType', a.details -> 'jobType', 'error', a.details -> 'error'))
+from
+  archived a
+where
+  j.id = a.id;
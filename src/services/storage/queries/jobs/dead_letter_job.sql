@@ -0,0 +1,10 @@
+-- A job that was already finished by its last attempt, as errored, is dead-lettered as well
+update
+  jobs
+set
+  status = 'dead_lettered',
+  version = version + 1,
+  details = jsonb_set(details, '{error}', to_jsonb($2::text), true)
+where
+  id = $1
+  and status in ('pending', 'error');
@@ -0,0 +1,19 @@
+select
+  id,
+  created_at,
+  updated_at,
+  archived_at,
+  project_cycle_id,
+  status,
+  label,
+  description,
+  details ->> 'jobType' as job_type,
+  details ->> 'error' as error
+from
+  jobs
+where
+  archived_at is not null
+  and ($1::uuid is null
+    or project_cycle_id = $1)
+order by
+  created_at desc;
@@ -0,0 +1,19 @@
+select
+  id,
+  created_at,
+  updated_at,
+  project_cycle_id,
+  status,
+  label,
+  description,
+  details,
+  version
+from
+  jobs
+where
+  details ->> 'jobType' = 'airtable_export_users'
+  and status = 'error'
+  and coalesce(updated_at, created_at) >= $1
+  and coalesce(updated_at, created_at) < $2
+order by
+  created_at;
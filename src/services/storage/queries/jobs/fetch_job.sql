@@ -6,7 +6,8 @@ select
   status,
   label,
   description,
-  details
+  details,
+  version
 from
   jobs
 where
@@ -0,0 +1,6 @@
+select
+  details
+from
+  job_archives
+where
+  job_id = $1;
@@ -0,0 +1,11 @@
+select
+  job_id,
+  created_at,
+  updated_at,
+  phase,
+  processed,
+  total
+from
+  job_progress
+where
+  job_id = $1;
@@ -6,7 +6,10 @@ select
   status,
   label,
   description,
-  details
+  details,
+  version
 from
-  jobs;
+  jobs
+where
+  archived_at is null;
 
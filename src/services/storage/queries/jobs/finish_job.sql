@@ -0,0 +1,16 @@
+-- Only a pending job can be finished, and only at the version it was claimed or read at, so a job
+-- that another worker already finished, resumed, or cancelled is left as it is
+update
+  jobs
+set
+  status = $2,
+  version = version + 1,
+  details = case when $3 is not null then
+    jsonb_set(details, '{error}', to_jsonb($3::text), true)
+  else
+    details - 'error'
+  end
+where
+  id = $1
+  and version = $4
+  and status = 'pending';
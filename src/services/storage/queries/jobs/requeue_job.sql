@@ -0,0 +1,9 @@
+update
+  jobs
+set
+  status = 'pending',
+  version = version + 1,
+  details = details - 'error'
+where
+  id = $1
+  and status = 'dead_lettered';
@@ -0,0 +1,12 @@
+-- A job is only resumed if its status has not changed since it was read at the given version, so
+-- two workers can't both resume it
+update
+  jobs
+set
+  status = 'pending',
+  version = version + 1,
+  details = details - 'error'
+where
+  id = $1
+  and version = $2
+  and status in ('pending', 'error', 'dead_lettered');
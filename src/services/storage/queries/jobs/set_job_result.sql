@@ -0,0 +1,6 @@
+update
+  jobs
+set
+  details = jsonb_set(details, '{result}', $2, true)
+where
+  id = $1;
@@ -2,6 +2,7 @@ update
   jobs
 set
   status = $2,
+  version = version + 1,
   details = case when $3 is not null then
     jsonb_set(details, '{error}', to_jsonb($3::text), true)
   when $3 is null
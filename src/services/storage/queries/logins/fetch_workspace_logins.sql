@@ -0,0 +1,24 @@
+select distinct on (vew.volunteer_id, vew.workspace_email)
+  vew.volunteer_id,
+  v.first_name,
+  v.last_name,
+  v.email,
+  vew.workspace_email,
+  vew.created_at as exported_at,
+  wl.last_login_at,
+  wl.checked_at
+from
+  volunteers_exported_to_workspace vew
+  join volunteers v on v.id = vew.volunteer_id
+  left join workspace_logins wl on wl.workspace_email = vew.workspace_email
+where
+  v.project_cycle_id = $1
+  and v.deleted_at is null
+  and vew.deleted_at is null
+  and (not $2
+    or (wl.checked_at is not null
+      and wl.last_login_at is null))
+order by
+  vew.volunteer_id,
+  vew.workspace_email,
+  vew.created_at desc;
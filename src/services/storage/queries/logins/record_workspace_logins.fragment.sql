@@ -0,0 +1 @@
+insert into workspace_logins(volunteer_id, workspace_email, last_login_at)
@@ -0,0 +1,4 @@
+insert into organizations(name)
+  values ($1)
+returning
+  id;
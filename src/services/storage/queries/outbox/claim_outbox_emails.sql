@@ -0,0 +1,35 @@
+update
+  email_outbox
+set
+  claimed_by = $1,
+  claimed_until = $4
+where
+  id in (
+    select
+      id
+    from
+      email_outbox
+    where
+      claimed_until <= now()
+      and ($2::uuid is null
+        or job_id = $2)
+    order by
+      created_at
+    limit $3
+    for update
+      skip locked)
+returning
+  id,
+  created_at,
+  updated_at,
+  job_id,
+  volunteer_id,
+  first_name,
+  last_name,
+  email,
+  workspace_email,
+  temporary_password,
+  locale,
+  onboarding_session_link,
+  claimed_by,
+  claimed_until;
@@ -0,0 +1,3 @@
+delete from email_outbox
+where id = $1
+  and claimed_by = $2;
@@ -0,0 +1 @@
+insert into email_outbox(id, job_id, volunteer_id, first_name, last_name, email, workspace_email, temporary_password, locale, onboarding_session_link, claimed_by, claimed_until)
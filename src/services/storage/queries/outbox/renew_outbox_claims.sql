@@ -0,0 +1,9 @@
+update
+  email_outbox
+set
+  claimed_until = $3
+where
+  claimed_by = $1
+  and id = any ($2)
+returning
+  id;
@@ -0,0 +1,46 @@
+update
+  job_queue
+set
+  claimed_by = $1,
+  claimed_until = $2,
+  attempts = attempts + 1
+where
+  job_id = (
+    select
+      job_id
+    from
+      job_queue
+    where
+      finished_at is null
+      and claimed_until <= now()
+      and run_at <= now()
+      -- A job waits until the job it depends on has completed
+      and not exists (
+        select
+          1
+        from
+          jobs
+          join jobs dependency on dependency.id = jobs.depends_on
+        where
+          jobs.id = job_queue.job_id
+          and dependency.status <> 'complete')
+    order by
+      priority,
+      created_at
+    limit 1
+    for update
+      skip locked)
+returning
+  job_id,
+  created_at,
+  updated_at,
+  payload,
+  priority,
+  run_at,
+  attempts,
+  claimed_by,
+  claimed_until,
+  finished_at,
+  error,
+  attempt_errors,
+  dead_lettered_at;
@@ -0,0 +1,12 @@
+update
+  job_queue
+set
+  claimed_by = null,
+  finished_at = now(),
+  dead_lettered_at = now(),
+  error = $3,
+  attempt_errors = attempt_errors || jsonb_build_array($4::jsonb)
+where
+  job_id = $1
+  and claimed_by = $2
+  and finished_at is null;
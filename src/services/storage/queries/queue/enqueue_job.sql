@@ -0,0 +1,17 @@
+insert into job_queue(job_id, payload, priority, run_at)
+  values ($1, $2, $3, coalesce($4, now()))
+on conflict (job_id)
+  do update set
+    payload = excluded.payload,
+    priority = excluded.priority,
+    run_at = excluded.run_at,
+    attempts = 0,
+    claimed_by = null,
+    claimed_until = now(),
+    finished_at = null,
+    error = null,
+    dead_lettered_at = null
+  where
+    job_queue.finished_at is not null
+  returning
+    job_id;
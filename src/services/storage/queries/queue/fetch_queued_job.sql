@@ -0,0 +1,18 @@
+select
+  job_id,
+  created_at,
+  updated_at,
+  payload,
+  priority,
+  run_at,
+  attempts,
+  claimed_by,
+  claimed_until,
+  finished_at,
+  error,
+  attempt_errors,
+  dead_lettered_at
+from
+  job_queue
+where
+  job_id = $1;
@@ -0,0 +1,10 @@
+update
+  job_queue
+set
+  claimed_by = null,
+  finished_at = now(),
+  error = $3
+where
+  job_id = $1
+  and claimed_by = $2
+  and finished_at is null;
@@ -0,0 +1,8 @@
+update
+  job_queue
+set
+  claimed_until = $3
+where
+  job_id = $1
+  and claimed_by = $2
+  and finished_at is null;
@@ -0,0 +1,13 @@
+-- The attempts start over, but the errors of the earlier ones are kept
+update
+  job_queue
+set
+  attempts = 0,
+  claimed_by = null,
+  claimed_until = now(),
+  finished_at = null,
+  dead_lettered_at = null,
+  error = null
+where
+  job_id = $1
+  and dead_lettered_at is not null;
@@ -0,0 +1,9 @@
+-- Only a job that no worker has started can be rescheduled
+update
+  job_queue
+set
+  run_at = $2
+where
+  job_id = $1
+  and attempts = 0
+  and finished_at is null;
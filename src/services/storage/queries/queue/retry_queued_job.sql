@@ -0,0 +1,12 @@
+-- The job is released rather than finished, and can't be claimed again until its backoff is over
+update
+  job_queue
+set
+  claimed_by = null,
+  claimed_until = $3,
+  error = $4,
+  attempt_errors = attempt_errors || jsonb_build_array($5::jsonb)
+where
+  job_id = $1
+  and claimed_by = $2
+  and finished_at is null;
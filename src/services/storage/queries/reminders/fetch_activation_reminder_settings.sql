@@ -0,0 +1,12 @@
+select
+  project_cycle_id,
+  created_at,
+  updated_at,
+  enabled,
+  remind_after_days,
+  locale,
+  principal
+from
+  activation_reminder_settings
+where
+  project_cycle_id = $1;
@@ -0,0 +1,15 @@
+select
+  r.id,
+  r.created_at,
+  r.job_id,
+  r.volunteer_id,
+  r.workspace_email,
+  r.status,
+  r.detail
+from
+  activation_reminders r
+  join volunteers v on v.id = r.volunteer_id
+where
+  v.project_cycle_id = $1
+order by
+  r.created_at desc;
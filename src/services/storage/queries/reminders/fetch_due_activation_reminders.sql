@@ -0,0 +1,31 @@
+select
+  s.project_cycle_id,
+  vew.job_id,
+  vew.volunteer_id,
+  v.first_name,
+  v.last_name,
+  v.email,
+  vew.workspace_email,
+  vew.created_at as exported_at,
+  s.locale,
+  s.principal
+from
+  volunteers_exported_to_workspace vew
+  join volunteers v on v.id = vew.volunteer_id
+  join activation_reminder_settings s on s.project_cycle_id = v.project_cycle_id
+where
+  s.enabled
+  and v.deleted_at is null
+  and vew.deleted_at is null
+  and ($1::uuid is null or s.project_cycle_id = $1)
+  and vew.created_at <= now() - make_interval(days => s.remind_after_days)
+  and not exists (
+    select
+      1
+    from
+      activation_reminders r
+    where
+      r.job_id = vew.job_id
+      and r.volunteer_id = vew.volunteer_id)
+order by
+  vew.created_at;
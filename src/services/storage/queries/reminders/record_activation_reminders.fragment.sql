@@ -0,0 +1 @@
+insert into activation_reminders(job_id, volunteer_id, workspace_email, status, detail)
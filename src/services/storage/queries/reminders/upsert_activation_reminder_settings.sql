@@ -0,0 +1,16 @@
+insert into activation_reminder_settings(project_cycle_id, enabled, remind_after_days, locale, principal)
+  values ($1, $2, $3, $4, $5)
+on conflict (project_cycle_id)
+  do update set
+    enabled = excluded.enabled,
+    remind_after_days = excluded.remind_after_days,
+    locale = excluded.locale,
+    principal = excluded.principal
+  returning
+    project_cycle_id,
+    created_at,
+    updated_at,
+    enabled,
+    remind_after_days,
+    locale,
+    principal;
@@ -0,0 +1,6 @@
+select
+  count(*)
+from
+  onboarding_email_retries
+where
+  created_at < $1;
@@ -0,0 +1,15 @@
+select
+  v.id
+from
+  volunteers v
+  join project_cycles pc on pc.id = v.project_cycle_id
+where
+  pc.archived
+  and pc.archived_at < $1
+  and exists (
+    select
+      1
+    from
+      volunteers_exported_to_workspace vew
+    where
+      vew.volunteer_id = v.id);
@@ -0,0 +1,5 @@
+-- The changes made to a volunteer's rows, including their deletion, hold copies of their data
+delete from audit_log
+where (entity = 'volunteers'
+  and entity_id = any ($1::uuid[]::text[]))
+  or coalesce(after, before) ->> 'volunteer_id' = any ($1::uuid[]::text[]);
@@ -0,0 +1,6 @@
+-- Email sends outlive the volunteer they were sent to, so they are deleted first
+with sends as (
+  delete from email_sends
+  where volunteer_id = any ($1))
+delete from volunteers
+where id = any ($1);
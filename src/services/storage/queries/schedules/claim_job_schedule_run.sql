@@ -0,0 +1,11 @@
+update
+  job_schedules
+set
+  next_run_at = $3,
+  last_run_at = now(),
+  last_job_id = null,
+  last_error = null
+where
+  name = $1
+  and enabled
+  and next_run_at = $2;
@@ -0,0 +1,2 @@
+delete from job_schedules
+where name = $1;
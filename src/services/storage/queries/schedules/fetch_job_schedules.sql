@@ -0,0 +1,16 @@
+select
+  name,
+  cron,
+  enabled,
+  params,
+  created_at,
+  updated_at,
+  updated_by,
+  next_run_at,
+  last_run_at,
+  last_job_id,
+  last_error
+from
+  job_schedules
+order by
+  name;
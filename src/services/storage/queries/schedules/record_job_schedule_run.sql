@@ -0,0 +1,7 @@
+update
+  job_schedules
+set
+  last_job_id = $2,
+  last_error = $3
+where
+  name = $1;
@@ -0,0 +1,21 @@
+insert into job_schedules(name, cron, enabled, params, updated_by, next_run_at)
+  values ($1, $2, $3, $4, $5, $6)
+on conflict (org_id, name)
+  do update set
+    cron = excluded.cron,
+    enabled = excluded.enabled,
+    params = excluded.params,
+    updated_by = excluded.updated_by,
+    next_run_at = excluded.next_run_at
+  returning
+    name,
+    cron,
+    enabled,
+    params,
+    created_at,
+    updated_at,
+    updated_by,
+    next_run_at,
+    last_run_at,
+    last_job_id,
+    last_error;
@@ -0,0 +1,9 @@
+select
+  key,
+  value,
+  created_at,
+  updated_at
+from
+  settings
+order by
+  key;
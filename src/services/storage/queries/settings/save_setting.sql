@@ -0,0 +1,10 @@
+insert into settings(key, value)
+  values ($1, $2)
+on conflict (org_id, key)
+  do update set
+    value = excluded.value
+  returning
+    key,
+    value,
+    created_at,
+    updated_at;
@@ -0,0 +1,10 @@
+select
+  id,
+  job_id,
+  created_at,
+  created_by,
+  snapshot
+from
+  job_snapshots
+where
+  id = $1;
@@ -0,0 +1,11 @@
+select
+  id,
+  job_id,
+  created_at,
+  created_by
+from
+  job_snapshots
+where
+  job_id = $1
+order by
+  created_at desc;
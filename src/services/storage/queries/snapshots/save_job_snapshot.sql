@@ -0,0 +1,8 @@
+insert into job_snapshots(job_id, created_by, snapshot)
+  values ($1, $2, $3)
+returning
+  id,
+  job_id,
+  created_at,
+  created_by,
+  snapshot;
@@ -5,7 +5,8 @@ select
     from
       volunteers
     where
-      volunteers.project_cycle_id = $1) as num_volunteers,
+      volunteers.project_cycle_id = $1
+      and volunteers.deleted_at is null) as num_volunteers,
 (
     select
       count(*)
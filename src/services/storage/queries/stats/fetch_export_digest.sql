@@ -0,0 +1,67 @@
+select
+  (
+    select
+      count(*)
+    from
+      jobs
+    where
+      jobs.details ->> 'jobType' = 'airtable_export_users'
+      and jobs.created_at >= $1
+      and jobs.created_at < $2) as jobs_run,
+(
+    select
+      count(*)
+    from
+      jobs
+    where
+      jobs.details ->> 'jobType' = 'airtable_export_users'
+      and jobs.status = 'complete'
+      and coalesce(jobs.updated_at, jobs.created_at) >= $1
+      and coalesce(jobs.updated_at, jobs.created_at) < $2) as jobs_completed,
+(
+    select
+      count(*)
+    from
+      jobs
+    where
+      jobs.details ->> 'jobType' = 'airtable_export_users'
+      and jobs.status = 'error'
+      and coalesce(jobs.updated_at, jobs.created_at) >= $1
+      and coalesce(jobs.updated_at, jobs.created_at) < $2) as jobs_failed,
+(
+    select
+      count(*)
+    from
+      volunteers_exported_to_workspace
+    where
+      volunteers_exported_to_workspace.created_at >= $1
+      and volunteers_exported_to_workspace.created_at < $2) as users_exported,
+(
+    select
+      count(*)
+    from
+      email_sends
+    where
+      email_sends.job_id is not null
+      and email_sends.status = 'sent'
+      and email_sends.created_at >= $1
+      and email_sends.created_at < $2) as emails_sent,
+(
+    select
+      count(*)
+    from
+      email_sends
+    where
+      email_sends.job_id is not null
+      and email_sends.status = 'failed'
+      and email_sends.created_at >= $1
+      and email_sends.created_at < $2) as emails_failed,
+(
+    select
+      count(*)
+    from
+      onboarding_email_deliveries
+    where
+      onboarding_email_deliveries.status = 'bounced'
+      and onboarding_email_deliveries.event_at >= $1
+      and onboarding_email_deliveries.event_at < $2) as emails_bounced;
@@ -0,0 +1,17 @@
+select distinct on (vew.volunteer_id, vew.workspace_email)
+  vew.volunteer_id,
+  v.first_name,
+  v.last_name,
+  vew.workspace_email
+from
+  volunteers_exported_to_workspace vew
+  join volunteers v on v.id = vew.volunteer_id
+where
+  v.project_cycle_id = $1
+  and v.deleted_at is null
+  and vew.deleted_at is null
+  and ($2::uuid[] is null or vew.volunteer_id = any ($2))
+order by
+  vew.volunteer_id,
+  vew.workspace_email,
+  vew.created_at desc;
@@ -0,0 +1,17 @@
+select
+  s.id,
+  s.created_at,
+  s.job_id,
+  s.volunteer_id,
+  s.workspace_email,
+  s.action,
+  s.succeeded,
+  s.error,
+  s.principal
+from
+  workspace_suspensions s
+  join volunteers v on v.id = s.volunteer_id
+where
+  v.project_cycle_id = $1
+order by
+  s.created_at desc;
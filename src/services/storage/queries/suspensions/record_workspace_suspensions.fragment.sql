@@ -0,0 +1 @@
+insert into workspace_suspensions(job_id, volunteer_id, workspace_email, action, succeeded, error, principal)
@@ -0,0 +1,19 @@
+insert into email_templates(name, version, subject, body, created_by)
+select
+  $1,
+  coalesce(max(version), 0) + 1,
+  $2,
+  $3,
+  $4
+from
+  email_templates
+where
+  name = $1
+returning
+  id,
+  created_at,
+  name,
+  version,
+  subject,
+  body,
+  created_by;
@@ -0,0 +1,15 @@
+select
+  id,
+  created_at,
+  name,
+  version,
+  subject,
+  body,
+  created_by
+from
+  email_templates
+where
+  name = $1
+order by
+  version desc
+limit 1;
@@ -0,0 +1,13 @@
+select distinct on (name)
+  id,
+  created_at,
+  name,
+  version,
+  subject,
+  body,
+  created_by
+from
+  email_templates
+order by
+  name,
+  version desc;
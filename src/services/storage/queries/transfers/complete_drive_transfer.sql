@@ -0,0 +1,7 @@
+update
+  workspace_drive_transfers
+set
+  status = 'completed',
+  error = null
+where
+  id = $1;
@@ -0,0 +1 @@
+insert into workspace_drive_transfers(job_id, volunteer_id, workspace_email, archive_email, principal)
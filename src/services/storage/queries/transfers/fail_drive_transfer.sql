@@ -0,0 +1,7 @@
+update
+  workspace_drive_transfers
+set
+  status = 'failed',
+  error = $2
+where
+  id = $1;
@@ -0,0 +1,20 @@
+select
+  t.id,
+  t.created_at,
+  t.updated_at,
+  t.job_id,
+  t.volunteer_id,
+  t.workspace_email,
+  t.archive_email,
+  t.transfer_id,
+  t.status,
+  t.error,
+  t.suspended,
+  t.principal
+from
+  workspace_drive_transfers t
+  join volunteers v on v.id = t.volunteer_id
+where
+  v.project_cycle_id = $1
+order by
+  t.created_at desc;
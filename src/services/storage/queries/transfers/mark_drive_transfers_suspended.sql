@@ -0,0 +1,6 @@
+update
+  workspace_drive_transfers
+set
+  suspended = true
+where
+  id = any($1);
@@ -0,0 +1,8 @@
+update
+  workspace_drive_transfers
+set
+  status = 'in_progress',
+  transfer_id = $2,
+  error = null
+where
+  id = $1;
@@ -0,0 +1 @@
+insert into workspace_aliases(volunteer_id, job_id, workspace_email, alias)
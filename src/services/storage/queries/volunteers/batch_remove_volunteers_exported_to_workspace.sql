@@ -1,3 +1,5 @@
+with removed_aliases as (
+  delete from workspace_aliases
+  where volunteer_id = any ($1))
 delete from volunteers_exported_to_workspace
 where volunteer_id = any ($1);
-
@@ -1,3 +1,20 @@
-delete from volunteers
-where id = $1;
-
+with deleted as (
+update
+  volunteers
+set
+  deleted_at = now()
+where
+  id = $1
+  and deleted_at is null
+returning
+  id,
+  deleted_at)
+update
+  volunteers_exported_to_workspace vew
+set
+  deleted_at = deleted.deleted_at
+from
+  deleted
+where
+  vew.volunteer_id = deleted.id
+  and vew.deleted_at is null;
@@ -4,5 +4,6 @@ set
   email = $2,
   phone = $3
 where
-  id = $1;
+  id = $1
+  and deleted_at is null;
 
@@ -0,0 +1,32 @@
+select
+  id,
+  created_at,
+  updated_at,
+  volunteer_id,
+  workspace_email,
+  org_unit,
+  job_id,
+  project_cycle_id,
+  status
+from
+  exported_volunteer_details
+where ($1::uuid is null
+  or job_id = $1)
+and ($2::uuid is null
+  or project_cycle_id = $2)
+and ($3::timestamptz is null
+  or created_at >= $3)
+and ($4::timestamptz is null
+  or created_at < $4)
+and ($5::text is null
+  or $5 = '/'
+  or org_unit = $5
+  or org_unit like $5 || '/%')
+and ($6::text is null
+  or lower(split_part(workspace_email, '@', 2)) = lower($6))
+and ($7::timestamptz is null
+  or (created_at, id) < ($7, $8::uuid))
+order by
+  created_at desc,
+  id desc
+limit $9;
@@ -0,0 +1,13 @@
+select
+  workspace_email
+from
+  volunteers_exported_to_workspace
+where
+  workspace_email = any ($1)
+union
+select
+  alias
+from
+  workspace_aliases
+where
+  alias = any ($1)
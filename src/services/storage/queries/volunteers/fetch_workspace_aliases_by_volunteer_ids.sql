@@ -0,0 +1,14 @@
+select
+  id,
+  created_at,
+  volunteer_id,
+  job_id,
+  workspace_email,
+  alias
+from
+  workspace_aliases
+where
+  volunteer_id = any ($1)
+order by
+  volunteer_id,
+  created_at;
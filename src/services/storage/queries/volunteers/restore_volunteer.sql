@@ -0,0 +1,39 @@
+with deleted as (
+  select
+    id,
+    deleted_at
+  from
+    volunteers
+  where
+    id = $1
+    and deleted_at > now() - make_interval(days => $2)
+),
+restored as (
+update
+  volunteers v
+set
+  deleted_at = null
+from
+  deleted
+where
+  v.id = deleted.id
+returning
+  v.id
+),
+-- only the export records deleted along with the volunteer are restored
+restored_exports as (
+update
+  volunteers_exported_to_workspace vew
+set
+  deleted_at = null
+from
+  deleted
+where
+  vew.volunteer_id = deleted.id
+  and vew.deleted_at = deleted.deleted_at)
+select
+  exists (
+    select
+      1
+    from
+      restored) as restored;
@@ -0,0 +1,8 @@
+-- A volunteer is only linked to the nonprofits and mentors of their own cohort
+with unlinked_clients as (
+  delete from client_volunteers
+  where volunteer_id = $1
+    and project_cycle_id <> $2)
+delete from volunteer_mentors
+where volunteer_id = $1
+  and project_cycle_id <> $2;
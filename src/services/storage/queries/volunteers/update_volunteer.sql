@@ -0,0 +1,12 @@
+update
+  volunteers
+set
+  email = coalesce($2, email),
+  project_cycle_id = coalesce($3, project_cycle_id),
+  first_name = coalesce($4, first_name),
+  last_name = coalesce($5, last_name)
+where
+  id = $1
+  and deleted_at is null
+returning
+  id;
@@ -0,0 +1,34 @@
+-- The calls are claimed until $2 by pushing back when they are next due, so a call whose worker
+-- stopped is made again once the claim runs out
+update
+  job_webhooks
+set
+  attempts = job_webhooks.attempts + 1,
+  next_attempt_at = $2
+from
+  jobs
+where
+  job_webhooks.id in (
+    select
+      id
+    from
+      job_webhooks
+    where
+      finished_at is null
+      and next_attempt_at <= now()
+    order by
+      created_at
+    limit $1
+    for update
+      skip locked)
+  and jobs.id = job_webhooks.job_id
+returning
+  job_webhooks.id,
+  job_webhooks.job_id,
+  job_webhooks.status,
+  job_webhooks.created_at,
+  job_webhooks.attempts,
+  job_webhooks.next_attempt_at,
+  job_webhooks.finished_at,
+  job_webhooks.error,
+  jobs.callback_url;
@@ -0,0 +1,7 @@
+update
+  job_webhooks
+set
+  finished_at = now(),
+  error = $2
+where
+  id = $1;
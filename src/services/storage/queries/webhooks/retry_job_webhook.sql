@@ -0,0 +1,8 @@
+update
+  job_webhooks
+set
+  next_attempt_at = $2,
+  error = $3
+where
+  id = $1
+  and finished_at is null;
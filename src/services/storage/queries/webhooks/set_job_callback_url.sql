@@ -0,0 +1,6 @@
+update
+  jobs
+set
+  callback_url = $2
+where
+  id = $1;
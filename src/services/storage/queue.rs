@@ -0,0 +1,475 @@
+//! This module contains the definition of the `QueryQueue` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+//!
+//! The job queue holds the jobs waiting to be run by workers. A worker claims one job at a time and
+//! renews its claim while it runs, so a job whose worker stopped is claimed again by another once
+//! its claim runs out. Jobs are claimed by priority, so an urgent job is not stuck behind a long
+//! one that was queued before it. A job can be scheduled for later, and is not claimed until then.
+//!
+//! Claims are leases held in the queue rather than advisory locks, since a lock lasts only as long
+//! as the connection that took it, and a job can run for hours. Claiming skips rows another worker
+//! is claiming, so two workers, in the same process or not, never claim a job at the same time. A
+//! worker that finds its claim was taken over stops running the job (see `ClaimLostError`), and
+//! every change a worker makes to the queue is conditional on it still holding the claim, so each
+//! job is run by exactly one worker at a time.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Database, Postgres, Transaction};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::entities::QueuedJob;
+use super::types::JobPriority;
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// Why an attempt at running a queued job failed, kept in the queue for every attempt.
+///
+/// * `attempt`: Which attempt failed, counting from 1
+/// * `failed_at`: When the attempt failed
+/// * `errors`: The chain of errors the attempt failed with, outermost first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttemptError {
+    pub attempt: i32,
+    pub failed_at: DateTime<Utc>,
+    pub errors: Vec<String>,
+}
+
+impl AttemptError {
+    /// Record why an attempt failed.
+    ///
+    /// * `attempt`: Which attempt failed, counting from 1
+    /// * `error`: The error the attempt failed with
+    pub fn new(attempt: i32, error: &anyhow::Error) -> Self {
+        Self {
+            attempt,
+            failed_at: Utc::now(),
+            errors: error.chain().map(|e| e.to_string()).collect(),
+        }
+    }
+}
+
+/// Returned when a worker no longer holds its claim on the job it is running, because the claim ran
+/// out and another worker may have claimed the job. The worker stops running the job, and leaves
+/// recording how it went to the worker that holds the claim.
+///
+/// * `job_id`: The ID of the job
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("the claim on job {job_id} was lost to another worker")]
+pub struct ClaimLostError {
+    pub job_id: Uuid,
+}
+
+/// Whether an error was returned because a worker lost its claim on a job.
+///
+/// * `e`: The error
+pub fn is_claim_lost(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| cause.downcast_ref::<ClaimLostError>().is_some())
+}
+
+/// A trait for querying the job queue.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryQueue<DB: Database> {
+    /// Queue a job to be run by a worker. A job that has finished can be queued again, which starts
+    /// its attempts over.
+    ///
+    /// * `job_id`: The id of the job
+    /// * `payload`: What the job needs to run
+    /// * `priority`: How urgently the job should run
+    /// * `run_at`: When the job may run at the earliest, or `None` to run it as soon as a worker is
+    ///   free
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the job was queued, which it is not if it is already waiting or running.
+    async fn enqueue_job(
+        &self,
+        job_id: Uuid,
+        payload: serde_json::Value,
+        priority: JobPriority,
+        run_at: Option<DateTime<Utc>>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Claim the job with the highest priority in the queue that has not finished and that no
+    /// worker holds a claim on, the oldest first among jobs of the same priority. Jobs another
+    /// worker is claiming at the same time are skipped rather than waited for, and so are jobs
+    /// scheduled for later and jobs whose dependency has not completed.
+    ///
+    /// * `claimed_by`: The ID of the worker
+    /// * `claimed_until`: When the claim runs out
+    /// * `exec_opts`: Execution options for the query
+    async fn claim_queued_job(
+        &self,
+        claimed_by: Uuid,
+        claimed_until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Option<QueuedJob>> {
+        unimplemented!()
+    }
+
+    /// Extend a worker's claim on a job.
+    ///
+    /// * `job_id`: The id of the job
+    /// * `claimed_by`: The ID of the worker
+    /// * `claimed_until`: When the claim now runs out
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the worker still holds the claim. A job whose claim ran out may have been
+    /// claimed by another worker.
+    async fn renew_queued_job_claim(
+        &self,
+        job_id: Uuid,
+        claimed_by: Uuid,
+        claimed_until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Mark a job in the queue as finished, so no worker claims it again.
+    ///
+    /// * `job_id`: The id of the job
+    /// * `claimed_by`: The ID of the worker that ran the job
+    /// * `error`: Why the job failed, if it did
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the worker still held the claim on the job.
+    async fn finish_queued_job(
+        &self,
+        job_id: Uuid,
+        claimed_by: Uuid,
+        error: Option<String>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Release a worker's claim on a job that failed, so that it is claimed again once its backoff
+    /// is over.
+    ///
+    /// * `job_id`: The id of the job
+    /// * `claimed_by`: The ID of the worker that ran the job
+    /// * `retry_at`: When the job can be claimed again
+    /// * `error`: Why the job failed
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the worker still held the claim on the job.
+    async fn retry_queued_job(
+        &self,
+        job_id: Uuid,
+        claimed_by: Uuid,
+        retry_at: DateTime<Utc>,
+        error: AttemptError,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Mark a job in the queue as dead-lettered, once it has failed on every attempt it was given.
+    /// No worker claims it again until it is requeued.
+    ///
+    /// * `job_id`: The id of the job
+    /// * `claimed_by`: The ID of the worker that ran the job
+    /// * `error`: Why the job's last attempt failed
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the worker still held the claim on the job.
+    async fn dead_letter_queued_job(
+        &self,
+        job_id: Uuid,
+        claimed_by: Uuid,
+        error: AttemptError,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Queue a dead-lettered job again, starting its attempts over. Why its earlier attempts
+    /// failed is kept.
+    ///
+    /// * `job_id`: The id of the job
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the job was requeued, which it is not if it is not dead-lettered.
+    async fn requeue_dead_lettered_job(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Change when a queued job may run, as long as no worker has started it.
+    ///
+    /// * `job_id`: The id of the job
+    /// * `run_at`: When the job may run at the earliest
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the job was rescheduled, which it is not if it is not waiting to run for the
+    /// first time.
+    async fn reschedule_queued_job(
+        &self,
+        job_id: Uuid,
+        run_at: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Fetch a job in the queue, if it has been queued.
+    ///
+    /// * `job_id`: The id of the job
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_queued_job(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Option<QueuedJob>> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryQueue<Postgres> for PgBackend {
+    async fn enqueue_job(
+        &self,
+        job_id: Uuid,
+        payload: serde_json::Value,
+        priority: JobPriority,
+        run_at: Option<DateTime<Utc>>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<bool> {
+        async fn exec(
+            job_id: Uuid,
+            payload: serde_json::Value,
+            priority: JobPriority,
+            run_at: Option<DateTime<Utc>>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<bool> {
+            let query = include_str!("queries/queue/enqueue_job.sql");
+            let queued = sqlx::query_scalar::<_, Uuid>(query)
+                .bind(job_id)
+                .bind(payload)
+                .bind(priority)
+                .bind(run_at)
+                .fetch_optional(&mut **tx)
+                .await
+                .with_context(|| format!("error queueing job {job_id}"))?;
+            Ok(queued.is_some())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id, payload, priority, run_at)
+    }
+
+    async fn claim_queued_job(
+        &self,
+        claimed_by: Uuid,
+        claimed_until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Option<QueuedJob>> {
+        async fn exec(
+            claimed_by: Uuid,
+            claimed_until: DateTime<Utc>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Option<QueuedJob>> {
+            let query = include_str!("queries/queue/claim_queued_job.sql");
+            let job = sqlx::query_as::<_, QueuedJob>(query)
+                .bind(claimed_by)
+                .bind(claimed_until)
+                .fetch_optional(&mut **tx)
+                .await
+                .context("error claiming queued job")?;
+            Ok(job)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, claimed_by, claimed_until)
+    }
+
+    async fn renew_queued_job_claim(
+        &self,
+        job_id: Uuid,
+        claimed_by: Uuid,
+        claimed_until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<bool> {
+        async fn exec(
+            job_id: Uuid,
+            claimed_by: Uuid,
+            claimed_until: DateTime<Utc>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<bool> {
+            let query = include_str!("queries/queue/renew_queued_job_claim.sql");
+            let res = sqlx::query(query)
+                .bind(job_id)
+                .bind(claimed_by)
+                .bind(claimed_until)
+                .execute(&mut **tx)
+                .await
+                .with_context(|| format!("error renewing claim on queued job {job_id}"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id, claimed_by, claimed_until)
+    }
+
+    async fn finish_queued_job(
+        &self,
+        job_id: Uuid,
+        claimed_by: Uuid,
+        error: Option<String>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<bool> {
+        async fn exec(
+            job_id: Uuid,
+            claimed_by: Uuid,
+            error: Option<String>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<bool> {
+            let query = include_str!("queries/queue/finish_queued_job.sql");
+            let res = sqlx::query(query)
+                .bind(job_id)
+                .bind(claimed_by)
+                .bind(error)
+                .execute(&mut **tx)
+                .await
+                .with_context(|| format!("error finishing queued job {job_id}"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id, claimed_by, error)
+    }
+
+    async fn retry_queued_job(
+        &self,
+        job_id: Uuid,
+        claimed_by: Uuid,
+        retry_at: DateTime<Utc>,
+        error: AttemptError,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<bool> {
+        async fn exec(
+            job_id: Uuid,
+            claimed_by: Uuid,
+            retry_at: DateTime<Utc>,
+            error: AttemptError,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<bool> {
+            let query = include_str!("queries/queue/retry_queued_job.sql");
+            let message = error.errors.join(": ");
+            let res = sqlx::query(query)
+                .bind(job_id)
+                .bind(claimed_by)
+                .bind(retry_at)
+                .bind(message)
+                .bind(serde_json::to_value(&error)?)
+                .execute(&mut **tx)
+                .await
+                .with_context(|| format!("error releasing queued job {job_id} to be retried"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id, claimed_by, retry_at, error)
+    }
+
+    async fn dead_letter_queued_job(
+        &self,
+        job_id: Uuid,
+        claimed_by: Uuid,
+        error: AttemptError,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<bool> {
+        async fn exec(
+            job_id: Uuid,
+            claimed_by: Uuid,
+            error: AttemptError,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<bool> {
+            let query = include_str!("queries/queue/dead_letter_queued_job.sql");
+            let message = error.errors.join(": ");
+            let res = sqlx::query(query)
+                .bind(job_id)
+                .bind(claimed_by)
+                .bind(message)
+                .bind(serde_json::to_value(&error)?)
+                .execute(&mut **tx)
+                .await
+                .with_context(|| format!("error dead-lettering queued job {job_id}"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id, claimed_by, error)
+    }
+
+    async fn requeue_dead_lettered_job(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<bool> {
+        async fn exec(job_id: Uuid, tx: &mut Transaction<'_, Postgres>) -> Result<bool> {
+            let query = include_str!("queries/queue/requeue_dead_lettered_job.sql");
+            let res = sqlx::query(query)
+                .bind(job_id)
+                .execute(&mut **tx)
+                .await
+                .with_context(|| format!("error requeueing dead-lettered job {job_id}"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id)
+    }
+
+    async fn reschedule_queued_job(
+        &self,
+        job_id: Uuid,
+        run_at: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<bool> {
+        async fn exec(
+            job_id: Uuid,
+            run_at: DateTime<Utc>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<bool> {
+            let query = include_str!("queries/queue/reschedule_queued_job.sql");
+            let res = sqlx::query(query)
+                .bind(job_id)
+                .bind(run_at)
+                .execute(&mut **tx)
+                .await
+                .with_context(|| format!("error rescheduling queued job {job_id}"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id, run_at)
+    }
+
+    async fn fetch_queued_job(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Option<QueuedJob>> {
+        async fn exec(
+            job_id: Uuid,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Option<QueuedJob>> {
+            let query = include_str!("queries/queue/fetch_queued_job.sql");
+            let job = sqlx::query_as::<_, QueuedJob>(query)
+                .bind(job_id)
+                .fetch_optional(&mut **tx)
+                .await
+                .with_context(|| format!("error fetching queued job {job_id}"))?;
+            Ok(job)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, job_id)
+    }
+}
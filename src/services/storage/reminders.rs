@@ -0,0 +1,252 @@
+//! This module contains the definition of the `QueryReminders` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use derive_builder::Builder;
+use sqlx::{Database, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+
+use super::entities::{ActivationReminder, ActivationReminderSettings, DueActivationReminder};
+use super::types::ActivationReminderStatus;
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// Data needed to configure activation reminders for a project cycle.
+///
+/// * `enabled`: Whether reminders are sent
+/// * `remind_after_days`: How many days after a volunteer's workspace account is created to remind
+///   them, if they have not signed in
+/// * `locale`: The locale to send reminders in
+/// * `principal`: The email of the staff member configuring reminders
+#[derive(Builder, Debug, Clone)]
+pub struct UpsertActivationReminderSettings {
+    #[builder(default = "true")]
+    pub enabled: bool,
+    pub remind_after_days: i32,
+    #[builder(setter(into))]
+    pub locale: String,
+    #[builder(setter(into))]
+    pub principal: String,
+}
+
+/// Data needed to record the check of an exported volunteer for an activation reminder.
+///
+/// * `job_id`: The ID of the export job that created the volunteer's workspace account
+/// * `volunteer_id`: The ID of the volunteer
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `status`: What happened when the volunteer was checked
+/// * `detail`: More about what happened, if anything
+#[derive(Builder, Debug, Clone)]
+pub struct RecordActivationReminder {
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    #[builder(setter(into))]
+    pub workspace_email: String,
+    pub status: ActivationReminderStatus,
+    #[builder(setter(into), default = "None")]
+    pub detail: Option<String>,
+}
+
+/// A trait for querying activation reminders.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryReminders<DB: Database> {
+    /// Configure activation reminders for a project cycle, replacing any existing configuration.
+    ///
+    /// * `project_cycle_id`: The ID of the project cycle
+    /// * `data`: The settings to save
+    /// * `exec_opts`: Execution options for the query
+    async fn upsert_activation_reminder_settings(
+        &self,
+        project_cycle_id: Uuid,
+        data: UpsertActivationReminderSettings,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<ActivationReminderSettings> {
+        unimplemented!()
+    }
+
+    /// Fetch the activation reminder settings of a project cycle, if reminders were ever
+    /// configured for it.
+    ///
+    /// * `project_cycle_id`: The ID of the project cycle
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_activation_reminder_settings(
+        &self,
+        project_cycle_id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Option<ActivationReminderSettings>> {
+        unimplemented!()
+    }
+
+    /// Fetch the exported volunteers who are due to be checked for an activation reminder, oldest
+    /// export first. A volunteer is due once their cycle's `remind_after_days` have passed since
+    /// their workspace account was recorded, if they have not been checked before.
+    ///
+    /// * `project_cycle_id`: Only fetch volunteers in this project cycle, if set
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_due_activation_reminders(
+        &self,
+        project_cycle_id: Option<Uuid>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<DueActivationReminder>> {
+        unimplemented!()
+    }
+
+    /// Record that exported volunteers were checked for an activation reminder. A volunteer who
+    /// was already checked keeps their first record.
+    ///
+    /// * `data`: The checks to record
+    /// * `exec_opts`: Execution options for the query
+    async fn record_activation_reminders(
+        &self,
+        data: Vec<RecordActivationReminder>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Fetch every check for an activation reminder in a project cycle, newest first.
+    ///
+    /// * `project_cycle_id`: The ID of the project cycle
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_activation_reminders(
+        &self,
+        project_cycle_id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<ActivationReminder>> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryReminders<Postgres> for PgBackend {
+    async fn upsert_activation_reminder_settings(
+        &self,
+        project_cycle_id: Uuid,
+        data: UpsertActivationReminderSettings,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<ActivationReminderSettings> {
+        async fn exec(
+            project_cycle_id: Uuid,
+            data: UpsertActivationReminderSettings,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<ActivationReminderSettings> {
+            let query = include_str!("queries/reminders/upsert_activation_reminder_settings.sql");
+            let settings = sqlx::query_as::<_, ActivationReminderSettings>(query)
+                .bind(project_cycle_id)
+                .bind(data.enabled)
+                .bind(data.remind_after_days)
+                .bind(data.locale)
+                .bind(data.principal)
+                .fetch_one(&mut **tx)
+                .await
+                .context("error saving activation reminder settings")?;
+            Ok(settings)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, project_cycle_id, data)
+    }
+
+    async fn fetch_activation_reminder_settings(
+        &self,
+        project_cycle_id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Option<ActivationReminderSettings>> {
+        async fn exec(
+            project_cycle_id: Uuid,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Option<ActivationReminderSettings>> {
+            let query = include_str!("queries/reminders/fetch_activation_reminder_settings.sql");
+            let settings = sqlx::query_as::<_, ActivationReminderSettings>(query)
+                .bind(project_cycle_id)
+                .fetch_optional(&mut **tx)
+                .await
+                .context("error fetching activation reminder settings")?;
+            Ok(settings)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, project_cycle_id)
+    }
+
+    async fn fetch_due_activation_reminders(
+        &self,
+        project_cycle_id: Option<Uuid>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<DueActivationReminder>> {
+        async fn exec(
+            project_cycle_id: Option<Uuid>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<DueActivationReminder>> {
+            let query = include_str!("queries/reminders/fetch_due_activation_reminders.sql");
+            let due = sqlx::query_as::<_, DueActivationReminder>(query)
+                .bind(project_cycle_id)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching due activation reminders")?;
+            Ok(due)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, project_cycle_id)
+    }
+
+    async fn record_activation_reminders(
+        &self,
+        data: Vec<RecordActivationReminder>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            data: Vec<RecordActivationReminder>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            if data.is_empty() {
+                return Ok(());
+            }
+
+            let fragment =
+                include_str!("queries/reminders/record_activation_reminders.fragment.sql");
+
+            QueryBuilder::<Postgres>::new(fragment)
+                .push_values(data, |mut b, r| {
+                    b.push_bind(r.job_id)
+                        .push_bind(r.volunteer_id)
+                        .push_bind(r.workspace_email)
+                        .push_bind(r.status)
+                        .push_bind(r.detail);
+                })
+                .push(" on conflict (job_id, volunteer_id) do nothing")
+                .build()
+                .execute(&mut **tx)
+                .await
+                .context("error recording activation reminders")?;
+
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn fetch_activation_reminders(
+        &self,
+        project_cycle_id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<ActivationReminder>> {
+        async fn exec(
+            project_cycle_id: Uuid,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<ActivationReminder>> {
+            let query = include_str!("queries/reminders/fetch_activation_reminders.sql");
+            let reminders = sqlx::query_as::<_, ActivationReminder>(query)
+                .bind(project_cycle_id)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching activation reminders")?;
+            Ok(reminders)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, project_cycle_id)
+    }
+}
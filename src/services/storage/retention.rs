@@ -0,0 +1,136 @@
+//! This module contains the definition of the `QueryRetention` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+//!
+//! Every purge can be run as a dry run, which counts the rows that would be purged without
+//! deleting them.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Database, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::exec_with_tx;
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// A trait for purging volunteers' data once it is past its retention period.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryRetention<DB: Database> {
+    /// Purge the onboarding emails queued to be sent again, which hold temporary passwords, that
+    /// were queued before a time. Returns how many were (or, in a dry run, would be) purged.
+    ///
+    /// * `queued_before`: Purge the emails queued before this time
+    /// * `dry_run`: Count the emails without purging them
+    /// * `exec_opts`: Execution options for the query
+    async fn purge_email_retries(
+        &self,
+        queued_before: DateTime<Utc>,
+        dry_run: bool,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<u64> {
+        unimplemented!()
+    }
+
+    /// Purge the exported volunteers of the project cycles that were archived before a time,
+    /// along with everything recorded about them, including their emails and audit log entries.
+    /// Returns how many volunteers were (or, in a dry run, would be) purged.
+    ///
+    /// * `archived_before`: Purge the volunteers of project cycles archived before this time
+    /// * `dry_run`: Count the volunteers without purging them
+    /// * `exec_opts`: Execution options for the query
+    async fn purge_exported_volunteers(
+        &self,
+        archived_before: DateTime<Utc>,
+        dry_run: bool,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<u64> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryRetention<Postgres> for PgBackend {
+    async fn purge_email_retries(
+        &self,
+        queued_before: DateTime<Utc>,
+        dry_run: bool,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<u64> {
+        async fn exec(
+            queued_before: DateTime<Utc>,
+            dry_run: bool,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<u64> {
+            if dry_run {
+                let query = include_str!("queries/retention/count_expired_email_retries.sql");
+                let count = sqlx::query_scalar::<_, i64>(query)
+                    .bind(queued_before)
+                    .fetch_one(&mut **tx)
+                    .await
+                    .context("error counting expired email retries")?;
+                return Ok(u64::try_from(count).unwrap_or_default());
+            }
+
+            let query = include_str!("queries/retention/purge_expired_email_retries.sql");
+            let res = sqlx::query(query)
+                .bind(queued_before)
+                .execute(&mut **tx)
+                .await
+                .context("error purging expired email retries")?;
+            Ok(res.rows_affected())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, queued_before, dry_run)
+    }
+
+    async fn purge_exported_volunteers(
+        &self,
+        archived_before: DateTime<Utc>,
+        dry_run: bool,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<u64> {
+        async fn exec(
+            archived_before: DateTime<Utc>,
+            dry_run: bool,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<u64> {
+            let query = include_str!("queries/retention/fetch_expired_exported_volunteers.sql");
+            let ids = sqlx::query_scalar::<_, Uuid>(query)
+                .bind(archived_before)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching expired exported volunteers")?;
+            if dry_run || ids.is_empty() {
+                return Ok(ids.len() as u64);
+            }
+
+            let query = include_str!("queries/retention/purge_volunteers.sql");
+            let res = sqlx::query(query)
+                .bind(&ids)
+                .execute(&mut **tx)
+                .await
+                .context("error purging expired exported volunteers")?;
+
+            // The audit log triggers fire for every row the purge deleted, so the entries they
+            // wrote are removed along with the older ones
+            let query = include_str!("queries/retention/purge_volunteer_audit_log.sql");
+            sqlx::query(query)
+                .bind(&ids)
+                .execute(&mut **tx)
+                .await
+                .context("error purging the audit log of expired exported volunteers")?;
+
+            Ok(res.rows_affected())
+        }
+
+        let res = exec_with_tx!(self, exec_opts, exec, archived_before, dry_run);
+        if !dry_run {
+            self.invalidate_cache(|cache| cache.volunteers.invalidate_all());
+        }
+        res
+    }
+}
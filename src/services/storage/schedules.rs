@@ -0,0 +1,290 @@
+//! This module contains the definition of the `QuerySchedules` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+//!
+//! A schedule runs a named job on a cron expression. Which jobs can be scheduled, and what running
+//! them does, is decided by `app::api::v1::data_exports::workspace::scheduler`; the storage layer
+//! only keeps the schedules and when each last ran.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Database, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::entities::JobSchedule;
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// The data needed to schedule a job, or to change its schedule.
+///
+/// * `name`: The name of the scheduled job
+/// * `cron`: The cron expression the job runs on
+/// * `enabled`: Whether the job runs
+/// * `params`: What the job needs to run
+/// * `updated_by`: Who changed the schedule, if a user did
+/// * `next_run_at`: When the job next runs
+#[derive(Debug, Clone)]
+pub struct SaveJobSchedule {
+    pub name: String,
+    pub cron: String,
+    pub enabled: bool,
+    pub params: serde_json::Value,
+    pub updated_by: Option<String>,
+    pub next_run_at: DateTime<Utc>,
+}
+
+/// A trait for querying the schedules of jobs that run on a cron expression.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QuerySchedules<DB: Database> {
+    /// Fetch every schedule, by name.
+    ///
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_job_schedules(&self, exec_opts: &mut ExecOpts<DB>) -> Result<Vec<JobSchedule>> {
+        unimplemented!()
+    }
+
+    /// Fetch the schedule of a job, if it is scheduled.
+    ///
+    /// * `name`: The name of the scheduled job
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_job_schedule(
+        &self,
+        name: &str,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Option<JobSchedule>> {
+        unimplemented!()
+    }
+
+    /// Schedule a job, replacing its schedule if it is already scheduled. When it last ran is
+    /// kept.
+    ///
+    /// * `data`: The schedule
+    /// * `exec_opts`: Execution options for the query
+    async fn save_job_schedule(
+        &self,
+        data: SaveJobSchedule,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<JobSchedule> {
+        unimplemented!()
+    }
+
+    /// Delete the schedule of a job, so it no longer runs.
+    ///
+    /// * `name`: The name of the scheduled job
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the job was scheduled.
+    async fn delete_job_schedule(&self, name: &str, exec_opts: &mut ExecOpts<DB>) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Fetch the enabled schedules whose next run is due, soonest first.
+    ///
+    /// * `now`: The current time
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_due_job_schedules(
+        &self,
+        now: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<JobSchedule>> {
+        unimplemented!()
+    }
+
+    /// Claim a run of a scheduled job, moving its next run forward.
+    ///
+    /// * `name`: The name of the scheduled job
+    /// * `scheduled_for`: When the run being claimed was due, as it was read
+    /// * `next_run_at`: When the job runs after this run
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the run was claimed, which it is not if another process claimed it first or
+    /// the schedule changed since it was read.
+    async fn claim_job_schedule_run(
+        &self,
+        name: &str,
+        scheduled_for: DateTime<Utc>,
+        next_run_at: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Record how the last run of a scheduled job went.
+    ///
+    /// * `name`: The name of the scheduled job
+    /// * `job_id`: The ID of the job the run started, if it started one
+    /// * `error`: Why the run failed, if it did
+    /// * `exec_opts`: Execution options for the query
+    async fn record_job_schedule_run(
+        &self,
+        name: &str,
+        job_id: Option<Uuid>,
+        error: Option<String>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QuerySchedules<Postgres> for PgBackend {
+    async fn fetch_job_schedules(
+        &self,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<JobSchedule>> {
+        async fn exec(tx: &mut Transaction<'_, Postgres>) -> Result<Vec<JobSchedule>> {
+            let query = include_str!("queries/schedules/fetch_job_schedules.sql");
+            let schedules = sqlx::query_as::<_, JobSchedule>(query)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching job schedules")?;
+            Ok(schedules)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec)
+    }
+
+    async fn fetch_job_schedule(
+        &self,
+        name: &str,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Option<JobSchedule>> {
+        async fn exec(
+            name: &str,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Option<JobSchedule>> {
+            let query = include_str!("queries/schedules/fetch_job_schedule.sql");
+            let schedule = sqlx::query_as::<_, JobSchedule>(query)
+                .bind(name)
+                .fetch_optional(&mut **tx)
+                .await
+                .with_context(|| format!("error fetching schedule of {name}"))?;
+            Ok(schedule)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, name)
+    }
+
+    async fn save_job_schedule(
+        &self,
+        data: SaveJobSchedule,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<JobSchedule> {
+        async fn exec(
+            data: SaveJobSchedule,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<JobSchedule> {
+            let query = include_str!("queries/schedules/save_job_schedule.sql");
+            let schedule = sqlx::query_as::<_, JobSchedule>(query)
+                .bind(&data.name)
+                .bind(&data.cron)
+                .bind(data.enabled)
+                .bind(&data.params)
+                .bind(&data.updated_by)
+                .bind(data.next_run_at)
+                .fetch_one(&mut **tx)
+                .await
+                .with_context(|| format!("error saving schedule of {}", data.name))?;
+            Ok(schedule)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn delete_job_schedule(
+        &self,
+        name: &str,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<bool> {
+        async fn exec(name: &str, tx: &mut Transaction<'_, Postgres>) -> Result<bool> {
+            let query = include_str!("queries/schedules/delete_job_schedule.sql");
+            let res = sqlx::query(query)
+                .bind(name)
+                .execute(&mut **tx)
+                .await
+                .with_context(|| format!("error deleting schedule of {name}"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, name)
+    }
+
+    async fn fetch_due_job_schedules(
+        &self,
+        now: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<JobSchedule>> {
+        async fn exec(
+            now: DateTime<Utc>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<JobSchedule>> {
+            let query = include_str!("queries/schedules/fetch_due_job_schedules.sql");
+            let schedules = sqlx::query_as::<_, JobSchedule>(query)
+                .bind(now)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching due job schedules")?;
+            Ok(schedules)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, now)
+    }
+
+    async fn claim_job_schedule_run(
+        &self,
+        name: &str,
+        scheduled_for: DateTime<Utc>,
+        next_run_at: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<bool> {
+        async fn exec(
+            name: &str,
+            scheduled_for: DateTime<Utc>,
+            next_run_at: DateTime<Utc>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<bool> {
+            let query = include_str!("queries/schedules/claim_job_schedule_run.sql");
+            let res = sqlx::query(query)
+                .bind(name)
+                .bind(scheduled_for)
+                .bind(next_run_at)
+                .execute(&mut **tx)
+                .await
+                .with_context(|| format!("error claiming run of {name}"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, name, scheduled_for, next_run_at)
+    }
+
+    async fn record_job_schedule_run(
+        &self,
+        name: &str,
+        job_id: Option<Uuid>,
+        error: Option<String>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            name: &str,
+            job_id: Option<Uuid>,
+            error: Option<String>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            let query = include_str!("queries/schedules/record_job_schedule_run.sql");
+            sqlx::query(query)
+                .bind(name)
+                .bind(job_id)
+                .bind(error)
+                .execute(&mut **tx)
+                .await
+                .with_context(|| format!("error recording run of {name}"))?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, name, job_id, error)
+    }
+}
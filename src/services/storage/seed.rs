@@ -0,0 +1,337 @@
+//! This module fills the storage layer with fake data for local development.
+//!
+//! Seeding creates a project cycle of made-up volunteers, along with an Airtable import that
+//! brought them in, a finished export of most of them to Google Workspace, and an export that
+//! failed partway through. That is enough to exercise the API and the export flows (listing,
+//! resuming, undoing, reconciling, and the like) without production data. Every seeded address is
+//! under `example.com` or `example.org`, so nothing seeded can be emailed by accident.
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use fake::faker::address::en::{CityName, StateAbbr};
+use fake::faker::name::en::{FirstName, LastName};
+use fake::Fake;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::cycles::CreateCycleBuilder;
+use super::exports::CreateExportCheckpoint;
+use super::jobs::{CreateJobBuilder, NEW_JOB_VERSION};
+use super::types::{
+    AgeRange, Ethnicity, ExportDesination, Fli, Gender, JobData, JobDetails, JobType, Lgbt,
+    StudentStage, VolunteerHearAbout, WorkspaceExportStatus,
+};
+use super::volunteers::{CreateVolunteer, InsertVolunteerExportedToWorkspace};
+use super::{ExecOptsBuilder, StorageService};
+
+/// The number of volunteers seeded if no other number is given.
+pub const DEFAULT_SEED_VOLUNTEERS: usize = 50;
+
+/// The domain seeded workspace accounts are in.
+pub const SEED_WORKSPACE_DOMAIN: &str = "example.org";
+
+/// The org unit seeded volunteers are recorded as exported to.
+pub const SEED_ORG_UNIT: &str = "/Programs/PantheonUsers";
+
+const MAJORS: &[&str] = &[
+    "Computer Science",
+    "Data Science",
+    "Design",
+    "Economics",
+    "Information Systems",
+    "Marketing",
+    "Mathematics",
+    "Psychology",
+];
+
+/// Options for seeding fake data.
+///
+/// * `volunteers`: How many volunteers to create
+/// * `rng_seed`: Seeds the random number generator, so the same names and details are generated
+///   every time. Addresses still differ between runs, since they must be unique.
+#[derive(Debug, Clone)]
+pub struct SeedOptions {
+    pub volunteers: usize,
+    pub rng_seed: Option<u64>,
+}
+
+impl Default for SeedOptions {
+    fn default() -> Self {
+        Self { volunteers: DEFAULT_SEED_VOLUNTEERS, rng_seed: None }
+    }
+}
+
+/// What was seeded.
+///
+/// * `project_cycle_id`: The ID of the project cycle the volunteers belong to
+/// * `volunteers`: How many volunteers were created
+/// * `exported`: How many volunteers were recorded as exported to a workspace
+/// * `job_ids`: The IDs of the jobs that were created, oldest first
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedSummary {
+    pub project_cycle_id: Uuid,
+    pub volunteers: usize,
+    pub exported: usize,
+    pub job_ids: Vec<Uuid>,
+}
+
+/// Generate a fake volunteer.
+///
+/// * `rng`: The random number generator
+/// * `suffix`: A suffix that makes the volunteer's address unique
+fn fake_volunteer(rng: &mut StdRng, suffix: &str) -> CreateVolunteer {
+    let first_name: String = FirstName().fake_with_rng(rng);
+    let last_name: String = LastName().fake_with_rng(rng);
+    let city: String = CityName().fake_with_rng(rng);
+    let state: String = StateAbbr().fake_with_rng(rng);
+    let minors = rng.gen_range(0..2);
+
+    CreateVolunteer {
+        email: format!("{}@example.com", address_local_part(&first_name, &last_name, suffix)),
+        // 555-01XX numbers are reserved for fiction, so they never reach a real phone
+        phone: Some(format!("(202) 555-01{:02}", rng.gen_range(0..100))),
+        volunteer_gender: *[Gender::Woman, Gender::Man, Gender::NonBinary, Gender::PreferNotToSay]
+            .choose(rng)
+            .unwrap_or(&Gender::PreferNotToSay),
+        volunteer_ethnicity: vec![*[
+            Ethnicity::Asian,
+            Ethnicity::WhiteOrCaucasian,
+            Ethnicity::BlackOrAfricanAmerican,
+            Ethnicity::LatinoOrHispanic,
+            Ethnicity::Other,
+        ]
+        .choose(rng)
+        .unwrap_or(&Ethnicity::PreferNotToSay)],
+        volunteer_age_range: *[AgeRange::R18_24, AgeRange::R25_29, AgeRange::R30_34]
+            .choose(rng)
+            .unwrap_or(&AgeRange::R18_24),
+        university: vec![format!("University of {city}")],
+        lgbt: *[Lgbt::Yes, Lgbt::No, Lgbt::Ally, Lgbt::PreferNotToSay]
+            .choose(rng)
+            .unwrap_or(&Lgbt::PreferNotToSay),
+        country: "United States".to_owned(),
+        us_state: Some(state),
+        fli: vec![*[Fli::FirstGeneration, Fli::LowIncome, Fli::Neither]
+            .choose(rng)
+            .unwrap_or(&Fli::Neither)],
+        student_stage: *[
+            StudentStage::Sophomore,
+            StudentStage::Junior,
+            StudentStage::Senior,
+            StudentStage::MastersStudent,
+            StudentStage::RecentGraduate,
+        ]
+        .choose(rng)
+        .unwrap_or(&StudentStage::Junior),
+        majors: MAJORS.choose_multiple(rng, 1).map(|&m| m.to_owned()).collect(),
+        minors: MAJORS.choose_multiple(rng, minors).map(|&m| m.to_owned()).collect(),
+        hear_about: vec![*[
+            VolunteerHearAbout::Linkedin,
+            VolunteerHearAbout::University,
+            VolunteerHearAbout::Colleague,
+        ]
+        .choose(rng)
+        .unwrap_or(&VolunteerHearAbout::Linkedin)],
+        first_name,
+        last_name,
+    }
+}
+
+/// The part of a seeded address before the `@` (e.g. `jane.doe.1a2b3c4d7`).
+///
+/// * `first_name`: The volunteer's first name
+/// * `last_name`: The volunteer's last name
+/// * `suffix`: A suffix that makes the address unique
+fn address_local_part(first_name: &str, last_name: &str, suffix: &str) -> String {
+    let clean = |name: &str| {
+        name.chars().filter(char::is_ascii_alphanumeric).collect::<String>().to_lowercase()
+    };
+    format!("{}.{}.{suffix}", clean(first_name), clean(last_name))
+}
+
+/// Fill the current organization with fake data.
+///
+/// * `storage`: The storage layer to write to
+/// * `options`: What to seed
+///
+/// Each call creates a new project cycle, so seeding twice adds to the data rather than
+/// conflicting with it. Of the seeded volunteers, the first 60% are recorded as exported by a
+/// finished export, the next 20% as attempted by an export that failed, and the rest are left to
+/// be exported.
+pub async fn seed_data(storage: &dyn StorageService, options: &SeedOptions) -> Result<SeedSummary> {
+    if options.volunteers == 0 {
+        bail!("At least one volunteer must be seeded");
+    }
+
+    let mut rng = match options.rng_seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let cycle = CreateCycleBuilder::default()
+        .name(format!("Seed Cohort {}", Utc::now().format("%Y-%m-%d %H:%M:%S")))
+        .description("Fake volunteers loaded by the seed command")
+        .build()?;
+    let project_cycle_id =
+        storage.create_cycle(cycle, &mut ExecOptsBuilder::default().build()?).await?;
+    let run = project_cycle_id.simple().to_string()[..8].to_owned();
+
+    let volunteers = (0..options.volunteers)
+        .map(|i| fake_volunteer(&mut rng, &format!("{run}{i}")))
+        .collect::<Vec<CreateVolunteer>>();
+    let created = storage
+        .batch_create_volunteers(
+            project_cycle_id,
+            volunteers,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    // Each volunteer's workspace email mirrors their personal one, in the seeded domain
+    let accounts = created
+        .iter()
+        .map(|(email, id)| {
+            let local_part = email.split('@').next().unwrap_or(email);
+            (*id, format!("{local_part}@{SEED_WORKSPACE_DOMAIN}"))
+        })
+        .collect::<Vec<(Uuid, String)>>();
+    let exported = accounts.len() * 3 / 5;
+    let attempted = accounts.len() / 5;
+
+    let import_job_id = create_seed_job(
+        storage,
+        project_cycle_id,
+        "Import Airtable Base",
+        JobType::AirtableImportBase,
+        JobData::AirtableImportBase { base_id: format!("appSeed{run}") },
+    )
+    .await?;
+    storage
+        .mark_job_complete(import_job_id, NEW_JOB_VERSION, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    let export_job_id =
+        seed_export(storage, project_cycle_id, &accounts[..exported], exported).await?;
+    storage
+        .mark_job_complete(export_job_id, NEW_JOB_VERSION, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    let failed = &accounts[exported..exported + attempted];
+    let failed_job_id = seed_export(storage, project_cycle_id, failed, failed.len() / 2).await?;
+    storage
+        .mark_job_errored(
+            failed_job_id,
+            NEW_JOB_VERSION,
+            format!("Failed to export {} users", failed.len() - failed.len() / 2),
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    Ok(SeedSummary {
+        project_cycle_id,
+        volunteers: created.len(),
+        exported: exported + failed.len() / 2,
+        job_ids: vec![import_job_id, export_job_id, failed_job_id],
+    })
+}
+
+/// Create a pending job for seeded data.
+///
+/// * `storage`: The storage layer to write to
+/// * `project_cycle_id`: The project cycle the job belongs to
+/// * `label`: The label of the job
+/// * `job_type`: The type of the job
+/// * `data`: The data the job was started with
+async fn create_seed_job(
+    storage: &dyn StorageService,
+    project_cycle_id: Uuid,
+    label: &str,
+    job_type: JobType,
+    data: JobData,
+) -> Result<Uuid> {
+    let job = CreateJobBuilder::default()
+        .label(label)
+        .description(Some("Seeded for local development".to_owned()))
+        .data(JobDetails { job_type, error: None, result: None, data })
+        .build()?;
+    storage.create_job(Some(project_cycle_id), job, &mut ExecOptsBuilder::default().build()?).await
+}
+
+/// Record an export of seeded volunteers to Google Workspace, as an export job leaves it.
+///
+/// * `storage`: The storage layer to write to
+/// * `project_cycle_id`: The project cycle the volunteers belong to
+/// * `accounts`: The ID and workspace email of each volunteer in the export
+/// * `succeeded`: How many of the volunteers, from the first, were exported. The rest are recorded
+///   as failed.
+///
+/// Returns the ID of the export job, which is left pending.
+async fn seed_export(
+    storage: &dyn StorageService,
+    project_cycle_id: Uuid,
+    accounts: &[(Uuid, String)],
+    succeeded: usize,
+) -> Result<Uuid> {
+    let job_id = create_seed_job(
+        storage,
+        project_cycle_id,
+        "Export Users",
+        JobType::AirtableExportUsers,
+        JobData::AirtableExportUsers { export_destination: ExportDesination::GoogleWorkspace },
+    )
+    .await?;
+
+    let checkpoints = accounts
+        .iter()
+        .map(|(volunteer_id, workspace_email)| CreateExportCheckpoint {
+            volunteer_id: *volunteer_id,
+            workspace_email: workspace_email.clone(),
+            org_unit: SEED_ORG_UNIT.to_owned(),
+        })
+        .collect();
+    storage
+        .create_export_checkpoints(job_id, checkpoints, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    let (exported, failed) = accounts.split_at(succeeded.min(accounts.len()));
+    storage
+        .batch_insert_volunteers_exported_to_workspace(
+            exported
+                .iter()
+                .map(|(volunteer_id, workspace_email)| InsertVolunteerExportedToWorkspace {
+                    volunteer_id: *volunteer_id,
+                    job_id,
+                    workspace_email: workspace_email.clone(),
+                    org_unit: SEED_ORG_UNIT.to_owned(),
+                })
+                .collect(),
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+    storage
+        .update_export_checkpoints(
+            job_id,
+            exported.iter().map(|(volunteer_id, _)| *volunteer_id).collect(),
+            WorkspaceExportStatus::Emailed,
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    for (volunteer_id, _) in failed {
+        storage
+            .update_export_checkpoint(
+                job_id,
+                *volunteer_id,
+                WorkspaceExportStatus::Failed,
+                Some("Seeded failure: the user could not be created".to_owned()),
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?;
+    }
+
+    Ok(job_id)
+}
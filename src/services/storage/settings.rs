@@ -0,0 +1,142 @@
+//! This module contains the definition of the `QuerySettings` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+//!
+//! Settings are stored as one JSON value per key. They are read and written with their types by
+//! `services::settings`, which should be used instead of this trait outside the storage layer.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::{Database, Postgres, Transaction};
+
+use super::entities::StoredSetting;
+use super::exec_with_tx;
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// A trait for querying settings.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QuerySettings<DB: Database> {
+    /// Fetch every setting that has been saved, by key.
+    ///
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_settings(&self, exec_opts: &mut ExecOpts<DB>) -> Result<Vec<StoredSetting>> {
+        unimplemented!()
+    }
+
+    /// Fetch a setting, if it has been saved.
+    ///
+    /// * `key`: The name of the setting
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_setting(
+        &self,
+        key: &str,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Option<StoredSetting>> {
+        unimplemented!()
+    }
+
+    /// Save a setting, replacing its value if it has already been saved.
+    ///
+    /// * `key`: The name of the setting
+    /// * `value`: The value of the setting
+    /// * `exec_opts`: Execution options for the query
+    async fn save_setting(
+        &self,
+        key: &str,
+        value: serde_json::Value,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<StoredSetting> {
+        unimplemented!()
+    }
+
+    /// Delete a setting, so its default is used again.
+    ///
+    /// * `key`: The name of the setting
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the setting had been saved.
+    async fn delete_setting(&self, key: &str, exec_opts: &mut ExecOpts<DB>) -> Result<bool> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QuerySettings<Postgres> for PgBackend {
+    async fn fetch_settings(
+        &self,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<StoredSetting>> {
+        async fn exec(tx: &mut Transaction<'_, Postgres>) -> Result<Vec<StoredSetting>> {
+            let query = include_str!("queries/settings/fetch_settings.sql");
+            let settings = sqlx::query_as::<_, StoredSetting>(query)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching settings")?;
+            Ok(settings)
+        }
+
+        exec_with_tx!(self, exec_opts, exec)
+    }
+
+    async fn fetch_setting(
+        &self,
+        key: &str,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Option<StoredSetting>> {
+        async fn exec(
+            key: &str,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Option<StoredSetting>> {
+            let query = include_str!("queries/settings/fetch_setting.sql");
+            let setting = sqlx::query_as::<_, StoredSetting>(query)
+                .bind(key)
+                .fetch_optional(&mut **tx)
+                .await
+                .with_context(|| format!("error fetching setting {key}"))?;
+            Ok(setting)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, key)
+    }
+
+    async fn save_setting(
+        &self,
+        key: &str,
+        value: serde_json::Value,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<StoredSetting> {
+        async fn exec(
+            key: &str,
+            value: serde_json::Value,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<StoredSetting> {
+            let query = include_str!("queries/settings/save_setting.sql");
+            let setting = sqlx::query_as::<_, StoredSetting>(query)
+                .bind(key)
+                .bind(value)
+                .fetch_one(&mut **tx)
+                .await
+                .with_context(|| format!("error saving setting {key}"))?;
+            Ok(setting)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, key, value)
+    }
+
+    async fn delete_setting(&self, key: &str, exec_opts: &mut ExecOpts<Postgres>) -> Result<bool> {
+        async fn exec(key: &str, tx: &mut Transaction<'_, Postgres>) -> Result<bool> {
+            let query = include_str!("queries/settings/delete_setting.sql");
+            let res = sqlx::query(query)
+                .bind(key)
+                .execute(&mut **tx)
+                .await
+                .with_context(|| format!("error deleting setting {key}"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, key)
+    }
+}
@@ -0,0 +1,131 @@
+//! This module contains the definition of the `QuerySnapshots` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+//!
+//! A snapshot is a JSON document holding the state of a job at the moment it was taken. What goes
+//! into it is decided by `app::api::v1::jobs::snapshots`, the storage layer only keeps it.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::{Database, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::entities::{JobSnapshot, JobSnapshotSummary};
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// A trait for querying snapshots of jobs.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QuerySnapshots<DB: Database> {
+    /// Save a snapshot of a job.
+    ///
+    /// * `job_id`: The id of the job the snapshot was taken of
+    /// * `created_by`: Who took the snapshot, if it was taken by a user
+    /// * `snapshot`: The state of the job
+    /// * `exec_opts`: Execution options for the query
+    async fn save_job_snapshot(
+        &self,
+        job_id: Uuid,
+        created_by: Option<String>,
+        snapshot: serde_json::Value,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<JobSnapshot> {
+        unimplemented!()
+    }
+
+    /// Fetch the snapshots taken of a job, newest first, without their contents.
+    ///
+    /// * `job_id`: The id of the job
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_job_snapshots(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<JobSnapshotSummary>> {
+        unimplemented!()
+    }
+
+    /// Fetch a snapshot with its contents, if it exists.
+    ///
+    /// * `id`: The id of the snapshot
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_job_snapshot(
+        &self,
+        id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Option<JobSnapshot>> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QuerySnapshots<Postgres> for PgBackend {
+    async fn save_job_snapshot(
+        &self,
+        job_id: Uuid,
+        created_by: Option<String>,
+        snapshot: serde_json::Value,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<JobSnapshot> {
+        async fn exec(
+            job_id: Uuid,
+            created_by: Option<String>,
+            snapshot: serde_json::Value,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<JobSnapshot> {
+            let query = include_str!("queries/snapshots/save_job_snapshot.sql");
+            let snapshot = sqlx::query_as::<_, JobSnapshot>(query)
+                .bind(job_id)
+                .bind(created_by)
+                .bind(snapshot)
+                .fetch_one(&mut **tx)
+                .await
+                .with_context(|| format!("error saving snapshot of job {job_id}"))?;
+            Ok(snapshot)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id, created_by, snapshot)
+    }
+
+    async fn fetch_job_snapshots(
+        &self,
+        job_id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<JobSnapshotSummary>> {
+        async fn exec(
+            job_id: Uuid,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<JobSnapshotSummary>> {
+            let query = include_str!("queries/snapshots/fetch_job_snapshots.sql");
+            let snapshots = sqlx::query_as::<_, JobSnapshotSummary>(query)
+                .bind(job_id)
+                .fetch_all(&mut **tx)
+                .await
+                .with_context(|| format!("error fetching snapshots of job {job_id}"))?;
+            Ok(snapshots)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, job_id)
+    }
+
+    async fn fetch_job_snapshot(
+        &self,
+        id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Option<JobSnapshot>> {
+        async fn exec(id: Uuid, tx: &mut Transaction<'_, Postgres>) -> Result<Option<JobSnapshot>> {
+            let query = include_str!("queries/snapshots/fetch_job_snapshot.sql");
+            let snapshot = sqlx::query_as::<_, JobSnapshot>(query)
+                .bind(id)
+                .fetch_optional(&mut **tx)
+                .await
+                .with_context(|| format!("error fetching job snapshot {id}"))?;
+            Ok(snapshot)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, id)
+    }
+}
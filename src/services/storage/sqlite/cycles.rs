@@ -0,0 +1,91 @@
+//! This module contains the implementation of the `QueryCycles` trait for the `SqliteBackend`
+//! struct.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::{Sqlite, Transaction};
+use uuid::Uuid;
+
+use super::{exec_with_tx, SqliteBackend};
+use crate::services::storage::cycles::{CreateCycle, EditCycle, QueryCycles};
+use crate::services::storage::entities::ProjectCycle;
+use crate::services::storage::{Acquire, ExecOpts};
+
+#[async_trait]
+impl QueryCycles<Sqlite> for SqliteBackend {
+    async fn create_cycle(
+        &self,
+        data: CreateCycle,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<Uuid> {
+        async fn exec(data: CreateCycle, tx: &mut Transaction<'_, Sqlite>) -> Result<Uuid> {
+            let query = include_str!("queries/cycles/create_cycle.sql");
+            let id = Uuid::new_v4();
+            sqlx::query(query)
+                .bind(id)
+                .bind(data.name)
+                .bind(data.description)
+                .execute(&mut **tx)
+                .await?;
+            Ok(id)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn fetch_cycles(&self, exec_opts: &mut ExecOpts<Sqlite>) -> Result<Vec<ProjectCycle>> {
+        async fn exec(tx: &mut Transaction<'_, Sqlite>) -> Result<Vec<ProjectCycle>> {
+            let query = include_str!("queries/cycles/fetch_cycles.sql");
+            let cycles = sqlx::query_as::<_, ProjectCycle>(query).fetch_all(&mut **tx).await?;
+            Ok(cycles)
+        }
+
+        exec_with_tx!(self, exec_opts, exec)
+    }
+
+    async fn fetch_cycle_by_id(
+        &self,
+        id: Uuid,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<Option<ProjectCycle>> {
+        async fn exec(id: Uuid, tx: &mut Transaction<'_, Sqlite>) -> Result<Option<ProjectCycle>> {
+            let query = include_str!("queries/cycles/fetch_cycle_by_id.sql");
+            let cycle =
+                sqlx::query_as::<_, ProjectCycle>(query).bind(id).fetch_optional(&mut **tx).await?;
+            Ok(cycle)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, id)
+    }
+
+    async fn edit_cycle(
+        &self,
+        id: Uuid,
+        data: EditCycle,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<()> {
+        async fn exec(id: Uuid, data: EditCycle, tx: &mut Transaction<'_, Sqlite>) -> Result<()> {
+            let query = include_str!("queries/cycles/edit_cycle.sql");
+            sqlx::query(query)
+                .bind(id)
+                .bind(data.name)
+                .bind(data.description)
+                .bind(data.archived)
+                .execute(&mut **tx)
+                .await?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, id, data)
+    }
+
+    async fn delete_cycle(&self, id: Uuid, exec_opts: &mut ExecOpts<Sqlite>) -> Result<()> {
+        async fn exec(id: Uuid, tx: &mut Transaction<'_, Sqlite>) -> Result<()> {
+            let query = include_str!("queries/cycles/delete_cycle.sql");
+            sqlx::query(query).bind(id).execute(&mut **tx).await?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, id)
+    }
+}
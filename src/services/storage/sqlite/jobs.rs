@@ -0,0 +1,305 @@
+//! This module contains the implementation of the `QueryJobs` trait for the `SqliteBackend`
+//! struct.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Sqlite, Transaction};
+use uuid::Uuid;
+
+use super::{exec_with_tx, SqliteBackend};
+use crate::services::storage::entities::{Job, JobProgress};
+use crate::services::storage::jobs::{
+    CreateJob, EditJob, JobConflictError, QueryJobs, UpdateJobProgress, UpdateJobStatus,
+};
+use crate::services::storage::types::JobStatus;
+use crate::services::storage::{Acquire, ExecOpts};
+
+/// Set the status of a job, and record or clear its error.
+///
+/// * `id`: The ID of the job
+/// * `status`: The new status
+/// * `error`: Information about the error, if the new status is `Error`
+/// * `tx`: The transaction to run the query in
+async fn set_job_status(
+    id: Uuid,
+    status: JobStatus,
+    error: Option<String>,
+    tx: &mut Transaction<'_, Sqlite>,
+) -> Result<()> {
+    let query = include_str!("queries/jobs/update_job_status.sql");
+    sqlx::query(query).bind(id).bind(status).bind(error).execute(&mut **tx).await?;
+    Ok(())
+}
+
+/// Finish a pending job, and record or clear its error. If the job is no longer pending or its
+/// status changed since it was read, it is left as it is and a `JobConflictError` is returned.
+///
+/// * `id`: The ID of the job
+/// * `version`: The version of the job when it was claimed or read
+/// * `status`: The status the job finished with
+/// * `error`: Information about the error, if the new status is `Error`
+/// * `tx`: The transaction to run the query in
+async fn finish_job(
+    id: Uuid,
+    version: i32,
+    status: JobStatus,
+    error: Option<String>,
+    tx: &mut Transaction<'_, Sqlite>,
+) -> Result<()> {
+    let query = include_str!("queries/jobs/finish_job.sql");
+    let res = sqlx::query(query)
+        .bind(id)
+        .bind(status)
+        .bind(error)
+        .bind(version)
+        .execute(&mut **tx)
+        .await?;
+    if res.rows_affected() == 0 {
+        return Err(JobConflictError { job_id: id }.into());
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl QueryJobs<Sqlite> for SqliteBackend {
+    async fn create_job(
+        &self,
+        project_cycle_id: Option<Uuid>,
+        data: CreateJob,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<Uuid> {
+        async fn exec(
+            project_cycle_id: Option<Uuid>,
+            data: CreateJob,
+            tx: &mut Transaction<'_, Sqlite>,
+        ) -> Result<Uuid> {
+            let query = include_str!("queries/jobs/create_job.sql");
+            let id = Uuid::new_v4();
+            sqlx::query(query)
+                .bind(id)
+                .bind(project_cycle_id)
+                .bind(data.label)
+                .bind(data.description)
+                .bind(serde_json::to_value(data.data)?)
+                .execute(&mut **tx)
+                .await?;
+            Ok(id)
+        }
+        exec_with_tx!(self, exec_opts, exec, project_cycle_id, data)
+    }
+
+    async fn fetch_jobs(&self, exec_opts: &mut ExecOpts<Sqlite>) -> Result<Vec<Job>> {
+        async fn exec(tx: &mut Transaction<'_, Sqlite>) -> Result<Vec<Job>> {
+            let query = include_str!("queries/jobs/fetch_jobs.sql");
+            let jobs = sqlx::query_as::<_, Job>(query).fetch_all(&mut **tx).await?;
+            Ok(jobs)
+        }
+        exec_with_tx!(self, exec_opts, exec)
+    }
+
+    async fn fetch_job(&self, id: Uuid, exec_opts: &mut ExecOpts<Sqlite>) -> Result<Job> {
+        async fn exec(id: Uuid, tx: &mut Transaction<'_, Sqlite>) -> Result<Job> {
+            let query = include_str!("queries/jobs/fetch_job.sql");
+            let job = sqlx::query_as::<_, Job>(query).bind(id).fetch_one(&mut **tx).await?;
+            Ok(job)
+        }
+        exec_with_tx!(self, exec_opts, exec, id)
+    }
+
+    async fn fetch_job_status(
+        &self,
+        id: Uuid,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<JobStatus> {
+        async fn exec(id: Uuid, tx: &mut Transaction<'_, Sqlite>) -> Result<JobStatus> {
+            let query = include_str!("queries/jobs/fetch_job_status.sql");
+            let status =
+                sqlx::query_scalar::<_, JobStatus>(query).bind(id).fetch_one(&mut **tx).await?;
+            Ok(status)
+        }
+        exec_with_tx!(self, exec_opts, exec, id)
+    }
+
+    async fn update_job_status(
+        &self,
+        id: Uuid,
+        data: UpdateJobStatus,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<()> {
+        async fn exec(
+            id: Uuid,
+            data: UpdateJobStatus,
+            tx: &mut Transaction<'_, Sqlite>,
+        ) -> Result<()> {
+            set_job_status(id, data.status, data.error, tx).await
+        }
+        exec_with_tx!(self, exec_opts, exec, id, data)
+    }
+
+    async fn mark_job_complete(
+        &self,
+        id: Uuid,
+        version: i32,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<()> {
+        async fn exec(id: Uuid, version: i32, tx: &mut Transaction<'_, Sqlite>) -> Result<()> {
+            finish_job(id, version, JobStatus::Complete, None, tx).await
+        }
+        exec_with_tx!(self, exec_opts, exec, id, version)
+    }
+
+    async fn mark_job_errored(
+        &self,
+        id: Uuid,
+        version: i32,
+        error: String,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<()> {
+        async fn exec(
+            id: Uuid,
+            version: i32,
+            error: String,
+            tx: &mut Transaction<'_, Sqlite>,
+        ) -> Result<()> {
+            finish_job(id, version, JobStatus::Error, Some(error), tx).await
+        }
+        exec_with_tx!(self, exec_opts, exec, id, version, error)
+    }
+
+    async fn resume_job(
+        &self,
+        id: Uuid,
+        version: i32,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<()> {
+        async fn exec(id: Uuid, version: i32, tx: &mut Transaction<'_, Sqlite>) -> Result<()> {
+            let query = include_str!("queries/jobs/resume_job.sql");
+            let res = sqlx::query(query).bind(id).bind(version).execute(&mut **tx).await?;
+            if res.rows_affected() == 0 {
+                return Err(JobConflictError { job_id: id }.into());
+            }
+            Ok(())
+        }
+        exec_with_tx!(self, exec_opts, exec, id, version)
+    }
+
+    async fn set_job_project_cycle(
+        &self,
+        id: Uuid,
+        project_cycle_id: Uuid,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<()> {
+        async fn exec(
+            id: Uuid,
+            project_cycle_id: Uuid,
+            tx: &mut Transaction<'_, Sqlite>,
+        ) -> Result<()> {
+            let query = include_str!("queries/jobs/set_job_project_cycle.sql");
+            sqlx::query(query).bind(id).bind(project_cycle_id).execute(&mut **tx).await?;
+            Ok(())
+        }
+        exec_with_tx!(self, exec_opts, exec, id, project_cycle_id)
+    }
+
+    async fn edit_job(&self, id: Uuid, data: EditJob, opts: &mut ExecOpts<Sqlite>) -> Result<()> {
+        async fn exec(id: Uuid, data: EditJob, tx: &mut Transaction<'_, Sqlite>) -> Result<()> {
+            let query = include_str!("queries/jobs/edit_job.sql");
+            sqlx::query(query)
+                .bind(id)
+                .bind(data.label)
+                .bind(data.description)
+                .execute(&mut **tx)
+                .await?;
+            Ok(())
+        }
+        exec_with_tx!(self, opts, exec, id, data)
+    }
+
+    async fn cancel_job(&self, id: Uuid, exec_opts: &mut ExecOpts<Sqlite>) -> Result<bool> {
+        async fn exec(id: Uuid, tx: &mut Transaction<'_, Sqlite>) -> Result<bool> {
+            let query = include_str!("queries/jobs/cancel_job.sql");
+            let res = sqlx::query(query).bind(id).execute(&mut **tx).await?;
+            Ok(res.rows_affected() > 0)
+        }
+        exec_with_tx!(self, exec_opts, exec, id)
+    }
+
+    async fn set_job_result(
+        &self,
+        id: Uuid,
+        result: serde_json::Value,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<()> {
+        async fn exec(
+            id: Uuid,
+            result: serde_json::Value,
+            tx: &mut Transaction<'_, Sqlite>,
+        ) -> Result<()> {
+            let query = include_str!("queries/jobs/set_job_result.sql");
+            sqlx::query(query).bind(id).bind(result).execute(&mut **tx).await?;
+            Ok(())
+        }
+        exec_with_tx!(self, exec_opts, exec, id, result)
+    }
+
+    async fn update_job_progress(
+        &self,
+        id: Uuid,
+        data: UpdateJobProgress,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<()> {
+        async fn exec(
+            id: Uuid,
+            data: UpdateJobProgress,
+            tx: &mut Transaction<'_, Sqlite>,
+        ) -> Result<()> {
+            let query = include_str!("queries/jobs/update_job_progress.sql");
+            sqlx::query(query)
+                .bind(id)
+                .bind(data.phase)
+                .bind(data.processed)
+                .bind(data.total)
+                .execute(&mut **tx)
+                .await?;
+            Ok(())
+        }
+        exec_with_tx!(self, exec_opts, exec, id, data)
+    }
+
+    async fn fetch_job_progress(
+        &self,
+        id: Uuid,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<Option<JobProgress>> {
+        async fn exec(id: Uuid, tx: &mut Transaction<'_, Sqlite>) -> Result<Option<JobProgress>> {
+            let query = include_str!("queries/jobs/fetch_job_progress.sql");
+            let progress =
+                sqlx::query_as::<_, JobProgress>(query).bind(id).fetch_optional(&mut **tx).await?;
+            Ok(progress)
+        }
+        exec_with_tx!(self, exec_opts, exec, id)
+    }
+
+    async fn fetch_failed_export_jobs(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<Vec<Job>> {
+        async fn exec(
+            since: DateTime<Utc>,
+            until: DateTime<Utc>,
+            tx: &mut Transaction<'_, Sqlite>,
+        ) -> Result<Vec<Job>> {
+            let query = include_str!("queries/jobs/fetch_failed_export_jobs.sql");
+            let jobs = sqlx::query_as::<_, Job>(query)
+                .bind(since)
+                .bind(until)
+                .fetch_all(&mut **tx)
+                .await?;
+            Ok(jobs)
+        }
+        exec_with_tx!(self, exec_opts, exec, since, until)
+    }
+}
@@ -0,0 +1,223 @@
+//! This module contains a partial SQLite implementation of the storage layer, for testing code
+//! that only needs cycles, jobs, and organizations without a Postgres server.
+//!
+//! `SqliteBackend` implements `StorageService` for `Sqlite`, so code written against the storage
+//! traits can run against a SQLite file (or an in-memory database). Its schema lives in
+//! `migrations/sqlite`, apart from the Postgres migrations, and only covers the data it implements
+//! queries for: cycles, jobs and their progress, and organizations. Every other query (volunteers,
+//! exports, the job queue, the outbox, and so on) falls back to the default body of its trait,
+//! which panics.
+//!
+//! The app cannot run on this backend. It is not selectable from the CLI or the environment, and
+//! `Services` and `ExportServices` hold a Postgres `StorageService`, so exports (and everything
+//! else the server does) still need Postgres.
+//!
+//! SQLite has no row level security or triggers that can read the audit principal, so the backend
+//! only serves the default organization and does not keep an audit log.
+
+mod cycles;
+mod jobs;
+mod organizations;
+
+use std::str::FromStr;
+use std::time::Instant;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+use crate::services::storage::audit::QueryAudit;
+use crate::services::storage::backups::QueryBackups;
+use crate::services::storage::deletions::QueryDeletions;
+use crate::services::storage::dependencies::QueryJobDependencies;
+use crate::services::storage::drives::QuerySharedDrives;
+use crate::services::storage::emails::QueryEmails;
+use crate::services::storage::events::QueryJobEvents;
+use crate::services::storage::exports::QueryExports;
+use crate::services::storage::logins::QueryLogins;
+use crate::services::storage::mentors::QueryMentors;
+use crate::services::storage::nonprofits::QueryNonprofits;
+use crate::services::storage::organizations::{current_org, DEFAULT_ORG_ID};
+use crate::services::storage::outbox::QueryOutbox;
+use crate::services::storage::pool::{AcquireMetrics, PoolMonitor, PoolStats, PoolUtilization};
+use crate::services::storage::queue::QueryQueue;
+use crate::services::storage::reminders::QueryReminders;
+use crate::services::storage::retention::QueryRetention;
+use crate::services::storage::schedules::QuerySchedules;
+use crate::services::storage::settings::QuerySettings;
+use crate::services::storage::snapshots::QuerySnapshots;
+use crate::services::storage::stats::QueryStats;
+use crate::services::storage::suspensions::QuerySuspensions;
+use crate::services::storage::templates::QueryTemplates;
+use crate::services::storage::timing::{QueryMonitor, QueryTimingStats, QueryTimings};
+use crate::services::storage::transfers::QueryTransfers;
+use crate::services::storage::volunteers::QueryVolunteers;
+use crate::services::storage::webhooks::QueryWebhooks;
+use crate::services::storage::{Acquire, MigrationStatus, Migrator};
+use crate::services::Service;
+
+/// Defines a backend for a SQLite database. It contains a connection pool.
+///
+/// * `pool`: A SQLite connection pool
+/// * `metrics`: How long transactions waited for a connection from the pool
+/// * `timings`: How long each storage operation took
+pub struct SqliteBackend {
+    pub pool: SqlitePool,
+    pub metrics: AcquireMetrics,
+    pub timings: QueryTimings,
+}
+
+/// The migrations in the `migrations/sqlite` directory, embedded in the binary.
+pub static SQLITE_MIGRATIONS: sqlx::migrate::Migrator = sqlx::migrate!("migrations/sqlite");
+
+impl SqliteBackend {
+    /// Construct a new `SqliteBackend` from a URL, creating the database file if it does not exist.
+    ///
+    /// * `url`: A string slice that holds a valid SQLite URL, e.g. `sqlite://scipio.db` or
+    ///   `sqlite::memory:`
+    ///
+    /// The pool holds a single connection that is never closed. SQLite only allows one writer at a
+    /// time, and an in-memory database only lives as long as the connection that opened it. A task
+    /// must not run a query without a transaction while it holds another transaction open.
+    pub async fn new(url: &str) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(url)
+            .context("parse sqlite url")?
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(options)
+            .await
+            .context("create sqlite pool")?;
+        Ok(Self { pool, metrics: AcquireMetrics::default(), timings: QueryTimings::default() })
+    }
+}
+
+#[async_trait]
+impl Acquire<Sqlite> for SqliteBackend {
+    async fn acquire<'a>(&self) -> Result<Transaction<'a, Sqlite>> {
+        if let Some(org_id) = current_org().filter(|org_id| *org_id != DEFAULT_ORG_ID) {
+            bail!("the SQLite backend only serves the default organization, not {org_id}");
+        }
+
+        let started = Instant::now();
+        let tx = self.pool.begin().await;
+        self.metrics.record(&self.pool, "sqlite", started.elapsed(), tx.is_ok());
+        tx.context("acquire transaction")
+    }
+}
+
+impl PoolMonitor for SqliteBackend {
+    fn pool_stats(&self) -> PoolStats {
+        PoolStats { primary: PoolUtilization::new(&self.pool, &self.metrics), replica: None }
+    }
+}
+
+impl QueryMonitor for SqliteBackend {
+    fn query_timings(&self) -> QueryTimingStats {
+        self.timings.stats()
+    }
+}
+
+#[async_trait]
+impl Migrator for SqliteBackend {
+    async fn migrate(&self) -> Result<()> {
+        SQLITE_MIGRATIONS.run(&self.pool).await.context("run migrations")?;
+        Ok(())
+    }
+
+    async fn migration_status(&self) -> Result<Vec<MigrationStatus>> {
+        // sqlx creates the table that records applied migrations the first time it runs them
+        let recorded = sqlx::query_scalar::<_, bool>(
+            "select exists(select 1 from sqlite_master where type = 'table' and name = \
+             '_sqlx_migrations')",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("check for applied migrations")?;
+
+        let applied = if recorded {
+            sqlx::query_scalar::<_, i64>("select version from _sqlx_migrations where success")
+                .fetch_all(&self.pool)
+                .await
+                .context("fetch applied migrations")?
+        } else {
+            vec![]
+        };
+
+        Ok(SQLITE_MIGRATIONS
+            .iter()
+            .filter(|m| !m.migration_type.is_down_migration())
+            .map(|m| MigrationStatus {
+                version: m.version,
+                description: m.description.to_string(),
+                applied: applied.contains(&m.version),
+            })
+            .collect())
+    }
+}
+
+impl Service for SqliteBackend {
+    fn get_id(&self) -> &'static str {
+        "sqlite-backend"
+    }
+}
+
+// The SQLite schema does not cover the data these traits query, so they keep the default bodies
+// of their methods, which panic
+impl QueryVolunteers<Sqlite> for SqliteBackend {}
+impl QueryMentors<Sqlite> for SqliteBackend {}
+impl QueryNonprofits<Sqlite> for SqliteBackend {}
+impl QueryExports<Sqlite> for SqliteBackend {}
+impl QueryStats<Sqlite> for SqliteBackend {}
+impl QueryTemplates<Sqlite> for SqliteBackend {}
+impl QueryEmails<Sqlite> for SqliteBackend {}
+impl QueryReminders<Sqlite> for SqliteBackend {}
+impl QuerySuspensions<Sqlite> for SqliteBackend {}
+impl QuerySharedDrives<Sqlite> for SqliteBackend {}
+impl QueryDeletions<Sqlite> for SqliteBackend {}
+impl QueryLogins<Sqlite> for SqliteBackend {}
+impl QueryTransfers<Sqlite> for SqliteBackend {}
+impl QueryAudit<Sqlite> for SqliteBackend {}
+impl QueryRetention<Sqlite> for SqliteBackend {}
+impl QueryBackups<Sqlite> for SqliteBackend {}
+impl QueryOutbox<Sqlite> for SqliteBackend {}
+impl QuerySettings<Sqlite> for SqliteBackend {}
+impl QuerySnapshots<Sqlite> for SqliteBackend {}
+impl QueryQueue<Sqlite> for SqliteBackend {}
+impl QuerySchedules<Sqlite> for SqliteBackend {}
+impl QueryJobEvents<Sqlite> for SqliteBackend {}
+impl QueryWebhooks<Sqlite> for SqliteBackend {}
+impl QueryJobDependencies<Sqlite> for SqliteBackend {}
+
+/// `exec_with_tx` is a macro that executes a query within a SQLite transaction.
+///
+/// It works like the Postgres `exec_with_tx` macro, except that the principal in the options is
+/// ignored, since the SQLite backend does not keep an audit log.
+macro_rules! exec_with_tx {
+    ($self:expr, $exec_opts:expr, $exec_fn:ident $(, $arg:expr )* ) => {{
+        let started = std::time::Instant::now();
+        let res = async {
+            match $exec_opts.tx {
+                Some(ref mut tx) => $exec_fn($( $arg, )* tx).await,
+                _ => {
+                    let mut tx = $self.acquire().await?;
+                    let res = $exec_fn($( $arg, )* &mut tx).await;
+                    tx.commit().await?;
+                    res
+                }
+            }
+        }
+        .await;
+        $self.timings.record(
+            $crate::services::storage::timing::operation_name!(),
+            started.elapsed(),
+            res.is_ok(),
+        );
+        res
+    }};
+}
+
+pub(in crate::services::storage::sqlite) use exec_with_tx;
@@ -0,0 +1,43 @@
+//! This module contains the implementation of the `QueryOrganizations` trait for the
+//! `SqliteBackend` struct.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use sqlx::{Sqlite, Transaction};
+use uuid::Uuid;
+
+use super::{exec_with_tx, SqliteBackend};
+use crate::services::storage::entities::Organization;
+use crate::services::storage::organizations::{CreateOrganization, QueryOrganizations};
+use crate::services::storage::{Acquire, ExecOpts};
+
+#[async_trait]
+impl QueryOrganizations<Sqlite> for SqliteBackend {
+    async fn create_organization(
+        &self,
+        data: CreateOrganization,
+        _: &mut ExecOpts<Sqlite>,
+    ) -> Result<Uuid> {
+        // Without row level security, the data of a second organization could not be kept apart
+        bail!(
+            "the SQLite backend only serves the default organization, so {} cannot be created",
+            data.name
+        )
+    }
+
+    async fn fetch_organizations(
+        &self,
+        exec_opts: &mut ExecOpts<Sqlite>,
+    ) -> Result<Vec<Organization>> {
+        async fn exec(tx: &mut Transaction<'_, Sqlite>) -> Result<Vec<Organization>> {
+            let query = include_str!("queries/organizations/fetch_organizations.sql");
+            let organizations = sqlx::query_as::<_, Organization>(query)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching organizations")?;
+            Ok(organizations)
+        }
+
+        exec_with_tx!(self, exec_opts, exec)
+    }
+}
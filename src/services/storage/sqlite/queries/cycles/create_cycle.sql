@@ -0,0 +1,2 @@
+insert into project_cycles(id, name, description)
+  values (?1, ?2, ?3);
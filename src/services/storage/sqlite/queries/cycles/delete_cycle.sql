@@ -0,0 +1,2 @@
+delete from project_cycles
+where id = ?1;
@@ -0,0 +1,8 @@
+update
+  project_cycles
+set
+  name = ?2,
+  description = ?3,
+  archived = ?4
+where
+  id = ?1;
@@ -0,0 +1,11 @@
+select
+  id,
+  created_at,
+  updated_at,
+  name,
+  description,
+  archived
+from
+  project_cycles
+where
+  id = ?1;
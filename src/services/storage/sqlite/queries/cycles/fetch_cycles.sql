@@ -0,0 +1,9 @@
+select
+  id,
+  created_at,
+  updated_at,
+  name,
+  description,
+  archived
+from
+  project_cycles;
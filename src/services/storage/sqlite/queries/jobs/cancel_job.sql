@@ -0,0 +1,8 @@
+update
+  jobs
+set
+  status = 'cancelled',
+  version = version + 1
+where
+  id = ?1
+  and status = 'pending';
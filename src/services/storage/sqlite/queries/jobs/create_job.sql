@@ -0,0 +1,2 @@
+insert into jobs(id, project_cycle_id, label, description, details)
+  values (?1, ?2, ?3, ?4, ?5);
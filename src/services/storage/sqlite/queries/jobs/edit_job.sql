@@ -0,0 +1,7 @@
+update
+  jobs
+set
+  label = coalesce(?2, label),
+  description = ?3
+where
+  id = ?1;
@@ -0,0 +1,19 @@
+select
+  id,
+  created_at,
+  updated_at,
+  project_cycle_id,
+  status,
+  label,
+  description,
+  details,
+  version
+from
+  jobs
+where
+  json_extract(details, '$.jobType') = 'airtable_export_users'
+  and status = 'error'
+  and julianday(coalesce(updated_at, created_at)) >= julianday(?1)
+  and julianday(coalesce(updated_at, created_at)) < julianday(?2)
+order by
+  created_at;
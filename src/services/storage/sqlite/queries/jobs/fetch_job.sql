@@ -0,0 +1,14 @@
+select
+  id,
+  created_at,
+  updated_at,
+  project_cycle_id,
+  status,
+  label,
+  description,
+  details,
+  version
+from
+  jobs
+where
+  id = ?1;
@@ -0,0 +1,6 @@
+select
+  status
+from
+  jobs
+where
+  id = ?1;
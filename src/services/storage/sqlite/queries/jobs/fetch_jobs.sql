@@ -0,0 +1,12 @@
+select
+  id,
+  created_at,
+  updated_at,
+  project_cycle_id,
+  status,
+  label,
+  description,
+  details,
+  version
+from
+  jobs;
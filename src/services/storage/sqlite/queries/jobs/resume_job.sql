@@ -0,0 +1,10 @@
+update
+  jobs
+set
+  status = 'pending',
+  version = version + 1,
+  details = json_remove(details, '$.error')
+where
+  id = ?1
+  and version = ?2
+  and status in ('pending', 'error');
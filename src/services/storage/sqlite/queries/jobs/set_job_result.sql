@@ -0,0 +1,6 @@
+update
+  jobs
+set
+  details = json_set(details, '$.result', json(?2))
+where
+  id = ?1;
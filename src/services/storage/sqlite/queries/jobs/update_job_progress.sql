@@ -0,0 +1,7 @@
+insert into job_progress(job_id, phase, processed, total)
+  values (?1, ?2, ?3, ?4)
+on conflict (job_id)
+  do update set
+    phase = excluded.phase,
+    processed = excluded.processed,
+    total = excluded.total;
@@ -0,0 +1,12 @@
+update
+  jobs
+set
+  status = ?2,
+  version = version + 1,
+  details = case when ?3 is not null then
+    json_set(details, '$.error', ?3)
+  else
+    json_remove(details, '$.error')
+  end
+where
+  id = ?1;
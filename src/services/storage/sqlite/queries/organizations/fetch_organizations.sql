@@ -0,0 +1,10 @@
+select
+  id,
+  created_at,
+  updated_at,
+  name
+from
+  organizations
+order by
+  created_at,
+  id;
@@ -1,13 +1,14 @@
 //! This module contains the definition of the `QueryStats` trait as well as the default
 //! implementation of the trait for the `PgBackend` struct.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::{Database, Postgres, Transaction};
 use uuid::Uuid;
 
-use super::{exec_with_tx, Acquire};
-use crate::services::storage::entities::BasicStats;
+use super::{exec_read_with_tx, Acquire};
+use crate::services::storage::entities::{BasicStats, ExportDigest};
 use crate::services::storage::{ExecOpts, PgBackend};
 
 /// A trait for querying statistics about the data in Pantheon.
@@ -28,6 +29,20 @@ pub trait QueryStats<DB: Database> {
     ) -> Result<BasicStats> {
         unimplemented!()
     }
+
+    /// Summarize the exports to Google Workspace over a period of time.
+    ///
+    /// * `since`: The start of the period
+    /// * `until`: The end of the period, exclusive
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_export_digest(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<ExportDigest> {
+        unimplemented!()
+    }
 }
 
 #[async_trait]
@@ -49,6 +64,30 @@ impl QueryStats<Postgres> for PgBackend {
             Ok(stats)
         }
 
-        exec_with_tx!(self, exec_opts, exec, project_cycle_id)
+        exec_read_with_tx!(self, exec_opts, exec, project_cycle_id)
+    }
+
+    async fn fetch_export_digest(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<ExportDigest> {
+        async fn exec(
+            since: DateTime<Utc>,
+            until: DateTime<Utc>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<ExportDigest> {
+            let query = include_str!("queries/stats/fetch_export_digest.sql");
+            let digest = sqlx::query_as::<_, ExportDigest>(query)
+                .bind(since)
+                .bind(until)
+                .fetch_one(&mut **tx)
+                .await
+                .context("error fetching export digest")?;
+            Ok(digest)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, since, until)
     }
 }
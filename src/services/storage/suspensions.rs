@@ -0,0 +1,169 @@
+//! This module contains the definition of the `QuerySuspensions` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use derive_builder::Builder;
+use sqlx::{Database, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+
+use super::entities::{SuspensionCandidate, WorkspaceSuspension};
+use super::types::WorkspaceSuspensionAction;
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// Data needed to record an offboarding job's action on a volunteer's workspace account.
+///
+/// * `job_id`: The ID of the offboarding job
+/// * `volunteer_id`: The ID of the volunteer
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `action`: Whether the account was suspended or unsuspended
+/// * `succeeded`: Whether the action worked
+/// * `error`: Why the action failed, if it did
+/// * `principal`: The email of the staff member who started the job
+#[derive(Builder, Debug, Clone)]
+pub struct RecordWorkspaceSuspension {
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    #[builder(setter(into))]
+    pub workspace_email: String,
+    pub action: WorkspaceSuspensionAction,
+    pub succeeded: bool,
+    #[builder(setter(into), default = "None")]
+    pub error: Option<String>,
+    #[builder(setter(into))]
+    pub principal: String,
+}
+
+/// A trait for querying the suspension of volunteers' workspace accounts.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QuerySuspensions<DB: Database> {
+    /// Fetch the exported volunteers of a project cycle, whose workspace accounts an offboarding
+    /// job acts on.
+    ///
+    /// * `project_cycle_id`: The ID of the project cycle
+    /// * `volunteer_ids`: Only fetch these volunteers, if set
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_suspension_candidates(
+        &self,
+        project_cycle_id: Uuid,
+        volunteer_ids: Option<Vec<Uuid>>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<SuspensionCandidate>> {
+        unimplemented!()
+    }
+
+    /// Record offboarding jobs' actions on volunteers' workspace accounts.
+    ///
+    /// * `data`: The actions to record
+    /// * `exec_opts`: Execution options for the query
+    async fn record_workspace_suspensions(
+        &self,
+        data: Vec<RecordWorkspaceSuspension>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Fetch every action taken on the workspace accounts of a project cycle's volunteers, newest
+    /// first.
+    ///
+    /// * `project_cycle_id`: The ID of the project cycle
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_workspace_suspensions(
+        &self,
+        project_cycle_id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<WorkspaceSuspension>> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QuerySuspensions<Postgres> for PgBackend {
+    async fn fetch_suspension_candidates(
+        &self,
+        project_cycle_id: Uuid,
+        volunteer_ids: Option<Vec<Uuid>>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<SuspensionCandidate>> {
+        async fn exec(
+            project_cycle_id: Uuid,
+            volunteer_ids: Option<Vec<Uuid>>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<SuspensionCandidate>> {
+            let query = include_str!("queries/suspensions/fetch_suspension_candidates.sql");
+            let candidates = sqlx::query_as::<_, SuspensionCandidate>(query)
+                .bind(project_cycle_id)
+                .bind(volunteer_ids)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching volunteers to suspend")?;
+            Ok(candidates)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, project_cycle_id, volunteer_ids)
+    }
+
+    async fn record_workspace_suspensions(
+        &self,
+        data: Vec<RecordWorkspaceSuspension>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            data: Vec<RecordWorkspaceSuspension>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            if data.is_empty() {
+                return Ok(());
+            }
+
+            let fragment =
+                include_str!("queries/suspensions/record_workspace_suspensions.fragment.sql");
+
+            QueryBuilder::<Postgres>::new(fragment)
+                .push_values(data, |mut b, s| {
+                    b.push_bind(s.job_id)
+                        .push_bind(s.volunteer_id)
+                        .push_bind(s.workspace_email)
+                        .push_bind(s.action)
+                        .push_bind(s.succeeded)
+                        .push_bind(s.error)
+                        .push_bind(s.principal);
+                })
+                .build()
+                .execute(&mut **tx)
+                .await
+                .context("error recording workspace suspensions")?;
+
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn fetch_workspace_suspensions(
+        &self,
+        project_cycle_id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<WorkspaceSuspension>> {
+        async fn exec(
+            project_cycle_id: Uuid,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<WorkspaceSuspension>> {
+            let query = include_str!("queries/suspensions/fetch_workspace_suspensions.sql");
+            let suspensions = sqlx::query_as::<_, WorkspaceSuspension>(query)
+                .bind(project_cycle_id)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching workspace suspensions")?;
+            Ok(suspensions)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, project_cycle_id)
+    }
+}
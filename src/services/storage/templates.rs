@@ -0,0 +1,171 @@
+//! This module contains the definition of the `QueryTemplates` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use derive_builder::Builder;
+use sqlx::{Database, Postgres, Transaction};
+
+use super::entities::EmailTemplate;
+use super::exec_with_tx;
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// Data needed to save a new version of an email template.
+///
+/// * `name`: The name of the template (e.g. `onboard`)
+/// * `subject`: The subject line of emails rendered from the template
+/// * `body`: The Tera source of the email body
+/// * `created_by`: The email of the user saving the version
+#[derive(Builder, Debug, Clone)]
+pub struct CreateEmailTemplate {
+    #[builder(setter(into))]
+    pub name: String,
+    #[builder(setter(into))]
+    pub subject: String,
+    #[builder(setter(into))]
+    pub body: String,
+    #[builder(setter(into))]
+    pub created_by: String,
+}
+
+/// A trait for querying email templates.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryTemplates<DB: Database> {
+    /// Save a new version of an email template, creating the template if it does not exist.
+    ///
+    /// * `data`: The template to save
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Existing versions are never modified. The new version is numbered one past the newest
+    /// existing version.
+    async fn create_email_template(
+        &self,
+        data: CreateEmailTemplate,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<EmailTemplate> {
+        unimplemented!()
+    }
+
+    /// Fetch the newest version of an email template.
+    ///
+    /// * `name`: The name of the template
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_email_template(
+        &self,
+        name: &str,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Option<EmailTemplate>> {
+        unimplemented!()
+    }
+
+    /// Fetch the newest version of every email template.
+    ///
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_email_templates(
+        &self,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<EmailTemplate>> {
+        unimplemented!()
+    }
+
+    /// Fetch every version of an email template, newest first.
+    ///
+    /// * `name`: The name of the template
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_email_template_versions(
+        &self,
+        name: &str,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<EmailTemplate>> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryTemplates<Postgres> for PgBackend {
+    async fn create_email_template(
+        &self,
+        data: CreateEmailTemplate,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<EmailTemplate> {
+        async fn exec(
+            data: CreateEmailTemplate,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<EmailTemplate> {
+            let query = include_str!("queries/templates/create_email_template.sql");
+            let template = sqlx::query_as::<_, EmailTemplate>(query)
+                .bind(data.name)
+                .bind(data.subject)
+                .bind(data.body)
+                .bind(data.created_by)
+                .fetch_one(&mut **tx)
+                .await
+                .context("error creating email template")?;
+            Ok(template)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn fetch_email_template(
+        &self,
+        name: &str,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Option<EmailTemplate>> {
+        async fn exec(
+            name: &str,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Option<EmailTemplate>> {
+            let query = include_str!("queries/templates/fetch_email_template.sql");
+            let template = sqlx::query_as::<_, EmailTemplate>(query)
+                .bind(name)
+                .fetch_optional(&mut **tx)
+                .await
+                .context("error fetching email template")?;
+            Ok(template)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, name)
+    }
+
+    async fn fetch_email_templates(
+        &self,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<EmailTemplate>> {
+        async fn exec(tx: &mut Transaction<'_, Postgres>) -> Result<Vec<EmailTemplate>> {
+            let query = include_str!("queries/templates/fetch_email_templates.sql");
+            let templates = sqlx::query_as::<_, EmailTemplate>(query)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching email templates")?;
+            Ok(templates)
+        }
+
+        exec_with_tx!(self, exec_opts, exec)
+    }
+
+    async fn fetch_email_template_versions(
+        &self,
+        name: &str,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<EmailTemplate>> {
+        async fn exec(
+            name: &str,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<EmailTemplate>> {
+            let query = include_str!("queries/templates/fetch_email_template_versions.sql");
+            let templates = sqlx::query_as::<_, EmailTemplate>(query)
+                .bind(name)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching email template versions")?;
+            Ok(templates)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, name)
+    }
+}
@@ -0,0 +1,61 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::audit::{AuditLogCursor, AuditLogFilter, QueryAudit};
+use crate::services::storage::types::AuditOperation;
+use crate::services::storage::volunteers::{EditVolunteerBuilder, QueryVolunteers};
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_audit_log_records_changes(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let volunteer_id = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+
+    let data = EditVolunteerBuilder::default().email("roger@federer.com").build()?;
+    storage.edit_volunteer(volunteer_id, data, &mut ExecOptsBuilder::default().build()?).await?;
+
+    let mut exec_opts = ExecOptsBuilder::default().principal("staff@developforgood.org").build()?;
+    storage.delete_volunteer(volunteer_id, &mut exec_opts).await?;
+
+    let filter = AuditLogFilter {
+        entity: Some("volunteers".to_owned()),
+        entity_id: Some(volunteer_id.to_string()),
+        ..Default::default()
+    };
+    let entries = storage
+        .fetch_audit_log(filter.clone(), None, 10, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    // the delete is the newest change, and is attributed to the principal that made it
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].operation, AuditOperation::Update);
+    assert_eq!(entries[0].principal.as_deref(), Some("staff@developforgood.org"));
+    assert!(entries[0].before.as_ref().unwrap()["deleted_at"].is_null());
+    assert!(!entries[0].after.as_ref().unwrap()["deleted_at"].is_null());
+
+    // changes made without a principal are still recorded
+    assert_eq!(entries[1].principal, None);
+    assert_eq!(entries[1].before.as_ref().unwrap()["email"], "roger.federer@gmail.com");
+    assert_eq!(entries[1].after.as_ref().unwrap()["email"], "roger@federer.com");
+
+    let by_principal =
+        AuditLogFilter { principal: Some("STAFF@developforgood.org".to_owned()), ..filter.clone() };
+    let entries = storage
+        .fetch_audit_log(by_principal, None, 10, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    assert_eq!(entries.len(), 1);
+
+    let first = storage
+        .fetch_audit_log(filter.clone(), None, 1, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    let cursor = AuditLogCursor::decode(&AuditLogCursor::after(&first[0]).encode())?;
+    let second = storage
+        .fetch_audit_log(filter, Some(cursor), 1, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    assert_eq!(second.len(), 1);
+    assert_ne!(first[0].id, second[0].id);
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::backups::{Backup, QueryBackups, BACKUP_FORMAT_VERSION};
+use crate::services::storage::entities::BackupCounts;
+use crate::services::storage::organizations::DEFAULT_ORG_ID;
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_backup_and_restore(pool: PgPool) -> Result<()> {
+    let storage = PgBackend {
+        pool: pool.clone(),
+        replica: None,
+        metrics: Default::default(),
+        cache: None,
+        cipher: None,
+    };
+    let volunteer_id = uuid!("5e7b3f35-2b84-46e7-8b7b-73e2716d42c9");
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    let data = storage.fetch_backup_data(&mut exec_opts).await?;
+    assert_eq!(data["organization"]["id"], DEFAULT_ORG_ID.to_string());
+    assert_eq!(data["project_cycles"].as_array().map(Vec::len), Some(2));
+    assert_eq!(data["volunteers"].as_array().map(Vec::len), Some(3));
+    assert_eq!(data["volunteers_exported_to_workspace"].as_array().map(Vec::len), Some(0));
+    assert_eq!(data["jobs"].as_array().map(Vec::len), Some(2));
+
+    // a backup survives being written to and read from object storage
+    let backup = Backup {
+        format_version: BACKUP_FORMAT_VERSION,
+        org_id: DEFAULT_ORG_ID,
+        created_at: chrono::Utc::now(),
+        data,
+    };
+    let backup = Backup::from_bytes(&backup.to_bytes()?)?;
+
+    // nothing is missing, so nothing is restored
+    let counts = storage.restore_backup_data(backup.data.clone(), &mut exec_opts).await?;
+    assert_eq!(
+        counts,
+        BackupCounts {
+            project_cycles: 0,
+            volunteers: 0,
+            exported_volunteers: 0,
+            jobs: 0,
+            job_archives: 0
+        }
+    );
+
+    sqlx::query("delete from volunteers where id = $1").bind(volunteer_id).execute(&pool).await?;
+
+    // only the volunteer that was lost is restored
+    let counts = storage.restore_backup_data(backup.data, &mut exec_opts).await?;
+    assert_eq!(counts.volunteers, 1);
+    assert_eq!(counts.jobs, 0);
+    let email: String = sqlx::query_scalar("select email from volunteers where id = $1")
+        .bind(volunteer_id)
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(email, "novak.djokovic@gmail.com");
+
+    Ok(())
+}
+
+#[test]
+fn test_backup_format_version() -> Result<()> {
+    let backup = Backup {
+        format_version: BACKUP_FORMAT_VERSION + 1,
+        org_id: DEFAULT_ORG_ID,
+        created_at: chrono::Utc::now(),
+        data: serde_json::json!({}),
+    };
+    assert!(Backup::from_bytes(&backup.to_bytes()?).is_err());
+    assert!(Backup::from_bytes(b"not a backup").is_err());
+
+    Ok(())
+}
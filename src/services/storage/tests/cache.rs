@@ -0,0 +1,81 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::cache::{CacheConfig, StorageCache};
+use crate::services::storage::jobs::QueryJobs;
+use crate::services::storage::types::JobStatus;
+use crate::services::storage::volunteers::{EditVolunteerBuilder, QueryVolunteers};
+use crate::services::storage::{Acquire, ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_cached_job_reads(pool: PgPool) -> Result<()> {
+    let storage = PgBackend {
+        pool: pool.clone(),
+        replica: None,
+        metrics: Default::default(),
+        cache: Some(StorageCache::new(&CacheConfig::default())),
+        cipher: None,
+    };
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+
+    let job = storage.fetch_job(job_id, &mut ExecOptsBuilder::default().build()?).await?;
+    assert_eq!(job.status, JobStatus::Error);
+
+    // a change made around the storage layer is not seen until the entry expires
+    sqlx::query("update jobs set label = 'Renamed' where id = $1")
+        .bind(job_id)
+        .execute(&pool)
+        .await?;
+    let cached = storage.fetch_job(job_id, &mut ExecOptsBuilder::default().build()?).await?;
+    assert_eq!(cached.label, job.label);
+
+    // but reads within a transaction always see the database
+    let mut tx = storage.acquire().await?;
+    let read =
+        storage.fetch_job(job_id, &mut ExecOptsBuilder::default().tx(&mut tx).build()?).await?;
+    assert_eq!(read.label, "Renamed");
+    tx.commit().await?;
+
+    // and a change made through the storage layer invalidates the entry
+    storage.resume_job(job_id, job.version, &mut ExecOptsBuilder::default().build()?).await?;
+    let job = storage.fetch_job(job_id, &mut ExecOptsBuilder::default().build()?).await?;
+    assert_eq!(job.status, JobStatus::Pending);
+    assert_eq!(job.label, "Renamed");
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_cached_volunteer_reads(pool: PgPool) -> Result<()> {
+    let storage = PgBackend {
+        pool,
+        replica: None,
+        metrics: Default::default(),
+        cache: Some(StorageCache::new(&CacheConfig::default())),
+        cipher: None,
+    };
+    let volunteer_id = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+
+    let volunteer = storage
+        .fetch_volunteer_by_id(volunteer_id, &mut ExecOptsBuilder::default().build()?)
+        .await?
+        .expect("volunteer exists");
+    assert_eq!(volunteer.email, "roger.federer@gmail.com");
+
+    let data = EditVolunteerBuilder::default().email("roger@federer.com").build()?;
+    storage.edit_volunteer(volunteer_id, data, &mut ExecOptsBuilder::default().build()?).await?;
+    let volunteer = storage
+        .fetch_volunteer_by_id(volunteer_id, &mut ExecOptsBuilder::default().build()?)
+        .await?
+        .expect("volunteer exists");
+    assert_eq!(volunteer.email, "roger@federer.com");
+
+    storage.delete_volunteer(volunteer_id, &mut ExecOptsBuilder::default().build()?).await?;
+    let volunteer = storage
+        .fetch_volunteer_by_id(volunteer_id, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    assert!(volunteer.is_none());
+
+    Ok(())
+}
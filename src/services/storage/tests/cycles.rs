@@ -1,5 +1,5 @@
 use anyhow::Result;
-use sqlx::PgPool;
+use sqlx::{postgres::PgPoolOptions, PgPool};
 use uuid::uuid;
 
 use crate::services::storage::{
@@ -9,7 +9,8 @@ use crate::services::storage::{
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_create_cycle(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let data = CreateCycleBuilder::default()
         .name("TestCycle")
         .description("Description of test cycle")
@@ -24,7 +25,8 @@ pub async fn test_create_cycle(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_fetch_cycles(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let cycles = storage
         .fetch_cycles(&mut ExecOptsBuilder::default().build()?)
         .await?;
@@ -32,9 +34,39 @@ pub async fn test_fetch_cycles(pool: PgPool) -> Result<()> {
     Ok(())
 }
 
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_fetch_cycles_from_replica(pool: PgPool) -> Result<()> {
+    let options = (*pool.connect_options())
+        .clone()
+        .options([("default_transaction_read_only", "on")]);
+    let replica = PgPoolOptions::new().connect_with(options).await?;
+    let storage = PgBackend {
+        pool,
+        replica: Some(replica),
+        metrics: Default::default(),
+        cache: None,
+        cipher: None,
+    };
+
+    let data = CreateCycleBuilder::default()
+        .name("ReplicaCycle")
+        .description("Description of test cycle")
+        .build()?;
+    let id = storage
+        .create_cycle(data, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+
+    let cycles = storage
+        .fetch_cycles(&mut ExecOptsBuilder::default().build()?)
+        .await?;
+    assert!(cycles.iter().any(|cycle| cycle.id == id));
+    Ok(())
+}
+
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_fetch_cycle_by_id(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
     let cycle = storage
         .fetch_cycle_by_id(cycle_id, &mut ExecOptsBuilder::default().build()?)
@@ -45,7 +77,8 @@ pub async fn test_fetch_cycle_by_id(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_edit_cycle(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
     let data = EditCycleBuilder::default()
         .name("Changed")
@@ -62,7 +95,8 @@ pub async fn test_edit_cycle(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_delete_cycle(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
 
     storage
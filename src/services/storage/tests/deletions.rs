@@ -0,0 +1,140 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::deletions::{QueryDeletions, ScheduleWorkspaceDeletionBuilder};
+use crate::services::storage::types::WorkspaceDeletionStatus;
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_workspace_deletions(pool: PgPool) -> Result<()> {
+    let storage = PgBackend {
+        pool: pool.clone(),
+        replica: None,
+        metrics: Default::default(),
+        cache: None,
+        cipher: None,
+    };
+    let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id1 = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+    let volunteer_id2 = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+
+    sqlx::query(
+        "insert into volunteers_exported_to_workspace(volunteer_id, job_id, workspace_email, \
+         org_unit) values ($1, $3, 'rafaelnadal@developforgood.org', '/'), ($2, $3, \
+         'rogerfederer@developforgood.org', '/')",
+    )
+    .bind(volunteer_id1)
+    .bind(volunteer_id2)
+    .bind(job_id)
+    .execute(&pool)
+    .await?;
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    let schedule = |volunteer_id, workspace_email: &str, delete_after| {
+        ScheduleWorkspaceDeletionBuilder::default()
+            .job_id(job_id)
+            .volunteer_id(volunteer_id)
+            .workspace_email(workspace_email)
+            .delete_after(delete_after)
+            .principal("anish@developforgood.org")
+            .build()
+    };
+
+    storage
+        .schedule_workspace_deletions(
+            vec![
+                schedule(
+                    volunteer_id1,
+                    "rafaelnadal@developforgood.org",
+                    Utc::now() - Duration::days(1),
+                )?,
+                schedule(
+                    volunteer_id2,
+                    "rogerfederer@developforgood.org",
+                    Utc::now() + Duration::days(30),
+                )?,
+            ],
+            &mut exec_opts,
+        )
+        .await?;
+
+    // scheduling an account again keeps its original schedule
+    storage
+        .schedule_workspace_deletions(
+            vec![schedule(volunteer_id2, "rogerfederer@developforgood.org", Utc::now())?],
+            &mut exec_opts,
+        )
+        .await?;
+
+    let deletions = storage.fetch_workspace_deletions(project_cycle_id, &mut exec_opts).await?;
+    assert_eq!(deletions.len(), 2);
+    assert!(deletions.iter().all(|d| d.status == WorkspaceDeletionStatus::Scheduled));
+
+    // only the deletion whose grace period is over is due
+    let due = storage.fetch_due_workspace_deletions(100, &mut exec_opts).await?;
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].volunteer_id, volunteer_id1);
+
+    let pending = deletions.iter().find(|d| d.volunteer_id == volunteer_id2).unwrap();
+    let cancelled = storage
+        .cancel_workspace_deletion(
+            pending.id,
+            "anish@developforgood.org".to_owned(),
+            &mut exec_opts,
+        )
+        .await?
+        .expect("deletion was scheduled");
+    assert_eq!(cancelled.status, WorkspaceDeletionStatus::Cancelled);
+    assert_eq!(cancelled.cancelled_by.as_deref(), Some("anish@developforgood.org"));
+
+    // a deletion can only be cancelled while it is scheduled
+    assert!(storage
+        .cancel_workspace_deletion(
+            pending.id,
+            "anish@developforgood.org".to_owned(),
+            &mut exec_opts
+        )
+        .await?
+        .is_none());
+
+    storage.complete_workspace_deletion(due[0].id, &mut exec_opts).await?;
+    assert!(storage.fetch_due_workspace_deletions(100, &mut exec_opts).await?.is_empty());
+
+    // the deleted account's export is removed, but the cancelled one's is kept
+    let exported = sqlx::query_scalar::<_, String>(
+        "select workspace_email from volunteers_exported_to_workspace where job_id = $1",
+    )
+    .bind(job_id)
+    .fetch_all(&pool)
+    .await?;
+    assert_eq!(exported, vec!["rogerfederer@developforgood.org".to_owned()]);
+
+    let deletions = storage.fetch_workspace_deletions(project_cycle_id, &mut exec_opts).await?;
+    let deleted = deletions.iter().find(|d| d.volunteer_id == volunteer_id1).unwrap();
+    assert_eq!(deleted.status, WorkspaceDeletionStatus::Deleted);
+
+    // a cancelled account can be scheduled again, and a failure is recorded against it
+    storage
+        .schedule_workspace_deletions(
+            vec![schedule(volunteer_id2, "rogerfederer@developforgood.org", Utc::now())?],
+            &mut exec_opts,
+        )
+        .await?;
+    let due = storage.fetch_due_workspace_deletions(100, &mut exec_opts).await?;
+    assert_eq!(due.len(), 1);
+
+    storage.fail_workspace_deletion(due[0].id, "User not found".to_owned(), &mut exec_opts).await?;
+    let deletions = storage.fetch_workspace_deletions(project_cycle_id, &mut exec_opts).await?;
+    let failed = deletions.iter().find(|d| d.id == due[0].id).unwrap();
+    assert_eq!(failed.status, WorkspaceDeletionStatus::Failed);
+    assert_eq!(failed.error.as_deref(), Some("User not found"));
+
+    // scheduling nothing is a no-op
+    storage.schedule_workspace_deletions(vec![], &mut exec_opts).await?;
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::{uuid, Uuid};
+
+use crate::services::storage::dependencies::QueryJobDependencies;
+use crate::services::storage::jobs::{CreateJob, QueryJobs, NEW_JOB_VERSION};
+use crate::services::storage::queue::QueryQueue;
+use crate::services::storage::types::{
+    ExportDesination, JobData, JobDetails, JobPriority, JobStatus, JobType,
+};
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+async fn create_export_job(storage: &PgBackend) -> Result<Uuid> {
+    storage
+        .create_job(
+            None,
+            CreateJob {
+                label: "Export Users".to_owned(),
+                description: None,
+                data: JobDetails {
+                    job_type: JobType::AirtableExportUsers,
+                    error: None,
+                    result: None,
+                    data: JobData::AirtableExportUsers {
+                        export_destination: ExportDesination::GoogleWorkspace,
+                    },
+                },
+            },
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_job_dependencies(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let first = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let worker = Uuid::new_v4();
+    let claimed_until = Utc::now() + Duration::minutes(5);
+
+    let second = create_export_job(&storage).await?;
+    let third = create_export_job(&storage).await?;
+    assert!(storage.set_job_dependency(second, first, &mut exec_opts).await?);
+    assert!(storage.set_job_dependency(third, second, &mut exec_opts).await?);
+    assert!(!storage.set_job_dependency(Uuid::new_v4(), first, &mut exec_opts).await?);
+    for job_id in [second, third] {
+        storage.enqueue_job(job_id, json!({}), JobPriority::Normal, None, &mut exec_opts).await?;
+    }
+
+    // the whole chain is fetched from any job in it
+    let chain = storage.fetch_job_chain(third, &mut exec_opts).await?;
+    assert_eq!(
+        chain.iter().map(|job| (job.id, job.depends_on)).collect::<Vec<_>>(),
+        vec![(first, None), (second, Some(first)), (third, Some(second))]
+    );
+    assert_eq!(storage.fetch_job_chain(first, &mut exec_opts).await?, chain);
+
+    // a job is not run until the job it depends on has completed
+    assert!(storage.claim_queued_job(worker, claimed_until, &mut exec_opts).await?.is_none());
+    assert!(storage.cancel_jobs_with_failed_dependencies(&mut exec_opts).await?.is_empty());
+    storage.mark_job_complete(first, NEW_JOB_VERSION, &mut exec_opts).await?;
+    let claimed = storage.claim_queued_job(worker, claimed_until, &mut exec_opts).await?;
+    assert_eq!(claimed.map(|job| job.job_id), Some(second));
+
+    // a job that errored between its attempts may still complete
+    storage
+        .mark_job_errored(second, NEW_JOB_VERSION, "quota exceeded".to_owned(), &mut exec_opts)
+        .await?;
+    assert!(storage.cancel_jobs_with_failed_dependencies(&mut exec_opts).await?.is_empty());
+
+    // once it has failed for good, the jobs that depend on it are cancelled
+    storage
+        .finish_queued_job(second, worker, Some("quota exceeded".to_owned()), &mut exec_opts)
+        .await?;
+    assert_eq!(storage.cancel_jobs_with_failed_dependencies(&mut exec_opts).await?, vec![third]);
+    assert_eq!(storage.fetch_job_status(third, &mut exec_opts).await?, JobStatus::Cancelled);
+    let queued = storage.fetch_queued_job(third, &mut exec_opts).await?.unwrap();
+    assert!(queued.finished_at.is_some());
+    assert!(storage.cancel_jobs_with_failed_dependencies(&mut exec_opts).await?.is_empty());
+
+    Ok(())
+}
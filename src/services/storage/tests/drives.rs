@@ -0,0 +1,60 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::drives::{QuerySharedDrives, RecordSharedDriveBuilder};
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_shared_drives(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    assert!(storage
+        .fetch_shared_drive(project_cycle_id, "Fall 2024", &mut exec_opts)
+        .await?
+        .is_none());
+
+    storage
+        .record_shared_drive(
+            RecordSharedDriveBuilder::default()
+                .project_cycle_id(project_cycle_id)
+                .job_id(job_id)
+                .drive_id("0ABcdEfGhIjKlUk9PVA")
+                .name("Fall 2024")
+                .build()?,
+            &mut exec_opts,
+        )
+        .await?;
+
+    // recording a second drive with the same name keeps the first one
+    storage
+        .record_shared_drive(
+            RecordSharedDriveBuilder::default()
+                .project_cycle_id(project_cycle_id)
+                .job_id(job_id)
+                .drive_id("0XYzzYxWvUtSrUk9PVA")
+                .name("Fall 2024")
+                .build()?,
+            &mut exec_opts,
+        )
+        .await?;
+
+    let drive = storage
+        .fetch_shared_drive(project_cycle_id, "Fall 2024", &mut exec_opts)
+        .await?
+        .expect("drive was recorded");
+    assert_eq!(drive.drive_id, "0ABcdEfGhIjKlUk9PVA");
+    assert_eq!(drive.job_id, job_id);
+
+    assert!(storage
+        .fetch_shared_drive(project_cycle_id, "Spring 2025", &mut exec_opts)
+        .await?
+        .is_none());
+
+    Ok(())
+}
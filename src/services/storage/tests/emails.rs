@@ -0,0 +1,274 @@
+use anyhow::Result;
+use chrono::{TimeDelta, Utc};
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::emails::{
+    CreateEmailRetryBuilder, CreateEmailSuppressionBuilder, QueryEmails, RecordEmailEventBuilder,
+    RecordEmailSendBuilder,
+};
+use crate::services::storage::entities::EmailDeliveryCounts;
+use crate::services::storage::types::{EmailEventType, EmailSendStatus, EmailSuppressionReason};
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_record_and_fetch_email_sends(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    storage
+        .record_email_send(
+            RecordEmailSendBuilder::default()
+                .job_id(Some(job_id))
+                .volunteer_id(Some(volunteer_id))
+                .recipient("rafaelnadal@gmail.com")
+                .template("email/onboard.en.html")
+                .status(EmailSendStatus::Failed)
+                .error(Some("provider is down".to_owned()))
+                .build()?,
+            &mut exec_opts,
+        )
+        .await?;
+    let sent = storage
+        .record_email_send(
+            RecordEmailSendBuilder::default()
+                .job_id(Some(job_id))
+                .volunteer_id(Some(volunteer_id))
+                .recipient("rafaelnadal@gmail.com")
+                .template("email/onboard.en.html")
+                .provider(Some("sendgrid".to_owned()))
+                .message_id(Some("abc.123".to_owned()))
+                .status(EmailSendStatus::Sent)
+                .build()?,
+            &mut exec_opts,
+        )
+        .await?;
+    storage
+        .record_email_send(
+            RecordEmailSendBuilder::default()
+                .job_id(Some(job_id))
+                .recipient("rogerfederer@gmail.com")
+                .template("onboard v2")
+                .status(EmailSendStatus::Sent)
+                .build()?,
+            &mut exec_opts,
+        )
+        .await?;
+
+    let sends = storage.fetch_email_sends(job_id, None, &mut exec_opts).await?;
+    assert_eq!(sends.len(), 3);
+
+    let sends = storage
+        .fetch_email_sends(job_id, Some("rafaelnadal@gmail.com".to_owned()), &mut exec_opts)
+        .await?;
+    assert_eq!(sends.len(), 2);
+    assert_eq!(sends[0].status, EmailSendStatus::Failed);
+    assert_eq!(sends[1], sent);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_email_retry_queue(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+
+    let retry = CreateEmailRetryBuilder::default()
+        .job_id(job_id)
+        .volunteer_id(volunteer_id)
+        .first_name("Rafael")
+        .last_name("Nadal")
+        .email("rafaelnadal@gmail.com")
+        .workspace_email("rafaelnadal@developforgood.org")
+        .temporary_password(Some("password123".to_owned()))
+        .locale("es")
+        .build()?;
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    storage.enqueue_email_retries(vec![retry.clone()], &mut exec_opts).await?;
+    // queueing the same email again does not duplicate it
+    storage.enqueue_email_retries(vec![retry], &mut exec_opts).await?;
+
+    let due = storage.fetch_due_email_retries(Some(job_id), 10, &mut exec_opts).await?;
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].temporary_password.as_deref(), Some("password123"));
+
+    // rescheduled into the future, so no longer due
+    let later = Utc::now() + TimeDelta::minutes(5);
+    storage
+        .record_email_retry_failure(
+            due[0].id,
+            "provider is down".to_owned(),
+            Some(later),
+            &mut exec_opts,
+        )
+        .await?;
+    assert!(storage.fetch_due_email_retries(None, 10, &mut exec_opts).await?.is_empty());
+
+    // given up on
+    storage
+        .record_email_retry_failure(due[0].id, "provider is down".to_owned(), None, &mut exec_opts)
+        .await?;
+    let queued = storage.fetch_email_retries(job_id, &mut exec_opts).await?;
+    assert_eq!(queued[0].attempts, 2);
+    assert!(queued[0].exhausted);
+
+    storage.reset_email_retries(job_id, &mut exec_opts).await?;
+    let due = storage.fetch_due_email_retries(Some(job_id), 10, &mut exec_opts).await?;
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].attempts, 0);
+
+    storage.delete_email_retry(due[0].id, &mut exec_opts).await?;
+    assert!(storage.fetch_email_retries(job_id, &mut exec_opts).await?.is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_email_suppressions(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    storage
+        .suppress_emails(
+            vec![
+                CreateEmailSuppressionBuilder::default()
+                    .email("RafaelNadal@gmail.com")
+                    .reason(EmailSuppressionReason::Bounced)
+                    .provider(Some("sendgrid".to_owned()))
+                    .detail(Some("550 mailbox unavailable".to_owned()))
+                    .build()?,
+                CreateEmailSuppressionBuilder::default()
+                    .email("rogerfederer@gmail.com")
+                    .reason(EmailSuppressionReason::Unsubscribed)
+                    .build()?,
+            ],
+            &mut exec_opts,
+        )
+        .await?;
+    // suppressing an address again replaces its entry
+    storage
+        .suppress_emails(
+            vec![CreateEmailSuppressionBuilder::default()
+                .email("rogerfederer@gmail.com")
+                .reason(EmailSuppressionReason::SpamReport)
+                .build()?],
+            &mut exec_opts,
+        )
+        .await?;
+
+    assert_eq!(storage.fetch_email_suppressions(&mut exec_opts).await?.len(), 2);
+
+    let suppressed = storage
+        .fetch_suppressed_emails(
+            vec!["rafaelnadal@GMAIL.com".to_owned(), "novakdjokovic@gmail.com".to_owned()],
+            &mut exec_opts,
+        )
+        .await?;
+    assert_eq!(suppressed.len(), 1);
+    assert_eq!(suppressed[0].email, "rafaelnadal@gmail.com");
+    assert_eq!(suppressed[0].reason, EmailSuppressionReason::Bounced);
+
+    let suppressed = storage
+        .fetch_suppressed_emails(vec!["rogerfederer@gmail.com".to_owned()], &mut exec_opts)
+        .await?;
+    assert_eq!(suppressed[0].reason, EmailSuppressionReason::SpamReport);
+
+    assert!(
+        storage
+            .delete_email_suppression("RAFAELNADAL@gmail.com".to_owned(), &mut exec_opts)
+            .await?
+    );
+    assert!(
+        !storage
+            .delete_email_suppression("rafaelnadal@gmail.com".to_owned(), &mut exec_opts)
+            .await?
+    );
+    assert_eq!(storage.fetch_email_suppressions(&mut exec_opts).await?.len(), 1);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_email_delivery_events(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+    let queued_at = Utc::now() - TimeDelta::minutes(10);
+    let sent_at = queued_at + TimeDelta::minutes(1);
+    let delivered_at = sent_at + TimeDelta::minutes(1);
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let recorded = storage
+        .record_email_events(
+            vec![
+                RecordEmailEventBuilder::default()
+                    .job_id(Some(job_id))
+                    .volunteer_id(Some(volunteer_id))
+                    .recipient("rafaelnadal@gmail.com")
+                    .event(EmailEventType::Queued)
+                    .event_at(queued_at)
+                    .build()?,
+                RecordEmailEventBuilder::default()
+                    .job_id(Some(job_id))
+                    .volunteer_id(Some(volunteer_id))
+                    .provider(Some("sendgrid".to_owned()))
+                    .message_id(Some("abc.123".to_owned()))
+                    .recipient("rafaelnadal@gmail.com")
+                    .event(EmailEventType::Sent)
+                    .event_at(sent_at)
+                    .build()?,
+                RecordEmailEventBuilder::default()
+                    .job_id(Some(job_id))
+                    .recipient("rogerfederer@gmail.com")
+                    .event(EmailEventType::Queued)
+                    .event_at(queued_at)
+                    .build()?,
+            ],
+            &mut exec_opts,
+        )
+        .await?;
+    assert_eq!(recorded, 3);
+
+    // the provider only reports the message ID, and reports the delivery twice
+    let delivered = RecordEmailEventBuilder::default()
+        .message_id(Some("abc.123".to_owned()))
+        .recipient("rafaelnadal@gmail.com")
+        .event(EmailEventType::Delivered)
+        .event_at(delivered_at)
+        .build()?;
+    let recorded =
+        storage.record_email_events(vec![delivered.clone(), delivered], &mut exec_opts).await?;
+    assert_eq!(recorded, 1);
+
+    // the delivery is matched to the job and volunteer the email was sent for
+    let events = storage.fetch_message_email_events("abc.123".to_owned(), &mut exec_opts).await?;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1].event, EmailEventType::Delivered);
+    assert_eq!(events[1].job_id, Some(job_id));
+    assert_eq!(events[1].volunteer_id, Some(volunteer_id));
+    assert_eq!(events[1].provider.as_deref(), Some("sendgrid"));
+
+    let events = storage.fetch_job_email_events(job_id, None, &mut exec_opts).await?;
+    assert_eq!(events.len(), 4);
+    let events = storage
+        .fetch_job_email_events(job_id, Some("rafaelnadal@gmail.com".to_owned()), &mut exec_opts)
+        .await?;
+    let kinds: Vec<_> = events.iter().map(|e| e.event).collect();
+    assert_eq!(
+        kinds,
+        vec![EmailEventType::Queued, EmailEventType::Sent, EmailEventType::Delivered]
+    );
+
+    let counts = storage.fetch_email_delivery_counts(job_id, &mut exec_opts).await?;
+    assert_eq!(counts, EmailDeliveryCounts { queued: 1, delivered: 1, ..Default::default() });
+
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{TimeDelta, Utc};
+use sqlx::PgPool;
+use uuid::{uuid, Uuid};
+
+use crate::services::storage::emails::{CreateEmailRetryBuilder, QueryEmails};
+use crate::services::storage::encryption::{FieldCipher, ENCRYPTED_PREFIX};
+use crate::services::storage::outbox::{CreateOutboxEmailBuilder, QueryOutbox};
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+fn key(id: &str, byte: u8) -> String {
+    format!("{id}:{}", BASE64.encode([byte; 32]))
+}
+
+#[test]
+fn test_encrypt_and_decrypt() -> Result<()> {
+    let cipher = FieldCipher::new(&[key("k1", 1)])?;
+
+    let encrypted = cipher.encrypt("rafael.nadal@gmail.com")?;
+    assert!(encrypted.starts_with("enc:v1:k1:"));
+    assert!(!encrypted.contains("rafael"));
+    assert_eq!(cipher.decrypt(&encrypted)?, "rafael.nadal@gmail.com");
+
+    // every value gets its own data key and nonce
+    assert_ne!(cipher.encrypt("rafael.nadal@gmail.com")?, encrypted);
+
+    // values written before encryption was configured are read as they are
+    assert_eq!(cipher.decrypt("rafael.nadal@gmail.com")?, "rafael.nadal@gmail.com");
+
+    Ok(())
+}
+
+#[test]
+fn test_rotate_keys() -> Result<()> {
+    let old = FieldCipher::new(&[key("k1", 1)])?;
+    let encrypted = old.encrypt("password123")?;
+
+    // the new key encrypts new values, and the old key still decrypts the values it encrypted
+    let rotated = FieldCipher::new(&[key("k2", 2), key("k1", 1)])?;
+    assert!(rotated.encrypt("password123")?.starts_with("enc:v1:k2:"));
+    assert_eq!(rotated.decrypt(&encrypted)?, "password123");
+
+    // values can't be read once their key is dropped
+    let dropped = FieldCipher::new(&[key("k2", 2)])?;
+    assert!(dropped.decrypt(&encrypted).is_err());
+
+    // or with the wrong key under the same id
+    let wrong = FieldCipher::new(&[key("k1", 3)])?;
+    assert!(wrong.decrypt(&encrypted).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_invalid_keys() {
+    assert!(FieldCipher::new(&[]).is_err());
+    assert!(FieldCipher::new(&["k1".to_owned()]).is_err());
+    assert!(FieldCipher::new(&["k1:not base64".to_owned()]).is_err());
+    assert!(FieldCipher::new(&[format!("k1:{}", BASE64.encode([1; 16]))]).is_err());
+    assert!(FieldCipher::new(&[key("k1", 1), key("k1", 2)]).is_err());
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_email_retries_are_encrypted_at_rest(pool: PgPool) -> Result<()> {
+    let storage = PgBackend {
+        pool: pool.clone(),
+        replica: None,
+        metrics: Default::default(),
+        cache: None,
+        cipher: Some(FieldCipher::new(&[key("k1", 1)])?),
+    };
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+
+    let retry = CreateEmailRetryBuilder::default()
+        .job_id(job_id)
+        .volunteer_id(volunteer_id)
+        .first_name("Rafael")
+        .last_name("Nadal")
+        .email("rafaelnadal@gmail.com")
+        .workspace_email("rafaelnadal@developforgood.org")
+        .temporary_password(Some("password123".to_owned()))
+        .locale("es")
+        .build()?;
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    storage.enqueue_email_retries(vec![retry], &mut exec_opts).await?;
+
+    let (email, password): (String, String) =
+        sqlx::query_as("select email, temporary_password from onboarding_email_retries")
+            .fetch_one(&pool)
+            .await?;
+    assert!(email.starts_with(ENCRYPTED_PREFIX));
+    assert!(password.starts_with(ENCRYPTED_PREFIX));
+
+    let due = storage.fetch_due_email_retries(Some(job_id), 10, &mut exec_opts).await?;
+    assert_eq!(due[0].email, "rafaelnadal@gmail.com");
+    assert_eq!(due[0].temporary_password.as_deref(), Some("password123"));
+
+    // without the keys, encrypted values can't be read
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    assert!(storage.fetch_email_retries(job_id, &mut exec_opts).await.is_err());
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_outbox_emails_are_encrypted_at_rest(pool: PgPool) -> Result<()> {
+    let storage = PgBackend {
+        pool: pool.clone(),
+        replica: None,
+        metrics: Default::default(),
+        cache: None,
+        cipher: Some(FieldCipher::new(&[key("k1", 1)])?),
+    };
+    let job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let volunteer_id = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+
+    let email = CreateOutboxEmailBuilder::default()
+        .job_id(job_id)
+        .volunteer_id(volunteer_id)
+        .first_name("Rafael")
+        .last_name("Nadal")
+        .email("rafaelnadal@gmail.com")
+        .workspace_email("rafaelnadal@developforgood.org")
+        .temporary_password(Some("password123".to_owned()))
+        .locale("es")
+        .build()?;
+
+    // the export's claim has already run out, so the relay can take the email right away
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    storage
+        .enqueue_outbox_emails(
+            vec![email],
+            Uuid::new_v4(),
+            Utc::now() - TimeDelta::minutes(1),
+            &mut exec_opts,
+        )
+        .await?;
+
+    let (email, password): (String, String) =
+        sqlx::query_as("select email, temporary_password from email_outbox")
+            .fetch_one(&pool)
+            .await?;
+    assert!(email.starts_with(ENCRYPTED_PREFIX) && !email.contains("rafaelnadal"));
+    assert!(password.starts_with(ENCRYPTED_PREFIX) && !password.contains("password123"));
+
+    let claimed = storage
+        .claim_outbox_emails(
+            Uuid::new_v4(),
+            Some(job_id),
+            10,
+            Utc::now() + TimeDelta::minutes(30),
+            &mut exec_opts,
+        )
+        .await?;
+    assert_eq!(claimed[0].email, "rafaelnadal@gmail.com");
+    assert_eq!(claimed[0].temporary_password.as_deref(), Some("password123"));
+
+    Ok(())
+}
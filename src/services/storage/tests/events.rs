@@ -0,0 +1,35 @@
+use anyhow::Result;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::events::QueryJobEvents;
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_job_events(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+
+    assert!(storage.fetch_job_events(job_id, 0, &mut exec_opts).await?.is_empty());
+
+    let first =
+        storage.record_job_event(job_id, json!({ "type": "phaseStarted" }), &mut exec_opts).await?;
+    let second = storage
+        .record_job_event(job_id, json!({ "type": "volunteerExported" }), &mut exec_opts)
+        .await?;
+    assert!(second.id > first.id);
+
+    // listed oldest first
+    let events = storage.fetch_job_events(job_id, 0, &mut exec_opts).await?;
+    assert_eq!(events, vec![first.clone(), second.clone()]);
+
+    // only the events after the last one seen
+    let events = storage.fetch_job_events(job_id, first.id, &mut exec_opts).await?;
+    assert_eq!(events, vec![second.clone()]);
+    assert!(storage.fetch_job_events(job_id, second.id, &mut exec_opts).await?.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use anyhow::Result;
+use chrono::DateTime;
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::exports::{
+    CreateExportCheckpoint, QueryExports, RecordOnboardingEmailDeliveryBuilder,
+};
+use crate::services::storage::types::{EmailDeliveryStatus, WorkspaceExportStatus};
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_create_and_update_export_checkpoints(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id1 = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+    let volunteer_id2 = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+
+    let data = vec![
+        CreateExportCheckpoint {
+            volunteer_id: volunteer_id1,
+            workspace_email: "rafaelnadal@developforgood.org".to_owned(),
+            org_unit: "/Programs/PantheonUsers".to_owned(),
+        },
+        CreateExportCheckpoint {
+            volunteer_id: volunteer_id2,
+            workspace_email: "rogerfederer@developforgood.org".to_owned(),
+            org_unit: "/Programs/PantheonUsers".to_owned(),
+        },
+    ];
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    storage.create_export_checkpoints(job_id, data.clone(), &mut exec_opts).await?;
+
+    // recording the same volunteers again should be a no-op
+    storage.create_export_checkpoints(job_id, data, &mut exec_opts).await?;
+
+    let checkpoints = storage.fetch_export_checkpoints(job_id, &mut exec_opts).await?;
+    assert_eq!(checkpoints.len(), 2);
+    assert!(checkpoints.iter().all(|c| c.status == WorkspaceExportStatus::Pending));
+
+    storage
+        .update_export_checkpoint(
+            job_id,
+            volunteer_id1,
+            WorkspaceExportStatus::Created,
+            None,
+            &mut exec_opts,
+        )
+        .await?;
+    storage
+        .update_export_checkpoint(
+            job_id,
+            volunteer_id2,
+            WorkspaceExportStatus::Failed,
+            Some("rate limited".to_owned()),
+            &mut exec_opts,
+        )
+        .await?;
+
+    let checkpoints = storage.fetch_export_checkpoints(job_id, &mut exec_opts).await?;
+    let created = checkpoints.iter().find(|c| c.volunteer_id == volunteer_id1).unwrap();
+    let failed = checkpoints.iter().find(|c| c.volunteer_id == volunteer_id2).unwrap();
+    assert_eq!(created.status, WorkspaceExportStatus::Created);
+    assert_eq!(failed.status, WorkspaceExportStatus::Failed);
+    assert_eq!(failed.error.as_deref(), Some("rate limited"));
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_batch_update_export_checkpoints(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id1 = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+    let volunteer_id2 = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+
+    let data = vec![
+        CreateExportCheckpoint {
+            volunteer_id: volunteer_id1,
+            workspace_email: "rafaelnadal@developforgood.org".to_owned(),
+            org_unit: "/Programs/PantheonUsers".to_owned(),
+        },
+        CreateExportCheckpoint {
+            volunteer_id: volunteer_id2,
+            workspace_email: "rogerfederer@developforgood.org".to_owned(),
+            org_unit: "/Programs/PantheonUsers".to_owned(),
+        },
+    ];
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    storage.create_export_checkpoints(job_id, data, &mut exec_opts).await?;
+
+    storage
+        .update_export_checkpoints(
+            job_id,
+            vec![volunteer_id1],
+            WorkspaceExportStatus::Recorded,
+            &mut exec_opts,
+        )
+        .await?;
+
+    let checkpoints = storage.fetch_export_checkpoints(job_id, &mut exec_opts).await?;
+    let recorded = checkpoints.iter().find(|c| c.volunteer_id == volunteer_id1).unwrap();
+    let pending = checkpoints.iter().find(|c| c.volunteer_id == volunteer_id2).unwrap();
+    assert_eq!(recorded.status, WorkspaceExportStatus::Recorded);
+    assert_eq!(pending.status, WorkspaceExportStatus::Pending);
+
+    storage
+        .update_export_checkpoints(
+            job_id,
+            vec![volunteer_id1, volunteer_id2],
+            WorkspaceExportStatus::Emailed,
+            &mut exec_opts,
+        )
+        .await?;
+
+    let checkpoints = storage.fetch_export_checkpoints(job_id, &mut exec_opts).await?;
+    assert!(checkpoints.iter().all(|c| c.status == WorkspaceExportStatus::Emailed));
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_record_onboarding_email_deliveries(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+    let delivered_at = DateTime::from_timestamp(1_729_000_000, 0).unwrap();
+    let opened_at = DateTime::from_timestamp(1_729_000_600, 0).unwrap();
+
+    let event = |status, event_at| {
+        RecordOnboardingEmailDeliveryBuilder::default()
+            .job_id(job_id)
+            .volunteer_id(volunteer_id)
+            .email("rafaelnadal@gmail.com")
+            .status(status)
+            .message_id(Some("abc.123".to_owned()))
+            .event_at(event_at)
+            .build()
+            .unwrap()
+    };
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    storage
+        .record_onboarding_email_deliveries(
+            vec![
+                event(EmailDeliveryStatus::Opened, opened_at),
+                // arrives late, and should not overwrite the newer event
+                event(EmailDeliveryStatus::Delivered, delivered_at),
+                // refers to a volunteer that does not exist, and should be ignored
+                RecordOnboardingEmailDeliveryBuilder::default()
+                    .job_id(job_id)
+                    .volunteer_id(uuid!("00000000-0000-0000-0000-000000000000"))
+                    .email("nobody@example.com")
+                    .status(EmailDeliveryStatus::Bounced)
+                    .event_at(delivered_at)
+                    .build()?,
+            ],
+            &mut exec_opts,
+        )
+        .await?;
+
+    let deliveries = storage.fetch_onboarding_email_deliveries(job_id, &mut exec_opts).await?;
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].volunteer_id, volunteer_id);
+    assert_eq!(deliveries[0].status, EmailDeliveryStatus::Opened);
+    assert_eq!(deliveries[0].event_at, opened_at);
+
+    Ok(())
+}
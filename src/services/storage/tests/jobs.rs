@@ -1,16 +1,22 @@
 use anyhow::Result;
+use chrono::{Duration, Utc};
 use sqlx::PgPool;
 use uuid::uuid;
 
 use crate::services::storage::{
-    jobs::{CreateJob, EditJobBuilder, QueryJobs, UpdateJobStatus},
-    types::{JobData, JobDetails, JobStatus, JobType},
-    ExecOptsBuilder, PgBackend,
+    jobs::{
+        is_job_conflict, CreateJob, EditJobBuilder, JobConflictError, QueryJobs,
+        UpdateJobProgressBuilder, UpdateJobStatus, NEW_JOB_VERSION,
+    },
+    types::{JobData, JobDetails, JobPhase, JobStatus, JobType},
+    volunteers::{InsertVolunteerExportedToWorkspace, QueryVolunteers},
+    Acquire, ExecOptsBuilder, PgBackend,
 };
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_create_job(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
 
     let mut exec_opts = ExecOptsBuilder::default().build()?;
     let job_id = storage
@@ -22,6 +28,7 @@ pub async fn test_create_job(pool: PgPool) -> Result<()> {
                 data: JobDetails {
                     job_type: JobType::AirtableImportBase,
                     error: None,
+                    result: None,
                     data: JobData::AirtableImportBase {
                         base_id: "appS5z0uqz4l0IJvP".to_owned(),
                     },
@@ -37,7 +44,8 @@ pub async fn test_create_job(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_fetch_job(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let job_id1 = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
     let job_id2 = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
 
@@ -57,7 +65,8 @@ pub async fn test_fetch_job(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_update_job_status(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let job_id1 = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
 
     let data = UpdateJobStatus {
@@ -80,7 +89,8 @@ pub async fn test_update_job_status(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_edit_job(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let job_id1 = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
     let data = EditJobBuilder::default()
         .label("New Label".to_owned())
@@ -95,3 +105,234 @@ pub async fn test_edit_job(pool: PgPool) -> Result<()> {
 
     Ok(())
 }
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_update_job_progress(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id1 = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    let progress = storage.fetch_job_progress(job_id1, &mut exec_opts).await?;
+    assert!(progress.is_none());
+
+    let data = UpdateJobProgressBuilder::default()
+        .phase(JobPhase::Provisioning)
+        .total(10)
+        .build()?;
+    storage.update_job_progress(job_id1, data, &mut exec_opts).await?;
+
+    let data = UpdateJobProgressBuilder::default()
+        .phase(JobPhase::Emailing)
+        .processed(3)
+        .total(8)
+        .build()?;
+    storage.update_job_progress(job_id1, data, &mut exec_opts).await?;
+
+    let progress = storage.fetch_job_progress(job_id1, &mut exec_opts).await?.unwrap();
+    assert_eq!(progress.phase, JobPhase::Emailing);
+    assert_eq!(progress.processed, 3);
+    assert_eq!(progress.total, 8);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_complete_job_in_transaction(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let volunteer_id = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+    let record = InsertVolunteerExportedToWorkspace {
+        volunteer_id,
+        job_id,
+        workspace_email: "roger.federer@developforgood.org".to_owned(),
+        org_unit: "/Programs/PantheonUsers".to_owned(),
+    };
+
+    // a transaction that is not committed leaves neither the records nor the job's status behind
+    {
+        let mut tx = storage.acquire().await?;
+        let mut exec_opts = ExecOptsBuilder::default().tx(&mut tx).build()?;
+        storage.batch_insert_volunteers_exported_to_workspace(vec![record.clone()], &mut exec_opts).await?;
+        storage.mark_job_complete(job_id, NEW_JOB_VERSION, &mut exec_opts).await?;
+    }
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let job = storage.fetch_job(job_id, &mut exec_opts).await?;
+    assert_ne!(job.status, JobStatus::Complete);
+    let exported =
+        storage.fetch_exported_volunteer_details_by_ids(vec![volunteer_id], &mut exec_opts).await?;
+    assert!(exported.is_empty());
+
+    let mut tx = storage.acquire().await?;
+    let mut exec_opts = ExecOptsBuilder::default().tx(&mut tx).build()?;
+    storage.batch_insert_volunteers_exported_to_workspace(vec![record], &mut exec_opts).await?;
+    storage.mark_job_complete(job_id, NEW_JOB_VERSION, &mut exec_opts).await?;
+    tx.commit().await?;
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let job = storage.fetch_job(job_id, &mut exec_opts).await?;
+    assert_eq!(job.status, JobStatus::Complete);
+    let exported =
+        storage.fetch_exported_volunteer_details_by_ids(vec![volunteer_id], &mut exec_opts).await?;
+    assert_eq!(exported.len(), 1);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_job_finishes_once(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    storage
+        .mark_job_errored(job_id, NEW_JOB_VERSION, "quota exceeded".to_owned(), &mut exec_opts)
+        .await?;
+
+    // a job that was already finished is not finished again, even at its current version
+    let err =
+        storage.mark_job_complete(job_id, NEW_JOB_VERSION + 1, &mut exec_opts).await.unwrap_err();
+    assert_eq!(err.downcast_ref::<JobConflictError>(), Some(&JobConflictError { job_id }));
+    let err = storage
+        .mark_job_errored(job_id, NEW_JOB_VERSION + 1, "other".to_owned(), &mut exec_opts)
+        .await
+        .unwrap_err();
+    assert!(is_job_conflict(&err));
+
+    let job = storage.fetch_job(job_id, &mut exec_opts).await?;
+    assert_eq!(job.status, JobStatus::Error);
+    assert_eq!(job.version, 1);
+    let details = serde_json::from_value::<JobDetails>(job.details)?;
+    assert_eq!(details.error.as_deref(), Some("quota exceeded"));
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_cancel_job(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let pending = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let errored = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    assert!(storage.cancel_job(pending, &mut exec_opts).await?);
+    assert_eq!(storage.fetch_job_status(pending, &mut exec_opts).await?, JobStatus::Cancelled);
+
+    // a job that is no longer pending can't be cancelled
+    assert!(!storage.cancel_job(pending, &mut exec_opts).await?);
+    assert!(!storage.cancel_job(errored, &mut exec_opts).await?);
+    assert_eq!(storage.fetch_job_status(errored, &mut exec_opts).await?, JobStatus::Error);
+
+    // the job can't be finished once it was cancelled
+    let version = storage.fetch_job(pending, &mut exec_opts).await?.version;
+    let err = storage.mark_job_complete(pending, version, &mut exec_opts).await.unwrap_err();
+    assert!(is_job_conflict(&err));
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_resume_job_at_version(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let job = storage.fetch_job(job_id, &mut exec_opts).await?;
+    storage.resume_job(job_id, job.version, &mut exec_opts).await?;
+
+    // a worker that read the job before it was resumed can't resume it too
+    let err = storage.resume_job(job_id, job.version, &mut exec_opts).await.unwrap_err();
+    assert!(is_job_conflict(&err));
+
+    let resumed = storage.fetch_job(job_id, &mut exec_opts).await?;
+    assert_eq!(resumed.status, JobStatus::Pending);
+    assert_eq!(resumed.version, job.version + 1);
+    assert!(serde_json::from_value::<JobDetails>(resumed.details)?.error.is_none());
+
+    storage.mark_job_complete(job_id, resumed.version, &mut exec_opts).await?;
+    assert_eq!(storage.fetch_job(job_id, &mut exec_opts).await?.status, JobStatus::Complete);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_finish_job_at_version(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let job = storage.fetch_job(job_id, &mut exec_opts).await?;
+    storage.resume_job(job_id, job.version, &mut exec_opts).await?;
+
+    // a worker that read the job before it was resumed can't finish it
+    let err = storage.mark_job_complete(job_id, job.version, &mut exec_opts).await.unwrap_err();
+    assert_eq!(err.downcast_ref::<JobConflictError>(), Some(&JobConflictError { job_id }));
+    let err = storage
+        .mark_job_errored(job_id, job.version, "stale".to_owned(), &mut exec_opts)
+        .await
+        .unwrap_err();
+    assert!(is_job_conflict(&err));
+    assert_eq!(storage.fetch_job_status(job_id, &mut exec_opts).await?, JobStatus::Pending);
+
+    // the worker that resumed it can
+    storage.mark_job_complete(job_id, job.version + 1, &mut exec_opts).await?;
+    assert_eq!(storage.fetch_job_status(job_id, &mut exec_opts).await?, JobStatus::Complete);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_archive_jobs(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let pending_job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let errored_job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    // jobs that finished after the cutoff are not archived
+    let cutoff = Utc::now() - Duration::days(1);
+    assert_eq!(storage.archive_jobs(cutoff, 100, &mut exec_opts).await?, 0);
+
+    // only finished jobs are archived
+    let cutoff = Utc::now() + Duration::days(1);
+    assert_eq!(storage.archive_jobs(cutoff, 100, &mut exec_opts).await?, 1);
+    assert_eq!(storage.archive_jobs(cutoff, 100, &mut exec_opts).await?, 0);
+
+    let jobs = storage.fetch_jobs(&mut exec_opts).await?;
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].id, pending_job_id);
+
+    // the summary is left in the jobs table, and the full details are in the archive
+    let archived = storage.fetch_archived_jobs(None, &mut exec_opts).await?;
+    assert_eq!(archived.len(), 1);
+    assert_eq!(archived[0].id, errored_job_id);
+    assert_eq!(archived[0].status, JobStatus::Error);
+    assert_eq!(archived[0].job_type.as_deref(), Some("export_users"));
+    assert_eq!(archived[0].error.as_deref(), Some("Error serializing X at line Y"));
+
+    let job = storage.fetch_job(errored_job_id, &mut exec_opts).await?;
+    assert!(job.details.get("export_destination").is_none());
+    let details = storage.fetch_job_archive(errored_job_id, &mut exec_opts).await?;
+    assert_eq!(
+        details.and_then(|d| d.get("export_destination").cloned()),
+        Some(serde_json::json!("google_workspace"))
+    );
+    assert!(storage.fetch_job_archive(pending_job_id, &mut exec_opts).await?.is_none());
+
+    // archived jobs can be listed by project cycle
+    let project_cycle_id = uuid!("76ed64a0-d88f-4148-9b02-331ea888d5d1");
+    let archived = storage.fetch_archived_jobs(Some(project_cycle_id), &mut exec_opts).await?;
+    assert_eq!(archived.len(), 1);
+    let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
+    assert!(storage.fetch_archived_jobs(Some(project_cycle_id), &mut exec_opts).await?.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::logins::{QueryLogins, RecordWorkspaceLogin};
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_workspace_logins(pool: PgPool) -> Result<()> {
+    let storage = PgBackend {
+        pool: pool.clone(),
+        replica: None,
+        metrics: Default::default(),
+        cache: None,
+        cipher: None,
+    };
+    let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id1 = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+    let volunteer_id2 = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+
+    sqlx::query(
+        "insert into volunteers_exported_to_workspace(volunteer_id, job_id, workspace_email, \
+         org_unit) values ($1, $3, 'rafaelnadal@developforgood.org', '/'), ($2, $3, \
+         'rogerfederer@developforgood.org', '/')",
+    )
+    .bind(volunteer_id1)
+    .bind(volunteer_id2)
+    .bind(job_id)
+    .execute(&pool)
+    .await?;
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    // accounts that have not been checked are listed, but are not known to be unactivated
+    let logins = storage.fetch_workspace_logins(project_cycle_id, false, &mut exec_opts).await?;
+    assert_eq!(logins.len(), 2);
+    assert!(logins.iter().all(|l| l.checked_at.is_none()));
+    assert!(storage
+        .fetch_workspace_logins(project_cycle_id, true, &mut exec_opts)
+        .await?
+        .is_empty());
+
+    let signed_in_at = "2026-10-15T18:30:00Z".parse::<DateTime<Utc>>()?;
+    storage
+        .record_workspace_logins(
+            vec![
+                RecordWorkspaceLogin {
+                    volunteer_id: volunteer_id1,
+                    workspace_email: "rafaelnadal@developforgood.org".to_owned(),
+                    last_login_at: Some(signed_in_at),
+                },
+                RecordWorkspaceLogin {
+                    volunteer_id: volunteer_id2,
+                    workspace_email: "rogerfederer@developforgood.org".to_owned(),
+                    last_login_at: None,
+                },
+            ],
+            &mut exec_opts,
+        )
+        .await?;
+
+    let never_activated =
+        storage.fetch_workspace_logins(project_cycle_id, true, &mut exec_opts).await?;
+    assert_eq!(never_activated.len(), 1);
+    assert_eq!(never_activated[0].volunteer_id, volunteer_id2);
+    assert!(never_activated[0].checked_at.is_some());
+
+    // a check that comes back without a sign in keeps the last one seen
+    storage
+        .record_workspace_logins(
+            vec![RecordWorkspaceLogin {
+                volunteer_id: volunteer_id1,
+                workspace_email: "rafaelnadal@developforgood.org".to_owned(),
+                last_login_at: None,
+            }],
+            &mut exec_opts,
+        )
+        .await?;
+
+    let logins = storage.fetch_workspace_logins(project_cycle_id, false, &mut exec_opts).await?;
+    let nadal = logins.iter().find(|l| l.volunteer_id == volunteer_id1).unwrap();
+    assert_eq!(nadal.last_login_at, Some(signed_in_at));
+
+    Ok(())
+}
@@ -10,7 +10,8 @@ use crate::services::storage::{ExecOptsBuilder, PgBackend};
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_create_mentor(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
     let data = CreateMentorBuilder::default()
         .first_name("Martina")
@@ -41,7 +42,8 @@ pub async fn test_create_mentor(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_batch_create_mentors(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
     let data = vec![
         CreateMentorBuilder::default()
@@ -90,7 +92,8 @@ pub async fn test_batch_create_mentors(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_fetch_mentors(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let mentors = storage.fetch_mentors(&mut ExecOptsBuilder::default().build()?).await?;
     dbg!(mentors);
     Ok(())
@@ -98,7 +101,8 @@ pub async fn test_fetch_mentors(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_fetch_mentor_by_id(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let mentor_id = uuid!("fa8377c8-1c0d-4f4e-9a2b-2c29f2737e0d");
     let mentor =
         storage.fetch_mentor_by_id(mentor_id, &mut ExecOptsBuilder::default().build()?).await?;
@@ -108,7 +112,8 @@ pub async fn test_fetch_mentor_by_id(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_edit_mentor(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let mentor_id = uuid!("fa8377c8-1c0d-4f4e-9a2b-2c29f2737e0d");
     let data = EditMentorBuilder::default()
         .first_name("John")
@@ -124,7 +129,8 @@ pub async fn test_edit_mentor(pool: PgPool) -> Result<()> {
 #[sqlx::test(fixtures("setup"))]
 
 pub async fn test_delete_mentor(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let mentor_id = uuid!("fa8377c8-1c0d-4f4e-9a2b-2c29f2737e0d");
     storage.delete_mentor(mentor_id, &mut ExecOptsBuilder::default().build()?).await?;
     Ok(())
@@ -0,0 +1,31 @@
+use anyhow::Result;
+use sqlx::PgPool;
+
+use crate::services::storage::{Migrator, PgBackend, MIGRATIONS};
+
+#[sqlx::test(migrations = false)]
+pub async fn test_migration_status(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+
+    let status = storage.migration_status().await?;
+    assert!(!status.is_empty());
+    assert!(status.iter().all(|m| !m.applied));
+
+    // migrations are listed once each, in the order they run
+    assert!(status.windows(2).all(|w| w[0].version < w[1].version));
+    assert_eq!(
+        status.len(),
+        MIGRATIONS.iter().filter(|m| !m.migration_type.is_down_migration()).count()
+    );
+
+    storage.migrate().await?;
+
+    let status = storage.migration_status().await?;
+    assert!(status.iter().all(|m| m.applied));
+
+    // running them again does nothing
+    storage.migrate().await?;
+
+    Ok(())
+}
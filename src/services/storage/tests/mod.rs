@@ -1,5 +1,34 @@
+mod audit;
+mod backups;
+mod cache;
 mod cycles;
+mod deletions;
+mod dependencies;
+mod drives;
+mod emails;
+mod encryption;
+mod events;
+mod exports;
 mod jobs;
+mod logins;
 mod mentors;
+mod migrations;
 mod nonprofits;
+mod organizations;
+mod outbox;
+mod pool;
+mod queue;
+mod reminders;
+mod retention;
+mod schedules;
+mod seed;
+mod settings;
+mod snapshots;
+mod sqlite;
+mod stats;
+mod suspensions;
+mod templates;
+mod timing;
+mod transfers;
 mod volunteers;
+mod webhooks;
@@ -10,7 +10,8 @@ use crate::services::storage::{Acquire, ExecOptsBuilder, PgBackend};
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_create_nonprofit(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let project_cycle_id = uuid!("76ed64a0-d88f-4148-9b02-331ea888d5d1");
     let data = CreateNonprofitBuilder::default()
         .representative_first_name("Venus")
@@ -41,7 +42,8 @@ pub async fn test_create_nonprofit(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_batch_create_nonprofits(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let project_cycle_id = uuid!("76ed64a0-d88f-4148-9b02-331ea888d5d1");
 
     let data = vec![
@@ -85,7 +87,8 @@ pub async fn test_batch_create_nonprofits(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_fetch_nonprofits(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
 
     let nonprofits = storage.fetch_nonprofits(&mut ExecOptsBuilder::default().build()?).await?;
     dbg!(&nonprofits);
@@ -95,7 +98,8 @@ pub async fn test_fetch_nonprofits(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_fetch_nonprofit_by_id(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let nonprofit_id = uuid!("bb9b7fa5-7283-4b73-82e1-c7244e47421d");
     let fake_nonprofit_id = Uuid::default();
 
@@ -115,7 +119,8 @@ pub async fn test_fetch_nonprofit_by_id(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_fetch_nonprofit_by_org_name(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let org_name = "AgassiOrg";
     let fake_org_name = "LaverOrg";
 
@@ -133,7 +138,8 @@ pub async fn test_fetch_nonprofit_by_org_name(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_edit_nonprofit(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let nonprofit_id = uuid!("bb9b7fa5-7283-4b73-82e1-c7244e47421d");
 
     let data = EditNonprofitBuilder::default()
@@ -151,7 +157,8 @@ pub async fn test_edit_nonprofit(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_delete_nonprofit(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let nonprofit_id = uuid!("bb9b7fa5-7283-4b73-82e1-c7244e47421d");
     let mut exec_opts = ExecOptsBuilder::default().build()?;
 
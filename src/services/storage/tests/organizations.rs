@@ -0,0 +1,84 @@
+use anyhow::Result;
+use sqlx::{postgres::PgPoolOptions, Executor, PgPool};
+
+use crate::services::storage::{
+    cycles::{CreateCycleBuilder, QueryCycles},
+    organizations::{with_org, CreateOrganizationBuilder, QueryOrganizations, DEFAULT_ORG_ID},
+    ExecOptsBuilder, PgBackend,
+};
+
+/// Connect a pool that runs as a role row level security applies to, since the test database is
+/// owned by a superuser that bypasses it.
+async fn tenant_pool(pool: &PgPool) -> Result<PgPool> {
+    pool.execute(
+        "do $$ begin
+            create role scipio_tenant;
+        exception when duplicate_object then null;
+        end $$",
+    )
+    .await?;
+    pool.execute(
+        "grant usage on schema public to scipio_tenant;
+        grant all on all tables in schema public to scipio_tenant;
+        grant all on all sequences in schema public to scipio_tenant",
+    )
+    .await?;
+
+    let tenant = PgPoolOptions::new()
+        .after_connect(|conn, _| {
+            Box::pin(async move {
+                conn.execute("set role scipio_tenant").await?;
+                Ok(())
+            })
+        })
+        .connect_with((*pool.connect_options()).clone())
+        .await?;
+    Ok(tenant)
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_fetch_organizations(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let data = CreateOrganizationBuilder::default().name("Second Nonprofit").build()?;
+    let id = storage.create_organization(data, &mut ExecOptsBuilder::default().build()?).await?;
+
+    let organizations =
+        storage.fetch_organizations(&mut ExecOptsBuilder::default().build()?).await?;
+    let ids = organizations.iter().map(|org| org.id).collect::<Vec<_>>();
+    assert_eq!(ids, vec![DEFAULT_ORG_ID, id]);
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_isolate_organizations(pool: PgPool) -> Result<()> {
+    let tenant = tenant_pool(&pool).await?;
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let data = CreateOrganizationBuilder::default().name("Second Nonprofit").build()?;
+    let org_id =
+        storage.create_organization(data, &mut ExecOptsBuilder::default().build()?).await?;
+
+    let storage = PgBackend { pool: tenant, ..storage };
+    let data = CreateCycleBuilder::default()
+        .name("Isolated")
+        .description("Description of test cycle")
+        .build()?;
+    let id = with_org(org_id, async {
+        storage.create_cycle(data, &mut ExecOptsBuilder::default().build()?).await
+    })
+    .await?;
+
+    // The default organization cannot see the other organization's cycle
+    let cycles = storage.fetch_cycles(&mut ExecOptsBuilder::default().build()?).await?;
+    assert!(!cycles.is_empty());
+    assert!(cycles.iter().all(|cycle| cycle.id != id));
+
+    // And the other organization sees only its own
+    let cycles = with_org(org_id, async {
+        storage.fetch_cycles(&mut ExecOptsBuilder::default().build()?).await
+    })
+    .await?;
+    assert_eq!(cycles.iter().map(|cycle| cycle.id).collect::<Vec<_>>(), vec![id]);
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use anyhow::Result;
+use chrono::{TimeDelta, Utc};
+use sqlx::PgPool;
+use uuid::{uuid, Uuid};
+
+use crate::services::storage::outbox::{CreateOutboxEmailBuilder, QueryOutbox};
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_email_outbox(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let volunteer_id = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+
+    let email = CreateOutboxEmailBuilder::default()
+        .job_id(job_id)
+        .volunteer_id(volunteer_id)
+        .first_name("Rafael")
+        .last_name("Nadal")
+        .email("rafaelnadal@gmail.com")
+        .workspace_email("rafaelnadal@developforgood.org")
+        .temporary_password(Some("password123".to_owned()))
+        .locale("es")
+        .build()?;
+    let id = email.id;
+
+    let export = Uuid::new_v4();
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    storage
+        .enqueue_outbox_emails(
+            vec![email],
+            export,
+            Utc::now() + TimeDelta::minutes(30),
+            &mut exec_opts,
+        )
+        .await?;
+
+    // the export still holds its claim, so the relay cannot take the email
+    let relay = Uuid::new_v4();
+    let claimed = storage
+        .claim_outbox_emails(relay, None, 10, Utc::now() + TimeDelta::minutes(30), &mut exec_opts)
+        .await?;
+    assert!(claimed.is_empty());
+
+    // the export's claim runs out, so the relay takes the email over
+    let expired = Utc::now() - TimeDelta::minutes(1);
+    assert_eq!(storage.renew_outbox_claims(export, vec![id], expired, &mut exec_opts).await?, [id]);
+    let claimed = storage
+        .claim_outbox_emails(
+            relay,
+            Some(job_id),
+            10,
+            Utc::now() + TimeDelta::minutes(30),
+            &mut exec_opts,
+        )
+        .await?;
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].id, id);
+    assert_eq!(claimed[0].temporary_password.as_deref(), Some("password123"));
+    assert_eq!(claimed[0].claimed_by, Some(relay));
+
+    // the export can no longer renew or send it
+    let later = Utc::now() + TimeDelta::minutes(30);
+    assert!(storage.renew_outbox_claims(export, vec![id], later, &mut exec_opts).await?.is_empty());
+    assert!(!storage.delete_outbox_email(id, export, &mut exec_opts).await?);
+
+    assert!(storage.delete_outbox_email(id, relay, &mut exec_opts).await?);
+    assert!(!storage.delete_outbox_email(id, relay, &mut exec_opts).await?);
+
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use anyhow::Result;
+use sqlx::PgPool;
+
+use crate::services::storage::{pool::PoolMonitor, Acquire, PgBackend};
+
+#[sqlx::test]
+pub async fn test_pool_stats(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+
+    let tx = storage.acquire().await?;
+    let stats = storage.pool_stats();
+    assert_eq!(stats.primary.acquired, 1);
+    assert_eq!(stats.primary.failed, 0);
+    assert!(stats.primary.in_use >= 1);
+    assert!(stats.replica.is_none());
+    tx.commit().await?;
+
+    let tx = storage.acquire_read().await?;
+    tx.commit().await?;
+    assert_eq!(storage.pool_stats().primary.acquired, 2);
+    Ok(())
+}
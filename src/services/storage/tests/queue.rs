@@ -0,0 +1,232 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::{uuid, Uuid};
+
+use crate::services::storage::jobs::{is_job_conflict, QueryJobs};
+use crate::services::storage::queue::{AttemptError, QueryQueue};
+use crate::services::storage::types::{JobPriority, JobStatus};
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_job_queue(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let worker = Uuid::new_v4();
+    let other_worker = Uuid::new_v4();
+
+    assert!(storage.claim_queued_job(worker, Utc::now(), &mut exec_opts).await?.is_none());
+
+    assert!(
+        storage
+            .enqueue_job(
+                job_id,
+                json!({ "kind": "test" }),
+                JobPriority::Normal,
+                None,
+                &mut exec_opts
+            )
+            .await?
+    );
+    // a job that is already waiting is not queued twice
+    assert!(
+        !storage
+            .enqueue_job(
+                job_id,
+                json!({ "kind": "test" }),
+                JobPriority::Normal,
+                None,
+                &mut exec_opts
+            )
+            .await?
+    );
+
+    let claimed_until = Utc::now() + Duration::minutes(5);
+    let claimed = storage.claim_queued_job(worker, claimed_until, &mut exec_opts).await?;
+    let claimed = claimed.expect("the queued job should be claimed");
+    assert_eq!(claimed.job_id, job_id);
+    assert_eq!(claimed.attempts, 1);
+    assert_eq!(claimed.claimed_by, Some(worker));
+    assert_eq!(claimed.payload, json!({ "kind": "test" }));
+
+    // a job another worker holds a claim on is not claimed
+    assert!(storage.claim_queued_job(other_worker, claimed_until, &mut exec_opts).await?.is_none());
+    assert!(
+        !storage
+            .renew_queued_job_claim(job_id, other_worker, claimed_until, &mut exec_opts)
+            .await?
+    );
+
+    // once its claim runs out, as if its worker stopped, another worker claims it
+    assert!(storage.renew_queued_job_claim(job_id, worker, Utc::now(), &mut exec_opts).await?);
+    let reclaimed = storage.claim_queued_job(other_worker, claimed_until, &mut exec_opts).await?;
+    assert_eq!(reclaimed.map(|job| job.attempts), Some(2));
+    assert!(!storage.finish_queued_job(job_id, worker, None, &mut exec_opts).await?);
+
+    assert!(
+        storage
+            .finish_queued_job(job_id, other_worker, Some("failed".to_owned()), &mut exec_opts)
+            .await?
+    );
+    let finished = storage.fetch_queued_job(job_id, &mut exec_opts).await?.unwrap();
+    assert!(finished.finished_at.is_some());
+    assert_eq!(finished.error.as_deref(), Some("failed"));
+    assert!(storage.claim_queued_job(worker, claimed_until, &mut exec_opts).await?.is_none());
+
+    // a finished job can be queued again, starting its attempts over
+    assert!(
+        storage
+            .enqueue_job(
+                job_id,
+                json!({ "kind": "retry" }),
+                JobPriority::Normal,
+                None,
+                &mut exec_opts
+            )
+            .await?
+    );
+    let requeued = storage.fetch_queued_job(job_id, &mut exec_opts).await?.unwrap();
+    assert_eq!(requeued.attempts, 0);
+    assert!(requeued.finished_at.is_none());
+    assert!(requeued.error.is_none());
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_job_queue_dead_letters(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let worker = Uuid::new_v4();
+    let claimed_until = Utc::now() + Duration::minutes(5);
+
+    assert!(
+        storage
+            .enqueue_job(
+                job_id,
+                json!({ "kind": "test" }),
+                JobPriority::Normal,
+                None,
+                &mut exec_opts
+            )
+            .await?
+    );
+    storage.claim_queued_job(worker, claimed_until, &mut exec_opts).await?.unwrap();
+
+    // a job that failed is released to be claimed again once its backoff is over
+    let error = AttemptError::new(1, &anyhow::anyhow!("timed out").context("export failed"));
+    assert_eq!(error.errors, vec!["export failed", "timed out"]);
+    assert!(storage.retry_queued_job(job_id, worker, Utc::now(), error, &mut exec_opts).await?);
+    assert!(!storage.finish_queued_job(job_id, worker, None, &mut exec_opts).await?);
+
+    let waiting = storage.fetch_queued_job(job_id, &mut exec_opts).await?.unwrap();
+    assert!(waiting.claimed_by.is_none());
+    assert!(waiting.finished_at.is_none());
+    assert_eq!(waiting.error.as_deref(), Some("export failed: timed out"));
+
+    // a job that failed on its last attempt is dead-lettered, keeping why every attempt failed
+    let retried = storage.claim_queued_job(worker, claimed_until, &mut exec_opts).await?;
+    assert_eq!(retried.map(|job| job.attempts), Some(2));
+    let error = AttemptError::new(2, &anyhow::anyhow!("failed again"));
+    assert!(storage.dead_letter_queued_job(job_id, worker, error, &mut exec_opts).await?);
+    storage.mark_job_dead_lettered(job_id, "failed again".to_owned(), &mut exec_opts).await?;
+    assert_eq!(storage.fetch_job_status(job_id, &mut exec_opts).await?, JobStatus::DeadLettered);
+
+    let dead_lettered = storage.fetch_queued_job(job_id, &mut exec_opts).await?.unwrap();
+    assert!(dead_lettered.finished_at.is_some());
+    assert!(dead_lettered.dead_lettered_at.is_some());
+    assert_eq!(dead_lettered.attempt_errors.as_array().map(Vec::len), Some(2));
+    assert_eq!(dead_lettered.attempt_errors[1]["errors"], json!(["failed again"]));
+    assert!(storage.claim_queued_job(worker, claimed_until, &mut exec_opts).await?.is_none());
+
+    // the job can't be finished once it was dead-lettered
+    let version = storage.fetch_job(job_id, &mut exec_opts).await?.version;
+    let err = storage.mark_job_complete(job_id, version, &mut exec_opts).await.unwrap_err();
+    assert!(is_job_conflict(&err));
+
+    // requeueing it starts its attempts over, but keeps why the earlier ones failed
+    assert!(storage.requeue_job(job_id, &mut exec_opts).await?);
+    assert!(storage.requeue_dead_lettered_job(job_id, &mut exec_opts).await?);
+    assert!(!storage.requeue_job(job_id, &mut exec_opts).await?);
+    assert!(!storage.requeue_dead_lettered_job(job_id, &mut exec_opts).await?);
+    assert_eq!(storage.fetch_job_status(job_id, &mut exec_opts).await?, JobStatus::Pending);
+
+    let requeued = storage.claim_queued_job(worker, claimed_until, &mut exec_opts).await?.unwrap();
+    assert_eq!(requeued.attempts, 1);
+    assert!(requeued.dead_lettered_at.is_none());
+    assert_eq!(requeued.attempt_errors.as_array().map(Vec::len), Some(2));
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_job_queue_priorities(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let cohort_export = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let urgent_export = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let worker = Uuid::new_v4();
+    let claimed_until = Utc::now() + Duration::minutes(5);
+
+    let payload = json!({ "kind": "test" });
+    assert!(
+        storage
+            .enqueue_job(cohort_export, payload.clone(), JobPriority::Low, None, &mut exec_opts)
+            .await?
+    );
+    assert!(
+        storage
+            .enqueue_job(urgent_export, payload, JobPriority::High, None, &mut exec_opts)
+            .await?
+    );
+
+    // the urgent job is claimed first, even though it was queued last
+    let claimed = storage.claim_queued_job(worker, claimed_until, &mut exec_opts).await?.unwrap();
+    assert_eq!(claimed.job_id, urgent_export);
+    assert_eq!(claimed.priority, JobPriority::High);
+
+    let claimed = storage.claim_queued_job(worker, claimed_until, &mut exec_opts).await?.unwrap();
+    assert_eq!(claimed.job_id, cohort_export);
+    assert_eq!(claimed.priority, JobPriority::Low);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_job_queue_run_at(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let worker = Uuid::new_v4();
+    let claimed_until = Utc::now() + Duration::minutes(5);
+
+    // a job scheduled for later is not claimed before then
+    let run_at = Utc::now() + Duration::days(1);
+    let payload = json!({ "kind": "test" });
+    assert!(
+        storage
+            .enqueue_job(job_id, payload, JobPriority::Normal, Some(run_at), &mut exec_opts)
+            .await?
+    );
+    let queued = storage.fetch_queued_job(job_id, &mut exec_opts).await?.unwrap();
+    assert_eq!(queued.run_at.timestamp_micros(), run_at.timestamp_micros());
+    assert!(storage.claim_queued_job(worker, claimed_until, &mut exec_opts).await?.is_none());
+
+    // until it is rescheduled to run now
+    assert!(storage.reschedule_queued_job(job_id, Utc::now(), &mut exec_opts).await?);
+    let claimed = storage.claim_queued_job(worker, claimed_until, &mut exec_opts).await?;
+    assert_eq!(claimed.map(|job| job.job_id), Some(job_id));
+
+    // a job a worker has started can no longer be rescheduled
+    assert!(!storage.reschedule_queued_job(job_id, run_at, &mut exec_opts).await?);
+    assert!(!storage.reschedule_queued_job(Uuid::new_v4(), run_at, &mut exec_opts).await?);
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::reminders::{
+    QueryReminders, RecordActivationReminder, RecordActivationReminderBuilder,
+    UpsertActivationReminderSettingsBuilder,
+};
+use crate::services::storage::types::ActivationReminderStatus;
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_activation_reminders(pool: PgPool) -> Result<()> {
+    let storage = PgBackend {
+        pool: pool.clone(),
+        replica: None,
+        metrics: Default::default(),
+        cache: None,
+        cipher: None,
+    };
+    let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id1 = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+    let volunteer_id2 = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+
+    sqlx::query(
+        "insert into volunteers_exported_to_workspace(created_at, volunteer_id, job_id, \
+         workspace_email, org_unit) values (now() - interval '10 days', $1, $3, \
+         'rafaelnadal@developforgood.org', '/'), (now() - interval '1 day', $2, $3, \
+         'rogerfederer@developforgood.org', '/')",
+    )
+    .bind(volunteer_id1)
+    .bind(volunteer_id2)
+    .bind(job_id)
+    .execute(&pool)
+    .await?;
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    // nobody is due until reminders are configured for the cycle
+    assert!(storage.fetch_due_activation_reminders(None, &mut exec_opts).await?.is_empty());
+    assert!(storage
+        .fetch_activation_reminder_settings(project_cycle_id, &mut exec_opts)
+        .await?
+        .is_none());
+
+    let settings = storage
+        .upsert_activation_reminder_settings(
+            project_cycle_id,
+            UpsertActivationReminderSettingsBuilder::default()
+                .remind_after_days(7)
+                .locale("es")
+                .principal("anish@developforgood.org")
+                .build()?,
+            &mut exec_opts,
+        )
+        .await?;
+    assert!(settings.enabled);
+    assert_eq!(settings.remind_after_days, 7);
+
+    // only the volunteer exported more than a week ago is due
+    let due =
+        storage.fetch_due_activation_reminders(Some(project_cycle_id), &mut exec_opts).await?;
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].volunteer_id, volunteer_id1);
+    assert_eq!(due[0].locale, "es");
+    assert_eq!(due[0].principal, "anish@developforgood.org");
+
+    let record = RecordActivationReminderBuilder::default()
+        .job_id(job_id)
+        .volunteer_id(volunteer_id1)
+        .workspace_email("rafaelnadal@developforgood.org")
+        .status(ActivationReminderStatus::Sent)
+        .build()?;
+    storage.record_activation_reminders(vec![record.clone()], &mut exec_opts).await?;
+
+    // recording the same volunteer again keeps the first record
+    storage
+        .record_activation_reminders(
+            vec![RecordActivationReminder { status: ActivationReminderStatus::Skipped, ..record }],
+            &mut exec_opts,
+        )
+        .await?;
+
+    let reminders = storage.fetch_activation_reminders(project_cycle_id, &mut exec_opts).await?;
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].status, ActivationReminderStatus::Sent);
+
+    // checked volunteers are no longer due
+    assert!(storage.fetch_due_activation_reminders(None, &mut exec_opts).await?.is_empty());
+
+    // disabling reminders stops the check
+    storage
+        .upsert_activation_reminder_settings(
+            project_cycle_id,
+            UpsertActivationReminderSettingsBuilder::default()
+                .enabled(false)
+                .remind_after_days(1)
+                .locale("en")
+                .principal("anish@developforgood.org")
+                .build()?,
+            &mut exec_opts,
+        )
+        .await?;
+    assert!(storage.fetch_due_activation_reminders(None, &mut exec_opts).await?.is_empty());
+
+    Ok(())
+}
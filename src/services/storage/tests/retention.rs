@@ -0,0 +1,70 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::retention::QueryRetention;
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_purge_exported_volunteers(pool: PgPool) -> Result<()> {
+    let storage = PgBackend {
+        pool: pool.clone(),
+        replica: None,
+        metrics: Default::default(),
+        cache: None,
+        cipher: None,
+    };
+    let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+
+    sqlx::query(
+        "insert into volunteers_exported_to_workspace(volunteer_id, job_id, workspace_email, \
+         org_unit) values ($1, $2, 'rafaelnadal@developforgood.org', '/')",
+    )
+    .bind(volunteer_id)
+    .bind(job_id)
+    .execute(&pool)
+    .await?;
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    // the project cycle has not been archived, so nothing is past its retention period
+    let purged = storage.purge_exported_volunteers(Utc::now(), false, &mut exec_opts).await?;
+    assert_eq!(purged, 0);
+
+    sqlx::query("update project_cycles set archived = true where id = $1")
+        .bind(project_cycle_id)
+        .execute(&pool)
+        .await?;
+
+    // the cycle was archived after the cutoff
+    let purged = storage
+        .purge_exported_volunteers(Utc::now() - Duration::days(1), false, &mut exec_opts)
+        .await?;
+    assert_eq!(purged, 0);
+
+    // a dry run counts the exported volunteer without purging them
+    let cutoff = Utc::now() + Duration::days(1);
+    let purged = storage.purge_exported_volunteers(cutoff, true, &mut exec_opts).await?;
+    assert_eq!(purged, 1);
+    let remaining: i64 = sqlx::query_scalar("select count(*) from volunteers where id = $1")
+        .bind(volunteer_id)
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(remaining, 1);
+
+    // only the volunteer who was exported is purged
+    let purged = storage.purge_exported_volunteers(cutoff, false, &mut exec_opts).await?;
+    assert_eq!(purged, 1);
+    let remaining: Vec<uuid::Uuid> =
+        sqlx::query_scalar("select id from volunteers where project_cycle_id = $1")
+            .bind(project_cycle_id)
+            .fetch_all(&pool)
+            .await?;
+    assert!(!remaining.contains(&volunteer_id));
+    assert!(!remaining.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::schedules::{QuerySchedules, SaveJobSchedule};
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_job_schedules(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let now = Utc::now();
+
+    assert!(storage.fetch_job_schedule("reconciliation", &mut exec_opts).await?.is_none());
+
+    let data = SaveJobSchedule {
+        name: "reconciliation".to_owned(),
+        cron: "0 3 * * *".to_owned(),
+        enabled: true,
+        params: json!({ "orgUnit": "/Programs/PantheonUsers" }),
+        updated_by: Some("admin@developforgood.org".to_owned()),
+        next_run_at: now - Duration::minutes(1),
+    };
+    let saved = storage.save_job_schedule(data.clone(), &mut exec_opts).await?;
+    assert_eq!(saved.cron, "0 3 * * *");
+    assert!(saved.last_run_at.is_none());
+
+    let due = storage.fetch_due_job_schedules(now, &mut exec_opts).await?;
+    assert_eq!(due.len(), 1);
+    let scheduled_for = due[0].next_run_at;
+
+    // only one process claims a run
+    let next_run_at = now + Duration::days(1);
+    assert!(
+        storage
+            .claim_job_schedule_run("reconciliation", scheduled_for, next_run_at, &mut exec_opts)
+            .await?
+    );
+    assert!(
+        !storage
+            .claim_job_schedule_run("reconciliation", scheduled_for, next_run_at, &mut exec_opts)
+            .await?
+    );
+    assert!(storage.fetch_due_job_schedules(now, &mut exec_opts).await?.is_empty());
+
+    storage
+        .record_job_schedule_run(
+            "reconciliation",
+            Some(job_id),
+            Some("failed".to_owned()),
+            &mut exec_opts,
+        )
+        .await?;
+    let ran = storage.fetch_job_schedule("reconciliation", &mut exec_opts).await?.unwrap();
+    assert!(ran.last_run_at.is_some());
+    assert_eq!(ran.last_job_id, Some(job_id));
+    assert_eq!(ran.last_error.as_deref(), Some("failed"));
+
+    // changing the schedule keeps when it last ran, and a paused job is never due
+    let paused = SaveJobSchedule { enabled: false, next_run_at: now, ..data };
+    let paused = storage.save_job_schedule(paused, &mut exec_opts).await?;
+    assert_eq!(paused.last_run_at, ran.last_run_at);
+    assert!(storage.fetch_due_job_schedules(now, &mut exec_opts).await?.is_empty());
+
+    assert_eq!(storage.fetch_job_schedules(&mut exec_opts).await?.len(), 1);
+    assert!(storage.delete_job_schedule("reconciliation", &mut exec_opts).await?);
+    assert!(!storage.delete_job_schedule("reconciliation", &mut exec_opts).await?);
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use sqlx::PgPool;
+
+use crate::services::storage::exports::QueryExports;
+use crate::services::storage::jobs::QueryJobs;
+use crate::services::storage::seed::{seed_data, SeedOptions, SEED_WORKSPACE_DOMAIN};
+use crate::services::storage::types::{JobStatus, WorkspaceExportStatus};
+use crate::services::storage::volunteers::QueryVolunteers;
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test]
+pub async fn test_seed_data(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let options = SeedOptions { volunteers: 10, rng_seed: Some(7) };
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    let summary = seed_data(&storage, &options).await?;
+    assert_eq!(summary.volunteers, 10);
+    assert_eq!(summary.exported, 7);
+
+    let volunteers =
+        storage.fetch_volunteers_by_cycle(summary.project_cycle_id, &mut exec_opts).await?;
+    assert_eq!(volunteers.len(), 10);
+    assert!(volunteers.iter().all(|v| v.email.ends_with("@example.com")));
+
+    let exported = storage
+        .fetch_exported_volunteer_details_by_project_cycle(summary.project_cycle_id, &mut exec_opts)
+        .await?;
+    assert_eq!(exported.len(), 7);
+    assert!(exported.iter().all(|v| v.workspace_email.ends_with(SEED_WORKSPACE_DOMAIN)));
+
+    let [import, export, failed] = summary.job_ids[..] else {
+        panic!("expected three jobs, got {:?}", summary.job_ids);
+    };
+    assert_eq!(storage.fetch_job(import, &mut exec_opts).await?.status, JobStatus::Complete);
+    assert_eq!(storage.fetch_job(export, &mut exec_opts).await?.status, JobStatus::Complete);
+    assert_eq!(storage.fetch_job(failed, &mut exec_opts).await?.status, JobStatus::Error);
+
+    let checkpoints = storage.fetch_export_checkpoints(failed, &mut exec_opts).await?;
+    assert_eq!(checkpoints.len(), 2);
+    assert_eq!(checkpoints.iter().filter(|c| c.status == WorkspaceExportStatus::Failed).count(), 1);
+
+    // seeding again adds another cohort rather than conflicting with the first
+    let again = seed_data(&storage, &options).await?;
+    assert_ne!(again.project_cycle_id, summary.project_cycle_id);
+
+    assert!(seed_data(&storage, &SeedOptions { volunteers: 0, rng_seed: None }).await.is_err());
+
+    Ok(())
+}
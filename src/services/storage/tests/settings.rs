@@ -0,0 +1,41 @@
+use anyhow::Result;
+use serde_json::json;
+use sqlx::PgPool;
+
+use crate::services::storage::settings::QuerySettings;
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_settings(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    assert!(storage.fetch_setting("senderAddress", &mut exec_opts).await?.is_none());
+
+    let saved = storage
+        .save_setting("senderAddress", json!("noreply@developforgood.org"), &mut exec_opts)
+        .await?;
+    assert_eq!(saved.value, json!("noreply@developforgood.org"));
+    assert!(saved.updated_at.is_none());
+
+    // saving a setting again replaces its value
+    let saved = storage
+        .save_setting("senderAddress", json!("hello@developforgood.org"), &mut exec_opts)
+        .await?;
+    assert_eq!(saved.value, json!("hello@developforgood.org"));
+    assert!(saved.updated_at.is_some());
+
+    storage.save_setting("featureToggles", json!({ "sms": true }), &mut exec_opts).await?;
+    let settings = storage.fetch_settings(&mut exec_opts).await?;
+    assert_eq!(
+        settings.iter().map(|s| s.key.as_str()).collect::<Vec<&str>>(),
+        ["featureToggles", "senderAddress"]
+    );
+
+    assert!(storage.delete_setting("senderAddress", &mut exec_opts).await?);
+    assert!(!storage.delete_setting("senderAddress", &mut exec_opts).await?);
+    assert!(storage.fetch_setting("senderAddress", &mut exec_opts).await?.is_none());
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anyhow::Result;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::{uuid, Uuid};
+
+use crate::services::storage::snapshots::QuerySnapshots;
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_job_snapshots(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+
+    assert!(storage.fetch_job_snapshots(job_id, &mut exec_opts).await?.is_empty());
+
+    let first = storage
+        .save_job_snapshot(job_id, None, json!({ "status": "running" }), &mut exec_opts)
+        .await?;
+    let second = storage
+        .save_job_snapshot(
+            job_id,
+            Some("support@developforgood.org".to_owned()),
+            json!({ "status": "error" }),
+            &mut exec_opts,
+        )
+        .await?;
+
+    // listed newest first, without their contents
+    let snapshots = storage.fetch_job_snapshots(job_id, &mut exec_opts).await?;
+    assert_eq!(snapshots.iter().map(|s| s.id).collect::<Vec<_>>(), vec![second.id, first.id]);
+    assert_eq!(snapshots[0].created_by.as_deref(), Some("support@developforgood.org"));
+
+    let fetched = storage.fetch_job_snapshot(first.id, &mut exec_opts).await?;
+    assert_eq!(fetched, Some(first));
+
+    assert!(storage.fetch_job_snapshot(Uuid::new_v4(), &mut exec_opts).await?.is_none());
+
+    Ok(())
+}
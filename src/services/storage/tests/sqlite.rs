@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use sqlx::Sqlite;
+use uuid::uuid;
+
+use crate::services::storage::{
+    cycles::{CreateCycleBuilder, QueryCycles},
+    jobs::{is_job_conflict, CreateJob, QueryJobs, UpdateJobProgressBuilder, NEW_JOB_VERSION},
+    organizations::{with_org, QueryOrganizations, DEFAULT_ORG_ID},
+    sqlite::SqliteBackend,
+    types::{JobData, JobDetails, JobPhase, JobStatus, JobType},
+    ExecOptsBuilder, Migrator, StorageService,
+};
+
+async fn storage() -> Result<SqliteBackend> {
+    let storage = SqliteBackend::new("sqlite::memory:").await?;
+    storage.migrate().await?;
+    Ok(storage)
+}
+
+#[tokio::test]
+pub async fn test_sqlite_migration_status() -> Result<()> {
+    let storage = SqliteBackend::new("sqlite::memory:").await?;
+    assert!(storage.migration_status().await?.iter().all(|m| !m.applied));
+
+    storage.migrate().await?;
+    assert!(storage.migration_status().await?.iter().all(|m| m.applied));
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_sqlite_cycles() -> Result<()> {
+    let storage: Arc<dyn StorageService<Sqlite>> = Arc::new(storage().await?);
+    let data = CreateCycleBuilder::default()
+        .name("TestCycle")
+        .description("Description of test cycle")
+        .build()?;
+    let id = storage.create_cycle(data, &mut ExecOptsBuilder::default().build()?).await?;
+
+    let cycle = storage
+        .fetch_cycle_by_id(id, &mut ExecOptsBuilder::default().build()?)
+        .await?
+        .expect("the cycle was just created");
+    assert_eq!(cycle.name, "TestCycle");
+    assert!(!cycle.archived);
+
+    storage.delete_cycle(id, &mut ExecOptsBuilder::default().build()?).await?;
+    let cycles = storage.fetch_cycles(&mut ExecOptsBuilder::default().build()?).await?;
+    assert!(cycles.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_sqlite_jobs() -> Result<()> {
+    let storage = storage().await?;
+    let job_id = storage
+        .create_job(
+            None,
+            CreateJob {
+                label: "test".to_string(),
+                description: None,
+                data: JobDetails {
+                    job_type: JobType::AirtableImportBase,
+                    error: None,
+                    result: None,
+                    data: JobData::AirtableImportBase { base_id: "appS5z0uqz4l0IJvP".to_owned() },
+                },
+            },
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+
+    let progress = UpdateJobProgressBuilder::default()
+        .phase(JobPhase::Provisioning)
+        .processed(1)
+        .total(2)
+        .build()?;
+    storage.update_job_progress(job_id, progress, &mut ExecOptsBuilder::default().build()?).await?;
+    let progress = storage
+        .fetch_job_progress(job_id, &mut ExecOptsBuilder::default().build()?)
+        .await?
+        .expect("progress was just reported");
+    assert_eq!((progress.processed, progress.total), (1, 2));
+
+    storage
+        .mark_job_errored(
+            job_id,
+            NEW_JOB_VERSION,
+            "failed".to_owned(),
+            &mut ExecOptsBuilder::default().build()?,
+        )
+        .await?;
+    let job = storage.fetch_job(job_id, &mut ExecOptsBuilder::default().build()?).await?;
+    assert_eq!(job.status, JobStatus::Error);
+    assert!(job.updated_at.is_some());
+    let details = serde_json::from_value::<JobDetails>(job.details)?;
+    assert_eq!(details.error.as_deref(), Some("failed"));
+
+    let finished_again = storage
+        .mark_job_complete(job_id, job.version, &mut ExecOptsBuilder::default().build()?)
+        .await;
+    assert!(finished_again.is_err_and(|e| is_job_conflict(&e)));
+
+    storage.resume_job(job_id, job.version, &mut ExecOptsBuilder::default().build()?).await?;
+    storage
+        .mark_job_complete(job_id, job.version + 1, &mut ExecOptsBuilder::default().build()?)
+        .await?;
+    let job = storage.fetch_job(job_id, &mut ExecOptsBuilder::default().build()?).await?;
+    assert_eq!(job.status, JobStatus::Complete);
+    assert!(serde_json::from_value::<JobDetails>(job.details)?.error.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_sqlite_only_serves_default_org() -> Result<()> {
+    let storage = storage().await?;
+    let organizations =
+        storage.fetch_organizations(&mut ExecOptsBuilder::default().build()?).await?;
+    assert_eq!(organizations.iter().map(|org| org.id).collect::<Vec<_>>(), vec![DEFAULT_ORG_ID]);
+
+    let org_id = uuid!("5b6c0d1e-3f4a-4b8c-9d2e-7f1a2b3c4d5e");
+    let cycles = with_org(org_id, async {
+        storage.fetch_cycles(&mut ExecOptsBuilder::default().build()?).await
+    })
+    .await;
+    assert!(cycles.is_err());
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use anyhow::Result;
+use chrono::{TimeDelta, Utc};
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::emails::{QueryEmails, RecordEmailSendBuilder};
+use crate::services::storage::jobs::{CreateJob, QueryJobs, NEW_JOB_VERSION};
+use crate::services::storage::stats::QueryStats;
+use crate::services::storage::types::{
+    EmailSendStatus, ExportDesination, JobData, JobDetails, JobType,
+};
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_fetch_export_digest(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let export_job_id = storage
+        .create_job(
+            None,
+            CreateJob {
+                label: "Export Fall 2024".to_owned(),
+                description: None,
+                data: JobDetails {
+                    job_type: JobType::AirtableExportUsers,
+                    error: None,
+                    result: None,
+                    data: JobData::AirtableExportUsers {
+                        export_destination: ExportDesination::GoogleWorkspace,
+                    },
+                },
+            },
+            &mut exec_opts,
+        )
+        .await?;
+    storage
+        .mark_job_errored(
+            export_job_id,
+            NEW_JOB_VERSION,
+            "quota exceeded".to_owned(),
+            &mut exec_opts,
+        )
+        .await?;
+
+    for status in [EmailSendStatus::Sent, EmailSendStatus::Sent, EmailSendStatus::Failed] {
+        storage
+            .record_email_send(
+                RecordEmailSendBuilder::default()
+                    .job_id(Some(job_id))
+                    .recipient("rafaelnadal@gmail.com")
+                    .template("email/onboard.en.html")
+                    .status(status)
+                    .build()?,
+                &mut exec_opts,
+            )
+            .await?;
+    }
+
+    let since = Utc::now() - TimeDelta::hours(1);
+    let until = Utc::now() + TimeDelta::hours(1);
+    let digest = storage.fetch_export_digest(since, until, &mut exec_opts).await?;
+    assert_eq!(digest.jobs_run, 1);
+    assert_eq!(digest.jobs_completed, 0);
+    assert_eq!(digest.jobs_failed, 1);
+    assert_eq!(digest.emails_sent, 2);
+    assert_eq!(digest.emails_failed, 1);
+
+    let failed = storage.fetch_failed_export_jobs(since, until, &mut exec_opts).await?;
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].id, export_job_id);
+    assert_eq!(failed[0].details["error"], "quota exceeded");
+
+    // nothing happened before the period
+    let digest =
+        storage.fetch_export_digest(since - TimeDelta::days(1), since, &mut exec_opts).await?;
+    assert_eq!(digest.jobs_run + digest.emails_sent + digest.emails_failed, 0);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_fetch_last_email_sent_at(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    assert!(storage
+        .fetch_last_email_sent_at("email/digest".to_owned(), &mut exec_opts)
+        .await?
+        .is_none());
+
+    let sent = storage
+        .record_email_send(
+            RecordEmailSendBuilder::default()
+                .recipient("admin@developforgood.org")
+                .template("email/digest.en.html")
+                .status(EmailSendStatus::Sent)
+                .build()?,
+            &mut exec_opts,
+        )
+        .await?;
+    storage
+        .record_email_send(
+            RecordEmailSendBuilder::default()
+                .recipient("admin@developforgood.org")
+                .template("email/digest.en.html")
+                .status(EmailSendStatus::Failed)
+                .build()?,
+            &mut exec_opts,
+        )
+        .await?;
+
+    // failed attempts and other templates are not counted
+    let last = storage.fetch_last_email_sent_at("email/digest".to_owned(), &mut exec_opts).await?;
+    assert_eq!(last, Some(sent.created_at));
+    assert!(storage
+        .fetch_last_email_sent_at("email/dig".to_owned(), &mut exec_opts)
+        .await?
+        .is_none());
+
+    Ok(())
+}
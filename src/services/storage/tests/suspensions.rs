@@ -0,0 +1,90 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::suspensions::{QuerySuspensions, RecordWorkspaceSuspensionBuilder};
+use crate::services::storage::types::WorkspaceSuspensionAction;
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_workspace_suspensions(pool: PgPool) -> Result<()> {
+    let storage = PgBackend {
+        pool: pool.clone(),
+        replica: None,
+        metrics: Default::default(),
+        cache: None,
+        cipher: None,
+    };
+    let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id1 = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+    let volunteer_id2 = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+
+    sqlx::query(
+        "insert into volunteers_exported_to_workspace(volunteer_id, job_id, workspace_email, \
+         org_unit) values ($1, $3, 'rafaelnadal@developforgood.org', '/'), ($2, $3, \
+         'rogerfederer@developforgood.org', '/')",
+    )
+    .bind(volunteer_id1)
+    .bind(volunteer_id2)
+    .bind(job_id)
+    .execute(&pool)
+    .await?;
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    let candidates =
+        storage.fetch_suspension_candidates(project_cycle_id, None, &mut exec_opts).await?;
+    assert_eq!(candidates.len(), 2);
+
+    // only the requested volunteers are candidates
+    let candidates = storage
+        .fetch_suspension_candidates(project_cycle_id, Some(vec![volunteer_id2]), &mut exec_opts)
+        .await?;
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].volunteer_id, volunteer_id2);
+    assert_eq!(candidates[0].workspace_email, "rogerfederer@developforgood.org");
+
+    assert!(storage
+        .fetch_workspace_suspensions(project_cycle_id, &mut exec_opts)
+        .await?
+        .is_empty());
+
+    storage
+        .record_workspace_suspensions(
+            vec![
+                RecordWorkspaceSuspensionBuilder::default()
+                    .job_id(job_id)
+                    .volunteer_id(volunteer_id1)
+                    .workspace_email("rafaelnadal@developforgood.org")
+                    .action(WorkspaceSuspensionAction::Suspend)
+                    .succeeded(true)
+                    .principal("anish@developforgood.org")
+                    .build()?,
+                RecordWorkspaceSuspensionBuilder::default()
+                    .job_id(job_id)
+                    .volunteer_id(volunteer_id2)
+                    .workspace_email("rogerfederer@developforgood.org")
+                    .action(WorkspaceSuspensionAction::Suspend)
+                    .succeeded(false)
+                    .error(Some("User not found".to_owned()))
+                    .principal("anish@developforgood.org")
+                    .build()?,
+            ],
+            &mut exec_opts,
+        )
+        .await?;
+
+    let suspensions = storage.fetch_workspace_suspensions(project_cycle_id, &mut exec_opts).await?;
+    assert_eq!(suspensions.len(), 2);
+    assert!(suspensions.iter().all(|s| s.action == WorkspaceSuspensionAction::Suspend));
+
+    let failed = suspensions.iter().find(|s| s.volunteer_id == volunteer_id2).unwrap();
+    assert!(!failed.succeeded);
+    assert_eq!(failed.error.as_deref(), Some("User not found"));
+
+    // recording nothing is a no-op
+    storage.record_workspace_suspensions(vec![], &mut exec_opts).await?;
+
+    Ok(())
+}
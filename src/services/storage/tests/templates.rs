@@ -0,0 +1,47 @@
+use anyhow::Result;
+use sqlx::PgPool;
+
+use crate::services::storage::templates::{CreateEmailTemplateBuilder, QueryTemplates};
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test]
+pub async fn test_create_and_fetch_email_template_versions(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    assert!(storage.fetch_email_template("onboard", &mut exec_opts).await?.is_none());
+
+    for subject in ["Welcome", "Welcome to Develop for Good"] {
+        let data = CreateEmailTemplateBuilder::default()
+            .name("onboard")
+            .subject(subject)
+            .body("<p>Hi {{ name }}</p>")
+            .created_by("staff@developforgood.org")
+            .build()?;
+        storage.create_email_template(data, &mut exec_opts).await?;
+    }
+
+    let data = CreateEmailTemplateBuilder::default()
+        .name("reminder")
+        .subject("Reminder")
+        .body("<p>Don't forget</p>")
+        .created_by("staff@developforgood.org")
+        .build()?;
+    storage.create_email_template(data, &mut exec_opts).await?;
+
+    let template = storage.fetch_email_template("onboard", &mut exec_opts).await?.unwrap();
+    assert_eq!(template.version, 2);
+    assert_eq!(template.subject, "Welcome to Develop for Good");
+
+    let versions = storage.fetch_email_template_versions("onboard", &mut exec_opts).await?;
+    assert_eq!(versions.iter().map(|t| t.version).collect::<Vec<_>>(), vec![2, 1]);
+
+    let templates = storage.fetch_email_templates(&mut exec_opts).await?;
+    assert_eq!(templates.len(), 2);
+    assert_eq!(templates[0].name, "onboard");
+    assert_eq!(templates[0].version, 2);
+    assert_eq!(templates[1].name, "reminder");
+
+    Ok(())
+}
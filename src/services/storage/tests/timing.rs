@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::jobs::QueryJobs;
+use crate::services::storage::timing::{
+    operation_from_path, DurationBucket, QueryMonitor, QueryTimings,
+};
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[test]
+fn test_operation_from_path() {
+    let path = "scipio::services::storage::jobs::<impl scipio::services::storage::jobs::QueryJobs<\
+                sqlx_postgres::database::Postgres> for scipio::services::storage::PgBackend>::\
+                fetch_job::{{closure}}::f";
+    assert_eq!(operation_from_path(path), "fetch_job");
+    assert_eq!(operation_from_path("scipio::services::storage::PgBackend::archive::f"), "archive");
+}
+
+#[test]
+fn test_record_query_timings() {
+    let timings = QueryTimings::new(None);
+    timings.record("fetch_job", Duration::from_millis(3), true);
+    timings.record("fetch_job", Duration::from_millis(40), false);
+    timings.record("fetch_jobs", Duration::from_secs(20), true);
+
+    let stats = timings.stats();
+    let names: Vec<_> = stats.operations.iter().map(|op| op.name).collect();
+    assert_eq!(names, vec!["fetch_jobs", "fetch_job"]);
+
+    let fetch_job = &stats.operations[1];
+    assert_eq!((fetch_job.calls, fetch_job.failed), (2, 1));
+    assert_eq!(fetch_job.max_ms, 40.0);
+    assert_eq!(fetch_job.avg_ms, 21.5);
+    let counted: Vec<_> = fetch_job.buckets.iter().filter(|b| b.count > 0).collect();
+    assert_eq!(
+        counted,
+        vec![
+            &DurationBucket { le_ms: Some(5), count: 1 },
+            &DurationBucket { le_ms: Some(50), count: 1 }
+        ]
+    );
+
+    // calls slower than every bound are counted in the last bucket
+    let last = stats.operations[0].buckets.last().unwrap();
+    assert_eq!(last, &DurationBucket { le_ms: None, count: 1 });
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_storage_operations_are_timed(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+
+    storage.fetch_job(job_id, &mut ExecOptsBuilder::default().build()?).await?;
+    storage.fetch_job(job_id, &mut ExecOptsBuilder::default().build()?).await?;
+    storage.fetch_jobs(&mut ExecOptsBuilder::default().build()?).await?;
+
+    let timings = storage.query_timings();
+    let fetch_job = timings.operations.iter().find(|op| op.name == "fetch_job").unwrap();
+    assert_eq!((fetch_job.calls, fetch_job.failed), (2, 0));
+    assert!(timings.operations.iter().any(|op| op.name == "fetch_jobs"));
+
+    Ok(())
+}
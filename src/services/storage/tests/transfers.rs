@@ -0,0 +1,72 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use uuid::uuid;
+
+use crate::services::storage::transfers::{CreateDriveTransferBuilder, QueryTransfers};
+use crate::services::storage::types::WorkspaceTransferStatus;
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_drive_transfers(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id1 = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+    let volunteer_id2 = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    let transfer = |volunteer_id, workspace_email: &str| {
+        CreateDriveTransferBuilder::default()
+            .job_id(job_id)
+            .volunteer_id(volunteer_id)
+            .workspace_email(workspace_email)
+            .archive_email("archive@developforgood.org")
+            .principal("anish@developforgood.org")
+            .build()
+    };
+
+    let created = storage
+        .create_drive_transfers(
+            vec![
+                transfer(volunteer_id1, "rafaelnadal@developforgood.org")?,
+                transfer(volunteer_id2, "rogerfederer@developforgood.org")?,
+            ],
+            &mut exec_opts,
+        )
+        .await?;
+    assert_eq!(created.len(), 2);
+    assert!(created.iter().all(|t| t.status == WorkspaceTransferStatus::Pending));
+    assert!(created.iter().all(|t| t.transfer_id.is_none() && !t.suspended));
+
+    let nadal = created.iter().find(|t| t.volunteer_id == volunteer_id1).unwrap();
+    let federer = created.iter().find(|t| t.volunteer_id == volunteer_id2).unwrap();
+
+    storage.start_drive_transfer(nadal.id, "transfer-1".to_owned(), &mut exec_opts).await?;
+    storage.complete_drive_transfer(nadal.id, &mut exec_opts).await?;
+    storage.mark_drive_transfers_suspended(vec![nadal.id], &mut exec_opts).await?;
+    storage
+        .fail_drive_transfer(federer.id, "User does not exist".to_owned(), &mut exec_opts)
+        .await?;
+
+    let transfers = storage.fetch_drive_transfers(project_cycle_id, &mut exec_opts).await?;
+    assert_eq!(transfers.len(), 2);
+
+    let nadal = transfers.iter().find(|t| t.volunteer_id == volunteer_id1).unwrap();
+    assert_eq!(nadal.status, WorkspaceTransferStatus::Completed);
+    assert_eq!(nadal.transfer_id.as_deref(), Some("transfer-1"));
+    assert!(nadal.suspended);
+    assert!(nadal.error.is_none());
+
+    let federer = transfers.iter().find(|t| t.volunteer_id == volunteer_id2).unwrap();
+    assert_eq!(federer.status, WorkspaceTransferStatus::Failed);
+    assert_eq!(federer.error.as_deref(), Some("User does not exist"));
+    assert!(!federer.suspended);
+
+    // transfers of volunteers in other project cycles are not fetched
+    let other_cycle = uuid!("76ed64a0-d88f-4148-9b02-331ea888d5d1");
+    assert!(storage.fetch_drive_transfers(other_cycle, &mut exec_opts).await?.is_empty());
+
+    Ok(())
+}
@@ -1,19 +1,22 @@
 use anyhow::Result;
+use chrono::{Duration, TimeZone, Utc};
 use sqlx::PgPool;
-use uuid::uuid;
+use uuid::{uuid, Uuid};
 
 use crate::services::storage::types::{
     AgeRange, Ethnicity, Fli, Gender, Lgbt, StudentStage, VolunteerHearAbout,
 };
 use crate::services::storage::volunteers::{
-    CreateVolunteerBuilder, EditVolunteerBuilder, InsertVolunteerExportedToWorkspaceBuilder,
-    QueryVolunteers,
+    CreateVolunteerBuilder, EditVolunteerBuilder, ExportedVolunteerCursor, ExportedVolunteerFilter,
+    InsertVolunteerExportedToWorkspaceBuilder, InsertWorkspaceAliasBuilder, QueryVolunteers,
+    UpdateVolunteerBuilder, VolunteerCursor,
 };
 use crate::services::storage::{Acquire, ExecOptsBuilder, PgBackend};
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_create_volunteer(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
     let data = CreateVolunteerBuilder::default()
         .first_name("Carlos")
@@ -45,7 +48,8 @@ pub async fn test_create_volunteer(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_batch_create_volunteers(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
 
     let data = vec![
@@ -93,7 +97,8 @@ pub async fn test_batch_create_volunteers(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_fetch_volunteers(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
 
     let mut exec_opts = ExecOptsBuilder::default().build()?;
     let volunteers = storage.fetch_volunteers(&mut exec_opts).await?;
@@ -105,7 +110,8 @@ pub async fn test_fetch_volunteers(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_fetch_volunteer_by_id(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let test_volunteer_id = uuid!("5e7b3f35-2b84-46e7-8b7b-73e2716d42c9");
 
     let mut exec_opts = ExecOptsBuilder::default().build()?;
@@ -118,7 +124,8 @@ pub async fn test_fetch_volunteer_by_id(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_fetch_volunteer_by_email(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let test_volunteer_email = "novak.djokovic@gmail.com";
 
     let mut exec_opts = ExecOptsBuilder::default().build()?;
@@ -132,7 +139,8 @@ pub async fn test_fetch_volunteer_by_email(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn edit_volunteer(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
     let test_volunteer_id = uuid!("5e7b3f35-2b84-46e7-8b7b-73e2716d42c9");
     let data = EditVolunteerBuilder::default().email("novak.djokovic@gmail.com").build()?;
 
@@ -143,9 +151,79 @@ pub async fn edit_volunteer(pool: PgPool) -> Result<()> {
     Ok(())
 }
 
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_batch_update_volunteers(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let rafael_id = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+    let roger_id = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+    let missing_id = uuid!("c2d1a9e4-6b0f-4f8e-9a31-5d7c2e8b4f10");
+    let fall_cycle_id = uuid!("76ed64a0-d88f-4148-9b02-331ea888d5d1");
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let updated = storage
+        .batch_update_volunteers(
+            vec![
+                UpdateVolunteerBuilder::default()
+                    .id(rafael_id)
+                    .email("rafa.nadal@gmail.com")
+                    .first_name("Rafa")
+                    .build()?,
+                UpdateVolunteerBuilder::default()
+                    .id(roger_id)
+                    .project_cycle_id(fall_cycle_id)
+                    .build()?,
+                UpdateVolunteerBuilder::default().id(missing_id).last_name("Nobody").build()?,
+            ],
+            &mut exec_opts,
+        )
+        .await?;
+    assert_eq!(updated, vec![rafael_id, roger_id]);
+
+    // only the fields that are set are changed
+    let rafael = storage.fetch_volunteer_by_id(rafael_id, &mut exec_opts).await?.unwrap();
+    assert_eq!(
+        (rafael.first_name.as_str(), rafael.last_name.as_str(), rafael.email.as_str()),
+        ("Rafa", "Nadal", "rafa.nadal@gmail.com")
+    );
+
+    // a volunteer moved to another cohort is unlinked from their old one
+    let roger = storage.fetch_volunteer_by_id(roger_id, &mut exec_opts).await?.unwrap();
+    assert_eq!(roger.project_cycle_id, fall_cycle_id);
+    assert_eq!(roger.first_name, "Roger");
+    let links = sqlx::query_scalar::<_, i64>(
+        "select (select count(*) from client_volunteers where volunteer_id = $1)
+           + (select count(*) from volunteer_mentors where volunteer_id = $1)",
+    )
+    .bind(roger_id)
+    .fetch_one(&storage.pool)
+    .await?;
+    assert_eq!(links, 0);
+
+    // a recovery email taken by another volunteer is rejected, and nothing in the batch is changed
+    let taken = storage
+        .batch_update_volunteers(
+            vec![
+                UpdateVolunteerBuilder::default().id(roger_id).last_name("Fed").build()?,
+                UpdateVolunteerBuilder::default()
+                    .id(rafael_id)
+                    .email("novak.djokovic@gmail.com")
+                    .build()?,
+            ],
+            &mut exec_opts,
+        )
+        .await;
+    assert!(taken.is_err());
+    let roger = storage.fetch_volunteer_by_id(roger_id, &mut exec_opts).await?.unwrap();
+    assert_eq!(roger.last_name, "Federer");
+
+    Ok(())
+}
+
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_link_volunteers_to_nonprofits(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
 
     let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
 
@@ -169,7 +247,8 @@ pub async fn test_link_volunteers_to_nonprofits(pool: PgPool) -> Result<()> {
 
 #[sqlx::test(fixtures("setup"))]
 pub async fn test_link_volunteers_to_mentors(pool: PgPool) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
 
     let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
 
@@ -194,7 +273,8 @@ pub async fn test_link_volunteers_to_mentors(pool: PgPool) -> Result<()> {
 pub async fn test_batch_insert_and_remove_volunteers_exported_to_workspace(
     pool: PgPool,
 ) -> Result<()> {
-    let storage = PgBackend { pool };
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
 
     let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
 
@@ -231,3 +311,464 @@ pub async fn test_batch_insert_and_remove_volunteers_exported_to_workspace(
 
     Ok(())
 }
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_rerun_batch_insert_volunteers_exported_to_workspace(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let rerun_job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let volunteer_id = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+    let record = |job_id: Uuid, org_unit: &str| {
+        InsertVolunteerExportedToWorkspaceBuilder::default()
+            .job_id(job_id)
+            .volunteer_id(volunteer_id)
+            .workspace_email("roger.federer@developforgood.org")
+            .org_unit(org_unit)
+            .build()
+    };
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    storage
+        .batch_insert_volunteers_exported_to_workspace(
+            vec![record(job_id, "/Programs/PantheonUsers")?],
+            &mut exec_opts,
+        )
+        .await?;
+
+    // re-running the export updates the record rather than failing on the duplicate, even when
+    // the same account appears twice in a batch
+    storage
+        .batch_insert_volunteers_exported_to_workspace(
+            vec![record(rerun_job_id, "/Programs")?, record(rerun_job_id, "/Programs/Mentors")?],
+            &mut exec_opts,
+        )
+        .await?;
+
+    let exported =
+        storage.fetch_exported_volunteer_details_by_ids(vec![volunteer_id], &mut exec_opts).await?;
+    assert_eq!(exported.len(), 1);
+    assert_eq!(exported[0].job_id, rerun_job_id);
+    assert_eq!(exported[0].org_unit, "/Programs/Mentors");
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_fetch_exported_volunteer_details_by_ids(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let exported_volunteer_id = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+    let other_volunteer_id = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+
+    let data = vec![InsertVolunteerExportedToWorkspaceBuilder::default()
+        .job_id(job_id)
+        .volunteer_id(exported_volunteer_id)
+        .workspace_email("rogerfederer@developforgood.org")
+        .org_unit("/Programs/PantheonUsers")
+        .build()?];
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    storage.batch_insert_volunteers_exported_to_workspace(data, &mut exec_opts).await?;
+
+    let exported = storage
+        .fetch_exported_volunteer_details_by_ids(
+            vec![exported_volunteer_id, other_volunteer_id],
+            &mut exec_opts,
+        )
+        .await?;
+
+    assert_eq!(exported.len(), 1);
+    assert_eq!(exported[0].volunteer_id, exported_volunteer_id);
+    assert_eq!(exported[0].workspace_email, "rogerfederer@developforgood.org");
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_fetch_exported_volunteer_details_by_org_unit(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let child_volunteer_id = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+    let sibling_volunteer_id = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+
+    let data = vec![
+        InsertVolunteerExportedToWorkspaceBuilder::default()
+            .job_id(job_id)
+            .volunteer_id(child_volunteer_id)
+            .workspace_email("rogerfederer@developforgood.org")
+            .org_unit("/Programs/PantheonUsers/2025-Spring")
+            .build()?,
+        InsertVolunteerExportedToWorkspaceBuilder::default()
+            .job_id(job_id)
+            .volunteer_id(sibling_volunteer_id)
+            .workspace_email("rafanadal@developforgood.org")
+            .org_unit("/Programs/PantheonUsersArchive")
+            .build()?,
+    ];
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    storage.batch_insert_volunteers_exported_to_workspace(data, &mut exec_opts).await?;
+
+    let exported = storage
+        .fetch_exported_volunteer_details_by_org_unit("/Programs/PantheonUsers", &mut exec_opts)
+        .await?;
+    assert_eq!(exported.len(), 1);
+    assert_eq!(exported[0].volunteer_id, child_volunteer_id);
+
+    let exported =
+        storage.fetch_exported_volunteer_details_by_org_unit("/", &mut exec_opts).await?;
+    assert_eq!(exported.len(), 2);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_fetch_exported_volunteer_details_page(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let exports = [
+        (
+            uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67"),
+            "rafanadal@developforgood.org",
+            "/Programs/A",
+        ),
+        (
+            uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90"),
+            "rogerfederer@DevelopForGood.org",
+            "/Programs/A/B",
+        ),
+        (
+            uuid!("0ef67e25-543c-4f0d-9a96-8cb71b3c0f60"),
+            "andymurray@alumni.developforgood.org",
+            "/Programs/C",
+        ),
+    ];
+
+    let data = exports
+        .into_iter()
+        .map(|(volunteer_id, workspace_email, org_unit)| {
+            InsertVolunteerExportedToWorkspaceBuilder::default()
+                .job_id(job_id)
+                .volunteer_id(volunteer_id)
+                .workspace_email(workspace_email)
+                .org_unit(org_unit)
+                .build()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    storage.batch_insert_volunteers_exported_to_workspace(data, &mut exec_opts).await?;
+
+    // paging through every export visits each one exactly once, newest first
+    let mut seen = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = storage
+            .fetch_exported_volunteer_details_page(
+                ExportedVolunteerFilter { job_id: Some(job_id), ..Default::default() },
+                cursor,
+                2,
+                &mut exec_opts,
+            )
+            .await?;
+        let Some(last) = page.last() else { break };
+        cursor = Some(ExportedVolunteerCursor::after(last));
+        seen.extend(page);
+    }
+    assert_eq!(seen.len(), 3);
+    assert!(seen.windows(2).all(|w| (w[0].created_at, w[0].id) > (w[1].created_at, w[1].id)));
+
+    let filters = [
+        // every export below the org unit is listed
+        (
+            ExportedVolunteerFilter {
+                org_unit: Some("/Programs/A".to_owned()),
+                ..Default::default()
+            },
+            2,
+        ),
+        // domains are compared ignoring case, and subdomains are different domains
+        (
+            ExportedVolunteerFilter {
+                email_domain: Some("developforgood.org".to_owned()),
+                ..Default::default()
+            },
+            2,
+        ),
+        (
+            ExportedVolunteerFilter {
+                exported_after: Some(Utc::now() + Duration::hours(1)),
+                ..Default::default()
+            },
+            0,
+        ),
+        (
+            ExportedVolunteerFilter {
+                exported_before: Some(Utc::now() + Duration::hours(1)),
+                project_cycle_id: Some(uuid!("76ed64a0-d88f-4148-9b02-331ea888d5d1")),
+                ..Default::default()
+            },
+            3,
+        ),
+    ];
+
+    for (filter, expected) in filters {
+        let exported =
+            storage.fetch_exported_volunteer_details_page(filter, None, 10, &mut exec_opts).await?;
+        assert_eq!(exported.len(), expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+pub fn test_exported_volunteer_cursor() -> Result<()> {
+    let cursor = ExportedVolunteerCursor {
+        created_at: Utc.with_ymd_and_hms(2026, 10, 16, 12, 30, 0).unwrap()
+            + Duration::microseconds(123_456),
+        id: uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90"),
+    };
+
+    assert_eq!(ExportedVolunteerCursor::decode(&cursor.encode())?, cursor);
+
+    assert!(ExportedVolunteerCursor::decode("").is_err());
+    assert!(ExportedVolunteerCursor::decode("yesterday_9edc52d8").is_err());
+    assert!(ExportedVolunteerCursor::decode("1760617800123456_not-a-uuid").is_err());
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_fetch_taken_workspace_emails(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+
+    let data = vec![InsertVolunteerExportedToWorkspaceBuilder::default()
+        .job_id(uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742"))
+        .volunteer_id(uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90"))
+        .workspace_email("rogerfederer@developforgood.org")
+        .org_unit("/Programs/PantheonUsers")
+        .build()?];
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    storage.batch_insert_volunteers_exported_to_workspace(data, &mut exec_opts).await?;
+
+    let taken = storage
+        .fetch_taken_workspace_emails(
+            vec![
+                "rogerfederer@developforgood.org".to_owned(),
+                "rogerfederer2@developforgood.org".to_owned(),
+            ],
+            &mut exec_opts,
+        )
+        .await?;
+
+    assert_eq!(taken, vec!["rogerfederer@developforgood.org".to_owned()]);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_workspace_aliases(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    storage
+        .batch_insert_volunteers_exported_to_workspace(
+            vec![InsertVolunteerExportedToWorkspaceBuilder::default()
+                .job_id(job_id)
+                .volunteer_id(volunteer_id)
+                .workspace_email("roger.federer@developforgood.org")
+                .org_unit("/Programs/PantheonUsers")
+                .build()?],
+            &mut exec_opts,
+        )
+        .await?;
+
+    let alias = InsertWorkspaceAliasBuilder::default()
+        .job_id(job_id)
+        .volunteer_id(volunteer_id)
+        .workspace_email("roger.federer@developforgood.org")
+        .alias("roger@developforgood.org")
+        .build()?;
+    storage.batch_insert_workspace_aliases(vec![alias.clone()], &mut exec_opts).await?;
+
+    // recording the same alias again is a no-op
+    storage.batch_insert_workspace_aliases(vec![alias], &mut exec_opts).await?;
+
+    let aliases = storage
+        .fetch_workspace_aliases_by_volunteer_ids(vec![volunteer_id], &mut exec_opts)
+        .await?;
+    assert_eq!(aliases.len(), 1);
+    assert_eq!(aliases[0].alias, "roger@developforgood.org");
+    assert_eq!(aliases[0].workspace_email, "roger.federer@developforgood.org");
+
+    // aliases are taken just like primary emails
+    let mut taken = storage
+        .fetch_taken_workspace_emails(
+            vec![
+                "roger.federer@developforgood.org".to_owned(),
+                "roger@developforgood.org".to_owned(),
+                "federer@developforgood.org".to_owned(),
+            ],
+            &mut exec_opts,
+        )
+        .await?;
+    taken.sort();
+    assert_eq!(
+        taken,
+        vec!["roger.federer@developforgood.org".to_owned(), "roger@developforgood.org".to_owned()]
+    );
+
+    // undoing the export frees the aliases too
+    storage
+        .batch_remove_volunteers_exported_to_workspace(vec![volunteer_id], &mut exec_opts)
+        .await?;
+    assert!(storage
+        .fetch_workspace_aliases_by_volunteer_ids(vec![volunteer_id], &mut exec_opts)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_fetch_profile_sync_candidates(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
+    let job_id = uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742");
+    let volunteer_id1 = uuid!("1b1b5e16-d0d6-4ad1-8fdc-80df15b18b67");
+    let volunteer_id2 = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    storage
+        .batch_insert_volunteers_exported_to_workspace(
+            vec![
+                InsertVolunteerExportedToWorkspaceBuilder::default()
+                    .job_id(job_id)
+                    .volunteer_id(volunteer_id1)
+                    .workspace_email("rafael.nadal@developforgood.org")
+                    .org_unit("/Programs/PantheonUsers")
+                    .build()?,
+                InsertVolunteerExportedToWorkspaceBuilder::default()
+                    .job_id(job_id)
+                    .volunteer_id(volunteer_id2)
+                    .workspace_email("roger.federer@developforgood.org")
+                    .org_unit("/Programs/PantheonUsers")
+                    .build()?,
+            ],
+            &mut exec_opts,
+        )
+        .await?;
+
+    let candidates =
+        storage.fetch_profile_sync_candidates(project_cycle_id, None, &mut exec_opts).await?;
+    assert_eq!(candidates.len(), 2);
+
+    // only the requested volunteers are candidates
+    let candidates = storage
+        .fetch_profile_sync_candidates(project_cycle_id, Some(vec![volunteer_id2]), &mut exec_opts)
+        .await?;
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].first_name, "Roger");
+    assert_eq!(candidates[0].last_name, "Federer");
+    assert_eq!(candidates[0].email, "roger.federer@gmail.com");
+    assert_eq!(candidates[0].workspace_email, "roger.federer@developforgood.org");
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_delete_and_restore_volunteer(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let volunteer_id = uuid!("9edc52d8-8cc7-4d44-80c1-7efcce246e90");
+
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+
+    storage
+        .batch_insert_volunteers_exported_to_workspace(
+            vec![InsertVolunteerExportedToWorkspaceBuilder::default()
+                .job_id(uuid!("413eed73-3c6f-456a-b9f0-ae72d136c742"))
+                .volunteer_id(volunteer_id)
+                .workspace_email("roger.federer@developforgood.org")
+                .org_unit("/Programs/PantheonUsers")
+                .build()?],
+            &mut exec_opts,
+        )
+        .await?;
+
+    storage.delete_volunteer(volunteer_id, &mut exec_opts).await?;
+
+    // deleted volunteers and their export records are left out of queries
+    assert!(storage.fetch_volunteer_by_id(volunteer_id, &mut exec_opts).await?.is_none());
+    let exported =
+        storage.fetch_exported_volunteer_details_by_ids(vec![volunteer_id], &mut exec_opts).await?;
+    assert!(exported.is_empty());
+
+    let volunteer =
+        storage.fetch_volunteer_by_email("roger.federer@gmail.com", &mut exec_opts).await?;
+    assert!(volunteer.is_none());
+
+    assert!(storage.restore_volunteer(volunteer_id, 30, &mut exec_opts).await?);
+    assert!(storage.fetch_volunteer_by_id(volunteer_id, &mut exec_opts).await?.is_some());
+    let exported =
+        storage.fetch_exported_volunteer_details_by_ids(vec![volunteer_id], &mut exec_opts).await?;
+    assert_eq!(exported.len(), 1);
+
+    // a volunteer that is not deleted cannot be restored
+    assert!(!storage.restore_volunteer(volunteer_id, 30, &mut exec_opts).await?);
+
+    // nor can one deleted longer ago than the retention window
+    storage.delete_volunteer(volunteer_id, &mut exec_opts).await?;
+    sqlx::query("update volunteers set deleted_at = now() - interval '31 days' where id = $1")
+        .bind(volunteer_id)
+        .execute(&storage.pool)
+        .await?;
+    assert!(!storage.restore_volunteer(volunteer_id, 30, &mut exec_opts).await?);
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_page_through_volunteers_by_cycle(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let project_cycle_id = uuid!("0e12b846-4de5-432e-8137-1bc2c92827b3");
+
+    let mut expected = storage
+        .fetch_volunteers_by_cycle(project_cycle_id, &mut ExecOptsBuilder::default().build()?)
+        .await?
+        .into_iter()
+        .map(|v| v.volunteer_id)
+        .collect::<Vec<Uuid>>();
+    expected.sort();
+    assert!(expected.len() > 2);
+
+    let mut cursor = VolunteerCursor::new(project_cycle_id, 2);
+    let mut paged = Vec::<Uuid>::new();
+    while let Some(page) = cursor.next_page(&storage).await? {
+        assert!(page.len() <= 2);
+        paged.extend(page.into_iter().map(|v| v.volunteer_id));
+    }
+    assert_eq!(paged, expected);
+    assert!(cursor.next_page(&storage).await?.is_none());
+    Ok(())
+}
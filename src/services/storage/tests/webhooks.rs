@@ -0,0 +1,49 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::{uuid, Uuid};
+
+use crate::services::storage::jobs::{QueryJobs, NEW_JOB_VERSION};
+use crate::services::storage::types::JobStatus;
+use crate::services::storage::webhooks::QueryWebhooks;
+use crate::services::storage::{ExecOptsBuilder, PgBackend};
+
+#[sqlx::test(fixtures("setup"))]
+pub async fn test_job_webhooks(pool: PgPool) -> Result<()> {
+    let storage =
+        PgBackend { pool, replica: None, metrics: Default::default(), cache: None, cipher: None };
+    let mut exec_opts = ExecOptsBuilder::default().build()?;
+    let job_id = uuid!("bc080e0d-8b14-46e0-9268-4bbb370035ec");
+    let claimed_until = Utc::now() + Duration::minutes(5);
+
+    let url = "https://hooks.example.org/scipio".to_owned();
+    assert!(storage.set_job_callback_url(job_id, Some(url.clone()), &mut exec_opts).await?);
+    assert!(!storage.set_job_callback_url(Uuid::new_v4(), None, &mut exec_opts).await?);
+
+    // nothing is called until a job finishes
+    assert!(storage.claim_due_job_webhooks(10, claimed_until, &mut exec_opts).await?.is_empty());
+
+    storage.mark_job_complete(job_id, NEW_JOB_VERSION, &mut exec_opts).await?;
+    let claimed = storage.claim_due_job_webhooks(10, claimed_until, &mut exec_opts).await?;
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].job_id, job_id);
+    assert_eq!(claimed[0].status, JobStatus::Complete);
+    assert_eq!(claimed[0].attempts, 1);
+    assert_eq!(claimed[0].callback_url, Some(url));
+
+    // a claimed call is not claimed again until it is due
+    assert!(storage.claim_due_job_webhooks(10, claimed_until, &mut exec_opts).await?.is_empty());
+
+    let id = claimed[0].id;
+    storage.retry_job_webhook(id, Utc::now(), "timed out".to_owned(), &mut exec_opts).await?;
+    let retried = storage.claim_due_job_webhooks(10, claimed_until, &mut exec_opts).await?;
+    assert_eq!(retried.iter().map(|w| (w.id, w.attempts)).collect::<Vec<_>>(), vec![(id, 2)]);
+    assert_eq!(retried[0].error.as_deref(), Some("timed out"));
+
+    // a finished call is never made again
+    storage.finish_job_webhook(id, None, &mut exec_opts).await?;
+    storage.retry_job_webhook(id, Utc::now(), "late".to_owned(), &mut exec_opts).await?;
+    assert!(storage.claim_due_job_webhooks(10, claimed_until, &mut exec_opts).await?.is_empty());
+
+    Ok(())
+}
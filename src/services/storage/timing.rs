@@ -0,0 +1,222 @@
+//! Timing of storage operations.
+//!
+//! Every storage operation that runs through `exec_with_tx` or `exec_read_with_tx` is timed, from
+//! acquiring its transaction to committing it, and the time is recorded under the name of the
+//! operation (the storage method, e.g. `fetch_job`). The durations are kept as histograms so the
+//! stats show which operations dominate the latency of an export, and operations slower than a
+//! configurable threshold are logged as they happen.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// Operations that take longer than this are logged as slow, unless configured otherwise.
+pub const DEFAULT_SLOW_QUERY_THRESHOLD: Duration = Duration::from_millis(500);
+
+/// The upper bounds of the histogram buckets, in milliseconds. Operations slower than the last
+/// bound are counted in a final bucket of their own.
+pub const DURATION_BUCKETS_MS: [u64; 12] =
+    [1, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000];
+
+/// Counters for the calls of a single storage operation.
+///
+/// * `calls`: The number of times the operation ran
+/// * `failed`: The number of times the operation returned an error
+/// * `total_micros`: The total time the operation took, in microseconds
+/// * `max_micros`: The longest time the operation took, in microseconds
+/// * `buckets`: How many calls took at most each of `DURATION_BUCKETS_MS`, and how many took longer
+#[derive(Debug, Default)]
+struct OperationMetrics {
+    calls: AtomicU64,
+    failed: AtomicU64,
+    total_micros: AtomicU64,
+    max_micros: AtomicU64,
+    buckets: [AtomicU64; DURATION_BUCKETS_MS.len() + 1],
+}
+
+/// How long storage operations took, by operation name.
+///
+/// * `slow_threshold`: Operations that take at least this long are logged as slow. If `None`,
+///   none are logged.
+/// * `operations`: The metrics of each operation that has run
+#[derive(Debug)]
+pub struct QueryTimings {
+    slow_threshold: Option<Duration>,
+    operations: RwLock<HashMap<&'static str, OperationMetrics>>,
+}
+
+impl Default for QueryTimings {
+    fn default() -> Self {
+        Self::new(Some(DEFAULT_SLOW_QUERY_THRESHOLD))
+    }
+}
+
+impl QueryTimings {
+    /// Create empty timings.
+    ///
+    /// * `slow_threshold`: Operations that take at least this long are logged as slow. If `None`,
+    ///   none are logged.
+    pub fn new(slow_threshold: Option<Duration>) -> Self {
+        Self { slow_threshold, operations: RwLock::default() }
+    }
+
+    /// Record a call of an operation, and warn if it was slow.
+    ///
+    /// * `operation`: The name of the operation
+    /// * `elapsed`: How long the call took
+    /// * `succeeded`: Whether the call returned without an error
+    pub fn record(&self, operation: &'static str, elapsed: Duration, succeeded: bool) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        let bucket = DURATION_BUCKETS_MS
+            .iter()
+            .position(|&bound| micros <= bound * 1000)
+            .unwrap_or(DURATION_BUCKETS_MS.len());
+
+        let record = |metrics: &OperationMetrics| {
+            metrics.calls.fetch_add(1, Ordering::Relaxed);
+            if !succeeded {
+                metrics.failed.fetch_add(1, Ordering::Relaxed);
+            }
+            metrics.total_micros.fetch_add(micros, Ordering::Relaxed);
+            metrics.max_micros.fetch_max(micros, Ordering::Relaxed);
+            metrics.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        };
+
+        // Every operation is inserted the first time it runs, so the write lock is rarely taken
+        let recorded = match self.operations.read() {
+            Ok(operations) => operations.get(operation).map(&record).is_some(),
+            Err(_) => true,
+        };
+        if !recorded {
+            if let Ok(mut operations) = self.operations.write() {
+                record(operations.entry(operation).or_default());
+            }
+        }
+
+        if self.slow_threshold.is_some_and(|threshold| elapsed >= threshold) {
+            log::warn!("Slow storage operation {operation} took {}ms", elapsed.as_millis());
+        }
+    }
+
+    /// Take a snapshot of the timings, with the operations that took the most time in total first.
+    pub fn stats(&self) -> QueryTimingStats {
+        let Ok(operations) = self.operations.read() else {
+            return QueryTimingStats::default();
+        };
+
+        let mut operations: Vec<_> =
+            operations.iter().map(|(&name, metrics)| OperationTiming::new(name, metrics)).collect();
+        operations.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms).then(a.name.cmp(b.name)));
+        QueryTimingStats { operations }
+    }
+}
+
+/// The number of calls of an operation that took at most a number of milliseconds.
+///
+/// * `le_ms`: The upper bound of the bucket, in milliseconds. If `None`, the bucket counts the
+///   calls slower than every other bucket.
+/// * `count`: The number of calls in the bucket, not counting those in faster buckets
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DurationBucket {
+    pub le_ms: Option<u64>,
+    pub count: u64,
+}
+
+/// A snapshot of how long a single storage operation took.
+///
+/// * `name`: The name of the operation
+/// * `calls`: The number of times the operation ran since startup
+/// * `failed`: The number of times the operation returned an error since startup
+/// * `total_ms`: The total time the operation took, in milliseconds
+/// * `avg_ms`: The average time the operation took, in milliseconds
+/// * `max_ms`: The longest time the operation took, in milliseconds
+/// * `buckets`: A histogram of the times the operation took
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationTiming {
+    pub name: &'static str,
+    pub calls: u64,
+    pub failed: u64,
+    pub total_ms: f64,
+    pub avg_ms: f64,
+    pub max_ms: f64,
+    pub buckets: Vec<DurationBucket>,
+}
+
+impl OperationTiming {
+    /// Take a snapshot of an operation.
+    ///
+    /// * `name`: The name of the operation
+    /// * `metrics`: The metrics recorded for the operation
+    fn new(name: &'static str, metrics: &OperationMetrics) -> Self {
+        let calls = metrics.calls.load(Ordering::Relaxed);
+        let total_ms = metrics.total_micros.load(Ordering::Relaxed) as f64 / 1000.0;
+        let bounds = DURATION_BUCKETS_MS.iter().map(|&bound| Some(bound)).chain([None]);
+
+        Self {
+            name,
+            calls,
+            failed: metrics.failed.load(Ordering::Relaxed),
+            total_ms,
+            avg_ms: if calls == 0 { 0.0 } else { total_ms / calls as f64 },
+            max_ms: metrics.max_micros.load(Ordering::Relaxed) as f64 / 1000.0,
+            buckets: bounds
+                .zip(&metrics.buckets)
+                .map(|(le_ms, count)| DurationBucket {
+                    le_ms,
+                    count: count.load(Ordering::Relaxed),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A snapshot of how long the storage operations of a backend took.
+///
+/// * `operations`: Every operation that has run since startup, with those that took the most time
+///   in total first
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QueryTimingStats {
+    pub operations: Vec<OperationTiming>,
+}
+
+/// `QueryMonitor` is a trait for reporting how long a backend's storage operations took.
+pub trait QueryMonitor {
+    fn query_timings(&self) -> QueryTimingStats;
+}
+
+/// The name of the operation that a function is defined in: the last segment of the function's path
+/// that is not a closure, which for a storage method (or the async block `async_trait` wraps its
+/// body in) is the name of the method.
+///
+/// * `path`: The path of the function, as given by `std::any::type_name`
+pub fn operation_from_path(path: &'static str) -> &'static str {
+    let mut path = path.rsplit_once("::").map_or(path, |(parent, _)| parent);
+    while let Some(parent) = path.strip_suffix("::{{closure}}") {
+        path = parent;
+    }
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+/// The type name of a value, which for a function item is its path.
+///
+/// * `_value`: The value
+pub fn type_name_of<T>(_value: T) -> &'static str {
+    std::any::type_name::<T>()
+}
+
+/// `operation_name` is a macro that returns the name of the storage method it is expanded in.
+macro_rules! operation_name {
+    () => {{
+        fn f() {}
+        $crate::services::storage::timing::operation_from_path(
+            $crate::services::storage::timing::type_name_of(f),
+        )
+    }};
+}
+
+pub(in crate::services::storage) use operation_name;
@@ -0,0 +1,253 @@
+//! This module contains the definition of the `QueryTransfers` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use derive_builder::Builder;
+use sqlx::{Database, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+
+use super::entities::WorkspaceDriveTransfer;
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// Data needed to record a transfer of a volunteer's Drive files before it is started.
+///
+/// * `job_id`: The ID of the job transferring the files
+/// * `volunteer_id`: The ID of the volunteer
+/// * `workspace_email`: The volunteer's workspace email address
+/// * `archive_email`: The email of the account the files are transferred to
+/// * `principal`: The email of the staff member who started the job
+#[derive(Builder, Debug, Clone)]
+pub struct CreateDriveTransfer {
+    pub job_id: Uuid,
+    pub volunteer_id: Uuid,
+    #[builder(setter(into))]
+    pub workspace_email: String,
+    #[builder(setter(into))]
+    pub archive_email: String,
+    #[builder(setter(into))]
+    pub principal: String,
+}
+
+/// A trait for querying the transfers of volunteers' Drive files to archive accounts.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryTransfers<DB: Database> {
+    /// Record transfers of volunteers' Drive files as pending. Returns the recorded transfers.
+    ///
+    /// * `data`: The transfers to record
+    /// * `exec_opts`: Execution options for the query
+    async fn create_drive_transfers(
+        &self,
+        data: Vec<CreateDriveTransfer>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<WorkspaceDriveTransfer>> {
+        unimplemented!()
+    }
+
+    /// Record that a transfer was started.
+    ///
+    /// * `id`: The ID of the transfer record
+    /// * `transfer_id`: The ID the provider gave the transfer
+    /// * `exec_opts`: Execution options for the query
+    async fn start_drive_transfer(
+        &self,
+        id: Uuid,
+        transfer_id: String,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Record that every file of a transfer was transferred.
+    ///
+    /// * `id`: The ID of the transfer record
+    /// * `exec_opts`: Execution options for the query
+    async fn complete_drive_transfer(&self, id: Uuid, exec_opts: &mut ExecOpts<DB>) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Record that a transfer could not be started, or did not finish.
+    ///
+    /// * `id`: The ID of the transfer record
+    /// * `error`: Why the transfer failed
+    /// * `exec_opts`: Execution options for the query
+    async fn fail_drive_transfer(
+        &self,
+        id: Uuid,
+        error: String,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Record that the accounts whose files were transferred have been suspended.
+    ///
+    /// * `ids`: The IDs of the transfer records
+    /// * `exec_opts`: Execution options for the query
+    async fn mark_drive_transfers_suspended(
+        &self,
+        ids: Vec<Uuid>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Fetch every transfer of the Drive files of a project cycle's volunteers, newest first.
+    ///
+    /// * `project_cycle_id`: The ID of the project cycle
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_drive_transfers(
+        &self,
+        project_cycle_id: Uuid,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<WorkspaceDriveTransfer>> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryTransfers<Postgres> for PgBackend {
+    async fn create_drive_transfers(
+        &self,
+        data: Vec<CreateDriveTransfer>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<WorkspaceDriveTransfer>> {
+        async fn exec(
+            data: Vec<CreateDriveTransfer>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<WorkspaceDriveTransfer>> {
+            if data.is_empty() {
+                return Ok(vec![]);
+            }
+
+            let fragment = include_str!("queries/transfers/create_drive_transfers.fragment.sql");
+
+            let transfers = QueryBuilder::<Postgres>::new(fragment)
+                .push_values(data, |mut b, d| {
+                    b.push_bind(d.job_id)
+                        .push_bind(d.volunteer_id)
+                        .push_bind(d.workspace_email)
+                        .push_bind(d.archive_email)
+                        .push_bind(d.principal);
+                })
+                .push(
+                    " returning id, created_at, updated_at, job_id, volunteer_id, workspace_email, \
+                     archive_email, transfer_id, status, error, suspended, principal",
+                )
+                .build_query_as::<WorkspaceDriveTransfer>()
+                .fetch_all(&mut **tx)
+                .await
+                .context("error recording drive transfers")?;
+
+            Ok(transfers)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn start_drive_transfer(
+        &self,
+        id: Uuid,
+        transfer_id: String,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            id: Uuid,
+            transfer_id: String,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            let query = include_str!("queries/transfers/start_drive_transfer.sql");
+            sqlx::query(query)
+                .bind(id)
+                .bind(transfer_id)
+                .execute(&mut **tx)
+                .await
+                .context("error recording the start of a drive transfer")?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, id, transfer_id)
+    }
+
+    async fn complete_drive_transfer(
+        &self,
+        id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(id: Uuid, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+            let query = include_str!("queries/transfers/complete_drive_transfer.sql");
+            sqlx::query(query)
+                .bind(id)
+                .execute(&mut **tx)
+                .await
+                .context("error completing drive transfer")?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, id)
+    }
+
+    async fn fail_drive_transfer(
+        &self,
+        id: Uuid,
+        error: String,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(id: Uuid, error: String, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+            let query = include_str!("queries/transfers/fail_drive_transfer.sql");
+            sqlx::query(query)
+                .bind(id)
+                .bind(error)
+                .execute(&mut **tx)
+                .await
+                .context("error recording drive transfer failure")?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, id, error)
+    }
+
+    async fn mark_drive_transfers_suspended(
+        &self,
+        ids: Vec<Uuid>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(ids: Vec<Uuid>, tx: &mut Transaction<'_, Postgres>) -> Result<()> {
+            let query = include_str!("queries/transfers/mark_drive_transfers_suspended.sql");
+            sqlx::query(query)
+                .bind(ids)
+                .execute(&mut **tx)
+                .await
+                .context("error marking drive transfers suspended")?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, ids)
+    }
+
+    async fn fetch_drive_transfers(
+        &self,
+        project_cycle_id: Uuid,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<WorkspaceDriveTransfer>> {
+        async fn exec(
+            project_cycle_id: Uuid,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<WorkspaceDriveTransfer>> {
+            let query = include_str!("queries/transfers/fetch_drive_transfers.sql");
+            let transfers = sqlx::query_as::<_, WorkspaceDriveTransfer>(query)
+                .bind(project_cycle_id)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching drive transfers")?;
+            Ok(transfers)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, project_cycle_id)
+    }
+}
@@ -43,6 +43,183 @@ pub enum JobStatus {
     Complete,
     /// The job has been cancelled
     Cancelled,
+    /// The job failed on every attempt it was given, and is not retried until it is requeued
+    DeadLettered,
+}
+
+/// How urgently a queued job should run. Workers claim the waiting jobs with the highest priority
+/// first, and the oldest of those first.
+#[derive(Debug, Serialize, Deserialize, Type, Copy, Clone, PartialEq, Eq, Default, Display)]
+#[sqlx(type_name = "job_priority", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum JobPriority {
+    /// The job runs before any job with a lower priority (e.g. a small, urgent re-export)
+    #[display("high")]
+    High,
+    /// The job runs in the order it was queued
+    #[default]
+    #[display("normal")]
+    Normal,
+    /// The job runs once no job with a higher priority is waiting (e.g. a large cohort export)
+    #[display("low")]
+    Low,
+}
+
+/// Phases a job moves through while it runs
+#[derive(Debug, Serialize, Deserialize, Type, Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "job_phase", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum JobPhase {
+    /// Accounts are being created in an external service (e.g. Google Workspace)
+    Provisioning,
+    /// Results are being recorded in the database
+    Persisting,
+    /// Emails are being sent
+    Emailing,
+}
+
+/// Possible states of a volunteer within a workspace export job
+#[derive(Debug, Serialize, Deserialize, Type, Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "workspace_export_status", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum WorkspaceExportStatus {
+    /// The volunteer has not been created in Google Workspace yet
+    Pending,
+    /// The volunteer has been created in Google Workspace
+    Created,
+    /// Creating the volunteer in Google Workspace failed
+    Failed,
+    /// The volunteer has been created in Google Workspace and recorded in Pantheon
+    Recorded,
+    /// The volunteer has been created, recorded, and sent their onboarding email
+    Emailed,
+}
+
+/// What the mail provider last reported about an onboarding email
+#[derive(Debug, Serialize, Deserialize, Type, Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "email_delivery_status", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum EmailDeliveryStatus {
+    /// The recipient's mail server accepted the email
+    Delivered,
+    /// The recipient's mail server rejected the email
+    Bounced,
+    /// The provider refused to send the email (e.g. because the address bounced before)
+    Dropped,
+    /// The recipient opened the email
+    Opened,
+}
+
+/// Whether a mail provider accepted an email
+#[derive(Debug, Serialize, Deserialize, Type, Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "email_send_status", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum EmailSendStatus {
+    /// A mail provider accepted the email
+    Sent,
+    /// No mail provider accepted the email
+    Failed,
+}
+
+/// What happened to an email, as recorded in its delivery events
+#[derive(Debug, Serialize, Deserialize, Type, Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "email_event_type", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum EmailEventType {
+    /// The email was queued to be sent
+    Queued,
+    /// A mail provider accepted the email
+    Sent,
+    /// The recipient's mail server accepted the email
+    Delivered,
+    /// The recipient's mail server rejected the email
+    Bounced,
+    /// The provider refused to send the email (e.g. because the address bounced before)
+    Dropped,
+}
+
+/// What happened when an exported volunteer was checked for an activation reminder
+#[derive(Debug, Serialize, Deserialize, Type, Copy, Clone, PartialEq, Eq)]
+#[sqlx(type_name = "activation_reminder_status", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum ActivationReminderStatus {
+    /// The volunteer had not signed in to their workspace account and was sent a reminder
+    Sent,
+    /// The volunteer had already signed in to their workspace account
+    Activated,
+    /// The volunteer was not reminded (e.g. their workspace account is suspended or gone)
+    Skipped,
+}
+
+/// What an offboarding job does to a volunteer's workspace account
+#[derive(Debug, Serialize, Deserialize, Type, Copy, Clone, PartialEq, Eq, Default, Display)]
+#[sqlx(type_name = "workspace_suspension_action", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum WorkspaceSuspensionAction {
+    /// Suspend the account, so the volunteer can no longer sign in
+    #[default]
+    #[display("suspend")]
+    Suspend,
+    /// Lift the suspension of the account
+    #[display("unsuspend")]
+    Unsuspend,
+}
+
+/// Where a scheduled deletion of a volunteer's workspace account stands
+#[derive(Debug, Serialize, Deserialize, Type, Copy, Clone, PartialEq, Eq, Display)]
+#[sqlx(type_name = "workspace_deletion_status", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum WorkspaceDeletionStatus {
+    /// The account is suspended and will be deleted once its grace period is over
+    #[display("scheduled")]
+    Scheduled,
+    /// The deletion was cancelled before it ran, so the account was kept
+    #[display("cancelled")]
+    Cancelled,
+    /// The account was deleted
+    #[display("deleted")]
+    Deleted,
+    /// The account could not be deleted
+    #[display("failed")]
+    Failed,
+}
+
+/// Where a transfer of a departing volunteer's Drive files to an archive account stands
+#[derive(Debug, Serialize, Deserialize, Type, Copy, Clone, PartialEq, Eq, Display)]
+#[sqlx(type_name = "workspace_transfer_status", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum WorkspaceTransferStatus {
+    /// The transfer has not been started yet
+    #[display("pending")]
+    Pending,
+    /// The files are being transferred
+    #[display("in progress")]
+    InProgress,
+    /// Every file was transferred
+    #[display("completed")]
+    Completed,
+    /// The transfer could not be started, or stopped before every file was transferred
+    #[display("failed")]
+    Failed,
+}
+
+/// Why email is not sent to an address on the suppression list
+#[derive(Debug, Serialize, Deserialize, Type, Copy, Clone, PartialEq, Eq, Display)]
+#[sqlx(type_name = "email_suppression_reason", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum EmailSuppressionReason {
+    /// The recipient's mail server permanently rejected email to the address
+    #[display("bounced")]
+    Bounced,
+    /// The recipient unsubscribed
+    #[display("unsubscribed")]
+    Unsubscribed,
+    /// The recipient reported email as spam
+    #[display("spam report")]
+    SpamReport,
+    /// Staff added the address to the suppression list
+    #[display("manual")]
+    Manual,
 }
 
 /// Possible destinations for exporting users
@@ -65,6 +242,22 @@ pub enum JobType {
     AirtableExportUsers,
     /// Undo an export of users to Workspace
     UndoWorkspaceExport,
+    /// Suspend (or unsuspend) the Workspace accounts of a project cycle's volunteers
+    SuspendWorkspaceUsers,
+    /// Push volunteers' Pantheon profiles to their Workspace accounts
+    SyncWorkspaceProfiles,
+    /// Suspend the Workspace accounts of a project cycle's volunteers and schedule their deletion
+    DeprovisionWorkspaceUsers,
+    /// Compare the accounts in a Workspace org unit with the volunteers exported to it
+    ReconcileWorkspaceUsers,
+    /// Invite a project cycle's exported volunteers to Slack
+    InviteToSlack,
+    /// Look up when a project cycle's exported volunteers last signed in to Workspace
+    SyncWorkspaceLogins,
+    /// Transfer the Drive files of a project cycle's volunteers to an archive account
+    TransferDriveFiles,
+    /// Back up an organization's data to object storage
+    BackupDatabase,
 }
 
 /// Data needed to run a job
@@ -86,18 +279,61 @@ pub enum JobData {
     },
     /// Data we track when we start a job to undo an export of users to Workspace.
     UndoWorkspaceExport { volunteers: Vec<(Uuid, String)> },
+    /// Data we track when we start a job to suspend (or unsuspend) users in Workspace.
+    SuspendWorkspaceUsers { action: WorkspaceSuspensionAction },
+    /// Data we track when we start a job to push volunteers' profiles to Workspace.
+    SyncWorkspaceProfiles {
+        #[serde(rename = "volunteerCount")]
+        volunteer_count: usize,
+    },
+    /// Data we track when we start a job to deprovision users in Workspace.
+    DeprovisionWorkspaceUsers {
+        #[serde(rename = "gracePeriodDays")]
+        grace_period_days: u32,
+    },
+    /// Data we track when we start a job to reconcile Workspace accounts with Pantheon.
+    ReconcileWorkspaceUsers {
+        #[serde(rename = "orgUnit")]
+        org_unit: String,
+        fix: bool,
+    },
+    /// Data we track when we start a job to invite volunteers to Slack.
+    InviteToSlack {
+        #[serde(rename = "channelIds")]
+        channel_ids: Vec<String>,
+    },
+    /// Data we track when we start a job to look up volunteers' last sign ins in Workspace.
+    SyncWorkspaceLogins {
+        #[serde(rename = "accountCount")]
+        account_count: usize,
+    },
+    /// Data we track when we start a job to transfer volunteers' Drive files to an archive account.
+    TransferDriveFiles {
+        #[serde(rename = "archiveEmail")]
+        archive_email: String,
+        #[serde(rename = "suspendAfterTransfer")]
+        suspend_after_transfer: bool,
+    },
+    /// Data we track when we start a job to back up an organization's data to object storage.
+    BackupDatabase {
+        #[serde(rename = "objectKey")]
+        object_key: String,
+    },
 }
 
 /// Details about a job
 ///
 /// * `job_type`: The type of the job
 /// * `error`: An error message if the job failed (otherwise this is `None`)
+/// * `result`: A summary of what the job did, if the job records one
 /// * `data`: Job metadata
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct JobDetails {
     pub job_type: JobType,
     pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
     #[serde(flatten)]
     pub data: JobData,
 }
@@ -372,3 +608,19 @@ pub enum Fli {
     #[serde(rename = "Prefer not to say")]
     PreferNotToSay,
 }
+
+/// How a row recorded in the audit log was changed
+#[derive(Debug, Serialize, Deserialize, Type, Copy, Clone, PartialEq, Eq, Display)]
+#[sqlx(type_name = "audit_operation", rename_all = "snake_case")]
+#[serde(rename_all = "camelCase")]
+pub enum AuditOperation {
+    /// The row was inserted
+    #[display("insert")]
+    Insert,
+    /// The row was updated
+    #[display("update")]
+    Update,
+    /// The row was deleted
+    #[display("delete")]
+    Delete,
+}
@@ -1,17 +1,22 @@
 //! This module contains the definition of the `QueryVolunteers` trait as well as the default
 //! implementation of the trait for the `PgBackend` struct.
 
+use std::collections::HashSet;
+
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 use sqlx::{Database, Postgres, QueryBuilder, Transaction};
 use uuid::Uuid;
 
-use super::entities::{ExportedVolunteerDetails, VolunteerDetails};
-use super::exec_with_tx;
+use super::entities::{
+    ExportedVolunteerDetails, ProfileSyncCandidate, VolunteerDetails, WorkspaceAlias,
+};
 use super::types::{AgeRange, Ethnicity, Fli, Gender, Lgbt, StudentStage, VolunteerHearAbout};
-use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+use super::{exec_read_with_tx, exec_with_tx};
+use crate::services::storage::{Acquire, ExecOpts, ExecOptsBuilder, PgBackend};
 
 /// Create a new volunteer.
 ///
@@ -70,6 +75,31 @@ pub struct EditVolunteer {
     pub phone: Option<String>,
 }
 
+/// Correct a volunteer's details. Only the fields that are set are changed.
+///
+/// * `id`: The ID of the volunteer
+/// * `email`: Their new personal email, which is their account's recovery email
+/// * `project_cycle_id`: The cohort (project cycle) to move them to
+/// * `first_name`: Their corrected first name
+/// * `last_name`: Their corrected last name
+#[derive(Builder, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateVolunteer {
+    pub id: Uuid,
+    #[builder(setter(into, strip_option), default)]
+    #[serde(default)]
+    pub email: Option<String>,
+    #[builder(setter(strip_option), default)]
+    #[serde(default)]
+    pub project_cycle_id: Option<Uuid>,
+    #[builder(setter(into, strip_option), default)]
+    #[serde(default)]
+    pub first_name: Option<String>,
+    #[builder(setter(into, strip_option), default)]
+    #[serde(default)]
+    pub last_name: Option<String>,
+}
+
 /// Record a volunteer as exported to a workspace.
 ///
 /// * `volunteer_id`: The ID of the volunteer
@@ -87,6 +117,144 @@ pub struct InsertVolunteerExportedToWorkspace {
     pub org_unit: String,
 }
 
+/// Record an alias added to a volunteer's workspace account.
+///
+/// * `volunteer_id`: The ID of the volunteer
+/// * `job_id`: The ID of the job that added the alias
+/// * `workspace_email`: The primary email of the volunteer's workspace account
+/// * `alias`: The alias
+#[derive(Builder, Clone)]
+pub struct InsertWorkspaceAlias {
+    pub volunteer_id: Uuid,
+    pub job_id: Uuid,
+    #[builder(setter(into))]
+    pub workspace_email: String,
+    #[builder(setter(into))]
+    pub alias: String,
+}
+
+/// Filters for listing the volunteers exported to a workspace. Every filter that is set must
+/// match.
+///
+/// * `job_id`: Only list volunteers exported by this job
+/// * `project_cycle_id`: Only list volunteers exported by a job of this project cycle
+/// * `exported_after`: Only list volunteers exported at or after this time
+/// * `exported_before`: Only list volunteers exported before this time
+/// * `org_unit`: Only list volunteers exported to this org unit or any org unit below it
+/// * `email_domain`: Only list volunteers whose workspace email is in this domain (e.g.
+///   `developforgood.org`), ignoring case
+#[derive(Builder, Debug, Clone, Default)]
+#[builder(default)]
+pub struct ExportedVolunteerFilter {
+    pub job_id: Option<Uuid>,
+    pub project_cycle_id: Option<Uuid>,
+    pub exported_after: Option<DateTime<Utc>>,
+    pub exported_before: Option<DateTime<Utc>>,
+    pub org_unit: Option<String>,
+    pub email_domain: Option<String>,
+}
+
+/// Where a page of exported volunteers starts. Exported volunteers are listed newest first, so a
+/// page starts with the record exported just before the last record of the previous page.
+///
+/// * `created_at`: When the last record of the previous page was exported
+/// * `id`: The ID of the last record of the previous page, which breaks ties between records
+///   exported at the same time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportedVolunteerCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl ExportedVolunteerCursor {
+    /// The cursor of the page that follows a record.
+    ///
+    /// * `record`: The last record of a page
+    pub fn after(record: &ExportedVolunteerDetails) -> Self {
+        Self { created_at: record.created_at, id: record.id }
+    }
+
+    /// Encode the cursor as an opaque token that can be handed to API clients.
+    pub fn encode(&self) -> String {
+        format!("{}_{}", self.created_at.timestamp_micros(), self.id.simple())
+    }
+
+    /// Decode a token made by `encode`.
+    ///
+    /// * `token`: The token
+    pub fn decode(token: &str) -> Result<Self> {
+        let (micros, id) = token.split_once('_').context("malformed cursor")?;
+        let created_at = micros
+            .parse::<i64>()
+            .ok()
+            .and_then(DateTime::from_timestamp_micros)
+            .context("malformed cursor")?;
+        let id = Uuid::parse_str(id).context("malformed cursor")?;
+        Ok(Self { created_at, id })
+    }
+}
+
+/// Reads the volunteers of a project cycle a page at a time, in order of ID, so that a whole cohort
+/// never has to be held in memory at once.
+///
+/// * `project_cycle_id`: The ID of the project cycle
+/// * `page_size`: The most volunteers in each page
+/// * `after`: The ID of the last volunteer returned so far, if any
+/// * `exhausted`: Whether every volunteer has been returned
+#[derive(Debug, Clone)]
+pub struct VolunteerCursor {
+    project_cycle_id: Uuid,
+    page_size: i64,
+    after: Option<Uuid>,
+    exhausted: bool,
+}
+
+impl VolunteerCursor {
+    /// Start reading the volunteers of a project cycle from the first page.
+    ///
+    /// * `project_cycle_id`: The ID of the project cycle
+    /// * `page_size`: The most volunteers in each page
+    pub fn new(project_cycle_id: Uuid, page_size: usize) -> Self {
+        Self {
+            project_cycle_id,
+            page_size: page_size.max(1).try_into().unwrap_or(i64::MAX),
+            after: None,
+            exhausted: false,
+        }
+    }
+
+    /// Fetch the next page of volunteers, or `None` once every volunteer has been returned.
+    ///
+    /// * `storage`: The storage layer to read the volunteers from
+    pub async fn next_page<DB: Database, S: QueryVolunteers<DB> + Sync + ?Sized>(
+        &mut self,
+        storage: &S,
+    ) -> Result<Option<Vec<VolunteerDetails>>> {
+        if self.exhausted {
+            return Ok(None);
+        }
+
+        let page = storage
+            .fetch_volunteer_details_page(
+                self.project_cycle_id,
+                self.after,
+                self.page_size,
+                &mut ExecOptsBuilder::default().build()?,
+            )
+            .await?;
+
+        // A short page is the last one, so there is no need to ask for an empty page after it
+        self.exhausted = i64::try_from(page.len()).unwrap_or(i64::MAX) < self.page_size;
+        match page.last() {
+            Some(last) => {
+                self.after = Some(last.volunteer_id);
+                Ok(Some(page))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
 /// A trait for querying data about volunteers.
 ///
 /// If you implement a new storage backend, this trait is required for it to implement
@@ -152,6 +320,22 @@ pub trait QueryVolunteers<DB: Database> {
         unimplemented!()
     }
 
+    /// Fetch a page of the volunteers associated with a project cycle, in order of ID.
+    ///
+    /// * `project_cycle_id`: The ID of the project cycle to fetch volunteers for
+    /// * `after`: The ID of the last volunteer of the previous page. Defaults to the first page.
+    /// * `limit`: The most volunteers to fetch
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_volunteer_details_page(
+        &self,
+        project_cycle_id: Uuid,
+        after: Option<Uuid>,
+        limit: i64,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<VolunteerDetails>> {
+        unimplemented!()
+    }
+
     /// Fetch a volunteer by ID.
     ///
     /// * `id`: The ID of the volunteer to fetch
@@ -190,7 +374,22 @@ pub trait QueryVolunteers<DB: Database> {
         unimplemented!()
     }
 
-    /// Delete a volunteer by ID.
+    /// Batch correct volunteers' recovery emails, cohorts, and names. A volunteer moved to another
+    /// cohort is unlinked from the nonprofits and mentors of their old one. Returns the IDs of the
+    /// volunteers that were updated, which leaves out those that do not exist or were deleted.
+    ///
+    /// * `data`: The corrections to make
+    /// * `exec_opts`: Execution options for the query
+    async fn batch_update_volunteers(
+        &self,
+        data: Vec<UpdateVolunteer>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<Uuid>> {
+        unimplemented!()
+    }
+
+    /// Soft-delete a volunteer by ID, along with the records of their exports to workspace. Deleted
+    /// volunteers are left out of every other query, and can be restored with `restore_volunteer`.
     ///
     /// * `id`: The ID of the volunteer to delete
     /// * `exec_opts`: Execution options for the query
@@ -198,6 +397,22 @@ pub trait QueryVolunteers<DB: Database> {
         unimplemented!()
     }
 
+    /// Restore a soft-deleted volunteer, along with the records of their exports to workspace that
+    /// were deleted with them. Returns whether the volunteer was restored, which they are not if
+    /// they were not deleted or were deleted longer ago than the retention window.
+    ///
+    /// * `id`: The ID of the volunteer to restore
+    /// * `retention_days`: How many days a deleted volunteer can still be restored for
+    /// * `exec_opts`: Execution options for the query
+    async fn restore_volunteer(
+        &self,
+        id: Uuid,
+        retention_days: i32,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<bool> {
+        unimplemented!()
+    }
+
     /// Batch link volunteers to nonprofits.
     ///
     /// * `project_cycle_id`: The project cycle ID that the volunteers and mentors are associated
@@ -230,7 +445,9 @@ pub trait QueryVolunteers<DB: Database> {
         unimplemented!()
     }
 
-    /// Batch record volunteers as exported to a workspace.
+    /// Batch record volunteers as exported to a workspace. A volunteer whose workspace account is
+    /// already recorded has its record moved to the new job and org unit instead, so exports can be
+    /// re-run.
     ///
     /// * `data`: Data required to record the volunteers as exported to a workspace
     /// * `exec_opts`: Execution options for the query
@@ -261,6 +478,100 @@ pub trait QueryVolunteers<DB: Database> {
     ) -> Result<Vec<ExportedVolunteerDetails>> {
         unimplemented!()
     }
+
+    /// Fetch the export records of the given volunteers, across every project cycle.
+    ///
+    /// * `volunteer_ids`: The IDs of the volunteers
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_exported_volunteer_details_by_ids(
+        &self,
+        volunteer_ids: Vec<Uuid>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<ExportedVolunteerDetails>> {
+        unimplemented!()
+    }
+
+    /// Fetch the export records of the volunteers exported to an org unit or any org unit below
+    /// it.
+    ///
+    /// * `org_unit`: The full path of the org unit (e.g. `/Programs/PantheonUsers`)
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_exported_volunteer_details_by_org_unit(
+        &self,
+        org_unit: &str,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<ExportedVolunteerDetails>> {
+        unimplemented!()
+    }
+
+    /// Fetch a page of the volunteers exported to a workspace, newest first.
+    ///
+    /// * `filter`: Which exported volunteers to list
+    /// * `cursor`: Where the page starts. Defaults to the first page.
+    /// * `limit`: The most exported volunteers to fetch
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_exported_volunteer_details_page(
+        &self,
+        filter: ExportedVolunteerFilter,
+        cursor: Option<ExportedVolunteerCursor>,
+        limit: i64,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<ExportedVolunteerDetails>> {
+        unimplemented!()
+    }
+
+    /// Fetch which of the given workspace emails have already been issued to a volunteer, either
+    /// as the primary email of their account or as an alias.
+    ///
+    /// * `workspace_emails`: The workspace emails to check
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_taken_workspace_emails(
+        &self,
+        workspace_emails: Vec<String>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<String>> {
+        unimplemented!()
+    }
+
+    /// Batch record aliases added to volunteers' workspace accounts. Aliases that are already
+    /// recorded are left as they are.
+    ///
+    /// * `data`: The aliases
+    /// * `exec_opts`: Execution options for the query
+    async fn batch_insert_workspace_aliases(
+        &self,
+        data: Vec<InsertWorkspaceAlias>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Fetch the aliases added to the given volunteers' workspace accounts.
+    ///
+    /// * `volunteer_ids`: The IDs of the volunteers
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_workspace_aliases_by_volunteer_ids(
+        &self,
+        volunteer_ids: Vec<Uuid>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<WorkspaceAlias>> {
+        unimplemented!()
+    }
+
+    /// Fetch the exported volunteers of a project cycle, whose Pantheon profiles a sync job pushes
+    /// to their workspace accounts.
+    ///
+    /// * `project_cycle_id`: The ID of the project cycle
+    /// * `volunteer_ids`: Only fetch these volunteers, if set
+    /// * `exec_opts`: Execution options for the query
+    async fn fetch_profile_sync_candidates(
+        &self,
+        project_cycle_id: Uuid,
+        volunteer_ids: Option<Vec<Uuid>>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<ProfileSyncCandidate>> {
+        unimplemented!()
+    }
 }
 
 #[async_trait]
@@ -365,7 +676,16 @@ impl QueryVolunteers<Postgres> for PgBackend {
             Ok(volunteer)
         }
 
-        exec_with_tx!(self, exec_opts, exec, id)
+        let cache = self.read_cache(exec_opts);
+        if let Some(cached) = cache.and_then(|cache| cache.volunteers.get(&id)) {
+            return Ok(cached);
+        }
+
+        let volunteer = exec_with_tx!(self, exec_opts, exec, id)?;
+        if let Some(cache) = cache {
+            cache.volunteers.insert(id, volunteer.clone());
+        }
+        Ok(volunteer)
     }
 
     async fn fetch_volunteer_by_email(
@@ -402,7 +722,7 @@ impl QueryVolunteers<Postgres> for PgBackend {
             Ok(volunteers)
         }
 
-        exec_with_tx!(self, exec_opts, exec)
+        exec_read_with_tx!(self, exec_opts, exec)
     }
 
     async fn fetch_volunteers_by_cycle(
@@ -423,7 +743,36 @@ impl QueryVolunteers<Postgres> for PgBackend {
             Ok(volunteers)
         }
 
-        exec_with_tx!(self, exec_opts, exec, project_cycle_id)
+        exec_read_with_tx!(self, exec_opts, exec, project_cycle_id)
+    }
+
+    async fn fetch_volunteer_details_page(
+        &self,
+        project_cycle_id: Uuid,
+        after: Option<Uuid>,
+        limit: i64,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<VolunteerDetails>> {
+        async fn exec(
+            project_cycle_id: Uuid,
+            after: Option<Uuid>,
+            limit: i64,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<VolunteerDetails>> {
+            let query = include_str!("queries/volunteers/fetch_volunteer_details_page.sql");
+            let volunteers = sqlx::query_as::<_, VolunteerDetails>(query)
+                .bind(project_cycle_id)
+                .bind(after)
+                .bind(limit)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching page of volunteers")?;
+            Ok(volunteers)
+        }
+
+        // Exports page through volunteers while they run, so the pages are read from the primary
+        // to include volunteers imported just before the export started
+        exec_with_tx!(self, exec_opts, exec, project_cycle_id, after, limit)
     }
 
     async fn edit_volunteer(
@@ -448,7 +797,60 @@ impl QueryVolunteers<Postgres> for PgBackend {
             Ok(())
         }
 
-        exec_with_tx!(self, exec_opts, exec, id, data)
+        let res = exec_with_tx!(self, exec_opts, exec, id, data);
+        self.invalidate_cache(|cache| cache.volunteers.invalidate(&id));
+        res
+    }
+
+    async fn batch_update_volunteers(
+        &self,
+        data: Vec<UpdateVolunteer>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<Uuid>> {
+        async fn exec(
+            data: Vec<UpdateVolunteer>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<Uuid>> {
+            let query = include_str!("queries/volunteers/update_volunteer.sql");
+            let unlink_query = include_str!("queries/volunteers/unlink_volunteer_from_cycle.sql");
+
+            let mut updated = Vec::with_capacity(data.len());
+            for v in data {
+                let id = sqlx::query_scalar::<_, Uuid>(query)
+                    .bind(v.id)
+                    .bind(v.email)
+                    .bind(v.project_cycle_id)
+                    .bind(v.first_name)
+                    .bind(v.last_name)
+                    .fetch_optional(&mut **tx)
+                    .await
+                    .with_context(|| format!("error updating volunteer {}", v.id))?;
+                let Some(id) = id else {
+                    continue;
+                };
+
+                if let Some(project_cycle_id) = v.project_cycle_id {
+                    sqlx::query(unlink_query)
+                        .bind(id)
+                        .bind(project_cycle_id)
+                        .execute(&mut **tx)
+                        .await
+                        .with_context(|| format!("error unlinking volunteer {id} from cohort"))?;
+                }
+                updated.push(id);
+            }
+            Ok(updated)
+        }
+
+        let res = exec_with_tx!(self, exec_opts, exec, data);
+        if let Ok(updated) = &res {
+            self.invalidate_cache(|cache| {
+                for id in updated {
+                    cache.volunteers.invalidate(id);
+                }
+            });
+        }
+        res
     }
 
     async fn delete_volunteer(&self, id: Uuid, exec_opts: &mut ExecOpts<Postgres>) -> Result<()> {
@@ -458,7 +860,35 @@ impl QueryVolunteers<Postgres> for PgBackend {
             Ok(())
         }
 
-        exec_with_tx!(self, exec_opts, exec, id)
+        let res = exec_with_tx!(self, exec_opts, exec, id);
+        self.invalidate_cache(|cache| cache.volunteers.invalidate(&id));
+        res
+    }
+
+    async fn restore_volunteer(
+        &self,
+        id: Uuid,
+        retention_days: i32,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<bool> {
+        async fn exec(
+            id: Uuid,
+            retention_days: i32,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<bool> {
+            let query = include_str!("queries/volunteers/restore_volunteer.sql");
+            let restored = sqlx::query_scalar::<_, bool>(query)
+                .bind(id)
+                .bind(retention_days)
+                .fetch_one(&mut **tx)
+                .await
+                .context("error restoring volunteer")?;
+            Ok(restored)
+        }
+
+        let res = exec_with_tx!(self, exec_opts, exec, id, retention_days);
+        self.invalidate_cache(|cache| cache.volunteers.invalidate(&id));
+        res
     }
 
     async fn batch_link_volunteers_to_nonprofits(
@@ -491,7 +921,9 @@ impl QueryVolunteers<Postgres> for PgBackend {
             Ok(())
         }
 
-        exec_with_tx!(self, exec_opts, exec, project_cycle_id, linkage)
+        let res = exec_with_tx!(self, exec_opts, exec, project_cycle_id, linkage);
+        self.invalidate_cache(|cache| cache.volunteers.invalidate_all());
+        res
     }
 
     async fn batch_link_volunteers_to_mentors(
@@ -524,7 +956,9 @@ impl QueryVolunteers<Postgres> for PgBackend {
             Ok(())
         }
 
-        exec_with_tx!(self, exec_opts, exec, project_cycle_id, linkage)
+        let res = exec_with_tx!(self, exec_opts, exec, project_cycle_id, linkage);
+        self.invalidate_cache(|cache| cache.volunteers.invalidate_all());
+        res
     }
 
     async fn batch_insert_volunteers_exported_to_workspace(
@@ -540,6 +974,15 @@ impl QueryVolunteers<Postgres> for PgBackend {
                 "queries/volunteers/batch_insert_volunteers_exported_to_workspace.fragment.sql"
             );
 
+            // A row can only be upserted once per statement, so the last record of each account wins
+            let mut seen = HashSet::with_capacity(data.len());
+            let mut data = data
+                .into_iter()
+                .rev()
+                .filter(|v| seen.insert((v.volunteer_id, v.workspace_email.clone())))
+                .collect::<Vec<_>>();
+            data.reverse();
+
             QueryBuilder::<Postgres>::new(fragment)
                 .push_values(data, |mut b, v| {
                     b.push_bind(v.volunteer_id)
@@ -547,6 +990,10 @@ impl QueryVolunteers<Postgres> for PgBackend {
                         .push_bind(v.workspace_email)
                         .push_bind(v.org_unit);
                 })
+                .push(
+                    " on conflict (volunteer_id, workspace_email) do update set job_id = \
+                     excluded.job_id, org_unit = excluded.org_unit, deleted_at = null",
+                )
                 .build()
                 .execute(&mut **tx)
                 .await?;
@@ -554,7 +1001,9 @@ impl QueryVolunteers<Postgres> for PgBackend {
             Ok(())
         }
 
-        exec_with_tx!(self, exec_opts, exec, data)
+        let res = exec_with_tx!(self, exec_opts, exec, data);
+        self.invalidate_cache(|cache| cache.volunteers.invalidate_all());
+        res
     }
 
     async fn batch_remove_volunteers_exported_to_workspace(
@@ -571,7 +1020,9 @@ impl QueryVolunteers<Postgres> for PgBackend {
             Ok(())
         }
 
-        exec_with_tx!(self, exec_opts, exec, data)
+        let res = exec_with_tx!(self, exec_opts, exec, data);
+        self.invalidate_cache(|cache| cache.volunteers.invalidate_all());
+        res
     }
 
     async fn fetch_exported_volunteer_details_by_project_cycle(
@@ -595,4 +1046,179 @@ impl QueryVolunteers<Postgres> for PgBackend {
 
         exec_with_tx!(self, exec_opts, exec, project_cycle_id)
     }
+
+    async fn fetch_exported_volunteer_details_by_ids(
+        &self,
+        volunteer_ids: Vec<Uuid>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<ExportedVolunteerDetails>> {
+        async fn exec(
+            volunteer_ids: Vec<Uuid>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<ExportedVolunteerDetails>> {
+            let query =
+                include_str!("queries/volunteers/fetch_exported_volunteer_details_by_ids.sql");
+            let volunteers = sqlx::query_as::<_, ExportedVolunteerDetails>(query)
+                .bind(volunteer_ids)
+                .fetch_all(&mut **tx)
+                .await?;
+            Ok(volunteers)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, volunteer_ids)
+    }
+
+    async fn fetch_exported_volunteer_details_by_org_unit(
+        &self,
+        org_unit: &str,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<ExportedVolunteerDetails>> {
+        async fn exec<'b>(
+            org_unit: &'b str,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<ExportedVolunteerDetails>> {
+            let query =
+                include_str!("queries/volunteers/fetch_exported_volunteer_details_by_org_unit.sql");
+            let volunteers = sqlx::query_as::<_, ExportedVolunteerDetails>(query)
+                .bind(org_unit)
+                .fetch_all(&mut **tx)
+                .await?;
+            Ok(volunteers)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, org_unit)
+    }
+
+    async fn fetch_exported_volunteer_details_page(
+        &self,
+        filter: ExportedVolunteerFilter,
+        cursor: Option<ExportedVolunteerCursor>,
+        limit: i64,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<ExportedVolunteerDetails>> {
+        async fn exec(
+            filter: ExportedVolunteerFilter,
+            cursor: Option<ExportedVolunteerCursor>,
+            limit: i64,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<ExportedVolunteerDetails>> {
+            let query =
+                include_str!("queries/volunteers/fetch_exported_volunteer_details_page.sql");
+            let volunteers = sqlx::query_as::<_, ExportedVolunteerDetails>(query)
+                .bind(filter.job_id)
+                .bind(filter.project_cycle_id)
+                .bind(filter.exported_after)
+                .bind(filter.exported_before)
+                .bind(filter.org_unit)
+                .bind(filter.email_domain)
+                .bind(cursor.map(|c| c.created_at))
+                .bind(cursor.map(|c| c.id))
+                .bind(limit)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching exported volunteers")?;
+            Ok(volunteers)
+        }
+
+        exec_read_with_tx!(self, exec_opts, exec, filter, cursor, limit)
+    }
+
+    async fn fetch_taken_workspace_emails(
+        &self,
+        workspace_emails: Vec<String>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<String>> {
+        async fn exec(
+            workspace_emails: Vec<String>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<String>> {
+            let query = include_str!("queries/volunteers/fetch_taken_workspace_emails.sql");
+            let taken = sqlx::query_scalar::<_, String>(query)
+                .bind(workspace_emails)
+                .fetch_all(&mut **tx)
+                .await?;
+            Ok(taken)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, workspace_emails)
+    }
+
+    async fn batch_insert_workspace_aliases(
+        &self,
+        data: Vec<InsertWorkspaceAlias>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            data: Vec<InsertWorkspaceAlias>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            if data.is_empty() {
+                return Ok(());
+            }
+
+            let fragment =
+                include_str!("queries/volunteers/batch_insert_workspace_aliases.fragment.sql");
+
+            QueryBuilder::<Postgres>::new(fragment)
+                .push_values(data, |mut b, a| {
+                    b.push_bind(a.volunteer_id)
+                        .push_bind(a.job_id)
+                        .push_bind(a.workspace_email)
+                        .push_bind(a.alias);
+                })
+                .push(" on conflict (alias) do nothing")
+                .build()
+                .execute(&mut **tx)
+                .await?;
+
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, data)
+    }
+
+    async fn fetch_workspace_aliases_by_volunteer_ids(
+        &self,
+        volunteer_ids: Vec<Uuid>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<WorkspaceAlias>> {
+        async fn exec(
+            volunteer_ids: Vec<Uuid>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<WorkspaceAlias>> {
+            let query =
+                include_str!("queries/volunteers/fetch_workspace_aliases_by_volunteer_ids.sql");
+            let aliases = sqlx::query_as::<_, WorkspaceAlias>(query)
+                .bind(volunteer_ids)
+                .fetch_all(&mut **tx)
+                .await?;
+            Ok(aliases)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, volunteer_ids)
+    }
+
+    async fn fetch_profile_sync_candidates(
+        &self,
+        project_cycle_id: Uuid,
+        volunteer_ids: Option<Vec<Uuid>>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<ProfileSyncCandidate>> {
+        async fn exec(
+            project_cycle_id: Uuid,
+            volunteer_ids: Option<Vec<Uuid>>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<ProfileSyncCandidate>> {
+            let query = include_str!("queries/volunteers/fetch_profile_sync_candidates.sql");
+            let candidates = sqlx::query_as::<_, ProfileSyncCandidate>(query)
+                .bind(project_cycle_id)
+                .bind(volunteer_ids)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error fetching volunteers to sync")?;
+            Ok(candidates)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, project_cycle_id, volunteer_ids)
+    }
 }
@@ -0,0 +1,186 @@
+//! This module contains the definition of the `QueryWebhooks` trait as well as the default
+//! implementation of the trait for the `PgBackend` struct.
+//!
+//! A webhook call is queued by a trigger whenever a job finishes, so every way of finishing a job
+//! queues one. What is sent, and where, is decided by `app::api::v1::jobs::webhooks`.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Database, Postgres, Transaction};
+use uuid::Uuid;
+
+use super::entities::JobWebhook;
+use super::exec_with_tx;
+use crate::services::storage::{Acquire, ExecOpts, PgBackend};
+
+/// A trait for querying the webhook calls made when jobs finish.
+///
+/// If you implement a new storage backend, this trait is required for it to implement
+/// `StorageLayer`. The default implementation is for `Postgres`.
+#[async_trait]
+#[allow(unused)]
+pub trait QueryWebhooks<DB: Database> {
+    /// Set the URL to call when a job finishes, in place of the one configured for every job.
+    ///
+    /// * `job_id`: The id of the job
+    /// * `callback_url`: The URL, or `None` to call the one configured for every job
+    /// * `exec_opts`: Execution options for the query
+    ///
+    /// Returns whether the job exists.
+    async fn set_job_callback_url(
+        &self,
+        job_id: Uuid,
+        callback_url: Option<String>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Claim the webhook calls that are due, oldest first. Calls another worker is claiming at the
+    /// same time are skipped rather than waited for.
+    ///
+    /// * `limit`: The most calls to claim
+    /// * `claimed_until`: When the claim runs out, and the calls are due again
+    /// * `exec_opts`: Execution options for the query
+    async fn claim_due_job_webhooks(
+        &self,
+        limit: i64,
+        claimed_until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<Vec<JobWebhook>> {
+        unimplemented!()
+    }
+
+    /// Mark a webhook call as finished, so it is not made again.
+    ///
+    /// * `id`: The id of the call
+    /// * `error`: Why the call was given up on, if it did not succeed
+    /// * `exec_opts`: Execution options for the query
+    async fn finish_job_webhook(
+        &self,
+        id: Uuid,
+        error: Option<String>,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Make a webhook call that failed again later.
+    ///
+    /// * `id`: The id of the call
+    /// * `retry_at`: When to make the call again
+    /// * `error`: Why the call failed
+    /// * `exec_opts`: Execution options for the query
+    async fn retry_job_webhook(
+        &self,
+        id: Uuid,
+        retry_at: DateTime<Utc>,
+        error: String,
+        exec_opts: &mut ExecOpts<DB>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl QueryWebhooks<Postgres> for PgBackend {
+    async fn set_job_callback_url(
+        &self,
+        job_id: Uuid,
+        callback_url: Option<String>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<bool> {
+        async fn exec(
+            job_id: Uuid,
+            callback_url: Option<String>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<bool> {
+            let query = include_str!("queries/webhooks/set_job_callback_url.sql");
+            let res = sqlx::query(query)
+                .bind(job_id)
+                .bind(callback_url)
+                .execute(&mut **tx)
+                .await
+                .with_context(|| format!("error setting callback URL of job {job_id}"))?;
+            Ok(res.rows_affected() > 0)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, job_id, callback_url)
+    }
+
+    async fn claim_due_job_webhooks(
+        &self,
+        limit: i64,
+        claimed_until: DateTime<Utc>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<Vec<JobWebhook>> {
+        async fn exec(
+            limit: i64,
+            claimed_until: DateTime<Utc>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<Vec<JobWebhook>> {
+            let query = include_str!("queries/webhooks/claim_due_job_webhooks.sql");
+            let webhooks = sqlx::query_as::<_, JobWebhook>(query)
+                .bind(limit)
+                .bind(claimed_until)
+                .fetch_all(&mut **tx)
+                .await
+                .context("error claiming due job webhooks")?;
+            Ok(webhooks)
+        }
+
+        exec_with_tx!(self, exec_opts, exec, limit, claimed_until)
+    }
+
+    async fn finish_job_webhook(
+        &self,
+        id: Uuid,
+        error: Option<String>,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            id: Uuid,
+            error: Option<String>,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            let query = include_str!("queries/webhooks/finish_job_webhook.sql");
+            sqlx::query(query)
+                .bind(id)
+                .bind(error)
+                .execute(&mut **tx)
+                .await
+                .with_context(|| format!("error finishing job webhook {id}"))?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, id, error)
+    }
+
+    async fn retry_job_webhook(
+        &self,
+        id: Uuid,
+        retry_at: DateTime<Utc>,
+        error: String,
+        exec_opts: &mut ExecOpts<Postgres>,
+    ) -> Result<()> {
+        async fn exec(
+            id: Uuid,
+            retry_at: DateTime<Utc>,
+            error: String,
+            tx: &mut Transaction<'_, Postgres>,
+        ) -> Result<()> {
+            let query = include_str!("queries/webhooks/retry_job_webhook.sql");
+            sqlx::query(query)
+                .bind(id)
+                .bind(retry_at)
+                .bind(error)
+                .execute(&mut **tx)
+                .await
+                .with_context(|| format!("error retrying job webhook {id}"))?;
+            Ok(())
+        }
+
+        exec_with_tx!(self, exec_opts, exec, id, retry_at, error)
+    }
+}
@@ -0,0 +1,586 @@
+//! A `WorkspaceClient` that acts on the behalf of a pool of delegated admin accounts.
+//!
+//! Every call to Google Workspace is made on the behalf of a principal, and each principal has its
+//! own quota and may be suspended or lose its admin role. Rather than making every call as the
+//! user who requested it, calls rotate between a configured pool of delegated admins. An admin
+//! that Google refuses (because it rate limited them, or because they can no longer be
+//! impersonated) is benched for a while and the call is retried with the next admin. The
+//! requesting user is only used once every admin in the pool has failed.
+//!
+//! An admin may be scoped to an org unit, in which case they are only used for calls about that
+//! org unit (or the org units below it), and are preferred over unscoped admins for those calls.
+
+use std::cmp::Reverse;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use scipio_workspace::AccessTokenError;
+
+use super::calendar::{CalendarClient, CalendarEvent, CreateCalendarEvent};
+use super::drive::{DriveClient, DriveRole, DriveTransferStatus, SharedDrive};
+use super::entities::{
+    CreateVolunteerOutcome, CreateWorkspaceVolunteer, ProfilePhoto, TwoStepVerificationSettings,
+    UpdateWorkspaceVolunteer, WorkspaceAccount, WorkspaceOrgUnit,
+};
+use super::{WorkspaceClient, WorkspaceService};
+use crate::services::Service;
+
+/// How long an admin is benched after Google refuses them, unless configured otherwise.
+pub const ADMIN_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// A delegated admin account that calls may be made on the behalf of.
+///
+/// * `email`: The email of the admin
+/// * `org_unit`: The org unit the admin is limited to, if any
+///
+/// Admins are configured as `admin@developforgood.org`, or as
+/// `admin@developforgood.org=/Programs/2025-Spring` to limit them to an org unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DelegatedAdmin {
+    pub email: String,
+    pub org_unit: Option<String>,
+}
+
+impl DelegatedAdmin {
+    /// Whether the admin may act on an org unit.
+    ///
+    /// * `org_unit`: The full path of the org unit, or `None` if the call is not about one
+    pub fn covers(&self, org_unit: Option<&str>) -> bool {
+        match (self.org_unit.as_deref(), org_unit) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(scope), Some(path)) => {
+                scope == "/"
+                    || path == scope
+                    || path.strip_prefix(scope).is_some_and(|rest| rest.starts_with('/'))
+            }
+        }
+    }
+}
+
+impl FromStr for DelegatedAdmin {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (email, org_unit) = match s.split_once('=') {
+            Some((email, org_unit)) => (email.trim(), Some(org_unit.trim())),
+            None => (s.trim(), None),
+        };
+
+        if !email.contains('@') {
+            bail!("Delegated admin {email} is not an email address");
+        }
+        if let Some(org_unit) = org_unit {
+            if !org_unit.starts_with('/') {
+                bail!("Org unit {org_unit} of delegated admin {email} must start with '/'");
+            }
+        }
+
+        Ok(Self {
+            email: email.to_owned(),
+            org_unit: org_unit.map(|org_unit| org_unit.trim_end_matches('/').to_owned()),
+        })
+    }
+}
+
+/// An admin in the pool, along with when they may be used again if they are benched.
+struct PooledAdmin {
+    admin: DelegatedAdmin,
+    benched_until: Mutex<Option<Instant>>,
+}
+
+impl PooledAdmin {
+    fn is_benched(&self, now: Instant) -> bool {
+        let benched_until = self.benched_until.lock().expect("admin health lock poisoned");
+        benched_until.is_some_and(|until| until > now)
+    }
+
+    fn bench(&self, cooldown: Duration) {
+        let mut benched_until = self.benched_until.lock().expect("admin health lock poisoned");
+        *benched_until = Some(Instant::now() + cooldown);
+    }
+
+    fn reinstate(&self) {
+        let mut benched_until = self.benched_until.lock().expect("admin health lock poisoned");
+        *benched_until = None;
+    }
+}
+
+/// Whether an error means Google refused the admin, rather than the call itself, so the call is
+/// worth making again as someone else.
+///
+/// * `e`: The error returned by the call
+fn is_admin_refused(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| {
+        cause.downcast_ref::<AccessTokenError>().is_some()
+            || cause.downcast_ref::<reqwest::Error>().and_then(reqwest::Error::status).is_some_and(
+                |status| {
+                    status == StatusCode::UNAUTHORIZED
+                        || status == StatusCode::FORBIDDEN
+                        || status == StatusCode::TOO_MANY_REQUESTS
+                },
+            )
+    })
+}
+
+/// A workspace client that makes each call on the behalf of the admins in a pool, in turn.
+pub struct AdminPoolWorkspaceClient {
+    inner: Arc<dyn WorkspaceService>,
+    admins: Vec<PooledAdmin>,
+    next: AtomicUsize,
+    cooldown: Duration,
+}
+
+impl AdminPoolWorkspaceClient {
+    /// Create a client.
+    ///
+    /// * `inner`: The client that makes the calls
+    /// * `admins`: The delegated admins to make calls on the behalf of
+    pub fn new(inner: Arc<dyn WorkspaceService>, admins: Vec<DelegatedAdmin>) -> Result<Self> {
+        if admins.is_empty() {
+            bail!("At least one delegated admin must be provided");
+        }
+
+        Ok(Self {
+            inner,
+            admins: admins
+                .into_iter()
+                .map(|admin| PooledAdmin { admin, benched_until: Mutex::new(None) })
+                .collect(),
+            next: AtomicUsize::new(0),
+            cooldown: ADMIN_COOLDOWN,
+        })
+    }
+
+    /// Bench the admins Google refuses for `cooldown` rather than `ADMIN_COOLDOWN`.
+    ///
+    /// * `cooldown`: How long an admin is benched after Google refuses them
+    pub fn with_cooldown(self, cooldown: Duration) -> Self {
+        Self { cooldown, ..self }
+    }
+
+    /// The admins that may make a call, in the order they should be tried.
+    ///
+    /// * `org_unit`: The org unit the call is about, if any
+    ///
+    /// The starting admin rotates from call to call. Admins scoped to the org unit come before
+    /// unscoped admins, and benched admins come last.
+    fn candidates(&self, org_unit: Option<&str>) -> Vec<&PooledAdmin> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let mut eligible = (0..self.admins.len())
+            .map(|i| &self.admins[(start + i) % self.admins.len()])
+            .filter(|a| a.admin.covers(org_unit))
+            .collect::<Vec<&PooledAdmin>>();
+        eligible.sort_by_key(|a| Reverse(a.admin.org_unit.as_ref().map_or(0, String::len)));
+
+        let now = Instant::now();
+        let (healthy, benched) =
+            eligible.into_iter().partition::<Vec<&PooledAdmin>, _>(|a| !a.is_benched(now));
+        healthy.into_iter().chain(benched).collect()
+    }
+
+    /// Make a call on the behalf of each admin in turn until Google accepts one of them.
+    ///
+    /// * `label`: A description of the call, for logs
+    /// * `principal`: The user who requested the call, who is tried once every admin has failed
+    /// * `org_unit`: The org unit the call is about, if any
+    /// * `call`: Makes the call on the behalf of an admin
+    ///
+    /// Errors that are not about the admin are returned right away, since another admin would run
+    /// into them too.
+    pub(crate) async fn with_admin<T, F, Fut>(
+        &self,
+        label: &str,
+        principal: &str,
+        org_unit: Option<&str>,
+        call: F,
+    ) -> Result<T>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut errors = Vec::<String>::new();
+        for admin in self.candidates(org_unit) {
+            let email = &admin.admin.email;
+            match call(email.clone()).await {
+                Ok(value) => {
+                    admin.reinstate();
+                    return Ok(value);
+                }
+                Err(e) if is_admin_refused(&e) => {
+                    log::warn!("Google refused {email} while {label}, trying the next admin: {e}");
+                    admin.bench(self.cooldown);
+                    errors.push(format!("{email}: {e}"));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        log::warn!("Every delegated admin failed while {label}, falling back to {principal}");
+        call(principal.to_owned()).await.map_err(|e| {
+            errors.push(format!("{principal}: {e}"));
+            anyhow!("Every admin failed while {label}: {}", errors.join("; "))
+        })
+    }
+}
+
+#[async_trait]
+impl WorkspaceClient for AdminPoolWorkspaceClient {
+    async fn create_volunteer(
+        &self,
+        principal: &str,
+        volunteer: CreateWorkspaceVolunteer,
+    ) -> Result<CreateVolunteerOutcome> {
+        let label = format!("creating {}", volunteer.primary_email);
+        let org_unit = volunteer.org_unit.clone();
+        let inner = &self.inner;
+        self.with_admin(&label, principal, Some(&org_unit), |admin| {
+            let volunteer = volunteer.clone();
+            async move { inner.create_volunteer(&admin, volunteer).await }
+        })
+        .await
+    }
+
+    async fn batch_create_volunteers(
+        &self,
+        principal: &str,
+        volunteers: Vec<CreateWorkspaceVolunteer>,
+    ) -> Result<Vec<Result<CreateVolunteerOutcome>>> {
+        let label = format!("creating a batch of {} users", volunteers.len());
+        // Only an admin who covers every org unit in the batch may create it
+        let org_unit = match volunteers.split_first() {
+            Some((first, rest)) if rest.iter().all(|v| v.org_unit == first.org_unit) => {
+                Some(first.org_unit.clone())
+            }
+            _ => None,
+        };
+        let inner = &self.inner;
+        self.with_admin(&label, principal, org_unit.as_deref(), |admin| {
+            let volunteers = volunteers.clone();
+            async move { inner.batch_create_volunteers(&admin, volunteers).await }
+        })
+        .await
+    }
+
+    async fn update_volunteer(
+        &self,
+        principal: &str,
+        workspace_email: &str,
+        volunteer: UpdateWorkspaceVolunteer,
+    ) -> Result<()> {
+        let label = format!("updating {workspace_email}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| {
+            let volunteer = volunteer.clone();
+            async move { inner.update_volunteer(&admin, workspace_email, volunteer).await }
+        })
+        .await
+    }
+
+    async fn create_alias(
+        &self,
+        principal: &str,
+        workspace_email: &str,
+        alias: &str,
+    ) -> Result<()> {
+        let label = format!("adding alias {alias} to {workspace_email}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.create_alias(&admin, workspace_email, alias).await
+        })
+        .await
+    }
+
+    async fn delete_user(&self, principal: &str, email_of_user_to_delete: &str) -> Result<()> {
+        let label = format!("deleting {email_of_user_to_delete}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.delete_user(&admin, email_of_user_to_delete).await
+        })
+        .await
+    }
+
+    async fn suspend_volunteer(&self, principal: &str, workspace_email: &str) -> Result<()> {
+        let label = format!("suspending {workspace_email}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.suspend_volunteer(&admin, workspace_email).await
+        })
+        .await
+    }
+
+    async fn unsuspend_volunteer(&self, principal: &str, workspace_email: &str) -> Result<()> {
+        let label = format!("unsuspending {workspace_email}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.unsuspend_volunteer(&admin, workspace_email).await
+        })
+        .await
+    }
+
+    async fn ensure_group(&self, principal: &str, group_email: &str) -> Result<bool> {
+        let label = format!("ensuring group {group_email} exists");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.ensure_group(&admin, group_email).await
+        })
+        .await
+    }
+
+    async fn ensure_program_schema(&self, principal: &str) -> Result<bool> {
+        let inner = &self.inner;
+        self.with_admin("ensuring the program schema exists", principal, None, |admin| async move {
+            inner.ensure_program_schema(&admin).await
+        })
+        .await
+    }
+
+    async fn add_group_member(
+        &self,
+        principal: &str,
+        group_email: &str,
+        workspace_email: &str,
+    ) -> Result<()> {
+        let label = format!("adding {workspace_email} to {group_email}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.add_group_member(&admin, group_email, workspace_email).await
+        })
+        .await
+    }
+
+    async fn list_org_unit_users(
+        &self,
+        principal: &str,
+        org_unit_path: &str,
+    ) -> Result<Vec<WorkspaceAccount>> {
+        let label = format!("listing the users in {org_unit_path}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, Some(org_unit_path), |admin| async move {
+            inner.list_org_unit_users(&admin, org_unit_path).await
+        })
+        .await
+    }
+
+    async fn find_user(&self, principal: &str, email: &str) -> Result<Option<WorkspaceAccount>> {
+        let label = format!("looking up {email}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.find_user(&admin, email).await
+        })
+        .await
+    }
+
+    async fn org_unit_exists(&self, principal: &str, org_unit_path: &str) -> Result<bool> {
+        let label = format!("looking up org unit {org_unit_path}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, Some(org_unit_path), |admin| async move {
+            inner.org_unit_exists(&admin, org_unit_path).await
+        })
+        .await
+    }
+
+    async fn list_org_units(&self, principal: &str) -> Result<Vec<WorkspaceOrgUnit>> {
+        let inner = &self.inner;
+        self.with_admin("listing org units", principal, None, |admin| async move {
+            inner.list_org_units(&admin).await
+        })
+        .await
+    }
+
+    async fn create_org_unit(
+        &self,
+        principal: &str,
+        org_unit_path: &str,
+    ) -> Result<WorkspaceOrgUnit> {
+        let label = format!("creating org unit {org_unit_path}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, Some(org_unit_path), |admin| async move {
+            inner.create_org_unit(&admin, org_unit_path).await
+        })
+        .await
+    }
+
+    async fn two_step_verification_settings(
+        &self,
+        principal: &str,
+        org_unit_path: &str,
+    ) -> Result<TwoStepVerificationSettings> {
+        let label = format!("looking up the 2-Step Verification settings of {org_unit_path}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, Some(org_unit_path), |admin| async move {
+            inner.two_step_verification_settings(&admin, org_unit_path).await
+        })
+        .await
+    }
+
+    async fn count_license_assignments(
+        &self,
+        principal: &str,
+        product_id: &str,
+        sku_id: &str,
+    ) -> Result<usize> {
+        let inner = &self.inner;
+        self.with_admin("counting licenses", principal, None, |admin| async move {
+            inner.count_license_assignments(&admin, product_id, sku_id).await
+        })
+        .await
+    }
+
+    async fn license_seats(
+        &self,
+        principal: &str,
+        product_id: &str,
+        sku_id: &str,
+    ) -> Result<Option<usize>> {
+        let inner = &self.inner;
+        self.with_admin("looking up license seats", principal, None, |admin| async move {
+            inner.license_seats(&admin, product_id, sku_id).await
+        })
+        .await
+    }
+
+    async fn assign_license(
+        &self,
+        principal: &str,
+        product_id: &str,
+        sku_id: &str,
+        workspace_email: &str,
+    ) -> Result<()> {
+        let label = format!("licensing {workspace_email}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.assign_license(&admin, product_id, sku_id, workspace_email).await
+        })
+        .await
+    }
+
+    async fn set_signature(
+        &self,
+        principal: &str,
+        workspace_email: &str,
+        signature: &str,
+    ) -> Result<()> {
+        let label = format!("setting the signature of {workspace_email}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.set_signature(&admin, workspace_email, signature).await
+        })
+        .await
+    }
+
+    async fn set_profile_photo(
+        &self,
+        principal: &str,
+        workspace_email: &str,
+        photo: &ProfilePhoto,
+    ) -> Result<()> {
+        let label = format!("setting the profile photo of {workspace_email}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.set_profile_photo(&admin, workspace_email, photo).await
+        })
+        .await
+    }
+
+    async fn domain_exists(&self, principal: &str, domain: &str) -> Result<bool> {
+        let label = format!("looking up domain {domain}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.domain_exists(&admin, domain).await
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl DriveClient for AdminPoolWorkspaceClient {
+    async fn create_shared_drive(
+        &self,
+        principal: &str,
+        request_id: &str,
+        name: &str,
+    ) -> Result<Option<SharedDrive>> {
+        let label = format!("creating shared drive {name}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.create_shared_drive(&admin, request_id, name).await
+        })
+        .await
+    }
+
+    async fn add_shared_drive_member(
+        &self,
+        principal: &str,
+        drive_id: &str,
+        workspace_email: &str,
+        role: DriveRole,
+    ) -> Result<()> {
+        let label = format!("adding {workspace_email} to shared drive {drive_id}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.add_shared_drive_member(&admin, drive_id, workspace_email, role).await
+        })
+        .await
+    }
+
+    async fn start_drive_transfer(
+        &self,
+        principal: &str,
+        workspace_email: &str,
+        archive_email: &str,
+    ) -> Result<String> {
+        let label = format!("transferring the files of {workspace_email} to {archive_email}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.start_drive_transfer(&admin, workspace_email, archive_email).await
+        })
+        .await
+    }
+
+    async fn drive_transfer_status(
+        &self,
+        principal: &str,
+        transfer_id: &str,
+    ) -> Result<DriveTransferStatus> {
+        let label = format!("checking transfer {transfer_id}");
+        let inner = &self.inner;
+        self.with_admin(&label, principal, None, |admin| async move {
+            inner.drive_transfer_status(&admin, transfer_id).await
+        })
+        .await
+    }
+}
+
+// Only an event's organizer can change it, so calendar calls are always made as the principal
+#[async_trait]
+impl CalendarClient for AdminPoolWorkspaceClient {
+    async fn create_calendar_event(
+        &self,
+        principal: &str,
+        event: CreateCalendarEvent,
+    ) -> Result<CalendarEvent> {
+        self.inner.create_calendar_event(principal, event).await
+    }
+
+    async fn invite_to_calendar_event(
+        &self,
+        principal: &str,
+        event_id: &str,
+        workspace_emails: &[String],
+    ) -> Result<()> {
+        self.inner.invite_to_calendar_event(principal, event_id, workspace_emails).await
+    }
+}
+
+impl Service for AdminPoolWorkspaceClient {
+    fn get_id(&self) -> &'static str {
+        self.inner.get_id()
+    }
+}
@@ -0,0 +1,82 @@
+//! This module provides an interface for scheduling events in Google Calendar, so that exported
+//! volunteers can be invited to an onboarding session.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use scipio_workspace::calendar::Event;
+use serde::{Deserialize, Serialize};
+
+/// An event in Google Calendar.
+///
+/// * `id`: The ID of the event
+/// * `link`: A link to the event in Google Calendar
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarEvent {
+    pub id: String,
+    pub link: String,
+}
+
+impl TryFrom<Event> for CalendarEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Event) -> Result<Self> {
+        let link = value.html_link.with_context(|| format!("Event {} has no link", value.id))?;
+        Ok(Self { id: value.id, link })
+    }
+}
+
+/// Data needed to schedule an event in Google Calendar.
+///
+/// * `id`: The ID to give the event. Scheduling an event with the same ID again returns the
+///   existing event. It may only contain the lowercase letters a-v and digits.
+/// * `summary`: The title of the event
+/// * `description`: A description of the event, if any
+/// * `start`: When the event starts
+/// * `end`: When the event ends
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCalendarEvent {
+    pub id: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// A trait for scheduling events in Google Calendar.
+///
+/// Events are scheduled on the primary calendar of the `principal`, who organizes them. Unlike the
+/// other workspace calls, the principal is not interchangeable, since only the organizer can
+/// change an event.
+#[async_trait]
+#[allow(unused_variables)]
+pub trait CalendarClient: Send + Sync {
+    /// Schedule an event on the principal's calendar, or fetch it if it was already scheduled.
+    ///
+    /// * `principal`: The email of the user requesting this action
+    /// * `event`: The event to schedule
+    async fn create_calendar_event(
+        &self,
+        principal: &str,
+        event: CreateCalendarEvent,
+    ) -> Result<CalendarEvent> {
+        unimplemented!()
+    }
+
+    /// Invite volunteers to an event on the principal's calendar. Volunteers who were already
+    /// invited are not invited again.
+    ///
+    /// * `principal`: The email of the user requesting this action
+    /// * `event_id`: The ID of the event
+    /// * `workspace_emails`: The volunteers' workspace email addresses
+    async fn invite_to_calendar_event(
+        &self,
+        principal: &str,
+        event_id: &str,
+        workspace_emails: &[String],
+    ) -> Result<()> {
+        unimplemented!()
+    }
+}
@@ -0,0 +1,149 @@
+//! This module provides an interface for provisioning Google Shared Drives, so that each cohort of
+//! volunteers can be given a drive to share their work in.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use scipio_workspace::drive::Drive;
+use serde::{Deserialize, Serialize};
+
+/// A shared drive in Google Drive.
+///
+/// * `id`: The ID of the drive
+/// * `name`: The name of the drive
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedDrive {
+    pub id: String,
+    pub name: String,
+}
+
+impl From<Drive> for SharedDrive {
+    fn from(value: Drive) -> Self {
+        Self { id: value.id, name: value.name }
+    }
+}
+
+/// The role a member of a shared drive has. Complete documentation of each role may be found
+/// [here](https://developers.google.com/drive/api/guides/ref-roles).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DriveRole {
+    Organizer,
+    FileOrganizer,
+    #[default]
+    Writer,
+    Commenter,
+    Reader,
+}
+
+impl DriveRole {
+    /// The name Google uses for the role.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DriveRole::Organizer => "organizer",
+            DriveRole::FileOrganizer => "fileOrganizer",
+            DriveRole::Writer => "writer",
+            DriveRole::Commenter => "commenter",
+            DriveRole::Reader => "reader",
+        }
+    }
+}
+
+/// Where a transfer of a user's Drive files to another user stands.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DriveTransferStatus {
+    /// The files are still being transferred
+    InProgress,
+    /// Every file was transferred
+    Completed,
+    /// The transfer stopped before every file was transferred
+    Failed,
+}
+
+impl DriveTransferStatus {
+    /// The status of a transfer, from the overall status code Google reports for it (e.g.
+    /// `inProgress`). Transfers that have not reported a status yet are in progress.
+    ///
+    /// * `code`: The status code
+    pub fn from_status_code(code: Option<&str>) -> Self {
+        match code {
+            Some("completed") => DriveTransferStatus::Completed,
+            Some("failed") => DriveTransferStatus::Failed,
+            _ => DriveTransferStatus::InProgress,
+        }
+    }
+}
+
+/// A trait for provisioning shared drives in Google Drive.
+///
+/// The functions in this trait take a `principal`, with the same restrictions as
+/// `WorkspaceClient::create_volunteer`.
+#[async_trait]
+#[allow(unused_variables)]
+pub trait DriveClient: Send + Sync {
+    /// Create a shared drive.
+    ///
+    /// * `principal`: The email of the user requesting this action. They become the drive's first
+    ///   organizer.
+    /// * `request_id`: A unique ID for the request, so that retrying it does not create a second
+    ///   drive.
+    /// * `name`: The name of the drive.
+    ///
+    /// Returns `None` if a drive was already created with `request_id`.
+    async fn create_shared_drive(
+        &self,
+        principal: &str,
+        request_id: &str,
+        name: &str,
+    ) -> Result<Option<SharedDrive>> {
+        unimplemented!()
+    }
+
+    /// Give a volunteer access to a shared drive. Giving a volunteer access they already have
+    /// succeeds.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `drive_id`: The ID of the shared drive.
+    /// * `workspace_email`: The volunteer's workspace email address.
+    /// * `role`: The role to give the volunteer.
+    async fn add_shared_drive_member(
+        &self,
+        principal: &str,
+        drive_id: &str,
+        workspace_email: &str,
+        role: DriveRole,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Start transferring every Drive file a volunteer owns to another account, e.g. a program's
+    /// archive account before the volunteer is offboarded.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `workspace_email`: The volunteer's workspace email address.
+    /// * `archive_email`: The email of the account that receives the files.
+    ///
+    /// Returns the ID of the transfer, which runs in the background. Its progress can be checked
+    /// with `drive_transfer_status`.
+    async fn start_drive_transfer(
+        &self,
+        principal: &str,
+        workspace_email: &str,
+        archive_email: &str,
+    ) -> Result<String> {
+        unimplemented!()
+    }
+
+    /// Check how far a transfer of Drive files has got.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `transfer_id`: The ID returned by `start_drive_transfer`.
+    async fn drive_transfer_status(
+        &self,
+        principal: &str,
+        transfer_id: &str,
+    ) -> Result<DriveTransferStatus> {
+        unimplemented!()
+    }
+}
@@ -0,0 +1,643 @@
+//! This module defines an in-memory emulator of Google Workspace.
+//!
+//! Unlike `NoopWorkspaceClient`, which accepts everything, the emulator remembers the users,
+//! groups, org units and drives it is asked to create and rejects what Google would reject:
+//! duplicate primary emails, users in org units that do not exist, and members of groups that do
+//! not exist. It can also be told to rate limit some of the calls it receives and to answer them
+//! slowly, so exports can be exercised end to end (including their retries) without touching
+//! Google.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::DateTime;
+use derive_builder::Builder;
+use reqwest::StatusCode;
+use scipio_workspace::batch::BatchEntryError;
+
+use super::calendar::{CalendarClient, CalendarEvent, CreateCalendarEvent};
+use super::drive::{DriveClient, DriveRole, DriveTransferStatus, SharedDrive};
+use super::entities::{
+    CreateVolunteerOutcome, CreateWorkspaceVolunteer, ProfilePhoto, TwoStepVerificationSettings,
+    UpdateWorkspaceVolunteer, WorkspaceAccount, WorkspaceOrgUnit,
+};
+use super::WorkspaceClient;
+use crate::services::Service;
+
+/// How the emulator behaves.
+///
+/// * `latency`: How long every call takes to answer
+/// * `rate_limit_every`: Rate limit every nth call, counting each user in a batch as a call, like
+///   Google does. `0` never rate limits.
+/// * `existing_users`: Primary emails that already belong to someone else, so creating a volunteer
+///   with one of them fails
+/// * `org_units`: The full paths of the org units that exist, besides the root
+/// * `domains`: The verified domains of the account
+/// * `license_seats`: The number of licenses purchased for each edition, by SKU ID. Editions that
+///   are left out are not reported, like Google does not report them.
+#[derive(Debug, Clone, Builder)]
+pub struct EmulatorConfig {
+    #[builder(default)]
+    pub latency: Duration,
+    #[builder(default)]
+    pub rate_limit_every: usize,
+    #[builder(default)]
+    pub existing_users: Vec<String>,
+    #[builder(default = "vec![\"/Programs\".to_owned(), \"/Programs/PantheonUsers\".to_owned()]")]
+    pub org_units: Vec<String>,
+    #[builder(default = "vec![\"developforgood.org\".to_owned()]")]
+    pub domains: Vec<String>,
+    #[builder(default)]
+    pub license_seats: HashMap<String, usize>,
+}
+
+impl Default for EmulatorConfig {
+    fn default() -> Self {
+        EmulatorConfigBuilder::default().build().expect("every field has a default")
+    }
+}
+
+/// Everything the emulator remembers. Emails are stored lowercase, since Google compares them
+/// case-insensitively.
+#[derive(Debug, Default)]
+struct EmulatorState {
+    calls: usize,
+    users: HashMap<String, WorkspaceAccount>,
+    aliases: HashMap<String, String>,
+    org_units: BTreeSet<String>,
+    groups: HashMap<String, HashSet<String>>,
+    licenses: HashMap<(String, String), HashSet<String>>,
+    program_schema: bool,
+    drives: HashMap<String, SharedDrive>,
+    drive_members: HashMap<String, HashMap<String, DriveRole>>,
+    drive_transfers: Vec<(String, String)>,
+    events: HashMap<String, HashSet<String>>,
+    signatures: HashMap<String, String>,
+    profile_photos: HashSet<String>,
+}
+
+/// An in-memory emulator of Google Workspace.
+pub struct EmulatorWorkspaceClient {
+    config: EmulatorConfig,
+    state: Mutex<EmulatorState>,
+}
+
+/// The error Google returns for a call that failed with `status`.
+///
+/// * `status`: The HTTP status of the call
+/// * `message`: Why the call failed
+///
+/// It is the same error a failed call in a batch request returns, so the emulator's errors are
+/// retried (or treated as conflicts) exactly like Google's are.
+fn google_error(status: StatusCode, message: impl Into<String>) -> anyhow::Error {
+    anyhow!(BatchEntryError { status: status.as_u16(), message: message.into() })
+}
+
+impl EmulatorWorkspaceClient {
+    /// Create an emulator with nothing in it but the config's users and org units.
+    ///
+    /// * `config`: How the emulator behaves
+    pub fn new(config: EmulatorConfig) -> Self {
+        let users = config
+            .existing_users
+            .iter()
+            .map(|email| {
+                let account = WorkspaceAccount {
+                    primary_email: email.clone(),
+                    recovery_email: None,
+                    suspended: false,
+                    last_login_at: None,
+                    org_unit_path: "/".to_owned(),
+                };
+                (email.to_lowercase(), account)
+            })
+            .collect();
+        let org_units = config.org_units.iter().cloned().collect();
+
+        let state = EmulatorState { users, org_units, ..Default::default() };
+        Self { config, state: Mutex::new(state) }
+    }
+
+    /// Every user in the emulator, sorted by primary email.
+    pub fn users(&self) -> Vec<WorkspaceAccount> {
+        let mut users = self.lock().users.values().cloned().collect::<Vec<WorkspaceAccount>>();
+        users.sort_by(|a, b| a.primary_email.cmp(&b.primary_email));
+        users
+    }
+
+    /// The members of a group, sorted, or `None` if the group does not exist.
+    ///
+    /// * `group_email`: The email address of the group
+    pub fn group_members(&self, group_email: &str) -> Option<Vec<String>> {
+        let state = self.lock();
+        let mut members =
+            state.groups.get(&group_email.to_lowercase())?.iter().cloned().collect::<Vec<String>>();
+        members.sort();
+        Some(members)
+    }
+
+    /// The Gmail signature of a user, or `None` if none was set.
+    ///
+    /// * `workspace_email`: The primary email of the user
+    pub fn signature(&self, workspace_email: &str) -> Option<String> {
+        self.lock().signatures.get(&workspace_email.to_lowercase()).cloned()
+    }
+
+    /// The account a user's Drive files were transferred to, or `None` if they were not
+    /// transferred.
+    ///
+    /// * `workspace_email`: The primary email of the user
+    pub fn drive_transferred_to(&self, workspace_email: &str) -> Option<String> {
+        let key = workspace_email.to_lowercase();
+        self.lock()
+            .drive_transfers
+            .iter()
+            .rev()
+            .find(|(from, _)| *from == key)
+            .map(|(_, to)| to.clone())
+    }
+
+    /// Whether a user was given a profile photo.
+    ///
+    /// * `workspace_email`: The primary email of the user
+    pub fn has_profile_photo(&self, workspace_email: &str) -> bool {
+        self.lock().profile_photos.contains(&workspace_email.to_lowercase())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, EmulatorState> {
+        // A panic while the lock is held cannot leave the state half updated, so a poisoned lock
+        // is still safe to use
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wait out the configured latency, then count a call, failing it if it is rate limited.
+    async fn call(&self) -> Result<MutexGuard<'_, EmulatorState>> {
+        if !self.config.latency.is_zero() {
+            tokio::time::sleep(self.config.latency).await;
+        }
+
+        let mut state = self.lock();
+        Self::count_call(&self.config, &mut state)?;
+        Ok(state)
+    }
+
+    /// Count a call, failing it if it is rate limited.
+    fn count_call(config: &EmulatorConfig, state: &mut EmulatorState) -> Result<()> {
+        state.calls += 1;
+        if config.rate_limit_every > 0 && state.calls % config.rate_limit_every == 0 {
+            return Err(google_error(StatusCode::TOO_MANY_REQUESTS, "Rate Limit Exceeded"));
+        }
+        Ok(())
+    }
+
+    /// Create a user, the way a single create request does.
+    fn create_user(
+        state: &mut EmulatorState,
+        volunteer: CreateWorkspaceVolunteer,
+    ) -> Result<CreateVolunteerOutcome> {
+        let key = volunteer.primary_email.to_lowercase();
+
+        if state.users.contains_key(&key) || state.aliases.contains_key(&key) {
+            return Err(google_error(StatusCode::CONFLICT, "Entity already exists."));
+        }
+
+        if volunteer.org_unit != "/" && !state.org_units.contains(&volunteer.org_unit) {
+            return Err(google_error(StatusCode::BAD_REQUEST, "Invalid Input: INVALID_OU_ID"));
+        }
+
+        let account = WorkspaceAccount {
+            primary_email: volunteer.primary_email,
+            recovery_email: Some(volunteer.recovery_email),
+            suspended: false,
+            last_login_at: None,
+            org_unit_path: volunteer.org_unit,
+        };
+        state.users.insert(key, account);
+
+        Ok(CreateVolunteerOutcome::Created)
+    }
+
+    /// Find a user by their primary email, failing like Google does if there is none.
+    fn user_mut<'a>(
+        state: &'a mut EmulatorState,
+        workspace_email: &str,
+    ) -> Result<&'a mut WorkspaceAccount> {
+        state
+            .users
+            .get_mut(&workspace_email.to_lowercase())
+            .ok_or_else(|| google_error(StatusCode::NOT_FOUND, "Resource Not Found: userKey"))
+    }
+}
+
+#[async_trait]
+impl WorkspaceClient for EmulatorWorkspaceClient {
+    async fn create_volunteer(
+        &self,
+        _principal: &str,
+        volunteer: CreateWorkspaceVolunteer,
+    ) -> Result<CreateVolunteerOutcome> {
+        let mut state = self.call().await?;
+
+        if let Some(existing) = state.users.get(&volunteer.primary_email.to_lowercase()) {
+            let same_owner = existing
+                .recovery_email
+                .as_ref()
+                .is_some_and(|email| email.eq_ignore_ascii_case(&volunteer.recovery_email));
+            if !same_owner {
+                return Err(anyhow!(
+                    "{} already belongs to another Workspace user",
+                    volunteer.primary_email
+                ));
+            }
+            return Ok(CreateVolunteerOutcome::AlreadyExists);
+        }
+
+        Self::create_user(&mut state, volunteer)
+    }
+
+    async fn batch_create_volunteers(
+        &self,
+        _principal: &str,
+        volunteers: Vec<CreateWorkspaceVolunteer>,
+    ) -> Result<Vec<Result<CreateVolunteerOutcome>>> {
+        if !self.config.latency.is_zero() {
+            tokio::time::sleep(self.config.latency).await;
+        }
+
+        let mut state = self.lock();
+        let results = volunteers
+            .into_iter()
+            .map(|volunteer| {
+                Self::count_call(&self.config, &mut state)?;
+                Self::create_user(&mut state, volunteer)
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    async fn update_volunteer(
+        &self,
+        _principal: &str,
+        workspace_email: &str,
+        volunteer: UpdateWorkspaceVolunteer,
+    ) -> Result<()> {
+        let mut state = self.call().await?;
+        Self::user_mut(&mut state, workspace_email)?.recovery_email =
+            Some(volunteer.recovery_email);
+        Ok(())
+    }
+
+    async fn create_alias(
+        &self,
+        _principal: &str,
+        workspace_email: &str,
+        alias: &str,
+    ) -> Result<()> {
+        let mut state = self.call().await?;
+        let primary = Self::user_mut(&mut state, workspace_email)?.primary_email.to_lowercase();
+
+        let key = alias.to_lowercase();
+        match state.aliases.get(&key) {
+            Some(owner) if *owner == primary => Ok(()),
+            Some(_) => Err(google_error(StatusCode::CONFLICT, "Entity already exists.")),
+            None if state.users.contains_key(&key) => {
+                Err(google_error(StatusCode::CONFLICT, "Entity already exists."))
+            }
+            None => {
+                state.aliases.insert(key, primary);
+                Ok(())
+            }
+        }
+    }
+
+    async fn delete_user(&self, _principal: &str, email_of_user_to_delete: &str) -> Result<()> {
+        let mut state = self.call().await?;
+        let key = email_of_user_to_delete.to_lowercase();
+
+        if state.users.remove(&key).is_none() {
+            return Err(google_error(StatusCode::NOT_FOUND, "Resource Not Found: userKey"));
+        }
+        state.aliases.retain(|_, owner| *owner != key);
+        for members in state.groups.values_mut() {
+            members.remove(&key);
+        }
+
+        Ok(())
+    }
+
+    async fn suspend_volunteer(&self, _principal: &str, workspace_email: &str) -> Result<()> {
+        let mut state = self.call().await?;
+        Self::user_mut(&mut state, workspace_email)?.suspended = true;
+        Ok(())
+    }
+
+    async fn unsuspend_volunteer(&self, _principal: &str, workspace_email: &str) -> Result<()> {
+        let mut state = self.call().await?;
+        Self::user_mut(&mut state, workspace_email)?.suspended = false;
+        Ok(())
+    }
+
+    async fn ensure_group(&self, _principal: &str, group_email: &str) -> Result<bool> {
+        let mut state = self.call().await?;
+        let key = group_email.to_lowercase();
+
+        if state.groups.contains_key(&key) {
+            return Ok(false);
+        }
+        state.groups.insert(key, HashSet::new());
+        Ok(true)
+    }
+
+    async fn ensure_program_schema(&self, _principal: &str) -> Result<bool> {
+        let mut state = self.call().await?;
+        let created = !state.program_schema;
+        state.program_schema = true;
+        Ok(created)
+    }
+
+    async fn add_group_member(
+        &self,
+        _principal: &str,
+        group_email: &str,
+        workspace_email: &str,
+    ) -> Result<()> {
+        let mut state = self.call().await?;
+        let member = Self::user_mut(&mut state, workspace_email)?.primary_email.to_lowercase();
+
+        let Some(members) = state.groups.get_mut(&group_email.to_lowercase()) else {
+            return Err(google_error(StatusCode::NOT_FOUND, "Resource Not Found: groupKey"));
+        };
+        members.insert(member);
+        Ok(())
+    }
+
+    async fn list_org_unit_users(
+        &self,
+        _principal: &str,
+        org_unit_path: &str,
+    ) -> Result<Vec<WorkspaceAccount>> {
+        let state = self.call().await?;
+
+        let prefix = format!("{}/", org_unit_path.trim_end_matches('/'));
+        let mut users = state
+            .users
+            .values()
+            .filter(|u| {
+                org_unit_path == "/"
+                    || u.org_unit_path == org_unit_path
+                    || u.org_unit_path.starts_with(&prefix)
+            })
+            .cloned()
+            .collect::<Vec<WorkspaceAccount>>();
+        users.sort_by(|a, b| a.primary_email.cmp(&b.primary_email));
+
+        Ok(users)
+    }
+
+    async fn find_user(&self, _principal: &str, email: &str) -> Result<Option<WorkspaceAccount>> {
+        let state = self.call().await?;
+
+        let key = email.to_lowercase();
+        let primary = state.aliases.get(&key).unwrap_or(&key);
+        Ok(state.users.get(primary).cloned())
+    }
+
+    async fn org_unit_exists(&self, _principal: &str, org_unit_path: &str) -> Result<bool> {
+        let state = self.call().await?;
+        Ok(org_unit_path == "/" || state.org_units.contains(org_unit_path))
+    }
+
+    async fn list_org_units(&self, _principal: &str) -> Result<Vec<WorkspaceOrgUnit>> {
+        let state = self.call().await?;
+
+        let org_units = state
+            .org_units
+            .iter()
+            .map(|path| WorkspaceOrgUnit {
+                org_unit_path: path.clone(),
+                name: path.rsplit('/').next().unwrap_or_default().to_owned(),
+                description: None,
+            })
+            .collect();
+
+        Ok(org_units)
+    }
+
+    async fn create_org_unit(
+        &self,
+        _principal: &str,
+        org_unit_path: &str,
+    ) -> Result<WorkspaceOrgUnit> {
+        let mut state = self.call().await?;
+
+        let (parent, name) = org_unit_path.rsplit_once('/').unwrap_or(("", org_unit_path));
+        if !parent.is_empty() && !state.org_units.contains(parent) {
+            return Err(google_error(StatusCode::BAD_REQUEST, "Invalid Parent Orgunit Id"));
+        }
+        if !state.org_units.insert(org_unit_path.to_owned()) {
+            return Err(google_error(StatusCode::BAD_REQUEST, "Invalid Ou Id"));
+        }
+
+        Ok(WorkspaceOrgUnit {
+            org_unit_path: org_unit_path.to_owned(),
+            name: name.to_owned(),
+            description: None,
+        })
+    }
+
+    async fn two_step_verification_settings(
+        &self,
+        _principal: &str,
+        _org_unit_path: &str,
+    ) -> Result<TwoStepVerificationSettings> {
+        let _ = self.call().await?;
+
+        Ok(TwoStepVerificationSettings {
+            allow_enrollment: true,
+            enforced_from: Some(DateTime::UNIX_EPOCH),
+            enrollment_grace_period: Some(Duration::from_secs(7 * 24 * 60 * 60)),
+        })
+    }
+
+    async fn count_license_assignments(
+        &self,
+        _principal: &str,
+        product_id: &str,
+        sku_id: &str,
+    ) -> Result<usize> {
+        let state = self.call().await?;
+
+        let key = (product_id.to_owned(), sku_id.to_owned());
+        Ok(state.licenses.get(&key).map_or(0, HashSet::len))
+    }
+
+    async fn license_seats(
+        &self,
+        _principal: &str,
+        _product_id: &str,
+        sku_id: &str,
+    ) -> Result<Option<usize>> {
+        let _ = self.call().await?;
+        Ok(self.config.license_seats.get(sku_id).copied())
+    }
+
+    async fn assign_license(
+        &self,
+        _principal: &str,
+        product_id: &str,
+        sku_id: &str,
+        workspace_email: &str,
+    ) -> Result<()> {
+        let mut state = self.call().await?;
+        let user = Self::user_mut(&mut state, workspace_email)?.primary_email.to_lowercase();
+
+        let key = (product_id.to_owned(), sku_id.to_owned());
+        state.licenses.entry(key).or_default().insert(user);
+        Ok(())
+    }
+
+    async fn set_signature(
+        &self,
+        _principal: &str,
+        workspace_email: &str,
+        signature: &str,
+    ) -> Result<()> {
+        let mut state = self.call().await?;
+        Self::user_mut(&mut state, workspace_email)?;
+        state.signatures.insert(workspace_email.to_lowercase(), signature.to_owned());
+        Ok(())
+    }
+
+    async fn set_profile_photo(
+        &self,
+        _principal: &str,
+        workspace_email: &str,
+        _photo: &ProfilePhoto,
+    ) -> Result<()> {
+        let mut state = self.call().await?;
+        Self::user_mut(&mut state, workspace_email)?;
+        state.profile_photos.insert(workspace_email.to_lowercase());
+        Ok(())
+    }
+
+    async fn domain_exists(&self, _principal: &str, domain: &str) -> Result<bool> {
+        let _ = self.call().await?;
+        Ok(self.config.domains.iter().any(|d| d.eq_ignore_ascii_case(domain)))
+    }
+}
+
+#[async_trait]
+impl DriveClient for EmulatorWorkspaceClient {
+    async fn create_shared_drive(
+        &self,
+        _principal: &str,
+        request_id: &str,
+        name: &str,
+    ) -> Result<Option<SharedDrive>> {
+        let mut state = self.call().await?;
+
+        if state.drives.contains_key(request_id) {
+            return Ok(None);
+        }
+
+        let drive = SharedDrive { id: request_id.to_owned(), name: name.to_owned() };
+        state.drives.insert(request_id.to_owned(), drive.clone());
+        Ok(Some(drive))
+    }
+
+    async fn add_shared_drive_member(
+        &self,
+        _principal: &str,
+        drive_id: &str,
+        workspace_email: &str,
+        role: DriveRole,
+    ) -> Result<()> {
+        let mut state = self.call().await?;
+        let member = Self::user_mut(&mut state, workspace_email)?.primary_email.to_lowercase();
+
+        if !state.drives.contains_key(drive_id) {
+            return Err(google_error(StatusCode::NOT_FOUND, "Shared drive not found"));
+        }
+        state.drive_members.entry(drive_id.to_owned()).or_default().insert(member, role);
+        Ok(())
+    }
+
+    // Transfers finish as soon as they start, so their status is always `Completed`
+    async fn start_drive_transfer(
+        &self,
+        _principal: &str,
+        workspace_email: &str,
+        archive_email: &str,
+    ) -> Result<String> {
+        let mut state = self.call().await?;
+        let from = Self::user_mut(&mut state, workspace_email)?.primary_email.to_lowercase();
+        let to = Self::user_mut(&mut state, archive_email)?.primary_email.to_lowercase();
+
+        state.drive_transfers.push((from, to));
+        Ok(format!("transfer-{}", state.drive_transfers.len()))
+    }
+
+    async fn drive_transfer_status(
+        &self,
+        _principal: &str,
+        transfer_id: &str,
+    ) -> Result<DriveTransferStatus> {
+        let state = self.call().await?;
+        let exists = transfer_id
+            .strip_prefix("transfer-")
+            .and_then(|n| n.parse::<usize>().ok())
+            .is_some_and(|n| n > 0 && n <= state.drive_transfers.len());
+
+        if !exists {
+            return Err(google_error(StatusCode::NOT_FOUND, "Transfer not found"));
+        }
+        Ok(DriveTransferStatus::Completed)
+    }
+}
+
+#[async_trait]
+impl CalendarClient for EmulatorWorkspaceClient {
+    async fn create_calendar_event(
+        &self,
+        _principal: &str,
+        event: CreateCalendarEvent,
+    ) -> Result<CalendarEvent> {
+        let mut state = self.call().await?;
+
+        if state.events.contains_key(&event.id) {
+            return Err(google_error(
+                StatusCode::CONFLICT,
+                "The requested identifier already exists.",
+            ));
+        }
+
+        state.events.insert(event.id.clone(), HashSet::new());
+        let link = format!("https://calendar.google.com/calendar/event?eid={}", event.id);
+        Ok(CalendarEvent { id: event.id, link })
+    }
+
+    async fn invite_to_calendar_event(
+        &self,
+        _principal: &str,
+        event_id: &str,
+        workspace_emails: &[String],
+    ) -> Result<()> {
+        let mut state = self.call().await?;
+
+        let Some(attendees) = state.events.get_mut(event_id) else {
+            return Err(google_error(StatusCode::NOT_FOUND, "Not Found"));
+        };
+        attendees.extend(workspace_emails.iter().map(|e| e.to_lowercase()));
+        Ok(())
+    }
+}
+
+impl Service for EmulatorWorkspaceClient {
+    fn get_id(&self) -> &'static str {
+        "emulator"
+    }
+}
@@ -1,6 +1,23 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+
+use chrono::{DateTime, Utc};
 use derive_builder::Builder;
-use scipio_workspace::user::{CreateWorkspaceUser, CreateWorkspaceUserBuilder, UserNameBuilder};
+use scipio_workspace::org_unit::OrgUnit;
+use scipio_workspace::policy::{
+    effective_policy, Policy, TWO_STEP_VERIFICATION_ENFORCEMENT, TWO_STEP_VERIFICATION_ENROLLMENT,
+    TWO_STEP_VERIFICATION_GRACE_PERIOD,
+};
+use scipio_workspace::user::{
+    CreateWorkspaceUser, CreateWorkspaceUserBuilder, UpdateWorkspaceUser,
+    UpdateWorkspaceUserBuilder, UserNameBuilder, WorkspaceUser,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Builder)]
 pub struct CreateWorkspaceVolunteer {
@@ -14,6 +31,276 @@ pub struct CreateWorkspaceVolunteer {
     pub password: String,
     #[builder(setter(into))]
     pub recovery_email: String,
+    // Google only accepts recovery phones in E.164 format, see `recovery_phone`
+    #[builder(default)]
+    #[serde(default)]
+    pub recovery_phone: Option<String>,
+    #[builder(setter(into))]
+    pub org_unit: String,
+    #[builder(default = "true")]
+    pub change_password_at_next_login: bool,
+    // Google does not take aliases when an account is created, so they are added once it exists
+    #[builder(default)]
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[builder(default)]
+    #[serde(default)]
+    pub program: Option<ProgramMetadata>,
+}
+
+/// A volunteer's phone number in the E.164 format Google Workspace requires of recovery phones
+/// (e.g. `+12025550123`), or `None` if it cannot be converted.
+///
+/// * `phone`: The phone number the volunteer gave, which may contain spaces, dashes, dots, and
+///   parentheses
+/// * `country`: The country the volunteer lives in. Numbers without a `+` country code are only
+///   converted for volunteers in the United States.
+pub fn recovery_phone(phone: &str, country: &str) -> Option<String> {
+    let phone = phone.trim();
+    let international = phone.starts_with('+');
+    let number = phone.strip_prefix('+').unwrap_or(phone);
+    if !number.chars().all(|c| c.is_ascii_digit() || " -.()".contains(c)) {
+        return None;
+    }
+
+    let digits = number.chars().filter(char::is_ascii_digit).collect::<String>();
+    let in_us =
+        matches!(country.trim(), "United States" | "United States of America" | "US" | "USA");
+    let e164 = match digits.len() {
+        _ if international => format!("+{digits}"),
+        10 if in_us => format!("+1{digits}"),
+        11 if in_us && digits.starts_with('1') => format!("+{digits}"),
+        _ => return None,
+    };
+
+    // E.164 numbers have at most 15 digits, and country codes never start with 0
+    if !(8..=15).contains(&(e164.len() - 1)) || e164.starts_with("+0") {
+        return None;
+    }
+
+    Some(e164)
+}
+
+/// The name of the custom user schema that holds a volunteer's program metadata in Google
+/// Workspace.
+pub const PROGRAM_SCHEMA_NAME: &str = "Program";
+
+/// Program metadata stored on a volunteer's Google Workspace account, so that Workspace admins can
+/// filter users by program and cohort without consulting Pantheon.
+///
+/// * `program_name`: The name of the program the volunteer is part of
+/// * `cohort`: The cohort the volunteer belongs to (the name of their project cycle)
+/// * `volunteer_id`: The volunteer's ID in Pantheon
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ProgramMetadata {
+    pub program_name: String,
+    pub cohort: String,
+    pub volunteer_id: Uuid,
+}
+
+impl ProgramMetadata {
+    /// The metadata as the `customSchemas` of a Google Workspace user.
+    pub fn custom_schemas(&self) -> Map<String, Value> {
+        let mut schemas = Map::new();
+        schemas.insert(
+            PROGRAM_SCHEMA_NAME.to_owned(),
+            json!({
+                "programName": self.program_name,
+                "cohort": self.cohort,
+                "volunteerId": self.volunteer_id.to_string(),
+            }),
+        );
+        schemas
+    }
+}
+
+/// Profile details of a volunteer to push to their Google Workspace account.
+///
+/// * `first_name`: The volunteer's first name
+/// * `last_name`: The volunteer's last name
+/// * `recovery_email`: The volunteer's personal email, used to recover their account
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UpdateWorkspaceVolunteer {
+    pub first_name: String,
+    pub last_name: String,
+    pub recovery_email: String,
+}
+
+impl TryFrom<UpdateWorkspaceVolunteer> for UpdateWorkspaceUser {
+    type Error = anyhow::Error;
+
+    fn try_from(value: UpdateWorkspaceVolunteer) -> std::result::Result<Self, Self::Error> {
+        let user = UpdateWorkspaceUserBuilder::default()
+            .name(
+                UserNameBuilder::default()
+                    .given_name(value.first_name)
+                    .family_name(value.last_name)
+                    .build()?,
+            )
+            .recovery_email(value.recovery_email)
+            .build()?;
+
+        Ok(user)
+    }
+}
+
+/// What happened when a volunteer was exported to Google Workspace.
+///
+/// * `Created`: A new account was created for the volunteer
+/// * `AlreadyExists`: The volunteer already had an account with the same primary and recovery
+///   email, so nothing was created. This usually means an earlier attempt succeeded but its
+///   response was lost.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum CreateVolunteerOutcome {
+    Created,
+    AlreadyExists,
+}
+
+/// A photo to give volunteers' accounts as their profile photo.
+///
+/// * `data`: The contents of the photo file
+/// * `content_type`: The MIME type of the photo (e.g. `image/jpeg`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfilePhoto {
+    pub data: Vec<u8>,
+    pub content_type: &'static str,
+}
+
+impl ProfilePhoto {
+    /// Read a profile photo from a file. Its type is taken from its extension, and must be one
+    /// that both Google Workspace and Microsoft 365 accept (JPEG, PNG, or GIF).
+    ///
+    /// * `path`: The path of the photo
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+        let content_type = match extension.as_deref() {
+            Some("jpg" | "jpeg") => "image/jpeg",
+            Some("png") => "image/png",
+            Some("gif") => "image/gif",
+            _ => bail!("{} is not a JPEG, PNG, or GIF", path.display()),
+        };
+
+        let data = fs::read(path)
+            .with_context(|| format!("Could not read profile photo {}", path.display()))?;
+        if data.is_empty() {
+            bail!("Profile photo {} is empty", path.display());
+        }
+
+        Ok(Self { data, content_type })
+    }
+}
+
+/// A user that already exists in Google Workspace.
+///
+/// * `primary_email`: The user's primary email
+/// * `recovery_email`: The user's recovery email, if they have one
+/// * `suspended`: Whether the user is suspended
+/// * `last_login_at`: When the user last signed in, or `None` if they never have
+/// * `org_unit_path`: The full path of the org unit the user is in
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorkspaceAccount {
+    pub primary_email: String,
+    pub recovery_email: Option<String>,
+    pub suspended: bool,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub org_unit_path: String,
+}
+
+/// An org unit in Google Workspace.
+///
+/// * `org_unit_path`: The full path of the org unit (e.g. `/Programs/2025-Spring`)
+/// * `name`: The name of the org unit, which is the last part of its path
+/// * `description`: The description of the org unit, if it has one
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceOrgUnit {
+    pub org_unit_path: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl From<OrgUnit> for WorkspaceOrgUnit {
+    fn from(value: OrgUnit) -> Self {
+        Self {
+            org_unit_path: value.org_unit_path,
+            name: value.name,
+            description: value.description,
+        }
+    }
+}
+
+/// The 2-Step Verification settings that apply to the users of an org unit.
+///
+/// * `allow_enrollment`: Whether users can enroll in 2-Step Verification
+/// * `enforced_from`: When 2-Step Verification is (or will be) enforced, if it is
+/// * `enrollment_grace_period`: How long new users have to enroll before 2-Step Verification is
+///   enforced for them, if they are given any time
+#[derive(Debug, Clone, PartialEq)]
+pub struct TwoStepVerificationSettings {
+    pub allow_enrollment: bool,
+    pub enforced_from: Option<DateTime<Utc>>,
+    pub enrollment_grace_period: Option<Duration>,
+}
+
+impl TwoStepVerificationSettings {
+    /// Work out the settings that apply to an org unit from the policies of the Workspace account.
+    ///
+    /// * `policies`: The 2-Step Verification policies of the account
+    /// * `org_unit_ids`: The IDs of the org unit and its ancestors, nearest first
+    ///
+    /// Settings that no policy sets have Google's defaults: enrollment is allowed, but not
+    /// enforced.
+    pub fn from_policies(policies: &[Policy], org_unit_ids: &[String]) -> Self {
+        let value = |setting_type: &str, field: &str| {
+            effective_policy(policies, setting_type, org_unit_ids)
+                .and_then(|p| p.setting.value.get(field).cloned())
+        };
+
+        let allow_enrollment = value(TWO_STEP_VERIFICATION_ENROLLMENT, "allowEnrollment")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(true);
+        let enforced_from = value(TWO_STEP_VERIFICATION_ENFORCEMENT, "enforcedFrom")
+            .and_then(|v| v.as_str().map(str::to_owned))
+            .and_then(|v| DateTime::parse_from_rfc3339(&v).ok())
+            .map(|v| v.with_timezone(&Utc));
+        let enrollment_grace_period =
+            value(TWO_STEP_VERIFICATION_GRACE_PERIOD, "enrollmentGracePeriod")
+                .and_then(|v| v.as_str().and_then(parse_policy_duration))
+                .filter(|period| !period.is_zero());
+
+        Self { allow_enrollment, enforced_from, enrollment_grace_period }
+    }
+}
+
+/// Parse a duration in a policy setting, which Google formats as seconds with an `s` suffix (e.g.
+/// `604800s`).
+///
+/// * `duration`: The duration
+pub fn parse_policy_duration(duration: &str) -> Option<Duration> {
+    let seconds = duration.strip_suffix('s')?.parse::<f64>().ok()?;
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// Parse the last login time Google Workspace reports for a user.
+///
+/// * `last_login_time`: The time, as an RFC 3339 timestamp
+///
+/// Google reports the UNIX epoch for users who have never signed in, which is treated as `None`.
+pub fn parse_last_login_time(last_login_time: Option<&str>) -> Option<DateTime<Utc>> {
+    let time = DateTime::parse_from_rfc3339(last_login_time?).ok()?.with_timezone(&Utc);
+    (time.timestamp() > 0).then_some(time)
+}
+
+impl From<WorkspaceUser> for WorkspaceAccount {
+    fn from(value: WorkspaceUser) -> Self {
+        Self {
+            last_login_at: parse_last_login_time(value.last_login_time.as_deref()),
+            primary_email: value.primary_email,
+            recovery_email: value.recovery_email,
+            suspended: value.suspended,
+            org_unit_path: value.org_unit_path,
+        }
+    }
 }
 
 impl TryFrom<CreateWorkspaceVolunteer> for CreateWorkspaceUser {
@@ -28,10 +315,12 @@ impl TryFrom<CreateWorkspaceVolunteer> for CreateWorkspaceUser {
                     .build()?,
             )
             .password(value.password)
-            .change_password_at_next_login(true)
+            .change_password_at_next_login(value.change_password_at_next_login)
             .primary_email(value.primary_email)
             .recovery_email(value.recovery_email)
-            .org_unit_path("/Programs/PantheonUsers".to_owned())
+            .recovery_phone(value.recovery_phone)
+            .org_unit_path(value.org_unit)
+            .custom_schemas(value.program.as_ref().map(ProgramMetadata::custom_schemas))
             .build()?;
 
         Ok(user)
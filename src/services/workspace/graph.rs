@@ -0,0 +1,742 @@
+//! This module contains a client that provisions volunteers in Microsoft 365 (Entra ID) with the
+//! Microsoft Graph API, so organizations on Microsoft 365 can use the same export pipeline as
+//! those on Google Workspace.
+//!
+//! The client authenticates as an app registration with the client credentials flow, so it needs
+//! the `User.ReadWrite.All`, `Group.ReadWrite.All`, `Domain.Read.All` and
+//! `LicenseAssignment.ReadWrite.All` application permissions. Calls are made as the app rather
+//! than on the behalf of the principal, who is only logged.
+//!
+//! Entra ID has no org units, so every user lives in the root (`/`) and exports must target it. A
+//! volunteer's recovery email is stored as their alternate email (`otherMails`). Aliases, custom
+//! schemas, shared drives and calendar events are not supported, so exports that ask for them
+//! fail before anyone is created.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, Method, Response, StatusCode};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, RequestBuilder};
+use reqwest_retry::policies::ExponentialBackoff;
+use reqwest_retry::RetryTransientMiddleware;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use super::calendar::{CalendarClient, CalendarEvent, CreateCalendarEvent};
+use super::drive::{DriveClient, DriveRole, DriveTransferStatus, SharedDrive};
+use super::entities::{
+    CreateVolunteerOutcome, CreateWorkspaceVolunteer, ProfilePhoto, TwoStepVerificationSettings,
+    UpdateWorkspaceVolunteer, WorkspaceAccount, WorkspaceOrgUnit,
+};
+use super::WorkspaceClient;
+use crate::services::Service;
+
+/// The base URL of the Microsoft Graph API.
+pub const GRAPH_API_URL: &str = "https://graph.microsoft.com/v1.0";
+
+/// The country licenses are used in if none is configured. Microsoft will not license a user
+/// without one.
+pub const DEFAULT_USAGE_LOCATION: &str = "US";
+
+/// The user properties to fetch from Microsoft Graph.
+const USER_SELECT: &str = "id,userPrincipalName,otherMails,accountEnabled";
+
+/// How long before it expires to stop using an access token, so that it does not expire mid-call.
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+/// The error Microsoft Graph returns for a call it did not accept.
+///
+/// * `status`: The HTTP status of the response
+/// * `code`: Microsoft's code for the error (e.g. `Request_BadRequest`)
+/// * `message`: Microsoft's explanation of the error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphError {
+    pub status: u16,
+    pub code: String,
+    pub message: String,
+}
+
+impl GraphError {
+    /// Whether the call failed because the object it creates already exists. Graph reports most
+    /// of these as a 400 rather than a 409.
+    pub fn is_conflict(&self) -> bool {
+        self.status == StatusCode::CONFLICT.as_u16()
+            || (self.status == StatusCode::BAD_REQUEST.as_u16()
+                && self.message.to_lowercase().contains("already exist"))
+    }
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Microsoft Graph returned {} ({}): {}", self.status, self.code, self.message)
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+/// Turn an unsuccessful response into a `GraphError`.
+///
+/// * `res`: The response
+async fn graph_error(res: Response) -> GraphError {
+    let status = res.status().as_u16();
+    let body = res.text().await.unwrap_or_default();
+
+    let error = serde_json::from_str::<Value>(&body).ok().and_then(|v| v.get("error").cloned());
+    let field = |name: &str| {
+        error.as_ref().and_then(|e| e.get(name)?.as_str().map(str::to_owned)).unwrap_or_default()
+    };
+
+    let message = field("message");
+    GraphError {
+        status,
+        code: field("code"),
+        message: if message.is_empty() { body } else { message },
+    }
+}
+
+/// A user in Entra ID.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphUser {
+    id: String,
+    user_principal_name: String,
+    #[serde(default)]
+    other_mails: Vec<String>,
+    #[serde(default)]
+    account_enabled: bool,
+}
+
+impl From<GraphUser> for WorkspaceAccount {
+    fn from(value: GraphUser) -> Self {
+        Self {
+            primary_email: value.user_principal_name,
+            recovery_email: value.other_mails.into_iter().next(),
+            suspended: !value.account_enabled,
+            // Reading sign-in activity needs an Entra ID P1 license, so it is not fetched
+            last_login_at: None,
+            org_unit_path: "/".to_owned(),
+        }
+    }
+}
+
+/// A page of a collection returned by Microsoft Graph.
+#[derive(Debug, Deserialize)]
+struct GraphPage<T> {
+    value: Vec<T>,
+    #[serde(rename = "@odata.nextLink")]
+    next_link: Option<String>,
+}
+
+/// An access token and when it stops being usable.
+struct AccessToken {
+    token: String,
+    expires_at: Instant,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+/// The body of a request to create a user in Entra ID. The volunteer's recovery phone is stored as
+/// their mobile phone, which self-service password reset can text.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateGraphUser {
+    pub account_enabled: bool,
+    pub display_name: String,
+    pub given_name: String,
+    pub surname: String,
+    pub mail_nickname: String,
+    pub user_principal_name: String,
+    pub other_mails: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mobile_phone: Option<String>,
+    pub usage_location: String,
+    pub password_profile: PasswordProfile,
+}
+
+/// The initial password of a user in Entra ID.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordProfile {
+    pub password: String,
+    pub force_change_password_next_sign_in: bool,
+}
+
+impl CreateGraphUser {
+    /// The body of a request to create a volunteer's account.
+    ///
+    /// * `volunteer`: The volunteer
+    /// * `usage_location`: The country the volunteer's licenses are used in
+    pub fn new(volunteer: CreateWorkspaceVolunteer, usage_location: &str) -> Result<Self> {
+        if volunteer.org_unit != "/" {
+            bail!("Microsoft 365 has no org units, so users must be exported to /");
+        }
+        if !volunteer.aliases.is_empty() {
+            bail!("Microsoft 365 users cannot be given aliases by Pantheon");
+        }
+        if volunteer.program.is_some() {
+            bail!("Microsoft 365 users cannot be tagged with program metadata by Pantheon");
+        }
+
+        Ok(Self {
+            account_enabled: true,
+            display_name: format!("{} {}", volunteer.first_name, volunteer.last_name),
+            mail_nickname: mail_nickname(&volunteer.primary_email),
+            given_name: volunteer.first_name,
+            surname: volunteer.last_name,
+            user_principal_name: volunteer.primary_email,
+            other_mails: vec![volunteer.recovery_email],
+            mobile_phone: volunteer.recovery_phone,
+            usage_location: usage_location.to_owned(),
+            password_profile: PasswordProfile {
+                password: volunteer.password,
+                force_change_password_next_sign_in: volunteer.change_password_at_next_login,
+            },
+        })
+    }
+}
+
+/// The mail nickname of a user or group, which Entra ID requires to be the local part of its
+/// email address.
+///
+/// * `email`: The email address
+pub fn mail_nickname(email: &str) -> String {
+    email.split('@').next().unwrap_or(email).to_owned()
+}
+
+/// A client for Microsoft 365 that uses the Microsoft Graph API.
+///
+/// * `http`: The HTTP client, which retries transient failures
+/// * `tenant_id`: The ID of the Entra ID tenant
+/// * `client_id`: The ID of the app registration
+/// * `client_secret`: A client secret of the app registration
+/// * `usage_location`: The country exported users' licenses are used in (e.g. `US`)
+/// * `token`: The cached access token
+pub struct GraphWorkspaceClient {
+    http: ClientWithMiddleware,
+    tenant_id: String,
+    client_id: String,
+    client_secret: String,
+    usage_location: String,
+    token: Mutex<Option<AccessToken>>,
+}
+
+impl GraphWorkspaceClient {
+    pub fn new(
+        tenant_id: &str,
+        client_id: &str,
+        client_secret: &str,
+        usage_location: &str,
+        max_retries: u32,
+    ) -> Result<Self> {
+        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(max_retries);
+
+        let http = ClientBuilder::new(Client::builder().build()?)
+            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+            .build();
+
+        Ok(Self {
+            http,
+            tenant_id: tenant_id.to_owned(),
+            client_id: client_id.to_owned(),
+            client_secret: client_secret.to_owned(),
+            usage_location: usage_location.to_owned(),
+            token: Mutex::new(None),
+        })
+    }
+
+    /// Get an access token for Microsoft Graph, reusing the last one until it is about to expire.
+    async fn access_token(&self) -> Result<String> {
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached.as_ref().filter(|t| t.expires_at > Instant::now()) {
+            return Ok(token.token.clone());
+        }
+
+        let url = format!("https://login.microsoftonline.com/{}/oauth2/v2.0/token", self.tenant_id);
+        let res = self
+            .http
+            .post(url)
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("scope", "https://graph.microsoft.com/.default"),
+            ])
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            bail!("Microsoft rejected the app's credentials with status {status}: {text}");
+        }
+
+        let data = res.json::<TokenResponse>().await?;
+        let lifetime = Duration::from_secs(data.expires_in).saturating_sub(TOKEN_EXPIRY_MARGIN);
+        *cached = Some(AccessToken {
+            token: data.access_token.clone(),
+            expires_at: Instant::now() + lifetime,
+        });
+
+        Ok(data.access_token)
+    }
+
+    /// Start an authenticated request to Microsoft Graph.
+    ///
+    /// * `method`: The HTTP method
+    /// * `url`: The URL, either absolute (e.g. a next link) or relative to `GRAPH_API_URL`
+    async fn request(&self, method: Method, url: &str) -> Result<RequestBuilder> {
+        let url = if url.starts_with("https://") {
+            url.to_owned()
+        } else {
+            format!("{GRAPH_API_URL}{url}")
+        };
+        let token = self.access_token().await?;
+        Ok(self.http.request(method, url).bearer_auth(token))
+    }
+
+    /// Start an authenticated request to Microsoft Graph with a JSON body.
+    ///
+    /// * `method`: The HTTP method
+    /// * `url`: The URL, relative to `GRAPH_API_URL`
+    /// * `body`: The body of the request
+    async fn request_json(
+        &self,
+        method: Method,
+        url: &str,
+        body: &Value,
+    ) -> Result<RequestBuilder> {
+        let req = self.request(method, url).await?;
+        Ok(req.header(CONTENT_TYPE, "application/json").body(serde_json::to_vec(body)?))
+    }
+
+    /// Send a request, failing with a `GraphError` if Microsoft Graph does not accept it.
+    ///
+    /// * `req`: The request
+    async fn send(req: RequestBuilder) -> Result<Response> {
+        let res = req.send().await?;
+        if !res.status().is_success() {
+            return Err(graph_error(res).await.into());
+        }
+        Ok(res)
+    }
+
+    /// Fetch every item of a collection, following its next links.
+    ///
+    /// * `url`: The URL of the collection's first page
+    async fn list<T: DeserializeOwned>(&self, url: &str) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        let mut next = Some(url.to_owned());
+
+        while let Some(url) = next {
+            let page = Self::send(self.request(Method::GET, &url).await?)
+                .await?
+                .json::<GraphPage<T>>()
+                .await?;
+            items.extend(page.value);
+            next = page.next_link;
+        }
+
+        Ok(items)
+    }
+
+    /// Fetch a user by their user principal name. Returns `None` if there is no such user.
+    ///
+    /// * `email`: The user principal name
+    async fn get_user(&self, email: &str) -> Result<Option<GraphUser>> {
+        let url = format!("/users/{email}?$select={USER_SELECT}");
+        match Self::send(self.request(Method::GET, &url).await?).await {
+            Ok(res) => Ok(Some(res.json::<GraphUser>().await?)),
+            Err(e) if is_not_found(&e) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Fetch a user by their user principal name, failing if there is no such user.
+    ///
+    /// * `email`: The user principal name
+    async fn user_id(&self, email: &str) -> Result<String> {
+        let user =
+            self.get_user(email).await?.with_context(|| format!("{email} does not exist"))?;
+        Ok(user.id)
+    }
+
+    /// Fetch the ID of the group with an email address. Returns `None` if there is no such group.
+    ///
+    /// * `group_email`: The email address of the group
+    async fn group_id(&self, group_email: &str) -> Result<Option<String>> {
+        let url =
+            format!("/groups?$filter=mail eq '{}'&$select=id", group_email.replace('\'', "''"));
+        let groups = self.list::<Value>(&url).await?;
+
+        Ok(groups.first().and_then(|g| g.get("id")?.as_str().map(str::to_owned)))
+    }
+
+    /// Create a volunteer's account without checking whether it already exists.
+    ///
+    /// * `volunteer`: The volunteer
+    async fn create_user(&self, volunteer: CreateWorkspaceVolunteer) -> Result<()> {
+        let user = serde_json::to_value(CreateGraphUser::new(volunteer, &self.usage_location)?)?;
+        Self::send(self.request_json(Method::POST, "/users", &user).await?).await?;
+        Ok(())
+    }
+
+    /// Fetch the tenant's subscription to a SKU.
+    ///
+    /// * `sku_id`: The ID of the SKU
+    async fn subscribed_sku(&self, sku_id: &str) -> Result<Value> {
+        let skus = self.list::<Value>("/subscribedSkus").await?;
+        skus.into_iter()
+            .find(|s| {
+                s.get("skuId")
+                    .and_then(Value::as_str)
+                    .is_some_and(|id| id.eq_ignore_ascii_case(sku_id))
+            })
+            .with_context(|| format!("The tenant has no subscription to SKU {sku_id}"))
+    }
+
+    /// Enable or disable the sign-in of a user.
+    ///
+    /// * `workspace_email`: The user principal name
+    /// * `enabled`: Whether the user can sign in
+    async fn set_account_enabled(&self, workspace_email: &str, enabled: bool) -> Result<()> {
+        let url = format!("/users/{workspace_email}");
+        let body = json!({ "accountEnabled": enabled });
+        let req = self.request_json(Method::PATCH, &url, &body).await?;
+        Self::send(req).await?;
+        Ok(())
+    }
+}
+
+/// Whether an error is Microsoft Graph saying that what was asked for does not exist.
+///
+/// * `err`: The error to inspect
+fn is_not_found(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<GraphError>().is_some_and(|e| e.status == StatusCode::NOT_FOUND.as_u16())
+}
+
+#[async_trait]
+impl WorkspaceClient for GraphWorkspaceClient {
+    async fn create_volunteer(
+        &self,
+        principal: &str,
+        volunteer: CreateWorkspaceVolunteer,
+    ) -> Result<CreateVolunteerOutcome> {
+        log::debug!("{principal} is creating {} in Microsoft 365", volunteer.primary_email);
+
+        if let Some(existing) = self.get_user(&volunteer.primary_email).await? {
+            let same_owner = existing
+                .other_mails
+                .iter()
+                .any(|email| email.eq_ignore_ascii_case(&volunteer.recovery_email));
+            if !same_owner {
+                bail!("{} already belongs to another Microsoft 365 user", volunteer.primary_email);
+            }
+            return Ok(CreateVolunteerOutcome::AlreadyExists);
+        }
+
+        self.create_user(volunteer).await?;
+
+        Ok(CreateVolunteerOutcome::Created)
+    }
+
+    async fn batch_create_volunteers(
+        &self,
+        principal: &str,
+        volunteers: Vec<CreateWorkspaceVolunteer>,
+    ) -> Result<Vec<Result<CreateVolunteerOutcome>>> {
+        log::debug!("{principal} is creating {} users in Microsoft 365", volunteers.len());
+
+        // Users are created one after another, which keeps each failure separate like a batch
+        let mut results = Vec::with_capacity(volunteers.len());
+        for volunteer in volunteers {
+            results
+                .push(self.create_user(volunteer).await.map(|_| CreateVolunteerOutcome::Created));
+        }
+
+        Ok(results)
+    }
+
+    async fn update_volunteer(
+        &self,
+        _principal: &str,
+        workspace_email: &str,
+        volunteer: UpdateWorkspaceVolunteer,
+    ) -> Result<()> {
+        let url = format!("/users/{workspace_email}");
+        let body = json!({
+            "displayName": format!("{} {}", volunteer.first_name, volunteer.last_name),
+            "givenName": volunteer.first_name,
+            "surname": volunteer.last_name,
+            "otherMails": [volunteer.recovery_email],
+        });
+        let req = self.request_json(Method::PATCH, &url, &body).await?;
+        Self::send(req).await?;
+        Ok(())
+    }
+
+    async fn create_alias(
+        &self,
+        _principal: &str,
+        _workspace_email: &str,
+        _alias: &str,
+    ) -> Result<()> {
+        bail!("Microsoft 365 users cannot be given aliases by Pantheon")
+    }
+
+    async fn delete_user(&self, _principal: &str, email_of_user_to_delete: &str) -> Result<()> {
+        let url = format!("/users/{email_of_user_to_delete}");
+        Self::send(self.request(Method::DELETE, &url).await?).await?;
+        Ok(())
+    }
+
+    async fn suspend_volunteer(&self, _principal: &str, workspace_email: &str) -> Result<()> {
+        self.set_account_enabled(workspace_email, false).await
+    }
+
+    async fn unsuspend_volunteer(&self, _principal: &str, workspace_email: &str) -> Result<()> {
+        self.set_account_enabled(workspace_email, true).await
+    }
+
+    async fn ensure_group(&self, _principal: &str, group_email: &str) -> Result<bool> {
+        if self.group_id(group_email).await?.is_some() {
+            return Ok(false);
+        }
+
+        // Only Microsoft 365 groups can be given an email address through Graph
+        let nickname = mail_nickname(group_email);
+        let body = json!({
+            "displayName": nickname,
+            "mailNickname": nickname,
+            "mailEnabled": true,
+            "securityEnabled": false,
+            "groupTypes": ["Unified"],
+        });
+        let req = self.request_json(Method::POST, "/groups", &body).await?;
+        Self::send(req).await?;
+
+        Ok(true)
+    }
+
+    async fn ensure_program_schema(&self, _principal: &str) -> Result<bool> {
+        bail!("Microsoft 365 users cannot be tagged with program metadata by Pantheon")
+    }
+
+    async fn add_group_member(
+        &self,
+        _principal: &str,
+        group_email: &str,
+        workspace_email: &str,
+    ) -> Result<()> {
+        let group_id = self
+            .group_id(group_email)
+            .await?
+            .with_context(|| format!("{group_email} does not exist"))?;
+        let user_id = self.user_id(workspace_email).await?;
+
+        let url = format!("/groups/{group_id}/members/$ref");
+        let body = json!({ "@odata.id": format!("{GRAPH_API_URL}/directoryObjects/{user_id}") });
+        let req = self.request_json(Method::POST, &url, &body).await?;
+
+        match Self::send(req).await {
+            Ok(_) => Ok(()),
+            // Graph reports adding an existing member as a conflict
+            Err(e) if e.downcast_ref::<GraphError>().is_some_and(GraphError::is_conflict) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn list_org_unit_users(
+        &self,
+        _principal: &str,
+        org_unit_path: &str,
+    ) -> Result<Vec<WorkspaceAccount>> {
+        if org_unit_path != "/" {
+            bail!("Microsoft 365 has no org units, so only / can be listed");
+        }
+
+        let users =
+            self.list::<GraphUser>(&format!("/users?$select={USER_SELECT}&$top=999")).await?;
+        Ok(users.into_iter().map(WorkspaceAccount::from).collect())
+    }
+
+    async fn find_user(&self, _principal: &str, email: &str) -> Result<Option<WorkspaceAccount>> {
+        Ok(self.get_user(email).await?.map(WorkspaceAccount::from))
+    }
+
+    async fn org_unit_exists(&self, _principal: &str, org_unit_path: &str) -> Result<bool> {
+        Ok(org_unit_path == "/")
+    }
+
+    async fn list_org_units(&self, _principal: &str) -> Result<Vec<WorkspaceOrgUnit>> {
+        Ok(Vec::new())
+    }
+
+    async fn create_org_unit(
+        &self,
+        _principal: &str,
+        _org_unit_path: &str,
+    ) -> Result<WorkspaceOrgUnit> {
+        bail!("Microsoft 365 has no org units")
+    }
+
+    async fn two_step_verification_settings(
+        &self,
+        _principal: &str,
+        _org_unit_path: &str,
+    ) -> Result<TwoStepVerificationSettings> {
+        bail!(
+            "Multi-factor authentication in Microsoft 365 is managed with Conditional Access, \
+             which Pantheon cannot check"
+        )
+    }
+
+    async fn count_license_assignments(
+        &self,
+        _principal: &str,
+        _product_id: &str,
+        sku_id: &str,
+    ) -> Result<usize> {
+        let sku = self.subscribed_sku(sku_id).await?;
+        let consumed = sku.get("consumedUnits").and_then(Value::as_u64).unwrap_or_default();
+        Ok(usize::try_from(consumed)?)
+    }
+
+    async fn license_seats(
+        &self,
+        _principal: &str,
+        _product_id: &str,
+        sku_id: &str,
+    ) -> Result<Option<usize>> {
+        let sku = self.subscribed_sku(sku_id).await?;
+        let enabled = sku.pointer("/prepaidUnits/enabled").and_then(Value::as_u64);
+        Ok(enabled.map(usize::try_from).transpose()?)
+    }
+
+    async fn assign_license(
+        &self,
+        _principal: &str,
+        _product_id: &str,
+        sku_id: &str,
+        workspace_email: &str,
+    ) -> Result<()> {
+        // Microsoft identifies a license by its SKU alone, and assigning one twice succeeds
+        let url = format!("/users/{workspace_email}/assignLicense");
+        let body = json!({
+            "addLicenses": [{ "skuId": sku_id, "disabledPlans": [] }],
+            "removeLicenses": [],
+        });
+        let req = self.request_json(Method::POST, &url, &body).await?;
+        Self::send(req).await?;
+        Ok(())
+    }
+
+    async fn set_signature(
+        &self,
+        _principal: &str,
+        _workspace_email: &str,
+        _signature: &str,
+    ) -> Result<()> {
+        bail!("Outlook signatures cannot be set through Microsoft Graph")
+    }
+
+    async fn set_profile_photo(
+        &self,
+        _principal: &str,
+        workspace_email: &str,
+        photo: &ProfilePhoto,
+    ) -> Result<()> {
+        let url = format!("/users/{workspace_email}/photo/$value");
+        let req = self
+            .request(Method::PUT, &url)
+            .await?
+            .header(CONTENT_TYPE, photo.content_type)
+            .body(photo.data.clone());
+        Self::send(req).await?;
+        Ok(())
+    }
+
+    async fn domain_exists(&self, _principal: &str, domain: &str) -> Result<bool> {
+        match Self::send(self.request(Method::GET, &format!("/domains/{domain}")).await?).await {
+            Ok(res) => {
+                let domain = res.json::<Value>().await?;
+                Ok(domain.get("isVerified").and_then(Value::as_bool).unwrap_or_default())
+            }
+            Err(e) if is_not_found(&e) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[async_trait]
+impl DriveClient for GraphWorkspaceClient {
+    async fn create_shared_drive(
+        &self,
+        _principal: &str,
+        _request_id: &str,
+        _name: &str,
+    ) -> Result<Option<SharedDrive>> {
+        bail!("Shared drives are not supported in Microsoft 365")
+    }
+
+    async fn add_shared_drive_member(
+        &self,
+        _principal: &str,
+        _drive_id: &str,
+        _workspace_email: &str,
+        _role: DriveRole,
+    ) -> Result<()> {
+        bail!("Shared drives are not supported in Microsoft 365")
+    }
+
+    async fn start_drive_transfer(
+        &self,
+        _principal: &str,
+        _workspace_email: &str,
+        _archive_email: &str,
+    ) -> Result<String> {
+        bail!("Drive files cannot be transferred in Microsoft 365")
+    }
+
+    async fn drive_transfer_status(
+        &self,
+        _principal: &str,
+        _transfer_id: &str,
+    ) -> Result<DriveTransferStatus> {
+        bail!("Drive files cannot be transferred in Microsoft 365")
+    }
+}
+
+#[async_trait]
+impl CalendarClient for GraphWorkspaceClient {
+    async fn create_calendar_event(
+        &self,
+        _principal: &str,
+        _event: CreateCalendarEvent,
+    ) -> Result<CalendarEvent> {
+        bail!("Onboarding sessions are not supported in Microsoft 365")
+    }
+
+    async fn invite_to_calendar_event(
+        &self,
+        _principal: &str,
+        _event_id: &str,
+        _workspace_emails: &[String],
+    ) -> Result<()> {
+        bail!("Onboarding sessions are not supported in Microsoft 365")
+    }
+}
+
+impl Service for GraphWorkspaceClient {
+    fn get_id(&self) -> &'static str {
+        "microsoft-graph"
+    }
+}
@@ -1,20 +1,36 @@
-//! This module provides interfaces and implementations for interacting with the Google Workspace
-//! API. Currently, the only implementation is a service account-based implementation.
+//! This module provides interfaces and implementations for provisioning volunteers' accounts. The
+//! interfaces are modelled on the Google Workspace API, whose implementation is based on a service
+//! account. Microsoft 365 is provisioned through the Microsoft Graph API instead, and there is a
+//! no-op implementation and an in-memory emulator for development and testing.
 
+pub mod admin_pool;
+pub mod calendar;
+pub mod drive;
+pub mod emulator;
 pub mod entities;
+pub mod graph;
 pub mod noop;
+pub mod retry;
+pub mod scim;
 pub mod service_account;
 
 use anyhow::Result;
 use async_trait::async_trait;
-use entities::CreateWorkspaceVolunteer;
+use calendar::CalendarClient;
+use drive::DriveClient;
+use entities::{
+    CreateVolunteerOutcome, CreateWorkspaceVolunteer, ProfilePhoto, TwoStepVerificationSettings,
+    UpdateWorkspaceVolunteer, WorkspaceAccount, WorkspaceOrgUnit,
+};
 
 use super::Service;
 
-/// A trait for interacting with the Google Workspace API.
+/// A trait for interacting with the provider that hosts volunteers' accounts (Google Workspace or
+/// Microsoft 365).
 ///
 /// Any type which implements this trait may
-/// be injected as a dependency in the application. The functions in this trait take a `principal`.
+/// be injected as a dependency in the application. Providers that lack a feature (e.g. org units
+/// in Microsoft 365) fail the calls that need it, so exports that rely on it stop with an error. The functions in this trait take a `principal`.
 /// This is unique to the service account implementation, and should be replaced with an enum in
 /// the future as this abstraction is currently leaky.
 #[async_trait]
@@ -29,10 +45,65 @@ pub trait WorkspaceClient: Send + Sync {
     /// header. This is a security measure and we are delegating authentication to Auth0. Never
     /// call this function with user provided input. This is one reason why we should try to find
     /// an alternative to the service account approach.
+    ///
+    /// If a user with the volunteer's primary email and recovery email already exists, nothing is
+    /// created and `CreateVolunteerOutcome::AlreadyExists` is returned, so that retrying a creation
+    /// whose response was lost does not fail. A user with the same primary email but a different
+    /// recovery email belongs to someone else, and is an error.
     async fn create_volunteer(
         &self,
         principal: &str,
         volunteer: CreateWorkspaceVolunteer,
+    ) -> Result<CreateVolunteerOutcome> {
+        unimplemented!()
+    }
+
+    /// Create several users in Google Workspace with as few requests as possible.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `volunteers`: The users to create
+    ///
+    /// Returns whether each user was created, in the same order as `volunteers`. This only fails
+    /// as a whole if none of the users could be attempted. Users that already exist are reported
+    /// as conflicts rather than checked, so callers should retry them with `create_volunteer`.
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn batch_create_volunteers(
+        &self,
+        principal: &str,
+        volunteers: Vec<CreateWorkspaceVolunteer>,
+    ) -> Result<Vec<Result<CreateVolunteerOutcome>>> {
+        unimplemented!()
+    }
+
+    /// Update the profile of a volunteer's account in Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `workspace_email`: The volunteer's workspace email address.
+    /// * `volunteer`: The name and recovery email to give the account.
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn update_volunteer(
+        &self,
+        principal: &str,
+        workspace_email: &str,
+        volunteer: UpdateWorkspaceVolunteer,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Add an alias to a volunteer's account in Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `workspace_email`: The volunteer's workspace email address.
+    /// * `alias`: The alias (e.g. `jane@developforgood.org` for `jane.doe@developforgood.org`).
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn create_alias(
+        &self,
+        principal: &str,
+        workspace_email: &str,
+        alias: &str,
     ) -> Result<()> {
         unimplemented!()
     }
@@ -50,7 +121,238 @@ pub trait WorkspaceClient: Send + Sync {
     async fn delete_user(&self, principal: &str, email_of_user_to_delete: &str) -> Result<()> {
         unimplemented!()
     }
+
+    /// Suspend a volunteer's account in Google Workspace, so they can no longer sign in. Their
+    /// account and data are kept, so the suspension can be undone.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `workspace_email`: The volunteer's workspace email address.
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn suspend_volunteer(&self, principal: &str, workspace_email: &str) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Lift the suspension of a volunteer's account in Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `workspace_email`: The volunteer's workspace email address.
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn unsuspend_volunteer(&self, principal: &str, workspace_email: &str) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Make sure a group exists in Google Workspace, creating it if it does not.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `group_email`: The email address of the group (e.g. `cohort@developforgood.org`).
+    ///
+    /// Returns whether the group had to be created. The same restrictions on `principal` as
+    /// `create_volunteer` apply.
+    async fn ensure_group(&self, principal: &str, group_email: &str) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Make sure the custom user schema that holds volunteers' program metadata exists in Google
+    /// Workspace, creating it if it does not.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    ///
+    /// Returns whether the schema had to be created. The same restrictions on `principal` as
+    /// `create_volunteer` apply.
+    async fn ensure_program_schema(&self, principal: &str) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// Add a volunteer to a group in Google Workspace. Adding a volunteer who is already a member
+    /// of the group succeeds without doing anything.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `group_email`: The email address of the group.
+    /// * `workspace_email`: The volunteer's workspace email address.
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn add_group_member(
+        &self,
+        principal: &str,
+        group_email: &str,
+        workspace_email: &str,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// List the users in an org unit and every org unit below it.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `org_unit_path`: The full path of the org unit (e.g. `/Programs/2025-Spring`).
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn list_org_unit_users(
+        &self,
+        principal: &str,
+        org_unit_path: &str,
+    ) -> Result<Vec<WorkspaceAccount>> {
+        unimplemented!()
+    }
+
+    /// Find a user in Google Workspace by email. Returns `None` if there is no such user.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn find_user(&self, principal: &str, email: &str) -> Result<Option<WorkspaceAccount>> {
+        unimplemented!()
+    }
+
+    /// Check whether an org unit exists in Google Workspace.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `org_unit_path`: The full path of the org unit (e.g. `/Programs/PantheonUsers`).
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn org_unit_exists(&self, principal: &str, org_unit_path: &str) -> Result<bool> {
+        unimplemented!()
+    }
+
+    /// List every org unit in Google Workspace, except the root.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn list_org_units(&self, principal: &str) -> Result<Vec<WorkspaceOrgUnit>> {
+        unimplemented!()
+    }
+
+    /// Create an org unit in Google Workspace. Its parent must already exist.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `org_unit_path`: The full path of the org unit (e.g. `/Programs/2025-Spring`).
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn create_org_unit(
+        &self,
+        principal: &str,
+        org_unit_path: &str,
+    ) -> Result<WorkspaceOrgUnit> {
+        unimplemented!()
+    }
+
+    /// Look up the 2-Step Verification settings that apply to the users of an org unit, including
+    /// the ones it inherits from its ancestors.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `org_unit_path`: The full path of the org unit (e.g. `/Programs/2025-Spring`).
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn two_step_verification_settings(
+        &self,
+        principal: &str,
+        org_unit_path: &str,
+    ) -> Result<TwoStepVerificationSettings> {
+        unimplemented!()
+    }
+
+    /// Count the users that have been assigned a license for a Google Workspace edition.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `product_id`: The product the license is for (e.g. `Google-Apps`).
+    /// * `sku_id`: The edition of the product (e.g. `1010020027` for Business Starter).
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn count_license_assignments(
+        &self,
+        principal: &str,
+        product_id: &str,
+        sku_id: &str,
+    ) -> Result<usize> {
+        unimplemented!()
+    }
+
+    /// Look up how many licenses have been purchased for a Google Workspace edition.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `product_id`: The product the license is for (e.g. `Google-Apps`).
+    /// * `sku_id`: The edition of the product (e.g. `1010020027` for Business Starter).
+    ///
+    /// Returns `None` if the provider does not report it. The same restrictions on `principal` as
+    /// `create_volunteer` apply.
+    async fn license_seats(
+        &self,
+        principal: &str,
+        product_id: &str,
+        sku_id: &str,
+    ) -> Result<Option<usize>> {
+        unimplemented!()
+    }
+
+    /// Assign a volunteer a license for a Google Workspace edition. Assigning a license the
+    /// volunteer already has succeeds without doing anything.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `product_id`: The product the license is for (e.g. `Google-Apps`).
+    /// * `sku_id`: The edition of the product (e.g. `1010020027` for Business Starter).
+    /// * `workspace_email`: The volunteer's workspace email address.
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn assign_license(
+        &self,
+        principal: &str,
+        product_id: &str,
+        sku_id: &str,
+        workspace_email: &str,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Set the signature of a volunteer's workspace email.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `workspace_email`: The volunteer's workspace email address.
+    /// * `signature`: The signature, as HTML.
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn set_signature(
+        &self,
+        principal: &str,
+        workspace_email: &str,
+        signature: &str,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Set the profile photo of a volunteer's account, replacing any photo it has.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `workspace_email`: The volunteer's workspace email address.
+    /// * `photo`: The photo.
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn set_profile_photo(
+        &self,
+        principal: &str,
+        workspace_email: &str,
+        photo: &ProfilePhoto,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Check whether a domain is a verified domain of the Google Workspace account.
+    ///
+    /// * `principal`: The email of the user requesting this action.
+    /// * `domain`: The domain (e.g. `developforgood.org`).
+    ///
+    /// The same restrictions on `principal` as `create_volunteer` apply.
+    async fn domain_exists(&self, principal: &str, domain: &str) -> Result<bool> {
+        unimplemented!()
+    }
 }
 
-pub trait WorkspaceService: WorkspaceClient + Service + Send + Sync {}
-impl<T> WorkspaceService for T where T: WorkspaceClient + Service + Send + Sync {}
+pub trait WorkspaceService:
+    WorkspaceClient + DriveClient + CalendarClient + Service + Send + Sync
+{
+}
+impl<T> WorkspaceService for T where
+    T: WorkspaceClient + DriveClient + CalendarClient + Service + Send + Sync
+{
+}
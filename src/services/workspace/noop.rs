@@ -1,9 +1,17 @@
 //! This module defines a no-op implementation of the `WorkspaceClient` trait.
 
+use std::time::Duration;
+
 use anyhow::Result;
 use axum::async_trait;
+use chrono::DateTime;
 
-use crate::services::workspace::entities::CreateWorkspaceVolunteer;
+use crate::services::workspace::calendar::{CalendarClient, CalendarEvent, CreateCalendarEvent};
+use crate::services::workspace::drive::{DriveClient, DriveRole, DriveTransferStatus, SharedDrive};
+use crate::services::workspace::entities::{
+    CreateVolunteerOutcome, CreateWorkspaceVolunteer, ProfilePhoto, TwoStepVerificationSettings,
+    UpdateWorkspaceVolunteer, WorkspaceAccount, WorkspaceOrgUnit,
+};
 use crate::services::workspace::WorkspaceClient;
 use crate::services::Service;
 
@@ -24,6 +32,32 @@ impl WorkspaceClient for NoopWorkspaceClient {
         &self,
         _principal: &str,
         _user: CreateWorkspaceVolunteer,
+    ) -> Result<CreateVolunteerOutcome> {
+        Ok(CreateVolunteerOutcome::Created)
+    }
+
+    async fn batch_create_volunteers(
+        &self,
+        _principal: &str,
+        volunteers: Vec<CreateWorkspaceVolunteer>,
+    ) -> Result<Vec<Result<CreateVolunteerOutcome>>> {
+        Ok(volunteers.iter().map(|_| Ok(CreateVolunteerOutcome::Created)).collect())
+    }
+
+    async fn update_volunteer(
+        &self,
+        _principal: &str,
+        _workspace_email: &str,
+        _volunteer: UpdateWorkspaceVolunteer,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn create_alias(
+        &self,
+        _principal: &str,
+        _workspace_email: &str,
+        _alias: &str,
     ) -> Result<()> {
         Ok(())
     }
@@ -31,6 +65,182 @@ impl WorkspaceClient for NoopWorkspaceClient {
     async fn delete_user(&self, _principal: &str, _email_of_user_to_delete: &str) -> Result<()> {
         Ok(())
     }
+
+    async fn suspend_volunteer(&self, _principal: &str, _workspace_email: &str) -> Result<()> {
+        Ok(())
+    }
+
+    async fn unsuspend_volunteer(&self, _principal: &str, _workspace_email: &str) -> Result<()> {
+        Ok(())
+    }
+
+    async fn ensure_group(&self, _principal: &str, _group_email: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn ensure_program_schema(&self, _principal: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn add_group_member(
+        &self,
+        _principal: &str,
+        _group_email: &str,
+        _workspace_email: &str,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn list_org_unit_users(
+        &self,
+        _principal: &str,
+        _org_unit_path: &str,
+    ) -> Result<Vec<WorkspaceAccount>> {
+        Ok(Vec::new())
+    }
+
+    async fn find_user(&self, _principal: &str, _email: &str) -> Result<Option<WorkspaceAccount>> {
+        Ok(None)
+    }
+
+    async fn org_unit_exists(&self, _principal: &str, _org_unit_path: &str) -> Result<bool> {
+        Ok(true)
+    }
+
+    async fn list_org_units(&self, _principal: &str) -> Result<Vec<WorkspaceOrgUnit>> {
+        Ok(Vec::new())
+    }
+
+    async fn create_org_unit(
+        &self,
+        _principal: &str,
+        org_unit_path: &str,
+    ) -> Result<WorkspaceOrgUnit> {
+        let name = org_unit_path.rsplit('/').next().unwrap_or_default().to_owned();
+        Ok(WorkspaceOrgUnit { org_unit_path: org_unit_path.to_owned(), name, description: None })
+    }
+
+    async fn two_step_verification_settings(
+        &self,
+        _principal: &str,
+        _org_unit_path: &str,
+    ) -> Result<TwoStepVerificationSettings> {
+        Ok(TwoStepVerificationSettings {
+            allow_enrollment: true,
+            enforced_from: Some(DateTime::UNIX_EPOCH),
+            enrollment_grace_period: Some(Duration::from_secs(7 * 24 * 60 * 60)),
+        })
+    }
+
+    async fn count_license_assignments(
+        &self,
+        _principal: &str,
+        _product_id: &str,
+        _sku_id: &str,
+    ) -> Result<usize> {
+        Ok(0)
+    }
+
+    async fn license_seats(
+        &self,
+        _principal: &str,
+        _product_id: &str,
+        _sku_id: &str,
+    ) -> Result<Option<usize>> {
+        Ok(None)
+    }
+
+    async fn assign_license(
+        &self,
+        _principal: &str,
+        _product_id: &str,
+        _sku_id: &str,
+        _workspace_email: &str,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn set_signature(
+        &self,
+        _principal: &str,
+        _workspace_email: &str,
+        _signature: &str,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn set_profile_photo(
+        &self,
+        _principal: &str,
+        _workspace_email: &str,
+        _photo: &ProfilePhoto,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn domain_exists(&self, _principal: &str, _domain: &str) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl DriveClient for NoopWorkspaceClient {
+    async fn create_shared_drive(
+        &self,
+        _principal: &str,
+        request_id: &str,
+        name: &str,
+    ) -> Result<Option<SharedDrive>> {
+        Ok(Some(SharedDrive { id: request_id.to_owned(), name: name.to_owned() }))
+    }
+
+    async fn add_shared_drive_member(
+        &self,
+        _principal: &str,
+        _drive_id: &str,
+        _workspace_email: &str,
+        _role: DriveRole,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn start_drive_transfer(
+        &self,
+        _principal: &str,
+        workspace_email: &str,
+        _archive_email: &str,
+    ) -> Result<String> {
+        Ok(format!("noop-{workspace_email}"))
+    }
+
+    async fn drive_transfer_status(
+        &self,
+        _principal: &str,
+        _transfer_id: &str,
+    ) -> Result<DriveTransferStatus> {
+        Ok(DriveTransferStatus::Completed)
+    }
+}
+
+#[async_trait]
+impl CalendarClient for NoopWorkspaceClient {
+    async fn create_calendar_event(
+        &self,
+        _principal: &str,
+        event: CreateCalendarEvent,
+    ) -> Result<CalendarEvent> {
+        let link = format!("https://calendar.google.com/calendar/event?eid={}", event.id);
+        Ok(CalendarEvent { id: event.id, link })
+    }
+
+    async fn invite_to_calendar_event(
+        &self,
+        _principal: &str,
+        _event_id: &str,
+        _workspace_emails: &[String],
+    ) -> Result<()> {
+        Ok(())
+    }
 }
 
 impl Service for NoopWorkspaceClient {
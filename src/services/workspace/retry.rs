@@ -0,0 +1,169 @@
+//! This module defines a retry policy for Google Workspace operations.
+//!
+//! The HTTP clients used by the workspace implementations already retry some requests at the
+//! transport level, but a single failed request still surfaces as an error to the caller. The
+//! `RetryPolicy` defined here wraps an entire workspace operation (for example, creating a user)
+//! and retries it with exponential backoff and jitter when the failure looks transient.
+
+use std::future::Future;
+use std::time::Duration;
+
+use anyhow::Result;
+use derive_builder::Builder;
+use rand::Rng;
+use reqwest::StatusCode;
+use scipio_workspace::batch::BatchEntryError;
+
+use super::graph::GraphError;
+use super::scim::ScimError;
+
+/// A policy describing how (and whether) to retry a failed workspace operation.
+///
+/// * `max_attempts`: The maximum number of attempts, including the first one. A value of 1
+///   disables retries.
+/// * `base_delay`: The delay before the first retry. Each subsequent retry doubles the delay.
+/// * `max_delay`: The upper bound on the delay between two attempts.
+/// * `jitter`: Whether to apply full jitter to the computed delay. This spreads out retries when
+///   many operations fail at once (for example, when Google starts rate limiting an export).
+#[derive(Debug, Clone, Builder)]
+pub struct RetryPolicy {
+    #[builder(default = "5")]
+    pub max_attempts: u32,
+    #[builder(default = "Duration::from_millis(500)")]
+    pub base_delay: Duration,
+    #[builder(default = "Duration::from_secs(30)")]
+    pub max_delay: Duration,
+    #[builder(default = "true")]
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Compute how long to wait before the given retry.
+    ///
+    /// * `attempt`: The 1-indexed number of the attempt that just failed
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self.base_delay.saturating_mul(1u32 << exponent).min(self.max_delay);
+
+        if self.jitter && !delay.is_zero() {
+            let millis = delay.as_millis() as u64;
+            Duration::from_millis(rand::thread_rng().gen_range(0..=millis))
+        } else {
+            delay
+        }
+    }
+
+    /// Run an operation, retrying it according to this policy.
+    ///
+    /// * `label`: A friendly description of the operation, used for logging
+    /// * `op`: A closure producing the future to run on each attempt
+    ///
+    /// Only errors for which `is_retryable` returns `true` are retried. Any other error, or the
+    /// last error once `max_attempts` is exhausted, is returned to the caller.
+    pub async fn run<T, F, Fut>(&self, label: &str, mut op: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match op().await {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < self.max_attempts && is_retryable(&e) => {
+                    let delay = self.delay_for(attempt);
+                    log::warn!(
+                        "{label} failed on attempt {attempt}/{}: {e}. Retrying in {}ms",
+                        self.max_attempts,
+                        delay.as_millis()
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Whether an error returned by a workspace operation is worth retrying.
+///
+/// * `err`: The error to inspect
+///
+/// Rate limiting (429), precondition failures (412, which Google returns while a newly created
+/// resource is still propagating), server errors (5xx), timeouts, and connection failures are
+/// considered transient. Everything else (bad requests, conflicts, authorization failures, etc.)
+/// is not. The same goes for the status of a single call in a batch request, except that a 403
+/// saying the call was rate limited is also transient, and for errors from Microsoft Graph and
+/// SCIM services.
+pub fn is_retryable(err: &anyhow::Error) -> bool {
+    for cause in err.chain() {
+        if let Some(e) = cause.downcast_ref::<GraphError>() {
+            return StatusCode::from_u16(e.status).is_ok_and(is_retryable_status);
+        }
+        if let Some(e) = cause.downcast_ref::<ScimError>() {
+            return StatusCode::from_u16(e.status).is_ok_and(is_retryable_status);
+        }
+        if let Some(e) = cause.downcast_ref::<BatchEntryError>() {
+            // Calls in a batch are not retried by the client, and Google reports some of them
+            // being rate limited as a 403
+            let rate_limited = e.status == StatusCode::FORBIDDEN.as_u16()
+                && e.message.to_lowercase().contains("rate limit");
+            return rate_limited || StatusCode::from_u16(e.status).is_ok_and(is_retryable_status);
+        }
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            if e.is_timeout() || e.is_connect() {
+                return true;
+            }
+            if let Some(status) = e.status() {
+                return is_retryable_status(status);
+            }
+        }
+        if let Some(reqwest_middleware::Error::Reqwest(e)) =
+            cause.downcast_ref::<reqwest_middleware::Error>()
+        {
+            return e.is_timeout() || e.is_connect();
+        }
+    }
+    false
+}
+
+/// Whether an error returned by a workspace operation means the resource already exists.
+///
+/// * `err`: The error to inspect
+///
+/// This is how Google reports creating a user whose primary email is taken, whether in a single
+/// request or as the status of a single call in a batch request. Microsoft Graph and SCIM
+/// services report it with their own errors.
+pub fn is_conflict(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if let Some(e) = cause.downcast_ref::<GraphError>() {
+            return e.is_conflict();
+        }
+        if let Some(e) = cause.downcast_ref::<ScimError>() {
+            return e.is_conflict();
+        }
+        if let Some(e) = cause.downcast_ref::<BatchEntryError>() {
+            return e.status == StatusCode::CONFLICT.as_u16();
+        }
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.status() == Some(StatusCode::CONFLICT))
+    })
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::PRECONDITION_FAILED
+        || status.is_server_error()
+}
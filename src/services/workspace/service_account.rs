@@ -2,33 +2,434 @@
 //!
 //!
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
-use scipio_workspace::user::CreateWorkspaceUser;
+use scipio_workspace::calendar::{CreateEventBuilder, EventDateTime};
+use scipio_workspace::drive::CreateDrive;
+use scipio_workspace::group::CreateGroup;
+use scipio_workspace::org_unit::CreateOrgUnit;
+use scipio_workspace::schema::{CreateSchema, ReadAccessType, SchemaField, SchemaFieldType};
+use scipio_workspace::transfer::{drive_application, DataTransfer};
+use scipio_workspace::user::{CreateWorkspaceUser, UpdateWorkspaceUser};
 use scipio_workspace::ServiceAccount;
 
-use super::entities::CreateWorkspaceVolunteer;
+use super::calendar::{CalendarClient, CalendarEvent, CreateCalendarEvent};
+use super::drive::{DriveClient, DriveRole, DriveTransferStatus, SharedDrive};
+use super::entities::{
+    CreateVolunteerOutcome, CreateWorkspaceVolunteer, ProfilePhoto, TwoStepVerificationSettings,
+    UpdateWorkspaceVolunteer, WorkspaceAccount, WorkspaceOrgUnit, PROGRAM_SCHEMA_NAME,
+};
 use super::WorkspaceClient;
 use crate::services::Service;
 
+/// The filter that lists every 2-Step Verification policy.
+const TWO_STEP_VERIFICATION_FILTER: &str =
+    "setting.type.matches('settings/security.two_step_verification.*')";
+
+/// Events are scheduled on the principal's primary calendar.
+const CALENDAR_ID: &str = "primary";
+
+/// Find the IDs of an org unit and its ancestors, nearest first, ending with the root org unit.
+///
+/// * `client`: The service account
+/// * `principal`: The email of the user requesting this action
+/// * `org_unit_path`: The full path of the org unit
+///
+/// The Directory API cannot fetch the root org unit itself, so its ID is taken from the parent ID
+/// of a top-level org unit.
+async fn org_unit_ancestry(
+    client: &ServiceAccount,
+    principal: &str,
+    org_unit_path: &str,
+) -> Result<Vec<String>> {
+    let strip = |id: String| id.trim_start_matches("id:").to_owned();
+
+    let mut ids = Vec::new();
+    let mut path = org_unit_path.to_owned();
+    while path != "/" {
+        let org_unit = client
+            .get_org_unit(principal, &path)
+            .await?
+            .with_context(|| format!("Org unit {path} does not exist"))?;
+        ids.extend(org_unit.org_unit_id.map(strip));
+        match org_unit.parent_org_unit_path {
+            Some(parent) if parent == "/" => {
+                ids.extend(org_unit.parent_org_unit_id.map(strip));
+                return Ok(ids);
+            }
+            Some(parent) => path = parent,
+            None => bail!("Org unit {path} has no parent"),
+        }
+    }
+
+    let root = client
+        .list_org_units(principal)
+        .await?
+        .into_iter()
+        .find(|o| o.parent_org_unit_path.as_deref() == Some("/"))
+        .and_then(|o| o.parent_org_unit_id)
+        .context("Could not find the root org unit")?;
+    ids.push(strip(root));
+
+    Ok(ids)
+}
+
 #[async_trait]
 impl WorkspaceClient for ServiceAccount {
     async fn create_volunteer(
         &self,
         principal: &str,
         volunteer: CreateWorkspaceVolunteer,
-    ) -> Result<()> {
+    ) -> Result<CreateVolunteerOutcome> {
+        if let Some(existing) = self.get_user(principal, &volunteer.primary_email).await? {
+            let same_owner = existing
+                .recovery_email
+                .is_some_and(|email| email.eq_ignore_ascii_case(&volunteer.recovery_email));
+            if !same_owner {
+                bail!("{} already belongs to another Workspace user", volunteer.primary_email);
+            }
+            return Ok(CreateVolunteerOutcome::AlreadyExists);
+        }
+
         let user = CreateWorkspaceUser::try_from(volunteer)?;
 
         let _ = self.create_user(principal, user).await?;
 
+        Ok(CreateVolunteerOutcome::Created)
+    }
+
+    async fn batch_create_volunteers(
+        &self,
+        principal: &str,
+        volunteers: Vec<CreateWorkspaceVolunteer>,
+    ) -> Result<Vec<Result<CreateVolunteerOutcome>>> {
+        // Volunteers that cannot be converted fail on their own without holding up the batch
+        let mut results = Vec::with_capacity(volunteers.len());
+        let mut users = Vec::with_capacity(volunteers.len());
+        for volunteer in volunteers {
+            match CreateWorkspaceUser::try_from(volunteer) {
+                Ok(user) => {
+                    users.push(user);
+                    results.push(None);
+                }
+                Err(e) => results.push(Some(Err(e))),
+            }
+        }
+
+        let mut created = self.batch_create_users(principal, users).await?.into_iter();
+
+        results
+            .into_iter()
+            .map(|result| match result {
+                Some(failed) => Ok(failed),
+                None => Ok(created
+                    .next()
+                    .context("Missing result from batch")?
+                    .map(|_| CreateVolunteerOutcome::Created)),
+            })
+            .collect()
+    }
+
+    async fn update_volunteer(
+        &self,
+        principal: &str,
+        workspace_email: &str,
+        volunteer: UpdateWorkspaceVolunteer,
+    ) -> Result<()> {
+        let user = UpdateWorkspaceUser::try_from(volunteer)?;
+
+        let _ = self.update_user(principal, workspace_email, user).await?;
+
         Ok(())
     }
 
+    async fn create_alias(
+        &self,
+        principal: &str,
+        workspace_email: &str,
+        alias: &str,
+    ) -> Result<()> {
+        self.insert_user_alias(principal, workspace_email, alias).await
+    }
+
     async fn delete_user(&self, principal: &str, email_of_user_to_delete: &str) -> Result<()> {
         self.delete_user(principal, email_of_user_to_delete).await?;
         Ok(())
     }
+
+    async fn suspend_volunteer(&self, principal: &str, workspace_email: &str) -> Result<()> {
+        self.suspend_user(principal, workspace_email).await?;
+        Ok(())
+    }
+
+    async fn unsuspend_volunteer(&self, principal: &str, workspace_email: &str) -> Result<()> {
+        self.unsuspend_user(principal, workspace_email).await?;
+        Ok(())
+    }
+
+    async fn ensure_group(&self, principal: &str, group_email: &str) -> Result<bool> {
+        if self.get_group(principal, group_email).await?.is_some() {
+            return Ok(false);
+        }
+
+        let group = CreateGroup { email: group_email.to_owned(), name: None, description: None };
+        self.create_group(principal, group).await?;
+
+        Ok(true)
+    }
+
+    async fn ensure_program_schema(&self, principal: &str) -> Result<bool> {
+        if self.get_schema(principal, PROGRAM_SCHEMA_NAME).await?.is_some() {
+            return Ok(false);
+        }
+
+        let field = |field_name: &str, display_name: &str| SchemaField {
+            field_name: field_name.to_owned(),
+            field_type: SchemaFieldType::String,
+            field_id: None,
+            display_name: Some(display_name.to_owned()),
+            multi_valued: Some(false),
+            indexed: Some(true),
+            read_access_type: Some(ReadAccessType::AdminsAndSelf),
+        };
+        let schema = CreateSchema {
+            schema_name: PROGRAM_SCHEMA_NAME.to_owned(),
+            display_name: Some("Program".to_owned()),
+            fields: vec![
+                field("programName", "Program name"),
+                field("cohort", "Cohort"),
+                field("volunteerId", "Volunteer ID"),
+            ],
+        };
+        self.create_schema(principal, schema).await?;
+
+        Ok(true)
+    }
+
+    async fn add_group_member(
+        &self,
+        principal: &str,
+        group_email: &str,
+        workspace_email: &str,
+    ) -> Result<()> {
+        self.add_group_member(principal, group_email, workspace_email).await?;
+        Ok(())
+    }
+
+    async fn list_org_unit_users(
+        &self,
+        principal: &str,
+        org_unit_path: &str,
+    ) -> Result<Vec<WorkspaceAccount>> {
+        let users = self.list_users(principal, org_unit_path).await?;
+        Ok(users.into_iter().map(WorkspaceAccount::from).collect())
+    }
+
+    async fn find_user(&self, principal: &str, email: &str) -> Result<Option<WorkspaceAccount>> {
+        Ok(self.get_user(principal, email).await?.map(WorkspaceAccount::from))
+    }
+
+    async fn org_unit_exists(&self, principal: &str, org_unit_path: &str) -> Result<bool> {
+        Ok(self.get_org_unit(principal, org_unit_path).await?.is_some())
+    }
+
+    async fn list_org_units(&self, principal: &str) -> Result<Vec<WorkspaceOrgUnit>> {
+        let org_units = self.list_org_units(principal).await?;
+        Ok(org_units.into_iter().map(WorkspaceOrgUnit::from).collect())
+    }
+
+    async fn create_org_unit(
+        &self,
+        principal: &str,
+        org_unit_path: &str,
+    ) -> Result<WorkspaceOrgUnit> {
+        let Some((parent, name)) = org_unit_path.rsplit_once('/') else {
+            bail!("Org unit {org_unit_path} must be a full path starting with '/'");
+        };
+        let parent = if parent.is_empty() { "/" } else { parent };
+
+        let data = CreateOrgUnit {
+            name: name.to_owned(),
+            parent_org_unit_path: parent.to_owned(),
+            description: None,
+        };
+        let org_unit = self.create_org_unit(principal, data).await?;
+
+        Ok(WorkspaceOrgUnit::from(org_unit))
+    }
+
+    async fn two_step_verification_settings(
+        &self,
+        principal: &str,
+        org_unit_path: &str,
+    ) -> Result<TwoStepVerificationSettings> {
+        let org_unit_ids = org_unit_ancestry(self, principal, org_unit_path).await?;
+        let policies = self.list_policies(principal, TWO_STEP_VERIFICATION_FILTER).await?;
+
+        Ok(TwoStepVerificationSettings::from_policies(&policies, &org_unit_ids))
+    }
+
+    async fn count_license_assignments(
+        &self,
+        principal: &str,
+        product_id: &str,
+        sku_id: &str,
+    ) -> Result<usize> {
+        let customer_id = self.get_customer_id(principal).await?;
+        let assignments =
+            self.list_license_assignments(principal, &customer_id, product_id, sku_id).await?;
+        Ok(assignments.len())
+    }
+
+    async fn license_seats(
+        &self,
+        _principal: &str,
+        _product_id: &str,
+        _sku_id: &str,
+    ) -> Result<Option<usize>> {
+        // Google only reports purchased seats through the Reseller API, to the reseller
+        Ok(None)
+    }
+
+    async fn assign_license(
+        &self,
+        principal: &str,
+        product_id: &str,
+        sku_id: &str,
+        workspace_email: &str,
+    ) -> Result<()> {
+        self.assign_license(principal, product_id, sku_id, workspace_email).await?;
+        Ok(())
+    }
+
+    async fn set_signature(
+        &self,
+        _principal: &str,
+        workspace_email: &str,
+        signature: &str,
+    ) -> Result<()> {
+        // Gmail settings are changed on the behalf of the volunteer themselves
+        self.update_signature(workspace_email, signature).await
+    }
+
+    async fn set_profile_photo(
+        &self,
+        principal: &str,
+        workspace_email: &str,
+        photo: &ProfilePhoto,
+    ) -> Result<()> {
+        // Google names photo formats by their subtype (e.g. `JPEG` for `image/jpeg`)
+        let mime_type = photo.content_type.trim_start_matches("image/").to_ascii_uppercase();
+        self.update_user_photo(principal, workspace_email, &photo.data, &mime_type).await
+    }
+
+    async fn domain_exists(&self, principal: &str, domain: &str) -> Result<bool> {
+        let domains = self.list_domains(principal).await?;
+        Ok(domains.iter().any(|d| d.verified && d.domain_name.eq_ignore_ascii_case(domain)))
+    }
+}
+
+#[async_trait]
+impl DriveClient for ServiceAccount {
+    async fn create_shared_drive(
+        &self,
+        principal: &str,
+        request_id: &str,
+        name: &str,
+    ) -> Result<Option<SharedDrive>> {
+        let data = CreateDrive { name: name.to_owned() };
+        Ok(self.create_drive(principal, request_id, data).await?.map(SharedDrive::from))
+    }
+
+    async fn add_shared_drive_member(
+        &self,
+        principal: &str,
+        drive_id: &str,
+        workspace_email: &str,
+        role: DriveRole,
+    ) -> Result<()> {
+        self.create_drive_permission(principal, drive_id, workspace_email, role.as_str()).await?;
+        Ok(())
+    }
+
+    async fn start_drive_transfer(
+        &self,
+        principal: &str,
+        workspace_email: &str,
+        archive_email: &str,
+    ) -> Result<String> {
+        // Transfers are between user IDs, not emails
+        let mut user_ids = Vec::with_capacity(2);
+        for email in [workspace_email, archive_email] {
+            let user = self
+                .get_user(principal, email)
+                .await?
+                .with_context(|| format!("User {email} does not exist"))?;
+            user_ids.push(user.id.with_context(|| format!("User {email} has no ID"))?);
+        }
+
+        let applications = self.list_transfer_applications(principal).await?;
+        let drive = drive_application(&applications)
+            .context("Drive files cannot be transferred in this workspace")?;
+
+        let transfer = self
+            .create_transfer(principal, DataTransfer::drive(&drive.id, &user_ids[0], &user_ids[1]))
+            .await?;
+
+        transfer.id.context("Google did not return the ID of the transfer")
+    }
+
+    async fn drive_transfer_status(
+        &self,
+        principal: &str,
+        transfer_id: &str,
+    ) -> Result<DriveTransferStatus> {
+        let transfer = self
+            .get_transfer(principal, transfer_id)
+            .await?
+            .with_context(|| format!("Transfer {transfer_id} does not exist"))?;
+
+        Ok(DriveTransferStatus::from_status_code(transfer.overall_transfer_status_code.as_deref()))
+    }
+}
+
+#[async_trait]
+impl CalendarClient for ServiceAccount {
+    async fn create_calendar_event(
+        &self,
+        principal: &str,
+        event: CreateCalendarEvent,
+    ) -> Result<CalendarEvent> {
+        let mut data = CreateEventBuilder::default();
+        data.id(event.id.clone())
+            .summary(event.summary)
+            .start(EventDateTime { date_time: Some(event.start), time_zone: None })
+            .end(EventDateTime { date_time: Some(event.end), time_zone: None });
+        if let Some(description) = event.description {
+            data.description(description);
+        }
+
+        let created = match self.create_event(principal, CALENDAR_ID, data.build()?).await? {
+            Some(created) => created,
+            None => {
+                self.get_event(principal, CALENDAR_ID, &event.id).await?.with_context(|| {
+                    format!("Event {} already exists on another calendar", event.id)
+                })?
+            }
+        };
+
+        CalendarEvent::try_from(created)
+    }
+
+    async fn invite_to_calendar_event(
+        &self,
+        principal: &str,
+        event_id: &str,
+        workspace_emails: &[String],
+    ) -> Result<()> {
+        self.add_event_attendees(principal, CALENDAR_ID, event_id, workspace_emails).await?;
+        Ok(())
+    }
 }
 
 impl Service for ServiceAccount {